@@ -0,0 +1,415 @@
+//! Cross-channel analysis between an input and an output signal
+//!
+//! Estimates the frequency response (transfer function) of a system from
+//! recorded input and output signals using Welch-averaged cross-spectral
+//! densities, together with the magnitude-squared coherence that tells how
+//! much of the output is linearly explained by the input at each frequency.
+//! Time-domain cross-correlation gives the lag between the two signals.
+//!
+//! Both signals must share one uniform grid (see
+//! [`resample_aligned`](super::resample::resample_aligned)).
+
+use rustfft::{num_complex::Complex, FftPlanner};
+
+use super::fft::{FftAnalyzer, FftConfig};
+
+/// Coherence below which a frequency response estimate is unreliable
+pub const LOW_COHERENCE: f64 = 0.6;
+
+/// Estimated frequency response of a system
+#[derive(Debug, Clone, Default)]
+pub struct FrequencyResponse {
+    /// Frequency of each bin (Hz), DC excluded
+    pub frequencies: Vec<f64>,
+    /// Gain from input to output (dB)
+    pub magnitude_db: Vec<f64>,
+    /// Phase of the output relative to the input (degrees, -180 to 180)
+    pub phase_deg: Vec<f64>,
+    /// Magnitude-squared coherence (0.0 to 1.0)
+    pub coherence: Vec<f64>,
+    /// Sample rate of the input (Hz)
+    pub sample_rate: f64,
+    /// Number of averaged segments
+    pub segment_count: usize,
+    /// Frequency resolution (Hz per bin)
+    pub frequency_resolution: f64,
+}
+
+impl FrequencyResponse {
+    /// Whether no estimate could be computed
+    pub fn is_empty(&self) -> bool {
+        self.frequencies.is_empty()
+    }
+
+    /// Get data points for plotting (frequency, gain in dB)
+    pub fn magnitude_points(&self) -> Vec<[f64; 2]> {
+        zip_points(&self.frequencies, &self.magnitude_db)
+    }
+
+    /// Get data points for plotting (frequency, phase in degrees)
+    pub fn phase_points(&self) -> Vec<[f64; 2]> {
+        zip_points(&self.frequencies, &self.phase_deg)
+    }
+
+    /// Get data points for plotting (frequency, unwrapped phase in degrees)
+    pub fn unwrapped_phase_points(&self) -> Vec<[f64; 2]> {
+        zip_points(&self.frequencies, &unwrap_phase_deg(&self.phase_deg))
+    }
+
+    /// Get data points for plotting (frequency, coherence)
+    pub fn coherence_points(&self) -> Vec<[f64; 2]> {
+        zip_points(&self.frequencies, &self.coherence)
+    }
+
+    /// Index of the bin nearest to a frequency
+    pub fn bin_at(&self, frequency: f64) -> Option<usize> {
+        if self.is_empty() || self.frequency_resolution <= 0.0 {
+            return None;
+        }
+        let first = self.frequencies[0];
+        let bin = ((frequency - first) / self.frequency_resolution).round();
+        (bin >= 0.0 && (bin as usize) < self.frequencies.len()).then_some(bin as usize)
+    }
+
+    /// Lowest frequency where the gain has fallen 3 dB below the low-frequency gain
+    ///
+    /// Only bins with coherence of at least [`LOW_COHERENCE`] are considered.
+    pub fn bandwidth_3db(&self) -> Option<f64> {
+        let reliable = |i: &usize| self.coherence[*i] >= LOW_COHERENCE;
+        let reference = (0..self.frequencies.len()).find(reliable)?;
+        let threshold = self.magnitude_db[reference] - 3.0;
+        (reference..self.frequencies.len())
+            .filter(reliable)
+            .find(|&i| self.magnitude_db[i] < threshold)
+            .map(|i| self.frequencies[i])
+    }
+}
+
+/// Normalized cross-correlation between two signals
+#[derive(Debug, Clone, Default)]
+pub struct CrossCorrelation {
+    /// Lag of each value (seconds); positive when the output follows the input
+    pub lags: Vec<f64>,
+    /// Correlation coefficient at each lag (-1.0 to 1.0)
+    pub values: Vec<f64>,
+    /// Sample rate of the inputs (Hz)
+    pub sample_rate: f64,
+}
+
+impl CrossCorrelation {
+    /// Lag (seconds) and value of the strongest correlation (by magnitude)
+    pub fn peak(&self) -> Option<(f64, f64)> {
+        let (i, &value) = self
+            .values
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))?;
+        Some((self.lags[i], value))
+    }
+
+    /// Get data points for plotting (lag, correlation)
+    pub fn plot_points(&self) -> Vec<[f64; 2]> {
+        zip_points(&self.lags, &self.values)
+    }
+}
+
+/// Unwrap a phase sequence (degrees) by removing jumps larger than 180 degrees
+pub fn unwrap_phase_deg(phase: &[f64]) -> Vec<f64> {
+    let mut offset = 0.0;
+    let mut previous: Option<f64> = None;
+    phase
+        .iter()
+        .map(|&p| {
+            if let Some(prev) = previous {
+                let delta = p - prev;
+                offset -= 360.0 * (delta / 360.0).round();
+            }
+            previous = Some(p);
+            p + offset
+        })
+        .collect()
+}
+
+fn zip_points(x: &[f64], y: &[f64]) -> Vec<[f64; 2]> {
+    x.iter().zip(y).map(|(&x, &y)| [x, y]).collect()
+}
+
+/// Computes frequency responses and cross-correlations, reusing FFT plans
+pub struct CrossSpectrumAnalyzer {
+    fft: FftAnalyzer,
+    planner: FftPlanner<f64>,
+}
+
+impl CrossSpectrumAnalyzer {
+    /// Create a new analyzer with default config
+    pub fn new() -> Self {
+        Self::with_config(FftConfig::default())
+    }
+
+    /// Create analyzer with specific segment config
+    pub fn with_config(config: FftConfig) -> Self {
+        Self {
+            fft: FftAnalyzer::with_config(config),
+            planner: FftPlanner::new(),
+        }
+    }
+
+    /// Get current config
+    pub fn config(&self) -> &FftConfig {
+        self.fft.config()
+    }
+
+    /// Set config
+    pub fn set_config(&mut self, config: FftConfig) {
+        self.fft.set_config(config);
+    }
+
+    /// Estimate the frequency response from `input` to `output`
+    ///
+    /// Uses Welch's method: both signals are cut into the same overlapping
+    /// windowed segments, and the averaged cross-spectral density `Pxy` and
+    /// auto-spectral densities `Pxx`, `Pyy` give the transfer function
+    /// `H = Pxy / Pxx` and coherence `|Pxy|² / (Pxx · Pyy)`. Coherence is only
+    /// meaningful with more than one segment.
+    ///
+    /// # Arguments
+    /// * `input` - Excitation samples (e.g. setpoint or injected noise)
+    /// * `output` - Response samples on the same grid as `input`
+    /// * `sample_rate` - Sample rate in Hz
+    pub fn frequency_response(
+        &mut self,
+        input: &[f64],
+        output: &[f64],
+        sample_rate: f64,
+    ) -> FrequencyResponse {
+        let n = input.len().min(output.len());
+        let input_spectra = self.fft.segment_spectra(&input[..n]);
+        let output_spectra = self.fft.segment_spectra(&output[..n]);
+        let Some(bins) = input_spectra.first().map(|s| s.len()) else {
+            return FrequencyResponse {
+                sample_rate,
+                ..Default::default()
+            };
+        };
+
+        let mut pxx = vec![0.0; bins];
+        let mut pyy = vec![0.0; bins];
+        let mut pxy = vec![Complex::new(0.0, 0.0); bins];
+        for (x, y) in input_spectra.iter().zip(&output_spectra) {
+            for k in 0..bins {
+                pxx[k] += x[k].norm_sqr();
+                pyy[k] += y[k].norm_sqr();
+                pxy[k] += x[k].conj() * y[k];
+            }
+        }
+
+        let fft_size = (bins - 1) * 2;
+        let frequency_resolution = sample_rate / fft_size as f64;
+        let mut response = FrequencyResponse {
+            sample_rate,
+            segment_count: input_spectra.len(),
+            frequency_resolution,
+            ..Default::default()
+        };
+        // Skip DC: the mean is removed from every segment
+        for k in 1..bins {
+            let (h, coherence) = if pxx[k] > 1e-20 {
+                let h = pxy[k] / pxx[k];
+                let coherence = if pyy[k] > 1e-20 {
+                    (pxy[k].norm_sqr() / (pxx[k] * pyy[k])).min(1.0)
+                } else {
+                    0.0
+                };
+                (h, coherence)
+            } else {
+                (Complex::new(0.0, 0.0), 0.0)
+            };
+            let gain = h.norm();
+            response.frequencies.push(k as f64 * frequency_resolution);
+            response.magnitude_db.push(if gain > 1e-10 {
+                20.0 * gain.log10()
+            } else {
+                -200.0
+            });
+            response.phase_deg.push(h.arg().to_degrees());
+            response.coherence.push(coherence);
+        }
+        response
+    }
+
+    /// Normalized cross-correlation of `output` against `input`
+    ///
+    /// Means are removed first; values are normalized so that identical
+    /// signals correlate to 1.0 at zero lag. Lags are limited to
+    /// `±max_lag` samples.
+    pub fn cross_correlation(
+        &mut self,
+        input: &[f64],
+        output: &[f64],
+        sample_rate: f64,
+        max_lag: usize,
+    ) -> CrossCorrelation {
+        let n = input.len().min(output.len());
+        let mut correlation = CrossCorrelation {
+            sample_rate,
+            ..Default::default()
+        };
+        if n < 2 || sample_rate <= 0.0 {
+            return correlation;
+        }
+
+        let centered = |samples: &[f64]| {
+            let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+            samples.iter().map(|s| s - mean).collect::<Vec<f64>>()
+        };
+        let x = centered(&input[..n]);
+        let y = centered(&output[..n]);
+        let norm =
+            (x.iter().map(|v| v * v).sum::<f64>() * y.iter().map(|v| v * v).sum::<f64>()).sqrt();
+        if norm <= 1e-20 {
+            return correlation;
+        }
+
+        // Zero padding to at least 2n - 1 avoids circular wrap-around
+        let size = (2 * n - 1).next_power_of_two();
+        let fft = self.planner.plan_fft_forward(size);
+        let ifft = self.planner.plan_fft_inverse(size);
+        let spectrum = |samples: &[f64]| {
+            let mut buffer: Vec<Complex<f64>> =
+                samples.iter().map(|&s| Complex::new(s, 0.0)).collect();
+            buffer.resize(size, Complex::new(0.0, 0.0));
+            fft.process(&mut buffer);
+            buffer
+        };
+        let x_spectrum = spectrum(&x);
+        let y_spectrum = spectrum(&y);
+        let mut product: Vec<Complex<f64>> = x_spectrum
+            .iter()
+            .zip(&y_spectrum)
+            .map(|(x, y)| x.conj() * y)
+            .collect();
+        ifft.process(&mut product);
+
+        // r[k] = sum(x[i] * y[i + k]); negative lags wrap to the end
+        let scale = 1.0 / (size as f64 * norm);
+        let max_lag = max_lag.min(n - 1) as isize;
+        for lag in -max_lag..=max_lag {
+            let index = lag.rem_euclid(size as isize) as usize;
+            correlation.lags.push(lag as f64 / sample_rate);
+            correlation.values.push(product[index].re * scale);
+        }
+        correlation
+    }
+}
+
+impl Default for CrossSpectrumAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::WindowFunction;
+
+    /// Deterministic white-ish noise in [-1, 1)
+    fn noise(n: usize, seed: u64) -> Vec<f64> {
+        let mut state = seed;
+        (0..n)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+            })
+            .collect()
+    }
+
+    /// First-order low-pass `y[n] = a * y[n-1] + (1 - a) * x[n]`
+    fn low_pass(x: &[f64], a: f64) -> Vec<f64> {
+        let mut y = 0.0;
+        x.iter()
+            .map(|&v| {
+                y = a * y + (1.0 - a) * v;
+                y
+            })
+            .collect()
+    }
+
+    fn analyzer(size: usize) -> CrossSpectrumAnalyzer {
+        CrossSpectrumAnalyzer::with_config(FftConfig::with_size(size).window(WindowFunction::Hann))
+    }
+
+    #[test]
+    fn test_gain_and_delay() {
+        let rate = 1000.0;
+        let x = noise(8192, 1);
+        // Output is twice the input, delayed by 4 samples
+        let y: Vec<f64> = (0..x.len())
+            .map(|i| if i >= 4 { 2.0 * x[i - 4] } else { 0.0 })
+            .collect();
+
+        let response = analyzer(256).frequency_response(&x, &y, rate);
+        assert!(response.segment_count > 10);
+        assert_eq!(response.frequencies.len(), 128);
+
+        let bin = response.bin_at(50.0).unwrap();
+        let f = response.frequencies[bin];
+        assert!((response.magnitude_db[bin] - 6.02).abs() < 0.3);
+        assert!(response.coherence[bin] > 0.95);
+        // A pure delay of d seconds has phase -360 * f * d degrees
+        let unwrapped = unwrap_phase_deg(&response.phase_deg);
+        assert!((unwrapped[bin] - (-360.0 * f * 0.004)).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_low_pass_bandwidth_and_coherence() {
+        let rate = 1000.0;
+        let x = noise(16384, 1);
+        let y = low_pass(&x, 0.9);
+
+        let response = analyzer(512).frequency_response(&x, &y, rate);
+        // Analog-equivalent cutoff of a = 0.9 at 1 kHz is about 16.6 Hz
+        let bandwidth = response.bandwidth_3db().unwrap();
+        assert!((bandwidth - 16.6).abs() < 3.0, "bandwidth {}", bandwidth);
+        // Phase lags and coherence stays high for a noiseless linear system
+        let bin = response.bin_at(100.0).unwrap();
+        assert!(response.phase_deg[bin] < -45.0);
+        assert!(response.coherence[bin] > 0.9);
+
+        // Unrelated output: coherence collapses
+        let unrelated = noise(16384, 7);
+        let response = analyzer(512).frequency_response(&x, &unrelated, rate);
+        let mean: f64 = response.coherence.iter().sum::<f64>() / response.coherence.len() as f64;
+        assert!(mean < 0.2, "mean coherence {}", mean);
+    }
+
+    #[test]
+    fn test_cross_correlation_lag() {
+        let rate = 100.0;
+        let x = noise(2000, 1);
+        let y: Vec<f64> = (0..x.len())
+            .map(|i| if i >= 7 { -x[i - 7] } else { 0.0 })
+            .collect();
+
+        let correlation = analyzer(256).cross_correlation(&x, &y, rate, 50);
+        assert_eq!(correlation.lags.len(), 101);
+        let (lag, value) = correlation.peak().unwrap();
+        assert!((lag - 0.07).abs() < 1e-9);
+        assert!(value < -0.95);
+
+        // Identical signals correlate to exactly one at zero lag
+        let correlation = analyzer(256).cross_correlation(&x, &x, rate, 5);
+        let (lag, value) = correlation.peak().unwrap();
+        assert_eq!(lag, 0.0);
+        assert!((value - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unwrap_phase() {
+        let wrapped = [170.0, -170.0, -150.0, 175.0];
+        let unwrapped = unwrap_phase_deg(&wrapped);
+        assert_eq!(unwrapped, vec![170.0, 190.0, 210.0, 175.0]);
+    }
+}
@@ -426,6 +426,53 @@ impl FftAnalyzer {
             frequency_resolution: freq_resolution,
         }
     }
+
+    /// Compute the complex spectrum of each overlapping segment
+    ///
+    /// Segments are cut the same way as in [`compute_averaged`](Self::compute_averaged)
+    /// (`fft_size` samples, configured overlap). Each segment has its mean removed
+    /// (if configured) and is windowed before the FFT. Only the non-negative
+    /// frequency bins are returned, unscaled. Returns no segments when there are
+    /// fewer than `fft_size` samples.
+    pub fn segment_spectra(&mut self, samples: &[f64]) -> Vec<Vec<Complex<f64>>> {
+        let segment_size = self.config.fft_size.max(2);
+        if samples.len() < segment_size {
+            return Vec::new();
+        }
+
+        let hop_size = ((1.0 - self.config.overlap) * segment_size as f64) as usize;
+        let hop_size = hop_size.max(1);
+        let num_segments = (samples.len() - segment_size) / hop_size + 1;
+
+        let fft_size = if self.config.zero_pad {
+            segment_size.next_power_of_two()
+        } else {
+            segment_size
+        };
+        let num_bins = fft_size / 2 + 1;
+        let window = self.config.window.generate(segment_size);
+        let fft = self.planner.plan_fft_forward(fft_size);
+
+        (0..num_segments)
+            .map(|i| {
+                let segment = &samples[i * hop_size..i * hop_size + segment_size];
+                let mean = if self.config.remove_dc {
+                    segment.iter().sum::<f64>() / segment_size as f64
+                } else {
+                    0.0
+                };
+                let mut buffer: Vec<Complex<f64>> = segment
+                    .iter()
+                    .zip(&window)
+                    .map(|(&s, &w)| Complex::new((s - mean) * w, 0.0))
+                    .collect();
+                buffer.resize(fft_size, Complex::new(0.0, 0.0));
+                fft.process(&mut buffer);
+                buffer.truncate(num_bins);
+                buffer
+            })
+            .collect()
+    }
 }
 
 impl Default for FftAnalyzer {
@@ -541,4 +588,28 @@ mod tests {
             assert!(size.is_power_of_two());
         }
     }
+
+    #[test]
+    fn test_segment_spectra() {
+        let mut analyzer = FftAnalyzer::with_config(FftConfig {
+            window: WindowFunction::Rectangular,
+            fft_size: 64,
+            overlap: 0.5,
+            ..Default::default()
+        });
+
+        // 64 + 32 * 3 samples give four half-overlapping segments
+        let samples: Vec<f64> = (0..160)
+            .map(|i| (2.0 * PI * 8.0 * i as f64 / 64.0).cos() + 5.0)
+            .collect();
+        let spectra = analyzer.segment_spectra(&samples);
+        assert_eq!(spectra.len(), 4);
+        assert_eq!(spectra[0].len(), 33);
+
+        // DC removed, all energy in bin 8
+        assert!(spectra[0][0].norm() < 1e-9);
+        assert!((spectra[0][8].norm() - 32.0).abs() < 1e-9);
+
+        assert!(analyzer.segment_spectra(&samples[..10]).is_empty());
+    }
 }
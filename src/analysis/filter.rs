@@ -0,0 +1,997 @@
+//! Digital filter design
+//!
+//! Designs IIR filters (Butterworth, Chebyshev type I, notch and single
+//! biquads) as cascades of second-order sections, and windowed-sinc FIR
+//! filters. A designed [`Filter`] can report its frequency response, filter
+//! a sample series offline, or be turned into a Rhai converter script that
+//! runs the same filter on live data.
+//!
+//! Butterworth and Chebyshev filters are derived from the analog prototype
+//! with the bilinear transform (cutoffs are pre-warped, so they land exactly
+//! where specified).
+
+use rustfft::num_complex::Complex;
+use std::f64::consts::PI;
+
+use super::cross_spectrum::unwrap_phase_deg;
+use super::fft::WindowFunction;
+use crate::error::{DataVisError, Result};
+
+/// Highest prototype order for Butterworth and Chebyshev designs
+pub const MAX_IIR_ORDER: usize = 10;
+
+/// Most taps a FIR design may have (generated scripts stay within the
+/// script engine's operation limit)
+pub const MAX_FIR_TAPS: usize = 129;
+
+/// Which band the filter passes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterType {
+    /// Pass below the cutoff
+    #[default]
+    Lowpass,
+    /// Pass above the cutoff
+    Highpass,
+    /// Pass between the two cutoffs
+    Bandpass,
+    /// Reject between the two cutoffs
+    Bandstop,
+}
+
+impl FilterType {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FilterType::Lowpass => "Lowpass",
+            FilterType::Highpass => "Highpass",
+            FilterType::Bandpass => "Bandpass",
+            FilterType::Bandstop => "Bandstop",
+        }
+    }
+
+    /// Get all filter types
+    pub fn all() -> &'static [FilterType] {
+        &[
+            FilterType::Lowpass,
+            FilterType::Highpass,
+            FilterType::Bandpass,
+            FilterType::Bandstop,
+        ]
+    }
+
+    /// Whether the filter is specified by a band (two cutoffs)
+    pub fn is_band(&self) -> bool {
+        matches!(self, FilterType::Bandpass | FilterType::Bandstop)
+    }
+}
+
+/// Filter design method
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterMethod {
+    /// Maximally flat passband (IIR)
+    #[default]
+    Butterworth,
+    /// Steeper roll-off with passband ripple (IIR, type I)
+    Chebyshev,
+    /// Narrow band rejection at one frequency (single biquad)
+    Notch,
+    /// Single second-order section with a quality factor (RBJ cookbook)
+    Biquad,
+    /// Windowed-sinc finite impulse response (linear phase)
+    Fir,
+}
+
+impl FilterMethod {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FilterMethod::Butterworth => "Butterworth",
+            FilterMethod::Chebyshev => "Chebyshev I",
+            FilterMethod::Notch => "Notch",
+            FilterMethod::Biquad => "Biquad",
+            FilterMethod::Fir => "FIR (windowed)",
+        }
+    }
+
+    /// Get all design methods
+    pub fn all() -> &'static [FilterMethod] {
+        &[
+            FilterMethod::Butterworth,
+            FilterMethod::Chebyshev,
+            FilterMethod::Notch,
+            FilterMethod::Biquad,
+            FilterMethod::Fir,
+        ]
+    }
+
+    /// Whether the order is a design parameter
+    pub fn has_order(&self) -> bool {
+        matches!(
+            self,
+            FilterMethod::Butterworth | FilterMethod::Chebyshev | FilterMethod::Fir
+        )
+    }
+
+    /// Whether the quality factor is a design parameter
+    pub fn has_q(&self) -> bool {
+        matches!(self, FilterMethod::Notch | FilterMethod::Biquad)
+    }
+}
+
+/// Filter specification
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterSpec {
+    /// Design method
+    pub method: FilterMethod,
+    /// Pass/reject band (ignored by notch filters)
+    pub filter_type: FilterType,
+    /// Prototype order for IIR designs, taps - 1 for FIR designs
+    pub order: usize,
+    /// Cutoff frequency (Hz); lower band edge for band filters, center for
+    /// notch and band biquads
+    pub cutoff_hz: f64,
+    /// Upper band edge for Butterworth, Chebyshev and FIR band filters (Hz)
+    pub cutoff_high_hz: f64,
+    /// Quality factor for notch and biquad filters
+    pub q: f64,
+    /// Passband ripple for Chebyshev filters (dB)
+    pub ripple_db: f64,
+    /// Window for FIR designs
+    pub window: WindowFunction,
+    /// Sample rate the filter runs at (Hz)
+    pub sample_rate: f64,
+}
+
+impl Default for FilterSpec {
+    fn default() -> Self {
+        Self {
+            method: FilterMethod::default(),
+            filter_type: FilterType::default(),
+            order: 2,
+            cutoff_hz: 10.0,
+            cutoff_high_hz: 20.0,
+            q: std::f64::consts::FRAC_1_SQRT_2,
+            ripple_db: 1.0,
+            window: WindowFunction::Hamming,
+            sample_rate: 100.0,
+        }
+    }
+}
+
+impl FilterSpec {
+    /// One-line description, e.g. "Butterworth lowpass, order 4, 10 Hz @ 100 Hz"
+    pub fn summary(&self) -> String {
+        let band = if self.method == FilterMethod::Notch {
+            format!("notch, {} Hz, Q {}", self.cutoff_hz, self.q)
+        } else if self.method == FilterMethod::Biquad {
+            format!(
+                "{}, {} Hz, Q {}",
+                self.filter_type.display_name().to_lowercase(),
+                self.cutoff_hz,
+                self.q
+            )
+        } else {
+            let edges = if self.filter_type.is_band() {
+                format!("{}-{} Hz", self.cutoff_hz, self.cutoff_high_hz)
+            } else {
+                format!("{} Hz", self.cutoff_hz)
+            };
+            format!(
+                "{}, order {}, {}",
+                self.filter_type.display_name().to_lowercase(),
+                self.order,
+                edges
+            )
+        };
+        let method = match self.method {
+            FilterMethod::Notch => "IIR",
+            method => method.display_name(),
+        };
+        format!("{} {} @ {} Hz", method, band, self.sample_rate)
+    }
+
+    /// Check the specification, returning a description of the first problem
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: String| Err(DataVisError::Config(msg));
+        let nyquist = self.sample_rate / 2.0;
+        if !(self.sample_rate > 0.0 && self.sample_rate.is_finite()) {
+            return invalid("Sample rate must be positive".to_string());
+        }
+        if !(self.cutoff_hz > 0.0 && self.cutoff_hz < nyquist) {
+            return invalid(format!(
+                "Cutoff must be between 0 and {} Hz (Nyquist)",
+                nyquist
+            ));
+        }
+        let uses_band = self.filter_type.is_band() && !self.method.has_q();
+        if uses_band && !(self.cutoff_high_hz > self.cutoff_hz && self.cutoff_high_hz < nyquist) {
+            return invalid(format!(
+                "Upper cutoff must be between {} and {} Hz",
+                self.cutoff_hz, nyquist
+            ));
+        }
+        match self.method {
+            FilterMethod::Butterworth | FilterMethod::Chebyshev => {
+                if !(1..=MAX_IIR_ORDER).contains(&self.order) {
+                    return invalid(format!("Order must be between 1 and {}", MAX_IIR_ORDER));
+                }
+                if self.method == FilterMethod::Chebyshev && self.ripple_db <= 0.0 {
+                    return invalid("Passband ripple must be positive".to_string());
+                }
+            }
+            FilterMethod::Notch | FilterMethod::Biquad => {
+                if self.q <= 0.0 {
+                    return invalid("Q must be positive".to_string());
+                }
+            }
+            FilterMethod::Fir => {
+                if !(2..MAX_FIR_TAPS).contains(&self.order) {
+                    return invalid(format!(
+                        "FIR order must be between 2 and {}",
+                        MAX_FIR_TAPS - 1
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Second-order section `(b0 + b1 z^-1 + b2 z^-2) / (1 + a1 z^-1 + a2 z^-2)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Biquad {
+    /// Numerator coefficients `[b0, b1, b2]`
+    pub b: [f64; 3],
+    /// Denominator coefficients `[a1, a2]` (a0 is normalized to 1)
+    pub a: [f64; 2],
+}
+
+impl Biquad {
+    /// Build a section from unnormalized coefficients
+    fn normalized(b: [f64; 3], a: [f64; 3]) -> Self {
+        Self {
+            b: [b[0] / a[0], b[1] / a[0], b[2] / a[0]],
+            a: [a[1] / a[0], a[2] / a[0]],
+        }
+    }
+
+    /// Gain at DC, `None` for a section with a pole at z = 1
+    fn dc_gain(&self) -> Option<f64> {
+        let den = 1.0 + self.a[0] + self.a[1];
+        (den.abs() > 1e-12).then(|| (self.b[0] + self.b[1] + self.b[2]) / den)
+    }
+
+    /// Complex response at `z^-1 = e^{-jw}`
+    fn response(&self, z1: Complex<f64>) -> Complex<f64> {
+        let z2 = z1 * z1;
+        let num = self.b[0] + z1 * self.b[1] + z2 * self.b[2];
+        let den = 1.0 + z1 * self.a[0] + z2 * self.a[1];
+        num / den
+    }
+
+    /// Whether both poles are strictly inside the unit circle
+    pub fn is_stable(&self) -> bool {
+        self.a[1].abs() < 1.0 && self.a[0].abs() < 1.0 + self.a[1]
+    }
+}
+
+/// Filter coefficients
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterCoefficients {
+    /// IIR cascade of second-order sections
+    Sections(Vec<Biquad>),
+    /// FIR taps
+    Taps(Vec<f64>),
+}
+
+/// Frequency response of a designed filter
+#[derive(Debug, Clone, Default)]
+pub struct FilterResponse {
+    /// Frequency of each point (Hz)
+    pub frequencies: Vec<f64>,
+    /// Gain (dB)
+    pub magnitude_db: Vec<f64>,
+    /// Phase (degrees, unwrapped)
+    pub phase_deg: Vec<f64>,
+}
+
+impl FilterResponse {
+    /// Gain plot points `[frequency, dB]`
+    pub fn magnitude_points(&self) -> Vec<[f64; 2]> {
+        zip_points(&self.frequencies, &self.magnitude_db)
+    }
+
+    /// Phase plot points `[frequency, degrees]`
+    pub fn phase_points(&self) -> Vec<[f64; 2]> {
+        zip_points(&self.frequencies, &self.phase_deg)
+    }
+}
+
+fn zip_points(x: &[f64], y: &[f64]) -> Vec<[f64; 2]> {
+    x.iter().zip(y).map(|(&x, &y)| [x, y]).collect()
+}
+
+/// A designed digital filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    /// Specification the filter was designed from
+    pub spec: FilterSpec,
+    /// Designed coefficients
+    pub coefficients: FilterCoefficients,
+}
+
+impl Filter {
+    /// Design a filter from a specification
+    pub fn design(spec: &FilterSpec) -> Result<Self> {
+        spec.validate()?;
+        let coefficients = match spec.method {
+            FilterMethod::Butterworth | FilterMethod::Chebyshev => {
+                FilterCoefficients::Sections(design_iir(spec))
+            }
+            FilterMethod::Notch => FilterCoefficients::Sections(vec![rbj_biquad(
+                FilterType::Bandstop,
+                spec.cutoff_hz,
+                spec.q,
+                spec.sample_rate,
+            )]),
+            FilterMethod::Biquad => FilterCoefficients::Sections(vec![rbj_biquad(
+                spec.filter_type,
+                spec.cutoff_hz,
+                spec.q,
+                spec.sample_rate,
+            )]),
+            FilterMethod::Fir => FilterCoefficients::Taps(design_fir(spec)),
+        };
+        Ok(Self {
+            spec: spec.clone(),
+            coefficients,
+        })
+    }
+
+    /// Complex response at a frequency (Hz)
+    pub fn response_at(&self, frequency: f64) -> Complex<f64> {
+        let w = 2.0 * PI * frequency / self.spec.sample_rate;
+        let z1 = Complex::from_polar(1.0, -w);
+        match &self.coefficients {
+            FilterCoefficients::Sections(sections) => sections
+                .iter()
+                .fold(Complex::new(1.0, 0.0), |acc, s| acc * s.response(z1)),
+            FilterCoefficients::Taps(taps) => {
+                let mut zn = Complex::new(1.0, 0.0);
+                let mut sum = Complex::new(0.0, 0.0);
+                for &h in taps {
+                    sum += zn * h;
+                    zn *= z1;
+                }
+                sum
+            }
+        }
+    }
+
+    /// Gain at a frequency (dB)
+    pub fn magnitude_db_at(&self, frequency: f64) -> f64 {
+        20.0 * self.response_at(frequency).norm().max(1e-12).log10()
+    }
+
+    /// Frequency response at `points` frequencies spread evenly up to Nyquist
+    /// (DC excluded so the result can be drawn on a log axis)
+    pub fn frequency_response(&self, points: usize) -> FilterResponse {
+        let nyquist = self.spec.sample_rate / 2.0;
+        let frequencies: Vec<f64> = (1..=points)
+            .map(|i| nyquist * i as f64 / points as f64)
+            .collect();
+        let responses: Vec<Complex<f64>> =
+            frequencies.iter().map(|&f| self.response_at(f)).collect();
+        let magnitude_db = responses
+            .iter()
+            .map(|h| 20.0 * h.norm().max(1e-12).log10())
+            .collect();
+        let phase: Vec<f64> = responses.iter().map(|h| h.arg().to_degrees()).collect();
+        FilterResponse {
+            frequencies,
+            magnitude_db,
+            phase_deg: unwrap_phase_deg(&phase),
+        }
+    }
+
+    /// Whether every IIR section is stable (FIR filters always are)
+    pub fn is_stable(&self) -> bool {
+        match &self.coefficients {
+            FilterCoefficients::Sections(sections) => sections.iter().all(Biquad::is_stable),
+            FilterCoefficients::Taps(_) => true,
+        }
+    }
+
+    /// Filter a series of evenly spaced samples
+    ///
+    /// The filter starts from the steady state for the first sample, so a
+    /// signal with an offset does not produce a start-up transient.
+    pub fn apply(&self, samples: &[f64]) -> Vec<f64> {
+        let Some(&first) = samples.first() else {
+            return Vec::new();
+        };
+        match &self.coefficients {
+            FilterCoefficients::Sections(sections) => {
+                let mut states: Vec<[f64; 2]> = Vec::with_capacity(sections.len());
+                let mut level = first;
+                for section in sections {
+                    let (state, out) = steady_state(section, level);
+                    states.push(state);
+                    level = out;
+                }
+                samples
+                    .iter()
+                    .map(|&x| {
+                        sections.iter().zip(states.iter_mut()).fold(x, |x, (s, z)| {
+                            let y = s.b[0] * x + z[0];
+                            z[0] = s.b[1] * x - s.a[0] * y + z[1];
+                            z[1] = s.b[2] * x - s.a[1] * y;
+                            y
+                        })
+                    })
+                    .collect()
+            }
+            FilterCoefficients::Taps(taps) => (0..samples.len())
+                .map(|i| {
+                    taps.iter()
+                        .enumerate()
+                        .map(|(k, h)| h * if k > i { first } else { samples[i - k] })
+                        .sum()
+                })
+                .collect(),
+        }
+    }
+
+    /// Generate a converter script that runs this filter on each new sample
+    ///
+    /// IIR filters keep two state slots per section (direct form II
+    /// transposed); FIR filters keep the last `taps - 1` inputs. Both start
+    /// from the steady state of the first sample, like [`Filter::apply`].
+    pub fn to_script(&self) -> String {
+        let mut script = format!(
+            "// {}\n// Generated by the filter designer. The coefficients assume a {} Hz\n\
+             // sample rate; polling at another rate shifts the cutoff.\n",
+            self.spec.summary(),
+            self.spec.sample_rate
+        );
+        match &self.coefficients {
+            FilterCoefficients::Sections(sections) => {
+                script.push_str("let x = value;\n");
+                script.push_str("if !has_state() {\n    // Start from the steady state of the first sample\n    let u = x;\n");
+                for (i, s) in sections.iter().enumerate() {
+                    let gain = s.dc_gain().unwrap_or(0.0);
+                    let (z0, z1) = (2 * i, 2 * i + 1);
+                    script.push_str(&format!(
+                        "    set_state({z1}, {b2} * u {a2} * {g} * u);\n    \
+                         set_state({z0}, {b1} * u {a1} * {g} * u + state({z1}));\n    \
+                         u = {g} * u;\n",
+                        b1 = literal(s.b[1]),
+                        b2 = literal(s.b[2]),
+                        a1 = signed(-s.a[0]),
+                        a2 = signed(-s.a[1]),
+                        g = literal(gain),
+                    ));
+                }
+                script.push_str("}\nlet y = 0.0;\n");
+                for (i, s) in sections.iter().enumerate() {
+                    let (z0, z1) = (2 * i, 2 * i + 1);
+                    script.push_str(&format!(
+                        "// Section {n}\n\
+                         y = {b0} * x + state({z0});\n\
+                         set_state({z0}, {b1} * x {a1} * y + state({z1}));\n\
+                         set_state({z1}, {b2} * x {a2} * y);\n\
+                         x = y;\n",
+                        n = i + 1,
+                        b0 = literal(s.b[0]),
+                        b1 = literal(s.b[1]),
+                        b2 = literal(s.b[2]),
+                        a1 = signed(-s.a[0]),
+                        a2 = signed(-s.a[1]),
+                    ));
+                }
+                script.push_str("x\n");
+            }
+            FilterCoefficients::Taps(taps) => {
+                let list: Vec<String> = taps.iter().map(|&h| literal(h)).collect();
+                script.push_str(&format!("let h = [{}];\n", list.join(", ")));
+                script.push_str(
+                    "if !has_state() {\n    \
+                     // Start from the steady state of the first sample\n    \
+                     for i in 0..h.len() - 1 {\n        set_state(i, value);\n    }\n}\n\
+                     let y = h[0] * value;\n\
+                     for i in 1..h.len() {\n    y += h[i] * state(i - 1);\n}\n\
+                     // Shift the delay line\n\
+                     let i = h.len() - 2;\n\
+                     while i > 0 {\n    set_state(i, state(i - 1));\n    i -= 1;\n}\n\
+                     set_state(0, value);\n\
+                     y\n",
+                );
+            }
+        }
+        script
+    }
+}
+
+/// Script literal for a coefficient (always with a decimal point)
+fn literal(value: f64) -> String {
+    format!("{:.16e}", value)
+}
+
+/// Script literal with an explicit `+` / `-` operator for use inside a sum
+fn signed(value: f64) -> String {
+    if value < 0.0 {
+        format!("- {}", literal(-value))
+    } else {
+        format!("+ {}", literal(value))
+    }
+}
+
+/// State and output of a section that has settled at a constant input
+fn steady_state(section: &Biquad, input: f64) -> ([f64; 2], f64) {
+    let Some(gain) = section.dc_gain() else {
+        return ([0.0, 0.0], 0.0);
+    };
+    let output = gain * input;
+    let z1 = section.b[2] * input - section.a[1] * output;
+    let z0 = section.b[1] * input - section.a[0] * output + z1;
+    ([z0, z1], output)
+}
+
+/// Zeros, poles and gain of a transfer function
+struct Zpk {
+    zeros: Vec<Complex<f64>>,
+    poles: Vec<Complex<f64>>,
+    gain: f64,
+}
+
+/// Pre-warp a frequency (Hz) for the bilinear transform (rad/s)
+fn prewarp(frequency: f64, sample_rate: f64) -> f64 {
+    2.0 * sample_rate * (PI * frequency / sample_rate).tan()
+}
+
+/// Product of `value - root` over all roots
+fn product(roots: &[Complex<f64>], value: Complex<f64>) -> Complex<f64> {
+    roots
+        .iter()
+        .fold(Complex::new(1.0, 0.0), |acc, r| acc * (value - r))
+}
+
+/// Analog lowpass prototype with a 1 rad/s cutoff
+fn analog_prototype(spec: &FilterSpec) -> Zpk {
+    let n = spec.order;
+    let nf = n as f64;
+    match spec.method {
+        FilterMethod::Chebyshev => {
+            let eps = (10f64.powf(spec.ripple_db / 10.0) - 1.0).sqrt();
+            let mu = (1.0 / eps).asinh() / nf;
+            let poles: Vec<Complex<f64>> = (0..n)
+                .map(|k| {
+                    let theta = PI * (2 * k + 1) as f64 / (2.0 * nf);
+                    Complex::new(-mu.sinh() * theta.sin(), mu.cosh() * theta.cos())
+                })
+                .collect();
+            let mut gain = product(&poles, Complex::new(0.0, 0.0)).re;
+            if n.is_multiple_of(2) {
+                gain /= (1.0 + eps * eps).sqrt();
+            }
+            Zpk {
+                zeros: Vec::new(),
+                poles,
+                gain,
+            }
+        }
+        _ => Zpk {
+            zeros: Vec::new(),
+            poles: (0..n)
+                .map(|k| Complex::from_polar(1.0, PI / 2.0 + PI * (2 * k + 1) as f64 / (2.0 * nf)))
+                .collect(),
+            gain: 1.0,
+        },
+    }
+}
+
+/// Move the prototype cutoff to the requested band (analog domain)
+fn transform_band(proto: Zpk, spec: &FilterSpec) -> Zpk {
+    let fs = spec.sample_rate;
+    let w1 = prewarp(spec.cutoff_hz, fs);
+    let degree = proto.poles.len() - proto.zeros.len();
+    let zero = Complex::new(0.0, 0.0);
+    match spec.filter_type {
+        FilterType::Lowpass => Zpk {
+            zeros: proto.zeros.iter().map(|z| z * w1).collect(),
+            poles: proto.poles.iter().map(|p| p * w1).collect(),
+            gain: proto.gain * w1.powi(degree as i32),
+        },
+        FilterType::Highpass => {
+            let gain = proto.gain * (product(&proto.zeros, zero) / product(&proto.poles, zero)).re;
+            let mut zeros: Vec<Complex<f64>> = proto.zeros.iter().map(|z| w1 / z).collect();
+            zeros.extend(std::iter::repeat_n(zero, degree));
+            Zpk {
+                zeros,
+                poles: proto.poles.iter().map(|p| w1 / p).collect(),
+                gain,
+            }
+        }
+        FilterType::Bandpass | FilterType::Bandstop => {
+            let w2 = prewarp(spec.cutoff_high_hz, fs);
+            let wo = (w1 * w2).sqrt();
+            let bw = w2 - w1;
+            // Each root r of the scaled prototype maps to r +/- sqrt(r^2 - wo^2)
+            let split = |roots: Vec<Complex<f64>>| -> Vec<Complex<f64>> {
+                roots
+                    .into_iter()
+                    .flat_map(|r| {
+                        let d = (r * r - wo * wo).sqrt();
+                        [r + d, r - d]
+                    })
+                    .collect()
+            };
+            if spec.filter_type == FilterType::Bandpass {
+                let mut zeros = split(proto.zeros.iter().map(|z| z * bw / 2.0).collect());
+                zeros.extend(std::iter::repeat_n(zero, degree));
+                Zpk {
+                    zeros,
+                    poles: split(proto.poles.iter().map(|p| p * bw / 2.0).collect()),
+                    gain: proto.gain * bw.powi(degree as i32),
+                }
+            } else {
+                let gain =
+                    proto.gain * (product(&proto.zeros, zero) / product(&proto.poles, zero)).re;
+                let mut zeros = split(proto.zeros.iter().map(|z| (bw / 2.0) / z).collect());
+                for _ in 0..degree {
+                    zeros.push(Complex::new(0.0, wo));
+                    zeros.push(Complex::new(0.0, -wo));
+                }
+                Zpk {
+                    zeros,
+                    poles: split(proto.poles.iter().map(|p| (bw / 2.0) / p).collect()),
+                    gain,
+                }
+            }
+        }
+    }
+}
+
+/// Map an analog filter to the z-plane with the bilinear transform
+fn bilinear(analog: Zpk, sample_rate: f64) -> Zpk {
+    let fs2 = Complex::new(2.0 * sample_rate, 0.0);
+    let map = |r: &Complex<f64>| (fs2 + r) / (fs2 - r);
+    let degree = analog.poles.len() - analog.zeros.len();
+    let gain = analog.gain * (product(&analog.zeros, fs2) / product(&analog.poles, fs2)).re;
+    let mut zeros: Vec<Complex<f64>> = analog.zeros.iter().map(map).collect();
+    zeros.extend(std::iter::repeat_n(Complex::new(-1.0, 0.0), degree));
+    Zpk {
+        zeros,
+        poles: analog.poles.iter().map(map).collect(),
+        gain,
+    }
+}
+
+/// Group roots into polynomials in z^-1 of degree two (conjugate pairs and
+/// pairs of real roots) and at most one of degree one
+fn quadratics(roots: &[Complex<f64>]) -> Vec<[f64; 3]> {
+    let is_real = |r: &Complex<f64>| r.im.abs() <= 1e-9 * r.norm().max(1.0);
+    let mut polys: Vec<[f64; 3]> = roots
+        .iter()
+        .filter(|r| !is_real(r) && r.im > 0.0)
+        .map(|r| [1.0, -2.0 * r.re, r.norm_sqr()])
+        .collect();
+    let reals: Vec<f64> = roots.iter().filter(|r| is_real(r)).map(|r| r.re).collect();
+    for pair in reals.chunks(2) {
+        match pair {
+            [a, b] => polys.push([1.0, -(a + b), a * b]),
+            [a] => polys.push([1.0, -a, 0.0]),
+            _ => {}
+        }
+    }
+    polys
+}
+
+/// Butterworth and Chebyshev designs as second-order sections
+fn design_iir(spec: &FilterSpec) -> Vec<Biquad> {
+    let digital = bilinear(
+        transform_band(analog_prototype(spec), spec),
+        spec.sample_rate,
+    );
+    let zeros = quadratics(&digital.zeros);
+    let poles = quadratics(&digital.poles);
+    let count = zeros.len().max(poles.len());
+    (0..count)
+        .map(|i| {
+            let mut b = zeros.get(i).copied().unwrap_or([1.0, 0.0, 0.0]);
+            let a = poles.get(i).copied().unwrap_or([1.0, 0.0, 0.0]);
+            if i == 0 {
+                b = b.map(|c| c * digital.gain);
+            }
+            Biquad::normalized(b, a)
+        })
+        .collect()
+}
+
+/// Second-order section from the RBJ audio EQ cookbook
+fn rbj_biquad(filter_type: FilterType, frequency: f64, q: f64, sample_rate: f64) -> Biquad {
+    let w0 = 2.0 * PI * frequency / sample_rate;
+    let (sin, cos) = w0.sin_cos();
+    let alpha = sin / (2.0 * q);
+    let a = [1.0 + alpha, -2.0 * cos, 1.0 - alpha];
+    let b = match filter_type {
+        FilterType::Lowpass => [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+        FilterType::Highpass => [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+        FilterType::Bandpass => [alpha, 0.0, -alpha],
+        FilterType::Bandstop => [1.0, -2.0 * cos, 1.0],
+    };
+    Biquad::normalized(b, a)
+}
+
+/// Windowed-sinc FIR taps
+fn design_fir(spec: &FilterSpec) -> Vec<f64> {
+    // Highpass and bandstop need a center tap (odd length)
+    let mut taps = spec.order + 1;
+    if matches!(
+        spec.filter_type,
+        FilterType::Highpass | FilterType::Bandstop
+    ) && taps.is_multiple_of(2)
+    {
+        taps += 1;
+    }
+    let m = (taps - 1) as f64;
+    let lowpass = |cutoff: f64| -> Vec<f64> {
+        let fc = cutoff / spec.sample_rate;
+        let h: Vec<f64> = (0..taps)
+            .map(|n| {
+                let x = n as f64 - m / 2.0;
+                let sinc = if x == 0.0 {
+                    2.0 * fc
+                } else {
+                    (2.0 * PI * fc * x).sin() / (PI * x)
+                };
+                sinc * spec.window.coefficient(n, taps - 1)
+            })
+            .collect();
+        let sum: f64 = h.iter().sum();
+        h.into_iter().map(|v| v / sum).collect()
+    };
+    let impulse = |h: Vec<f64>| -> Vec<f64> {
+        h.into_iter()
+            .enumerate()
+            .map(|(n, v)| if 2 * n == taps - 1 { 1.0 - v } else { -v })
+            .collect()
+    };
+    match spec.filter_type {
+        FilterType::Lowpass => lowpass(spec.cutoff_hz),
+        FilterType::Highpass => impulse(lowpass(spec.cutoff_hz)),
+        FilterType::Bandpass | FilterType::Bandstop => {
+            let band: Vec<f64> = lowpass(spec.cutoff_high_hz)
+                .into_iter()
+                .zip(lowpass(spec.cutoff_hz))
+                .map(|(high, low)| high - low)
+                .collect();
+            if spec.filter_type == FilterType::Bandpass {
+                band
+            } else {
+                impulse(band)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripting::{ExecutionContext, ScriptEngine};
+
+    fn spec(method: FilterMethod, filter_type: FilterType, order: usize) -> FilterSpec {
+        FilterSpec {
+            method,
+            filter_type,
+            order,
+            ..FilterSpec::default()
+        }
+    }
+
+    fn sine(frequency: f64, sample_rate: f64, n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|i| (2.0 * PI * frequency * i as f64 / sample_rate).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_butterworth_lowpass_and_highpass() {
+        let lowpass =
+            Filter::design(&spec(FilterMethod::Butterworth, FilterType::Lowpass, 4)).unwrap();
+        assert!(lowpass.is_stable());
+        assert!(lowpass.magnitude_db_at(0.01).abs() < 0.01);
+        assert!((lowpass.magnitude_db_at(10.0) + 3.01).abs() < 0.05);
+        assert!(lowpass.magnitude_db_at(40.0) < -40.0);
+        match &lowpass.coefficients {
+            FilterCoefficients::Sections(sections) => assert_eq!(sections.len(), 2),
+            FilterCoefficients::Taps(_) => panic!("expected sections"),
+        }
+
+        let highpass =
+            Filter::design(&spec(FilterMethod::Butterworth, FilterType::Highpass, 3)).unwrap();
+        assert!(highpass.is_stable());
+        assert!((highpass.magnitude_db_at(10.0) + 3.01).abs() < 0.05);
+        assert!(highpass.magnitude_db_at(1.0) < -50.0);
+        assert!(highpass.magnitude_db_at(49.9).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_chebyshev_ripple() {
+        let mut s = spec(FilterMethod::Chebyshev, FilterType::Lowpass, 4);
+        s.ripple_db = 1.0;
+        let filter = Filter::design(&s).unwrap();
+        assert!(filter.is_stable());
+        // Ripple stays within 1 dB up to the cutoff, where the gain is -1 dB
+        for i in 1..100 {
+            let gain = filter.magnitude_db_at(10.0 * i as f64 / 100.0);
+            assert!(gain > -1.01 && gain < 0.01, "gain {}", gain);
+        }
+        assert!((filter.magnitude_db_at(10.0) + 1.0).abs() < 0.05);
+        // Steeper than the Butterworth of the same order
+        let butterworth =
+            Filter::design(&spec(FilterMethod::Butterworth, FilterType::Lowpass, 4)).unwrap();
+        assert!(filter.magnitude_db_at(20.0) < butterworth.magnitude_db_at(20.0) - 5.0);
+    }
+
+    #[test]
+    fn test_band_filters() {
+        let mut s = spec(FilterMethod::Butterworth, FilterType::Bandpass, 2);
+        s.cutoff_hz = 5.0;
+        s.cutoff_high_hz = 15.0;
+        let bandpass = Filter::design(&s).unwrap();
+        assert!(bandpass.is_stable());
+        assert!((bandpass.magnitude_db_at(5.0) + 3.01).abs() < 0.05);
+        assert!((bandpass.magnitude_db_at(15.0) + 3.01).abs() < 0.05);
+        assert!(bandpass.magnitude_db_at(9.0).abs() < 0.1);
+        assert!(bandpass.magnitude_db_at(40.0) < -30.0);
+
+        s.filter_type = FilterType::Bandstop;
+        let bandstop = Filter::design(&s).unwrap();
+        assert!(bandstop.is_stable());
+        assert!((bandstop.magnitude_db_at(5.0) + 3.01).abs() < 0.05);
+        assert!((bandstop.magnitude_db_at(15.0) + 3.01).abs() < 0.05);
+        assert!(bandstop.magnitude_db_at(0.01).abs() < 0.01);
+        assert!(bandstop.magnitude_db_at(40.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_notch_and_biquad() {
+        let mut s = spec(FilterMethod::Notch, FilterType::Lowpass, 2);
+        s.cutoff_hz = 50.0;
+        s.q = 5.0;
+        s.sample_rate = 1000.0;
+        let notch = Filter::design(&s).unwrap();
+        assert!(notch.magnitude_db_at(50.0) < -60.0);
+        assert!(notch.magnitude_db_at(0.01).abs() < 0.01);
+        assert!(notch.magnitude_db_at(200.0).abs() < 0.1);
+
+        // A Q of 1/sqrt(2) biquad lowpass is a second-order Butterworth
+        let biquad = Filter::design(&spec(FilterMethod::Biquad, FilterType::Lowpass, 2)).unwrap();
+        let butterworth =
+            Filter::design(&spec(FilterMethod::Butterworth, FilterType::Lowpass, 2)).unwrap();
+        for f in [1.0, 10.0, 30.0] {
+            assert!((biquad.magnitude_db_at(f) - butterworth.magnitude_db_at(f)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_fir_design() {
+        let mut s = spec(FilterMethod::Fir, FilterType::Lowpass, 64);
+        let lowpass = Filter::design(&s).unwrap();
+        let FilterCoefficients::Taps(taps) = &lowpass.coefficients else {
+            panic!("expected taps");
+        };
+        assert_eq!(taps.len(), 65);
+        // Linear phase: symmetric taps
+        for i in 0..taps.len() / 2 {
+            assert!((taps[i] - taps[taps.len() - 1 - i]).abs() < 1e-12);
+        }
+        assert!(lowpass.magnitude_db_at(0.01).abs() < 0.01);
+        assert!((lowpass.magnitude_db_at(10.0) + 6.0).abs() < 0.5);
+        assert!(lowpass.magnitude_db_at(25.0) < -40.0);
+
+        // Highpass taps are forced to an odd length
+        s.filter_type = FilterType::Highpass;
+        s.order = 31;
+        let highpass = Filter::design(&s).unwrap();
+        let FilterCoefficients::Taps(taps) = &highpass.coefficients else {
+            panic!("expected taps");
+        };
+        assert_eq!(taps.len(), 33);
+        assert!(highpass.magnitude_db_at(0.01) < -40.0);
+        assert!(highpass.magnitude_db_at(40.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_apply() {
+        let filter =
+            Filter::design(&spec(FilterMethod::Butterworth, FilterType::Lowpass, 4)).unwrap();
+
+        // Starts settled: a constant input gives the same constant output
+        let out = filter.apply(&[3.0; 50]);
+        assert!(out.iter().all(|v| (v - 3.0).abs() < 1e-9));
+
+        // 2 Hz passes, 40 Hz is removed
+        let out = filter.apply(&sine(2.0, 100.0, 1000));
+        let peak = out[500..].iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        assert!((peak - 1.0).abs() < 0.02);
+        let out = filter.apply(&sine(40.0, 100.0, 1000));
+        let peak = out[500..].iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        assert!(peak < 0.01);
+
+        let mut s = spec(FilterMethod::Fir, FilterType::Lowpass, 20);
+        s.window = WindowFunction::Hann;
+        let fir = Filter::design(&s).unwrap();
+        let out = fir.apply(&[2.0; 30]);
+        assert!(out.iter().all(|v| (v - 2.0).abs() < 1e-9));
+        assert!(fir.apply(&[]).is_empty());
+    }
+
+    /// Run a generated script sample by sample, carrying state like the converter engine
+    fn run_script(script: &str, samples: &[f64]) -> Vec<f64> {
+        let engine = ScriptEngine::new();
+        let converter = engine.compile("filter", script).unwrap();
+        let mut state = Vec::new();
+        samples
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| {
+                let ctx = ExecutionContext::new(i as f64 * 0.01, 0.01, f64::NAN, f64::NAN)
+                    .with_state(std::mem::take(&mut state));
+                let y = engine.execute(&converter, x, ctx).unwrap();
+                state = engine.take_state();
+                y
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_script_matches_apply() {
+        let input: Vec<f64> = sine(3.0, 100.0, 200)
+            .iter()
+            .zip(sine(35.0, 100.0, 200))
+            .map(|(a, b)| 5.0 + a + 0.5 * b)
+            .collect();
+
+        let mut s = spec(FilterMethod::Chebyshev, FilterType::Bandpass, 3);
+        s.cutoff_hz = 2.0;
+        s.cutoff_high_hz = 8.0;
+        let mut fir = spec(FilterMethod::Fir, FilterType::Lowpass, MAX_FIR_TAPS - 1);
+        fir.window = WindowFunction::Blackman;
+        for spec in [s, fir] {
+            let filter = Filter::design(&spec).unwrap();
+            let script = filter.to_script();
+            let expected = filter.apply(&input);
+            let actual = run_script(&script, &input);
+            for (e, a) in expected.iter().zip(&actual) {
+                assert!((e - a).abs() < 1e-9, "{} != {}\n{}", e, a, script);
+            }
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut s = FilterSpec::default();
+        assert!(s.validate().is_ok());
+        s.cutoff_hz = 60.0;
+        assert!(Filter::design(&s).is_err());
+        s.cutoff_hz = 10.0;
+        s.filter_type = FilterType::Bandpass;
+        s.cutoff_high_hz = 5.0;
+        assert!(s.validate().is_err());
+        s.filter_type = FilterType::Lowpass;
+        s.order = MAX_IIR_ORDER + 1;
+        assert!(s.validate().is_err());
+        s.method = FilterMethod::Fir;
+        s.order = MAX_FIR_TAPS;
+        assert!(s.validate().is_err());
+        s.method = FilterMethod::Notch;
+        s.q = 0.0;
+        assert!(s.validate().is_err());
+        assert_eq!(
+            FilterSpec::default().summary(),
+            "Butterworth lowpass, order 2, 10 Hz @ 100 Hz"
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! Value distributions: histograms and summary statistics
+//!
+//! Bins the values of a signal over a time range and summarises their
+//! distribution: mean, standard deviation, percentiles, skewness and
+//! kurtosis, plus the normal distribution with the same mean and standard
+//! deviation for comparison.
+
+use std::f64::consts::PI;
+
+use crate::types::DataPoint;
+
+/// Maximum number of histogram bins
+pub const MAX_BINS: usize = 1000;
+
+/// Bin count used for automatic binning when the data has no spread
+const MIN_AUTO_BINS: usize = 1;
+
+/// Summary statistics of a set of values
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Distribution {
+    /// Number of values
+    pub count: usize,
+    /// Minimum value
+    pub min: f64,
+    /// Maximum value
+    pub max: f64,
+    /// Arithmetic mean
+    pub mean: f64,
+    /// Population standard deviation
+    pub std_dev: f64,
+    /// Median (50th percentile)
+    pub p50: f64,
+    /// 95th percentile
+    pub p95: f64,
+    /// 99th percentile
+    pub p99: f64,
+    /// Skewness (0 for a symmetric distribution)
+    pub skewness: f64,
+    /// Excess kurtosis (0 for a normal distribution)
+    pub kurtosis: f64,
+}
+
+impl Distribution {
+    /// Compute the statistics of a set of values
+    ///
+    /// Non-finite values are ignored. Returns `None` if no values remain.
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+
+        let n = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / n;
+        let (m2, m3, m4) = sorted.iter().fold((0.0, 0.0, 0.0), |(m2, m3, m4), v| {
+            let d = v - mean;
+            let d2 = d * d;
+            (m2 + d2, m3 + d2 * d, m4 + d2 * d2)
+        });
+        let (m2, m3, m4) = (m2 / n, m3 / n, m4 / n);
+        let (skewness, kurtosis) = if m2 > 0.0 {
+            (m3 / m2.powf(1.5), m4 / (m2 * m2) - 3.0)
+        } else {
+            (0.0, 0.0)
+        };
+
+        Some(Self {
+            count: sorted.len(),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean,
+            std_dev: m2.sqrt(),
+            p50: percentile(&sorted, 50.0),
+            p95: percentile(&sorted, 95.0),
+            p99: percentile(&sorted, 99.0),
+            skewness,
+            kurtosis,
+        })
+    }
+
+    /// Normal probability density with this mean and standard deviation
+    pub fn normal_pdf(&self, x: f64) -> f64 {
+        if self.std_dev <= 0.0 {
+            return 0.0;
+        }
+        let z = (x - self.mean) / self.std_dev;
+        (-0.5 * z * z).exp() / (self.std_dev * (2.0 * PI).sqrt())
+    }
+}
+
+/// Percentile of sorted values with linear interpolation between ranks
+///
+/// `p` is in percent (0–100). Returns NaN for an empty slice.
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => f64::NAN,
+        1 => sorted[0],
+        len => {
+            let rank = (p / 100.0).clamp(0.0, 1.0) * (len - 1) as f64;
+            let lower = rank.floor() as usize;
+            let upper = (lower + 1).min(len - 1);
+            let frac = rank - lower as f64;
+            sorted[lower] + (sorted[upper] - sorted[lower]) * frac
+        }
+    }
+}
+
+/// Values of data points within `[start, end]` seconds, skipping gaps
+pub fn values_in_range<'a, I>(points: I, start: f64, end: f64) -> Vec<f64>
+where
+    I: IntoIterator<Item = &'a DataPoint>,
+{
+    points
+        .into_iter()
+        .filter(|p| !p.is_gap() && p.converted_value.is_finite())
+        .filter(|p| {
+            let t = p.timestamp.as_secs_f64();
+            t >= start && t <= end
+        })
+        .map(|p| p.converted_value)
+        .collect()
+}
+
+/// A histogram of values in equal-width bins
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// Lower edge of the first bin
+    pub start: f64,
+    /// Width of each bin
+    pub bin_width: f64,
+    /// Number of values in each bin
+    pub counts: Vec<u64>,
+}
+
+impl Histogram {
+    /// Bin values into `bins` equal-width bins spanning their range
+    ///
+    /// With `bins == 0` the bin count is chosen automatically
+    /// (Freedman–Diaconis rule). Non-finite values are ignored. Returns
+    /// `None` if no values remain.
+    pub fn from_values(values: &[f64], bins: usize) -> Option<Self> {
+        let mut finite: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+        if finite.is_empty() {
+            return None;
+        }
+        finite.sort_by(f64::total_cmp);
+        let min = finite[0];
+        let max = finite[finite.len() - 1];
+
+        let bins = match bins {
+            0 => auto_bin_count(&finite),
+            n => n.min(MAX_BINS),
+        };
+
+        // Constant data: one bin centred on the value
+        let (start, bin_width) = if max > min {
+            (min, (max - min) / bins as f64)
+        } else {
+            (min - 0.5, 1.0 / bins as f64)
+        };
+
+        let mut counts = vec![0u64; bins];
+        for v in &finite {
+            let index = (((v - start) / bin_width) as usize).min(bins - 1);
+            counts[index] += 1;
+        }
+
+        Some(Self {
+            start,
+            bin_width,
+            counts,
+        })
+    }
+
+    /// Number of bins
+    pub fn bin_count(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Center of bin `index`
+    pub fn bin_center(&self, index: usize) -> f64 {
+        self.start + (index as f64 + 0.5) * self.bin_width
+    }
+
+    /// Upper edge of the last bin
+    pub fn end(&self) -> f64 {
+        self.start + self.bin_width * self.counts.len() as f64
+    }
+
+    /// Total number of values binned
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Expected bin counts of a normal fit, as `[x, count]` curve points
+    ///
+    /// The density is scaled by the number of values and the bin width so the
+    /// curve is directly comparable with the bar heights.
+    pub fn normal_fit(&self, distribution: &Distribution, points: usize) -> Vec<[f64; 2]> {
+        if distribution.std_dev <= 0.0 || points < 2 {
+            return Vec::new();
+        }
+        // Cover the histogram and at least ±4σ around the mean
+        let lo = self
+            .start
+            .min(distribution.mean - 4.0 * distribution.std_dev);
+        let hi = self
+            .end()
+            .max(distribution.mean + 4.0 * distribution.std_dev);
+        let scale = self.total() as f64 * self.bin_width;
+        (0..points)
+            .map(|i| {
+                let x = lo + (hi - lo) * i as f64 / (points - 1) as f64;
+                [x, distribution.normal_pdf(x) * scale]
+            })
+            .collect()
+    }
+}
+
+/// Freedman–Diaconis bin count for sorted values, clamped to `MAX_BINS`
+fn auto_bin_count(sorted: &[f64]) -> usize {
+    let range = sorted[sorted.len() - 1] - sorted[0];
+    let iqr = percentile(sorted, 75.0) - percentile(sorted, 25.0);
+    let n = sorted.len() as f64;
+    if range <= 0.0 {
+        return MIN_AUTO_BINS;
+    }
+    let bins = if iqr > 0.0 {
+        range / (2.0 * iqr / n.cbrt())
+    } else {
+        // Sturges' rule when most values are identical
+        n.log2() + 1.0
+    };
+    (bins.ceil() as usize).clamp(MIN_AUTO_BINS, MAX_BINS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<f64> = (0..=100).map(f64::from).collect();
+        assert_eq!(percentile(&sorted, 50.0), 50.0);
+        assert_eq!(percentile(&sorted, 95.0), 95.0);
+        assert_eq!(percentile(&[1.0, 2.0], 50.0), 1.5);
+        assert_eq!(percentile(&[3.0], 99.0), 3.0);
+        assert!(percentile(&[], 50.0).is_nan());
+    }
+
+    #[test]
+    fn test_distribution_moments() {
+        // Symmetric two-point distribution: no skew, excess kurtosis of -2
+        let values = [-1.0, 1.0, -1.0, 1.0];
+        let dist = Distribution::from_values(&values).unwrap();
+        assert_eq!(dist.count, 4);
+        assert!(dist.mean.abs() < 1e-12);
+        assert!((dist.std_dev - 1.0).abs() < 1e-12);
+        assert!(dist.skewness.abs() < 1e-12);
+        assert!((dist.kurtosis + 2.0).abs() < 1e-12);
+
+        // Uniform distribution has excess kurtosis of -1.2
+        let uniform: Vec<f64> = (0..10_000).map(|i| i as f64 / 10_000.0).collect();
+        let dist = Distribution::from_values(&uniform).unwrap();
+        assert!((dist.kurtosis + 1.2).abs() < 1e-3);
+        assert!((dist.p99 - 0.99).abs() < 1e-3);
+
+        assert!(Distribution::from_values(&[f64::NAN]).is_none());
+    }
+
+    #[test]
+    fn test_histogram_bins() {
+        let values = [0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.9, 4.0];
+        let hist = Histogram::from_values(&values, 4).unwrap();
+        assert_eq!(hist.bin_count(), 4);
+        assert_eq!(hist.counts, vec![2, 2, 2, 3]);
+        assert_eq!(hist.total(), 9);
+        assert!((hist.bin_center(0) - 0.5).abs() < 1e-12);
+        assert!((hist.end() - 4.0).abs() < 1e-12);
+
+        // Constant data lands in a single bin
+        let hist = Histogram::from_values(&[2.0; 5], 0).unwrap();
+        assert_eq!(hist.counts, vec![5]);
+        assert!((hist.bin_center(0) - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_normal_fit_matches_counts() {
+        // Approximately normal data: sums of 12 pseudo-random uniform values
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut uniform = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let values: Vec<f64> = (0..20_000)
+            .map(|_| (0..12).map(|_| uniform()).sum::<f64>())
+            .collect();
+        let dist = Distribution::from_values(&values).unwrap();
+        let hist = Histogram::from_values(&values, 0).unwrap();
+        assert!(hist.bin_count() > 10);
+
+        let fit = hist.normal_fit(&dist, 200);
+        assert_eq!(fit.len(), 200);
+        let peak_fit = fit.iter().map(|p| p[1]).fold(0.0, f64::max);
+        let peak_count = *hist.counts.iter().max().unwrap() as f64;
+        assert!((peak_fit - peak_count).abs() / peak_count < 0.25);
+    }
+}
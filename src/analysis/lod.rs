@@ -0,0 +1,374 @@
+//! Min/max level-of-detail pyramid for plotting long signals
+//!
+//! Each level summarises the signal in buckets holding the minimum and
+//! maximum sample (with their timestamps), [`LOD_FACTOR`] times coarser than
+//! the level below. Drawing both extremes of every bucket keeps spikes
+//! visible however far the plot is zoomed out. The pyramid is built
+//! incrementally as samples arrive; each level keeps at most
+//! [`MAX_LOD_BUCKETS`] buckets, so fine levels cover recent data and coarse
+//! levels reach back over the whole session at bounded memory.
+
+use std::collections::VecDeque;
+
+use crate::types::DataPoint;
+
+/// Number of buckets (or samples, for level 0) merged into one bucket of the
+/// next level
+pub const LOD_FACTOR: usize = 4;
+
+/// Maximum number of buckets retained per level
+pub const MAX_LOD_BUCKETS: usize = 16_384;
+
+/// Maximum number of levels; the coarsest bucket spans `4^12` samples
+pub const MAX_LOD_LEVELS: usize = 12;
+
+/// Extremes of a run of samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LodBucket {
+    /// Time of the first sample (seconds)
+    pub start: f64,
+    /// Time of the last sample (seconds)
+    pub end: f64,
+    /// Minimum value
+    pub min: f64,
+    /// Time of the minimum (seconds)
+    pub min_time: f64,
+    /// Maximum value
+    pub max: f64,
+    /// Time of the maximum (seconds)
+    pub max_time: f64,
+    /// Number of samples summarised (0 for a pause gap)
+    pub count: usize,
+}
+
+impl LodBucket {
+    /// Bucket holding a single sample
+    fn sample(time: f64, value: f64) -> Self {
+        Self {
+            start: time,
+            end: time,
+            min: value,
+            min_time: time,
+            max: value,
+            max_time: time,
+            count: 1,
+        }
+    }
+
+    /// Bucket marking a pause gap, drawn as a break in the line
+    fn gap(time: f64) -> Self {
+        Self {
+            start: time,
+            end: time,
+            min: f64::NAN,
+            min_time: time,
+            max: f64::NAN,
+            max_time: time,
+            count: 0,
+        }
+    }
+
+    /// Whether this bucket marks a pause gap
+    pub fn is_gap(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Extend this bucket with a later one
+    fn merge(&mut self, other: &LodBucket) {
+        self.end = other.end;
+        if other.min < self.min {
+            self.min = other.min;
+            self.min_time = other.min_time;
+        }
+        if other.max > self.max {
+            self.max = other.max;
+            self.max_time = other.max_time;
+        }
+        self.count += other.count;
+    }
+
+    /// Append the bucket's extremes to `out` in time order
+    fn push_points(&self, out: &mut Vec<[f64; 2]>) {
+        if self.is_gap() {
+            out.push([self.start, f64::NAN]);
+        } else if self.count == 1 || self.min_time == self.max_time {
+            out.push([self.min_time, self.min]);
+        } else if self.min_time < self.max_time {
+            out.push([self.min_time, self.min]);
+            out.push([self.max_time, self.max]);
+        } else {
+            out.push([self.max_time, self.max]);
+            out.push([self.min_time, self.min]);
+        }
+    }
+}
+
+/// One resolution of the pyramid
+#[derive(Debug, Default)]
+struct Level {
+    /// Completed buckets, oldest first
+    buckets: VecDeque<LodBucket>,
+    /// Bucket still being filled
+    pending: Option<LodBucket>,
+    /// Number of samples or child buckets merged into `pending`
+    pending_parts: usize,
+    /// Whether old buckets have been dropped from this level
+    evicted: bool,
+}
+
+impl Level {
+    /// Whether the level holds data from before `time`, or all of its data
+    fn covers(&self, time: f64) -> bool {
+        !self.evicted || self.buckets.front().is_some_and(|b| b.start <= time)
+    }
+
+    fn push_bucket(&mut self, bucket: LodBucket) {
+        if self.buckets.len() >= MAX_LOD_BUCKETS {
+            self.buckets.pop_front();
+            self.evicted = true;
+        }
+        self.buckets.push_back(bucket);
+    }
+
+    /// Index range of completed buckets overlapping `[start, end]`
+    fn range(&self, start: f64, end: f64) -> std::ops::Range<usize> {
+        let first = self.buckets.partition_point(|b| b.end < start);
+        let last = self.buckets.partition_point(|b| b.start <= end);
+        first..last.max(first)
+    }
+}
+
+/// Incrementally built min/max pyramid of one signal
+#[derive(Debug)]
+pub struct MinMaxPyramid {
+    /// Levels from finest to coarsest
+    levels: Vec<Level>,
+    /// Total number of samples pushed
+    sample_count: u64,
+    /// Total number of points pushed, including gap markers
+    point_count: u64,
+}
+
+impl Default for MinMaxPyramid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MinMaxPyramid {
+    /// Create an empty pyramid
+    pub fn new() -> Self {
+        Self {
+            levels: (0..MAX_LOD_LEVELS).map(|_| Level::default()).collect(),
+            sample_count: 0,
+            point_count: 0,
+        }
+    }
+
+    /// Build a pyramid from a sequence of data points
+    pub fn from_points<'a, I>(points: I) -> Self
+    where
+        I: IntoIterator<Item = &'a DataPoint>,
+    {
+        let mut pyramid = Self::new();
+        for point in points {
+            pyramid.push(point);
+        }
+        pyramid
+    }
+
+    /// Add a data point; gap markers break the line at every level
+    pub fn push(&mut self, point: &DataPoint) {
+        let time = point.timestamp.as_secs_f64();
+        self.point_count += 1;
+        if point.is_gap() {
+            self.push_gap(time);
+        } else if point.converted_value.is_finite() {
+            self.sample_count += 1;
+            self.absorb(0, LodBucket::sample(time, point.converted_value));
+        }
+    }
+
+    /// Total number of samples pushed
+    pub fn sample_count(&self) -> u64 {
+        self.sample_count
+    }
+
+    /// Total number of points pushed, including gap markers
+    ///
+    /// Changes whenever the plotted data does, so it can key render caches.
+    pub fn point_count(&self) -> u64 {
+        self.point_count
+    }
+
+    /// Check if no samples have been pushed
+    pub fn is_empty(&self) -> bool {
+        self.sample_count == 0
+    }
+
+    /// Remove all data
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Choose the finest level that covers `[start, end]` in at most
+    /// `max_buckets` buckets, falling back to the coarsest level
+    pub fn select_level(&self, start: f64, end: f64, max_buckets: usize) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        let fits = |(index, level): &(usize, &Level)| {
+            let tail = self.levels[..=*index]
+                .iter()
+                .filter(|l| l.pending.is_some())
+                .count();
+            level.covers(start) && level.range(start, end).len() + tail <= max_buckets
+        };
+        self.levels
+            .iter()
+            .enumerate()
+            .find(fits)
+            .map(|(index, _)| index)
+            .or(Some(MAX_LOD_LEVELS - 1))
+    }
+
+    /// Plot points for `[start, end]` seconds, at most about `max_points`
+    ///
+    /// Each bucket contributes its minimum and maximum in time order, and a
+    /// NaN point at pause gaps. One bucket either side of the range is
+    /// included so lines run to the plot edges.
+    pub fn points(&self, start: f64, end: f64, max_points: usize) -> Vec<[f64; 2]> {
+        let Some(index) = self.select_level(start, end, (max_points / 2).max(1)) else {
+            return Vec::new();
+        };
+        let level = &self.levels[index];
+        let range = level.range(start, end);
+        let first = range.start.saturating_sub(1);
+        let last = (range.end + 1).min(level.buckets.len());
+
+        let mut out = Vec::with_capacity((last - first + index + 1) * 2);
+        for bucket in level.buckets.range(first..last) {
+            bucket.push_points(&mut out);
+        }
+        // Data not yet merged into a completed bucket of this level lives in
+        // the pending buckets of this and the finer levels, newest last
+        for level in self.levels[..=index].iter().rev() {
+            if let Some(bucket) = &level.pending {
+                if bucket.start <= end {
+                    bucket.push_points(&mut out);
+                }
+            }
+        }
+        out
+    }
+
+    /// Merge a bucket into the pending bucket of `index`
+    fn absorb(&mut self, index: usize, bucket: LodBucket) {
+        if index >= MAX_LOD_LEVELS {
+            return;
+        }
+        let level = &mut self.levels[index];
+        match &mut level.pending {
+            Some(pending) => pending.merge(&bucket),
+            None => level.pending = Some(bucket),
+        }
+        level.pending_parts += 1;
+        if level.pending_parts >= LOD_FACTOR {
+            self.complete(index);
+        }
+    }
+
+    /// Close the pending bucket of `index` and pass it up a level
+    fn complete(&mut self, index: usize) {
+        let level = &mut self.levels[index];
+        let Some(bucket) = level.pending.take() else {
+            return;
+        };
+        level.pending_parts = 0;
+        level.push_bucket(bucket);
+        self.absorb(index + 1, bucket);
+    }
+
+    fn push_gap(&mut self, time: f64) {
+        if self.is_empty() {
+            return;
+        }
+        // Flush bottom-up so partial buckets never span the gap
+        for index in 0..self.levels.len() {
+            self.complete(index);
+        }
+        for level in &mut self.levels {
+            if level.buckets.back().is_some_and(|b| b.is_gap()) {
+                continue;
+            }
+            level.push_bucket(LodBucket::gap(time));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn point(t: f64, v: f64) -> DataPoint {
+        DataPoint::new(Duration::from_secs_f64(t), v)
+    }
+
+    #[test]
+    fn test_pyramid_levels_keep_spikes() {
+        let mut pyramid = MinMaxPyramid::new();
+        for i in 0..100_000 {
+            let value = if i == 54_321 { 1000.0 } else { (i % 7) as f64 };
+            pyramid.push(&point(i as f64 * 0.001, value));
+        }
+        assert_eq!(pyramid.sample_count(), 100_000);
+
+        let points = pyramid.points(0.0, 100.0, 2000);
+        assert!(points.len() <= 2000 + 2 * MAX_LOD_LEVELS);
+        assert!(points.iter().any(|p| p[1] == 1000.0));
+        assert!(points.iter().all(|p| p[1] >= 0.0));
+        assert!(points.windows(2).all(|w| w[0][0] <= w[1][0]));
+
+        // A narrow window selects a fine level
+        let narrow = pyramid.points(50.0, 50.1, 2000);
+        assert_eq!(pyramid.select_level(50.0, 50.1, 1000), Some(0));
+        assert!(narrow.len() > 40);
+    }
+
+    #[test]
+    fn test_pyramid_includes_pending_tail() {
+        // 21 samples: five level-0 buckets, one level-1 bucket, one sample pending
+        let points: Vec<DataPoint> = (0..21).map(|i| point(i as f64, i as f64)).collect();
+        let pyramid = MinMaxPyramid::from_points(&points);
+        let coarse = pyramid.points(0.0, 20.0, 2);
+        assert_eq!(coarse.first(), Some(&[0.0, 0.0]));
+        assert_eq!(coarse.last(), Some(&[20.0, 20.0]));
+    }
+
+    #[test]
+    fn test_pyramid_gaps_and_eviction() {
+        let mut pyramid = MinMaxPyramid::new();
+        for i in 0..10 {
+            pyramid.push(&point(i as f64, 1.0));
+        }
+        pyramid.push(&DataPoint::gap_marker(Duration::from_secs(10)));
+        for i in 11..20 {
+            pyramid.push(&point(i as f64, 2.0));
+        }
+        let points = pyramid.points(0.0, 20.0, 2000);
+        let gap = points.iter().position(|p| p[1].is_nan()).unwrap();
+        assert!(points[..gap].iter().all(|p| p[1] == 1.0));
+        assert!(points[gap + 1..].iter().all(|p| p[1] == 2.0));
+
+        // Once level 0 drops old buckets it no longer covers the start
+        let mut long = MinMaxPyramid::new();
+        for i in 0..(MAX_LOD_BUCKETS * LOD_FACTOR * 2) {
+            long.push(&point(i as f64, 0.0));
+        }
+        assert!(long.select_level(0.0, 10.0, usize::MAX) > Some(0));
+        long.clear();
+        assert!(long.is_empty());
+        assert_eq!(long.select_level(0.0, 1.0, 10), None);
+    }
+}
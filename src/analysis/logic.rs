@@ -0,0 +1,297 @@
+//! Digital timing analysis of integer signals
+//!
+//! Splits status registers and flag words into lanes: single bits drawn as
+//! logic levels, or multi-bit fields drawn as bus values. Each lane is reduced
+//! to segments of constant value, from which edges, pulse widths and periods
+//! are measured.
+
+use crate::types::{DataPoint, VariableType};
+
+/// A bit or group of bits extracted from an integer value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaneField {
+    /// Lane name (bitfield or register field name, or `[N]` for bit N)
+    pub name: String,
+    /// Position of the least significant bit
+    pub shift: u32,
+    /// Number of bits (1 for a bit lane, more for a bus lane)
+    pub width: u32,
+}
+
+impl LaneField {
+    /// Create a field
+    pub fn new(name: impl Into<String>, shift: u32, width: u32) -> Self {
+        Self {
+            name: name.into(),
+            shift: shift.min(63),
+            width: width.clamp(1, 64),
+        }
+    }
+
+    /// Single-bit lane named after its bit position
+    pub fn bit(bit: u32) -> Self {
+        Self::new(format!("[{}]", bit), bit, 1)
+    }
+
+    /// Whether this lane shows a multi-bit bus value
+    pub fn is_bus(&self) -> bool {
+        self.width > 1
+    }
+
+    /// Extract the field from an integer bit pattern
+    pub fn extract(&self, bits: u64) -> u64 {
+        let shifted = bits >> self.shift;
+        if self.width >= 64 {
+            shifted
+        } else {
+            shifted & ((1u64 << self.width) - 1)
+        }
+    }
+
+    /// Format a field value: a level for bit lanes, zero-padded hex for buses
+    pub fn format_value(&self, value: u64) -> String {
+        if self.is_bus() {
+            format!(
+                "0x{:0width$X}",
+                value,
+                width = self.width.div_ceil(4) as usize
+            )
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+/// Number of bits of a variable type that can be split into lanes
+///
+/// Only integer and boolean variables carry exact bit patterns; `None` for
+/// floating-point, fixed-point and byte-buffer types.
+pub fn lane_bits(var_type: VariableType) -> Option<u32> {
+    match var_type {
+        VariableType::Bool => Some(1),
+        VariableType::U8 | VariableType::I8 => Some(8),
+        VariableType::U16 | VariableType::I16 => Some(16),
+        VariableType::U32 | VariableType::I32 => Some(32),
+        VariableType::U64 | VariableType::I64 => Some(64),
+        _ => None,
+    }
+}
+
+/// A time span over which a lane holds one value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    /// Start time in seconds
+    pub start: f64,
+    /// End time in seconds
+    pub end: f64,
+    /// Field value
+    pub value: u64,
+    /// Whether `start` is a value change (not the window start or a resume)
+    pub starts_at_edge: bool,
+}
+
+impl Segment {
+    /// Duration in seconds
+    pub fn duration(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+/// Split a lane into segments of constant value over `[start, end]` seconds
+///
+/// The value before `start` carries into the window. A segment ends at the
+/// next change, at a pause gap, or at the last sample; gaps leave holes
+/// between segments.
+pub fn lane_segments<'a, I>(points: I, field: &LaneField, start: f64, end: f64) -> Vec<Segment>
+where
+    I: IntoIterator<Item = &'a DataPoint>,
+{
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut current: Option<Segment> = None;
+
+    for point in points {
+        let t = point.timestamp.as_secs_f64();
+        if t > end {
+            break;
+        }
+        if point.is_gap() {
+            if let Some(mut segment) = current.take() {
+                segment.end = t.max(start);
+                if t >= start {
+                    segments.push(segment);
+                }
+            }
+            continue;
+        }
+        let value = field.extract(point.raw_value.as_i64_bits() as u64);
+        match &mut current {
+            Some(segment) if segment.value == value => segment.end = t,
+            _ => {
+                let starts_at_edge = current.is_some();
+                if let Some(mut previous) = current.take() {
+                    previous.end = t;
+                    if t > start {
+                        segments.push(previous);
+                    }
+                }
+                current = Some(Segment {
+                    start: t,
+                    end: t,
+                    value,
+                    starts_at_edge,
+                });
+            }
+        }
+    }
+    if let Some(segment) = current {
+        if segment.end >= start {
+            segments.push(segment);
+        }
+    }
+
+    // Clip the value carried in from before the window
+    for segment in &mut segments {
+        if segment.start < start {
+            segment.start = start;
+            segment.starts_at_edge = false;
+        }
+    }
+    segments
+}
+
+/// Times of the value changes among `segments`
+pub fn edges(segments: &[Segment]) -> impl Iterator<Item = f64> + '_ {
+    segments
+        .iter()
+        .filter(|s| s.starts_at_edge)
+        .map(|s| s.start)
+}
+
+/// Edge closest to `time`, if any lies within `tolerance` seconds
+pub fn nearest_edge(segments: &[Segment], time: f64, tolerance: f64) -> Option<f64> {
+    edges(segments)
+        .map(|t| (t, (t - time).abs()))
+        .filter(|(_, distance)| *distance <= tolerance)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(t, _)| t)
+}
+
+/// Pulse measured around a point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pulse {
+    /// Value of the lane during the pulse
+    pub value: u64,
+    /// Pulse width (seconds), if both of its edges are known
+    pub width: Option<f64>,
+    /// Time from this pulse's leading edge to the next one with the same value
+    pub period: Option<f64>,
+}
+
+/// Measure the pulse containing `time`
+pub fn pulse_at(segments: &[Segment], time: f64) -> Option<Pulse> {
+    let index = segments
+        .iter()
+        .position(|s| time >= s.start && time <= s.end)?;
+    let segment = &segments[index];
+    // The pulse ends at a known edge when the next segment continues it
+    let ends_at_edge = segments
+        .get(index + 1)
+        .is_some_and(|next| next.starts_at_edge && next.start == segment.end);
+    let width = (segment.starts_at_edge && ends_at_edge).then(|| segment.duration());
+    let period = if segment.starts_at_edge {
+        segments[index + 1..]
+            .iter()
+            .find(|s| s.starts_at_edge && s.value == segment.value)
+            .map(|next| next.start - segment.start)
+    } else {
+        None
+    };
+    Some(Pulse {
+        value: segment.value,
+        width,
+        period,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RawValue;
+    use std::time::Duration;
+
+    fn point(t: f64, bits: u64) -> DataPoint {
+        DataPoint {
+            timestamp: Duration::from_secs_f64(t),
+            raw_value: RawValue::UInt(bits),
+            converted_value: bits as f64,
+        }
+    }
+
+    #[test]
+    fn test_lane_field_extract_and_format() {
+        let field = LaneField::new("mode", 4, 3);
+        assert_eq!(field.extract(0b1101_0000), 0b101);
+        assert_eq!(field.format_value(5), "0x5");
+        assert_eq!(LaneField::new("word", 0, 16).format_value(0xBEEF), "0xBEEF");
+        assert_eq!(LaneField::bit(7).extract(0x80), 1);
+        assert_eq!(LaneField::bit(7).format_value(1), "1");
+        assert_eq!(LaneField::new("all", 0, 64).extract(u64::MAX), u64::MAX);
+        assert_eq!(lane_bits(VariableType::U16), Some(16));
+        assert_eq!(lane_bits(VariableType::F32), None);
+    }
+
+    #[test]
+    fn test_lane_segments_and_pulses() {
+        // bit 0: low until 1.0, high until 1.5, low until 3.0, high after
+        let points: Vec<DataPoint> = [
+            (0.0, 0),
+            (0.5, 0),
+            (1.0, 1),
+            (1.5, 0),
+            (2.0, 2),
+            (3.0, 1),
+            (3.5, 1),
+        ]
+        .iter()
+        .map(|&(t, bits)| point(t, bits))
+        .collect();
+        let field = LaneField::bit(0);
+        let segments = lane_segments(&points, &field, 0.25, 10.0);
+
+        let spans: Vec<(f64, f64, u64)> =
+            segments.iter().map(|s| (s.start, s.end, s.value)).collect();
+        assert_eq!(
+            spans,
+            vec![(0.25, 1.0, 0), (1.0, 1.5, 1), (1.5, 3.0, 0), (3.0, 3.5, 1)]
+        );
+        assert_eq!(edges(&segments).collect::<Vec<_>>(), vec![1.0, 1.5, 3.0]);
+        assert_eq!(nearest_edge(&segments, 1.4, 0.2), Some(1.5));
+        assert_eq!(nearest_edge(&segments, 2.2, 0.2), None);
+
+        let high = pulse_at(&segments, 1.2).unwrap();
+        assert_eq!(high.value, 1);
+        assert_eq!(high.width, Some(0.5));
+        assert_eq!(high.period, Some(2.0));
+        // The first segment starts at the window edge: width unknown
+        assert_eq!(pulse_at(&segments, 0.5).unwrap().width, None);
+        // The last segment has no trailing edge yet
+        assert_eq!(pulse_at(&segments, 3.2).unwrap().width, None);
+    }
+
+    #[test]
+    fn test_lane_segments_split_at_gaps() {
+        let points = vec![
+            point(0.0, 1),
+            point(1.0, 1),
+            DataPoint::gap_marker(Duration::from_secs_f64(1.5)),
+            point(2.0, 1),
+            point(3.0, 0),
+        ];
+        let segments = lane_segments(&points, &LaneField::bit(0), 0.0, 10.0);
+        assert_eq!(segments.len(), 3);
+        assert_eq!((segments[0].start, segments[0].end), (0.0, 1.5));
+        // Resuming after a pause is not an edge
+        assert!(!segments[1].starts_at_edge);
+        assert!(segments[2].starts_at_edge);
+    }
+}
@@ -4,7 +4,47 @@
 //! - FFT (Fast Fourier Transform) for frequency domain analysis
 //! - Power spectral density computation
 //! - Peak detection
+//! - Timestamp-accurate resampling onto a uniform grid
+//! - Spectrograms (short-time Fourier transform)
+//! - Frequency response, coherence and cross-correlation between two signals
+//! - Step-response metrics (rise time, overshoot, settling time)
+//! - Digital filter design (IIR and FIR) with converter script generation
+//! - Histograms and distribution statistics (percentiles, kurtosis)
+//! - Time-aligned X-Y traces
+//! - Digital timing of bit and bus lanes (edges, pulse widths)
+//! - Min/max level-of-detail pyramids for plotting long recordings
 
+pub mod cross_spectrum;
 pub mod fft;
+pub mod filter;
+pub mod histogram;
+pub mod lod;
+pub mod logic;
+pub mod resample;
+pub mod spectrogram;
+pub mod step_response;
+pub mod xy;
 
+pub use cross_spectrum::{
+    unwrap_phase_deg, CrossCorrelation, CrossSpectrumAnalyzer, FrequencyResponse, LOW_COHERENCE,
+};
 pub use fft::{FftAnalyzer, FftConfig, FftResult, WindowFunction};
+pub use filter::{
+    Biquad, Filter, FilterCoefficients, FilterMethod, FilterResponse, FilterSpec, FilterType,
+    MAX_FIR_TAPS, MAX_IIR_ORDER,
+};
+pub use histogram::{percentile, values_in_range, Distribution, Histogram, MAX_BINS};
+pub use lod::{LodBucket, MinMaxPyramid, LOD_FACTOR, MAX_LOD_BUCKETS, MAX_LOD_LEVELS};
+pub use logic::{
+    edges, lane_bits, lane_segments, nearest_edge, pulse_at, LaneField, Pulse, Segment,
+};
+pub use resample::{
+    measure_timing, resample, resample_aligned, resample_data_points, resample_data_points_aligned,
+    Interpolation, ResampleConfig, Resampled, TimingStats,
+};
+pub use spectrogram::{Spectrogram, SpectrogramAnalyzer, SpectrogramConfig};
+pub use step_response::{
+    analyze_step, detect_steps, series_from_points, step_in_region, StepConfig, StepEvent,
+    StepMetrics,
+};
+pub use xy::{align_at, xy_trace, Alignment, XySample};
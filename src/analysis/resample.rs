@@ -0,0 +1,546 @@
+//! Timestamp-accurate resampling onto a uniform grid
+//!
+//! Probe reads do not arrive at exactly `poll_rate_hz`: USB latency jitters
+//! the sample spacing and failed reads leave holes. Spectral analysis assumes
+//! uniformly spaced samples, so this module measures the real sample timing
+//! and interpolates the signal onto a uniform grid before it is handed to
+//! [`FftAnalyzer`](super::FftAnalyzer).
+//!
+//! The effective rate counts the reads lost in gaps (intervals well above the
+//! typical spacing), so dropped reads neither lower the rate nor inflate the
+//! reported jitter.
+
+use std::f64::consts::PI;
+
+use crate::types::DataPoint;
+
+/// Intervals longer than this multiple of the typical interval are gaps
+const GAP_FACTOR: f64 = 1.5;
+
+/// Sinc cutoff as a fraction of the Nyquist frequency
+const SINC_CUTOFF: f64 = 0.8;
+
+/// Interpolation method used to evaluate the signal between samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Straight line between the two neighbouring samples
+    #[default]
+    Linear,
+    /// Lanczos-windowed sinc over the surrounding samples (band-limited)
+    Sinc,
+}
+
+impl Interpolation {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Interpolation::Linear => "Linear",
+            Interpolation::Sinc => "Sinc",
+        }
+    }
+
+    /// Get all interpolation methods
+    pub fn all() -> &'static [Interpolation] {
+        &[Interpolation::Linear, Interpolation::Sinc]
+    }
+}
+
+/// Resampler configuration
+#[derive(Debug, Clone)]
+pub struct ResampleConfig {
+    /// Interpolation method
+    pub interpolation: Interpolation,
+    /// Output sample rate in Hz (None: use the measured effective rate)
+    pub target_rate: Option<f64>,
+    /// Half-width of the sinc kernel in samples
+    pub sinc_lobes: usize,
+}
+
+impl Default for ResampleConfig {
+    fn default() -> Self {
+        Self {
+            interpolation: Interpolation::Linear,
+            target_rate: None,
+            sinc_lobes: 4,
+        }
+    }
+}
+
+impl ResampleConfig {
+    /// Set interpolation method
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Set a fixed output sample rate
+    pub fn target_rate(mut self, rate: f64) -> Self {
+        self.target_rate = Some(rate);
+        self
+    }
+}
+
+/// Measured timing of an irregularly sampled signal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingStats {
+    /// Number of samples measured
+    pub sample_count: usize,
+    /// Effective sample rate (Hz), counting the reads lost in gaps
+    pub effective_rate: f64,
+    /// Delivered sample rate (Hz) over the whole span
+    pub mean_rate: f64,
+    /// Standard deviation of the regular (non-gap) sample intervals (seconds)
+    pub jitter: f64,
+    /// Largest sample interval (seconds)
+    pub max_interval: f64,
+    /// Intervals longer than 1.5x the typical interval
+    pub gap_count: usize,
+    /// Estimated number of reads lost in those gaps
+    pub missing_samples: usize,
+}
+
+impl TimingStats {
+    /// Jitter relative to the nominal interval (0.01 = 1%)
+    pub fn relative_jitter(&self) -> f64 {
+        self.jitter * self.effective_rate
+    }
+}
+
+/// A signal resampled onto a uniform grid
+#[derive(Debug, Clone)]
+pub struct Resampled {
+    /// Uniformly spaced samples
+    pub samples: Vec<f64>,
+    /// Sample rate of `samples` (Hz)
+    pub sample_rate: f64,
+    /// Time of the first sample (seconds)
+    pub start_time: f64,
+    /// Timing of the input signal
+    pub timing: TimingStats,
+}
+
+/// Measure the timing of strictly increasing timestamps (seconds)
+///
+/// Returns `None` for fewer than two samples.
+pub fn measure_timing(times: &[f64]) -> Option<TimingStats> {
+    if times.len() < 2 {
+        return None;
+    }
+    let intervals: Vec<f64> = times.windows(2).map(|w| w[1] - w[0]).collect();
+    let span = times[times.len() - 1] - times[0];
+    if span <= 0.0 {
+        return None;
+    }
+
+    // Refine the typical interval twice, each time ignoring gaps relative to
+    // the previous estimate, so a few long stalls do not skew it
+    let mut typical = span / intervals.len() as f64;
+    for _ in 0..2 {
+        let regular: Vec<f64> = intervals
+            .iter()
+            .copied()
+            .filter(|&d| d <= GAP_FACTOR * typical)
+            .collect();
+        if regular.is_empty() {
+            break;
+        }
+        typical = regular.iter().sum::<f64>() / regular.len() as f64;
+    }
+
+    let mut gap_count = 0;
+    let mut missing_samples = 0;
+    let mut regular_count = 0usize;
+    let mut sum_sq = 0.0;
+    for &d in &intervals {
+        if d > GAP_FACTOR * typical {
+            gap_count += 1;
+            missing_samples += ((d / typical).round() as usize).saturating_sub(1);
+        } else {
+            regular_count += 1;
+            sum_sq += (d - typical).powi(2);
+        }
+    }
+
+    Some(TimingStats {
+        sample_count: times.len(),
+        effective_rate: (intervals.len() + missing_samples) as f64 / span,
+        mean_rate: intervals.len() as f64 / span,
+        jitter: (sum_sq / regular_count.max(1) as f64).sqrt(),
+        max_interval: intervals.iter().copied().fold(0.0, f64::max),
+        gap_count,
+        missing_samples,
+    })
+}
+
+/// Resample `(time, value)` pairs onto a uniform grid
+///
+/// Times are in seconds and must be increasing; samples that do not advance
+/// time are dropped. Returns `None` when fewer than two usable samples remain.
+pub fn resample(times: &[f64], values: &[f64], config: &ResampleConfig) -> Option<Resampled> {
+    let (times, values) = usable_samples(times, values);
+    let timing = measure_timing(&times)?;
+    let sample_rate = config
+        .target_rate
+        .filter(|r| *r > 0.0)
+        .unwrap_or(timing.effective_rate);
+    let start_time = times[0];
+    let span = times[times.len() - 1] - start_time;
+    let count = (span * sample_rate).floor() as usize + 1;
+
+    let samples = interpolate(
+        &times,
+        &values,
+        &timing,
+        start_time,
+        sample_rate,
+        count,
+        config,
+    );
+    Some(Resampled {
+        samples,
+        sample_rate,
+        start_time,
+        timing,
+    })
+}
+
+/// Resample two signals onto one shared uniform grid
+///
+/// The grid covers only the time span where both signals have samples, at the
+/// lower of their effective rates (or the configured target rate), so sample
+/// `i` of both outputs refers to the same instant. Returns `None` when the
+/// signals do not overlap.
+pub fn resample_aligned(
+    a: (&[f64], &[f64]),
+    b: (&[f64], &[f64]),
+    config: &ResampleConfig,
+) -> Option<(Resampled, Resampled)> {
+    let (times_a, values_a) = usable_samples(a.0, a.1);
+    let (times_b, values_b) = usable_samples(b.0, b.1);
+    let timing_a = measure_timing(&times_a)?;
+    let timing_b = measure_timing(&times_b)?;
+    let sample_rate = config
+        .target_rate
+        .filter(|r| *r > 0.0)
+        .unwrap_or(timing_a.effective_rate.min(timing_b.effective_rate));
+    let start_time = times_a[0].max(times_b[0]);
+    let end_time = times_a[times_a.len() - 1].min(times_b[times_b.len() - 1]);
+    if end_time <= start_time {
+        return None;
+    }
+    let count = ((end_time - start_time) * sample_rate).floor() as usize + 1;
+
+    let resampled = |times: &[f64], values: &[f64], timing: TimingStats| Resampled {
+        samples: interpolate(
+            times,
+            values,
+            &timing,
+            start_time,
+            sample_rate,
+            count,
+            config,
+        ),
+        sample_rate,
+        start_time,
+        timing,
+    };
+    Some((
+        resampled(&times_a, &values_a, timing_a),
+        resampled(&times_b, &values_b, timing_b),
+    ))
+}
+
+/// Resample the most recent continuous run of data points
+///
+/// Pause gap markers split a recording; only the samples after the last gap
+/// are used so the interpolation never bridges a pause.
+pub fn resample_data_points<'a, I>(points: I, config: &ResampleConfig) -> Option<Resampled>
+where
+    I: IntoIterator<Item = &'a DataPoint>,
+{
+    let (times, values) = last_run(points);
+    resample(&times, &values, config)
+}
+
+/// Resample the most recent continuous runs of two variables onto one grid
+///
+/// See [`resample_aligned`].
+pub fn resample_data_points_aligned<'a, A, B>(
+    a: A,
+    b: B,
+    config: &ResampleConfig,
+) -> Option<(Resampled, Resampled)>
+where
+    A: IntoIterator<Item = &'a DataPoint>,
+    B: IntoIterator<Item = &'a DataPoint>,
+{
+    let (times_a, values_a) = last_run(a);
+    let (times_b, values_b) = last_run(b);
+    resample_aligned((&times_a, &values_a), (&times_b, &values_b), config)
+}
+
+/// Times (seconds) and values after the last gap marker
+fn last_run<'a, I>(points: I) -> (Vec<f64>, Vec<f64>)
+where
+    I: IntoIterator<Item = &'a DataPoint>,
+{
+    let mut times = Vec::new();
+    let mut values = Vec::new();
+    for point in points {
+        if point.is_gap() {
+            times.clear();
+            values.clear();
+            continue;
+        }
+        times.push(point.timestamp.as_secs_f64());
+        values.push(point.converted_value);
+    }
+    (times, values)
+}
+
+/// Drop non-finite samples and samples that do not advance time
+fn usable_samples(times: &[f64], values: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    times
+        .iter()
+        .zip(values)
+        .filter(|(t, v)| t.is_finite() && v.is_finite())
+        .fold((Vec::new(), Vec::new()), |(mut ts, mut vs), (&t, &v)| {
+            if ts.last().is_none_or(|&last| t > last) {
+                ts.push(t);
+                vs.push(v);
+            }
+            (ts, vs)
+        })
+}
+
+/// Interpolate onto `count` grid points starting at `start_time`
+fn interpolate(
+    times: &[f64],
+    values: &[f64],
+    timing: &TimingStats,
+    start_time: f64,
+    sample_rate: f64,
+    count: usize,
+    config: &ResampleConfig,
+) -> Vec<f64> {
+    let grid = (0..count).map(|i| start_time + i as f64 / sample_rate);
+    match config.interpolation {
+        Interpolation::Linear => interpolate_linear(times, values, grid),
+        Interpolation::Sinc => {
+            let lobes = config.sinc_lobes.max(1) as f64;
+            // Cut off below the lower of the input and output Nyquist
+            // frequencies; the margin keeps the kernel smooth across jitter
+            let bandwidth = sample_rate.min(timing.effective_rate) * SINC_CUTOFF;
+            grid.map(|t| interpolate_sinc(times, values, t, bandwidth, lobes))
+                .collect()
+        }
+    }
+}
+
+/// Linear interpolation at increasing grid times
+fn interpolate_linear(times: &[f64], values: &[f64], grid: impl Iterator<Item = f64>) -> Vec<f64> {
+    let mut j = 0;
+    grid.map(|t| {
+        while j + 2 < times.len() && times[j + 1] < t {
+            j += 1;
+        }
+        let (t0, t1) = (times[j], times[j + 1]);
+        let frac = ((t - t0) / (t1 - t0)).clamp(0.0, 1.0);
+        values[j] + (values[j + 1] - values[j]) * frac
+    })
+    .collect()
+}
+
+/// Lanczos-windowed sinc interpolation at time `t`
+///
+/// Each sample is weighted by the time span it covers (half the distance to
+/// each neighbour), which approximates the reconstruction integral on
+/// irregular spacing. Weights are normalized by their sum so the DC level
+/// stays exact.
+fn interpolate_sinc(times: &[f64], values: &[f64], t: f64, bandwidth: f64, lobes: f64) -> f64 {
+    let reach = lobes / bandwidth;
+    let first = times.partition_point(|&ti| ti < t - reach);
+    let last = times.len() - 1;
+    let mut weighted = 0.0;
+    let mut weight_sum = 0.0;
+    for i in first..times.len() {
+        if times[i] > t + reach {
+            break;
+        }
+        let before = times[i] - times[i.saturating_sub(1)];
+        let after = times[(i + 1).min(last)] - times[i];
+        let coverage = (before + after) / 2.0;
+        let w = lanczos((t - times[i]) * bandwidth, lobes) * coverage;
+        weighted += w * values[i];
+        weight_sum += w;
+    }
+    if weight_sum.abs() > 1e-12 {
+        weighted / weight_sum
+    } else {
+        // No usable neighbours within the kernel: fall back to linear
+        interpolate_linear(times, values, std::iter::once(t))[0]
+    }
+}
+
+/// Lanczos kernel `sinc(x) * sinc(x / a)` for |x| < a
+fn lanczos(x: f64, a: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else if x.abs() >= a {
+        0.0
+    } else {
+        let px = PI * x;
+        a * px.sin() * (px / a).sin() / (px * px)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{FftAnalyzer, FftConfig};
+    use std::time::Duration;
+
+    /// Sine sampled at a nominal 1 kHz with deterministic jitter and dropped reads
+    fn jittered_sine(freq: f64, n: usize) -> (Vec<f64>, Vec<f64>) {
+        let mut times = Vec::new();
+        let mut t = 0.0;
+        for i in 0..n {
+            // Spacing alternates between 0.6 ms and 1.4 ms; every 50th read is lost
+            t += if i % 2 == 0 { 0.0006 } else { 0.0014 };
+            if i % 50 != 49 {
+                times.push(t);
+            }
+        }
+        let values = times.iter().map(|t| (2.0 * PI * freq * t).sin()).collect();
+        (times, values)
+    }
+
+    #[test]
+    fn test_measure_timing() {
+        let times: Vec<f64> = (0..101).map(|i| i as f64 * 0.01).collect();
+        let stats = measure_timing(&times).unwrap();
+        assert!((stats.effective_rate - 100.0).abs() < 1e-6);
+        assert!(stats.jitter < 1e-9);
+        assert_eq!(stats.gap_count, 0);
+
+        // A dropped read doubles one interval but not the effective rate
+        let mut dropped = times.clone();
+        dropped.remove(50);
+        let stats = measure_timing(&dropped).unwrap();
+        assert!((stats.effective_rate - 100.0).abs() < 1e-6);
+        assert!(stats.mean_rate < 100.0);
+        assert_eq!(stats.gap_count, 1);
+        assert_eq!(stats.missing_samples, 1);
+        assert!((stats.max_interval - 0.02).abs() < 1e-9);
+
+        assert!(measure_timing(&[1.0]).is_none());
+    }
+
+    #[test]
+    fn test_linear_resample_is_exact_for_lines() {
+        let times = [0.0, 0.3, 1.0, 1.2, 2.0];
+        let values: Vec<f64> = times.iter().map(|t| 2.0 * t + 1.0).collect();
+        let config = ResampleConfig::default().target_rate(10.0);
+        let out = resample(&times, &values, &config).unwrap();
+
+        assert_eq!(out.samples.len(), 21);
+        assert_eq!(out.sample_rate, 10.0);
+        for (i, v) in out.samples.iter().enumerate() {
+            let t = i as f64 / 10.0;
+            assert!((v - (2.0 * t + 1.0)).abs() < 1e-9, "t={} v={}", t, v);
+        }
+    }
+
+    #[test]
+    fn test_resample_drops_non_monotonic_and_nan() {
+        let times = [0.0, 0.1, 0.1, 0.05, 0.2, f64::NAN, 0.3];
+        let values = [0.0, 1.0, 9.0, 9.0, 2.0, 5.0, 3.0];
+        let out = resample(&times, &values, &ResampleConfig::default()).unwrap();
+        assert_eq!(out.timing.sample_count, 4);
+        assert_eq!(out.samples.len(), 4);
+        assert!((out.samples[3] - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sinc_matches_dc_and_tracks_sine() {
+        let (times, _) = jittered_sine(1.0, 400);
+        let flat = vec![3.5; times.len()];
+        let config = ResampleConfig::default().interpolation(Interpolation::Sinc);
+        let out = resample(&times, &flat, &config).unwrap();
+        assert!(out.samples.iter().all(|v| (v - 3.5).abs() < 1e-9));
+
+        // A slow sine is reproduced closely away from the edges
+        let values: Vec<f64> = times.iter().map(|t| (2.0 * PI * 5.0 * t).sin()).collect();
+        let out = resample(&times, &values, &config).unwrap();
+        for (i, v) in out.samples.iter().enumerate().skip(10).take(300) {
+            let t = out.start_time + i as f64 / out.sample_rate;
+            let err = (v - (2.0 * PI * 5.0 * t).sin()).abs();
+            assert!(err < 0.02, "t={} err={}", t, err);
+        }
+    }
+
+    #[test]
+    fn test_resampled_fft_peak_uses_measured_rate() {
+        let freq = 93.0;
+        let (times, values) = jittered_sine(freq, 4096);
+        let out = resample(&times, &values, &ResampleConfig::default()).unwrap();
+
+        // Nominal spacing is 1 ms, even though no interval is actually 1 ms
+        assert!((out.timing.effective_rate - 1000.0).abs() < 1.0);
+        assert!(out.timing.gap_count > 0);
+        assert!(out.timing.relative_jitter() > 0.3);
+
+        let mut analyzer = FftAnalyzer::with_config(FftConfig::with_size(4096));
+        let result = analyzer.compute(&out.samples, out.sample_rate);
+        let (peak, _) = result.peak().unwrap();
+        assert!(
+            (peak - freq).abs() < 2.0 * result.frequency_resolution,
+            "peak {} Hz, expected {} Hz",
+            peak,
+            freq
+        );
+    }
+
+    #[test]
+    fn test_resample_data_points_uses_last_run() {
+        let mut points: Vec<DataPoint> = (0..10)
+            .map(|i| DataPoint::new(Duration::from_millis(i * 10), 1.0))
+            .collect();
+        points.push(DataPoint::gap_marker(Duration::from_millis(95)));
+        points.extend((0..5).map(|i| DataPoint::new(Duration::from_millis(500 + i * 20), 2.0)));
+
+        let out = resample_data_points(&points, &ResampleConfig::default()).unwrap();
+        assert!((out.start_time - 0.5).abs() < 1e-9);
+        assert!((out.sample_rate - 50.0).abs() < 1e-6);
+        assert!(out.samples.iter().all(|v| (v - 2.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_aligned_grid_covers_overlap() {
+        // `a` at 100 Hz from 0 s, `b` at 50 Hz from 0.5 s, both ramps of time
+        let times_a: Vec<f64> = (0..201).map(|i| i as f64 * 0.01).collect();
+        let times_b: Vec<f64> = (0..51).map(|i| 0.5 + i as f64 * 0.02).collect();
+        let (a, b) = resample_aligned(
+            (&times_a, &times_a),
+            (&times_b, &times_b),
+            &ResampleConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(a.samples.len(), b.samples.len());
+        assert!((a.sample_rate - 50.0).abs() < 1e-6);
+        assert!((a.start_time - 0.5).abs() < 1e-9);
+        for (va, vb) in a.samples.iter().zip(&b.samples) {
+            assert!((va - vb).abs() < 1e-9);
+        }
+
+        // No overlap
+        let late: Vec<f64> = times_a.iter().map(|t| t + 10.0).collect();
+        assert!(resample_aligned(
+            (&times_a, &times_a),
+            (&late, &late),
+            &ResampleConfig::default()
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,262 @@
+//! Spectrogram (short-time Fourier transform) analysis
+//!
+//! Splits a uniformly sampled signal into overlapping frames and computes the
+//! power spectrum of each one, giving a time-frequency picture of how
+//! harmonics move over time. Input is expected to be resampled onto a uniform
+//! grid first (see [`resample`](super::resample)).
+
+use super::fft::{FftAnalyzer, FftConfig, WindowFunction};
+
+/// Spectrogram configuration
+#[derive(Debug, Clone)]
+pub struct SpectrogramConfig {
+    /// Samples per frame (power of 2 recommended)
+    pub fft_size: usize,
+    /// Overlap between consecutive frames (0.0 to 0.95)
+    pub overlap: f64,
+    /// Window applied to each frame
+    pub window: WindowFunction,
+    /// Maximum number of frames kept (the most recent ones)
+    pub max_frames: usize,
+}
+
+impl Default for SpectrogramConfig {
+    fn default() -> Self {
+        Self {
+            fft_size: 256,
+            overlap: 0.75,
+            window: WindowFunction::Hann,
+            max_frames: 512,
+        }
+    }
+}
+
+impl SpectrogramConfig {
+    /// Distance between consecutive frames in samples
+    pub fn hop_size(&self) -> usize {
+        ((1.0 - self.overlap.clamp(0.0, 0.95)) * self.fft_size as f64).round() as usize
+    }
+
+    /// Get available frame sizes (powers of 2)
+    pub fn available_sizes() -> &'static [usize] {
+        &[64, 128, 256, 512, 1024, 2048, 4096]
+    }
+}
+
+/// Result of a spectrogram computation
+#[derive(Debug, Clone, Default)]
+pub struct Spectrogram {
+    /// Power spectrum of each frame in dB, oldest frame first
+    pub frames: Vec<Vec<f64>>,
+    /// Center time of each frame (seconds)
+    pub frame_times: Vec<f64>,
+    /// Sample rate of the input (Hz)
+    pub sample_rate: f64,
+    /// Frequency resolution (Hz per bin)
+    pub frequency_resolution: f64,
+    /// Time between consecutive frames (seconds)
+    pub hop_duration: f64,
+}
+
+impl Spectrogram {
+    /// Number of frequency bins per frame
+    pub fn bin_count(&self) -> usize {
+        self.frames.first().map_or(0, |f| f.len())
+    }
+
+    /// Highest frequency shown (the Nyquist frequency)
+    pub fn max_frequency(&self) -> f64 {
+        self.sample_rate / 2.0
+    }
+
+    /// Whether no frame could be computed
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Power (dB) of the cell nearest to a time and frequency
+    pub fn value_at(&self, time: f64, frequency: f64) -> Option<f64> {
+        let first = *self.frame_times.first()?;
+        if self.hop_duration <= 0.0 || self.frequency_resolution <= 0.0 {
+            return None;
+        }
+        let frame = ((time - first) / self.hop_duration).round();
+        let bin = (frequency / self.frequency_resolution).round();
+        if frame < 0.0 || bin < 0.0 {
+            return None;
+        }
+        self.frames.get(frame as usize)?.get(bin as usize).copied()
+    }
+
+    /// Smallest and largest power (dB) over all cells
+    pub fn db_range(&self) -> Option<(f64, f64)> {
+        self.frames
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|v| v.is_finite())
+            .fold(None, |range, v| match range {
+                None => Some((v, v)),
+                Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+            })
+    }
+}
+
+/// Computes spectrograms, reusing FFT plans between calls
+pub struct SpectrogramAnalyzer {
+    fft: FftAnalyzer,
+    config: SpectrogramConfig,
+}
+
+impl SpectrogramAnalyzer {
+    /// Create analyzer with specific config
+    pub fn with_config(config: SpectrogramConfig) -> Self {
+        Self {
+            fft: FftAnalyzer::new(),
+            config,
+        }
+    }
+
+    /// Get current config
+    pub fn config(&self) -> &SpectrogramConfig {
+        &self.config
+    }
+
+    /// Set config
+    pub fn set_config(&mut self, config: SpectrogramConfig) {
+        self.config = config;
+    }
+
+    /// Compute the spectrogram of uniformly spaced samples
+    ///
+    /// # Arguments
+    /// * `samples` - Time-domain samples
+    /// * `sample_rate` - Sample rate in Hz
+    /// * `start_time` - Time of the first sample in seconds
+    pub fn compute(&mut self, samples: &[f64], sample_rate: f64, start_time: f64) -> Spectrogram {
+        let size = self.config.fft_size.max(2);
+        let hop = self.config.hop_size().max(1);
+        let mut spectrogram = Spectrogram {
+            sample_rate,
+            frequency_resolution: sample_rate / size as f64,
+            hop_duration: hop as f64 / sample_rate,
+            ..Default::default()
+        };
+        if samples.len() < size || sample_rate <= 0.0 {
+            return spectrogram;
+        }
+
+        self.fft.set_config(FftConfig {
+            window: self.config.window,
+            fft_size: size,
+            zero_pad: true,
+            remove_dc: true,
+            overlap: self.config.overlap,
+        });
+
+        let total = (samples.len() - size) / hop + 1;
+        let skip = total.saturating_sub(self.config.max_frames.max(1));
+        for frame in skip..total {
+            let start = frame * hop;
+            let result = self.fft.compute(&samples[start..start + size], sample_rate);
+            // Zero padding to a power of two may refine the resolution
+            spectrogram.frequency_resolution = result.frequency_resolution;
+            spectrogram.frames.push(result.psd_db);
+            spectrogram
+                .frame_times
+                .push(start_time + (start as f64 + size as f64 / 2.0) / sample_rate);
+        }
+        spectrogram
+    }
+}
+
+impl Default for SpectrogramAnalyzer {
+    fn default() -> Self {
+        Self::with_config(SpectrogramConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    /// Peak bin frequency of one frame
+    fn peak_frequency(spectrogram: &Spectrogram, frame: usize) -> f64 {
+        let (bin, _) = spectrogram.frames[frame]
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        bin as f64 * spectrogram.frequency_resolution
+    }
+
+    #[test]
+    fn test_frame_layout() {
+        let config = SpectrogramConfig {
+            fft_size: 64,
+            overlap: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(config.hop_size(), 32);
+
+        let mut analyzer = SpectrogramAnalyzer::with_config(config);
+        let samples = vec![0.0; 64 + 32 * 9];
+        let spectrogram = analyzer.compute(&samples, 1000.0, 2.0);
+
+        assert_eq!(spectrogram.frames.len(), 10);
+        assert_eq!(spectrogram.bin_count(), 33);
+        assert!((spectrogram.frame_times[0] - 2.032).abs() < 1e-9);
+        assert!((spectrogram.hop_duration - 0.032).abs() < 1e-9);
+        assert!((spectrogram.max_frequency() - 500.0).abs() < 1e-9);
+
+        // Too few samples for one frame
+        assert!(analyzer.compute(&samples[..10], 1000.0, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_tracks_frequency_change() {
+        // 50 Hz for the first second, then 200 Hz
+        let rate = 1000.0;
+        let samples: Vec<f64> = (0..2000)
+            .map(|i| {
+                let t = i as f64 / rate;
+                let f = if t < 1.0 { 50.0 } else { 200.0 };
+                (2.0 * PI * f * t).sin()
+            })
+            .collect();
+
+        let mut analyzer = SpectrogramAnalyzer::default();
+        let spectrogram = analyzer.compute(&samples, rate, 0.0);
+        let resolution = spectrogram.frequency_resolution;
+
+        assert!((peak_frequency(&spectrogram, 0) - 50.0).abs() <= resolution);
+        let last = spectrogram.frames.len() - 1;
+        assert!((peak_frequency(&spectrogram, last) - 200.0).abs() <= resolution);
+
+        // Hover lookups land on the same cells
+        let early = spectrogram.value_at(0.3, 50.0).unwrap();
+        let early_off = spectrogram.value_at(0.3, 200.0).unwrap();
+        assert!(early > early_off + 20.0);
+        assert!(spectrogram.value_at(-5.0, 50.0).is_none());
+        assert!(spectrogram.value_at(0.3, 10_000.0).is_none());
+    }
+
+    #[test]
+    fn test_max_frames_keeps_latest() {
+        let config = SpectrogramConfig {
+            fft_size: 64,
+            overlap: 0.0,
+            max_frames: 4,
+            ..Default::default()
+        };
+        let mut analyzer = SpectrogramAnalyzer::with_config(config);
+        let samples = vec![1.0; 64 * 10];
+        let spectrogram = analyzer.compute(&samples, 64.0, 0.0);
+
+        assert_eq!(spectrogram.frames.len(), 4);
+        // Frames 6..10, centered half a frame in
+        assert!((spectrogram.frame_times[0] - 6.5).abs() < 1e-9);
+        assert!(spectrogram.db_range().is_some());
+    }
+}
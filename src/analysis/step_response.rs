@@ -0,0 +1,428 @@
+//! Step-response and control-loop metrics
+//!
+//! Detects step changes in a setpoint signal and measures how a response
+//! signal follows each one: rise time, overshoot, settling time,
+//! steady-state error and the damping ratio implied by the overshoot.
+//!
+//! Setpoint and response may have different timestamps; both are plain
+//! `(time, value)` series in seconds.
+
+use std::f64::consts::PI;
+
+use crate::types::DataPoint;
+
+/// Fraction of the analysis window averaged for the final value
+const FINAL_VALUE_FRACTION: f64 = 0.1;
+
+/// Overshoot (fraction of the step) below which a response counts as not
+/// overshooting; averaging the final value leaves a residue on monotonic responses
+const MIN_OVERSHOOT: f64 = 1e-3;
+
+/// Step detection and metric configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepConfig {
+    /// Minimum setpoint change counted as a step (0.0: 5% of the setpoint range)
+    pub min_step: f64,
+    /// Lower rise-time threshold as a fraction of the step (e.g. 0.1)
+    pub rise_low: f64,
+    /// Upper rise-time threshold as a fraction of the step (e.g. 0.9)
+    pub rise_high: f64,
+    /// Settling band as a fraction of the step (e.g. 0.02 for ±2%)
+    pub settling_band: f64,
+}
+
+impl Default for StepConfig {
+    fn default() -> Self {
+        Self {
+            min_step: 0.0,
+            rise_low: 0.1,
+            rise_high: 0.9,
+            settling_band: 0.02,
+        }
+    }
+}
+
+/// A step change in the setpoint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepEvent {
+    /// Time of the step (seconds)
+    pub start: f64,
+    /// End of the analysis window: the next step or the end of the data (seconds)
+    pub end: f64,
+    /// Setpoint before the step
+    pub from: f64,
+    /// Setpoint after the step
+    pub to: f64,
+}
+
+impl StepEvent {
+    /// Signed step size
+    pub fn size(&self) -> f64 {
+        self.to - self.from
+    }
+}
+
+/// Metrics of the response to one step
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepMetrics {
+    /// The step being measured
+    pub step: StepEvent,
+    /// Response value at the step
+    pub initial_value: f64,
+    /// Response value at the end of the window (averaged)
+    pub final_value: f64,
+    /// Time from the lower to the upper rise threshold (seconds)
+    pub rise_time: Option<f64>,
+    /// Time from the step to the peak of the response (seconds)
+    pub peak_time: f64,
+    /// Response value at the peak
+    pub peak_value: f64,
+    /// Overshoot past the final value, in percent of the response change
+    pub overshoot_percent: f64,
+    /// Time from the step until the response stays inside the settling band
+    /// (seconds); `None` if it never settles within the window
+    pub settling_time: Option<f64>,
+    /// Setpoint minus final value
+    pub steady_state_error: f64,
+    /// Damping ratio of a second-order system with the same overshoot;
+    /// `None` without overshoot (critically damped or slower)
+    pub damping_ratio: Option<f64>,
+}
+
+impl StepMetrics {
+    /// Half-width of a settling band (fraction of the step) around the final value
+    pub fn settling_half_width(&self, band: f64) -> f64 {
+        (self.final_value - self.initial_value).abs() * band
+    }
+}
+
+/// Split data points into time (seconds) and value series, skipping gap markers
+pub fn series_from_points<'a, I>(points: I) -> (Vec<f64>, Vec<f64>)
+where
+    I: IntoIterator<Item = &'a DataPoint>,
+{
+    points
+        .into_iter()
+        .filter(|p| !p.is_gap() && p.converted_value.is_finite())
+        .map(|p| (p.timestamp.as_secs_f64(), p.converted_value))
+        .unzip()
+}
+
+/// Detect step changes in a setpoint series
+///
+/// Consecutive changes in the same direction are merged into one step, so a
+/// setpoint that moves over a few samples still counts once.
+pub fn detect_steps(times: &[f64], setpoint: &[f64], config: &StepConfig) -> Vec<StepEvent> {
+    let n = times.len().min(setpoint.len());
+    if n < 2 {
+        return Vec::new();
+    }
+    let threshold = if config.min_step > 0.0 {
+        config.min_step
+    } else {
+        let (lo, hi) = setpoint[..n]
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v), hi.max(v))
+            });
+        if hi - lo <= 0.0 {
+            return Vec::new();
+        }
+        (hi - lo) * 0.05
+    };
+
+    let mut steps: Vec<StepEvent> = Vec::new();
+    let mut i = 1;
+    while i < n {
+        let delta = setpoint[i] - setpoint[i - 1];
+        if delta.abs() < threshold {
+            i += 1;
+            continue;
+        }
+        let from = setpoint[i - 1];
+        let start = times[i];
+        // Merge the following changes in the same direction
+        let mut j = i;
+        while j + 1 < n {
+            let next = setpoint[j + 1] - setpoint[j];
+            if next.abs() >= threshold && next.signum() == delta.signum() {
+                j += 1;
+            } else {
+                break;
+            }
+        }
+        if let Some(previous) = steps.last_mut() {
+            previous.end = start;
+        }
+        steps.push(StepEvent {
+            start,
+            end: times[n - 1],
+            from,
+            to: setpoint[j],
+        });
+        i = j + 1;
+    }
+    steps
+}
+
+/// Build a step from a marked region
+///
+/// The largest setpoint change inside `[start, end]` is used as the step. If
+/// the setpoint does not change inside the region, the step starts at the
+/// region start and targets the setpoint at the region end.
+pub fn step_in_region(times: &[f64], setpoint: &[f64], start: f64, end: f64) -> Option<StepEvent> {
+    let n = times.len().min(setpoint.len());
+    let inside: Vec<usize> = (0..n)
+        .filter(|&i| times[i] >= start && times[i] <= end)
+        .collect();
+    let (&first, &last) = (inside.first()?, inside.last()?);
+
+    let largest = inside
+        .iter()
+        .filter(|&&i| i > 0)
+        .map(|&i| (i, setpoint[i] - setpoint[i - 1]))
+        .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()));
+    match largest {
+        Some((i, delta)) if delta != 0.0 => Some(StepEvent {
+            start: times[i],
+            end,
+            from: setpoint[i - 1],
+            to: setpoint[last],
+        }),
+        _ => Some(StepEvent {
+            start,
+            end,
+            from: setpoint[first.saturating_sub(1)],
+            to: setpoint[last],
+        }),
+    }
+}
+
+/// Measure the response to one step
+///
+/// Returns `None` if the response has fewer than two samples inside the step
+/// window or does not move at all.
+pub fn analyze_step(
+    times: &[f64],
+    response: &[f64],
+    step: &StepEvent,
+    config: &StepConfig,
+) -> Option<StepMetrics> {
+    let n = times.len().min(response.len());
+    let first = times[..n].partition_point(|&t| t < step.start);
+    let last = times[..n].partition_point(|&t| t <= step.end);
+    if last < first + 2 {
+        return None;
+    }
+    let window_times = &times[first..last];
+    let window = &response[first..last];
+
+    // Value just before the step, or the first one inside the window
+    let initial_value = if first > 0 {
+        response[first - 1]
+    } else {
+        window[0]
+    };
+    let tail = ((window.len() as f64 * FINAL_VALUE_FRACTION).ceil() as usize).max(1);
+    let final_value = window[window.len() - tail..].iter().sum::<f64>() / tail as f64;
+    let change = final_value - initial_value;
+    if change.abs() < 1e-12 {
+        return None;
+    }
+
+    // Progress of the response towards its final value (0 = start, 1 = done)
+    let progress: Vec<f64> = window
+        .iter()
+        .map(|v| (v - initial_value) / change)
+        .collect();
+    let crossing = |level: f64| -> Option<f64> {
+        let i = progress.iter().position(|&p| p >= level)?;
+        if i == 0 {
+            return Some(window_times[0]);
+        }
+        let (p0, p1) = (progress[i - 1], progress[i]);
+        let frac = (level - p0) / (p1 - p0);
+        Some(window_times[i - 1] + frac * (window_times[i] - window_times[i - 1]))
+    };
+    let rise_time = match (crossing(config.rise_low), crossing(config.rise_high)) {
+        (Some(low), Some(high)) => Some(high - low),
+        _ => None,
+    };
+
+    let (peak_index, peak_progress) = progress
+        .iter()
+        .copied()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let overshoot = if peak_progress - 1.0 > MIN_OVERSHOOT {
+        peak_progress - 1.0
+    } else {
+        0.0
+    };
+
+    // Last sample outside the band; the response has settled from the next one on
+    let settling_time = match progress
+        .iter()
+        .rposition(|&p| (p - 1.0).abs() > config.settling_band)
+    {
+        None => Some(0.0),
+        Some(i) if i + 1 < window_times.len() => Some(window_times[i + 1] - step.start),
+        Some(_) => None,
+    };
+
+    let damping_ratio = (overshoot > 0.0).then(|| {
+        let ln = overshoot.ln();
+        -ln / (PI * PI + ln * ln).sqrt()
+    });
+
+    Some(StepMetrics {
+        step: *step,
+        initial_value,
+        final_value,
+        rise_time,
+        peak_time: window_times[peak_index] - step.start,
+        peak_value: window[peak_index],
+        overshoot_percent: overshoot * 100.0,
+        settling_time,
+        steady_state_error: step.to - final_value,
+        damping_ratio,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Underdamped second-order step response from 0 to 1
+    fn second_order(t: f64, zeta: f64, wn: f64) -> f64 {
+        if t < 0.0 {
+            return 0.0;
+        }
+        let wd = wn * (1.0 - zeta * zeta).sqrt();
+        let phi = (1.0 - zeta * zeta).sqrt().atan2(zeta);
+        1.0 - (-zeta * wn * t).exp() / (1.0 - zeta * zeta).sqrt() * (wd * t + phi).sin()
+    }
+
+    #[test]
+    fn test_detect_steps() {
+        let times: Vec<f64> = (0..100).map(|i| i as f64 * 0.1).collect();
+        // 0 -> 10 at 2.0 s (over two samples), 10 -> 4 at 6.0 s, small noise
+        let setpoint: Vec<f64> = times
+            .iter()
+            .enumerate()
+            .map(|(i, &t)| {
+                let base = if t < 1.95 {
+                    0.0
+                } else if t < 2.05 {
+                    5.0
+                } else if t < 5.95 {
+                    10.0
+                } else {
+                    4.0
+                };
+                base + if i % 2 == 0 { 0.01 } else { -0.01 }
+            })
+            .collect();
+
+        let steps = detect_steps(&times, &setpoint, &StepConfig::default());
+        assert_eq!(steps.len(), 2);
+        assert!((steps[0].start - 2.0).abs() < 1e-9);
+        assert!((steps[0].end - 6.0).abs() < 1e-9);
+        assert!((steps[0].size() - 10.0).abs() < 0.05);
+        assert!((steps[1].to - 4.0).abs() < 0.05);
+        assert!((steps[1].end - 9.9).abs() < 1e-9);
+
+        // A constant setpoint has no steps
+        assert!(detect_steps(&times, &vec![1.0; 100], &StepConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_second_order_metrics() {
+        let zeta = 0.4;
+        let wn = 10.0;
+        let times: Vec<f64> = (0..5000).map(|i| i as f64 * 0.001).collect();
+        let response: Vec<f64> = times
+            .iter()
+            .map(|&t| 0.9 * second_order(t - 1.0, zeta, wn))
+            .collect();
+        let step = StepEvent {
+            start: 1.0,
+            end: 4.999,
+            from: 0.0,
+            to: 1.0,
+        };
+
+        let metrics = analyze_step(&times, &response, &step, &StepConfig::default()).unwrap();
+        // Theoretical overshoot: exp(-pi * zeta / sqrt(1 - zeta^2)) = 25.4%
+        assert!((metrics.overshoot_percent - 25.4).abs() < 0.5);
+        assert!((metrics.damping_ratio.unwrap() - zeta).abs() < 0.01);
+        // Peak at pi / wd
+        let wd = wn * (1.0 - zeta * zeta).sqrt();
+        assert!((metrics.peak_time - PI / wd).abs() < 0.002);
+        // 10-90% rise time of this system is about 0.14 s
+        let rise = metrics.rise_time.unwrap();
+        assert!(rise > 0.1 && rise < 0.2, "rise {}", rise);
+        // 2% settling is roughly 4 / (zeta * wn) = 1 s
+        let settling = metrics.settling_time.unwrap();
+        assert!(settling > 0.6 && settling < 1.2, "settling {}", settling);
+        assert!((metrics.final_value - 0.9).abs() < 1e-3);
+        assert!((metrics.steady_state_error - 0.1).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_first_order_and_downward_steps() {
+        // First-order decay from 5 to 1: no overshoot
+        let times: Vec<f64> = (0..3000).map(|i| i as f64 * 0.001).collect();
+        let response: Vec<f64> = times
+            .iter()
+            .map(|&t| 1.0 + 4.0 * (-t / 0.2).exp())
+            .collect();
+        let step = StepEvent {
+            start: 0.0,
+            end: 2.999,
+            from: 5.0,
+            to: 1.0,
+        };
+        let metrics = analyze_step(&times, &response, &step, &StepConfig::default()).unwrap();
+        assert_eq!(metrics.overshoot_percent, 0.0);
+        assert!(metrics.damping_ratio.is_none());
+        // 10-90% rise time of a first-order system is ln(9) * tau
+        assert!((metrics.rise_time.unwrap() - 9f64.ln() * 0.2).abs() < 0.01);
+
+        // A response that never moves has no metrics
+        let flat = vec![1.0; times.len()];
+        assert!(analyze_step(&times, &flat, &step, &StepConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_unsettled_and_region_steps() {
+        let times: Vec<f64> = (0..100).map(|i| i as f64 * 0.01).collect();
+        // Oscillation that never settles
+        let response: Vec<f64> = times
+            .iter()
+            .map(|&t| 1.0 + 0.5 * (2.0 * PI * 5.0 * t).cos() - if t == 0.0 { 1.5 } else { 0.0 })
+            .collect();
+        let step = StepEvent {
+            start: 0.0,
+            end: 0.99,
+            from: 0.0,
+            to: 1.0,
+        };
+        let metrics = analyze_step(&times, &response, &step, &StepConfig::default()).unwrap();
+        assert!(metrics.settling_time.is_none());
+
+        // Region containing a step at 0.5 s
+        let setpoint: Vec<f64> = times
+            .iter()
+            .map(|&t| if t < 0.5 { 2.0 } else { 3.0 })
+            .collect();
+        let step = step_in_region(&times, &setpoint, 0.3, 0.8).unwrap();
+        assert!((step.start - 0.5).abs() < 1e-9);
+        assert_eq!((step.from, step.to, step.end), (2.0, 3.0, 0.8));
+
+        // Region without a setpoint change starts at the region start
+        let step = step_in_region(&times, &setpoint, 0.6, 0.9).unwrap();
+        assert_eq!(step.start, 0.6);
+        assert!(step_in_region(&times, &setpoint, 5.0, 6.0).is_none());
+    }
+}
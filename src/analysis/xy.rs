@@ -0,0 +1,196 @@
+//! Time-aligned X-Y traces
+//!
+//! Pairs the samples of two signals by timestamp so one can be plotted
+//! against the other (phase portraits, Lissajous figures, alpha-beta vectors,
+//! 2D positions). The X signal drives the sample instants; the Y signal is
+//! evaluated at each X timestamp, either at its nearest sample or linearly
+//! interpolated between its neighbours.
+
+use crate::types::DataPoint;
+
+use super::resample::measure_timing;
+
+/// Extra time around the trace window searched for Y samples, so the first
+/// and last X samples still find a neighbour on both sides
+const ALIGN_MARGIN: f64 = 0.5;
+
+/// How a signal is evaluated at another signal's timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+    /// Value of the sample closest in time
+    Nearest,
+    /// Straight line between the two neighbouring samples
+    #[default]
+    Linear,
+}
+
+impl Alignment {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Alignment::Nearest => "Nearest",
+            Alignment::Linear => "Interpolated",
+        }
+    }
+
+    /// Get all alignment methods
+    pub fn all() -> &'static [Alignment] {
+        &[Alignment::Nearest, Alignment::Linear]
+    }
+}
+
+/// One point of an X-Y trace
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XySample {
+    /// Timestamp of the X sample (seconds)
+    pub time: f64,
+    /// X value
+    pub x: f64,
+    /// Y value aligned to `time`
+    pub y: f64,
+}
+
+/// Evaluate a signal at the given times
+///
+/// `times` must be increasing. Times more than one typical sample interval
+/// outside the signal's span yield `None` rather than an extrapolated value.
+pub fn align_at(
+    times: &[f64],
+    values: &[f64],
+    at: &[f64],
+    alignment: Alignment,
+) -> Vec<Option<f64>> {
+    let len = times.len().min(values.len());
+    if len == 0 {
+        return vec![None; at.len()];
+    }
+    let (first, last) = (times[0], times[len - 1]);
+    let tolerance = measure_timing(&times[..len])
+        .map(|timing| 1.0 / timing.effective_rate)
+        .unwrap_or(0.0);
+
+    at.iter()
+        .map(|&t| {
+            if t < first - tolerance || t > last + tolerance {
+                return None;
+            }
+            // First sample at or after t
+            let i = times[..len].partition_point(|&ti| ti < t);
+            if i == 0 {
+                return Some(values[0]);
+            }
+            if i == len {
+                return Some(values[len - 1]);
+            }
+            let (t0, t1) = (times[i - 1], times[i]);
+            Some(match alignment {
+                Alignment::Nearest if t - t0 <= t1 - t => values[i - 1],
+                Alignment::Nearest => values[i],
+                Alignment::Linear => {
+                    let frac = if t1 > t0 { (t - t0) / (t1 - t0) } else { 0.0 };
+                    values[i - 1] + (values[i] - values[i - 1]) * frac
+                }
+            })
+        })
+        .collect()
+}
+
+/// Pair X and Y samples over `[start, end]` seconds
+///
+/// Only the samples after the last pause gap in the window are used, so the
+/// trace never bridges a pause. X samples without a Y value nearby are
+/// skipped.
+pub fn xy_trace<'a, X, Y>(x: X, y: Y, start: f64, end: f64, alignment: Alignment) -> Vec<XySample>
+where
+    X: IntoIterator<Item = &'a DataPoint>,
+    Y: IntoIterator<Item = &'a DataPoint>,
+{
+    let (x_times, x_values) = run_in_window(x, start, end);
+    let Some(&run_start) = x_times.first() else {
+        return Vec::new();
+    };
+    let (y_times, y_values) = run_in_window(y, run_start - ALIGN_MARGIN, end + ALIGN_MARGIN);
+    align_at(&y_times, &y_values, &x_times, alignment)
+        .into_iter()
+        .zip(x_times.iter().zip(&x_values))
+        .filter_map(|(y, (&time, &x))| y.map(|y| XySample { time, x, y }))
+        .collect()
+}
+
+/// Times and values within `[start, end]` after the last gap marker there
+fn run_in_window<'a, I>(points: I, start: f64, end: f64) -> (Vec<f64>, Vec<f64>)
+where
+    I: IntoIterator<Item = &'a DataPoint>,
+{
+    let mut times = Vec::new();
+    let mut values = Vec::new();
+    for point in points {
+        let t = point.timestamp.as_secs_f64();
+        if t < start {
+            continue;
+        }
+        if t > end {
+            break;
+        }
+        if point.is_gap() {
+            times.clear();
+            values.clear();
+            continue;
+        }
+        if point.converted_value.is_finite() && times.last().is_none_or(|&last| t > last) {
+            times.push(t);
+            values.push(point.converted_value);
+        }
+    }
+    (times, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_align_at_nearest_and_linear() {
+        let times = [0.0, 1.0, 2.0, 3.0];
+        let values = [0.0, 10.0, 20.0, 30.0];
+        let at = [0.4, 0.6, 2.5];
+
+        let nearest = align_at(&times, &values, &at, Alignment::Nearest);
+        assert_eq!(nearest, vec![Some(0.0), Some(10.0), Some(20.0)]);
+
+        let linear = align_at(&times, &values, &at, Alignment::Linear);
+        assert_eq!(linear, vec![Some(4.0), Some(6.0), Some(25.0)]);
+    }
+
+    #[test]
+    fn test_align_at_out_of_range() {
+        let times = [0.0, 1.0, 2.0];
+        let values = [1.0, 2.0, 3.0];
+        // Within one sample interval of the ends: hold the end value
+        let aligned = align_at(&times, &values, &[-0.5, 2.5], Alignment::Linear);
+        assert_eq!(aligned, vec![Some(1.0), Some(3.0)]);
+        // Further away: no value
+        let aligned = align_at(&times, &values, &[-2.0, 5.0], Alignment::Linear);
+        assert_eq!(aligned, vec![None, None]);
+        assert_eq!(align_at(&[], &[], &[0.0], Alignment::Nearest), vec![None]);
+    }
+
+    #[test]
+    fn test_xy_trace_skips_before_gap() {
+        use std::time::Duration;
+
+        let point = |t: f64, v: f64| DataPoint::new(Duration::from_secs_f64(t), v);
+        let mut x: Vec<DataPoint> = (0..10).map(|i| point(i as f64 * 0.1, i as f64)).collect();
+        x.insert(5, DataPoint::gap_marker(Duration::from_secs_f64(0.45)));
+        // Y is sampled half a period later than X
+        let y: Vec<DataPoint> = (0..10)
+            .map(|i| point(i as f64 * 0.1 + 0.05, -(i as f64)))
+            .collect();
+
+        let trace = xy_trace(&x, &y, 0.0, 1.0, Alignment::Linear);
+        assert_eq!(trace.len(), 5);
+        assert!((trace[0].time - 0.5).abs() < 1e-9);
+        assert_eq!(trace[0].x, 5.0);
+        assert!((trace[0].y + 4.5).abs() < 1e-9);
+    }
+}
@@ -6,7 +6,7 @@
 //! stateful converters (derivatives, filters, etc.).
 
 use crate::scripting::{CompiledConverter, ExecutionContext, ScriptEngine};
-use crate::types::Variable;
+use crate::types::{RawValue, Variable};
 use std::collections::HashMap;
 use std::time::Duration;
 
@@ -16,6 +16,8 @@ struct ConverterState {
     prev_raw: f64,
     prev_converted: f64,
     prev_time: Duration,
+    /// Script state slots (`state()` / `set_state()`)
+    script_state: Vec<f64>,
 }
 
 /// Engine for applying per-variable converters to probe data
@@ -43,18 +45,20 @@ impl ConverterEngine {
     /// Apply converters to probe results, returning (var_id, timestamp, raw, converted)
     ///
     /// This performs in-place modification of converter state while producing
-    /// a new Vec with the converted values.
+    /// a new Vec with the converted values. The raw value is passed through
+    /// unchanged, so integer values stay exact.
     pub fn apply_converters(
         &mut self,
-        results: &[(u32, Duration, f64)], // (var_id, timestamp, raw_value)
-    ) -> Vec<(u32, Duration, f64, f64)> {
+        results: &[(u32, Duration, RawValue)], // (var_id, timestamp, raw_value)
+    ) -> Vec<(u32, Duration, RawValue, f64)> {
         // (var_id, timestamp, raw, converted)
         results
             .iter()
-            .map(|(var_id, timestamp, raw)| {
+            .map(|(var_id, timestamp, raw_exact)| {
+                let raw = raw_exact.as_f64();
                 let converted = if let Some(converter) = self.converters.get(var_id) {
                     // Build execution context from previous state
-                    let ctx = if let Some(state) = self.prev_state.get(var_id) {
+                    let ctx = if let Some(state) = self.prev_state.get_mut(var_id) {
                         let dt_secs = timestamp.saturating_sub(state.prev_time).as_secs_f64();
                         ExecutionContext::new(
                             timestamp.as_secs_f64(),
@@ -62,21 +66,23 @@ impl ConverterEngine {
                             state.prev_raw,
                             state.prev_converted,
                         )
+                        .with_state(std::mem::take(&mut state.script_state))
                     } else {
                         // First sample for this variable
                         ExecutionContext::first_sample(timestamp.as_secs_f64())
                     };
 
                     // Execute converter
-                    match self.engine.execute(converter, *raw, ctx) {
+                    match self.engine.execute_raw(converter, *raw_exact, ctx) {
                         Ok(val) => {
                             // Update state on success
                             self.prev_state.insert(
                                 *var_id,
                                 ConverterState {
-                                    prev_raw: *raw,
+                                    prev_raw: raw,
                                     prev_converted: val,
                                     prev_time: *timestamp,
+                                    script_state: self.engine.take_state(),
                                 },
                             );
                             val
@@ -87,12 +93,13 @@ impl ConverterEngine {
                             self.prev_state.insert(
                                 *var_id,
                                 ConverterState {
-                                    prev_raw: *raw,
-                                    prev_converted: *raw,
+                                    prev_raw: raw,
+                                    prev_converted: raw,
                                     prev_time: *timestamp,
+                                    script_state: self.engine.take_state(),
                                 },
                             );
-                            *raw
+                            raw
                         }
                     }
                 } else {
@@ -101,15 +108,16 @@ impl ConverterEngine {
                     self.prev_state.insert(
                         *var_id,
                         ConverterState {
-                            prev_raw: *raw,
-                            prev_converted: *raw,
+                            prev_raw: raw,
+                            prev_converted: raw,
                             prev_time: *timestamp,
+                            script_state: Vec::new(),
                         },
                     );
-                    *raw
+                    raw
                 };
 
-                (*var_id, *timestamp, *raw, converted)
+                (*var_id, *timestamp, *raw_exact, converted)
             })
             .collect()
     }
@@ -119,6 +127,10 @@ impl ConverterEngine {
     /// If `script` is None, the converter is removed.
     /// If `script` is Some, it will be compiled and cached.
     pub fn update_converter(&mut self, var_id: u32, var_name: &str, script: Option<String>) {
+        // State slots belong to the previous script
+        if let Some(state) = self.prev_state.get_mut(&var_id) {
+            state.script_state.clear();
+        }
         if let Some(script) = script {
             match self.engine.compile(var_name, &script) {
                 Ok(compiled) => {
@@ -193,11 +205,14 @@ mod tests {
     fn test_no_converter() {
         let mut engine = ConverterEngine::new();
 
-        let results = vec![(1, Duration::from_secs(0), 42.0)];
+        let results = vec![(1, Duration::from_secs(0), RawValue::Float(42.0))];
         let converted = engine.apply_converters(&results);
 
         assert_eq!(converted.len(), 1);
-        assert_eq!(converted[0], (1, Duration::from_secs(0), 42.0, 42.0));
+        assert_eq!(
+            converted[0],
+            (1, Duration::from_secs(0), RawValue::Float(42.0), 42.0)
+        );
     }
 
     #[test]
@@ -207,11 +222,14 @@ mod tests {
         // Add a simple scale converter
         engine.update_converter(1, "test_var", Some("value * 2.0".to_string()));
 
-        let results = vec![(1, Duration::from_secs(0), 21.0)];
+        let results = vec![(1, Duration::from_secs(0), RawValue::Float(21.0))];
         let converted = engine.apply_converters(&results);
 
         assert_eq!(converted.len(), 1);
-        assert_eq!(converted[0], (1, Duration::from_secs(0), 21.0, 42.0));
+        assert_eq!(
+            converted[0],
+            (1, Duration::from_secs(0), RawValue::Float(21.0), 42.0)
+        );
     }
 
     #[test]
@@ -221,12 +239,12 @@ mod tests {
         // ADC to voltage: 12-bit (0-4095), 3.3V reference
         engine.update_converter(1, "adc_voltage", Some("value * 3.3 / 4095.0".to_string()));
 
-        let results = vec![(1, Duration::from_secs(0), 2048.0)];
+        let results = vec![(1, Duration::from_secs(0), RawValue::Float(2048.0))];
         let converted = engine.apply_converters(&results);
 
         assert_eq!(converted.len(), 1);
         let (_, _, raw, conv) = converted[0];
-        assert_eq!(raw, 2048.0);
+        assert_eq!(raw, RawValue::Float(2048.0));
         assert!((conv - 1.65).abs() < 0.01); // ~1.65V
     }
 
@@ -238,23 +256,50 @@ mod tests {
         engine.update_converter(1, "smoothed", Some("smooth(value, 0.5)".to_string()));
 
         // First sample: no previous data, should return raw value
-        let results1 = vec![(1, Duration::from_millis(0), 10.0)];
+        let results1 = vec![(1, Duration::from_millis(0), RawValue::Float(10.0))];
         let converted1 = engine.apply_converters(&results1);
         assert_eq!(converted1[0].3, 10.0);
 
         // Second sample: value jumps to 20
         // smooth(20, 10, 0.5) = 0.5 * 10 + 0.5 * 20 = 15
-        let results2 = vec![(1, Duration::from_millis(100), 20.0)];
+        let results2 = vec![(1, Duration::from_millis(100), RawValue::Float(20.0))];
         let converted2 = engine.apply_converters(&results2);
         assert!((converted2[0].3 - 15.0).abs() < 0.01);
 
         // Third sample: value stays at 20
         // smooth(20, 15, 0.5) = 0.5 * 15 + 0.5 * 20 = 17.5
-        let results3 = vec![(1, Duration::from_millis(200), 20.0)];
+        let results3 = vec![(1, Duration::from_millis(200), RawValue::Float(20.0))];
         let converted3 = engine.apply_converters(&results3);
         assert!((converted3[0].3 - 17.5).abs() < 0.01);
     }
 
+    #[test]
+    fn test_script_state_slots() {
+        let mut engine = ConverterEngine::new();
+
+        // Sum of the last two raw values, using a state slot as delay line
+        engine.update_converter(
+            1,
+            "pair_sum",
+            Some("let y = value + state(0); set_state(0, value); y".to_string()),
+        );
+
+        let outputs: Vec<f64> = [1.0, 2.0, 3.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                let results = vec![(1, Duration::from_millis(i as u64 * 10), RawValue::Float(v))];
+                engine.apply_converters(&results)[0].3
+            })
+            .collect();
+        assert_eq!(outputs, vec![1.0, 3.0, 5.0]);
+
+        // Replacing the script starts from empty state
+        engine.update_converter(1, "pair_sum", Some("state(0) + value".to_string()));
+        let results = vec![(1, Duration::from_millis(30), RawValue::Float(4.0))];
+        assert_eq!(engine.apply_converters(&results)[0].3, 4.0);
+    }
+
     #[test]
     fn test_stateful_converter_lowpass() {
         let mut engine = ConverterEngine::new();
@@ -263,12 +308,12 @@ mod tests {
         engine.update_converter(1, "filtered", Some("lowpass(value, 10.0)".to_string()));
 
         // First sample: no filtering
-        let results1 = vec![(1, Duration::from_millis(0), 10.0)];
+        let results1 = vec![(1, Duration::from_millis(0), RawValue::Float(10.0))];
         let converted1 = engine.apply_converters(&results1);
         assert_eq!(converted1[0].3, 10.0);
 
         // Second sample: step to 20, should be filtered (between 10 and 20)
-        let results2 = vec![(1, Duration::from_millis(10), 20.0)];
+        let results2 = vec![(1, Duration::from_millis(10), RawValue::Float(20.0))];
         let converted2 = engine.apply_converters(&results2);
         let filtered = converted2[0].3;
         assert!(
@@ -289,9 +334,12 @@ mod tests {
         assert_eq!(engine.converter_count(), 0);
 
         // Apply should return raw value
-        let results = vec![(1, Duration::from_secs(0), 42.0)];
+        let results = vec![(1, Duration::from_secs(0), RawValue::Float(42.0))];
         let converted = engine.apply_converters(&results);
-        assert_eq!(converted[0], (1, Duration::from_secs(0), 42.0, 42.0));
+        assert_eq!(
+            converted[0],
+            (1, Duration::from_secs(0), RawValue::Float(42.0), 42.0)
+        );
     }
 
     #[test]
@@ -303,9 +351,9 @@ mod tests {
         engine.update_converter(3, "var3", None); // No converter
 
         let results = vec![
-            (1, Duration::from_secs(0), 10.0),
-            (2, Duration::from_secs(0), 50.0),
-            (3, Duration::from_secs(0), 99.0),
+            (1, Duration::from_secs(0), RawValue::Float(10.0)),
+            (2, Duration::from_secs(0), RawValue::Float(50.0)),
+            (3, Duration::from_secs(0), RawValue::Float(99.0)),
         ];
 
         let converted = engine.apply_converters(&results);
@@ -341,10 +389,10 @@ mod tests {
         engine.update_converter(1, "var1", Some("derivative(value)".to_string()));
 
         // Build up some state
-        let results1 = vec![(1, Duration::from_millis(0), 10.0)];
+        let results1 = vec![(1, Duration::from_millis(0), RawValue::Float(10.0))];
         engine.apply_converters(&results1);
 
-        let results2 = vec![(1, Duration::from_millis(100), 20.0)];
+        let results2 = vec![(1, Duration::from_millis(100), RawValue::Float(20.0))];
         engine.apply_converters(&results2);
 
         assert_eq!(engine.prev_state.len(), 1);
@@ -354,7 +402,7 @@ mod tests {
         assert_eq!(engine.prev_state.len(), 0);
 
         // Next sample should be treated as first sample
-        let results3 = vec![(1, Duration::from_millis(200), 30.0)];
+        let results3 = vec![(1, Duration::from_millis(200), RawValue::Float(30.0))];
         let converted3 = engine.apply_converters(&results3);
         assert_eq!(converted3[0].3, 0.0); // Derivative with no prev = 0
     }
@@ -375,7 +423,7 @@ mod tests {
 
         let mut accumulated = 0.0;
         for (timestamp, value) in samples {
-            let results = vec![(1, timestamp, value)];
+            let results = vec![(1, timestamp, RawValue::Float(value))];
             let converted = engine.apply_converters(&results);
             accumulated = converted[0].3;
         }
@@ -394,22 +442,40 @@ mod tests {
         // Add first converter
         engine.update_converter(1, "var1", Some("value * 2.0".to_string()));
 
-        let results = vec![(1, Duration::from_secs(0), 10.0)];
+        let results = vec![(1, Duration::from_secs(0), RawValue::Float(10.0))];
         let converted = engine.apply_converters(&results);
         assert_eq!(converted[0].3, 20.0);
 
         // Update to different converter
         engine.update_converter(1, "var1", Some("value * 3.0".to_string()));
 
-        let results = vec![(1, Duration::from_secs(1), 10.0)];
+        let results = vec![(1, Duration::from_secs(1), RawValue::Float(10.0))];
         let converted = engine.apply_converters(&results);
         assert_eq!(converted[0].3, 30.0);
 
         // Remove converter
         engine.update_converter(1, "var1", None);
 
-        let results = vec![(1, Duration::from_secs(2), 10.0)];
+        let results = vec![(1, Duration::from_secs(2), RawValue::Float(10.0))];
         let converted = engine.apply_converters(&results);
         assert_eq!(converted[0].3, 10.0); // No conversion
     }
+
+    #[test]
+    fn test_integer_raw_value_is_exact() {
+        let mut engine = ConverterEngine::new();
+        engine.update_converter(2, "low_byte", Some("bit_and(raw_int, 0xFF)".to_string()));
+
+        // Above 2^53, so not representable as f64
+        let big = RawValue::from_u64(u64::MAX - 1);
+        let results = vec![
+            (1, Duration::from_secs(0), big),
+            (2, Duration::from_secs(0), big),
+        ];
+        let converted = engine.apply_converters(&results);
+
+        assert_eq!(converted[0].2, big);
+        assert_eq!(converted[1].2, big);
+        assert_eq!(converted[1].3, 254.0);
+    }
 }
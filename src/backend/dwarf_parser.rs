@@ -13,8 +13,8 @@
 //! and DW_TAG_template_value_parameter DIEs.
 
 use super::type_table::{
-    BaseClassDef, DwarfTypeKey, EnumDef, EnumVariant, ForwardDeclKind, GlobalTypeKey, MemberDef,
-    PrimitiveDef, StructDef, TemplateParam, TypeDef, TypeId, TypeTable,
+    BaseClassDef, BitOffsetKind, DwarfTypeKey, EnumDef, EnumVariant, ForwardDeclKind,
+    GlobalTypeKey, MemberDef, PrimitiveDef, StructDef, TemplateParam, TypeDef, TypeId, TypeTable,
 };
 use gimli::{
     AttributeValue, DebuggingInformationEntry, Dwarf, EndianSlice, ReaderOffset, RunTimeEndian,
@@ -317,6 +317,7 @@ impl<'a> DwarfParser<'a, Reader<'a>> {
             .next()
             .map_err(|e| format!("Failed to read DWARF unit: {}", e))?
         {
+            self.type_table.set_pointer_size(u64::from(header.address_size()));
             let unit = self
                 .dwarf
                 .unit(header)
@@ -733,7 +734,13 @@ impl<'a> DwarfParser<'a, Reader<'a>> {
         };
 
         let mut member = MemberDef::new(name, offset, type_id);
-        member.bit_offset = self.get_bit_offset(entry);
+        if let Some((bit_offset, kind)) = self.get_bit_offset(entry) {
+            member.bit_offset = Some(bit_offset);
+            member.bit_offset_kind = kind;
+            if kind == BitOffsetKind::StorageMsb {
+                member.storage_size = self.get_byte_size(entry);
+            }
+        }
         member.bit_size = self.get_bit_size(entry);
 
         Some(member)
@@ -1199,6 +1206,8 @@ impl<'a> DwarfParser<'a, Reader<'a>> {
                         type_id: member.type_id,
                         bit_offset: member.bit_offset,
                         bit_size: member.bit_size,
+                        bit_offset_kind: member.bit_offset_kind,
+                        storage_size: member.storage_size,
                     });
                 }
             }
@@ -1399,7 +1408,10 @@ impl<'a> DwarfParser<'a, Reader<'a>> {
         Some(0)
     }
 
-    fn get_bit_offset(&self, entry: &DebuggingInformationEntry<Reader<'a>>) -> Option<u64> {
+    fn get_bit_offset(
+        &self,
+        entry: &DebuggingInformationEntry<Reader<'a>>,
+    ) -> Option<(u64, BitOffsetKind)> {
         // Try DW_AT_data_bit_offset first (DWARF 4+)
         if let Ok(Some(attr)) = entry.attr_value(gimli::DW_AT_data_bit_offset) {
             return match attr {
@@ -1409,18 +1421,20 @@ impl<'a> DwarfParser<'a, Reader<'a>> {
                 AttributeValue::Data4(v) => Some(v as u64),
                 AttributeValue::Data8(v) => Some(v),
                 _ => None,
-            };
+            }
+            .map(|v| (v, BitOffsetKind::Data));
         }
 
-        // Fall back to DW_AT_bit_offset (DWARF 3)
-        match entry.attr_value(gimli::DW_AT_bit_offset).ok()?? {
+        // Fall back to DW_AT_bit_offset (DWARF 2/3), counted from the MSB
+        let offset = match entry.attr_value(gimli::DW_AT_bit_offset).ok()?? {
             AttributeValue::Udata(v) => Some(v),
             AttributeValue::Data1(v) => Some(v as u64),
             AttributeValue::Data2(v) => Some(v as u64),
             AttributeValue::Data4(v) => Some(v as u64),
             AttributeValue::Data8(v) => Some(v),
             _ => None,
-        }
+        };
+        offset.map(|v| (v, BitOffsetKind::StorageMsb))
     }
 
     fn get_bit_size(&self, entry: &DebuggingInformationEntry<Reader<'a>>) -> Option<u64> {
@@ -13,7 +13,7 @@
 use super::dwarf_parser::{DwarfDiagnostics, DwarfParser, VariableStatus};
 use super::type_table::{MemberDef, SharedTypeTable, TypeHandle, TypeId, TypeTable};
 use crate::error::{DataVisError, Result};
-use crate::types::{Variable, VariableType};
+use crate::types::{Endianness, Variable, VariableType};
 use cpp_demangle::Symbol as CppSymbol;
 use object::{Object, ObjectSection, ObjectSymbol, SymbolKind};
 use rustc_demangle::demangle as rust_demangle;
@@ -193,6 +193,15 @@ impl ElfInfo {
         self.diagnostics = diagnostics;
     }
 
+    /// Get the target byte order recorded in the ELF header
+    pub fn endianness(&self) -> Endianness {
+        if self.is_little_endian {
+            Endianness::Little
+        } else {
+            Endianness::Big
+        }
+    }
+
     /// Get a reference to the shared type table
     pub fn type_table(&self) -> &SharedTypeTable {
         &self.type_table
@@ -246,6 +255,15 @@ impl ElfInfo {
             .unwrap_or_default()
     }
 
+    /// Find the variable symbol whose storage contains an address
+    pub fn find_variable_containing(&self, address: u64) -> Option<&SymbolInfo> {
+        self.symbols.iter().find(|s| {
+            s.symbol_type == SymbolType::Variable
+                && address >= s.address
+                && address < s.address.saturating_add(s.size.max(1))
+        })
+    }
+
     /// Get all variable symbols
     pub fn get_variables(&self) -> Vec<&SymbolInfo> {
         self.symbols
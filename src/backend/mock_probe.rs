@@ -57,7 +57,7 @@
 //! ```
 
 use crate::error::{DataVisError, Result};
-use crate::types::{Variable, VariableType};
+use crate::types::{Endianness, RawValue, Variable};
 use std::collections::HashMap;
 use std::time::Instant;
 
@@ -343,6 +343,8 @@ pub struct MockProbeBackend {
     reads_this_second: u32,
     /// Start of the current rate-limit second
     second_start: Instant,
+    /// Simulated target byte order for values in mock memory
+    endianness: Endianness,
 }
 
 impl MockProbeBackend {
@@ -368,6 +370,7 @@ impl MockProbeBackend {
             accumulated_latency_increase: 0.0,
             reads_this_second: 0,
             second_start: Instant::now(),
+            endianness: Endianness::Little,
         }
     }
 
@@ -395,6 +398,12 @@ impl MockProbeBackend {
         self
     }
 
+    /// Set the simulated target byte order
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
     /// Set a fault injection configuration
     pub fn with_fault_config(mut self, config: FaultConfig) -> Self {
         self.fault_config = Some(config);
@@ -616,6 +625,11 @@ impl MockProbeBackend {
 
     /// Read a variable's value
     pub fn read_variable(&mut self, variable: &Variable) -> Result<f64> {
+        self.read_variable_raw(variable).map(|raw| raw.as_f64())
+    }
+
+    /// Read a variable's value, keeping integers from mock memory exact
+    pub fn read_variable_raw(&mut self, variable: &Variable) -> Result<RawValue> {
         if !self.connected {
             return Err(DataVisError::Config("Mock probe not connected".to_string()));
         }
@@ -637,7 +651,7 @@ impl MockProbeBackend {
         if let Some(config) = self.variable_configs.get_mut(&variable.id) {
             let value = config.generate_value(elapsed);
             let value = self.apply_corruption(value, variable);
-            return Ok(value);
+            return Ok(RawValue::Float(value));
         }
 
         // If not in pattern-only mode, try to read from mock memory first
@@ -646,9 +660,14 @@ impl MockProbeBackend {
                 .memory
                 .read(variable.address, variable.var_type.size_bytes())
             {
-                if let Some(value) = variable.var_type.parse_to_f64(&bytes) {
-                    let value = self.apply_corruption(value, variable);
-                    return Ok(value);
+                if let Some(raw) = variable.var_type.parse_raw_endian(&bytes, self.endianness) {
+                    let value = raw.as_f64();
+                    let corrupted = self.apply_corruption(value, variable);
+                    // Only fall back to f64 when corruption actually changed the value
+                    if corrupted.to_bits() == value.to_bits() {
+                        return Ok(raw);
+                    }
+                    return Ok(RawValue::Float(corrupted));
                 }
             }
         }
@@ -704,7 +723,7 @@ impl MockProbeBackend {
         // Apply corruption if fault config is set
         let value = self.apply_corruption(value, variable);
 
-        Ok(value)
+        Ok(RawValue::Float(value))
     }
 
     /// Read raw memory
@@ -746,24 +765,12 @@ impl MockProbeBackend {
             return Err(DataVisError::Config("Mock probe not connected".to_string()));
         }
 
-        let bytes = match variable.var_type {
-            VariableType::U8 => vec![(value as u8)],
-            VariableType::I8 => vec![(value as i8 as u8)],
-            VariableType::Bool => vec![if value != 0.0 { 1 } else { 0 }],
-            VariableType::U16 => (value as u16).to_le_bytes().to_vec(),
-            VariableType::I16 => (value as i16).to_le_bytes().to_vec(),
-            VariableType::U32 => (value as u32).to_le_bytes().to_vec(),
-            VariableType::I32 => (value as i32).to_le_bytes().to_vec(),
-            VariableType::F32 => (value as f32).to_le_bytes().to_vec(),
-            VariableType::U64 => (value as u64).to_le_bytes().to_vec(),
-            VariableType::I64 => (value as i64).to_le_bytes().to_vec(),
-            VariableType::F64 => value.to_le_bytes().to_vec(),
-            VariableType::Raw(_) => {
-                return Err(DataVisError::Variable(
-                    "Cannot write raw type variables".to_string(),
-                ))
-            }
-        };
+        let bytes = variable
+            .var_type
+            .encode_f64_endian(value, self.endianness)
+            .ok_or_else(|| {
+                DataVisError::Variable("Cannot write raw type variables".to_string())
+            })?;
 
         self.write_memory(variable.address, &bytes)
     }
@@ -798,8 +805,16 @@ impl DebugProbe for MockProbeBackend {
     }
 
     fn read_variable(&mut self, variable: &Variable) -> Result<f64> {
+        DebugProbe::read_variable_raw(self, variable).map(|raw| raw.as_f64())
+    }
+
+    fn read_variables(&mut self, variables: &[Variable]) -> Vec<Result<f64>> {
+        variables.iter().map(|v| self.read_variable(v)).collect()
+    }
+
+    fn read_variable_raw(&mut self, variable: &Variable) -> Result<RawValue> {
         let start = Instant::now();
-        let result = MockProbeBackend::read_variable(self, variable);
+        let result = MockProbeBackend::read_variable_raw(self, variable);
         // Ensure minimum 1μs to avoid division by zero in rate calculations
         let elapsed = start.elapsed().as_micros().max(1) as u64;
 
@@ -813,10 +828,6 @@ impl DebugProbe for MockProbeBackend {
         result
     }
 
-    fn read_variables(&mut self, variables: &[Variable]) -> Vec<Result<f64>> {
-        variables.iter().map(|v| self.read_variable(v)).collect()
-    }
-
     fn write_variable(&mut self, variable: &Variable, value: f64) -> Result<()> {
         MockProbeBackend::write_variable(self, variable, value)
     }
@@ -864,6 +875,14 @@ impl DebugProbe for MockProbeBackend {
         Ok(self.halted)
     }
 
+    fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
     fn stats(&self) -> &ProbeStats {
         &self.stats
     }
@@ -960,4 +979,63 @@ mod tests {
         // Should return some value (either from pattern or memory)
         assert!(value.is_finite());
     }
+
+    #[test]
+    fn test_mock_probe_fixed_and_half_roundtrip() {
+        use crate::types::VariableType;
+
+        let mut probe = MockProbeBackend::new().with_pattern_only_mode(false);
+        probe.connect(None, "Test").unwrap();
+
+        let q15 = Variable::new("q15", 0x2000_0000, VariableType::Q15);
+        probe.write_variable(&q15, -0.5).unwrap();
+        assert_eq!(probe.read_memory(0x2000_0000, 2).unwrap(), vec![0x00, 0xC0]);
+        assert_eq!(probe.read_variable(&q15).unwrap(), -0.5);
+
+        let half = Variable::new("half", 0x2000_0010, VariableType::F16);
+        probe.write_variable(&half, 1.5).unwrap();
+        assert_eq!(probe.read_memory(0x2000_0010, 2).unwrap(), vec![0x00, 0x3E]);
+        assert_eq!(probe.read_variable(&half).unwrap(), 1.5);
+
+        let bf = Variable::new("bf", 0x2000_0020, VariableType::BF16);
+        probe.write_variable(&bf, -2.0).unwrap();
+        assert_eq!(probe.read_variable(&bf).unwrap(), -2.0);
+    }
+
+    #[test]
+    fn test_mock_probe_big_endian_roundtrip() {
+        use crate::types::VariableType;
+
+        let mut probe = MockProbeBackend::new()
+            .with_pattern_only_mode(false)
+            .with_endianness(Endianness::Big);
+        probe.connect(None, "Test").unwrap();
+
+        let word = Variable::new("word", 0x2000_0000, VariableType::U32);
+        probe.write_variable(&word, 0x1234_5678 as f64).unwrap();
+        assert_eq!(
+            probe.read_memory(0x2000_0000, 4).unwrap(),
+            vec![0x12, 0x34, 0x56, 0x78]
+        );
+        assert_eq!(probe.read_variable(&word).unwrap(), 0x1234_5678 as f64);
+
+        let float = Variable::new("float", 0x2000_0010, VariableType::F32);
+        probe.write_variable(&float, -1.25).unwrap();
+        assert_eq!(probe.read_variable(&float).unwrap(), -1.25);
+    }
+
+    #[test]
+    fn test_mock_probe_u64_read_is_exact() {
+        use crate::types::VariableType;
+
+        let mut probe = MockProbeBackend::new().with_pattern_only_mode(false);
+        probe.connect(None, "Test").unwrap();
+
+        let cycles = u64::MAX - 1;
+        probe.write_memory(0x2000_0000, &cycles.to_le_bytes()).unwrap();
+
+        let var = Variable::new("cycles", 0x2000_0000, VariableType::U64);
+        let results = DebugProbe::read_variables_raw(&mut probe, &[var]);
+        assert_eq!(results[0].as_ref().unwrap(), &RawValue::UInt(cycles));
+    }
 }
@@ -59,6 +59,7 @@ pub mod openocd;
 pub mod probe;
 pub mod probe_trait;
 pub mod read_manager;
+pub mod struct_snapshot;
 pub mod type_table;
 pub mod worker;
 
@@ -83,10 +84,13 @@ pub use openocd::OpenOcdProbe;
 pub use probe::{ProbeBackend, ProbeInfo};
 pub use probe_trait::{DebugProbe, DetectedProbeInfo, ProbeStats};
 pub use read_manager::{ReadManager, ReadRegion, DEFAULT_GAP_THRESHOLD};
+pub use struct_snapshot::{SnapshotNode, SnapshotValue};
 pub use worker::{BackendWorker, SwdCommand, SwdResponse};
 
 use crate::config::AppConfig;
-use crate::types::{CollectionStats, ConnectionStatus, PointerState, Variable};
+use crate::types::{
+    CollectionStats, ConnectionStatus, Endianness, PointerState, RawValue, Variable,
+};
 use crossbeam_channel::{bounded, Receiver, Sender};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
@@ -98,9 +102,9 @@ use std::time::Duration;
 #[derive(Debug, Clone)]
 pub struct DataUpdate {
     /// Global data stream (all variables, all panes can see this)
-    pub global: Vec<(u32, Duration, f64, f64)>, // (var_id, timestamp, raw, converted)
+    pub global: Vec<(u32, Duration, RawValue, f64)>, // (var_id, timestamp, raw, converted)
     /// Per-pane filtered data streams
-    pub per_pane: HashMap<u64, Vec<(u32, Duration, f64, f64)>>, // pane_id -> data
+    pub per_pane: HashMap<u64, Vec<(u32, Duration, RawValue, f64)>>, // pane_id -> data
 }
 
 /// Message sent from the UI to the backend
@@ -163,6 +167,21 @@ pub enum BackendCommand {
         /// Variable IDs this pane wants to receive
         var_ids: HashSet<u32>,
     },
+    /// Set the byte order reported by the loaded ELF file
+    ///
+    /// Used unless the probe configuration overrides the endianness.
+    SetTargetEndianness(Endianness),
+    /// Read a block of memory in one request on every poll (struct inspector)
+    AddMemoryWatch {
+        /// Watch ID (the owning pane's ID)
+        id: u64,
+        /// Start address
+        address: u64,
+        /// Number of bytes to read
+        size: usize,
+    },
+    /// Stop a memory watch by ID
+    RemoveMemoryWatch(u64),
 }
 
 /// Represents a detected probe (real or mock)
@@ -258,13 +277,15 @@ pub enum BackendMessage {
     DataPoint {
         variable_id: u32,
         timestamp: Duration,
-        raw_value: f64,
+        raw_value: RawValue,
         converted_value: f64,
     },
     /// Batch of data points (more efficient for high-frequency updates)
-    DataBatch(Vec<(u32, Duration, f64, f64)>),
+    DataBatch(Vec<(u32, Duration, RawValue, f64)>),
     /// Data update with per-pane routing (Phase 2 - replaces DataBatch)
     DataUpdate(DataUpdate),
+    /// Decoded text of string variables: (var_id, timestamp, text)
+    TextBatch(Vec<(u32, Duration, String)>),
     /// Variable read error
     ReadError { variable_id: u32, error: String },
     /// Variable write succeeded
@@ -279,6 +300,14 @@ pub enum BackendMessage {
     ProbeList(Vec<DetectedProbe>),
     /// Pointer state updates for UI display
     PointerStates(std::collections::HashMap<u32, PointerState>),
+    /// Contents of a memory watch from one poll
+    MemorySnapshot {
+        id: u64,
+        timestamp: Duration,
+        bytes: Vec<u8>,
+    },
+    /// Memory watch read error
+    MemorySnapshotError { id: u64, error: String },
     /// Backend is shutting down
     Shutdown,
 }
@@ -366,6 +395,13 @@ impl FrontendReceiver {
         let _ = self.command_sender.send(BackendCommand::ClearData);
     }
 
+    /// Set the target byte order reported by the loaded ELF
+    pub fn set_target_endianness(&self, endianness: Endianness) {
+        let _ = self
+            .command_sender
+            .send(BackendCommand::SetTargetEndianness(endianness));
+    }
+
     /// Set whether to use mock probe (only available with mock-probe feature)
     #[cfg(feature = "mock-probe")]
     pub fn use_mock_probe(&self, use_mock: bool) {
@@ -6,7 +6,7 @@
 use crate::backend::probe_trait::{DebugProbe, ProbeStats};
 use crate::config::ProbeConfig;
 use crate::error::{DataVisError, Result};
-use crate::types::{Variable, VariableType};
+use crate::types::{Endianness, RawValue, Variable};
 
 use super::process::OpenOcdProcess;
 use super::tcl_client::TclClient;
@@ -17,6 +17,7 @@ pub struct OpenOcdProbe {
     config: ProbeConfig,
     connected: bool,
     stats: ProbeStats,
+    endianness: Endianness,
 }
 
 impl OpenOcdProbe {
@@ -24,14 +25,46 @@ impl OpenOcdProbe {
         Self {
             process: None,
             client: None,
+            endianness: config.endianness.unwrap_or_default(),
             config,
             connected: false,
             stats: ProbeStats::default(),
         }
     }
 
+    /// Convert a word returned by `mdh`/`mdw` back into target memory byte order
+    ///
+    /// OpenOCD interprets half-words and words using the target's byte order, so
+    /// the numeric value has to be laid out again in that order before decoding.
+    fn word_to_memory_bytes(endianness: Endianness, word: u32, size: usize) -> Vec<u8> {
+        match (endianness, size) {
+            (Endianness::Little, 2) => (word as u16).to_le_bytes().to_vec(),
+            (Endianness::Big, 2) => (word as u16).to_be_bytes().to_vec(),
+            (Endianness::Little, _) => word.to_le_bytes().to_vec(),
+            (Endianness::Big, _) => word.to_be_bytes().to_vec(),
+        }
+    }
+
+    /// Convert target memory bytes into the numeric word expected by `mwh`/`mww`
+    fn memory_bytes_to_word(endianness: Endianness, bytes: &[u8]) -> u32 {
+        let mut word = [0u8; 4];
+        match endianness {
+            Endianness::Little => {
+                word[..bytes.len()].copy_from_slice(bytes);
+                u32::from_le_bytes(word)
+            }
+            Endianness::Big => {
+                word[4 - bytes.len()..].copy_from_slice(bytes);
+                u32::from_be_bytes(word)
+            }
+        }
+    }
+
     /// Read raw bytes for a variable's type size from the appropriate memory command
+    ///
+    /// The returned bytes are in target memory order.
     fn read_variable_bytes(&mut self, variable: &Variable) -> Result<Vec<u8>> {
+        let endianness = self.endianness;
         let client = self.client.as_mut().ok_or_else(|| {
             DataVisError::Config("Not connected to OpenOCD".to_string())
         })?;
@@ -52,14 +85,14 @@ impl OpenOcdProbe {
                 if values.is_empty() {
                     return Err(DataVisError::Variable("No data returned from mdh".to_string()));
                 }
-                Ok(values[0].to_le_bytes().to_vec())
+                Ok(Self::word_to_memory_bytes(endianness, values[0] as u32, 2))
             }
             4 => {
                 let values = client.read_memory_32(addr, 1)?;
                 if values.is_empty() {
                     return Err(DataVisError::Variable("No data returned from mdw".to_string()));
                 }
-                Ok(values[0].to_le_bytes().to_vec())
+                Ok(Self::word_to_memory_bytes(endianness, values[0], 4))
             }
             8 => {
                 // Read two 32-bit words for 64-bit types
@@ -67,10 +100,10 @@ impl OpenOcdProbe {
                 if values.len() < 2 {
                     return Err(DataVisError::Variable("Insufficient data for 64-bit read".to_string()));
                 }
-                // Little-endian: low word first
+                // Lower address word first, each word in target byte order
                 let mut bytes = Vec::with_capacity(8);
-                bytes.extend_from_slice(&values[0].to_le_bytes());
-                bytes.extend_from_slice(&values[1].to_le_bytes());
+                bytes.extend_from_slice(&Self::word_to_memory_bytes(endianness, values[0], 4));
+                bytes.extend_from_slice(&Self::word_to_memory_bytes(endianness, values[1], 4));
                 Ok(bytes)
             }
             n => {
@@ -122,6 +155,17 @@ impl DebugProbe for OpenOcdProbe {
     }
 
     fn read_variable(&mut self, variable: &Variable) -> Result<f64> {
+        self.read_variable_raw(variable).map(|raw| raw.as_f64())
+    }
+
+    fn read_variables(&mut self, variables: &[Variable]) -> Vec<Result<f64>> {
+        self.read_variables_raw(variables)
+            .into_iter()
+            .map(|result| result.map(|raw| raw.as_f64()))
+            .collect()
+    }
+
+    fn read_variable_raw(&mut self, variable: &Variable) -> Result<RawValue> {
         let start = std::time::Instant::now();
 
         let bytes = self.read_variable_bytes(variable)?;
@@ -132,11 +176,11 @@ impl DebugProbe for OpenOcdProbe {
 
         variable
             .var_type
-            .parse_to_f64(&bytes)
+            .parse_raw_endian(&bytes, self.endianness)
             .ok_or_else(|| DataVisError::Variable("Failed to parse value".to_string()))
     }
 
-    fn read_variables(&mut self, variables: &[Variable]) -> Vec<Result<f64>> {
+    fn read_variables_raw(&mut self, variables: &[Variable]) -> Vec<Result<RawValue>> {
         let start = std::time::Instant::now();
         let mut results = Vec::with_capacity(variables.len());
         let mut total_bytes = 0u64;
@@ -145,7 +189,7 @@ impl DebugProbe for OpenOcdProbe {
             match self.read_variable_bytes(variable) {
                 Ok(bytes) => {
                     total_bytes += bytes.len() as u64;
-                    match variable.var_type.parse_to_f64(&bytes) {
+                    match variable.var_type.parse_raw_endian(&bytes, self.endianness) {
                         Some(value) => results.push(Ok(value)),
                         None => results.push(Err(DataVisError::Variable(
                             format!("Failed to parse value for '{}'", variable.name),
@@ -169,50 +213,37 @@ impl DebugProbe for OpenOcdProbe {
     }
 
     fn write_variable(&mut self, variable: &Variable, value: f64) -> Result<()> {
+        let bytes = variable
+            .var_type
+            .encode_f64_endian(value, self.endianness)
+            .ok_or_else(|| {
+                DataVisError::Variable("Cannot write raw type variables".to_string())
+            })?;
+        let words: Vec<u32> = bytes
+            .chunks(4)
+            .map(|c| Self::memory_bytes_to_word(self.endianness, c))
+            .collect();
+
         let client = self.client.as_mut().ok_or_else(|| {
             DataVisError::Config("Not connected to OpenOCD".to_string())
         })?;
 
         let addr = variable.address;
 
-        match variable.var_type {
-            VariableType::U8 | VariableType::I8 | VariableType::Bool => {
-                let byte_val = match variable.var_type {
-                    VariableType::I8 => value as i8 as u8,
-                    VariableType::Bool => if value != 0.0 { 1 } else { 0 },
-                    _ => value as u8,
-                };
-                client.write_memory_8(addr, byte_val)
-            }
-            VariableType::U16 | VariableType::I16 => {
-                let val = match variable.var_type {
-                    VariableType::I16 => value as i16 as u16,
-                    _ => value as u16,
-                };
-                client.write_memory_16(addr, val)
-            }
-            VariableType::U32 | VariableType::I32 | VariableType::F32 => {
-                let val = match variable.var_type {
-                    VariableType::I32 => (value as i32) as u32,
-                    VariableType::F32 => (value as f32).to_bits(),
-                    _ => value as u32,
-                };
-                client.write_memory_32(addr, val)
-            }
-            VariableType::U64 | VariableType::I64 | VariableType::F64 => {
-                let bytes = match variable.var_type {
-                    VariableType::I64 => (value as i64).to_le_bytes(),
-                    VariableType::F64 => value.to_le_bytes(),
-                    _ => (value as u64).to_le_bytes(),
-                };
-                // Write as two 32-bit words (little-endian)
-                let low = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
-                let high = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
-                client.write_memory_32(addr, low)?;
-                client.write_memory_32(addr + 4, high)
+        match bytes.len() {
+            1 => client.write_memory_8(addr, bytes[0]),
+            2 => client.write_memory_16(addr, words[0] as u16),
+            4 => client.write_memory_32(addr, words[0]),
+            8 => {
+                // Write as two 32-bit words, lower address first
+                client.write_memory_32(addr, words[0])?;
+                client.write_memory_32(addr + 4, words[1])
             }
-            VariableType::Raw(_) => {
-                Err(DataVisError::Variable("Cannot write raw type variables".to_string()))
+            _ => {
+                for (i, byte) in bytes.iter().enumerate() {
+                    client.write_memory_8(addr + i as u64, *byte)?;
+                }
+                Ok(())
             }
         }
     }
@@ -277,6 +308,14 @@ impl DebugProbe for OpenOcdProbe {
         Ok(response.trim() == "halted")
     }
 
+    fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
     fn stats(&self) -> &ProbeStats {
         &self.stats
     }
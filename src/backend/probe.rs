@@ -43,7 +43,7 @@
 
 use crate::config::{AppConfig, ConnectUnderReset, ProbeConfig, ProbeProtocol};
 use crate::error::{DataVisError, Result};
-use crate::types::{Variable, VariableType};
+use crate::types::{Endianness, RawValue, Variable};
 use probe_rs::architecture::arm::FullyQualifiedApAddress;
 use probe_rs::{config::Registry, probe::list::Lister, Permissions, Session};
 use std::time::{Duration, Instant};
@@ -93,6 +93,8 @@ pub struct ProbeBackend {
     read_buffer: Vec<u8>,
     /// Statistics
     stats: ProbeStats,
+    /// Target byte order for variable values
+    endianness: Endianness,
 }
 
 impl ProbeBackend {
@@ -100,9 +102,10 @@ impl ProbeBackend {
     pub fn new(config: ProbeConfig) -> Self {
         Self {
             session: None,
-            config,
             read_buffer: vec![0u8; 256], // Pre-allocate buffer
             stats: ProbeStats::default(),
+            endianness: config.endianness.unwrap_or_default(),
+            config,
         }
     }
 
@@ -381,6 +384,11 @@ impl ProbeBackend {
 
     /// Read a variable's value from memory
     pub fn read_variable(&mut self, variable: &Variable) -> Result<f64> {
+        self.read_variable_raw(variable).map(|raw| raw.as_f64())
+    }
+
+    /// Read a variable's value from memory, keeping integers exact
+    pub fn read_variable_raw(&mut self, variable: &Variable) -> Result<RawValue> {
         let session = self
             .session
             .as_mut()
@@ -410,10 +418,10 @@ impl ProbeBackend {
                 self.stats.total_read_time_us += self.stats.last_read_time_us;
                 self.stats.total_bytes_read += size as u64;
 
-                // Parse the raw bytes to f64
+                // Parse the raw bytes, keeping integers exact
                 variable
                     .var_type
-                    .parse_to_f64(&self.read_buffer[..size])
+                    .parse_raw_endian(&self.read_buffer[..size], self.endianness)
                     .ok_or_else(|| DataVisError::Variable("Failed to parse value".to_string()))
             }
             Err(e) => {
@@ -433,6 +441,14 @@ impl ProbeBackend {
     /// Reads are performed via the ARM Debug Access Port (DAP) which accesses memory
     /// directly through AP 0 without halting the CPU core.
     pub fn read_variables(&mut self, variables: &[Variable]) -> Vec<Result<f64>> {
+        self.read_variables_raw(variables)
+            .into_iter()
+            .map(|result| result.map(|raw| raw.as_f64()))
+            .collect()
+    }
+
+    /// Read multiple variables with bulk reads, keeping integers exact
+    pub fn read_variables_raw(&mut self, variables: &[Variable]) -> Vec<Result<RawValue>> {
         use super::read_manager::ReadManager;
 
         if variables.is_empty() {
@@ -475,7 +491,8 @@ impl ProbeBackend {
             gap_threshold,
             self.config.max_bulk_read_size,
             self.config.disable_bulk_reads,
-        );
+        )
+        .with_endianness(self.endianness);
         let regions = read_manager.plan_reads(variables);
 
         // Track bulk read statistics
@@ -492,7 +509,7 @@ impl ProbeBackend {
         );
 
         // Pre-initialize results vector with errors (will be replaced on success)
-        let mut results: Vec<Result<f64>> = variables
+        let mut results: Vec<Result<RawValue>> = variables
             .iter()
             .map(|_| Err(DataVisError::Variable("Not read".to_string())))
             .collect();
@@ -515,7 +532,7 @@ impl ProbeBackend {
                     // Extract values for each variable in this region
                     for &var_idx in &region.variable_indices {
                         let variable = &variables[var_idx];
-                        if let Some(value) = read_manager.extract_value(
+                        if let Some(value) = read_manager.extract_raw_value(
                             variable,
                             region,
                             &self.read_buffer[..region.size],
@@ -611,24 +628,12 @@ impl ProbeBackend {
             .as_mut()
             .ok_or_else(|| DataVisError::Config("Not connected to probe".to_string()))?;
 
-        let bytes = match variable.var_type {
-            VariableType::U8 => vec![(value as u8)],
-            VariableType::I8 => vec![(value as i8 as u8)],
-            VariableType::Bool => vec![if value != 0.0 { 1 } else { 0 }],
-            VariableType::U16 => (value as u16).to_le_bytes().to_vec(),
-            VariableType::I16 => (value as i16).to_le_bytes().to_vec(),
-            VariableType::U32 => (value as u32).to_le_bytes().to_vec(),
-            VariableType::I32 => (value as i32).to_le_bytes().to_vec(),
-            VariableType::F32 => (value as f32).to_le_bytes().to_vec(),
-            VariableType::U64 => (value as u64).to_le_bytes().to_vec(),
-            VariableType::I64 => (value as i64).to_le_bytes().to_vec(),
-            VariableType::F64 => value.to_le_bytes().to_vec(),
-            VariableType::Raw(_) => {
-                return Err(DataVisError::Variable(
-                    "Cannot write raw type variables".to_string(),
-                ))
-            }
-        };
+        let bytes = variable
+            .var_type
+            .encode_f64_endian(value, self.endianness)
+            .ok_or_else(|| {
+                DataVisError::Variable("Cannot write raw type variables".to_string())
+            })?;
 
         let mut memory = Self::arm_memory_interface(session)?;
         memory
@@ -738,6 +743,14 @@ impl DebugProbe for ProbeBackend {
         ProbeBackend::read_variables(self, variables)
     }
 
+    fn read_variable_raw(&mut self, variable: &Variable) -> Result<RawValue> {
+        ProbeBackend::read_variable_raw(self, variable)
+    }
+
+    fn read_variables_raw(&mut self, variables: &[Variable]) -> Vec<Result<RawValue>> {
+        ProbeBackend::read_variables_raw(self, variables)
+    }
+
     fn write_variable(&mut self, variable: &Variable, value: f64) -> Result<()> {
         ProbeBackend::write_variable(self, variable, value)
     }
@@ -766,6 +779,14 @@ impl DebugProbe for ProbeBackend {
         ProbeBackend::is_halted(self)
     }
 
+    fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
     fn stats(&self) -> &ProbeStats {
         ProbeBackend::stats(self)
     }
@@ -4,7 +4,7 @@
 //! enabling both real hardware probes (via probe-rs) and mock probes for testing.
 
 use crate::error::Result;
-use crate::types::Variable;
+use crate::types::{Endianness, RawValue, Variable};
 use std::collections::VecDeque;
 
 /// Size of the rolling window for recent read times
@@ -187,6 +187,26 @@ pub trait DebugProbe: Send {
         variables.iter().map(|v| self.read_variable(v)).collect()
     }
 
+    /// Read a single variable without losing integer precision
+    ///
+    /// Default implementation widens [`read_variable`](Self::read_variable);
+    /// implementations that see the raw bytes should override this so 64-bit
+    /// integers stay exact.
+    fn read_variable_raw(&mut self, variable: &Variable) -> Result<RawValue> {
+        self.read_variable(variable).map(RawValue::Float)
+    }
+
+    /// Read multiple variables without losing integer precision
+    ///
+    /// Default implementation reads one by one via
+    /// [`read_variable_raw`](Self::read_variable_raw).
+    fn read_variables_raw(&mut self, variables: &[Variable]) -> Vec<Result<RawValue>> {
+        variables
+            .iter()
+            .map(|v| self.read_variable_raw(v))
+            .collect()
+    }
+
     /// Write a variable's value
     ///
     /// The value is converted to bytes according to the variable's type.
@@ -221,6 +241,12 @@ pub trait DebugProbe: Send {
     /// Check if the target is halted
     fn is_halted(&mut self) -> Result<bool>;
 
+    /// Set the target byte order used to decode and encode variable values
+    fn set_endianness(&mut self, endianness: Endianness);
+
+    /// Get the target byte order used to decode and encode variable values
+    fn endianness(&self) -> Endianness;
+
     /// Get probe operation statistics
     fn stats(&self) -> &ProbeStats;
 
@@ -25,7 +25,7 @@
 //! }
 //! ```
 
-use crate::types::{PointerRuntime, Variable};
+use crate::types::{Endianness, PointerRuntime, RawValue, Variable};
 use std::time::Instant;
 
 /// Default gap threshold for combining reads (64 bytes)
@@ -43,6 +43,8 @@ pub struct ReadManager {
     max_read_size: usize,
     /// When true, each variable gets its own read (no grouping).
     disable_bulk: bool,
+    /// Byte order used when decoding values from read data
+    endianness: Endianness,
 }
 
 /// A planned memory read region
@@ -70,6 +72,7 @@ impl ReadManager {
             gap_threshold,
             max_read_size: 0,
             disable_bulk: false,
+            endianness: Endianness::Little,
         }
     }
 
@@ -84,14 +87,31 @@ impl ReadManager {
             gap_threshold,
             max_read_size,
             disable_bulk,
+            endianness: Endianness::Little,
         }
     }
 
+    /// Set the target byte order used by [`extract_value`](Self::extract_value)
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
     /// Get the current gap threshold
     pub fn gap_threshold(&self) -> usize {
         self.gap_threshold
     }
 
+    /// Get the target byte order
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// Change the target byte order (e.g. after loading a different ELF)
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
     /// Plan optimized reads for a set of variables
     ///
     /// Analyzes the variable addresses and groups them into read regions.
@@ -191,6 +211,19 @@ impl ReadManager {
         region: &ReadRegion,
         data: &[u8],
     ) -> Option<f64> {
+        self.extract_raw_value(variable, region, data).map(|raw| raw.as_f64())
+    }
+
+    /// Extract a variable's exact raw value from bulk read data
+    ///
+    /// Same as [`extract_value`](Self::extract_value) but keeps integer
+    /// values exact instead of widening them to `f64`.
+    pub fn extract_raw_value(
+        &self,
+        variable: &Variable,
+        region: &ReadRegion,
+        data: &[u8],
+    ) -> Option<RawValue> {
         // Check if variable address is within the region
         if variable.address < region.address {
             return None;
@@ -205,7 +238,9 @@ impl ReadManager {
         }
 
         // Parse the value from the appropriate offset
-        variable.var_type.parse_to_f64(&data[offset..offset + size])
+        variable
+            .var_type
+            .parse_raw_endian(&data[offset..offset + size], self.endianness)
     }
 
     /// Calculate how many individual reads would be saved by using bulk reads
@@ -235,6 +270,7 @@ impl Default for ReadManager {
             gap_threshold: DEFAULT_GAP_THRESHOLD,
             max_read_size: 0,
             disable_bulk: false,
+            endianness: Endianness::Little,
         }
     }
 }
@@ -523,6 +559,65 @@ mod tests {
         assert!(value.is_none());
     }
 
+    #[test]
+    fn test_extract_value_fixed_and_half() {
+        let manager = ReadManager::new(64);
+        let q15 = create_test_variable("q15", 0x2000_0000, VariableType::Q15);
+        let half = create_test_variable("half", 0x2000_0002, VariableType::F16);
+        let bf16 = create_test_variable("bf16", 0x2000_0004, VariableType::BF16);
+        let vars = vec![q15.clone(), half.clone(), bf16.clone()];
+        let regions = manager.plan_reads(&vars);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].size, 6);
+
+        let data: Vec<u8> = vec![
+            0x00, 0xC0, // Q15 -0.5
+            0x00, 0x3C, // f16 1.0
+            0x20, 0x41, // bf16 10.0
+        ];
+        assert_eq!(manager.extract_value(&q15, &regions[0], &data), Some(-0.5));
+        assert_eq!(manager.extract_value(&half, &regions[0], &data), Some(1.0));
+        assert_eq!(manager.extract_value(&bf16, &regions[0], &data), Some(10.0));
+    }
+
+    #[test]
+    fn test_extract_value_big_endian() {
+        let manager = ReadManager::new(64).with_endianness(Endianness::Big);
+        let var = create_test_variable("test", 0x2000_0004, VariableType::U32);
+        let half = create_test_variable("half", 0x2000_0008, VariableType::I16);
+        let region = ReadRegion {
+            address: 0x2000_0000,
+            size: 10,
+            variable_indices: vec![0, 1],
+        };
+        let data: Vec<u8> = vec![
+            0x00, 0x00, 0x00, 0x00, // offset 0-3
+            0x12, 0x34, 0x56, 0x78, // offset 4-7 (big endian 0x12345678)
+            0xFF, 0x9C, // offset 8-9 (big endian -100)
+        ];
+
+        assert_eq!(manager.extract_value(&var, &region, &data), Some(0x12345678 as f64));
+        assert_eq!(manager.extract_value(&half, &region, &data), Some(-100.0));
+    }
+
+    #[test]
+    fn test_extract_raw_value_u64_exact() {
+        let manager = ReadManager::new(64);
+        let var = create_test_variable("cycles", 0x2000_0000, VariableType::U64);
+        let region = ReadRegion {
+            address: 0x2000_0000,
+            size: 8,
+            variable_indices: vec![0],
+        };
+        let cycles = u64::MAX - 1;
+        let data = cycles.to_le_bytes();
+
+        assert_eq!(
+            manager.extract_raw_value(&var, &region, &data),
+            Some(RawValue::UInt(cycles))
+        );
+    }
+
     #[test]
     fn test_calculate_savings() {
         let manager = ReadManager::new(64);
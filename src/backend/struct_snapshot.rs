@@ -0,0 +1,611 @@
+//! Struct snapshot layout and decoding
+//!
+//! Describes a struct/class/union (or array) as a tree of byte ranges inside
+//! one contiguous buffer, so the whole object can be fetched with a single
+//! `read_memory` call and every member decoded from that copy. This backs the
+//! struct inspector, which behaves like a debugger watch window: nothing is
+//! added to the variable list and the tree updates at poll rate.
+//!
+//! Inherited members are already flattened into `StructDef::members` by the
+//! DWARF parser (at their offset within the derived class), so base classes
+//! need no special handling here.
+
+use crate::backend::type_table::{EnumDef, MemberDef, TypeDef, TypeId, TypeTable};
+use crate::types::{Endianness, RawValue, VariableType};
+
+/// Maximum nesting depth followed when building a layout
+const MAX_DEPTH: usize = 32;
+
+/// Maximum number of elements listed per array
+pub const MAX_SNAPSHOT_ARRAY_ELEMENTS: u64 = 256;
+
+/// How the value of a snapshot node is decoded
+#[derive(Debug, Clone)]
+pub enum SnapshotValue {
+    /// Struct, union or array: no value of its own
+    Aggregate,
+    /// Primitive scalar
+    Scalar(VariableType),
+    /// Bit field; `bit_offset` counts from the start of the snapshot buffer
+    BitField {
+        bit_offset: u64,
+        bit_size: u64,
+        signed: bool,
+    },
+    /// Enumeration shown by variant name
+    Enum {
+        var_type: VariableType,
+        def: EnumDef,
+    },
+    /// Pointer or reference shown as an address
+    Pointer,
+    /// `char` array shown as a string
+    Text,
+}
+
+/// A member of a snapshot layout
+#[derive(Debug, Clone)]
+pub struct SnapshotNode {
+    /// Member name (`[i]` for array elements)
+    pub name: String,
+    /// Display name of the member's type
+    pub type_name: String,
+    /// Byte offset within the snapshot buffer
+    pub offset: u64,
+    /// Size in bytes
+    pub size: u64,
+    /// How the value is decoded
+    pub value: SnapshotValue,
+    /// Pre-order index of this node, unique within its layout
+    pub index: usize,
+    /// Nested members or elements
+    pub children: Vec<SnapshotNode>,
+}
+
+impl SnapshotNode {
+    /// Build the layout of a type, rooted at offset 0
+    ///
+    /// `endianness` is the target's; it places DWARF 2/3 bit fields.
+    pub fn build(
+        table: &TypeTable,
+        type_id: TypeId,
+        name: impl Into<String>,
+        endianness: Endianness,
+    ) -> Self {
+        let mut next_index = 0;
+        Self::build_at(
+            table,
+            type_id,
+            name.into(),
+            0,
+            0,
+            endianness,
+            &mut next_index,
+        )
+    }
+
+    fn build_at(
+        table: &TypeTable,
+        type_id: TypeId,
+        name: String,
+        offset: u64,
+        depth: usize,
+        endianness: Endianness,
+        next_index: &mut usize,
+    ) -> Self {
+        let index = *next_index;
+        *next_index += 1;
+
+        let mut node = SnapshotNode {
+            name,
+            type_name: table.type_name(type_id),
+            offset,
+            size: table.type_size(type_id).unwrap_or(0),
+            value: SnapshotValue::Aggregate,
+            index,
+            children: Vec::new(),
+        };
+
+        let underlying = table.get_underlying(type_id);
+        match table.get(underlying) {
+            Some(TypeDef::Struct(s)) | Some(TypeDef::Union(s)) if depth < MAX_DEPTH => {
+                node.children = s
+                    .members
+                    .iter()
+                    .map(|m| {
+                        Self::build_member(table, m, offset, depth + 1, endianness, next_index)
+                    })
+                    .collect();
+            }
+            Some(TypeDef::Array { element, count }) => {
+                if table.is_char(*element) {
+                    node.value = SnapshotValue::Text;
+                } else if depth < MAX_DEPTH {
+                    let elem_size = table.type_size(*element).unwrap_or(0);
+                    let count = count.unwrap_or(0).min(MAX_SNAPSHOT_ARRAY_ELEMENTS);
+                    node.children = (0..count)
+                        .map(|i| {
+                            Self::build_at(
+                                table,
+                                *element,
+                                format!("[{}]", i),
+                                offset + i * elem_size,
+                                depth + 1,
+                                endianness,
+                                next_index,
+                            )
+                        })
+                        .collect();
+                }
+            }
+            Some(TypeDef::Enum(e)) => {
+                node.value = SnapshotValue::Enum {
+                    var_type: table.to_variable_type(underlying),
+                    def: e.clone(),
+                };
+            }
+            Some(TypeDef::Pointer(_)) | Some(TypeDef::Reference(_)) => {
+                node.value = SnapshotValue::Pointer;
+            }
+            Some(TypeDef::Primitive(_)) => {
+                node.value = SnapshotValue::Scalar(table.to_variable_type(underlying));
+            }
+            _ => {}
+        }
+
+        node
+    }
+
+    fn build_member(
+        table: &TypeTable,
+        member: &MemberDef,
+        parent_offset: u64,
+        depth: usize,
+        endianness: Endianness,
+        next_index: &mut usize,
+    ) -> Self {
+        let offset = parent_offset + member.offset;
+        let mut node = Self::build_at(
+            table,
+            member.type_id,
+            member.name.clone(),
+            offset,
+            depth,
+            endianness,
+            next_index,
+        );
+        let bit_offset = member.data_bit_offset(node.size, endianness);
+        if let (Some(bit_size), Some(bit_offset)) = (member.bit_size, bit_offset) {
+            node.value = SnapshotValue::BitField {
+                bit_offset: offset * 8 + bit_offset,
+                bit_size,
+                signed: is_signed(table.to_variable_type(member.type_id)),
+            };
+        }
+        node
+    }
+
+    /// Total number of nodes in this layout (including the root)
+    pub fn node_count(&self) -> usize {
+        1 + self.children.iter().map(|c| c.node_count()).sum::<usize>()
+    }
+
+    /// Bytes of this node within a snapshot buffer, if the buffer covers them
+    fn bytes<'a>(&self, buffer: &'a [u8]) -> Option<&'a [u8]> {
+        let start = usize::try_from(self.offset).ok()?;
+        let end = start.checked_add(usize::try_from(self.size).ok()?)?;
+        buffer.get(start..end)
+    }
+
+    /// Decode the raw value of a scalar, bit field, enum or pointer node
+    pub fn raw_value(&self, buffer: &[u8], endianness: Endianness) -> Option<RawValue> {
+        match &self.value {
+            SnapshotValue::Scalar(var_type) | SnapshotValue::Enum { var_type, .. } => {
+                var_type.parse_raw_endian(self.bytes(buffer)?, endianness)
+            }
+            SnapshotValue::Pointer => {
+                pointer_type(self.size).parse_raw_endian(self.bytes(buffer)?, endianness)
+            }
+            SnapshotValue::BitField {
+                bit_offset,
+                bit_size,
+                signed,
+            } => extract_bits(buffer, *bit_offset, *bit_size, *signed, endianness),
+            SnapshotValue::Aggregate | SnapshotValue::Text => None,
+        }
+    }
+
+    /// Format the value of this node for display
+    ///
+    /// Returns `None` for aggregates and when the buffer is too short.
+    pub fn format_value(&self, buffer: &[u8], endianness: Endianness) -> Option<String> {
+        match &self.value {
+            SnapshotValue::Aggregate => None,
+            SnapshotValue::Text => Some(format!(
+                "\"{}\"",
+                VariableType::decode_text(self.bytes(buffer)?)
+            )),
+            SnapshotValue::Pointer => {
+                let address = self.raw_value(buffer, endianness)?;
+                Some(format!("0x{:08X}", address.as_i64_bits() as u64))
+            }
+            SnapshotValue::Enum { def, .. } => {
+                let raw = self.raw_value(buffer, endianness)?;
+                Some(def.value_to_string(raw.as_i64_bits()))
+            }
+            SnapshotValue::Scalar(var_type) => {
+                let raw = self.raw_value(buffer, endianness)?;
+                Some(match var_type {
+                    // Print single precision values without f64 widening noise
+                    VariableType::F32 | VariableType::F16 | VariableType::BF16 => {
+                        (raw.as_f64() as f32).to_string()
+                    }
+                    _ => raw.to_string(),
+                })
+            }
+            SnapshotValue::BitField { .. } => Some(self.raw_value(buffer, endianness)?.to_string()),
+        }
+    }
+
+    /// Whether this node's value differs between two snapshots
+    ///
+    /// Bit fields compare only their own bits; everything else compares the
+    /// node's whole byte range.
+    pub fn changed(&self, previous: &[u8], current: &[u8], endianness: Endianness) -> bool {
+        match self.value {
+            SnapshotValue::BitField { .. } => {
+                self.raw_value(previous, endianness) != self.raw_value(current, endianness)
+            }
+            _ => self.bytes(previous) != self.bytes(current),
+        }
+    }
+
+    /// Collect the indices of all nodes whose value changed between two snapshots
+    pub fn collect_changes(
+        &self,
+        previous: &[u8],
+        current: &[u8],
+        endianness: Endianness,
+        changed: &mut Vec<usize>,
+    ) {
+        if !self.changed(previous, current, endianness) {
+            return;
+        }
+        changed.push(self.index);
+        for child in &self.children {
+            child.collect_changes(previous, current, endianness, changed);
+        }
+    }
+
+    /// Bit fields lying entirely within the `size` bytes at `offset`
+    ///
+    /// Returns `(name, shift, width)` for each field, with `shift` counted
+    /// from the least significant bit of the integer those bytes decode to,
+    /// so the fields of a register or flag word read as one variable can be
+    /// extracted from its value.
+    pub fn bit_fields_within(
+        &self,
+        offset: u64,
+        size: u64,
+        endianness: Endianness,
+    ) -> Vec<(String, u32, u32)> {
+        let mut fields = Vec::new();
+        self.collect_bit_fields(offset * 8, size * 8, endianness, &mut fields);
+        fields
+    }
+
+    fn collect_bit_fields(
+        &self,
+        start_bit: u64,
+        bits: u64,
+        endianness: Endianness,
+        fields: &mut Vec<(String, u32, u32)>,
+    ) {
+        if let SnapshotValue::BitField {
+            bit_offset,
+            bit_size,
+            ..
+        } = self.value
+        {
+            if bit_offset >= start_bit && bit_offset + bit_size <= start_bit + bits {
+                let position = bit_offset - start_bit;
+                let shift = match endianness {
+                    Endianness::Little => position,
+                    // Big-endian bit numbering starts at the most significant bit
+                    Endianness::Big => bits - position - bit_size,
+                };
+                fields.push((self.name.clone(), shift as u32, bit_size as u32));
+            }
+        }
+        for child in &self.children {
+            child.collect_bit_fields(start_bit, bits, endianness, fields);
+        }
+    }
+}
+
+/// Whether a variable type is a signed integer
+fn is_signed(var_type: VariableType) -> bool {
+    matches!(
+        var_type,
+        VariableType::I8 | VariableType::I16 | VariableType::I32 | VariableType::I64
+    )
+}
+
+/// Unsigned integer type used to read a pointer of the given size
+fn pointer_type(size: u64) -> VariableType {
+    match size {
+        1 => VariableType::U8,
+        2 => VariableType::U16,
+        8 => VariableType::U64,
+        _ => VariableType::U32,
+    }
+}
+
+/// Extract a bit field of up to 57 bits from a buffer
+///
+/// Little-endian targets number bits from the least significant bit of the
+/// first byte, big-endian targets from the most significant bit.
+fn extract_bits(
+    buffer: &[u8],
+    bit_offset: u64,
+    bit_size: u64,
+    signed: bool,
+    endianness: Endianness,
+) -> Option<RawValue> {
+    if bit_size == 0 || bit_size > 57 {
+        return None;
+    }
+    let start = usize::try_from(bit_offset / 8).ok()?;
+    let shift = bit_offset % 8;
+    let byte_count = (shift + bit_size).div_ceil(8) as usize;
+    let window = buffer.get(start..start.checked_add(byte_count)?)?;
+
+    let word = match endianness {
+        Endianness::Little => {
+            let word = window
+                .iter()
+                .enumerate()
+                .fold(0u64, |acc, (i, &b)| acc | (b as u64) << (8 * i));
+            word >> shift
+        }
+        Endianness::Big => {
+            let word = window.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+            word >> (byte_count as u64 * 8 - shift - bit_size)
+        }
+    };
+    let mask = (1u64 << bit_size) - 1;
+    let value = word & mask;
+
+    if signed && (value >> (bit_size - 1)) & 1 == 1 {
+        Some(RawValue::from_i64((value | !mask) as i64))
+    } else {
+        Some(RawValue::from_u64(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::type_table::{BitOffsetKind, EnumVariant, PrimitiveDef, StructDef};
+
+    /// struct Motor { float speed; uint8_t state : 3; int8_t dir : 2; Mode mode; char tag[4]; int hist[2]; }
+    fn motor_table() -> (TypeTable, TypeId) {
+        let mut table = TypeTable::new();
+        let float_id = table.insert(TypeDef::Primitive(PrimitiveDef::Float));
+        let uchar_id = table.insert(TypeDef::Primitive(PrimitiveDef::UnsignedChar));
+        let schar_id = table.insert(TypeDef::Primitive(PrimitiveDef::SignedChar));
+        let char_id = table.insert(TypeDef::Primitive(PrimitiveDef::Char));
+        let int_id = table.insert(TypeDef::Primitive(PrimitiveDef::Int));
+
+        let mut mode = EnumDef::new(Some("Mode".to_string()), 1, false);
+        mode.variants.push(EnumVariant {
+            name: "IDLE".to_string(),
+            value: 0,
+        });
+        mode.variants.push(EnumVariant {
+            name: "RUN".to_string(),
+            value: 1,
+        });
+        let mode_id = table.insert(TypeDef::Enum(mode));
+        let tag_id = table.insert(TypeDef::Array {
+            element: char_id,
+            count: Some(4),
+        });
+        let hist_id = table.insert(TypeDef::Array {
+            element: int_id,
+            count: Some(2),
+        });
+
+        let mut motor = StructDef::new(Some("Motor".to_string()), 20, false);
+        motor
+            .members
+            .push(MemberDef::new("speed".to_string(), 0, float_id));
+        let mut state = MemberDef::new("state".to_string(), 4, uchar_id);
+        state.bit_offset = Some(0);
+        state.bit_size = Some(3);
+        motor.members.push(state);
+        let mut dir = MemberDef::new("dir".to_string(), 4, schar_id);
+        dir.bit_offset = Some(3);
+        dir.bit_size = Some(2);
+        motor.members.push(dir);
+        motor
+            .members
+            .push(MemberDef::new("mode".to_string(), 5, mode_id));
+        motor
+            .members
+            .push(MemberDef::new("tag".to_string(), 6, tag_id));
+        motor
+            .members
+            .push(MemberDef::new("hist".to_string(), 12, hist_id));
+        let motor_id = table.insert(TypeDef::Struct(motor));
+        (table, motor_id)
+    }
+
+    /// The Motor bit fields as DWARF 2/3 describes them (`DW_AT_bit_offset`)
+    /// for a little-endian target, in a storage unit of `unit` bytes
+    fn legacy_motor_table(unit: u64) -> (TypeTable, TypeId) {
+        let (mut table, motor_id) = motor_table();
+        if let Some(TypeDef::Struct(motor)) = table.get_mut(motor_id) {
+            for member in motor.members.iter_mut().filter(|m| m.is_bitfield()) {
+                // Counted from the most significant bit of the unit
+                let (offset, size) = (member.bit_offset.unwrap(), member.bit_size.unwrap());
+                member.bit_offset = Some(unit * 8 - offset - size);
+                member.bit_offset_kind = BitOffsetKind::StorageMsb;
+                member.storage_size = Some(unit);
+            }
+        }
+        (table, motor_id)
+    }
+
+    fn motor_bytes(speed: f32, bits: u8, mode: u8, tag: &[u8; 4], hist: [i32; 2]) -> Vec<u8> {
+        let mut bytes = vec![0u8; 20];
+        bytes[0..4].copy_from_slice(&speed.to_le_bytes());
+        bytes[4] = bits;
+        bytes[5] = mode;
+        bytes[6..10].copy_from_slice(tag);
+        bytes[12..16].copy_from_slice(&hist[0].to_le_bytes());
+        bytes[16..20].copy_from_slice(&hist[1].to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_layout_structure() {
+        let (table, motor_id) = motor_table();
+        let root = SnapshotNode::build(&table, motor_id, "motor", Endianness::Little);
+
+        assert_eq!(root.name, "motor");
+        assert_eq!(root.size, 20);
+        let names: Vec<&str> = root.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["speed", "state", "dir", "mode", "tag", "hist"]);
+        // tag is shown as text, hist has two elements
+        assert!(root.children[4].children.is_empty());
+        assert_eq!(root.children[5].children.len(), 2);
+        assert_eq!(root.children[5].children[1].offset, 16);
+        // Indices are unique and dense
+        assert_eq!(root.node_count(), 9);
+        assert_eq!(root.children[5].children[1].index, 8);
+    }
+
+    #[test]
+    fn test_decode_members() {
+        let (table, motor_id) = motor_table();
+        let root = SnapshotNode::build(&table, motor_id, "motor", Endianness::Little);
+        let le = Endianness::Little;
+        // state = 5 (bits 0-2), dir = -1 (bits 3-4 = 0b11)
+        let bytes = motor_bytes(1.5, 0b0001_1101, 1, b"ok\0\0", [-7, 42]);
+
+        let value = |i: usize| root.children[i].format_value(&bytes, le);
+        assert_eq!(value(0).as_deref(), Some("1.5"));
+        assert_eq!(value(1).as_deref(), Some("5"));
+        assert_eq!(value(2).as_deref(), Some("-1"));
+        assert_eq!(value(3).as_deref(), Some("RUN"));
+        assert_eq!(value(4).as_deref(), Some("\"ok\""));
+        assert_eq!(value(5), None);
+        assert_eq!(
+            root.children[5].children[0]
+                .format_value(&bytes, le)
+                .as_deref(),
+            Some("-7")
+        );
+
+        // Truncated buffers decode nothing instead of panicking
+        assert_eq!(
+            root.children[5].children[1].format_value(&bytes[..18], le),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decode_legacy_bit_offset() {
+        let (table, motor_id) = legacy_motor_table(1);
+        let root = SnapshotNode::build(&table, motor_id, "motor", Endianness::Little);
+        let le = Endianness::Little;
+        let bytes = motor_bytes(1.5, 0b0001_1101, 1, b"ok\0\0", [-7, 42]);
+
+        assert_eq!(
+            root.children[1].format_value(&bytes, le).as_deref(),
+            Some("5")
+        );
+        assert_eq!(
+            root.children[2].format_value(&bytes, le).as_deref(),
+            Some("-1")
+        );
+
+        // Big-endian targets count from the most significant bit already
+        let root = SnapshotNode::build(&table, motor_id, "motor", Endianness::Big);
+        let bytes = motor_bytes(1.5, 0b0000_0101, 1, b"ok\0\0", [-7, 42]);
+        let be = Endianness::Big;
+        assert_eq!(
+            root.children[1].format_value(&bytes, be).as_deref(),
+            Some("5")
+        );
+    }
+
+    #[test]
+    fn test_change_detection() {
+        let (table, motor_id) = motor_table();
+        let root = SnapshotNode::build(&table, motor_id, "motor", Endianness::Little);
+        let le = Endianness::Little;
+        let before = motor_bytes(1.5, 0b0001_1101, 0, b"ok\0\0", [1, 2]);
+        // Only dir and hist[1] change; state shares dir's byte but keeps its bits
+        let after = motor_bytes(1.5, 0b0000_1101, 0, b"ok\0\0", [1, 3]);
+
+        let mut changed = Vec::new();
+        root.collect_changes(&before, &after, le, &mut changed);
+        let dir = root.children[2].index;
+        let hist = root.children[5].index;
+        let hist1 = root.children[5].children[1].index;
+        assert_eq!(changed, vec![root.index, dir, hist, hist1]);
+    }
+
+    #[test]
+    fn test_bit_fields_within() {
+        let (table, motor_id) = motor_table();
+        let root = SnapshotNode::build(&table, motor_id, "motor", Endianness::Little);
+
+        // state and dir share the byte at offset 4
+        assert_eq!(
+            root.bit_fields_within(4, 1, Endianness::Little),
+            vec![("state".to_string(), 0, 3), ("dir".to_string(), 3, 2)]
+        );
+        assert_eq!(
+            root.bit_fields_within(4, 1, Endianness::Big),
+            vec![("state".to_string(), 5, 3), ("dir".to_string(), 3, 2)]
+        );
+        // A wider word around them still finds both, shifted by the byte offset
+        assert_eq!(
+            root.bit_fields_within(4, 4, Endianness::Little),
+            vec![("state".to_string(), 0, 3), ("dir".to_string(), 3, 2)]
+        );
+        assert!(root.bit_fields_within(0, 4, Endianness::Little).is_empty());
+    }
+
+    #[test]
+    fn test_bit_fields_within_legacy_bit_offset() {
+        // Byte and word storage units put the names on the same lanes
+        for unit in [1, 4] {
+            let (table, motor_id) = legacy_motor_table(unit);
+            let root = SnapshotNode::build(&table, motor_id, "motor", Endianness::Little);
+            assert_eq!(
+                root.bit_fields_within(4, 1, Endianness::Little),
+                vec![("state".to_string(), 0, 3), ("dir".to_string(), 3, 2)],
+                "{}-byte storage unit",
+                unit
+            );
+        }
+    }
+
+    #[test]
+    fn test_big_endian_bitfield() {
+        // Big-endian bit numbering starts at the most significant bit
+        assert_eq!(
+            extract_bits(&[0b1010_0000], 0, 3, false, Endianness::Big),
+            Some(RawValue::UInt(0b101))
+        );
+        assert_eq!(
+            extract_bits(&[0b1010_0000], 0, 3, false, Endianness::Little),
+            Some(RawValue::UInt(0))
+        );
+        assert_eq!(extract_bits(&[0xFF], 4, 8, false, Endianness::Little), None);
+    }
+}
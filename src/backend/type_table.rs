@@ -8,7 +8,7 @@
 //!
 //! The TypeHandle struct wraps `Arc<TypeTable>` + TypeId for zero-overhead type access.
 
-use crate::types::VariableType;
+use crate::types::{Endianness, VariableType};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -302,6 +302,7 @@ impl PrimitiveDef {
                 _ => VariableType::Raw(*size as usize),
             },
             PrimitiveDef::SizedFloat { size } => match size {
+                2 => VariableType::F16,
                 4 => VariableType::F32,
                 8 => VariableType::F64,
                 _ => VariableType::Raw(*size as usize),
@@ -347,6 +348,18 @@ impl StructDef {
     }
 }
 
+/// DWARF attribute a bit field's offset was read from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BitOffsetKind {
+    /// `DW_AT_data_bit_offset` (DWARF 4+): bits from the member's byte
+    /// offset, in the target's bit numbering
+    #[default]
+    Data,
+    /// `DW_AT_bit_offset` (DWARF 2/3): bits from the most significant bit of
+    /// the storage unit at the member's byte offset
+    StorageMsb,
+}
+
 /// A member of a struct/union
 #[derive(Debug, Clone)]
 pub struct MemberDef {
@@ -355,6 +368,9 @@ pub struct MemberDef {
     pub type_id: TypeId,
     pub bit_offset: Option<u64>,
     pub bit_size: Option<u64>,
+    pub bit_offset_kind: BitOffsetKind,
+    /// Size of the storage unit of a DWARF 2/3 bit field (`DW_AT_byte_size`)
+    pub storage_size: Option<u64>,
 }
 
 impl MemberDef {
@@ -365,6 +381,8 @@ impl MemberDef {
             type_id,
             bit_offset: None,
             bit_size: None,
+            bit_offset_kind: BitOffsetKind::Data,
+            storage_size: None,
         }
     }
 
@@ -372,6 +390,22 @@ impl MemberDef {
     pub fn is_bitfield(&self) -> bool {
         self.bit_size.is_some()
     }
+
+    /// Bit offset from the member's byte offset in the target's bit numbering
+    ///
+    /// Little-endian targets number bits from the least significant bit, so
+    /// a `DW_AT_bit_offset` is flipped within its storage unit. `type_size`
+    /// is the unit size when DWARF gives none.
+    pub fn data_bit_offset(&self, type_size: u64, endianness: Endianness) -> Option<u64> {
+        let bit_offset = self.bit_offset.unwrap_or(0);
+        match (self.bit_offset_kind, endianness) {
+            (BitOffsetKind::StorageMsb, Endianness::Little) => {
+                let unit_bits = self.storage_size.unwrap_or(type_size) * 8;
+                unit_bits.checked_sub(bit_offset + self.bit_size?)
+            }
+            _ => Some(bit_offset),
+        }
+    }
 }
 
 /// Base class for C++ inheritance
@@ -459,6 +493,8 @@ pub struct TypeTable {
     name_to_id: HashMap<String, Vec<TypeId>>,
     /// Pending forward declarations that need resolution
     pending_forward_decls: Vec<TypeId>,
+    /// Size of pointers and references on the target in bytes
+    pointer_size: u64,
 }
 
 impl Default for TypeTable {
@@ -475,9 +511,20 @@ impl TypeTable {
             dwarf_to_id: HashMap::new(),
             name_to_id: HashMap::new(),
             pending_forward_decls: Vec::new(),
+            pointer_size: 4,
         }
     }
 
+    /// Size of pointers and references in bytes (4 unless set from DWARF)
+    pub fn pointer_size(&self) -> u64 {
+        self.pointer_size
+    }
+
+    /// Set the size of pointers and references (the unit address size)
+    pub fn set_pointer_size(&mut self, size: u64) {
+        self.pointer_size = size;
+    }
+
     /// Get the number of types in the table
     pub fn len(&self) -> usize {
         self.types.len()
@@ -659,7 +706,7 @@ impl TypeTable {
         let resolved_id = self.resolve(id);
         match self.get(resolved_id)? {
             TypeDef::Primitive(p) => Some(p.size()),
-            TypeDef::Pointer(_) | TypeDef::Reference(_) => Some(4),
+            TypeDef::Pointer(_) | TypeDef::Reference(_) => Some(self.pointer_size),
             TypeDef::Array { element, count } => {
                 let elem_size = self.type_size(*element)?;
                 Some(elem_size * count.unwrap_or(0))
@@ -852,12 +899,33 @@ impl TypeTable {
         }
     }
 
+    /// Check if a type is a character type spelled directly as `char`,
+    /// `signed char` or `unsigned char` (qualifiers are ignored)
+    ///
+    /// Typedefs are deliberately not followed: `int8_t`/`uint8_t` buffers are
+    /// binary data, not text.
+    pub fn is_char(&self, id: TypeId) -> bool {
+        match self.get(self.resolve(id)) {
+            Some(TypeDef::Primitive(
+                PrimitiveDef::Char | PrimitiveDef::SignedChar | PrimitiveDef::UnsignedChar,
+            )) => true,
+            Some(TypeDef::Const(inner))
+            | Some(TypeDef::Volatile(inner))
+            | Some(TypeDef::Restrict(inner)) => self.is_char(*inner),
+            _ => false,
+        }
+    }
+
     /// Convert a TypeId to VariableType
     pub fn to_variable_type(&self, id: TypeId) -> VariableType {
         let resolved_id = self.resolve(id);
         match self.get(resolved_id) {
             Some(TypeDef::Primitive(prim)) => prim.to_variable_type(),
-            Some(TypeDef::Pointer(_)) | Some(TypeDef::Reference(_)) => VariableType::U32,
+            Some(TypeDef::Pointer(_)) | Some(TypeDef::Reference(_)) => match self.pointer_size {
+                2 => VariableType::U16,
+                8 => VariableType::U64,
+                _ => VariableType::U32,
+            },
             Some(TypeDef::Enum(e)) => match e.size {
                 1 => VariableType::U8,
                 2 => VariableType::U16,
@@ -871,7 +939,11 @@ impl TypeTable {
             Some(TypeDef::Array { element, count }) => {
                 let elem_size = self.type_size(*element).unwrap_or(0);
                 let total = elem_size * count.unwrap_or(1);
-                VariableType::Raw(total as usize)
+                if self.is_char(*element) {
+                    VariableType::Text(total as usize)
+                } else {
+                    VariableType::Raw(total as usize)
+                }
             }
             Some(TypeDef::Typedef { underlying, .. }) => self.to_variable_type(*underlying),
             Some(TypeDef::Const(inner))
@@ -1116,6 +1188,13 @@ impl TypeHandle {
         }
     }
 
+    /// Check if this is a pointer to `char` (e.g. `const char*`)
+    pub fn is_char_pointer(&self) -> bool {
+        self.underlying()
+            .pointee()
+            .is_some_and(|pointee| self.table.is_char(pointee.id()))
+    }
+
     /// Get the pointee's underlying type handle for pointer expansion
     /// This allows accessing the struct/union members that the pointer points to
     pub fn pointee_underlying(&self) -> Option<TypeHandle> {
@@ -1192,6 +1271,21 @@ mod tests {
         assert_eq!(table.type_size(ptr_id), Some(4));
     }
 
+    #[test]
+    fn test_pointer_size_follows_target() {
+        let mut table = TypeTable::new();
+        let char_id = table.insert(TypeDef::Primitive(PrimitiveDef::Char));
+        let ptr_id = table.insert(TypeDef::Pointer(char_id));
+        assert_eq!(table.to_variable_type(ptr_id), VariableType::U32);
+
+        table.set_pointer_size(8);
+        assert_eq!(table.type_size(ptr_id), Some(8));
+        assert_eq!(table.to_variable_type(ptr_id), VariableType::U64);
+
+        table.set_pointer_size(2);
+        assert_eq!(table.to_variable_type(ptr_id), VariableType::U16);
+    }
+
     #[test]
     fn test_struct_type() {
         let mut table = TypeTable::new();
@@ -1269,6 +1363,36 @@ mod tests {
         assert_eq!(table.type_size(array_id), Some(40));
     }
 
+    #[test]
+    fn test_char_array_and_pointer_are_text() {
+        let mut table = TypeTable::new();
+        let char_id = table.insert(TypeDef::Primitive(PrimitiveDef::UnsignedChar));
+        let const_char_id = table.insert(TypeDef::Const(char_id));
+        let uint8_id = table.insert(TypeDef::Typedef {
+            name: "uint8_t".to_string(),
+            underlying: char_id,
+        });
+
+        let name_id = table.insert(TypeDef::Array {
+            element: char_id,
+            count: Some(32),
+        });
+        let bytes_id = table.insert(TypeDef::Array {
+            element: uint8_id,
+            count: Some(32),
+        });
+        let msg_ptr = table.insert(TypeDef::Pointer(const_char_id));
+        let byte_ptr = table.insert(TypeDef::Pointer(uint8_id));
+        assert_eq!(table.to_variable_type(name_id), VariableType::Text(32));
+        assert_eq!(table.to_variable_type(bytes_id), VariableType::Raw(32));
+
+        let table = Arc::new(table);
+        assert!(TypeHandle::new(table.clone(), msg_ptr).is_char_pointer());
+        assert!(!TypeHandle::new(table.clone(), byte_ptr).is_char_pointer());
+        // The pointer itself still reads as an address
+        assert_eq!(table.to_variable_type(msg_ptr), VariableType::U32);
+    }
+
     #[test]
     fn test_template_struct() {
         let mut table = TypeTable::new();
@@ -25,6 +25,7 @@
 //! For each variable read, the worker provides execution context to Rhai scripts:
 //!
 //! - `value` / `raw` - Current raw value
+//! - `raw_int` - Current raw value as an exact 64-bit integer
 //! - `time()` - Time since collection started
 //! - `dt()` - Time since last sample
 //! - `prev()` / `prev_raw()` - Previous values for derivative calculations
@@ -34,7 +35,10 @@ use crate::backend::probe_trait::DebugProbe;
 use crate::backend::read_manager::{resolve_dependent_addresses, DependentReadPlanner};
 use crate::backend::{BackendCommand, BackendMessage, OpenOcdProbe, ProbeBackend};
 use crate::config::{AppConfig, BackendType};
-use crate::types::{CollectionStats, ConnectionStatus, PointerRuntime, Variable};
+use crate::types::{
+    CollectionStats, ConnectionStatus, Endianness, PointerRuntime, RawValue, Variable,
+    VariableType,
+};
 use crossbeam_channel::{Receiver, Sender, TryRecvError};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -67,7 +71,7 @@ pub enum SwdResponse {
     /// Read completed successfully
     ReadComplete {
         variable_id: u32,
-        raw_value: f64,
+        raw_value: RawValue,
         converted_value: f64,
         timestamp: Duration,
     },
@@ -125,10 +129,10 @@ impl DataRouter {
     #[allow(dead_code, clippy::type_complexity)]
     pub fn route(
         &self,
-        data: Vec<(u32, Duration, f64, f64)>,
+        data: Vec<(u32, Duration, RawValue, f64)>,
     ) -> (
-        Vec<(u32, Duration, f64, f64)>,
-        HashMap<u64, Vec<(u32, Duration, f64, f64)>>,
+        Vec<(u32, Duration, RawValue, f64)>,
+        HashMap<u64, Vec<(u32, Duration, RawValue, f64)>>,
     ) {
         let global = data.clone(); // All panes see global data
 
@@ -189,6 +193,19 @@ pub struct BackendWorker {
     /// Data router for per-pane filtering (Phase 2 - not yet used)
     #[allow(dead_code)]
     data_router: DataRouter,
+    /// Byte order reported by the loaded ELF file
+    elf_endianness: Endianness,
+    /// Byte order override from the probe configuration
+    endianness_override: Option<Endianness>,
+    /// Memory blocks read in one request per poll, keyed by watch ID
+    memory_watches: HashMap<u64, MemoryWatch>,
+}
+
+/// A memory block read as a whole on every poll
+#[derive(Debug, Clone, Copy)]
+struct MemoryWatch {
+    address: u64,
+    size: usize,
 }
 
 impl BackendWorker {
@@ -200,11 +217,13 @@ impl BackendWorker {
         running: Arc<AtomicBool>,
     ) -> Self {
         let poll_rate_hz = config.collection.poll_rate_hz;
+        let endianness_override = config.probe.endianness;
         let backend_type = config.probe.backend_type;
-        let probe: Box<dyn DebugProbe> = match backend_type {
+        let mut probe: Box<dyn DebugProbe> = match backend_type {
             BackendType::ProbeRs => Box::new(ProbeBackend::from_app_config(&config)),
             BackendType::OpenOcd => Box::new(OpenOcdProbe::new(config.probe.clone())),
         };
+        probe.set_endianness(endianness_override.unwrap_or_default());
         tracing::info!("Loaded backend: {backend_type}");
 
         Self {
@@ -227,7 +246,24 @@ impl BackendWorker {
             dependent_read_planner: DependentReadPlanner::new(),
             pointer_runtime: HashMap::new(),
             data_router: DataRouter::new(),
+            elf_endianness: Endianness::Little,
+            endianness_override,
+            memory_watches: HashMap::new(),
+        }
+    }
+
+    /// Byte order in effect: the configured override, else the ELF's byte order
+    pub fn effective_endianness(&self) -> Endianness {
+        self.endianness_override.unwrap_or(self.elf_endianness)
+    }
+
+    /// Push the effective byte order to the active probe
+    fn apply_endianness(&mut self) {
+        let endianness = self.effective_endianness();
+        if self.probe.endianness() != endianness {
+            tracing::info!("Target byte order: {}", endianness);
         }
+        self.probe.set_endianness(endianness);
     }
 
     /// Run the main worker loop
@@ -339,6 +375,7 @@ impl BackendWorker {
                     self.is_mock_probe = false;
                     tracing::info!("Switched to real probe: {backend_type}");
                 }
+                self.apply_endianness();
             }
             BackendCommand::RefreshProbes => {
                 self.refresh_probes();
@@ -361,6 +398,17 @@ impl BackendWorker {
                 // Update data router with pane subscriptions
                 self.data_router.subscribe_pane(pane_id, var_ids);
             }
+            BackendCommand::SetTargetEndianness(endianness) => {
+                self.elf_endianness = endianness;
+                self.apply_endianness();
+            }
+            BackendCommand::AddMemoryWatch { id, address, size } => {
+                self.memory_watches
+                    .insert(id, MemoryWatch { address, size });
+            }
+            BackendCommand::RemoveMemoryWatch(id) => {
+                self.memory_watches.remove(&id);
+            }
         }
     }
 
@@ -391,10 +439,13 @@ impl BackendWorker {
         &mut self,
         selector: Option<String>,
         target: String,
-        _probe_config: crate::config::ProbeConfig,
+        probe_config: crate::config::ProbeConfig,
     ) {
         self.update_connection_status(ConnectionStatus::Connecting);
 
+        self.endianness_override = probe_config.endianness;
+        self.apply_endianness();
+
         // Connect using the trait method (works for both real and mock probes)
         match self.probe.connect(selector.as_deref(), &target) {
             Ok(()) => {
@@ -536,12 +587,40 @@ impl BackendWorker {
         self.pointer_runtime.clear();
     }
 
+    /// Read every memory watch with a single request each
+    fn poll_memory_watches(&mut self, timestamp: Duration) {
+        let watches: Vec<(u64, MemoryWatch)> = self
+            .memory_watches
+            .iter()
+            .map(|(&id, &w)| (id, w))
+            .collect();
+        for (id, watch) in watches {
+            match self.probe.read_memory(watch.address, watch.size) {
+                Ok(bytes) => {
+                    self.try_send_message(BackendMessage::MemorySnapshot {
+                        id,
+                        timestamp,
+                        bytes,
+                    });
+                }
+                Err(e) => {
+                    self.try_send_message(BackendMessage::MemorySnapshotError {
+                        id,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
     /// Poll all enabled variables using batched reads for better performance
     /// Supports two-stage pointer dereferencing: read pointers at lower rate,
     /// then read pointed-to data using cached addresses.
     fn poll_variables(&mut self) {
         let timestamp = self.start_time.elapsed();
 
+        self.poll_memory_watches(timestamp);
+
         // Collect enabled variables
         let enabled_vars: Vec<Variable> = self
             .variables
@@ -600,11 +679,34 @@ impl BackendWorker {
             .collect();
         data_vars = resolve_dependent_addresses(&resolved_vars, &self.pointer_runtime);
 
-        // Stage 2: Read data variables (with resolved addresses)
-        let read_results = self.probe.read_variables(&data_vars);
+        // Strings are read as whole buffers; their numeric value is the decoded length
+        let (text_vars, data_vars): (Vec<Variable>, Vec<Variable>) =
+            data_vars.into_iter().partition(|v| v.var_type.is_text());
+
+        // Stage 2: Read data variables (with resolved addresses), keeping integers exact
+        let read_results = self.probe.read_variables_raw(&data_vars);
 
         // Build probe results vector: (var_id, timestamp, raw_value)
         let mut probe_data = Vec::new();
+        let mut text_batch = Vec::new();
+        for var in &text_vars {
+            match self
+                .probe
+                .read_memory(var.address, var.var_type.size_bytes())
+            {
+                Ok(bytes) => {
+                    let text = VariableType::decode_text(&bytes);
+                    probe_data.push((var.id, timestamp, RawValue::from_u64(text.len() as u64)));
+                    text_batch.push((var.id, timestamp, text));
+                }
+                Err(e) => {
+                    self.try_send_message(BackendMessage::ReadError {
+                        variable_id: var.id,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
         for (var, read_result) in data_vars.iter().zip(read_results.into_iter()) {
             match read_result {
                 Ok(raw_value) => {
@@ -630,6 +732,9 @@ impl BackendWorker {
         if !batch.is_empty() {
             self.try_send_message(BackendMessage::DataBatch(batch));
         }
+        if !text_batch.is_empty() {
+            self.try_send_message(BackendMessage::TextBatch(text_batch));
+        }
 
         // Update stats from probe
         let probe_stats = self.probe.stats();
@@ -1030,4 +1135,52 @@ mod tests {
         // Drain any messages
         while msg_rx.try_recv().is_ok() {}
     }
+
+    #[test]
+    fn test_target_endianness_command() {
+        let (mut worker, msg_rx, _) = create_test_worker();
+        assert_eq!(worker.effective_endianness(), Endianness::Little);
+
+        worker.handle_command(BackendCommand::SetTargetEndianness(Endianness::Big));
+        assert_eq!(worker.effective_endianness(), Endianness::Big);
+        assert_eq!(worker.probe.endianness(), Endianness::Big);
+
+        // A configured override wins over the ELF byte order
+        worker.endianness_override = Some(Endianness::Little);
+        worker.handle_command(BackendCommand::SetTargetEndianness(Endianness::Big));
+        assert_eq!(worker.probe.endianness(), Endianness::Little);
+
+        // Drain any messages
+        while msg_rx.try_recv().is_ok() {}
+    }
+
+    #[test]
+    fn test_memory_watch_commands() {
+        let (mut worker, msg_rx, _) = create_test_worker();
+
+        worker.handle_command(BackendCommand::AddMemoryWatch {
+            id: 7,
+            address: 0x2000_0000,
+            size: 64,
+        });
+        assert_eq!(worker.memory_watches.get(&7).map(|w| w.size), Some(64));
+
+        // Re-adding the same ID replaces the watched block
+        worker.handle_command(BackendCommand::AddMemoryWatch {
+            id: 7,
+            address: 0x2000_0100,
+            size: 16,
+        });
+        assert_eq!(worker.memory_watches.len(), 1);
+        assert_eq!(
+            worker.memory_watches.get(&7).map(|w| w.address),
+            Some(0x2000_0100)
+        );
+
+        worker.handle_command(BackendCommand::RemoveMemoryWatch(7));
+        assert!(worker.memory_watches.is_empty());
+
+        // Drain any messages
+        while msg_rx.try_recv().is_ok() {}
+    }
 }
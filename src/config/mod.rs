@@ -43,7 +43,7 @@ pub use settings::*;
 pub use ui_session::{SerializedPane, SerializedWorkspaceLayout, UiSessionState, WindowState};
 
 use crate::error::{DataVisError, Result};
-use crate::types::{Variable, VariableType};
+use crate::types::{Endianness, RawValue, Variable, VariableType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -94,6 +94,16 @@ pub fn ensure_app_data_dir() -> Result<PathBuf> {
     Ok(dir)
 }
 
+/// Get the directory holding on-disk session history
+pub fn history_dir() -> Option<PathBuf> {
+    dirs_next::cache_dir().map(|p| p.join(APP_ID).join("history"))
+}
+
+/// Get the directory holding crash-recovery journals of recordings
+pub fn journal_dir() -> Option<PathBuf> {
+    app_data_dir().map(|p| p.join("journal"))
+}
+
 /// Get the path to the app state file
 pub fn app_state_path() -> Option<PathBuf> {
     app_data_dir().map(|p| p.join(APP_STATE_FILE))
@@ -515,6 +525,20 @@ pub enum PersistenceFormat {
     JsonLines,
     /// Binary format - compact, fast, but not human readable
     Binary,
+    /// MCAP format - one channel per variable, readable by Foxglove
+    Mcap,
+}
+
+impl PersistenceFormat {
+    /// File extension for this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PersistenceFormat::Csv => "csv",
+            PersistenceFormat::JsonLines => "jsonl",
+            PersistenceFormat::Binary => "bin",
+            PersistenceFormat::Mcap => "mcap",
+        }
+    }
 }
 
 impl std::fmt::Display for PersistenceFormat {
@@ -523,6 +547,7 @@ impl std::fmt::Display for PersistenceFormat {
             PersistenceFormat::Csv => write!(f, "CSV"),
             PersistenceFormat::JsonLines => write!(f, "JSON Lines"),
             PersistenceFormat::Binary => write!(f, "Binary"),
+            PersistenceFormat::Mcap => write!(f, "MCAP"),
         }
     }
 }
@@ -540,8 +565,8 @@ pub struct PersistedDataRecord {
     pub variable_address: Option<u64>,
     /// Variable ID
     pub variable_id: u32,
-    /// Raw value
-    pub raw_value: f64,
+    /// Raw value (exact for integer types)
+    pub raw_value: RawValue,
     /// Converted value
     pub converted_value: f64,
 }
@@ -700,6 +725,12 @@ pub struct ProbeConfig {
     /// When None, derived from target_chip via family prefix mapping
     #[serde(default)]
     pub openocd_target: Option<String>,
+
+    /// Target byte order override for decoding and encoding values.
+    /// When None, the byte order of the loaded ELF is used (little-endian
+    /// if no ELF is loaded).
+    #[serde(default)]
+    pub endianness: Option<Endianness>,
 }
 
 fn default_usb_timeout_ms() -> u64 {
@@ -731,6 +762,7 @@ impl Default for ProbeConfig {
             openocd_path: None,
             openocd_interface: None,
             openocd_target: None,
+            endianness: None,
         }
     }
 }
@@ -876,6 +908,10 @@ pub struct CollectionConfig {
 
     /// Buffer size for channel communication
     pub channel_buffer_size: usize,
+
+    /// Keep every sample of the session on disk, beyond `max_data_points`
+    #[serde(default)]
+    pub keep_history: bool,
 }
 
 impl Default for CollectionConfig {
@@ -888,6 +924,7 @@ impl Default for CollectionConfig {
             log_file_path: None,
             log_format: LogFormat::Csv,
             channel_buffer_size: 1024,
+            keep_history: false,
         }
     }
 }
@@ -161,6 +161,17 @@ impl RuntimeSettings {
         self.autoscale_x = true;
     }
 
+    /// Visible X-axis (time) range of the time-series plots
+    ///
+    /// Follows `current_time` when autoscaling, otherwise uses the manual
+    /// bounds if both are set.
+    pub fn visible_x_range(&self, current_time: f64) -> (f64, f64) {
+        match (self.autoscale_x, self.x_min, self.x_max) {
+            (false, Some(min), Some(max)) => (min, max),
+            _ => (current_time - self.display_time_window, current_time),
+        }
+    }
+
     /// Update time window from user zoom, respecting max limit
     pub fn update_time_window_from_zoom(&mut self, new_window: f64) {
         self.display_time_window = new_window.clamp(0.1, self.max_time_window);
@@ -258,14 +269,7 @@ impl TriggerSettings {
             return false;
         }
 
-        let should_trigger = match self.condition {
-            TriggerCondition::RisingEdge => previous < self.threshold && current >= self.threshold,
-            TriggerCondition::FallingEdge => previous > self.threshold && current <= self.threshold,
-            TriggerCondition::Above => current > self.threshold,
-            TriggerCondition::Below => current < self.threshold,
-            TriggerCondition::Equal => (current - self.threshold).abs() < f64::EPSILON,
-            TriggerCondition::Change => (current - previous).abs() > self.threshold,
-        };
+        let should_trigger = self.condition.is_met(self.threshold, previous, current);
 
         if should_trigger {
             self.triggered = true;
@@ -298,6 +302,32 @@ pub enum TriggerCondition {
     Change,
 }
 
+impl TriggerCondition {
+    /// Check whether a value transition satisfies the condition
+    pub fn is_met(&self, threshold: f64, previous: f64, current: f64) -> bool {
+        match self {
+            TriggerCondition::RisingEdge => previous < threshold && current >= threshold,
+            TriggerCondition::FallingEdge => previous > threshold && current <= threshold,
+            TriggerCondition::Above => current > threshold,
+            TriggerCondition::Below => current < threshold,
+            TriggerCondition::Equal => (current - threshold).abs() < f64::EPSILON,
+            TriggerCondition::Change => (current - previous).abs() > threshold,
+        }
+    }
+
+    /// Get all trigger conditions
+    pub fn all() -> &'static [TriggerCondition] {
+        &[
+            TriggerCondition::RisingEdge,
+            TriggerCondition::FallingEdge,
+            TriggerCondition::Above,
+            TriggerCondition::Below,
+            TriggerCondition::Equal,
+            TriggerCondition::Change,
+        ]
+    }
+}
+
 impl std::fmt::Display for TriggerCondition {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -485,6 +515,19 @@ mod tests {
         assert!(trigger.check_trigger(1.5, 0.5));
     }
 
+    #[test]
+    fn test_visible_x_range() {
+        let mut settings = RuntimeSettings::default();
+        assert_eq!(settings.visible_x_range(25.0), (15.0, 25.0));
+
+        settings.set_x_range(2.0, 4.0);
+        assert_eq!(settings.visible_x_range(25.0), (2.0, 4.0));
+
+        // Autoscale ignores stale manual bounds
+        settings.autoscale_x = true;
+        assert_eq!(settings.visible_x_range(25.0), (15.0, 25.0));
+    }
+
     #[test]
     fn test_y_range() {
         let mut settings = RuntimeSettings::default();
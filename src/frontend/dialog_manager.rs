@@ -2,7 +2,7 @@
 
 use super::dialogs::{
     CollectionSettingsState, ConnectionSettingsState, DuplicateConfirmState, ElfSymbolsState,
-    PersistenceSettingsState, PreferencesState, VariableChangeState,
+    PersistenceSettingsState, PreferencesState, RecoverRecordingsState, VariableChangeState,
 };
 
 /// Manages all dialog open/close state and per-dialog data.
@@ -14,6 +14,7 @@ pub struct DialogManager {
     pub collection_settings: (bool, CollectionSettingsState),
     pub persistence_settings: (bool, PersistenceSettingsState),
     pub preferences: (bool, PreferencesState),
+    pub recover_recordings: (bool, RecoverRecordingsState),
     pub connection_dialog: bool,
     pub help: bool,
 }
@@ -28,6 +29,7 @@ impl DialogManager {
             collection_settings: (false, CollectionSettingsState::default()),
             persistence_settings: (false, PersistenceSettingsState::default()),
             preferences: (false, PreferencesState::default()),
+            recover_recordings: (false, RecoverRecordingsState::default()),
             connection_dialog: false,
             help: false,
         }
@@ -41,6 +43,7 @@ impl DialogManager {
         self.collection_settings.0 = false;
         self.persistence_settings.0 = false;
         self.preferences.0 = false;
+        self.recover_recordings.0 = false;
         self.connection_dialog = false;
         self.help = false;
     }
@@ -66,6 +69,7 @@ mod tests {
         assert!(!dm.collection_settings.0);
         assert!(!dm.persistence_settings.0);
         assert!(!dm.preferences.0);
+        assert!(!dm.recover_recordings.0);
         assert!(!dm.connection_dialog);
         assert!(!dm.help);
     }
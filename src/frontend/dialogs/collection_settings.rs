@@ -1,7 +1,7 @@
 //! Collection settings dialog
 //!
 //! Extracted from settings.rs collection section.
-//! Covers poll rate, max data points, timeout.
+//! Covers poll rate, max data points, timeout and on-disk history.
 
 use egui::Ui;
 
@@ -14,6 +14,7 @@ pub struct CollectionSettingsState {
     pub poll_rate_hz: u32,
     pub max_data_points: usize,
     pub timeout_ms: u64,
+    pub keep_history: bool,
 }
 
 impl Default for CollectionSettingsState {
@@ -23,6 +24,7 @@ impl Default for CollectionSettingsState {
             poll_rate_hz: defaults.poll_rate_hz,
             max_data_points: defaults.max_data_points,
             timeout_ms: defaults.timeout_ms,
+            keep_history: defaults.keep_history,
         }
     }
 }
@@ -34,6 +36,7 @@ impl CollectionSettingsState {
             poll_rate_hz: config.poll_rate_hz,
             max_data_points: config.max_data_points,
             timeout_ms: config.timeout_ms,
+            keep_history: config.keep_history,
         }
     }
 }
@@ -101,6 +104,14 @@ impl Dialog for CollectionSettingsDialog {
                         .speed(1000),
                 );
                 ui.end_row();
+
+                ui.label("Full History:");
+                ui.checkbox(&mut state.keep_history, "Keep on disk")
+                    .on_hover_text(
+                        "Store every sample of the session on disk so the plot can scroll \
+                         back past the in-memory buffer and the recorder can save it later",
+                    );
+                ui.end_row();
             });
 
         ui.add_space(8.0);
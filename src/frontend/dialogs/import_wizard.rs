@@ -0,0 +1,496 @@
+//! Log import wizard
+//!
+//! Walks through importing a CSV or JSON Lines file as a session recording:
+//! how to split the file, which columns hold the time and the variables, and
+//! the name of the resulting recording.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use egui::Ui;
+
+use crate::config::settings::TimestampFormat;
+use crate::frontend::dialogs::{Dialog, DialogAction, DialogState, DialogWindowConfig};
+use crate::session::import::{
+    ImportFileFormat, ImportLayout, ImportSettings, ImportTable, ParseOptions,
+};
+use crate::session::SessionRecording;
+
+/// Number of rows shown in the file preview
+const PREVIEW_ROWS: usize = 8;
+
+/// Page of the import wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportStep {
+    /// File selection and parsing options
+    #[default]
+    File,
+    /// Timestamp and variable columns
+    Columns,
+    /// Recording name and summary
+    Review,
+}
+
+/// State for the import wizard
+#[derive(Debug, Clone, Default)]
+pub struct ImportWizardState {
+    pub step: ImportStep,
+    pub path: Option<PathBuf>,
+    /// Contents of the selected file
+    pub text: String,
+    pub options: ParseOptions,
+    pub table: ImportTable,
+    pub settings: ImportSettings,
+    /// Name of the imported recording
+    pub name: String,
+    pub error: Option<String>,
+}
+
+impl ImportWizardState {
+    /// Read a file and guess how to import it
+    pub fn open(&mut self, path: PathBuf) {
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                self.options = ParseOptions::detect(&path, &text);
+                self.name = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                self.text = text;
+                self.path = Some(path);
+                self.reparse();
+            }
+            Err(e) => self.error = Some(format!("Failed to read file: {}", e)),
+        }
+    }
+
+    /// Split the file again after the parse options changed
+    fn reparse(&mut self) {
+        match ImportTable::parse(&self.text, &self.options) {
+            Ok(table) => {
+                let decimal_separator = self.settings.decimal_separator;
+                self.settings = ImportSettings {
+                    decimal_separator,
+                    ..ImportSettings::detect(&table)
+                };
+                self.settings.mappings = self.settings.default_mappings(&table);
+                self.table = table;
+                self.error = None;
+            }
+            Err(e) => {
+                self.table = ImportTable::default();
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+}
+
+impl DialogState for ImportWizardState {
+    fn is_valid(&self) -> bool {
+        !self.table.rows.is_empty() && self.settings.mappings.iter().any(|m| m.enabled)
+    }
+}
+
+/// Actions produced by the import wizard
+#[derive(Debug, Clone)]
+pub enum ImportWizardAction {
+    /// Add the imported recording
+    Import(SessionRecording),
+}
+
+/// Context for rendering
+pub struct ImportWizardContext;
+
+/// The log import wizard dialog
+pub struct ImportWizardDialog;
+
+impl Dialog for ImportWizardDialog {
+    type State = ImportWizardState;
+    type Action = ImportWizardAction;
+    type Context<'a> = ImportWizardContext;
+
+    fn title(_state: &Self::State) -> &'static str {
+        "Import Log"
+    }
+
+    fn window_config() -> DialogWindowConfig {
+        DialogWindowConfig::resizable(620.0, 480.0)
+    }
+
+    fn render(
+        state: &mut Self::State,
+        _ctx: Self::Context<'_>,
+        ui: &mut Ui,
+    ) -> DialogAction<Self::Action> {
+        ui.horizontal(|ui| {
+            for (step, label) in [
+                (ImportStep::File, "1. File"),
+                (ImportStep::Columns, "2. Columns"),
+                (ImportStep::Review, "3. Import"),
+            ] {
+                ui.add_enabled(false, egui::Button::selectable(state.step == step, label));
+            }
+        });
+        ui.separator();
+
+        match state.step {
+            ImportStep::File => render_file_step(state, ui),
+            ImportStep::Columns => render_columns_step(state, ui),
+            ImportStep::Review => render_review_step(state, ui),
+        }
+
+        if let Some(error) = &state.error {
+            ui.colored_label(egui::Color32::from_rgb(220, 80, 80), error);
+        }
+
+        ui.add_space(8.0);
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui.button("Cancel").clicked() {
+                return DialogAction::Close;
+            }
+            if state.step != ImportStep::File && ui.button("< Back").clicked() {
+                state.step = match state.step {
+                    ImportStep::Review => ImportStep::Columns,
+                    _ => ImportStep::File,
+                };
+            }
+            match state.step {
+                ImportStep::File => {
+                    if ui
+                        .add_enabled(!state.table.rows.is_empty(), egui::Button::new("Next >"))
+                        .clicked()
+                    {
+                        state.step = ImportStep::Columns;
+                    }
+                }
+                ImportStep::Columns => {
+                    if ui
+                        .add_enabled(state.is_valid(), egui::Button::new("Next >"))
+                        .clicked()
+                    {
+                        state.step = ImportStep::Review;
+                    }
+                }
+                ImportStep::Review => {
+                    if ui
+                        .add_enabled(state.is_valid(), egui::Button::new("Import"))
+                        .clicked()
+                    {
+                        match state.settings.import(&state.table, &state.name) {
+                            Ok(recording) => {
+                                return DialogAction::CloseWithAction(ImportWizardAction::Import(
+                                    recording,
+                                ));
+                            }
+                            Err(e) => state.error = Some(format!("Import failed: {}", e)),
+                        }
+                    }
+                }
+            }
+            DialogAction::None
+        })
+        .inner
+    }
+}
+
+fn render_file_step(state: &mut ImportWizardState, ui: &mut Ui) {
+    ui.horizontal(|ui| {
+        ui.label("File:");
+        match &state.path {
+            Some(path) => ui.label(path.display().to_string()),
+            None => ui.label("(none)"),
+        };
+        if ui.button("Browse...").clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_title("Import Log")
+                .add_filter("Logs", &["csv", "tsv", "txt", "jsonl", "ndjson"])
+                .pick_file()
+            {
+                state.open(path);
+            }
+        }
+    });
+    if state.path.is_none() {
+        return;
+    }
+
+    let options = state.options.clone();
+    egui::Grid::new("import_parse_grid")
+        .num_columns(2)
+        .spacing([10.0, 6.0])
+        .show(ui, |ui| {
+            ui.label("Format:");
+            egui::ComboBox::from_id_salt("import_format")
+                .selected_text(state.options.format.display_name())
+                .show_ui(ui, |ui| {
+                    for format in [ImportFileFormat::Csv, ImportFileFormat::JsonLines] {
+                        ui.selectable_value(
+                            &mut state.options.format,
+                            format,
+                            format.display_name(),
+                        );
+                    }
+                });
+            ui.end_row();
+
+            if state.options.format == ImportFileFormat::Csv {
+                ui.label("Delimiter:");
+                ui.horizontal(|ui| {
+                    for (delimiter, label) in [
+                        (',', "Comma"),
+                        (';', "Semicolon"),
+                        ('\t', "Tab"),
+                        ('|', "Pipe"),
+                    ] {
+                        ui.selectable_value(&mut state.options.delimiter, delimiter, label);
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Header row:");
+                ui.checkbox(
+                    &mut state.options.has_header,
+                    "First row holds column names",
+                );
+                ui.end_row();
+            }
+
+            ui.label("Skip lines:");
+            ui.add(egui::DragValue::new(&mut state.options.skip_lines).range(0..=1000));
+            ui.end_row();
+
+            ui.label("Decimal separator:");
+            ui.horizontal(|ui| {
+                let mut decimal = state.settings.decimal_separator;
+                ui.selectable_value(&mut decimal, '.', "Point (1.5)");
+                ui.selectable_value(&mut decimal, ',', "Comma (1,5)");
+                if decimal != state.settings.decimal_separator {
+                    state.settings.decimal_separator = decimal;
+                    state.settings.mappings = state.settings.default_mappings(&state.table);
+                }
+            });
+            ui.end_row();
+        });
+    if state.options != options {
+        state.reparse();
+    }
+
+    ui.add_space(4.0);
+    ui.label(format!(
+        "{} rows, {} columns",
+        state.table.rows.len(),
+        state.table.width()
+    ));
+    render_preview(&state.table, ui);
+}
+
+fn render_preview(table: &ImportTable, ui: &mut Ui) {
+    egui::ScrollArea::both()
+        .id_salt("import_preview")
+        .max_height(180.0)
+        .show(ui, |ui| {
+            egui::Grid::new("import_preview_grid")
+                .striped(true)
+                .show(ui, |ui| {
+                    for column in &table.columns {
+                        ui.strong(column);
+                    }
+                    ui.end_row();
+                    for row in 0..table.rows.len().min(PREVIEW_ROWS) {
+                        for column in 0..table.width() {
+                            ui.label(table.cell(row, column).unwrap_or(""));
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// Combo box choosing a column, optionally allowing none
+fn column_combo(
+    ui: &mut Ui,
+    id: &str,
+    table: &ImportTable,
+    selected: &mut Option<usize>,
+    none_label: Option<&str>,
+) -> bool {
+    let text = selected
+        .and_then(|c| table.columns.get(c).cloned())
+        .or(none_label.map(str::to_string))
+        .unwrap_or_default();
+    let before = *selected;
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(text)
+        .show_ui(ui, |ui| {
+            if let Some(label) = none_label {
+                ui.selectable_value(selected, None, label);
+            }
+            for (index, column) in table.columns.iter().enumerate() {
+                ui.selectable_value(selected, Some(index), column);
+            }
+        });
+    *selected != before
+}
+
+fn render_columns_step(state: &mut ImportWizardState, ui: &mut Ui) {
+    let table = &state.table;
+    let settings = &mut state.settings;
+    let mut remap = false;
+
+    egui::Grid::new("import_time_grid")
+        .num_columns(2)
+        .spacing([10.0, 6.0])
+        .show(ui, |ui| {
+            ui.label("Time column:");
+            remap |= column_combo(
+                ui,
+                "import_time_column",
+                table,
+                &mut settings.timestamp_column,
+                Some("(none, fixed interval)"),
+            );
+            ui.end_row();
+
+            if settings.timestamp_column.is_some() {
+                ui.label("Time format:");
+                egui::ComboBox::from_id_salt("import_time_format")
+                    .selected_text(settings.timestamp_format.to_string())
+                    .show_ui(ui, |ui| {
+                        for format in [
+                            TimestampFormat::Seconds,
+                            TimestampFormat::Milliseconds,
+                            TimestampFormat::Microseconds,
+                            TimestampFormat::Iso8601,
+                            TimestampFormat::UnixTimestamp,
+                        ] {
+                            ui.selectable_value(
+                                &mut settings.timestamp_format,
+                                format,
+                                format.to_string(),
+                            );
+                        }
+                    });
+            } else {
+                ui.label("Row interval:");
+                let mut interval_ms = settings.row_interval.as_secs_f64() * 1e3;
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut interval_ms)
+                            .range(0.001..=60_000.0)
+                            .suffix(" ms"),
+                    )
+                    .changed()
+                {
+                    settings.row_interval = Duration::from_secs_f64(interval_ms / 1e3);
+                }
+            }
+            ui.end_row();
+
+            ui.label("Layout:");
+            ui.horizontal(|ui| {
+                let long = matches!(settings.layout, ImportLayout::Long { .. });
+                if ui.selectable_label(!long, "Column per variable").clicked() && long {
+                    settings.layout = ImportLayout::Wide;
+                    remap = true;
+                }
+                if ui
+                    .selectable_label(long, "Row per sample")
+                    .on_hover_text("One column names the variable, another holds its value")
+                    .clicked()
+                    && !long
+                {
+                    settings.layout = ImportLayout::Long {
+                        name_column: 0,
+                        value_column: table.width().saturating_sub(1),
+                        raw_column: None,
+                    };
+                    remap = true;
+                }
+            });
+            ui.end_row();
+
+            if let ImportLayout::Long {
+                name_column,
+                value_column,
+                raw_column,
+            } = &mut settings.layout
+            {
+                let mut name = Some(*name_column);
+                let mut value = Some(*value_column);
+                ui.label("Variable column:");
+                remap |= column_combo(ui, "import_name_column", table, &mut name, None);
+                ui.end_row();
+                ui.label("Value column:");
+                remap |= column_combo(ui, "import_value_column", table, &mut value, None);
+                ui.end_row();
+                ui.label("Raw value column:");
+                remap |= column_combo(
+                    ui,
+                    "import_raw_column",
+                    table,
+                    raw_column,
+                    Some("(same as value)"),
+                );
+                ui.end_row();
+                *name_column = name.unwrap_or(*name_column);
+                *value_column = value.unwrap_or(*value_column);
+            }
+        });
+    if remap {
+        settings.mappings = settings.default_mappings(table);
+    }
+
+    ui.add_space(4.0);
+    ui.label("Variables:");
+    egui::ScrollArea::vertical()
+        .id_salt("import_mappings")
+        .max_height(220.0)
+        .show(ui, |ui| {
+            egui::Grid::new("import_mapping_grid")
+                .num_columns(4)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Import");
+                    ui.strong("Name");
+                    ui.strong("Unit");
+                    ui.strong("Type");
+                    ui.end_row();
+                    for mapping in &mut settings.mappings {
+                        ui.checkbox(&mut mapping.enabled, &mapping.source);
+                        ui.add(egui::TextEdit::singleline(&mut mapping.name).desired_width(160.0));
+                        ui.add(egui::TextEdit::singleline(&mut mapping.unit).desired_width(60.0));
+                        ui.label(mapping.var_type.to_string());
+                        ui.end_row();
+                    }
+                });
+        });
+    if settings.mappings.is_empty() {
+        ui.label("No numeric columns found.");
+    }
+}
+
+fn render_review_step(state: &mut ImportWizardState, ui: &mut Ui) {
+    ui.horizontal(|ui| {
+        ui.label("Recording name:");
+        ui.text_edit_singleline(&mut state.name);
+    });
+    let enabled = state.settings.mappings.iter().filter(|m| m.enabled).count();
+    ui.label(format!(
+        "{} rows, {} variables",
+        state.table.rows.len(),
+        enabled
+    ));
+    if let Some(column) = state.settings.timestamp_column {
+        ui.label(format!(
+            "Time from \"{}\" ({})",
+            state.table.columns[column], state.settings.timestamp_format
+        ));
+    } else {
+        ui.label(format!(
+            "Rows {:.3} ms apart",
+            state.settings.row_interval.as_secs_f64() * 1e3
+        ));
+    }
+    ui.label("The recording is added to the saved recordings and loaded for playback.");
+}
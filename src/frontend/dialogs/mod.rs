@@ -313,8 +313,10 @@ pub mod converter_editor;
 pub mod duplicate_confirm;
 pub mod elf_symbols;
 pub mod export_config;
+pub mod import_wizard;
 pub mod persistence_settings;
 pub mod preferences;
+pub mod recover_recordings;
 pub mod trigger_config;
 pub mod value_editor;
 pub mod variable_change;
@@ -339,11 +341,18 @@ pub use export_config::{
     DownsampleMode, ExportConfigAction, ExportConfigContext, ExportConfigDialog, ExportConfigState,
     ExportFormat,
 };
+pub use import_wizard::{
+    ImportWizardAction, ImportWizardContext, ImportWizardDialog, ImportWizardState,
+};
 pub use persistence_settings::{
     PersistenceSettingsAction, PersistenceSettingsContext, PersistenceSettingsDialog,
     PersistenceSettingsState,
 };
 pub use preferences::{PreferencesAction, PreferencesContext, PreferencesDialog, PreferencesState};
+pub use recover_recordings::{
+    RecoverRecordingsAction, RecoverRecordingsContext, RecoverRecordingsDialog,
+    RecoverRecordingsState,
+};
 pub use trigger_config::{
     TriggerConfigAction, TriggerConfigContext, TriggerConfigDialog, TriggerConfigState,
 };
@@ -120,7 +120,8 @@ impl Dialog for PersistenceSettingsDialog {
                                 .add_filter("CSV", &["csv"])
                                 .add_filter("JSON Lines", &["jsonl"])
                                 .add_filter("Binary", &["bin"])
-                                .set_file_name("data.csv")
+                                .add_filter("MCAP", &["mcap"])
+                                .set_file_name(format!("data.{}", state.format.extension()))
                                 .save_file()
                             {
                                 state.file_path = Some(path);
@@ -144,6 +145,11 @@ impl Dialog for PersistenceSettingsDialog {
                                 PersistenceFormat::Binary,
                                 "Binary",
                             );
+                            ui.selectable_value(
+                                &mut state.format,
+                                PersistenceFormat::Mcap,
+                                "MCAP",
+                            );
                         });
                     ui.end_row();
 
@@ -0,0 +1,156 @@
+//! Recover recordings dialog
+//!
+//! This dialog is shown at launch when journals of recordings that were in
+//! progress when the app last exited are found on disk.
+
+use std::path::PathBuf;
+
+use super::{Dialog, DialogAction, DialogState, DialogWindowConfig};
+use crate::session::SessionMetadata;
+use egui::{Align2, Ui};
+
+/// A journal left over from a crash
+#[derive(Debug)]
+pub struct OrphanedJournal {
+    /// Path of the journal file
+    pub path: PathBuf,
+    /// Metadata read from the journal (None if unreadable)
+    pub metadata: Option<SessionMetadata>,
+    /// Whether the journal is selected
+    pub selected: bool,
+}
+
+/// State for the recover recordings dialog
+#[derive(Debug, Default)]
+pub struct RecoverRecordingsState {
+    /// Journals found at launch
+    pub journals: Vec<OrphanedJournal>,
+}
+
+impl DialogState for RecoverRecordingsState {
+    fn is_valid(&self) -> bool {
+        self.journals.iter().any(|j| j.selected)
+    }
+}
+
+impl RecoverRecordingsState {
+    /// Create a new state listing the given journals, all selected
+    pub fn with_journals(paths: Vec<PathBuf>) -> Self {
+        let journals = paths
+            .into_iter()
+            .map(|path| OrphanedJournal {
+                metadata: crate::session::journal::journal_metadata(&path).ok(),
+                path,
+                selected: true,
+            })
+            .collect();
+        Self { journals }
+    }
+
+    /// Remove the selected journals from the list and return their paths
+    fn take_selected(&mut self) -> Vec<PathBuf> {
+        let (selected, rest) = std::mem::take(&mut self.journals)
+            .into_iter()
+            .partition(|j| j.selected);
+        self.journals = rest;
+        selected.into_iter().map(|j| j.path).collect()
+    }
+}
+
+/// Action from the recover recordings dialog
+#[derive(Debug, Clone)]
+pub enum RecoverRecordingsAction {
+    /// Recover the journals as recordings and remove them
+    Recover(Vec<PathBuf>),
+    /// Delete the journals
+    Discard(Vec<PathBuf>),
+}
+
+/// Context for rendering (none needed for this dialog)
+pub struct RecoverRecordingsContext;
+
+/// The recover recordings dialog
+pub struct RecoverRecordingsDialog;
+
+impl Dialog for RecoverRecordingsDialog {
+    type State = RecoverRecordingsState;
+    type Action = RecoverRecordingsAction;
+    type Context<'a> = RecoverRecordingsContext;
+
+    fn title(_state: &Self::State) -> &'static str {
+        "Recover Recordings"
+    }
+
+    fn window_config() -> DialogWindowConfig {
+        DialogWindowConfig {
+            default_width: 420.0,
+            default_height: None,
+            resizable: false,
+            collapsible: false,
+            anchor: Some((Align2::CENTER_CENTER, [0.0, 0.0])),
+            modal: true,
+        }
+    }
+
+    fn render(
+        state: &mut Self::State,
+        _ctx: Self::Context<'_>,
+        ui: &mut Ui,
+    ) -> DialogAction<Self::Action> {
+        if state.journals.is_empty() {
+            return DialogAction::Close;
+        }
+
+        ui.label("These recordings were still in progress when the app last exited.");
+        ui.label("Recovered recordings appear in the Recorder pane.");
+
+        ui.separator();
+
+        for journal in &mut state.journals {
+            let label = match &journal.metadata {
+                Some(meta) => format!(
+                    "{} - {} - {:.1}s, {} frames",
+                    meta.name,
+                    meta.recorded_at.format("%Y-%m-%d %H:%M:%S"),
+                    meta.duration.as_secs_f64(),
+                    meta.total_data_points
+                ),
+                None => format!("{} (unreadable)", journal.path.display()),
+            };
+            ui.checkbox(&mut journal.selected, label);
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            let valid = state.is_valid();
+            let mut action = None;
+            if ui
+                .add_enabled(valid, egui::Button::new("Recover"))
+                .clicked()
+            {
+                action = Some(RecoverRecordingsAction::Recover(state.take_selected()));
+            }
+            if ui
+                .add_enabled(valid, egui::Button::new("Discard"))
+                .on_hover_text("Delete the selected journals")
+                .clicked()
+            {
+                action = Some(RecoverRecordingsAction::Discard(state.take_selected()));
+            }
+            if ui
+                .button("Later")
+                .on_hover_text("Keep the journals and ask again at next launch")
+                .clicked()
+            {
+                return DialogAction::Close;
+            }
+            match action {
+                Some(action) if state.journals.is_empty() => DialogAction::CloseWithAction(action),
+                Some(action) => DialogAction::Action(action),
+                None => DialogAction::None,
+            }
+        })
+        .inner
+    }
+}
@@ -3,7 +3,7 @@
 //! This dialog allows users to write new values to writable variables.
 
 use super::{Dialog, DialogAction, DialogState, DialogWindowConfig};
-use crate::types::{ConnectionStatus, VariableType};
+use crate::types::{ConnectionStatus, RawValue, VariableType};
 use egui::{Color32, Ui};
 
 /// State for the value editor dialog
@@ -58,7 +58,7 @@ pub struct ValueEditorContext<'a> {
     /// Current connection status
     pub connection_status: ConnectionStatus,
     /// Current raw value (if available)
-    pub current_value: Option<f64>,
+    pub current_value: Option<RawValue>,
 }
 
 /// The value editor dialog
@@ -114,6 +114,9 @@ impl Dialog for ValueEditorDialog {
                 ui.horizontal(|ui| {
                     ui.label("Current:");
                     ui.label(format!("{:.6}", value));
+                    if let Some(hex) = value.format_hex(ctx.var_type.size_bytes()) {
+                        ui.label(egui::RichText::new(hex).monospace().weak());
+                    }
                 });
             }
 
@@ -1,9 +1,11 @@
 //! Variable detail dialog for viewing and editing variable properties
 //!
 //! This dialog shows detailed information about a variable and allows editing
-//! the name, unit, and color.
+//! the name, unit, color, and how its bytes are interpreted (e.g. reading a
+//! 16-bit integer as Q15 fixed-point or f16).
 
 use super::{Dialog, DialogAction, DialogState, DialogWindowConfig};
+use crate::types::VariableType;
 use egui::{Align2, Color32, Ui};
 
 /// State for the variable detail dialog
@@ -17,6 +19,10 @@ pub struct VariableDetailState {
     pub unit: String,
     /// Editable color
     pub color: [u8; 4],
+    /// Editable type interpretation (must keep the original storage size)
+    pub var_type: VariableType,
+    /// Type the variable had when the dialog was opened
+    pub original_type: VariableType,
 }
 
 impl DialogState for VariableDetailState {
@@ -25,6 +31,8 @@ impl DialogState for VariableDetailState {
         self.name.clear();
         self.unit.clear();
         self.color = [0, 0, 0, 255];
+        self.var_type = VariableType::default();
+        self.original_type = VariableType::default();
     }
 
     fn is_valid(&self) -> bool {
@@ -34,13 +42,76 @@ impl DialogState for VariableDetailState {
 
 impl VariableDetailState {
     /// Create a new state for viewing a variable
-    pub fn for_variable(var_id: u32, name: &str, unit: &str, color: [u8; 4]) -> Self {
+    pub fn for_variable(
+        var_id: u32,
+        name: &str,
+        unit: &str,
+        color: [u8; 4],
+        var_type: VariableType,
+    ) -> Self {
         Self {
             var_id: Some(var_id),
             name: name.to_string(),
             unit: unit.to_string(),
             color,
+            var_type,
+            original_type: var_type,
+        }
+    }
+
+    /// Render the "interpret as" selector for the variable type
+    ///
+    /// Offers every primitive with the same storage size, plus a fixed-point
+    /// option whose bit layout is editable but limited to the storage size.
+    fn type_selector(&mut self, ui: &mut Ui) {
+        if let VariableType::Raw(size) | VariableType::Text(size) = self.original_type {
+            // Byte buffers can only be shown as bytes or as a string
+            egui::ComboBox::from_id_salt("variable_detail_type")
+                .selected_text(self.var_type.to_string())
+                .show_ui(ui, |ui| {
+                    for ty in [VariableType::Raw(size), VariableType::Text(size)] {
+                        ui.selectable_value(&mut self.var_type, ty, ty.to_string());
+                    }
+                });
+            return;
         }
+
+        let storage_bits = (self.original_type.size_bytes() * 8) as u8;
+        ui.vertical(|ui| {
+            egui::ComboBox::from_id_salt("variable_detail_type")
+                .selected_text(self.var_type.to_string())
+                .show_ui(ui, |ui| {
+                    for ty in self.original_type.same_size_types() {
+                        ui.selectable_value(&mut self.var_type, ty, ty.to_string());
+                    }
+                    let is_fixed = matches!(self.var_type, VariableType::Fixed { .. });
+                    if ui.selectable_label(is_fixed, "Fixed point (Q)").clicked() && !is_fixed {
+                        self.var_type = VariableType::Fixed {
+                            signed: true,
+                            int_bits: 0,
+                            frac_bits: storage_bits - 1,
+                        };
+                    }
+                });
+
+            if let VariableType::Fixed {
+                signed,
+                int_bits,
+                frac_bits,
+            } = &mut self.var_type
+            {
+                ui.horizontal(|ui| {
+                    ui.checkbox(signed, "Signed");
+                    let available = storage_bits - *signed as u8;
+                    *int_bits = (*int_bits).min(available);
+                    ui.label("Int:");
+                    ui.add(egui::DragValue::new(int_bits).range(0..=available));
+                    *frac_bits = (*frac_bits).min(available - *int_bits);
+                    ui.label("Frac:");
+                    ui.add(egui::DragValue::new(frac_bits).range(0..=available - *int_bits));
+                });
+            }
+        });
     }
 }
 
@@ -53,6 +124,7 @@ pub enum VariableDetailAction {
         name: String,
         unit: String,
         color: [u8; 4],
+        var_type: VariableType,
     },
 }
 
@@ -60,8 +132,6 @@ pub enum VariableDetailAction {
 pub struct VariableDetailContext {
     /// Variable address (static or dynamic)
     pub address: crate::types::VariableAddress,
-    /// Variable type as string
-    pub var_type: String,
     /// Whether sampling is enabled
     pub enabled: bool,
     /// Whether shown in graph
@@ -117,9 +187,9 @@ impl Dialog for VariableDetailDialog {
                 ui.label(egui::RichText::new(ctx.address.to_string()).monospace());
                 ui.end_row();
 
-                // Type (read-only)
+                // Type interpretation (editable for primitive types)
                 ui.label("Type:");
-                ui.label(&ctx.var_type);
+                state.type_selector(ui);
                 ui.end_row();
 
                 // Unit (editable)
@@ -234,6 +304,7 @@ impl Dialog for VariableDetailDialog {
                     name: state.name.clone(),
                     unit: state.unit.clone(),
                     color: state.color,
+                    var_type: state.var_type,
                 });
             }
             if ui.button("Cancel").clicked() {
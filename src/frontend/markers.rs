@@ -14,23 +14,9 @@ use egui::Color32;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-/// Marker type for categorization and color coding
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
-pub enum MarkerType {
-    /// Generic event marker (blue)
-    #[default]
-    Event,
-    /// Error or issue marker (red)
-    Error,
-    /// Note/annotation marker (yellow)
-    Note,
-    /// Start of a region marker (green)
-    RegionStart,
-    /// End of a region marker (green)
-    RegionEnd,
-    /// Custom marker (user-defined color)
-    Custom,
-}
+use crate::session::events::RecordingEvent;
+
+pub use crate::session::events::MarkerType;
 
 impl MarkerType {
     /// Get the default color for this marker type
@@ -44,30 +30,6 @@ impl MarkerType {
             MarkerType::Custom => Color32::from_rgb(200, 200, 200), // Gray
         }
     }
-
-    /// Get the display name for this marker type
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            MarkerType::Event => "Event",
-            MarkerType::Error => "Error",
-            MarkerType::Note => "Note",
-            MarkerType::RegionStart => "Region Start",
-            MarkerType::RegionEnd => "Region End",
-            MarkerType::Custom => "Custom",
-        }
-    }
-
-    /// Get all marker types
-    pub fn all() -> &'static [MarkerType] {
-        &[
-            MarkerType::Event,
-            MarkerType::Error,
-            MarkerType::Note,
-            MarkerType::RegionStart,
-            MarkerType::RegionEnd,
-            MarkerType::Custom,
-        ]
-    }
 }
 
 /// A marker/bookmark at a specific time point
@@ -129,6 +91,41 @@ impl Marker {
         self.description = Some(description.into());
         self
     }
+
+    /// Create a marker from a recording event
+    pub fn from_event(id: u32, event: &RecordingEvent) -> Self {
+        Self {
+            id,
+            name: event.name.clone(),
+            time: event.time,
+            marker_type: event.kind,
+            description: event.note.clone(),
+            custom_color: event.color,
+            visible: true,
+        }
+    }
+
+    /// Convert to a recording event
+    pub fn to_event(&self) -> RecordingEvent {
+        RecordingEvent {
+            time: self.time,
+            kind: self.marker_type,
+            name: self.name.clone(),
+            note: self.description.clone(),
+            color: self.custom_color,
+        }
+    }
+}
+
+/// A time span bounded by a region start and a region end marker
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkedRegion {
+    /// Name of the region start marker
+    pub name: String,
+    /// Start of the region (seconds)
+    pub start: f64,
+    /// End of the region (seconds)
+    pub end: f64,
 }
 
 /// Manager for markers
@@ -226,6 +223,42 @@ impl MarkerManager {
         self.markers.iter().rev().find(|m| m.time < time)
     }
 
+    /// Pair each region start marker with the next region end marker
+    ///
+    /// Unmatched starts and ends are ignored; hidden markers are skipped.
+    pub fn regions(&self) -> Vec<MarkedRegion> {
+        let mut regions = Vec::new();
+        let mut open: Option<&Marker> = None;
+        for marker in self.visible() {
+            match marker.marker_type {
+                MarkerType::RegionStart => open = Some(marker),
+                MarkerType::RegionEnd => {
+                    if let Some(start) = open.take() {
+                        regions.push(MarkedRegion {
+                            name: start.name.clone(),
+                            start: start.time_secs(),
+                            end: marker.time_secs(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        regions
+    }
+
+    /// Markers as recording events, sorted by time
+    pub fn to_events(&self) -> Vec<RecordingEvent> {
+        self.markers.iter().map(Marker::to_event).collect()
+    }
+
+    /// Add a marker for each recording event
+    pub fn add_events(&mut self, events: &[RecordingEvent]) {
+        for event in events {
+            self.add_marker(Marker::from_event(0, event));
+        }
+    }
+
     /// Get count of markers
     pub fn len(&self) -> usize {
         self.markers.len()
@@ -332,6 +365,42 @@ mod tests {
         assert_eq!(nearest.unwrap().name, "B");
     }
 
+    #[test]
+    fn test_marker_manager_regions() {
+        let mut manager = MarkerManager::new();
+        manager.add("Orphan end", Duration::from_secs(1), MarkerType::RegionEnd);
+        manager.add("Step 1", Duration::from_secs(2), MarkerType::RegionStart);
+        manager.add("Note", Duration::from_secs(3), MarkerType::Note);
+        manager.add("End 1", Duration::from_secs(4), MarkerType::RegionEnd);
+        manager.add("Step 2", Duration::from_secs(6), MarkerType::RegionStart);
+
+        let regions = manager.regions();
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].name, "Step 1");
+        assert_eq!((regions[0].start, regions[0].end), (2.0, 4.0));
+
+        manager.add("End 2", Duration::from_secs(8), MarkerType::RegionEnd);
+        assert_eq!(manager.regions().len(), 2);
+    }
+
+    #[test]
+    fn test_marker_manager_events_round_trip() {
+        let mut manager = MarkerManager::new();
+        manager.add("Start", Duration::from_secs(2), MarkerType::RegionStart);
+        let id = manager.add("Fault", Duration::from_secs(1), MarkerType::Error);
+        manager.get_mut(id).unwrap().description = Some("overcurrent".to_string());
+
+        let events = manager.to_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].name, "Fault");
+        assert_eq!(events[0].note.as_deref(), Some("overcurrent"));
+
+        let mut restored = MarkerManager::new();
+        restored.add_events(&events);
+        assert_eq!(restored.to_events(), events);
+        assert_eq!(restored.regions().len(), 0);
+    }
+
     #[test]
     fn test_marker_type_colors() {
         // Just verify colors are defined
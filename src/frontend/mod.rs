@@ -35,6 +35,7 @@ pub mod pane_trait;
 mod panels;
 pub mod panes;
 mod plot;
+pub mod recording;
 pub mod script_editor;
 pub mod state;
 pub mod status_bar;
@@ -123,6 +124,12 @@ pub struct DataVisApp {
     collection_start: Option<Instant>,
     last_error: Option<String>,
     persistence_config: crate::config::DataPersistenceConfig,
+    /// On-disk history of the current session (when full history is enabled)
+    history_store: Option<crate::session::HistoryStore>,
+    /// Session recording started from the Recorder pane
+    live_recorder: recording::LiveRecorder,
+    /// Writes live data to the Data Persistence file
+    live_persistence: recording::LivePersistence,
 
     // === All published data (variables, stats, status, snapshots) ===
     topics: Topics,
@@ -392,6 +399,19 @@ impl DataVisApp {
         let show_status_bar = ui_session.show_status_bar;
         let selected_probe_index = ui_session.selected_probe_index;
 
+        // Offer to recover recordings interrupted by a crash
+        let mut dialogs = dialog_manager::DialogManager::new();
+        let journal_dir = crate::config::journal_dir();
+        let journals = journal_dir
+            .as_deref()
+            .map(crate::session::journal::orphaned_journals)
+            .unwrap_or_default();
+        if !journals.is_empty() {
+            tracing::info!("Found {} orphaned recording journal(s)", journals.len());
+            dialogs.recover_recordings.1 = dialogs::RecoverRecordingsState::with_journals(journals);
+            dialogs.recover_recordings.0 = true;
+        }
+
         Self {
             frontend,
             config,
@@ -402,6 +422,9 @@ impl DataVisApp {
             collection_start: None,
             last_error: None,
             persistence_config: crate::config::DataPersistenceConfig::default(),
+            history_store: None,
+            live_recorder: recording::LiveRecorder::with_journal_dir(journal_dir),
+            live_persistence: recording::LivePersistence::new(),
             topics,
             elf_file_path,
             elf_info,
@@ -414,7 +437,7 @@ impl DataVisApp {
             show_status_bar,
             selected_probe_index,
             target_chip_input,
-            dialogs: dialog_manager::DialogManager::new(),
+            dialogs,
             ui_session,
         }
     }
@@ -438,14 +461,15 @@ impl DataVisApp {
                 SinkMessage::DataBatch(batch) => {
                     // Skip adding data when paused - this effectively freezes the graph
                     if !self.settings.paused {
+                        let live = self.topics.live_data_mut();
                         for (var_id, timestamp, raw_value, converted_value) in batch {
                             let variable_id = var_id.0;
-                            if let Some(data) = self.topics.variable_data.get_mut(&variable_id) {
-                                data.push(DataPoint::with_conversion(
-                                    timestamp,
-                                    raw_value,
-                                    converted_value,
-                                ));
+                            if let Some(data) = live.get_mut(&variable_id) {
+                                let point =
+                                    DataPoint::with_conversion(timestamp, raw_value, converted_value);
+                                self.live_recorder.record(variable_id, &point);
+                                self.live_persistence.record(variable_id, &point);
+                                data.push(point);
                             }
                         }
                         // Record timestamp for global data freshness
@@ -491,16 +515,18 @@ impl DataVisApp {
                             }
                             None => {
                                 // Broadcast to all panes - add to global variable_data
+                                let live = self.topics.live_data_mut();
                                 for (var_id, timestamp, raw_value, converted_value) in data {
                                     let variable_id = var_id.0;
-                                    if let Some(data) =
-                                        self.topics.variable_data.get_mut(&variable_id)
-                                    {
-                                        data.push(DataPoint::with_conversion(
+                                    if let Some(data) = live.get_mut(&variable_id) {
+                                        let point = DataPoint::with_conversion(
                                             timestamp,
                                             raw_value,
                                             converted_value,
-                                        ));
+                                        );
+                                        self.live_recorder.record(variable_id, &point);
+                                        self.live_persistence.record(variable_id, &point);
+                                        data.push(point);
                                     }
                                 }
                                 // Record timestamp for global data freshness
@@ -509,8 +535,18 @@ impl DataVisApp {
                         }
                     }
                 }
+                SinkMessage::TextBatch(batch) => {
+                    if !self.settings.paused {
+                        let live = self.topics.live_data_mut();
+                        for (var_id, timestamp, text) in batch {
+                            if let Some(data) = live.get_mut(&var_id.0) {
+                                data.push_text(timestamp, text);
+                            }
+                        }
+                    }
+                }
                 SinkMessage::ReadError { variable_id, error } => {
-                    if let Some(data) = self.topics.variable_data.get_mut(&variable_id) {
+                    if let Some(data) = self.topics.live_data_mut().get_mut(&variable_id) {
                         data.record_error(error);
                     }
                 }
@@ -521,10 +557,11 @@ impl DataVisApp {
                 SinkMessage::VariableList(vars) => {
                     for var in vars {
                         self.topics
-                            .variable_data
+                            .live_data_mut()
                             .entry(var.id)
                             .or_insert_with(|| VariableData::new(var));
                     }
+                    self.attach_history();
                 }
                 SinkMessage::ProbeList(probes) => {
                     tracing::info!("Received {} probes", probes.len());
@@ -574,14 +611,84 @@ impl DataVisApp {
                 SinkMessage::PointerStates(states) => {
                     self.topics.pointer_states = states;
                 }
+                SinkMessage::MemorySnapshot {
+                    id,
+                    timestamp,
+                    bytes,
+                } => {
+                    let snapshot = self.topics.memory_snapshots.entry(id).or_default();
+                    snapshot.timestamp = timestamp;
+                    snapshot.bytes = bytes;
+                    snapshot.error = None;
+                }
+                SinkMessage::MemorySnapshotError { id, error } => {
+                    self.topics.memory_snapshots.entry(id).or_default().error = Some(error);
+                }
             }
         }
 
         had_messages
     }
 
-    /// Compute current display time (frozen when not collecting)
+    /// Open the Data Persistence file for a collection run, if enabled
+    fn start_persistence(&mut self) {
+        let variables: Vec<_> = self.config.variables.values().cloned().collect();
+        if let Err(e) = self
+            .live_persistence
+            .start(&self.persistence_config, &variables)
+        {
+            self.last_error = Some(format!("Failed to open persistence file: {}", e));
+        }
+    }
+
+    /// Start a new on-disk history for every variable, or drop it when disabled
+    fn reset_history(&mut self) {
+        for data in self.topics.variable_data.values_mut() {
+            data.history = None;
+        }
+        self.history_store = None;
+        if !self.config.collection.keep_history {
+            return;
+        }
+        let Some(dir) = crate::config::history_dir() else {
+            self.last_error = Some("Could not determine history directory".to_string());
+            return;
+        };
+        match crate::session::HistoryStore::create_in(&dir) {
+            Ok(store) => {
+                self.history_store = Some(store);
+                self.attach_history();
+            }
+            Err(e) => {
+                self.last_error = Some(format!("Failed to create session history: {}", e));
+            }
+        }
+    }
+
+    /// Give variables without one a history in the current store
+    fn attach_history(&mut self) {
+        let Some(store) = &self.history_store else {
+            return;
+        };
+        for (id, data) in self.topics.live_data_mut() {
+            if data.history.is_some() {
+                continue;
+            }
+            match store.variable(*id) {
+                Ok(history) => data.history = Some(history),
+                Err(e) => {
+                    tracing::warn!("Failed to start history of '{}': {}", data.variable.name, e)
+                }
+            }
+        }
+    }
+
+    /// Compute current display time (frozen when not collecting, the
+    /// playback position while a recording drives the panes)
     fn display_time(&self) -> Duration {
+        if let Some(playback) = &self.topics.playback {
+            return playback.time;
+        }
         self.accumulated_time
             + self
                 .collection_start
@@ -605,16 +712,22 @@ impl DataVisApp {
                 self.frontend.send_command(PipelineCommand::Disconnect);
             }
             AppAction::StartCollection => {
+                // Live data takes the panes back from a playback
+                self.topics.end_playback();
                 // Clear data on start to avoid timestamp discontinuity
                 // (backend resets timestamps to 0 on each start)
                 for data in self.topics.variable_data.values_mut() {
                     data.clear();
                 }
+                self.reset_history();
+                // The backend restarts timestamps; a recording carries on
+                self.live_recorder.restart_clock();
                 // Reset accumulated time since we're clearing the data
                 self.accumulated_time = Duration::ZERO;
                 self.settings.collecting = true;
                 self.settings.paused = false; // Ensure not paused when starting
                 self.collection_start = Some(Instant::now());
+                self.start_persistence();
                 self.frontend.send_command(PipelineCommand::Start);
             }
             AppAction::StopCollection => {
@@ -624,6 +737,8 @@ impl DataVisApp {
                     self.accumulated_time += start.elapsed();
                 }
                 self.frontend.send_command(PipelineCommand::Stop);
+                self.live_recorder.checkpoint();
+                self.live_persistence.stop();
             }
             AppAction::RefreshProbes => {
                 tracing::debug!("Refreshing probe list...");
@@ -722,6 +837,36 @@ impl DataVisApp {
                     data.clear();
                 }
             }
+            AppAction::StartMemoryWatch { id, address, size } => {
+                self.topics.memory_snapshots.remove(&id);
+                self.frontend
+                    .send_command(PipelineCommand::AddMemoryWatch { id, address, size });
+            }
+            AppAction::StopMemoryWatch(id) => {
+                self.topics.memory_snapshots.remove(&id);
+                self.frontend
+                    .send_command(PipelineCommand::RemoveMemoryWatch(id));
+            }
+            AppAction::StartRecording(request) => {
+                let variables: Vec<_> = self.config.variables.values().cloned().collect();
+                let now = self.accumulated_time
+                    + self
+                        .collection_start
+                        .map(|s| s.elapsed())
+                        .unwrap_or(Duration::ZERO);
+                if let Err(e) = self
+                    .live_recorder
+                    .start(request, variables, now, &mut self.topics)
+                {
+                    self.last_error = Some(format!("Failed to start recording: {}", e));
+                }
+            }
+            AppAction::StopRecording => {
+                self.live_recorder.stop(&mut self.topics);
+            }
+            AppAction::CancelRecording => {
+                self.live_recorder.cancel(&mut self.topics);
+            }
             AppAction::OpenPane(kind) => {
                 if self.workspace.is_singleton(kind) {
                     if let Some(id) = self.workspace.find_singleton(kind) {
@@ -775,6 +920,20 @@ impl DataVisApp {
             }
             AppAction::ClosePane(id) => {
                 // Pipeline node linkage removed - just close the pane
+                if self.workspace.pane_entries.get(&id).map(|e| e.kind)
+                    == Some(PaneKind::StructInspector)
+                {
+                    self.handle_action(AppAction::StopMemoryWatch(id.0));
+                }
+                self.topics.marker_regions.remove(&id.0);
+                self.topics.plot_markers.remove(&id.0);
+                self.topics.cursor_ranges.remove(&id.0);
+                self.topics.step_annotations.remove(&id.0);
+                self.topics.filter_overlays.remove(&id.0);
+                self.topics.recording_overlays.remove(&id.0);
+                if self.topics.playback.as_ref().is_some_and(|p| p.pane == id.0) {
+                    self.topics.end_playback();
+                }
                 self.workspace.remove_pane(id);
             }
             AppAction::NewProject => {
@@ -786,12 +945,31 @@ impl DataVisApp {
                 self.elf_file_path = None;
                 self.elf_info = None;
                 self.elf_symbols.clear();
+                self.topics.end_playback();
                 self.topics.variable_data.clear();
                 self.topics.stats = CollectionStats::default();
                 self.last_error = None;
                 self.persistence_config = crate::config::DataPersistenceConfig::default();
             }
             AppAction::ResetLayout => {
+                let watches: Vec<u64> = self
+                    .workspace
+                    .pane_entries
+                    .iter()
+                    .filter(|(_, e)| e.kind == PaneKind::StructInspector)
+                    .map(|(id, _)| id.0)
+                    .collect();
+                for id in watches {
+                    self.handle_action(AppAction::StopMemoryWatch(id));
+                }
+                self.topics.marker_regions.clear();
+                self.topics.plot_markers.clear();
+                self.topics.cursor_ranges.clear();
+                self.topics.step_annotations.clear();
+                self.topics.filter_overlays.clear();
+                self.topics.recording_overlays.clear();
+                self.topics.end_playback();
+
                 // Rebuild workspace with default layout
                 let mut workspace = Workspace::new();
                 let dock_state = workspace::default_layout::build_default_layout(&mut workspace);
@@ -843,6 +1021,29 @@ impl DataVisApp {
         }
     }
 
+    /// Recover orphaned journals into completed recordings and remove them
+    fn recover_journals(&mut self, paths: &[PathBuf]) {
+        for path in paths {
+            match crate::session::journal::recover(path) {
+                Ok(recording) => {
+                    tracing::info!(
+                        "Recovered recording '{}' ({} frames)",
+                        recording.metadata.name,
+                        recording.frame_count()
+                    );
+                    self.topics.completed_recordings.push(recording);
+                    if let Err(e) = std::fs::remove_file(path) {
+                        tracing::warn!("Failed to remove recovered journal {:?}: {}", path, e);
+                    }
+                }
+                Err(e) => {
+                    // Keep the journal so recovery can be retried
+                    self.last_error = Some(format!("Failed to recover {}: {}", path.display(), e));
+                }
+            }
+        }
+    }
+
     fn load_elf(&mut self, path: &Path) {
         self.elf_file_path = Some(path.to_path_buf());
         match parse_elf(path) {
@@ -853,6 +1054,8 @@ impl DataVisApp {
                     info.function_count()
                 );
                 self.elf_symbols = info.get_variables().into_iter().cloned().collect();
+                self.frontend
+                    .send_command(PipelineCommand::SetTargetEndianness(info.endianness()));
                 self.elf_info = Some(info);
                 // Signal ELF reload — VariableBrowser auto-refreshes via elf_generation
                 self.topics.elf_generation += 1;
@@ -884,8 +1087,9 @@ impl DataVisApp {
     fn add_variable_confirmed(&mut self, var: crate::types::Variable) {
         self.config.add_variable(var.clone());
         self.topics
-            .variable_data
+            .live_data_mut()
             .insert(var.id, VariableData::new(var.clone()));
+        self.attach_history();
         self.frontend.add_variable(var);
     }
 
@@ -907,7 +1111,8 @@ impl DataVisApp {
             let mut child =
                 crate::types::Variable::new(&spec.name, address, spec.var_type);
             child.parent_id = Some(parent_id);
-            child.enabled = false;
+            // The text behind a `char*` is the reason the pointer was added
+            child.enabled = spec.var_type.is_text() && spec.address_mode.is_dynamic();
             child.show_in_graph = false;
             child.color =
                 crate::types::Variable::generate_child_color(parent_color, i, child_count);
@@ -949,6 +1154,7 @@ impl DataVisApp {
     fn remove_variable_internal(&mut self, id: u32) {
         self.config.remove_variable(id);
         self.topics.variable_data.remove(&id);
+        self.topics.live_data_mut().remove(&id);
         self.frontend
             .send_command(PipelineCommand::RemoveVariable(id));
     }
@@ -1052,6 +1258,9 @@ impl DataVisApp {
                     match crate::backend::parse_elf(&binary_path) {
                         Ok(info) => {
                             self.elf_symbols = info.symbols.clone();
+                            self.frontend.send_command(PipelineCommand::SetTargetEndianness(
+                                info.endianness(),
+                            ));
                             self.elf_info = Some(info);
                             // Signal ELF reload — VariableBrowser auto-refreshes via elf_generation
                             self.topics.elf_generation += 1;
@@ -1064,6 +1273,7 @@ impl DataVisApp {
                     }
                 }
 
+                self.topics.end_playback();
                 self.topics.variable_data.clear();
                 for var in self.config.variables.values() {
                     self.topics
@@ -1071,6 +1281,7 @@ impl DataVisApp {
                         .insert(var.id, crate::types::VariableData::new(var.clone()));
                     self.frontend.add_variable(var.clone());
                 }
+                self.attach_history();
 
                 tracing::info!("Project loaded successfully");
             }
@@ -1112,7 +1323,10 @@ impl DataVisApp {
                     }
 
                     let new_var_type = elf_info.infer_variable_type_for_symbol(sym);
-                    if var.var_type != new_var_type {
+                    // A user-chosen reinterpretation of the same storage is not a change
+                    let reinterpreted = var.var_type.is_reinterpreted()
+                        && var.var_type.size_bytes() == new_var_type.size_bytes();
+                    if var.var_type != new_var_type && !reinterpreted {
                         let type_name = elf_info.get_symbol_type_name(sym);
                         changes.push(VariableChange {
                             variable_id: var.id,
@@ -1235,6 +1449,7 @@ impl DataVisApp {
             }
             self.config.remove_variable(id);
             self.topics.variable_data.remove(&id);
+            self.topics.live_data_mut().remove(&id);
             self.frontend
                 .send_command(PipelineCommand::RemoveVariable(id));
         }
@@ -1270,8 +1485,11 @@ impl DataVisApp {
             if self.settings.collecting {
                 self.settings.collecting = false;
                 self.frontend.send_command(PipelineCommand::Stop);
+                self.live_recorder.checkpoint();
+                self.live_persistence.stop();
             } else {
                 self.settings.collecting = true;
+                self.start_persistence();
                 self.frontend.send_command(PipelineCommand::Start);
             }
         }
@@ -1971,6 +2189,10 @@ impl DataVisApp {
                         self.config.collection.poll_rate_hz = state.poll_rate_hz;
                         self.config.collection.max_data_points = state.max_data_points;
                         self.config.collection.timeout_ms = state.timeout_ms;
+                        if self.config.collection.keep_history != state.keep_history {
+                            self.config.collection.keep_history = state.keep_history;
+                            self.reset_history();
+                        }
                         if self.config.collection.poll_rate_hz != old_rate {
                             self.handle_action(AppAction::SetPollRate(
                                 self.config.collection.poll_rate_hz,
@@ -2078,6 +2300,15 @@ impl DataVisApp {
                         ui.label("- Time Series: Real-time data plot");
                         ui.label("- FFT View: Frequency analysis");
                         ui.label("- Watcher: Monitor variable values");
+                        ui.label("- Text Log: History of string variables");
+                        ui.label("- Struct Inspector: Live view of a whole struct");
+                        ui.label("- Spectrogram: Time-frequency waterfall");
+                        ui.label("- Frequency Response: Bode plot, coherence and lag between two variables");
+                        ui.label("- Step Response: rise time, overshoot and settling of a control loop");
+                        ui.label("- Filter Designer: IIR/FIR filters as plot overlays or converter scripts");
+                        ui.label("- Histogram: value distribution with percentiles, kurtosis and a normal fit");
+                        ui.label("- X-Y Plot: one variable against others, time-aligned, with a fading trail");
+                        ui.label("- Logic Analyzer: bit and bus lanes of integer variables with edge and pulse measurements");
                         ui.add_space(12.0);
 
                         ui.separator();
@@ -2136,6 +2367,10 @@ impl eframe::App for DataVisApp {
         self.capture_window_state(ctx);
 
         let had_messages = self.process_backend_messages();
+        self.live_recorder.update(&mut self.topics);
+        if let Some(error) = self.live_persistence.update() {
+            self.last_error = Some(error);
+        }
         self.handle_keyboard_shortcuts(ctx);
 
         // Process native menu events (if using native menus)
@@ -2270,10 +2505,41 @@ impl eframe::App for DataVisApp {
                 }
             }
         }
+
+        // Recover recordings dialog
+        if self.dialogs.recover_recordings.0 {
+            use dialogs::{
+                show_dialog, RecoverRecordingsAction, RecoverRecordingsContext,
+                RecoverRecordingsDialog,
+            };
+
+            let dialog_ctx = RecoverRecordingsContext;
+            if let Some(action) = show_dialog::<RecoverRecordingsDialog>(
+                ctx,
+                &mut self.dialogs.recover_recordings.0,
+                &mut self.dialogs.recover_recordings.1,
+                dialog_ctx,
+            ) {
+                match action {
+                    RecoverRecordingsAction::Recover(paths) => {
+                        self.recover_journals(&paths);
+                    }
+                    RecoverRecordingsAction::Discard(paths) => {
+                        for path in paths {
+                            if let Err(e) = std::fs::remove_file(&path) {
+                                tracing::warn!("Failed to discard journal {:?}: {}", path, e);
+                            }
+                        }
+                    }
+                }
+            }
+        }
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
         self.frontend.shutdown();
+        self.live_recorder.shutdown(&mut self.topics);
+        self.live_persistence.stop();
 
         self.app_state.update_last_connection(
             &self.config.probe.target_chip,
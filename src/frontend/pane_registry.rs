@@ -6,8 +6,9 @@
 
 use crate::frontend::pane_trait::Pane;
 use crate::frontend::panes::{
-    FftViewState, RecorderPaneState, TimeSeriesState, VariableBrowserState, VariableListState,
-    WatcherState,
+    FftViewState, FilterDesignerState, FrequencyResponseState, HistogramState, LogicAnalyzerState,
+    RecorderPaneState, RecordingCompareState, SpectrogramState, StepResponseState, StructInspectorState, TextLogState,
+    TimeSeriesState, VariableBrowserState, VariableListState, WatcherState, XyPlotState,
 };
 use crate::frontend::workspace::PaneKind;
 
@@ -60,5 +61,65 @@ pub fn build_registry() -> Vec<PaneKindInfo> {
             is_singleton: false,
             factory: || Box::new(FftViewState::default()),
         },
+        PaneKindInfo {
+            kind: PaneKind::TextLog,
+            display_name: "Text Log",
+            is_singleton: false,
+            factory: || Box::new(TextLogState::default()),
+        },
+        PaneKindInfo {
+            kind: PaneKind::StructInspector,
+            display_name: "Struct Inspector",
+            is_singleton: false,
+            factory: || Box::new(StructInspectorState::default()),
+        },
+        PaneKindInfo {
+            kind: PaneKind::Spectrogram,
+            display_name: "Spectrogram",
+            is_singleton: false,
+            factory: || Box::new(SpectrogramState::default()),
+        },
+        PaneKindInfo {
+            kind: PaneKind::FrequencyResponse,
+            display_name: "Frequency Response",
+            is_singleton: false,
+            factory: || Box::new(FrequencyResponseState::default()),
+        },
+        PaneKindInfo {
+            kind: PaneKind::StepResponse,
+            display_name: "Step Response",
+            is_singleton: false,
+            factory: || Box::new(StepResponseState::default()),
+        },
+        PaneKindInfo {
+            kind: PaneKind::FilterDesigner,
+            display_name: "Filter Designer",
+            is_singleton: false,
+            factory: || Box::new(FilterDesignerState::default()),
+        },
+        PaneKindInfo {
+            kind: PaneKind::Histogram,
+            display_name: "Histogram",
+            is_singleton: false,
+            factory: || Box::new(HistogramState::default()),
+        },
+        PaneKindInfo {
+            kind: PaneKind::XyPlot,
+            display_name: "X-Y Plot",
+            is_singleton: false,
+            factory: || Box::new(XyPlotState::default()),
+        },
+        PaneKindInfo {
+            kind: PaneKind::LogicAnalyzer,
+            display_name: "Logic Analyzer",
+            is_singleton: false,
+            factory: || Box::new(LogicAnalyzerState::default()),
+        },
+        PaneKindInfo {
+            kind: PaneKind::RecordingCompare,
+            display_name: "Compare Recordings",
+            is_singleton: false,
+            factory: || Box::new(RecordingCompareState::default()),
+        },
     ]
 }
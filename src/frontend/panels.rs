@@ -347,8 +347,12 @@ impl VariableListPanel {
                         ui.end_row();
 
                         if let Some(raw) = data.last_value {
+                            let text = match raw.format_hex(var.var_type.size_bytes()) {
+                                Some(hex) => format!("{} ({})", raw, hex),
+                                None => format!("{:.6}", raw),
+                            };
                             ui.label("Raw:");
-                            ui.label(RichText::new(format!("{:.6}", raw)).monospace());
+                            ui.label(RichText::new(text).monospace());
                             ui.end_row();
                         }
 
@@ -1,10 +1,16 @@
 //! FFT View pane - Frequency spectrum analysis
 //!
 //! Extracted from the FFT panel of the Visualizer page.
+//!
+//! Samples are resampled onto a uniform grid from their real timestamps before
+//! the FFT, so jittered or dropped reads do not shift spectral peaks.
 
 use egui::{Color32, Ui};
 
-use crate::analysis::{FftAnalyzer, FftConfig, FftResult, WindowFunction};
+use crate::analysis::{
+    resample_data_points, FftAnalyzer, FftConfig, FftResult, Interpolation, ResampleConfig,
+    TimingStats, WindowFunction,
+};
 use crate::frontend::pane_trait::Pane;
 use crate::frontend::state::{AppAction, SharedState};
 use crate::frontend::workspace::PaneKind;
@@ -23,6 +29,10 @@ pub struct FftViewState {
     pub db_scale: bool,
     /// Whether to use Welch's method (averaged FFT)
     pub averaged: bool,
+    /// Resampling applied before the FFT
+    pub resample_config: ResampleConfig,
+    /// Measured timing of the samples behind the cached result
+    pub timing: Option<TimingStats>,
 }
 
 impl Default for FftViewState {
@@ -34,6 +44,8 @@ impl Default for FftViewState {
             fft_result: None,
             db_scale: true,
             averaged: true,
+            resample_config: ResampleConfig::default(),
+            timing: None,
         }
     }
 }
@@ -114,6 +126,30 @@ pub fn render(
 
         ui.separator();
 
+        // Interpolation used to resample onto a uniform grid
+        ui.label("Resample:");
+        egui::ComboBox::from_id_salt("fft_pane_interpolation_selector")
+            .selected_text(state.resample_config.interpolation.display_name())
+            .width(70.0)
+            .show_ui(ui, |ui| {
+                for interpolation in Interpolation::all() {
+                    let is_selected = state.resample_config.interpolation == *interpolation;
+                    if ui
+                        .selectable_label(is_selected, interpolation.display_name())
+                        .on_hover_text(match interpolation {
+                            Interpolation::Linear => "Straight line between neighbouring samples",
+                            Interpolation::Sinc => "Band-limited (windowed sinc) interpolation",
+                        })
+                        .clicked()
+                    {
+                        state.resample_config.interpolation = *interpolation;
+                        state.fft_result = None;
+                    }
+                }
+            });
+
+        ui.separator();
+
         // Scale toggle
         let scale_text = if state.db_scale { "dB" } else { "Linear" };
         if ui
@@ -151,28 +187,19 @@ pub fn render(
     if let Some(var_id) = state.target_variable_id {
         if state.fft_result.is_none() {
             if let Some(data) = shared.state.topics.variable_data.get(&var_id) {
-                if !data.data_points.is_empty() {
-                    let samples: Vec<f64> =
-                        data.data_points.iter().map(|p| p.converted_value).collect();
-
-                    let sample_rate = if data.data_points.len() >= 2 {
-                        let duration = data.data_points.back().unwrap().timestamp.as_secs_f64()
-                            - data.data_points.front().unwrap().timestamp.as_secs_f64();
-                        if duration > 0.0 {
-                            data.data_points.len() as f64 / duration
-                        } else {
-                            shared.state.config.collection.poll_rate_hz as f64
-                        }
-                    } else {
-                        shared.state.config.collection.poll_rate_hz as f64
-                    };
-
+                let resampled = resample_data_points(&data.data_points, &state.resample_config);
+                if let Some(resampled) = resampled {
                     let result = if state.averaged {
-                        state.fft_analyzer.compute_averaged(&samples, sample_rate)
+                        state
+                            .fft_analyzer
+                            .compute_averaged(&resampled.samples, resampled.sample_rate)
                     } else {
-                        state.fft_analyzer.compute(&samples, sample_rate)
+                        state
+                            .fft_analyzer
+                            .compute(&resampled.samples, resampled.sample_rate)
                     };
 
+                    state.timing = Some(resampled.timing);
                     state.fft_result = Some(result);
                 }
             }
@@ -188,6 +215,28 @@ pub fn render(
                     result.sample_rate / 2.0
                 ));
 
+                if let Some(timing) = &state.timing {
+                    ui.separator();
+                    let label = ui.label(format!(
+                        "Rate: {:.1} Hz | Jitter: {:.1} µs ({:.1}%)",
+                        timing.effective_rate,
+                        timing.jitter * 1e6,
+                        timing.relative_jitter() * 100.0
+                    ));
+                    label.on_hover_text(format!(
+                        "Measured from {} timestamps (poll rate setting: {} Hz)\n\
+                         Delivered rate: {:.1} Hz\n\
+                         Gaps: {} (~{} reads lost)\n\
+                         Longest interval: {:.2} ms",
+                        timing.sample_count,
+                        shared.state.config.collection.poll_rate_hz,
+                        timing.mean_rate,
+                        timing.gap_count,
+                        timing.missing_samples,
+                        timing.max_interval * 1e3
+                    ));
+                }
+
                 if let Some((peak_freq, peak_mag)) = result.peak() {
                     ui.separator();
                     let peak_display = if state.db_scale {
@@ -0,0 +1,643 @@
+//! Filter Designer pane - Design digital filters and apply them to data
+//!
+//! Designs IIR (Butterworth, Chebyshev, notch, biquad) and windowed FIR
+//! filters, shows their frequency response and previews them on live data or
+//! a completed recording. A filter can be drawn as a display-only overlay on
+//! time-series plots, or installed as a variable's converter script so the
+//! backend filters every new sample.
+
+use std::time::{Duration, Instant};
+
+use egui::{Color32, Ui};
+
+use crate::analysis::{
+    measure_timing, series_from_points, Filter, FilterMethod, FilterResponse, FilterSpec,
+    FilterType, WindowFunction, MAX_FIR_TAPS, MAX_IIR_ORDER,
+};
+use crate::frontend::pane_trait::Pane;
+use crate::frontend::plot::{frequency_points, frequency_x};
+use crate::frontend::state::{AppAction, SharedState};
+use crate::frontend::topics::FilterOverlay;
+use crate::frontend::workspace::PaneKind;
+
+/// How often the preview is refiltered while showing live data
+const LIVE_REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Number of frequencies in the response plots
+const RESPONSE_POINTS: usize = 512;
+
+/// Which plot is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DesignerView {
+    /// Gain and phase of the filter
+    #[default]
+    Response,
+    /// Original and filtered data
+    Preview,
+}
+
+impl DesignerView {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            DesignerView::Response => "Response",
+            DesignerView::Preview => "Preview",
+        }
+    }
+
+    /// Get all views
+    pub fn all() -> &'static [DesignerView] {
+        &[DesignerView::Response, DesignerView::Preview]
+    }
+}
+
+/// Original and filtered series of the preview source
+#[derive(Debug, Clone, Default)]
+struct Preview {
+    original: Vec<[f64; 2]>,
+    filtered: Vec<[f64; 2]>,
+    /// Sample rate measured from the timestamps (Hz)
+    measured_rate: Option<f64>,
+}
+
+/// State for the Filter Designer pane
+pub struct FilterDesignerState {
+    /// Filter specification being edited
+    pub spec: FilterSpec,
+    /// Variable the filter is previewed on and applied to
+    pub target_variable_id: Option<u32>,
+    /// Completed recording to preview instead of live data
+    pub recording_index: Option<usize>,
+    /// Plotted view
+    pub view: DesignerView,
+    /// Plot frequency on a logarithmic axis
+    pub log_frequency: bool,
+    /// Draw the filtered live series on time-series plots
+    pub overlay: bool,
+    /// Designed filter, or the reason the spec is invalid
+    filter: Result<Filter, String>,
+    /// Frequency response of the designed filter
+    response: FilterResponse,
+    /// Cached preview
+    preview: Option<Preview>,
+    /// When the preview was last computed
+    computed_at: Option<Instant>,
+}
+
+impl Default for FilterDesignerState {
+    fn default() -> Self {
+        let mut state = Self {
+            spec: FilterSpec::default(),
+            target_variable_id: None,
+            recording_index: None,
+            view: DesignerView::default(),
+            log_frequency: true,
+            overlay: false,
+            filter: Err(String::new()),
+            response: FilterResponse::default(),
+            preview: None,
+            computed_at: None,
+        };
+        state.redesign();
+        state
+    }
+}
+
+impl FilterDesignerState {
+    /// Designed filter, if the spec is valid
+    pub fn filter(&self) -> Option<&Filter> {
+        self.filter.as_ref().ok()
+    }
+
+    /// Redesign the filter after a spec change
+    fn redesign(&mut self) {
+        self.filter = Filter::design(&self.spec).map_err(|e| e.to_string());
+        self.response = self
+            .filter()
+            .map(|f| f.frequency_response(RESPONSE_POINTS))
+            .unwrap_or_default();
+        self.preview = None;
+    }
+
+    /// Filter the selected source, returning `None` without data
+    fn compute_preview(&self, shared: &SharedState<'_>) -> Option<Preview> {
+        let var_id = self.target_variable_id?;
+        let topics = &shared.state.topics;
+        let (times, values) = match self.recording_index {
+            Some(index) => {
+                let recording = topics.completed_recordings.get(index)?;
+                let points =
+                    recording.get_variable_data(var_id, Duration::ZERO, recording.duration());
+                series_from_points(&points)
+            }
+            None => series_from_points(&topics.variable_data.get(&var_id)?.data_points),
+        };
+        if times.is_empty() {
+            return None;
+        }
+        let filtered = self.filter().map(|f| f.apply(&values)).unwrap_or_default();
+        let zip = |values: &[f64]| -> Vec<[f64; 2]> {
+            times.iter().zip(values).map(|(&t, &v)| [t, v]).collect()
+        };
+        Some(Preview {
+            original: zip(&values),
+            filtered: zip(&filtered),
+            measured_rate: measure_timing(&times).map(|t| t.effective_rate),
+        })
+    }
+}
+
+/// Label of a completed recording in the source selector
+fn recording_label(index: usize, name: &str) -> String {
+    if name.is_empty() {
+        format!("Recording {}", index + 1)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Specification editor; returns true when the spec changed
+fn render_spec_editor(state: &mut FilterDesignerState, ui: &mut Ui) -> bool {
+    let spec = &mut state.spec;
+    let mut changed = false;
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Method:");
+        egui::ComboBox::from_id_salt("filter_designer_method")
+            .selected_text(spec.method.display_name())
+            .width(110.0)
+            .show_ui(ui, |ui| {
+                for method in FilterMethod::all() {
+                    changed |= ui
+                        .selectable_value(&mut spec.method, *method, method.display_name())
+                        .changed();
+                }
+            });
+
+        if spec.method != FilterMethod::Notch {
+            ui.label("Type:");
+            egui::ComboBox::from_id_salt("filter_designer_type")
+                .selected_text(spec.filter_type.display_name())
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    for filter_type in FilterType::all() {
+                        changed |= ui
+                            .selectable_value(
+                                &mut spec.filter_type,
+                                *filter_type,
+                                filter_type.display_name(),
+                            )
+                            .changed();
+                    }
+                });
+        }
+
+        if spec.method.has_order() {
+            let max_order = if spec.method == FilterMethod::Fir {
+                MAX_FIR_TAPS - 1
+            } else {
+                MAX_IIR_ORDER
+            };
+            ui.label("Order:");
+            changed |= ui
+                .add(egui::DragValue::new(&mut spec.order).range(1..=max_order))
+                .changed();
+        }
+
+        let band = spec.filter_type.is_band() && !spec.method.has_q();
+        ui.label(if band {
+            "Band:"
+        } else if spec.method.has_q() {
+            "Center:"
+        } else {
+            "Cutoff:"
+        });
+        changed |= ui
+            .add(
+                egui::DragValue::new(&mut spec.cutoff_hz)
+                    .speed(0.1)
+                    .range(0.001..=f64::MAX)
+                    .suffix(" Hz"),
+            )
+            .changed();
+        if band {
+            ui.label("–");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut spec.cutoff_high_hz)
+                        .speed(0.1)
+                        .range(0.001..=f64::MAX)
+                        .suffix(" Hz"),
+                )
+                .changed();
+        }
+
+        if spec.method.has_q() {
+            ui.label("Q:");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut spec.q)
+                        .speed(0.01)
+                        .range(0.01..=100.0),
+                )
+                .changed();
+        }
+
+        if spec.method == FilterMethod::Chebyshev {
+            ui.label("Ripple:");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut spec.ripple_db)
+                        .speed(0.05)
+                        .range(0.01..=10.0)
+                        .suffix(" dB"),
+                )
+                .changed();
+        }
+
+        if spec.method == FilterMethod::Fir {
+            ui.label("Window:");
+            egui::ComboBox::from_id_salt("filter_designer_window")
+                .selected_text(spec.window.display_name())
+                .width(100.0)
+                .show_ui(ui, |ui| {
+                    for window in WindowFunction::all() {
+                        changed |= ui
+                            .selectable_value(&mut spec.window, *window, window.display_name())
+                            .changed();
+                    }
+                });
+        }
+
+        ui.label("Rate:");
+        changed |= ui
+            .add(
+                egui::DragValue::new(&mut spec.sample_rate)
+                    .speed(1.0)
+                    .range(0.1..=1_000_000.0)
+                    .suffix(" Hz"),
+            )
+            .on_hover_text("Sample rate the filter runs at (the variable's poll rate)")
+            .changed();
+    });
+
+    changed
+}
+
+/// Render the filter designer pane
+pub fn render(
+    state: &mut FilterDesignerState,
+    shared: &mut SharedState<'_>,
+    ui: &mut Ui,
+) -> Vec<AppAction> {
+    let mut actions = Vec::new();
+
+    // Toolbar: target variable and data source
+    ui.horizontal_wrapped(|ui| {
+        ui.heading("Filter Designer");
+        ui.separator();
+
+        ui.label("Variable:");
+        egui::ComboBox::from_id_salt("filter_designer_variable")
+            .selected_text(
+                state
+                    .target_variable_id
+                    .and_then(|id| shared.state.config.variables.get(&id))
+                    .map(|v| v.name.as_str())
+                    .unwrap_or("Select..."),
+            )
+            .width(120.0)
+            .show_ui(ui, |ui| {
+                for var in shared.state.config.variables.values() {
+                    if var.enabled && var.show_in_graph {
+                        let is_selected = state.target_variable_id == Some(var.id);
+                        if ui.selectable_label(is_selected, &var.name).clicked() {
+                            state.target_variable_id = Some(var.id);
+                            state.preview = None;
+                        }
+                    }
+                }
+            });
+
+        ui.label("Data:");
+        let recordings = &shared.state.topics.completed_recordings;
+        let selected_text = match state.recording_index.and_then(|i| recordings.get(i)) {
+            Some(recording) => {
+                recording_label(state.recording_index.unwrap_or(0), &recording.metadata.name)
+            }
+            None => "Live".to_string(),
+        };
+        egui::ComboBox::from_id_salt("filter_designer_data")
+            .selected_text(selected_text)
+            .width(120.0)
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(state.recording_index.is_none(), "Live")
+                    .clicked()
+                {
+                    state.recording_index = None;
+                    state.preview = None;
+                }
+                for (i, recording) in recordings.iter().enumerate() {
+                    let label = recording_label(i, &recording.metadata.name);
+                    if ui
+                        .selectable_label(state.recording_index == Some(i), label)
+                        .clicked()
+                    {
+                        state.recording_index = Some(i);
+                        state.preview = None;
+                    }
+                }
+            });
+
+        ui.separator();
+
+        for view in DesignerView::all() {
+            ui.selectable_value(&mut state.view, *view, view.display_name());
+        }
+        if state.view == DesignerView::Response {
+            ui.checkbox(&mut state.log_frequency, "Log f")
+                .on_hover_text("Logarithmic frequency axis");
+        }
+    });
+
+    if render_spec_editor(state, ui) {
+        state.redesign();
+    }
+
+    // Refilter the preview source when needed
+    let live = state.recording_index.is_none();
+    let stale = state
+        .computed_at
+        .is_none_or(|t| live && t.elapsed() >= LIVE_REFRESH_INTERVAL);
+    if state.preview.is_none() || stale {
+        state.preview = state.compute_preview(shared);
+        state.computed_at = Some(Instant::now());
+
+        // Publish the overlay for time-series plots (live data only: recordings
+        // have their own time base)
+        if let Some(pane_id) = shared.ctx.current_pane_id {
+            let overlay = match (&state.preview, state.filter(), state.target_variable_id) {
+                (Some(preview), Some(filter), Some(variable_id)) if state.overlay && live => {
+                    Some(FilterOverlay {
+                        variable_id,
+                        label: filter.spec.summary(),
+                        points: preview.filtered.clone(),
+                    })
+                }
+                _ => None,
+            };
+            match overlay {
+                Some(overlay) => {
+                    shared
+                        .state
+                        .topics
+                        .filter_overlays
+                        .insert(pane_id.0, overlay);
+                }
+                None => {
+                    shared.state.topics.filter_overlays.remove(&pane_id.0);
+                }
+            }
+        }
+    }
+
+    // Actions and status
+    ui.horizontal_wrapped(|ui| {
+        if ui
+            .checkbox(&mut state.overlay, "Overlay on plots")
+            .on_hover_text("Draw the filtered live data on time-series plots (display only)")
+            .changed()
+        {
+            state.preview = None;
+        }
+
+        let target = state
+            .target_variable_id
+            .and_then(|id| shared.state.config.variables.get(&id));
+        match (&state.filter, target) {
+            (Ok(filter), target) => {
+                if ui
+                    .button("Copy Script")
+                    .on_hover_text("Copy the converter script to the clipboard")
+                    .clicked()
+                {
+                    ui.ctx().copy_text(filter.to_script());
+                }
+                if let Some(var) = target {
+                    let replaces = var
+                        .converter_script
+                        .as_deref()
+                        .is_some_and(|s| !s.trim().is_empty());
+                    let hover = if replaces {
+                        "Replace the variable's current converter with this filter"
+                    } else {
+                        "Filter every new sample in the backend"
+                    };
+                    if ui
+                        .button("Apply as Converter")
+                        .on_hover_text(hover)
+                        .clicked()
+                    {
+                        let mut var = var.clone();
+                        var.converter_script = Some(filter.to_script());
+                        actions.push(AppAction::UpdateVariable(var));
+                    }
+                    if replaces {
+                        ui.colored_label(
+                            Color32::from_rgb(255, 165, 0),
+                            "Replaces the current converter",
+                        );
+                    }
+                }
+                if !filter.is_stable() {
+                    ui.colored_label(Color32::RED, "Unstable design");
+                }
+            }
+            (Err(error), _) => {
+                ui.colored_label(Color32::RED, error);
+            }
+        }
+
+        // Warn when the design rate does not match the data
+        if let Some(rate) = state.preview.as_ref().and_then(|p| p.measured_rate) {
+            ui.separator();
+            ui.label(format!("Measured: {:.1} Hz", rate));
+            if ((rate - state.spec.sample_rate) / rate).abs() > 0.1 {
+                ui.colored_label(Color32::YELLOW, "differs from the design rate");
+                if ui.small_button("Use").clicked() {
+                    state.spec.sample_rate = (rate * 10.0).round() / 10.0;
+                    state.redesign();
+                }
+            }
+        }
+    });
+
+    ui.separator();
+
+    match state.view {
+        DesignerView::Response => render_response(state, ui),
+        DesignerView::Preview => render_preview(state, ui),
+    }
+
+    actions
+}
+
+/// Gain and phase of the designed filter with linked frequency axes
+fn render_response(state: &FilterDesignerState, ui: &mut Ui) {
+    use egui_plot::{Line, LineStyle, Plot, PlotPoints, VLine};
+
+    if state.filter().is_none() {
+        ui.centered_and_justified(|ui| {
+            ui.label("Fix the filter specification to see its response");
+        });
+        return;
+    }
+
+    let log = state.log_frequency;
+    let frequency_plot = |id: &'static str| {
+        Plot::new(id)
+            .x_axis_label("Frequency (Hz)")
+            .allow_zoom(true)
+            .allow_drag(true)
+            .x_axis_formatter(move |mark, _range| {
+                let frequency = if log {
+                    10f64.powf(mark.value)
+                } else {
+                    mark.value
+                };
+                format!("{:.4}", frequency)
+                    .trim_end_matches('0')
+                    .trim_end_matches('.')
+                    .to_string()
+            })
+            .label_formatter(move |name, point| {
+                let frequency = if log { 10f64.powf(point.x) } else { point.x };
+                format!("{}\n{:.2} Hz\n{:.2}", name, frequency, point.y)
+            })
+    };
+
+    let mut cutoffs = vec![state.spec.cutoff_hz];
+    if state.spec.filter_type.is_band() && !state.spec.method.has_q() {
+        cutoffs.push(state.spec.cutoff_high_hz);
+    }
+
+    let link_group = ui.id().with("filter_designer_link");
+    let half_height = (ui.available_height() / 2.0 - 4.0).max(80.0);
+
+    frequency_plot("filter_designer_magnitude")
+        .y_axis_label("Gain (dB)")
+        .height(half_height)
+        .include_y(3.0)
+        .link_axis(link_group, [true, false])
+        .link_cursor(link_group, [true, false])
+        .show(ui, |plot_ui| {
+            // Clip deep stopband values so the passband stays readable
+            let points: Vec<[f64; 2]> =
+                frequency_points(state.log_frequency, state.response.magnitude_points())
+                    .into_iter()
+                    .map(|[f, db]| [f, db.max(-120.0)])
+                    .collect();
+            plot_ui.line(
+                Line::new("Gain", PlotPoints::from(points))
+                    .color(Color32::from_rgb(100, 150, 255))
+                    .width(1.5),
+            );
+            for &cutoff in &cutoffs {
+                plot_ui.vline(
+                    VLine::new("Cutoff", frequency_x(state.log_frequency, cutoff))
+                        .color(Color32::GRAY)
+                        .style(LineStyle::dashed_loose()),
+                );
+            }
+        });
+
+    frequency_plot("filter_designer_phase")
+        .y_axis_label("Phase (°)")
+        .height(half_height)
+        .link_axis(link_group, [true, false])
+        .link_cursor(link_group, [true, false])
+        .show(ui, |plot_ui| {
+            let points = frequency_points(state.log_frequency, state.response.phase_points());
+            plot_ui.line(
+                Line::new("Phase", PlotPoints::from(points))
+                    .color(Color32::from_rgb(255, 160, 60))
+                    .width(1.5),
+            );
+        });
+}
+
+/// Original and filtered data of the preview source
+fn render_preview(state: &FilterDesignerState, ui: &mut Ui) {
+    use egui_plot::{Legend, Line, Plot, PlotPoints};
+
+    let Some(preview) = &state.preview else {
+        ui.centered_and_justified(|ui| {
+            ui.label(if state.target_variable_id.is_some() {
+                "No data for the selected variable"
+            } else {
+                "Select a variable to preview the filter on"
+            });
+        });
+        return;
+    };
+
+    Plot::new("filter_designer_preview")
+        .legend(Legend::default())
+        .x_axis_label("Time (s)")
+        .allow_zoom(true)
+        .allow_drag(true)
+        .show(ui, |plot_ui| {
+            plot_ui.line(
+                Line::new("Original", PlotPoints::from(preview.original.clone()))
+                    .color(Color32::from_gray(140))
+                    .width(1.0),
+            );
+            if !preview.filtered.is_empty() {
+                plot_ui.line(
+                    Line::new("Filtered", PlotPoints::from(preview.filtered.clone()))
+                        .color(Color32::from_rgb(100, 200, 255))
+                        .width(1.5),
+                );
+            }
+        });
+}
+
+impl Pane for FilterDesignerState {
+    fn kind(&self) -> PaneKind {
+        PaneKind::FilterDesigner
+    }
+
+    fn render(&mut self, shared: &mut SharedState, ui: &mut Ui) -> Vec<AppAction> {
+        render(self, shared, ui)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_design_is_valid() {
+        let state = FilterDesignerState::default();
+        assert!(state.filter().is_some());
+        assert_eq!(state.response.frequencies.len(), RESPONSE_POINTS);
+    }
+
+    #[test]
+    fn test_invalid_spec_reports_error() {
+        let mut state = FilterDesignerState::default();
+        state.spec.cutoff_hz = state.spec.sample_rate;
+        state.redesign();
+        assert!(state.filter().is_none());
+        assert!(state.response.frequencies.is_empty());
+        assert!(state.filter.unwrap_err().contains("Nyquist"));
+    }
+}
@@ -0,0 +1,486 @@
+//! Frequency Response pane - Input/output cross-channel analysis
+//!
+//! Estimates the transfer function (Bode plot) between an input and an
+//! output variable for loop tuning, along with the coherence that shows where
+//! the estimate can be trusted and the time-domain cross-correlation that
+//! gives the lag between the two signals.
+//!
+//! Both variables are resampled onto one shared grid from their real
+//! timestamps before any spectra are computed.
+
+use egui::{Color32, Ui};
+
+use crate::analysis::{
+    resample_data_points_aligned, CrossCorrelation, CrossSpectrumAnalyzer, FftConfig,
+    FrequencyResponse, Interpolation, ResampleConfig, WindowFunction, LOW_COHERENCE,
+};
+use crate::frontend::pane_trait::Pane;
+use crate::frontend::plot::{frequency_points, frequency_x, variable_selector};
+use crate::frontend::state::{AppAction, SharedState};
+use crate::frontend::workspace::PaneKind;
+
+/// Which result is plotted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseView {
+    /// Gain and phase against frequency
+    #[default]
+    Bode,
+    /// Coherence against frequency
+    Coherence,
+    /// Correlation against lag
+    CrossCorrelation,
+}
+
+impl ResponseView {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ResponseView::Bode => "Bode",
+            ResponseView::Coherence => "Coherence",
+            ResponseView::CrossCorrelation => "Cross-Correlation",
+        }
+    }
+
+    /// Get all views
+    pub fn all() -> &'static [ResponseView] {
+        &[
+            ResponseView::Bode,
+            ResponseView::Coherence,
+            ResponseView::CrossCorrelation,
+        ]
+    }
+}
+
+/// State for the Frequency Response pane
+pub struct FrequencyResponseState {
+    /// Excitation variable (e.g. setpoint)
+    pub input_variable_id: Option<u32>,
+    /// Response variable (e.g. measured speed)
+    pub output_variable_id: Option<u32>,
+    /// Cross-spectrum analyzer instance
+    pub analyzer: CrossSpectrumAnalyzer,
+    /// Segment configuration (size, window, overlap)
+    pub fft_config: FftConfig,
+    /// Resampling applied before the analysis
+    pub resample_config: ResampleConfig,
+    /// Plotted result
+    pub view: ResponseView,
+    /// Plot frequency on a logarithmic axis
+    pub log_frequency: bool,
+    /// Remove 360 degree jumps from the phase
+    pub unwrap_phase: bool,
+    /// Largest cross-correlation lag (seconds)
+    pub max_lag: f64,
+    /// Cached frequency response
+    pub response: Option<FrequencyResponse>,
+    /// Cached cross-correlation
+    pub correlation: Option<CrossCorrelation>,
+}
+
+impl Default for FrequencyResponseState {
+    fn default() -> Self {
+        Self {
+            input_variable_id: None,
+            output_variable_id: None,
+            analyzer: CrossSpectrumAnalyzer::new(),
+            fft_config: FftConfig::default(),
+            resample_config: ResampleConfig::default(),
+            view: ResponseView::default(),
+            log_frequency: true,
+            unwrap_phase: false,
+            max_lag: 0.5,
+            response: None,
+            correlation: None,
+        }
+    }
+}
+
+impl FrequencyResponseState {
+    /// Drop cached results so they are recomputed on the next frame
+    fn invalidate(&mut self) {
+        self.response = None;
+        self.correlation = None;
+    }
+}
+
+/// Render the frequency response pane
+pub fn render(
+    state: &mut FrequencyResponseState,
+    shared: &mut SharedState<'_>,
+    ui: &mut Ui,
+) -> Vec<AppAction> {
+    // Toolbar
+    ui.horizontal_wrapped(|ui| {
+        ui.heading("Frequency Response");
+        ui.separator();
+
+        ui.label("Input:");
+        if variable_selector(
+            ui,
+            "freq_response_input_selector",
+            &mut state.input_variable_id,
+            &shared.state.config.variables,
+        ) {
+            state.invalidate();
+        }
+        ui.label("Output:");
+        if variable_selector(
+            ui,
+            "freq_response_output_selector",
+            &mut state.output_variable_id,
+            &shared.state.config.variables,
+        ) {
+            state.invalidate();
+        }
+
+        ui.separator();
+
+        // Segment size selector
+        ui.label("Segment:");
+        egui::ComboBox::from_id_salt("freq_response_size_selector")
+            .selected_text(format!("{}", state.fft_config.fft_size))
+            .width(80.0)
+            .show_ui(ui, |ui| {
+                for &size in FftConfig::available_sizes() {
+                    let is_selected = state.fft_config.fft_size == size;
+                    if ui
+                        .selectable_label(is_selected, format!("{}", size))
+                        .clicked()
+                    {
+                        state.fft_config.fft_size = size;
+                        state.invalidate();
+                    }
+                }
+            });
+
+        // Window function selector
+        ui.label("Window:");
+        egui::ComboBox::from_id_salt("freq_response_window_selector")
+            .selected_text(state.fft_config.window.display_name())
+            .width(100.0)
+            .show_ui(ui, |ui| {
+                for window in WindowFunction::all() {
+                    let is_selected = state.fft_config.window == *window;
+                    if ui
+                        .selectable_label(is_selected, window.display_name())
+                        .clicked()
+                    {
+                        state.fft_config.window = *window;
+                        state.invalidate();
+                    }
+                }
+            });
+
+        // Interpolation used to resample onto the shared grid
+        ui.label("Resample:");
+        egui::ComboBox::from_id_salt("freq_response_interpolation_selector")
+            .selected_text(state.resample_config.interpolation.display_name())
+            .width(70.0)
+            .show_ui(ui, |ui| {
+                for interpolation in Interpolation::all() {
+                    let is_selected = state.resample_config.interpolation == *interpolation;
+                    if ui
+                        .selectable_label(is_selected, interpolation.display_name())
+                        .clicked()
+                    {
+                        state.resample_config.interpolation = *interpolation;
+                        state.invalidate();
+                    }
+                }
+            });
+
+        ui.separator();
+
+        for view in ResponseView::all() {
+            ui.selectable_value(&mut state.view, *view, view.display_name());
+        }
+
+        ui.separator();
+
+        match state.view {
+            ResponseView::Bode | ResponseView::Coherence => {
+                ui.checkbox(&mut state.log_frequency, "Log f")
+                    .on_hover_text("Logarithmic frequency axis");
+                if state.view == ResponseView::Bode {
+                    ui.checkbox(&mut state.unwrap_phase, "Unwrap")
+                        .on_hover_text("Remove 360° jumps from the phase");
+                }
+            }
+            ResponseView::CrossCorrelation => {
+                ui.label("Max lag:");
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut state.max_lag)
+                            .speed(0.01)
+                            .range(0.001..=60.0)
+                            .suffix(" s"),
+                    )
+                    .changed()
+                {
+                    state.correlation = None;
+                }
+            }
+        }
+
+        if ui
+            .button("Compute")
+            .on_hover_text("Recompute from the latest data")
+            .clicked()
+        {
+            state.invalidate();
+        }
+    });
+
+    ui.separator();
+
+    let (Some(input_id), Some(output_id)) = (state.input_variable_id, state.output_variable_id)
+    else {
+        ui.centered_and_justified(|ui| {
+            ui.label("Select an input and an output variable to estimate their frequency response");
+        });
+        return Vec::new();
+    };
+
+    // Compute if needed
+    if state.response.is_none() || state.correlation.is_none() {
+        let topics = &shared.state.topics;
+        if let (Some(input), Some(output)) = (
+            topics.variable_data.get(&input_id),
+            topics.variable_data.get(&output_id),
+        ) {
+            let aligned = resample_data_points_aligned(
+                &input.data_points,
+                &output.data_points,
+                &state.resample_config,
+            );
+            if let Some((input, output)) = aligned {
+                let rate = input.sample_rate;
+                state.analyzer.set_config(state.fft_config.clone());
+                if state.response.is_none() {
+                    state.response = Some(state.analyzer.frequency_response(
+                        &input.samples,
+                        &output.samples,
+                        rate,
+                    ));
+                }
+                if state.correlation.is_none() {
+                    let max_lag = (state.max_lag * rate).round() as usize;
+                    state.correlation = Some(state.analyzer.cross_correlation(
+                        &input.samples,
+                        &output.samples,
+                        rate,
+                        max_lag,
+                    ));
+                }
+            }
+        }
+    }
+
+    let (Some(response), Some(correlation)) = (&state.response, &state.correlation) else {
+        ui.centered_and_justified(|ui| {
+            ui.label("No overlapping data for the selected variables");
+        });
+        return Vec::new();
+    };
+
+    // Summary line
+    ui.horizontal(|ui| {
+        ui.label(format!(
+            "Rate: {:.1} Hz | Segments: {} | Resolution: {:.2} Hz",
+            response.sample_rate, response.segment_count, response.frequency_resolution
+        ));
+        if response.segment_count < 2 {
+            ui.colored_label(
+                Color32::YELLOW,
+                "Collect more data: coherence needs several segments",
+            );
+        }
+        if let Some(bandwidth) = response.bandwidth_3db() {
+            ui.separator();
+            ui.strong(format!("-3 dB: {:.2} Hz", bandwidth));
+        }
+        if let Some((lag, value)) = correlation.peak() {
+            ui.separator();
+            ui.strong(format!("Lag: {:.2} ms (r = {:.3})", lag * 1e3, value))
+                .on_hover_text("Positive lag: the output follows the input");
+        }
+    });
+
+    match state.view {
+        ResponseView::Bode => render_bode(state, response, ui),
+        ResponseView::Coherence => render_coherence(state, response, ui),
+        ResponseView::CrossCorrelation => render_correlation(correlation, ui),
+    }
+
+    Vec::new()
+}
+
+/// Base plot configuration shared by the frequency-axis plots
+fn frequency_plot<'a>(state: &FrequencyResponseState, id: &'a str) -> egui_plot::Plot<'a> {
+    let log = state.log_frequency;
+    egui_plot::Plot::new(id)
+        .x_axis_label("Frequency (Hz)")
+        .allow_zoom(true)
+        .allow_drag(true)
+        .x_axis_formatter(move |mark, _range| {
+            let frequency = if log {
+                10f64.powf(mark.value)
+            } else {
+                mark.value
+            };
+            format!("{:.4}", frequency)
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string()
+        })
+        .label_formatter(move |name, point| {
+            let frequency = if log { 10f64.powf(point.x) } else { point.x };
+            format!("{}\n{:.2} Hz\n{:.2}", name, frequency, point.y)
+        })
+}
+
+/// Gain and phase plots with linked frequency axes
+fn render_bode(state: &FrequencyResponseState, response: &FrequencyResponse, ui: &mut Ui) {
+    use egui_plot::{Line, PlotPoints, Points};
+
+    let link_group = ui.id().with("freq_response_bode_link");
+    let half_height = (ui.available_height() / 2.0 - 4.0).max(80.0);
+
+    // Bins with low coherence are marked; their estimate is dominated by noise
+    let unreliable: Vec<usize> = (0..response.frequencies.len())
+        .filter(|&i| response.coherence[i] < LOW_COHERENCE)
+        .collect();
+    let marked = |values: &[f64]| -> Vec<[f64; 2]> {
+        unreliable
+            .iter()
+            .map(|&i| {
+                [
+                    frequency_x(state.log_frequency, response.frequencies[i]),
+                    values[i],
+                ]
+            })
+            .collect()
+    };
+
+    frequency_plot(state, "freq_response_magnitude")
+        .y_axis_label("Gain (dB)")
+        .height(half_height)
+        .link_axis(link_group, [true, false])
+        .link_cursor(link_group, [true, false])
+        .show(ui, |plot_ui| {
+            let points = frequency_points(state.log_frequency, response.magnitude_points());
+            plot_ui.line(
+                Line::new("Gain", PlotPoints::from(points))
+                    .color(Color32::from_rgb(100, 150, 255))
+                    .width(1.5),
+            );
+            plot_ui.points(
+                Points::new(
+                    "Low coherence",
+                    PlotPoints::from(marked(&response.magnitude_db)),
+                )
+                .color(Color32::GRAY)
+                .radius(2.0),
+            );
+        });
+
+    let phase = if state.unwrap_phase {
+        response.unwrapped_phase_points()
+    } else {
+        response.phase_points()
+    };
+    let phase_values: Vec<f64> = phase.iter().map(|p| p[1]).collect();
+    frequency_plot(state, "freq_response_phase")
+        .y_axis_label("Phase (°)")
+        .height(half_height)
+        .link_axis(link_group, [true, false])
+        .link_cursor(link_group, [true, false])
+        .show(ui, |plot_ui| {
+            plot_ui.line(
+                Line::new(
+                    "Phase",
+                    PlotPoints::from(frequency_points(state.log_frequency, phase)),
+                )
+                .color(Color32::from_rgb(255, 160, 60))
+                .width(1.5),
+            );
+            plot_ui.points(
+                Points::new("Low coherence", PlotPoints::from(marked(&phase_values)))
+                    .color(Color32::GRAY)
+                    .radius(2.0),
+            );
+        });
+}
+
+/// Coherence plot with the reliability threshold
+fn render_coherence(state: &FrequencyResponseState, response: &FrequencyResponse, ui: &mut Ui) {
+    use egui_plot::{HLine, Line, LineStyle, PlotPoints};
+
+    frequency_plot(state, "freq_response_coherence")
+        .y_axis_label("Coherence")
+        .include_y(0.0)
+        .include_y(1.0)
+        .show(ui, |plot_ui| {
+            let points = frequency_points(state.log_frequency, response.coherence_points());
+            plot_ui.line(
+                Line::new("Coherence", PlotPoints::from(points))
+                    .color(Color32::from_rgb(120, 200, 120))
+                    .width(1.5),
+            );
+            plot_ui.hline(
+                HLine::new("Reliable above", LOW_COHERENCE)
+                    .color(Color32::GRAY)
+                    .style(LineStyle::dashed_loose()),
+            );
+        });
+}
+
+/// Cross-correlation against lag with the peak marked
+fn render_correlation(correlation: &CrossCorrelation, ui: &mut Ui) {
+    use egui_plot::{Line, LineStyle, Plot, PlotPoints, VLine};
+
+    let points: Vec<[f64; 2]> = correlation
+        .plot_points()
+        .into_iter()
+        .map(|[lag, r]| [lag * 1e3, r])
+        .collect();
+
+    Plot::new("freq_response_correlation")
+        .x_axis_label("Lag (ms)")
+        .y_axis_label("Correlation")
+        .include_y(-1.0)
+        .include_y(1.0)
+        .allow_zoom(true)
+        .allow_drag(true)
+        .show(ui, |plot_ui| {
+            plot_ui.line(
+                Line::new("Correlation", PlotPoints::from(points))
+                    .color(Color32::from_rgb(100, 150, 255))
+                    .width(1.5),
+            );
+            if let Some((lag, _)) = correlation.peak() {
+                plot_ui.vline(
+                    VLine::new("Peak", lag * 1e3)
+                        .color(Color32::YELLOW)
+                        .style(LineStyle::dashed_loose()),
+                );
+            }
+        });
+}
+
+impl Pane for FrequencyResponseState {
+    fn kind(&self) -> PaneKind {
+        PaneKind::FrequencyResponse
+    }
+
+    fn render(&mut self, shared: &mut SharedState, ui: &mut Ui) -> Vec<AppAction> {
+        render(self, shared, ui)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
@@ -0,0 +1,446 @@
+//! Histogram pane - Value distribution with live statistics
+//!
+//! Bins the values of one variable over the visible time window of the
+//! time-series plots, or over the range between the A/B cursors of a
+//! time-series pane, and shows percentiles, standard deviation and kurtosis
+//! alongside an optional normal fit.
+
+use std::time::{Duration, Instant};
+
+use egui::{Color32, Ui};
+
+use crate::analysis::{values_in_range, Distribution, Histogram, MAX_BINS};
+use crate::frontend::pane_trait::Pane;
+use crate::frontend::state::{AppAction, SharedState};
+use crate::frontend::workspace::PaneKind;
+
+/// How often the histogram is recomputed while data is collected
+const LIVE_REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Lowest decade drawn on the log-scale count axis, so single counts stay visible
+const LOG_FLOOR: f64 = -0.5;
+
+/// Points of the normal fit curve
+const FIT_POINTS: usize = 200;
+
+/// Time range the histogram is computed over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistogramRange {
+    /// Visible window of the time-series plots
+    #[default]
+    VisibleWindow,
+    /// Range between the A/B cursors of a time-series pane
+    CursorRange,
+}
+
+impl HistogramRange {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            HistogramRange::VisibleWindow => "Visible Window",
+            HistogramRange::CursorRange => "Cursor Range",
+        }
+    }
+
+    /// Get all range sources
+    pub fn all() -> &'static [HistogramRange] {
+        &[HistogramRange::VisibleWindow, HistogramRange::CursorRange]
+    }
+}
+
+/// State for the Histogram pane
+pub struct HistogramState {
+    /// Variable whose values are binned
+    pub target_variable_id: Option<u32>,
+    /// Time range the histogram is computed over
+    pub range: HistogramRange,
+    /// Number of bins (0: automatic)
+    pub bins: usize,
+    /// Logarithmic count axis
+    pub log_scale: bool,
+    /// Overlay the normal distribution with the same mean and deviation
+    pub show_normal_fit: bool,
+    /// Mark the p50/p95/p99 percentiles on the plot
+    pub show_percentiles: bool,
+    /// Cached histogram
+    histogram: Option<Histogram>,
+    /// Statistics of the cached histogram's values
+    distribution: Option<Distribution>,
+    /// Time range of the cached result
+    computed_range: Option<(f64, f64)>,
+    /// When the cached result was computed
+    computed_at: Option<Instant>,
+}
+
+impl Default for HistogramState {
+    fn default() -> Self {
+        Self {
+            target_variable_id: None,
+            range: HistogramRange::default(),
+            bins: 0,
+            log_scale: false,
+            show_normal_fit: true,
+            show_percentiles: true,
+            histogram: None,
+            distribution: None,
+            computed_range: None,
+            computed_at: None,
+        }
+    }
+}
+
+impl HistogramState {
+    /// Drop the cached result so it is recomputed on the next frame
+    fn invalidate(&mut self) {
+        self.computed_at = None;
+    }
+
+    /// Whether the cached result should be recomputed for `range`
+    fn needs_compute(&self, range: Option<(f64, f64)>, collecting: bool) -> bool {
+        match self.computed_at {
+            None => true,
+            Some(at) if collecting => at.elapsed() >= LIVE_REFRESH_INTERVAL,
+            Some(_) => self.computed_range != range,
+        }
+    }
+
+    /// Time range to compute over, if one is available
+    fn time_range(&self, shared: &SharedState<'_>) -> Option<(f64, f64)> {
+        match self.range {
+            HistogramRange::VisibleWindow => Some(
+                shared
+                    .state
+                    .settings
+                    .visible_x_range(shared.ctx.display_time),
+            ),
+            // Lowest pane ID when several time-series panes have cursors set
+            HistogramRange::CursorRange => shared
+                .state
+                .topics
+                .cursor_ranges
+                .iter()
+                .min_by_key(|(id, _)| **id)
+                .map(|(_, range)| *range),
+        }
+    }
+
+    /// Recompute the histogram and statistics over `range`
+    fn compute(&mut self, shared: &SharedState<'_>, range: Option<(f64, f64)>) {
+        let values = match (self.target_variable_id, range) {
+            (Some(id), Some((start, end))) => shared
+                .state
+                .topics
+                .variable_data
+                .get(&id)
+                .map(|data| values_in_range(&data.data_points, start, end))
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        self.histogram = Histogram::from_values(&values, self.bins);
+        self.distribution = Distribution::from_values(&values);
+        self.computed_range = range;
+        self.computed_at = Some(Instant::now());
+    }
+}
+
+/// Plot height of a bin count (log10 of the count on a log scale)
+fn count_to_y(count: f64, log_scale: bool) -> f64 {
+    if log_scale {
+        count.max(10f64.powf(LOG_FLOOR)).log10()
+    } else {
+        count
+    }
+}
+
+/// Format a count axis tick
+fn format_count_tick(y: f64, log_scale: bool) -> String {
+    if log_scale {
+        // Only label whole decades
+        if y.fract().abs() > 1e-9 || y < 0.0 {
+            return String::new();
+        }
+        format!("{}", 10f64.powf(y).round())
+    } else {
+        format!("{}", y)
+    }
+}
+
+/// Statistics readout
+fn render_statistics(ui: &mut Ui, dist: &Distribution) {
+    egui::Grid::new("histogram_statistics")
+        .num_columns(10)
+        .spacing([12.0, 2.0])
+        .show(ui, |ui| {
+            for header in [
+                "Samples", "Mean", "Std Dev", "Min", "Max", "p50", "p95", "p99", "Skew", "Kurtosis",
+            ] {
+                ui.strong(header);
+            }
+            ui.end_row();
+
+            ui.label(format!("{}", dist.count));
+            for value in [
+                dist.mean,
+                dist.std_dev,
+                dist.min,
+                dist.max,
+                dist.p50,
+                dist.p95,
+                dist.p99,
+                dist.skewness,
+            ] {
+                ui.label(format!("{:.4}", value));
+            }
+            ui.label(format!("{:.3}", dist.kurtosis))
+                .on_hover_text("Excess kurtosis: 0 for a normal distribution, > 0 for heavy tails");
+            ui.end_row();
+        });
+}
+
+/// Render the histogram pane
+pub fn render(
+    state: &mut HistogramState,
+    shared: &mut SharedState<'_>,
+    ui: &mut Ui,
+) -> Vec<AppAction> {
+    use egui_plot::{Bar, BarChart, Line, LineStyle, Plot, PlotPoints, VLine};
+
+    // Toolbar
+    ui.horizontal_wrapped(|ui| {
+        ui.heading("Histogram");
+        ui.separator();
+
+        ui.label("Variable:");
+        egui::ComboBox::from_id_salt("histogram_var_selector")
+            .selected_text(
+                state
+                    .target_variable_id
+                    .and_then(|id| shared.state.config.variables.get(&id))
+                    .map(|v| v.name.as_str())
+                    .unwrap_or("Select..."),
+            )
+            .width(120.0)
+            .show_ui(ui, |ui| {
+                for var in shared.state.config.variables.values() {
+                    if var.enabled && var.show_in_graph {
+                        let is_selected = state.target_variable_id == Some(var.id);
+                        if ui.selectable_label(is_selected, &var.name).clicked() {
+                            state.target_variable_id = Some(var.id);
+                            state.invalidate();
+                        }
+                    }
+                }
+            });
+
+        ui.separator();
+
+        for range in HistogramRange::all() {
+            if ui
+                .selectable_value(&mut state.range, *range, range.display_name())
+                .changed()
+            {
+                state.invalidate();
+            }
+        }
+
+        ui.separator();
+
+        ui.label("Bins:");
+        if ui
+            .add(
+                egui::DragValue::new(&mut state.bins)
+                    .speed(1.0)
+                    .range(0..=MAX_BINS)
+                    .custom_formatter(|n, _| {
+                        if n == 0.0 {
+                            "Auto".to_string()
+                        } else {
+                            format!("{}", n)
+                        }
+                    }),
+            )
+            .on_hover_text("Number of bins (0: automatic, Freedman–Diaconis)")
+            .changed()
+        {
+            state.invalidate();
+        }
+
+        ui.checkbox(&mut state.log_scale, "Log")
+            .on_hover_text("Logarithmic count axis");
+        ui.checkbox(&mut state.show_normal_fit, "Normal Fit")
+            .on_hover_text("Overlay the normal distribution with the same mean and deviation");
+        ui.checkbox(&mut state.show_percentiles, "Percentiles")
+            .on_hover_text("Mark p50, p95 and p99");
+    });
+
+    ui.separator();
+
+    let Some(var_id) = state.target_variable_id else {
+        ui.centered_and_justified(|ui| {
+            ui.label("Select a variable to show its distribution");
+        });
+        return Vec::new();
+    };
+
+    let range = state.time_range(shared);
+    let collecting = shared.state.settings.collecting && !shared.state.settings.paused;
+    if state.needs_compute(range, collecting) {
+        state.compute(shared, range);
+    }
+    if collecting {
+        ui.ctx().request_repaint_after(LIVE_REFRESH_INTERVAL);
+    }
+
+    let Some((start, end)) = state.computed_range else {
+        ui.centered_and_justified(|ui| {
+            ui.label("Set cursors A and B on a time-series plot to choose the range");
+        });
+        return Vec::new();
+    };
+    let (Some(histogram), Some(dist)) = (&state.histogram, &state.distribution) else {
+        ui.centered_and_justified(|ui| {
+            ui.label(format!(
+                "No data for the selected variable in {:.3} s – {:.3} s",
+                start, end
+            ));
+        });
+        return Vec::new();
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(format!(
+            "{} over {:.3} s – {:.3} s, {} bins of {:.4}",
+            state.range.display_name(),
+            start,
+            end,
+            histogram.bin_count(),
+            histogram.bin_width
+        ));
+    });
+    render_statistics(ui, dist);
+    ui.separator();
+
+    let log_scale = state.log_scale;
+    let base = if log_scale { LOG_FLOOR } else { 0.0 };
+    let bars: Vec<Bar> = histogram
+        .counts
+        .iter()
+        .enumerate()
+        .filter(|(_, &count)| count > 0)
+        .map(|(i, &count)| {
+            Bar::new(
+                histogram.bin_center(i),
+                count_to_y(count as f64, log_scale) - base,
+            )
+            .base_offset(base)
+            .width(histogram.bin_width)
+        })
+        .collect();
+    let variable_name = shared
+        .state
+        .config
+        .variables
+        .get(&var_id)
+        .map(|v| v.name.clone())
+        .unwrap_or_default();
+
+    Plot::new("histogram_plot")
+        .legend(egui_plot::Legend::default())
+        .x_axis_label(variable_name)
+        .y_axis_label(if log_scale { "Count (log)" } else { "Count" })
+        .y_axis_formatter(move |mark, _range| format_count_tick(mark.value, log_scale))
+        .label_formatter(move |name, point| {
+            let count = if log_scale {
+                10f64.powf(point.y)
+            } else {
+                point.y
+            };
+            format!("{}\nx: {:.4}\ncount: {:.1}", name, point.x, count)
+        })
+        .include_y(base)
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new("Count", bars).color(Color32::from_rgb(100, 150, 255)));
+
+            if state.show_normal_fit {
+                let fit: Vec<[f64; 2]> = histogram
+                    .normal_fit(dist, FIT_POINTS)
+                    .into_iter()
+                    .map(|[x, count]| [x, count_to_y(count, log_scale)])
+                    .collect();
+                if !fit.is_empty() {
+                    plot_ui.line(
+                        Line::new("Normal Fit", PlotPoints::from(fit))
+                            .color(Color32::from_rgb(255, 160, 60))
+                            .width(1.5),
+                    );
+                }
+            }
+
+            if state.show_percentiles {
+                for (name, value) in [("p50", dist.p50), ("p95", dist.p95), ("p99", dist.p99)] {
+                    plot_ui.vline(
+                        VLine::new(name, value)
+                            .color(Color32::GRAY)
+                            .style(LineStyle::dashed_loose()),
+                    );
+                }
+            }
+        });
+
+    Vec::new()
+}
+
+impl Pane for HistogramState {
+    fn kind(&self) -> PaneKind {
+        PaneKind::Histogram
+    }
+
+    fn render(&mut self, shared: &mut SharedState, ui: &mut Ui) -> Vec<AppAction> {
+        render(self, shared, ui)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_to_y() {
+        assert_eq!(count_to_y(100.0, false), 100.0);
+        assert_eq!(count_to_y(100.0, true), 2.0);
+        // Empty counts clamp to the floor instead of -inf
+        assert_eq!(count_to_y(0.0, true), LOG_FLOOR);
+    }
+
+    #[test]
+    fn test_format_count_tick() {
+        assert_eq!(format_count_tick(2.0, true), "100");
+        assert_eq!(format_count_tick(1.5, true), "");
+        assert_eq!(format_count_tick(-0.5, true), "");
+        assert_eq!(format_count_tick(25.0, false), "25");
+    }
+
+    #[test]
+    fn test_needs_compute() {
+        let mut state = HistogramState::default();
+        assert!(state.needs_compute(Some((0.0, 1.0)), false));
+
+        state.computed_at = Some(Instant::now());
+        state.computed_range = Some((0.0, 1.0));
+        assert!(!state.needs_compute(Some((0.0, 1.0)), false));
+        // A moved range recomputes right away when not collecting
+        assert!(state.needs_compute(Some((0.5, 1.5)), false));
+        // While collecting, recompute at the refresh interval only
+        assert!(!state.needs_compute(Some((0.5, 1.5)), true));
+
+        state.invalidate();
+        assert!(state.needs_compute(Some((0.0, 1.0)), true));
+    }
+}
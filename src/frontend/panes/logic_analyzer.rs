@@ -0,0 +1,565 @@
+//! Logic Analyzer pane - Digital timing view of bits and bus values
+//!
+//! Expands integer variables (status registers, flag words) into lanes: one
+//! lane per bit drawn as a logic level, or multi-bit fields drawn as bus
+//! values in hex. Bitfields declared in the DWARF type of the variable's
+//! storage give named lanes; otherwise lanes are numbered by bit.
+//!
+//! The time axis follows the time-series plots. Clicking sets the A/B
+//! cursors (snapping to nearby edges) to measure edge-to-edge time, and the
+//! pulse under the pointer is measured automatically.
+
+use egui::{Color32, Stroke, Ui};
+use egui_plot::PlotPoint;
+
+use crate::analysis::{lane_bits, lane_segments, nearest_edge, pulse_at, LaneField, Segment};
+use crate::backend::{ElfInfo, SnapshotNode};
+use crate::frontend::pane_trait::Pane;
+use crate::frontend::plot::PlotCursor;
+use crate::frontend::state::{AppAction, SharedState};
+use crate::frontend::workspace::PaneKind;
+use crate::types::Variable;
+
+/// Vertical distance between lane baselines
+const LANE_PITCH: f64 = 1.0;
+
+/// Height of the high level and of bus outlines within a lane
+const LANE_HEIGHT: f64 = 0.7;
+
+/// Distance (in points) within which cursors snap to an edge
+const SNAP_DISTANCE: f32 = 8.0;
+
+/// Approximate width (in points) of one character of a bus value label
+const LABEL_CHAR_WIDTH: f64 = 7.0;
+
+/// A lane: one field of one variable
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogicLane {
+    /// Variable the field is extracted from
+    pub variable_id: u32,
+    /// Extracted bits
+    pub field: LaneField,
+}
+
+/// State for the Logic Analyzer pane
+pub struct LogicAnalyzerState {
+    /// Lanes from top to bottom
+    pub lanes: Vec<LogicLane>,
+    /// Measurement cursors (time only)
+    pub cursor: PlotCursor,
+    /// Snap cursors to the nearest edge of the lane under the pointer
+    pub snap_to_edges: bool,
+    /// Custom field being defined in the Add menu
+    custom_field: LaneField,
+}
+
+impl Default for LogicAnalyzerState {
+    fn default() -> Self {
+        Self {
+            lanes: Vec::new(),
+            cursor: PlotCursor::new(true),
+            snap_to_edges: true,
+            custom_field: LaneField::new("field", 0, 4),
+        }
+    }
+}
+
+impl LogicAnalyzerState {
+    /// Append lanes for a variable, skipping ones already shown
+    fn add_lanes(&mut self, variable_id: u32, fields: impl IntoIterator<Item = LaneField>) {
+        for field in fields {
+            let lane = LogicLane { variable_id, field };
+            if !self.lanes.contains(&lane) {
+                self.lanes.push(lane);
+            }
+        }
+    }
+
+    /// Lane index at a plot Y coordinate
+    fn lane_at(&self, y: f64) -> Option<usize> {
+        let from_bottom = (y / LANE_PITCH).floor();
+        if from_bottom < 0.0 {
+            return None;
+        }
+        let from_bottom = from_bottom as usize;
+        (from_bottom < self.lanes.len()).then(|| self.lanes.len() - 1 - from_bottom)
+    }
+}
+
+/// Baseline Y coordinate of lane `index` of `count` (first lane on top)
+fn lane_base(index: usize, count: usize) -> f64 {
+    (count - 1 - index) as f64 * LANE_PITCH
+}
+
+/// Label of a lane: the variable name plus the field name
+fn lane_label(variable: &str, field: &LaneField) -> String {
+    if field.name.is_empty() {
+        variable.to_string()
+    } else if field.name.starts_with('[') {
+        format!("{}{}", variable, field.name)
+    } else {
+        format!("{}.{}", variable, field.name)
+    }
+}
+
+/// Bus lane spanning all bits of a variable
+fn whole_value(bits: u32) -> LaneField {
+    LaneField::new("", 0, bits)
+}
+
+/// Bitfields declared in the DWARF type around a variable's storage
+fn dwarf_fields(info: &ElfInfo, variable: &Variable) -> Vec<LaneField> {
+    let Some(symbol) = info.find_variable_containing(variable.address) else {
+        return Vec::new();
+    };
+    let Some(handle) = info.symbol_type_handle(symbol) else {
+        return Vec::new();
+    };
+    let layout = SnapshotNode::build(info.type_table(), handle.id(), "", info.endianness());
+    layout
+        .bit_fields_within(
+            variable.address - symbol.address,
+            variable.var_type.size_bytes() as u64,
+            info.endianness(),
+        )
+        .into_iter()
+        .map(|(name, shift, width)| LaneField::new(name, shift, width))
+        .collect()
+}
+
+/// Format a duration in seconds with a readable unit
+fn format_time(secs: f64) -> String {
+    let abs = secs.abs();
+    if abs >= 1.0 {
+        format!("{:.4} s", secs)
+    } else if abs >= 1e-3 {
+        format!("{:.3} ms", secs * 1e3)
+    } else {
+        format!("{:.1} µs", secs * 1e6)
+    }
+}
+
+/// Draw one bit lane as a logic level trace
+fn draw_bit_lane(
+    plot_ui: &mut egui_plot::PlotUi,
+    name: &str,
+    segments: &[Segment],
+    base: f64,
+    color: Color32,
+) {
+    use egui_plot::{Line, PlotPoints};
+
+    // One line per run of contiguous segments; pauses leave holes
+    let mut run: Vec<[f64; 2]> = Vec::new();
+    for segment in segments {
+        if !segment.starts_at_edge && !run.is_empty() {
+            plot_ui.line(Line::new(name, PlotPoints::from(std::mem::take(&mut run))).color(color));
+        }
+        let y = base + if segment.value != 0 { LANE_HEIGHT } else { 0.0 };
+        run.push([segment.start, y]);
+        run.push([segment.end, y]);
+    }
+    if !run.is_empty() {
+        plot_ui.line(Line::new(name, PlotPoints::from(run)).color(color));
+    }
+}
+
+/// Draw one bus lane as hexagonal value cells with hex labels
+fn draw_bus_lane(
+    plot_ui: &mut egui_plot::PlotUi,
+    name: &str,
+    field: &LaneField,
+    segments: &[Segment],
+    base: f64,
+    color: Color32,
+    points_per_sec: f64,
+) {
+    use egui_plot::{PlotPoints, Polygon, Text};
+
+    let mid = base + LANE_HEIGHT / 2.0;
+    let top = base + LANE_HEIGHT;
+    // Slanted cell ends about 3 points wide
+    let slant = 3.0 / points_per_sec.max(f64::MIN_POSITIVE);
+    for segment in segments {
+        let d = slant.min(segment.duration() / 2.0);
+        let outline = vec![
+            [segment.start, mid],
+            [segment.start + d, top],
+            [segment.end - d, top],
+            [segment.end, mid],
+            [segment.end - d, base],
+            [segment.start + d, base],
+        ];
+        plot_ui.polygon(
+            Polygon::new(name, PlotPoints::from(outline))
+                .stroke(Stroke::new(1.0, color))
+                .fill_color(color.gamma_multiply(0.15)),
+        );
+
+        let label = field.format_value(segment.value);
+        if segment.duration() * points_per_sec > (label.len() as f64 + 1.0) * LABEL_CHAR_WIDTH {
+            plot_ui.text(
+                Text::new(
+                    name,
+                    PlotPoint::new((segment.start + segment.end) / 2.0, mid),
+                    label,
+                )
+                .color(color),
+            );
+        }
+    }
+}
+
+/// Lanes menu: reorder and remove lanes
+fn render_lane_menu(state: &mut LogicAnalyzerState, labels: &[String], ui: &mut Ui) {
+    let mut move_up = None;
+    let mut remove = None;
+    for (i, label) in labels.iter().enumerate() {
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(i > 0, egui::Button::new("⏶").small())
+                .clicked()
+            {
+                move_up = Some(i);
+            }
+            if ui.small_button("✖").on_hover_text("Remove lane").clicked() {
+                remove = Some(i);
+            }
+            ui.label(label);
+        });
+    }
+    if let Some(i) = move_up {
+        state.lanes.swap(i - 1, i);
+    }
+    if let Some(i) = remove {
+        state.lanes.remove(i);
+    }
+    if !state.lanes.is_empty() {
+        ui.separator();
+        if ui.button("Remove All").clicked() {
+            state.lanes.clear();
+            ui.close();
+        }
+    }
+}
+
+/// Render the logic analyzer pane
+pub fn render(
+    state: &mut LogicAnalyzerState,
+    shared: &mut SharedState<'_>,
+    ui: &mut Ui,
+) -> Vec<AppAction> {
+    use egui_plot::{GridMark, Plot, PlotBounds, VLine};
+
+    let variables = &shared.state.config.variables;
+    let labels: Vec<String> = state
+        .lanes
+        .iter()
+        .map(|lane| {
+            let name = variables
+                .get(&lane.variable_id)
+                .map(|v| v.name.as_str())
+                .unwrap_or("?");
+            lane_label(name, &lane.field)
+        })
+        .collect();
+
+    // Toolbar
+    ui.horizontal_wrapped(|ui| {
+        ui.heading("Logic Analyzer");
+        ui.separator();
+
+        ui.menu_button("➕ Add", |ui| {
+            let candidates = variables
+                .values()
+                .filter(|v| v.enabled)
+                .filter_map(|v| lane_bits(v.var_type).map(|bits| (v, bits)));
+            let mut any = false;
+            for (var, bits) in candidates {
+                any = true;
+                ui.menu_button(&var.name, |ui| {
+                    let named = shared
+                        .ctx
+                        .elf_info
+                        .map(|info| dwarf_fields(info, var))
+                        .unwrap_or_default();
+                    if !named.is_empty()
+                        && ui
+                            .button(format!("Named Fields ({})", named.len()))
+                            .on_hover_text("Bitfields declared in the debug info")
+                            .clicked()
+                    {
+                        state.add_lanes(var.id, named);
+                        ui.close();
+                    }
+                    if ui.button(format!("All Bits ({})", bits)).clicked() {
+                        state.add_lanes(var.id, (0..bits).map(LaneField::bit));
+                        ui.close();
+                    }
+                    if bits > 1 && ui.button("Bus Value").clicked() {
+                        state.add_lanes(var.id, [whole_value(bits)]);
+                        ui.close();
+                    }
+
+                    ui.separator();
+                    ui.label("Custom field:");
+                    let field = &mut state.custom_field;
+                    ui.text_edit_singleline(&mut field.name);
+                    ui.horizontal(|ui| {
+                        ui.label("Bit:");
+                        ui.add(egui::DragValue::new(&mut field.shift).range(0..=bits - 1));
+                        ui.label("Width:");
+                        ui.add(egui::DragValue::new(&mut field.width).range(1..=bits));
+                    });
+                    if ui.button("Add Field").clicked() {
+                        let field = LaneField::new(
+                            field.name.clone(),
+                            field.shift,
+                            field.width.min(bits - field.shift.min(bits - 1)),
+                        );
+                        state.add_lanes(var.id, [field]);
+                        ui.close();
+                    }
+                });
+            }
+            if !any {
+                ui.label("No integer variables");
+            }
+        });
+
+        ui.menu_button(format!("Lanes ({})", state.lanes.len()), |ui| {
+            render_lane_menu(state, &labels, ui);
+        });
+
+        ui.separator();
+
+        ui.checkbox(&mut state.snap_to_edges, "Snap")
+            .on_hover_text("Snap cursors to the nearest edge");
+        if ui.button("Clear Cursors").clicked() {
+            state.cursor.clear_cursors();
+        }
+        if let (Some(a), Some(dt)) = (state.cursor.cursor_a, state.cursor.time_delta()) {
+            ui.separator();
+            ui.label(format!("A: {:.6} s", a.x));
+            ui.label(format!("Δt: {}", format_time(dt)));
+            if dt > 0.0 {
+                ui.label(format!("1/Δt: {:.3} Hz", 1.0 / dt));
+            }
+        }
+    });
+
+    ui.separator();
+
+    if state.lanes.is_empty() {
+        ui.centered_and_justified(|ui| {
+            ui.label("Add an integer variable to show its bits");
+        });
+        return Vec::new();
+    }
+
+    // Segments of every lane over the visible window
+    let (start, end) = shared
+        .state
+        .settings
+        .visible_x_range(shared.ctx.display_time);
+    let topics = &shared.state.topics;
+    let lane_segments: Vec<Vec<Segment>> = state
+        .lanes
+        .iter()
+        .map(|lane| {
+            let Some(data) = topics.variable_data.get(&lane.variable_id) else {
+                return Vec::new();
+            };
+            // Start one sample early so the value before the window carries in
+            let first = data
+                .data_points
+                .partition_point(|p| p.timestamp.as_secs_f64() < start)
+                .saturating_sub(1);
+            lane_segments(data.data_points.range(first..), &lane.field, start, end)
+        })
+        .collect();
+    let colors: Vec<Color32> = state
+        .lanes
+        .iter()
+        .map(|lane| {
+            variables
+                .get(&lane.variable_id)
+                .map(|v| Color32::from_rgb(v.color[0], v.color[1], v.color[2]))
+                .unwrap_or(Color32::GRAY)
+        })
+        .collect();
+
+    let count = state.lanes.len();
+    let axis_labels = labels.clone();
+    let points_per_sec = ui.available_width() as f64 / (end - start).max(f64::MIN_POSITIVE);
+
+    let cursor_a = state.cursor.cursor_a;
+    let cursor_b = state.cursor.cursor_b;
+    let response = Plot::new("logic_analyzer_plot")
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .show_grid([true, false])
+        .x_axis_label("Time (s)")
+        .y_axis_min_width(80.0)
+        .y_grid_spacer(move |_input| {
+            (0..count)
+                .map(|i| GridMark {
+                    value: lane_base(i, count) + LANE_HEIGHT / 2.0,
+                    step_size: LANE_PITCH,
+                })
+                .collect()
+        })
+        .y_axis_formatter(move |mark, _range| {
+            let from_bottom = (mark.value / LANE_PITCH).floor();
+            if from_bottom < 0.0 || from_bottom as usize >= count {
+                return String::new();
+            }
+            axis_labels[count - 1 - from_bottom as usize].clone()
+        })
+        .label_formatter(|_name, point| format!("{:.6} s", point.x))
+        .show(ui, |plot_ui| {
+            plot_ui.set_plot_bounds(PlotBounds::from_min_max(
+                [start, -0.3 * LANE_PITCH],
+                [end, count as f64 * LANE_PITCH],
+            ));
+
+            for (i, lane) in state.lanes.iter().enumerate() {
+                let base = lane_base(i, count);
+                if lane.field.is_bus() {
+                    draw_bus_lane(
+                        plot_ui,
+                        &labels[i],
+                        &lane.field,
+                        &lane_segments[i],
+                        base,
+                        colors[i],
+                        points_per_sec,
+                    );
+                } else {
+                    draw_bit_lane(plot_ui, &labels[i], &lane_segments[i], base, colors[i]);
+                }
+            }
+
+            for (name, cursor, color) in [
+                ("Cursor A", cursor_a, Color32::from_rgb(255, 100, 100)),
+                ("Cursor B", cursor_b, Color32::from_rgb(255, 255, 100)),
+            ] {
+                if let Some(pos) = cursor {
+                    plot_ui.vline(VLine::new(name, pos.x).color(color).width(1.5));
+                }
+            }
+        });
+
+    // Pointer: snap to the nearest edge of the hovered lane
+    let hovered = response.response.hover_pos().map(|pos| {
+        let value = response.transform.value_from_position(pos);
+        let lane = state.lane_at(value.y);
+        let tolerance = SNAP_DISTANCE as f64 / points_per_sec;
+        let snapped = lane
+            .filter(|_| state.snap_to_edges)
+            .and_then(|i| nearest_edge(&lane_segments[i], value.x, tolerance))
+            .unwrap_or(value.x);
+        (lane, value.x, PlotPoint::new(snapped, value.y))
+    });
+    state
+        .cursor
+        .update_position(hovered.map(|(_, _, point)| point));
+
+    if response.response.clicked() {
+        if state.cursor.cursor_a.is_none() || state.cursor.cursor_b.is_some() {
+            state.cursor.clear_cursors();
+            state.cursor.set_cursor_a();
+        } else {
+            state.cursor.set_cursor_b();
+        }
+    }
+    if response.response.secondary_clicked() {
+        state.cursor.clear_cursors();
+    }
+
+    // Tooltip: value and pulse measurement of the hovered lane
+    if let Some((Some(lane), time, _)) = hovered {
+        if let Some(pulse) = pulse_at(&lane_segments[lane], time) {
+            let field = &state.lanes[lane].field;
+            let mut lines = vec![
+                labels[lane].clone(),
+                format!("T: {:.6} s", time),
+                format!("Value: {}", field.format_value(pulse.value)),
+            ];
+            if let Some(width) = pulse.width {
+                lines.push(format!("Width: {}", format_time(width)));
+            }
+            if let Some(period) = pulse.period {
+                lines.push(format!(
+                    "Period: {} ({:.3} Hz)",
+                    format_time(period),
+                    1.0 / period
+                ));
+            }
+            response.response.on_hover_text(lines.join("\n"));
+        }
+    }
+
+    if shared.state.settings.collecting && !shared.state.settings.paused {
+        ui.ctx().request_repaint();
+    }
+
+    Vec::new()
+}
+
+impl Pane for LogicAnalyzerState {
+    fn kind(&self) -> PaneKind {
+        PaneKind::LogicAnalyzer
+    }
+
+    fn render(&mut self, shared: &mut SharedState, ui: &mut Ui) -> Vec<AppAction> {
+        render(self, shared, ui)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lane_layout() {
+        let mut state = LogicAnalyzerState::default();
+        state.add_lanes(1, (0..3).map(LaneField::bit));
+        // Adding the same lanes again does not duplicate them
+        state.add_lanes(1, [LaneField::bit(0), whole_value(8)]);
+        assert_eq!(state.lanes.len(), 4);
+
+        // First lane on top
+        assert_eq!(lane_base(0, 4), 3.0);
+        assert_eq!(lane_base(3, 4), 0.0);
+        assert_eq!(state.lane_at(3.5), Some(0));
+        assert_eq!(state.lane_at(0.2), Some(3));
+        assert_eq!(state.lane_at(-0.2), None);
+        assert_eq!(state.lane_at(4.1), None);
+    }
+
+    #[test]
+    fn test_lane_label() {
+        assert_eq!(lane_label("STATUS", &LaneField::bit(3)), "STATUS[3]");
+        assert_eq!(
+            lane_label("STATUS", &LaneField::new("ready", 0, 1)),
+            "STATUS.ready"
+        );
+        assert_eq!(lane_label("STATUS", &whole_value(32)), "STATUS");
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(1.5), "1.5000 s");
+        assert_eq!(format_time(0.0125), "12.500 ms");
+        assert_eq!(format_time(0.000_004), "4.0 µs");
+    }
+}
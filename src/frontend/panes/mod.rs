@@ -4,15 +4,35 @@
 //! Panes return Vec<AppAction> instead of mutating state directly.
 
 pub mod fft_view;
+pub mod filter_designer;
+pub mod frequency_response;
+pub mod histogram;
+pub mod logic_analyzer;
 pub mod recorder;
+pub mod recording_compare;
+pub mod spectrogram;
+pub mod step_response;
+pub mod struct_inspector;
+pub mod text_log;
 pub mod time_series;
 pub mod variable_browser;
 pub mod variable_list;
 pub mod watcher;
+pub mod xy_plot;
 
 pub use fft_view::FftViewState;
+pub use filter_designer::FilterDesignerState;
+pub use frequency_response::FrequencyResponseState;
+pub use histogram::HistogramState;
+pub use logic_analyzer::LogicAnalyzerState;
 pub use recorder::RecorderPaneState;
+pub use recording_compare::RecordingCompareState;
+pub use spectrogram::SpectrogramState;
+pub use step_response::StepResponseState;
+pub use struct_inspector::StructInspectorState;
+pub use text_log::TextLogState;
 pub use time_series::TimeSeriesState;
 pub use variable_browser::VariableBrowserState;
 pub use variable_list::VariableListState;
 pub use watcher::WatcherState;
+pub use xy_plot::XyPlotState;
@@ -1,27 +1,31 @@
 //! Session Capture pane — combines recording and export functionality.
 //!
 //! Provides session recording controls (arm/start/stop), status display,
-//! saved recordings list, playback controls, and file export controls.
+//! saved recordings list with events and edit tools, playback controls, and
+//! file export controls.
 
-use std::collections::HashMap;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use egui::Ui;
 
+use crate::frontend::dialogs::{
+    show_dialog, ImportWizardAction, ImportWizardContext, ImportWizardDialog, ImportWizardState,
+};
 use crate::frontend::pane_trait::Pane;
+use crate::frontend::recording::RecordingRequest;
 use crate::frontend::state::{AppAction, SharedState};
 use crate::frontend::workspace::PaneKind;
+use crate::session::columnar::{self, ColumnarFormat, ColumnarOptions};
+use crate::session::events::{
+    insert_event, save_events_csv, sort_events, MarkerType, RecordingEvent,
+};
+use crate::session::reconvert::{match_variables, reconvert};
 use crate::session::types::{RecordedFrame, RecordedValue, SessionMetadata, SessionRecording};
 use crate::session::{SessionPlayer, SessionState};
 
-/// Export layout modes (formerly from pipeline::nodes::exporter_sink)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ExportLayout {
-    /// Long format: one row per sample (timestamp, var_name, value)
-    Long,
-    /// Wide format: one row per timestamp, columns for each variable
-    Wide,
-}
+pub use crate::session::ExportLayout;
 
 /// Which value to export (formerly from pipeline::nodes::exporter_sink)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -68,8 +72,21 @@ pub struct RecorderPaneState {
     pub max_frames: usize,
     /// Sample interval in milliseconds.
     pub sample_interval_ms: u64,
+    /// Whether recordings are streamed to a file instead of kept in memory.
+    pub record_to_file: bool,
+    /// Session file to stream recordings to.
+    pub output_path: String,
     /// Playback controller.
     pub session_player: SessionPlayer,
+    /// Whether playback is shown in every pane in place of live data.
+    pub drive_panes: bool,
+    /// Playback time up to which data has been pushed to the panes.
+    pub playback_fed: Option<Duration>,
+    /// Recorded variable ID to current variable ID, for the pushed data.
+    pub playback_ids: HashMap<u32, u32>,
+    /// Whether the loaded recording has the current converters re-applied
+    /// and is not yet in the saved list.
+    pub reconverted: bool,
 
     // --- Snapshot fields ---
     /// Accumulated snapshot frames.
@@ -88,6 +105,34 @@ pub struct RecorderPaneState {
     pub export_layout: ExportLayout,
     /// Per-variable value choice for wide export. Key: VarId raw u32.
     pub value_choices: HashMap<u32, ValueChoice>,
+    /// Index of the saved recording selected for file export.
+    pub export_recording: usize,
+
+    // --- Events fields ---
+    /// Index of the saved recording whose events are being edited.
+    pub events_recording: Option<usize>,
+
+    // --- Edit fields ---
+    /// Index of the saved recording open in the edit tools.
+    pub edit_recording: Option<usize>,
+    /// Start of the range to trim to, in seconds.
+    pub trim_start: f64,
+    /// End of the range to trim to, in seconds.
+    pub trim_end: f64,
+    /// Saved recordings to append when merging, in order of selection.
+    pub merge_with: Vec<usize>,
+    /// Gap between merged recordings, in milliseconds.
+    pub merge_gap_ms: f64,
+    /// IDs of the variables to drop.
+    pub drop_ids: HashSet<u32>,
+    /// Error from the last edit.
+    pub edit_error: Option<String>,
+
+    // --- Import fields ---
+    /// Whether the log import wizard is open.
+    pub import_wizard_open: bool,
+    /// Log import wizard state.
+    pub import_wizard_state: ImportWizardState,
 }
 
 impl Default for RecorderPaneState {
@@ -97,7 +142,13 @@ impl Default for RecorderPaneState {
             session_name: String::new(),
             max_frames: 0,
             sample_interval_ms: 10,
+            record_to_file: false,
+            output_path: String::new(),
             session_player: SessionPlayer::new(),
+            drive_panes: true,
+            playback_fed: None,
+            playback_ids: HashMap::new(),
+            reconverted: false,
             snapshot_frames: Vec::new(),
             snapshot_start_time: None,
             snapshot_tag: String::new(),
@@ -105,6 +156,17 @@ impl Default for RecorderPaneState {
             export_format: ExportFormat::Csv,
             export_layout: ExportLayout::Long,
             value_choices: HashMap::new(),
+            export_recording: 0,
+            events_recording: None,
+            edit_recording: None,
+            trim_start: 0.0,
+            trim_end: 0.0,
+            merge_with: Vec::new(),
+            merge_gap_ms: 10.0,
+            drop_ids: HashSet::new(),
+            edit_error: None,
+            import_wizard_open: false,
+            import_wizard_state: ImportWizardState::default(),
         }
     }
 }
@@ -131,9 +193,28 @@ pub fn render(
         CaptureTab::Export => render_export_tab(state, shared, ui, &mut actions),
     }
 
+    render_import_wizard(state, shared, ui);
+
     actions
 }
 
+fn render_import_wizard(state: &mut RecorderPaneState, shared: &mut SharedState<'_>, ui: &Ui) {
+    if let Some(ImportWizardAction::Import(recording)) = show_dialog::<ImportWizardDialog>(
+        ui.ctx(),
+        &mut state.import_wizard_open,
+        &mut state.import_wizard_state,
+        ImportWizardContext,
+    ) {
+        tracing::info!(
+            "Imported {} ({} frames)",
+            recording.metadata.name,
+            recording.frame_count()
+        );
+        load_recording(state, recording.clone());
+        shared.state.topics.completed_recordings.push(recording);
+    }
+}
+
 // ============================================================================
 // Record tab
 // ============================================================================
@@ -152,8 +233,12 @@ fn render_record_tab(
     render_snapshot_controls(state, shared, ui);
     ui.separator();
 
+    // --- On-disk session history ---
+    render_history_controls(state, shared, ui);
+    ui.separator();
+
     // --- Playback controls ---
-    render_playback_controls(state, ui);
+    render_playback_controls(state, shared, ui);
     ui.separator();
 
     // --- Saved recordings list ---
@@ -164,7 +249,7 @@ fn render_recording_controls(
     state: &mut RecorderPaneState,
     shared: &SharedState<'_>,
     ui: &mut Ui,
-    _actions: &mut Vec<AppAction>,
+    actions: &mut Vec<AppAction>,
 ) {
     let recorder_state = shared.state.topics.recorder_state;
 
@@ -205,10 +290,57 @@ fn render_recording_controls(
                 );
             });
 
-            if ui.button("Start Recording").clicked() {
-                // TODO: Implement recording with new backend architecture
-                // Recording functionality needs to be re-implemented without pipeline nodes
-                ui.label("Recording not yet implemented in new architecture");
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut state.record_to_file, "Stream to file")
+                    .on_hover_text(
+                        "Write frames to a session file as they arrive instead of keeping \
+                         them in memory",
+                    );
+                if state.record_to_file {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.output_path)
+                            .hint_text("session.dvs")
+                            .desired_width(150.0),
+                    );
+                    if ui.button("Browse...").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_title("Record to Session File")
+                            .add_filter("DataVis Session", &["dvs"])
+                            .save_file()
+                        {
+                            state.output_path = path.display().to_string();
+                        }
+                    }
+                }
+            });
+
+            let ready = !state.record_to_file || !state.output_path.is_empty();
+            if ui
+                .add_enabled(ready, egui::Button::new("Start Recording"))
+                .clicked()
+            {
+                let name = if state.session_name.is_empty() {
+                    "Recording".to_string()
+                } else {
+                    state.session_name.clone()
+                };
+                actions.push(AppAction::StartRecording(RecordingRequest {
+                    name,
+                    max_frames: state.max_frames,
+                    sample_interval: Duration::from_millis(state.sample_interval_ms),
+                    output: state
+                        .record_to_file
+                        .then(|| PathBuf::from(&state.output_path)),
+                }));
+            }
+
+            if let Some(path) = &shared.state.topics.recorder_output {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Recorded to {}", path.display()));
+                    if ui.button("Load").clicked() {
+                        load_file(state, path);
+                    }
+                });
             }
         }
         SessionState::Recording => {
@@ -216,13 +348,16 @@ fn render_recording_controls(
                 ui.colored_label(egui::Color32::from_rgb(255, 100, 100), "● REC");
                 ui.label(format!("{} frames", shared.state.topics.recorder_frame_count));
             });
+            if state.record_to_file {
+                ui.label(format!("Writing to {}", state.output_path));
+            }
 
             ui.horizontal(|ui| {
                 if ui.button("Stop").clicked() {
-                    // TODO: Implement stop recording
+                    actions.push(AppAction::StopRecording);
                 }
                 if ui.button("Cancel").clicked() {
-                    // TODO: Implement cancel recording
+                    actions.push(AppAction::CancelRecording);
                 }
             });
         }
@@ -282,6 +417,58 @@ fn render_snapshot_controls(
     }
 }
 
+fn render_history_controls(state: &RecorderPaneState, shared: &mut SharedState<'_>, ui: &mut Ui) {
+    ui.label("Session History");
+
+    let histories: Vec<_> = shared
+        .state
+        .topics
+        .variable_data
+        .values()
+        .filter_map(|data| data.history.as_ref().map(|h| (&data.variable, h)))
+        .collect();
+    if histories.is_empty() {
+        ui.label("Full history is off. Enable it in Collection Settings.");
+        return;
+    }
+
+    let points: u64 = histories.iter().map(|(_, h)| h.len()).sum();
+    let disk_size: u64 = histories.iter().map(|(_, h)| h.disk_size()).sum();
+    ui.label(format!(
+        "{} points kept ({} on disk)",
+        points,
+        crate::config::format_file_size(disk_size)
+    ));
+
+    if ui
+        .add_enabled(points > 0, egui::Button::new("Save History as Recording"))
+        .on_hover_text("Add everything collected this session to the saved recordings")
+        .clicked()
+    {
+        let name = if state.session_name.is_empty() {
+            "Session History"
+        } else {
+            state.session_name.as_str()
+        };
+        let mut recording = crate::session::recording_from_history(name, histories);
+        // History shares the plots' time base, so their markers carry over
+        recording.metadata.events = plot_markers(shared);
+        shared.state.topics.completed_recordings.push(recording);
+    }
+}
+
+/// Markers of all time-series panes as recording events, sorted by time
+fn plot_markers(shared: &SharedState<'_>) -> Vec<RecordingEvent> {
+    let mut events: Vec<RecordingEvent> = Vec::new();
+    for event in shared.state.topics.plot_markers.values().flatten() {
+        if !events.contains(event) {
+            events.push(event.clone());
+        }
+    }
+    sort_events(&mut events);
+    events
+}
+
 /// Take a single snapshot of all current variable values.
 fn take_snapshot(state: &mut RecorderPaneState, shared: &SharedState<'_>) {
     let now = Instant::now();
@@ -320,7 +507,11 @@ fn build_snapshot_recording(state: &RecorderPaneState) -> SessionRecording {
     recording
 }
 
-fn render_playback_controls(state: &mut RecorderPaneState, ui: &mut Ui) {
+fn render_playback_controls(
+    state: &mut RecorderPaneState,
+    shared: &mut SharedState<'_>,
+    ui: &mut Ui,
+) {
     ui.label("Playback");
 
     if state.session_player.has_recording() {
@@ -391,24 +582,228 @@ fn render_playback_controls(state: &mut RecorderPaneState, ui: &mut Ui) {
                 state.session_player.set_loop_playback(loop_enabled);
             }
         });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut state.drive_panes, "Show in all panes")
+                .on_hover_text("Play the recording through every pane in place of live data");
+            if ui
+                .button("Re-apply Converters")
+                .on_hover_text(
+                    "Re-evaluate the recorded raw values with the current converter scripts",
+                )
+                .clicked()
+            {
+                reapply_converters(state, shared);
+            }
+            if state.reconverted
+                && ui
+                    .button("Keep as Recording")
+                    .on_hover_text("Add the re-converted recording to the saved recordings")
+                    .clicked()
+            {
+                if let Some(recording) = state.session_player.recording() {
+                    shared
+                        .state
+                        .topics
+                        .completed_recordings
+                        .push(recording.clone());
+                }
+                state.reconverted = false;
+            }
+        });
+        if state.drive_panes {
+            let pane = shared.ctx.current_pane_id.map(|id| id.0);
+            if shared.state.settings.collecting {
+                ui.weak("Panes show live data while collecting.");
+            } else if shared
+                .state
+                .topics
+                .playback
+                .as_ref()
+                .is_some_and(|p| Some(p.pane) != pane)
+            {
+                ui.weak("Another Session Capture pane is playing in the panes.");
+            }
+        }
+
+        render_playback_events(state, current, ui);
     } else {
         ui.label("No recording loaded.");
         if ui.button("Load from file...").clicked() {
             if let Some(path) = rfd::FileDialog::new()
                 .set_title("Load Session Recording")
-                .add_filter("JSON Session", &["json"])
+                .add_filter("DataVis Session", &["dvs", "json"])
+                .add_filter("MCAP", &["mcap"])
+                .add_filter("MDF4", &["mf4", "mdf"])
                 .pick_file()
             {
-                match SessionRecording::load_from_file(&path) {
-                    Ok(recording) => {
-                        state.session_player.load(recording);
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to load session: {}", e);
-                    }
-                }
+                load_file(state, &path);
+            }
+        }
+    }
+}
+
+/// Load a recording into the player, replacing the one shown in the panes
+fn load_recording(state: &mut RecorderPaneState, recording: SessionRecording) {
+    state.session_player.load(recording);
+    state.playback_fed = None;
+    state.reconverted = false;
+}
+
+/// Load a session file into the player, reading frames as playback needs them
+fn load_file(state: &mut RecorderPaneState, path: &Path) {
+    match state.session_player.load_file(path) {
+        Ok(()) => {
+            state.playback_fed = None;
+            state.reconverted = false;
+        }
+        Err(e) => tracing::error!("Failed to load session: {}", e),
+    }
+}
+
+/// Replace the loaded recording by a copy with the current converters applied
+fn reapply_converters(state: &mut RecorderPaneState, shared: &SharedState<'_>) {
+    let recording = match state.session_player.read_recording() {
+        Ok(Some(recording)) => recording,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!("Failed to read session: {}", e);
+            return;
+        }
+    };
+    let variables: Vec<_> = shared.state.config.variables.values().cloned().collect();
+    let reconverted = reconvert(&recording, &variables);
+
+    // Keep the playback position and state
+    let time = state.session_player.current_time();
+    let playing = state.session_player.is_playing();
+    load_recording(state, reconverted);
+    state.session_player.seek(time);
+    if playing {
+        state.session_player.play();
+    }
+    state.reconverted = true;
+}
+
+/// Push the played data to the panes in place of the live buffers
+///
+/// Runs every frame, whether or not the pane is visible. Panes show live
+/// data again when the recording is unloaded, "Show in all panes" is turned
+/// off or collection starts.
+fn drive_playback(
+    state: &mut RecorderPaneState,
+    shared: &mut SharedState<'_>,
+    ctx: &egui::Context,
+) {
+    let Some(pane) = shared.ctx.current_pane_id.map(|id| id.0) else {
+        return;
+    };
+    let topics = &mut shared.state.topics;
+    let owner = topics.playback.as_ref().map(|p| p.pane);
+    if !state.drive_panes
+        || !state.session_player.has_recording()
+        || shared.state.settings.collecting
+    {
+        if owner == Some(pane) {
+            topics.end_playback();
+        }
+        state.playback_fed = None;
+        return;
+    }
+    match owner {
+        Some(owner) if owner != pane => return,
+        Some(_) => {}
+        None => {
+            topics.begin_playback(pane);
+            state.playback_fed = None;
+        }
+    }
+
+    let playing = state.session_player.advance();
+    let now = state.session_player.current_time();
+    let data = match state.playback_fed {
+        Some(fed) if fed == now => HashMap::new(),
+        Some(fed) if fed < now => state.session_player.data_between(fed, now),
+        // Started, seeked back or looped: show everything up to now
+        _ => {
+            for data in topics.variable_data.values_mut() {
+                data.clear();
             }
+            let recorded = state
+                .session_player
+                .metadata()
+                .map(|m| m.variables.as_slice())
+                .unwrap_or_default();
+            let current: Vec<_> = topics
+                .variable_data
+                .values()
+                .map(|d| d.variable.clone())
+                .collect();
+            state.playback_ids = if recorded.is_empty() {
+                current.iter().map(|v| (v.id, v.id)).collect()
+            } else {
+                match_variables(recorded, &current)
+            };
+            state.session_player.get_data_until_now()
         }
+    };
+    for (id, points) in data {
+        let Some(target) = state.playback_ids.get(&id) else {
+            continue;
+        };
+        if let Some(buffer) = topics.variable_data.get_mut(target) {
+            for point in points {
+                buffer.push(point);
+            }
+        }
+    }
+    state.playback_fed = Some(now);
+    if let Some(playback) = &mut topics.playback {
+        playback.time = now;
+    }
+    if playing {
+        ctx.request_repaint();
+    }
+}
+
+/// Events of the loaded recording, with a button to seek to each
+fn render_playback_events(state: &mut RecorderPaneState, current: Duration, ui: &mut Ui) {
+    let events = match state.session_player.metadata() {
+        Some(metadata) if !metadata.events.is_empty() => metadata.events.clone(),
+        _ => return,
+    };
+
+    let mut seek_to = None;
+    egui::CollapsingHeader::new(format!("Events ({})", events.len()))
+        .id_salt("playback_events")
+        .default_open(true)
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt("playback_events_scroll")
+                .max_height(150.0)
+                .show(ui, |ui| {
+                    for event in &events {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("Go").clicked() {
+                                seek_to = Some(event.time);
+                            }
+                            let text = format!("{:.3}s  {}", event.time.as_secs_f64(), event.name);
+                            let text = if event.time <= current {
+                                egui::RichText::new(text).strong()
+                            } else {
+                                egui::RichText::new(text)
+                            };
+                            let label = ui.colored_label(event.kind.color(), text);
+                            if let Some(note) = &event.note {
+                                label.on_hover_text(note);
+                            }
+                        });
+                    }
+                });
+        });
+
+    if let Some(time) = seek_to {
+        state.session_player.seek(time);
     }
 }
 
@@ -418,7 +813,24 @@ fn render_saved_recordings(
     ui: &mut Ui,
 ) {
     let recordings = &mut shared.state.topics.completed_recordings;
-    ui.label(format!("Saved Recordings ({})", recordings.len()));
+    ui.horizontal(|ui| {
+        ui.label(format!("Saved Recordings ({})", recordings.len()));
+        if ui
+            .button("Import log...")
+            .on_hover_text("Import a CSV or JSON Lines log from another tool")
+            .clicked()
+        {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_title("Import Log")
+                .add_filter("Logs", &["csv", "tsv", "txt", "jsonl", "ndjson"])
+                .pick_file()
+            {
+                state.import_wizard_state = ImportWizardState::default();
+                state.import_wizard_state.open(path);
+                state.import_wizard_open = true;
+            }
+        }
+    });
 
     if recordings.is_empty() {
         ui.label("No saved recordings yet.");
@@ -443,6 +855,33 @@ fn render_saved_recordings(
             if ui.small_button("Save").clicked() {
                 save_idx = Some(i);
             }
+            let events = format!("Events ({})", recording.metadata.events.len());
+            if ui
+                .selectable_label(state.events_recording == Some(i), events)
+                .on_hover_text("Edit the markers, regions and notes of this recording")
+                .clicked()
+            {
+                state.events_recording = match state.events_recording {
+                    Some(open) if open == i => None,
+                    _ => Some(i),
+                };
+            }
+            if ui
+                .selectable_label(state.edit_recording == Some(i), "Edit")
+                .on_hover_text("Trim, split, merge or drop variables into new recordings")
+                .clicked()
+            {
+                if state.edit_recording == Some(i) {
+                    state.edit_recording = None;
+                } else {
+                    state.edit_recording = Some(i);
+                    state.trim_start = 0.0;
+                    state.trim_end = recording.duration().as_secs_f64();
+                    state.merge_with.clear();
+                    state.drop_ids.clear();
+                    state.edit_error = None;
+                }
+            }
             if ui.small_button("Remove").clicked() {
                 remove_idx = Some(i);
             }
@@ -451,23 +890,355 @@ fn render_saved_recordings(
 
     if let Some(i) = play_idx {
         let recording = recordings[i].clone();
-        state.session_player.load(recording);
+        load_recording(state, recording);
         state.session_player.play();
     }
     if let Some(i) = save_idx {
         if let Some(path) = rfd::FileDialog::new()
             .set_title("Save Session Recording")
+            .add_filter("DataVis Session", &["dvs"])
             .add_filter("JSON Session", &["json"])
             .save_file()
         {
-            if let Err(e) = recordings[i].save_to_file(&path) {
+            let is_json = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+            let result = if is_json {
+                recordings[i].save_json_to_file(&path)
+            } else {
+                recordings[i].save_to_file(&path)
+            };
+            if let Err(e) = result {
                 tracing::error!("Failed to save session: {}", e);
             }
         }
     }
     if let Some(i) = remove_idx {
         recordings.remove(i);
+        let shift = |open: Option<usize>| match open {
+            Some(open) if open == i => None,
+            Some(open) if open > i => Some(open - 1),
+            open => open,
+        };
+        state.events_recording = shift(state.events_recording);
+        state.edit_recording = shift(state.edit_recording);
+        state.merge_with = state
+            .merge_with
+            .iter()
+            .filter_map(|&j| shift(Some(j)))
+            .collect();
     }
+
+    render_events_editor(state, shared, ui);
+    render_edit_tools(state, shared, ui);
+}
+
+/// Trim, split, merge and variable tools for the selected saved recording
+///
+/// Every tool adds its results to the saved recordings and leaves the
+/// selected one as it is.
+fn render_edit_tools(state: &mut RecorderPaneState, shared: &mut SharedState<'_>, ui: &mut Ui) {
+    let Some(index) = state.edit_recording else {
+        return;
+    };
+    let cursors = shared.state.topics.cursor_ranges.values().next().copied();
+    let recordings = &mut shared.state.topics.completed_recordings;
+    let Some(recording) = recordings.get(index) else {
+        state.edit_recording = None;
+        return;
+    };
+    let name = &recording.metadata.name;
+    let duration = recording.duration().as_secs_f64();
+    let mut results = Vec::new();
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label(format!("Edit {}", name));
+        if ui.small_button("Close").clicked() {
+            state.edit_recording = None;
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Trim to");
+        ui.add(
+            egui::DragValue::new(&mut state.trim_start)
+                .speed(0.01)
+                .range(0.0..=state.trim_end)
+                .suffix(" s"),
+        );
+        ui.label("-");
+        ui.add(
+            egui::DragValue::new(&mut state.trim_end)
+                .speed(0.01)
+                .range(state.trim_start..=duration)
+                .suffix(" s"),
+        );
+        if ui
+            .add_enabled(cursors.is_some(), egui::Button::new("From Cursors"))
+            .on_hover_text("Use the A/B cursor range of a time-series plot")
+            .clicked()
+        {
+            if let Some((a, b)) = cursors {
+                state.trim_start = a.clamp(0.0, duration);
+                state.trim_end = b.clamp(state.trim_start, duration);
+            }
+        }
+        if ui.button("Trim").clicked() {
+            let mut trimmed = recording.trim(
+                Duration::from_secs_f64(state.trim_start),
+                Duration::from_secs_f64(state.trim_end),
+            );
+            trimmed.metadata.name = format!("{} (trimmed)", name);
+            results.push(trimmed);
+        }
+    });
+
+    let markers = recording
+        .marker_times()
+        .iter()
+        .filter(|t| !t.is_zero() && t.as_secs_f64() < duration)
+        .count();
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(
+                markers > 0,
+                egui::Button::new(format!("Split at Markers ({})", markers)),
+            )
+            .on_hover_text("Split at every event and tagged frame")
+            .clicked()
+        {
+            results.extend(recording.split_at_markers());
+        }
+    });
+
+    ui.label("Append, in order:");
+    for (j, other) in recordings.iter().enumerate() {
+        if j == index {
+            continue;
+        }
+        let position = state.merge_with.iter().position(|&k| k == j);
+        let label = match position {
+            Some(p) => format!("{}. {}", p + 1, other.metadata.name),
+            None => other.metadata.name.clone(),
+        };
+        let mut selected = position.is_some();
+        if ui.checkbox(&mut selected, label).changed() {
+            match position {
+                Some(p) => {
+                    state.merge_with.remove(p);
+                }
+                None => state.merge_with.push(j),
+            }
+        }
+    }
+    ui.horizontal(|ui| {
+        ui.label("Gap:");
+        ui.add(
+            egui::DragValue::new(&mut state.merge_gap_ms)
+                .speed(1.0)
+                .range(0.0..=60_000.0)
+                .suffix(" ms"),
+        );
+        if ui
+            .add_enabled(!state.merge_with.is_empty(), egui::Button::new("Merge"))
+            .on_hover_text("Append the selected recordings, which must record the same variables")
+            .clicked()
+        {
+            let runs: Vec<SessionRecording> = std::iter::once(index)
+                .chain(state.merge_with.iter().copied())
+                .filter_map(|j| recordings.get(j).cloned())
+                .collect();
+            let gap = Duration::from_secs_f64(state.merge_gap_ms / 1000.0);
+            match SessionRecording::concat(&runs, gap) {
+                Ok(merged) => results.push(merged),
+                Err(e) => state.edit_error = Some(e.to_string()),
+            }
+        }
+    });
+
+    let variables = &recording.metadata.variables;
+    ui.label("Keep variables:");
+    for var in variables {
+        let mut keep = !state.drop_ids.contains(&var.id);
+        if ui.checkbox(&mut keep, &var.name).changed() {
+            if keep {
+                state.drop_ids.remove(&var.id);
+            } else {
+                state.drop_ids.insert(var.id);
+            }
+        }
+    }
+    let dropped = variables
+        .iter()
+        .filter(|v| state.drop_ids.contains(&v.id))
+        .count();
+    if ui
+        .add_enabled(
+            dropped > 0 && dropped < variables.len(),
+            egui::Button::new("Drop Unchecked"),
+        )
+        .clicked()
+    {
+        let mut pruned = recording.drop_variables(&state.drop_ids);
+        pruned.metadata.name = format!(
+            "{} ({} of {} variables)",
+            name,
+            variables.len() - dropped,
+            variables.len()
+        );
+        results.push(pruned);
+    }
+
+    if let Some(error) = &state.edit_error {
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+    }
+    if !results.is_empty() {
+        state.edit_error = None;
+        recordings.extend(results);
+    }
+}
+
+/// Editor for the events of the selected saved recording
+fn render_events_editor(state: &mut RecorderPaneState, shared: &mut SharedState<'_>, ui: &mut Ui) {
+    let Some(index) = state.events_recording else {
+        return;
+    };
+    let markers = plot_markers(shared);
+    let playhead = state
+        .session_player
+        .has_recording()
+        .then(|| state.session_player.current_time());
+    let Some(recording) = shared.state.topics.completed_recordings.get_mut(index) else {
+        state.events_recording = None;
+        return;
+    };
+    let duration = recording.duration().as_secs_f64();
+    let events = &mut recording.metadata.events;
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label(format!("Events of {}", recording.metadata.name));
+        if ui.small_button("Close").clicked() {
+            state.events_recording = None;
+        }
+    });
+
+    let mut resort = false;
+    let mut remove = None;
+    if events.is_empty() {
+        ui.label("No events. Add one below, or copy the markers of the plots.");
+    } else {
+        egui::ScrollArea::vertical()
+            .id_salt("recording_events_scroll")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                egui::Grid::new("recording_events_grid")
+                    .striped(true)
+                    .num_columns(5)
+                    .show(ui, |ui| {
+                        for header in ["Time", "Type", "Name", "Note", ""] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+
+                        for (i, event) in events.iter_mut().enumerate() {
+                            let mut secs = event.time.as_secs_f64();
+                            let max = duration.max(secs);
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut secs)
+                                        .speed(0.001)
+                                        .range(0.0..=max)
+                                        .suffix(" s"),
+                                )
+                                .changed()
+                            {
+                                event.time = Duration::from_secs_f64(secs.max(0.0));
+                                resort = true;
+                            }
+                            egui::ComboBox::from_id_salt(("recording_event_type", i))
+                                .selected_text(
+                                    egui::RichText::new(event.kind.display_name())
+                                        .color(event.kind.color()),
+                                )
+                                .width(100.0)
+                                .show_ui(ui, |ui| {
+                                    for kind in MarkerType::all() {
+                                        ui.selectable_value(
+                                            &mut event.kind,
+                                            *kind,
+                                            kind.display_name(),
+                                        );
+                                    }
+                                });
+                            ui.add(
+                                egui::TextEdit::singleline(&mut event.name).desired_width(100.0),
+                            );
+                            let mut note = event.note.clone().unwrap_or_default();
+                            if ui
+                                .add(
+                                    egui::TextEdit::singleline(&mut note)
+                                        .hint_text("note...")
+                                        .desired_width(160.0),
+                                )
+                                .changed()
+                            {
+                                event.note = (!note.is_empty()).then_some(note);
+                            }
+                            if ui.small_button("Remove").clicked() {
+                                remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+    if let Some(i) = remove {
+        events.remove(i);
+    }
+    if resort {
+        sort_events(events);
+    }
+
+    ui.horizontal(|ui| {
+        let hover = if playhead.is_some() {
+            "Add an event at the playback position"
+        } else {
+            "Add an event at the start of the recording"
+        };
+        if ui.button("Add Event").on_hover_text(hover).clicked() {
+            let time = playhead.unwrap_or_default();
+            insert_event(events, RecordingEvent::new(time, MarkerType::Note, "Note"));
+        }
+        if ui
+            .add_enabled(!markers.is_empty(), egui::Button::new("Add Plot Markers"))
+            .on_hover_text("Copy the markers of the time-series plots (plot times are kept as-is)")
+            .clicked()
+        {
+            for marker in markers {
+                if !events.contains(&marker) {
+                    insert_event(events, marker);
+                }
+            }
+        }
+        if ui
+            .add_enabled(!events.is_empty(), egui::Button::new("Export Table..."))
+            .on_hover_text("Save the events as a CSV table")
+            .clicked()
+        {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_title("Export Events")
+                .add_filter("CSV Files", &["csv"])
+                .set_file_name(format!("{} events.csv", recording.metadata.name))
+                .save_file()
+            {
+                if let Err(e) = save_events_csv(events, &path) {
+                    tracing::error!("Failed to export events: {}", e);
+                }
+            }
+        }
+    });
 }
 
 // ============================================================================
@@ -480,6 +1251,14 @@ fn render_export_tab(
     ui: &mut Ui,
     _actions: &mut Vec<AppAction>,
 ) {
+    // --- Saved recording export ---
+    render_recording_export(state, shared, ui);
+    ui.separator();
+
+    // --- Live buffer export ---
+    render_live_buffer_export(state, shared, ui);
+    ui.separator();
+
     // --- Status ---
     ui.horizontal(|ui| {
         if shared.state.topics.exporter_active {
@@ -597,6 +1376,140 @@ fn render_export_tab(
     });
 }
 
+/// Export one of the saved recordings to a file for other tools.
+fn render_recording_export(state: &mut RecorderPaneState, shared: &SharedState<'_>, ui: &mut Ui) {
+    ui.label("Recording Export");
+
+    let recordings = &shared.state.topics.completed_recordings;
+    if recordings.is_empty() {
+        ui.label("No saved recordings to export.");
+        return;
+    }
+    if state.export_recording >= recordings.len() {
+        state.export_recording = 0;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Recording:");
+        egui::ComboBox::from_id_salt("recording_export_select")
+            .selected_text(&recordings[state.export_recording].metadata.name)
+            .show_ui(ui, |ui| {
+                for (i, recording) in recordings.iter().enumerate() {
+                    ui.selectable_value(&mut state.export_recording, i, &recording.metadata.name);
+                }
+            });
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Table layout:");
+        ui.selectable_value(&mut state.export_layout, ExportLayout::Long, "Long");
+        ui.selectable_value(&mut state.export_layout, ExportLayout::Wide, "Wide");
+    });
+
+    let recording = &recordings[state.export_recording];
+    ui.horizontal(|ui| {
+        if ui
+            .button("Export MCAP...")
+            .on_hover_text("One channel per variable, for Foxglove-compatible tools")
+            .clicked()
+        {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_title("Export Recording as MCAP")
+                .add_filter("MCAP", &["mcap"])
+                .set_file_name(format!("{}.mcap", recording.metadata.name))
+                .save_file()
+            {
+                if let Err(e) = recording.save_mcap_to_file(&path) {
+                    tracing::error!("Failed to export MCAP: {}", e);
+                }
+            }
+        }
+        if ui
+            .button("Export MDF4...")
+            .on_hover_text("Channel groups per poll rate, for calibration and measurement tools")
+            .clicked()
+        {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_title("Export Recording as MDF4")
+                .add_filter("MDF4", &["mf4"])
+                .set_file_name(format!("{}.mf4", recording.metadata.name))
+                .save_file()
+            {
+                if let Err(e) = recording.save_mdf_to_file(&path) {
+                    tracing::error!("Failed to export MDF4: {}", e);
+                }
+            }
+        }
+        if ui.button("Export JSON...").clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_title("Export Recording as JSON")
+                .add_filter("JSON Session", &["json"])
+                .set_file_name(format!("{}.json", recording.metadata.name))
+                .save_file()
+            {
+                if let Err(e) = recording.save_json_to_file(&path) {
+                    tracing::error!("Failed to export session: {}", e);
+                }
+            }
+        }
+        for &format in ColumnarFormat::all() {
+            if ui
+                .button(format!("Export {}...", format.display_name()))
+                .on_hover_text("Typed table for pandas, Polars and other Arrow tools")
+                .clicked()
+            {
+                if let Some(path) = pick_columnar_path(format, &recording.metadata.name) {
+                    let options = columnar_options(state);
+                    if let Err(e) = columnar::export_recording(recording, format, &options, &path) {
+                        tracing::error!("Failed to export {}: {}", format.display_name(), e);
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Export the samples currently held in the live buffers.
+fn render_live_buffer_export(state: &RecorderPaneState, shared: &SharedState<'_>, ui: &mut Ui) {
+    ui.label("Live Buffer Export");
+
+    let data = &shared.state.topics.variable_data;
+    ui.horizontal(|ui| {
+        for &format in ColumnarFormat::all() {
+            if ui
+                .add_enabled(
+                    !data.is_empty(),
+                    egui::Button::new(format!("Export {}...", format.display_name())),
+                )
+                .on_hover_text("Table of the samples currently in memory, in the layout above")
+                .clicked()
+            {
+                if let Some(path) = pick_columnar_path(format, "live") {
+                    let options = columnar_options(state);
+                    if let Err(e) = columnar::export_live(data.values(), format, &options, &path) {
+                        tracing::error!("Failed to export {}: {}", format.display_name(), e);
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn columnar_options(state: &RecorderPaneState) -> ColumnarOptions {
+    ColumnarOptions {
+        layout: state.export_layout,
+        ..Default::default()
+    }
+}
+
+fn pick_columnar_path(format: ColumnarFormat, name: &str) -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new()
+        .set_title(format!("Export as {}", format.display_name()))
+        .add_filter(format.display_name(), &[format.extension()])
+        .set_file_name(format!("{}.{}", name, format.extension()))
+        .save_file()
+}
+
 impl Pane for RecorderPaneState {
     fn kind(&self) -> PaneKind {
         PaneKind::Recorder
@@ -606,6 +1519,11 @@ impl Pane for RecorderPaneState {
         render(self, shared, ui)
     }
 
+    fn render_dialogs(&mut self, shared: &mut SharedState, ctx: &egui::Context) -> Vec<AppAction> {
+        drive_playback(self, shared, ctx);
+        Vec::new()
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
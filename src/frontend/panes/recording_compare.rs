@@ -0,0 +1,567 @@
+//! Recording Comparison pane - Overlay one variable from several recordings
+//!
+//! Lines up completed recordings (e.g. "before fix" and "after fix" runs) on
+//! their start, a tagged marker or a trigger event, plots the same variable
+//! from each with a distinct colour and line style, and shows each run's
+//! difference from the reference run alongside summary statistics deltas.
+//! The aligned runs can also be overlaid on time-series plots, placed at a
+//! chosen plot time.
+
+use egui::{Color32, Ui};
+use egui_plot::LineStyle;
+
+use crate::config::settings::TriggerCondition;
+use crate::frontend::pane_trait::Pane;
+use crate::frontend::plot::run_style;
+use crate::frontend::state::{AppAction, SharedState};
+use crate::frontend::topics::RecordingOverlay;
+use crate::frontend::workspace::PaneKind;
+use crate::session::compare::{compare, marker_names, AlignMode, Comparison};
+use crate::session::types::SessionRecording;
+
+/// State for the Recording Comparison pane
+pub struct RecordingCompareState {
+    /// Indices of the compared recordings; the first is the reference
+    pub selected: Vec<usize>,
+    /// Name of the compared variable
+    pub variable: Option<String>,
+    /// Event the recordings are aligned on
+    pub align: AlignMode,
+    /// Limit the statistics to a window around the alignment event
+    pub use_window: bool,
+    /// Statistics window `(start, end)` in seconds of aligned time
+    pub window: (f64, f64),
+    /// Draw each run's difference from the reference
+    pub show_difference: bool,
+    /// Overlay the aligned runs on time-series plots
+    pub overlay: bool,
+    /// Plot time the alignment event is placed at on time-series plots
+    pub anchor: f64,
+    /// Cached comparison
+    comparison: Option<Comparison>,
+    /// Number of completed recordings when the comparison was computed
+    recording_count: usize,
+}
+
+impl Default for RecordingCompareState {
+    fn default() -> Self {
+        Self {
+            selected: Vec::new(),
+            variable: None,
+            align: AlignMode::Start,
+            use_window: false,
+            window: (0.0, 1.0),
+            show_difference: true,
+            overlay: false,
+            anchor: 0.0,
+            comparison: None,
+            recording_count: 0,
+        }
+    }
+}
+
+impl RecordingCompareState {
+    /// Toggle a recording in the comparison
+    fn toggle(&mut self, index: usize) {
+        match self.selected.iter().position(|&i| i == index) {
+            Some(position) => {
+                self.selected.remove(position);
+            }
+            None => self.selected.push(index),
+        }
+        self.comparison = None;
+    }
+
+    /// Make a compared recording the reference
+    fn make_reference(&mut self, index: usize) {
+        self.selected.retain(|&i| i != index);
+        self.selected.insert(0, index);
+        self.comparison = None;
+    }
+
+    /// Compare the selected recordings, `None` without a variable
+    fn compute(&self, recordings: &[SessionRecording]) -> Option<Comparison> {
+        let variable = self.variable.as_deref()?;
+        let runs: Vec<&SessionRecording> = self
+            .selected
+            .iter()
+            .filter_map(|&i| recordings.get(i))
+            .collect();
+        if runs.is_empty() {
+            return None;
+        }
+        let window = self.use_window.then_some(self.window);
+        Some(compare(&runs, variable, &self.align, window))
+    }
+
+    /// Overlays of the current comparison for time-series plots
+    fn overlays(&self) -> Vec<RecordingOverlay> {
+        let Some(comparison) = &self.comparison else {
+            return Vec::new();
+        };
+        let place = |points: &[[f64; 2]]| -> Vec<[f64; 2]> {
+            points.iter().map(|[t, v]| [t + self.anchor, *v]).collect()
+        };
+        let mut overlays = Vec::new();
+        for (run, compared) in comparison.runs.iter().enumerate() {
+            let name = run_label(run, &compared.name);
+            overlays.push(RecordingOverlay {
+                label: format!("{} [{}]", comparison.variable, name),
+                points: place(&compared.points),
+                run,
+                difference: false,
+            });
+            if self.show_difference && !compared.difference.is_empty() {
+                overlays.push(RecordingOverlay {
+                    label: format!("Δ {} [{}]", comparison.variable, name),
+                    points: place(&compared.difference),
+                    run,
+                    difference: true,
+                });
+            }
+        }
+        overlays
+    }
+}
+
+/// Label of a completed recording in the selector
+fn recording_label(index: usize, name: &str) -> String {
+    if name.is_empty() {
+        format!("Recording {}", index + 1)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Label of a compared run in legends and the statistics table
+fn run_label(run: usize, name: &str) -> String {
+    if name.is_empty() {
+        format!("Run {}", run + 1)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Variable names found in any of the given recordings, sorted
+fn variable_names(recordings: &[SessionRecording], selected: &[usize]) -> Vec<String> {
+    let mut names: Vec<String> = selected
+        .iter()
+        .filter_map(|&i| recordings.get(i))
+        .flat_map(|r| r.metadata.variables.iter().map(|v| v.name.clone()))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Format a signed statistics delta
+fn format_delta(value: f64) -> String {
+    format!("{:+.4}", value)
+}
+
+/// Alignment editor; returns true when the alignment changed
+fn render_alignment(
+    state: &mut RecordingCompareState,
+    ui: &mut Ui,
+    markers: &[String],
+    variables: &[String],
+) -> bool {
+    let mut changed = false;
+
+    ui.label("Align:");
+    egui::ComboBox::from_id_salt("recording_compare_align")
+        .selected_text(state.align.display_name())
+        .width(80.0)
+        .show_ui(ui, |ui| {
+            let modes = [
+                AlignMode::Start,
+                AlignMode::Marker(markers.first().cloned().unwrap_or_default()),
+                AlignMode::Trigger {
+                    variable: state.variable.clone().unwrap_or_default(),
+                    condition: TriggerCondition::RisingEdge,
+                    threshold: 0.0,
+                },
+            ];
+            for mode in modes {
+                let is_selected =
+                    std::mem::discriminant(&state.align) == std::mem::discriminant(&mode);
+                if ui
+                    .selectable_label(is_selected, mode.display_name())
+                    .clicked()
+                    && !is_selected
+                {
+                    state.align = mode;
+                    changed = true;
+                }
+            }
+        });
+
+    match &mut state.align {
+        AlignMode::Start => {}
+        AlignMode::Marker(name) => {
+            ui.label("Marker:");
+            egui::ComboBox::from_id_salt("recording_compare_marker")
+                .selected_text(if name.is_empty() {
+                    "Select..."
+                } else {
+                    name.as_str()
+                })
+                .width(120.0)
+                .show_ui(ui, |ui| {
+                    for marker in markers {
+                        if ui.selectable_label(name == marker, marker).clicked() {
+                            *name = marker.clone();
+                            changed = true;
+                        }
+                    }
+                });
+            if markers.is_empty() {
+                ui.colored_label(
+                    Color32::from_rgb(255, 165, 0),
+                    "The reference has no tagged frames",
+                );
+            }
+        }
+        AlignMode::Trigger {
+            variable,
+            condition,
+            threshold,
+        } => {
+            egui::ComboBox::from_id_salt("recording_compare_trigger_variable")
+                .selected_text(if variable.is_empty() {
+                    "Select..."
+                } else {
+                    variable.as_str()
+                })
+                .width(120.0)
+                .show_ui(ui, |ui| {
+                    for name in variables {
+                        if ui.selectable_label(variable == name, name).clicked() {
+                            *variable = name.clone();
+                            changed = true;
+                        }
+                    }
+                });
+            egui::ComboBox::from_id_salt("recording_compare_trigger_condition")
+                .selected_text(condition.to_string())
+                .width(100.0)
+                .show_ui(ui, |ui| {
+                    for option in TriggerCondition::all() {
+                        changed |= ui
+                            .selectable_value(condition, *option, option.to_string())
+                            .changed();
+                    }
+                });
+            changed |= ui
+                .add(egui::DragValue::new(threshold).speed(0.01))
+                .on_hover_text("Trigger threshold")
+                .changed();
+        }
+    }
+
+    changed
+}
+
+/// Render the recording comparison pane
+pub fn render(
+    state: &mut RecordingCompareState,
+    shared: &mut SharedState<'_>,
+    ui: &mut Ui,
+) -> Vec<AppAction> {
+    let recordings = &shared.state.topics.completed_recordings;
+    state.selected.retain(|&i| i < recordings.len());
+    let variables = variable_names(recordings, &state.selected);
+    let markers = state
+        .selected
+        .first()
+        .and_then(|&i| recordings.get(i))
+        .map(marker_names)
+        .unwrap_or_default();
+    let mut changed = false;
+
+    // Toolbar: variable and alignment
+    ui.horizontal_wrapped(|ui| {
+        ui.heading("Compare Recordings");
+        ui.separator();
+
+        ui.label("Variable:");
+        egui::ComboBox::from_id_salt("recording_compare_variable")
+            .selected_text(state.variable.as_deref().unwrap_or("Select..."))
+            .width(120.0)
+            .show_ui(ui, |ui| {
+                for name in &variables {
+                    let is_selected = state.variable.as_ref() == Some(name);
+                    if ui.selectable_label(is_selected, name).clicked() {
+                        state.variable = Some(name.clone());
+                        changed = true;
+                    }
+                }
+            });
+
+        ui.separator();
+        changed |= render_alignment(state, ui, &markers, &variables);
+    });
+
+    // Recording selection
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Recordings:");
+        if recordings.is_empty() {
+            ui.weak("none yet (record or load sessions in Session Capture)");
+        }
+        for (i, recording) in recordings.iter().enumerate() {
+            let position = state.selected.iter().position(|&s| s == i);
+            let label = recording_label(i, &recording.metadata.name);
+            if ui.checkbox(&mut position.is_some(), label).changed() {
+                state.toggle(i);
+            }
+            if position == Some(0) {
+                ui.weak("(reference)");
+            } else if position.is_some()
+                && ui
+                    .small_button("Ref")
+                    .on_hover_text("Compare the other runs against this one")
+                    .clicked()
+            {
+                state.make_reference(i);
+            }
+        }
+    });
+
+    // Statistics window and overlay
+    ui.horizontal_wrapped(|ui| {
+        changed |= ui
+            .checkbox(&mut state.use_window, "Window:")
+            .on_hover_text("Limit the statistics to this range around the alignment event")
+            .changed();
+        ui.add_enabled_ui(state.use_window, |ui| {
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut state.window.0)
+                        .speed(0.01)
+                        .suffix(" s"),
+                )
+                .changed();
+            ui.label("–");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut state.window.1)
+                        .speed(0.01)
+                        .suffix(" s"),
+                )
+                .changed();
+        });
+
+        ui.separator();
+        changed |= ui
+            .checkbox(&mut state.show_difference, "Difference")
+            .on_hover_text("Show each run minus the reference run")
+            .changed();
+        changed |= ui
+            .checkbox(&mut state.overlay, "Overlay on plots")
+            .on_hover_text("Draw the aligned runs on time-series plots")
+            .changed();
+        if state.overlay {
+            ui.label("at");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut state.anchor)
+                        .speed(0.01)
+                        .suffix(" s"),
+                )
+                .on_hover_text("Plot time the alignment event is placed at")
+                .changed();
+            let cursor = shared
+                .state
+                .topics
+                .cursor_ranges
+                .values()
+                .next()
+                .map(|(start, _)| *start);
+            if let Some(start) = cursor {
+                if ui
+                    .small_button("Cursor")
+                    .on_hover_text("Place the alignment event at the first A/B cursor")
+                    .clicked()
+                {
+                    state.anchor = start;
+                    changed = true;
+                }
+            }
+        }
+    });
+
+    // Recompare after a change or when recordings were added
+    let recordings = &shared.state.topics.completed_recordings;
+    if changed || state.recording_count != recordings.len() {
+        state.comparison = None;
+    }
+    if state.comparison.is_none() {
+        state.comparison = state.compute(recordings);
+        state.recording_count = recordings.len();
+
+        if let Some(pane_id) = shared.ctx.current_pane_id {
+            let overlays = if state.overlay {
+                state.overlays()
+            } else {
+                Vec::new()
+            };
+            if overlays.is_empty() {
+                shared.state.topics.recording_overlays.remove(&pane_id.0);
+            } else {
+                shared
+                    .state
+                    .topics
+                    .recording_overlays
+                    .insert(pane_id.0, overlays);
+            }
+        }
+    }
+
+    ui.separator();
+
+    let Some(comparison) = &state.comparison else {
+        ui.centered_and_justified(|ui| {
+            ui.label(if state.selected.is_empty() {
+                "Select the recordings to compare"
+            } else {
+                "Select a variable to compare"
+            });
+        });
+        return Vec::new();
+    };
+
+    render_statistics(ui, comparison);
+    ui.separator();
+    render_plot(ui, comparison, state.show_difference);
+
+    Vec::new()
+}
+
+/// Per-run statistics with deltas against the reference
+fn render_statistics(ui: &mut Ui, comparison: &Comparison) {
+    egui::Grid::new("recording_compare_statistics")
+        .striped(true)
+        .num_columns(8)
+        .show(ui, |ui| {
+            for header in [
+                "Run",
+                "Aligned at",
+                "Samples",
+                "Min",
+                "Max",
+                "Mean",
+                "RMS",
+                "Std Dev",
+            ] {
+                ui.strong(header);
+            }
+            ui.end_row();
+
+            for (run, compared) in comparison.runs.iter().enumerate() {
+                let (color, _) = run_style(run, false);
+                ui.colored_label(color, run_label(run, &compared.name));
+                match compared.alignment {
+                    Some(at) => ui.label(format!("{:.3} s", at)),
+                    None => ui.colored_label(Color32::from_rgb(255, 165, 0), "no event"),
+                };
+                let Some(stats) = compared.stats else {
+                    ui.label("—");
+                    ui.end_row();
+                    continue;
+                };
+                ui.label(stats.count.to_string());
+                let delta = compared.delta;
+                let cells = [
+                    (stats.min, delta.map(|d| d.min)),
+                    (stats.max, delta.map(|d| d.max)),
+                    (stats.mean, delta.map(|d| d.mean)),
+                    (stats.rms, delta.map(|d| d.rms)),
+                    (stats.std_dev, delta.map(|d| d.std_dev)),
+                ];
+                for (value, delta) in cells {
+                    match delta {
+                        Some(delta) => ui.label(format!("{:.4} ({})", value, format_delta(delta))),
+                        None => ui.label(format!("{:.4}", value)),
+                    };
+                }
+                ui.end_row();
+            }
+        });
+}
+
+/// Aligned runs and their difference traces
+fn render_plot(ui: &mut Ui, comparison: &Comparison, show_difference: bool) {
+    use egui_plot::{Legend, Line, Plot, PlotPoints, VLine};
+
+    Plot::new("recording_compare_plot")
+        .legend(Legend::default())
+        .x_axis_label("Time from alignment (s)")
+        .allow_zoom(true)
+        .allow_drag(true)
+        .show(ui, |plot_ui| {
+            plot_ui.vline(
+                VLine::new("Alignment", 0.0)
+                    .color(Color32::GRAY)
+                    .style(LineStyle::dashed_loose()),
+            );
+            for (run, compared) in comparison.runs.iter().enumerate() {
+                let name = run_label(run, &compared.name);
+                if !compared.points.is_empty() {
+                    let (color, style) = run_style(run, false);
+                    plot_ui.line(
+                        Line::new(name.as_str(), PlotPoints::from(compared.points.clone()))
+                            .color(color)
+                            .width(1.5)
+                            .style(style),
+                    );
+                }
+                if show_difference && !compared.difference.is_empty() {
+                    let (color, style) = run_style(run, true);
+                    plot_ui.line(
+                        Line::new(
+                            format!("Δ {}", name),
+                            PlotPoints::from(compared.difference.clone()),
+                        )
+                        .color(color)
+                        .width(1.0)
+                        .style(style),
+                    );
+                }
+            }
+        });
+}
+
+impl Pane for RecordingCompareState {
+    fn kind(&self) -> PaneKind {
+        PaneKind::RecordingCompare
+    }
+
+    fn render(&mut self, shared: &mut SharedState, ui: &mut Ui) -> Vec<AppAction> {
+        render(self, shared, ui)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_and_reference() {
+        let mut state = RecordingCompareState::default();
+        state.toggle(2);
+        state.toggle(0);
+        state.toggle(1);
+        assert_eq!(state.selected, vec![2, 0, 1]);
+        state.make_reference(1);
+        assert_eq!(state.selected, vec![1, 2, 0]);
+        state.toggle(2);
+        assert_eq!(state.selected, vec![1, 0]);
+    }
+}
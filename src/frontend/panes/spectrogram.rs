@@ -0,0 +1,533 @@
+//! Spectrogram pane - Time-frequency waterfall
+//!
+//! Shows how the spectrum of a variable evolves over time using a short-time
+//! Fourier transform, which makes drifting harmonics (e.g. motor vibration
+//! following the shaft speed) visible where a single FFT would smear them.
+
+use std::time::{Duration, Instant};
+
+use egui::{Color32, ColorImage, TextureHandle, TextureOptions, Ui};
+
+use crate::analysis::{
+    resample_data_points, Interpolation, ResampleConfig, Spectrogram, SpectrogramAnalyzer,
+    SpectrogramConfig, WindowFunction,
+};
+use crate::frontend::pane_trait::Pane;
+use crate::frontend::state::{AppAction, SharedState};
+use crate::frontend::workspace::PaneKind;
+
+/// Minimum time between live recomputations
+const LIVE_REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Available frame overlaps
+const OVERLAPS: &[f64] = &[0.0, 0.5, 0.75, 0.875];
+
+/// Color map used to render power values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMap {
+    /// Perceptually uniform blue-green-yellow
+    #[default]
+    Viridis,
+    /// Perceptually uniform black-red-yellow
+    Inferno,
+    /// Classic blue-cyan-yellow-red rainbow
+    Jet,
+    /// Black to white
+    Grayscale,
+}
+
+impl ColorMap {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ColorMap::Viridis => "Viridis",
+            ColorMap::Inferno => "Inferno",
+            ColorMap::Jet => "Jet",
+            ColorMap::Grayscale => "Grayscale",
+        }
+    }
+
+    /// Get all available color maps
+    pub fn all() -> &'static [ColorMap] {
+        &[
+            ColorMap::Viridis,
+            ColorMap::Inferno,
+            ColorMap::Jet,
+            ColorMap::Grayscale,
+        ]
+    }
+
+    /// Evenly spaced color stops from low to high
+    fn stops(&self) -> &'static [[u8; 3]] {
+        match self {
+            ColorMap::Viridis => &[
+                [68, 1, 84],
+                [59, 82, 139],
+                [33, 145, 140],
+                [94, 201, 98],
+                [253, 231, 37],
+            ],
+            ColorMap::Inferno => &[
+                [0, 0, 4],
+                [87, 16, 110],
+                [188, 55, 84],
+                [249, 142, 9],
+                [252, 255, 164],
+            ],
+            ColorMap::Jet => &[
+                [0, 0, 143],
+                [0, 112, 255],
+                [0, 255, 255],
+                [255, 255, 0],
+                [255, 0, 0],
+                [128, 0, 0],
+            ],
+            ColorMap::Grayscale => &[[0, 0, 0], [255, 255, 255]],
+        }
+    }
+
+    /// Color of a normalized value (0.0 = low, 1.0 = high)
+    pub fn color(&self, t: f64) -> Color32 {
+        let stops = self.stops();
+        let t = if t.is_finite() {
+            t.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let scaled = t * (stops.len() - 1) as f64;
+        let index = (scaled.floor() as usize).min(stops.len() - 2);
+        let frac = scaled - index as f64;
+        let (a, b) = (stops[index], stops[index + 1]);
+        let mix = |i: usize| (a[i] as f64 + (b[i] as f64 - a[i] as f64) * frac).round() as u8;
+        Color32::from_rgb(mix(0), mix(1), mix(2))
+    }
+}
+
+/// Normalize a power value into the displayed dB range
+fn normalize_db(value: f64, db_min: f64, db_max: f64) -> f64 {
+    if db_max <= db_min {
+        return 0.0;
+    }
+    (value - db_min) / (db_max - db_min)
+}
+
+/// State for the Spectrogram pane
+pub struct SpectrogramState {
+    /// Selected variable
+    pub target_variable_id: Option<u32>,
+    /// STFT analyzer instance
+    pub analyzer: SpectrogramAnalyzer,
+    /// STFT configuration (frame size, overlap, window)
+    pub config: SpectrogramConfig,
+    /// Resampling applied before the STFT
+    pub resample_config: ResampleConfig,
+    /// Color map for power values
+    pub color_map: ColorMap,
+    /// Power shown at the bottom of the color map (dB)
+    pub db_min: f64,
+    /// Power shown at the top of the color map (dB)
+    pub db_max: f64,
+    /// Recompute periodically while data arrives
+    pub live: bool,
+    /// Cached spectrogram
+    spectrogram: Option<Spectrogram>,
+    /// Texture rendered from the cached spectrogram
+    texture: Option<TextureHandle>,
+    /// Whether the texture must be rebuilt (new data or color settings)
+    texture_dirty: bool,
+    /// When the cached spectrogram was computed
+    computed_at: Option<Instant>,
+}
+
+impl Default for SpectrogramState {
+    fn default() -> Self {
+        Self {
+            target_variable_id: None,
+            analyzer: SpectrogramAnalyzer::default(),
+            config: SpectrogramConfig::default(),
+            resample_config: ResampleConfig::default(),
+            color_map: ColorMap::default(),
+            db_min: -80.0,
+            db_max: 0.0,
+            live: true,
+            spectrogram: None,
+            texture: None,
+            texture_dirty: true,
+            computed_at: None,
+        }
+    }
+}
+
+impl SpectrogramState {
+    /// Drop the cached result so it is recomputed on the next frame
+    fn invalidate(&mut self) {
+        self.spectrogram = None;
+        self.computed_at = None;
+    }
+
+    /// Whether the cached result should be recomputed
+    fn needs_compute(&self, collecting: bool) -> bool {
+        match self.computed_at {
+            None => true,
+            Some(at) => self.live && collecting && at.elapsed() >= LIVE_REFRESH_INTERVAL,
+        }
+    }
+}
+
+/// Build an image with one column per frame and high frequencies at the top
+fn build_image(
+    spectrogram: &Spectrogram,
+    color_map: ColorMap,
+    db_min: f64,
+    db_max: f64,
+) -> ColorImage {
+    let width = spectrogram.frames.len();
+    let height = spectrogram.bin_count();
+    let mut pixels = vec![Color32::BLACK; width * height];
+    for (x, frame) in spectrogram.frames.iter().enumerate() {
+        for (bin, &value) in frame.iter().enumerate() {
+            let y = height - 1 - bin;
+            pixels[y * width + x] = color_map.color(normalize_db(value, db_min, db_max));
+        }
+    }
+    ColorImage::new([width, height], pixels)
+}
+
+/// Render the spectrogram pane
+pub fn render(
+    state: &mut SpectrogramState,
+    shared: &mut SharedState<'_>,
+    ui: &mut Ui,
+) -> Vec<AppAction> {
+    use egui_plot::{Plot, PlotImage, PlotPoint};
+
+    // Toolbar
+    ui.horizontal_wrapped(|ui| {
+        ui.heading("Spectrogram");
+        ui.separator();
+
+        // Variable selector
+        ui.label("Variable:");
+        egui::ComboBox::from_id_salt("spectrogram_variable_selector")
+            .selected_text(
+                state
+                    .target_variable_id
+                    .and_then(|id| shared.state.config.variables.get(&id))
+                    .map(|v| v.name.as_str())
+                    .unwrap_or("Select..."),
+            )
+            .width(120.0)
+            .show_ui(ui, |ui| {
+                for var in shared.state.config.variables.values() {
+                    if var.enabled && var.show_in_graph {
+                        let is_selected = state.target_variable_id == Some(var.id);
+                        if ui.selectable_label(is_selected, &var.name).clicked() {
+                            state.target_variable_id = Some(var.id);
+                            state.invalidate();
+                        }
+                    }
+                }
+            });
+
+        ui.separator();
+
+        // Frame size selector
+        ui.label("Frame:");
+        egui::ComboBox::from_id_salt("spectrogram_size_selector")
+            .selected_text(format!("{}", state.config.fft_size))
+            .width(70.0)
+            .show_ui(ui, |ui| {
+                for &size in SpectrogramConfig::available_sizes() {
+                    let is_selected = state.config.fft_size == size;
+                    if ui
+                        .selectable_label(is_selected, format!("{}", size))
+                        .clicked()
+                    {
+                        state.config.fft_size = size;
+                        state.invalidate();
+                    }
+                }
+            });
+
+        // Overlap selector
+        ui.label("Overlap:");
+        egui::ComboBox::from_id_salt("spectrogram_overlap_selector")
+            .selected_text(format!("{:.1}%", state.config.overlap * 100.0))
+            .width(70.0)
+            .show_ui(ui, |ui| {
+                for &overlap in OVERLAPS {
+                    let is_selected = state.config.overlap == overlap;
+                    if ui
+                        .selectable_label(is_selected, format!("{:.1}%", overlap * 100.0))
+                        .clicked()
+                    {
+                        state.config.overlap = overlap;
+                        state.invalidate();
+                    }
+                }
+            });
+
+        // Window function selector
+        ui.label("Window:");
+        egui::ComboBox::from_id_salt("spectrogram_window_selector")
+            .selected_text(state.config.window.display_name())
+            .width(100.0)
+            .show_ui(ui, |ui| {
+                for window in WindowFunction::all() {
+                    let is_selected = state.config.window == *window;
+                    if ui
+                        .selectable_label(is_selected, window.display_name())
+                        .clicked()
+                    {
+                        state.config.window = *window;
+                        state.invalidate();
+                    }
+                }
+            });
+
+        // Interpolation used to resample onto a uniform grid
+        ui.label("Resample:");
+        egui::ComboBox::from_id_salt("spectrogram_interpolation_selector")
+            .selected_text(state.resample_config.interpolation.display_name())
+            .width(70.0)
+            .show_ui(ui, |ui| {
+                for interpolation in Interpolation::all() {
+                    let is_selected = state.resample_config.interpolation == *interpolation;
+                    if ui
+                        .selectable_label(is_selected, interpolation.display_name())
+                        .clicked()
+                    {
+                        state.resample_config.interpolation = *interpolation;
+                        state.invalidate();
+                    }
+                }
+            });
+
+        ui.separator();
+
+        // Color map and dB range
+        ui.label("Colors:");
+        egui::ComboBox::from_id_salt("spectrogram_color_map_selector")
+            .selected_text(state.color_map.display_name())
+            .width(80.0)
+            .show_ui(ui, |ui| {
+                for color_map in ColorMap::all() {
+                    let is_selected = state.color_map == *color_map;
+                    if ui
+                        .selectable_label(is_selected, color_map.display_name())
+                        .clicked()
+                    {
+                        state.color_map = *color_map;
+                        state.texture_dirty = true;
+                    }
+                }
+            });
+
+        ui.label("dB:");
+        let min_changed = ui
+            .add(
+                egui::DragValue::new(&mut state.db_min)
+                    .speed(0.5)
+                    .range(-300.0..=state.db_max - 1.0),
+            )
+            .on_hover_text("Power shown at the bottom of the color map")
+            .changed();
+        ui.label("to");
+        let max_changed = ui
+            .add(
+                egui::DragValue::new(&mut state.db_max)
+                    .speed(0.5)
+                    .range(state.db_min + 1.0..=300.0),
+            )
+            .on_hover_text("Power shown at the top of the color map")
+            .changed();
+        if min_changed || max_changed {
+            state.texture_dirty = true;
+        }
+        if ui
+            .button("Auto")
+            .on_hover_text("Fit the color range to the current data")
+            .clicked()
+        {
+            if let Some((lo, hi)) = state.spectrogram.as_ref().and_then(|s| s.db_range()) {
+                state.db_min = lo.floor();
+                state.db_max = hi.ceil().max(state.db_min + 1.0);
+                state.texture_dirty = true;
+            }
+        }
+
+        ui.separator();
+
+        ui.checkbox(&mut state.live, "Live")
+            .on_hover_text("Keep updating while data is collected");
+        if ui
+            .button("Compute")
+            .on_hover_text("Recompute spectrogram")
+            .clicked()
+        {
+            state.invalidate();
+        }
+    });
+
+    ui.separator();
+
+    let Some(var_id) = state.target_variable_id else {
+        ui.centered_and_justified(|ui| {
+            ui.label("Select a variable to see how its spectrum changes over time");
+        });
+        return Vec::new();
+    };
+
+    let collecting = shared.state.settings.collecting && !shared.state.settings.paused;
+    if state.needs_compute(collecting) {
+        if let Some(data) = shared.state.topics.variable_data.get(&var_id) {
+            if let Some(resampled) = resample_data_points(&data.data_points, &state.resample_config)
+            {
+                state.analyzer.set_config(state.config.clone());
+                let spectrogram = state.analyzer.compute(
+                    &resampled.samples,
+                    resampled.sample_rate,
+                    resampled.start_time,
+                );
+                state.spectrogram = Some(spectrogram);
+                state.texture_dirty = true;
+            }
+        }
+        state.computed_at = Some(Instant::now());
+    }
+    if state.live && collecting {
+        ui.ctx().request_repaint_after(LIVE_REFRESH_INTERVAL);
+    }
+
+    let Some(spectrogram) = state.spectrogram.as_ref().filter(|s| !s.is_empty()) else {
+        ui.centered_and_justified(|ui| {
+            ui.label(format!(
+                "Need at least {} samples for one frame",
+                state.config.fft_size
+            ));
+        });
+        return Vec::new();
+    };
+
+    if state.texture_dirty || state.texture.is_none() {
+        let image = build_image(spectrogram, state.color_map, state.db_min, state.db_max);
+        match &mut state.texture {
+            Some(texture) => texture.set(image, TextureOptions::NEAREST),
+            None => {
+                state.texture = Some(ui.ctx().load_texture(
+                    "spectrogram",
+                    image,
+                    TextureOptions::NEAREST,
+                ));
+            }
+        }
+        state.texture_dirty = false;
+    }
+    let Some(texture) = &state.texture else {
+        return Vec::new();
+    };
+
+    ui.label(format!(
+        "Frames: {} | Resolution: {:.2} Hz, {:.1} ms | Nyquist: {:.1} Hz",
+        spectrogram.frames.len(),
+        spectrogram.frequency_resolution,
+        spectrogram.hop_duration * 1e3,
+        spectrogram.max_frequency()
+    ));
+
+    // Each pixel is centered on its frame time and bin frequency
+    let width = spectrogram.frames.len() as f64 * spectrogram.hop_duration;
+    let height = spectrogram.bin_count() as f64 * spectrogram.frequency_resolution;
+    let first = spectrogram.frame_times[0];
+    let last = spectrogram.frame_times[spectrogram.frame_times.len() - 1];
+    let center = PlotPoint::new(
+        (first + last) / 2.0,
+        (spectrogram.bin_count() - 1) as f64 * spectrogram.frequency_resolution / 2.0,
+    );
+
+    let plot = Plot::new("spectrogram_plot")
+        .x_axis_label("Time (s)")
+        .y_axis_label("Frequency (Hz)")
+        .allow_zoom(true)
+        .allow_drag(true);
+
+    let response = plot.show(ui, |plot_ui| {
+        plot_ui.image(PlotImage::new(
+            "Spectrogram",
+            texture.id(),
+            center,
+            egui::vec2(width as f32, height as f32),
+        ));
+        plot_ui.pointer_coordinate()
+    });
+
+    if let Some(pointer) = response.inner {
+        if let Some(db) = spectrogram.value_at(pointer.x, pointer.y) {
+            response.response.on_hover_text_at_pointer(format!(
+                "t = {:.3} s\nf = {:.2} Hz\n{:.1} dB",
+                pointer.x, pointer.y, db
+            ));
+        }
+    }
+
+    Vec::new()
+}
+
+impl Pane for SpectrogramState {
+    fn kind(&self) -> PaneKind {
+        PaneKind::Spectrogram
+    }
+
+    fn render(&mut self, shared: &mut SharedState, ui: &mut Ui) -> Vec<AppAction> {
+        render(self, shared, ui)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_map_endpoints() {
+        for color_map in ColorMap::all() {
+            let stops = color_map.stops();
+            let [r, g, b] = stops[0];
+            assert_eq!(color_map.color(0.0), Color32::from_rgb(r, g, b));
+            let [r, g, b] = stops[stops.len() - 1];
+            assert_eq!(color_map.color(1.0), Color32::from_rgb(r, g, b));
+            // Out of range and NaN values are clamped
+            assert_eq!(color_map.color(2.0), color_map.color(1.0));
+            assert_eq!(color_map.color(f64::NAN), color_map.color(0.0));
+        }
+        assert_eq!(
+            ColorMap::Grayscale.color(0.5),
+            Color32::from_rgb(128, 128, 128)
+        );
+    }
+
+    #[test]
+    fn test_image_orientation() {
+        let spectrogram = Spectrogram {
+            frames: vec![vec![0.0, -100.0], vec![-100.0, 0.0]],
+            frame_times: vec![0.0, 1.0],
+            sample_rate: 4.0,
+            frequency_resolution: 2.0,
+            hop_duration: 1.0,
+        };
+        let image = build_image(&spectrogram, ColorMap::Grayscale, -100.0, 0.0);
+        assert_eq!(image.size, [2, 2]);
+        // Bottom row is DC, top row the highest bin
+        assert_eq!(image.pixels[2], Color32::WHITE);
+        assert_eq!(image.pixels[3], Color32::BLACK);
+        assert_eq!(image.pixels[0], Color32::BLACK);
+        assert_eq!(image.pixels[1], Color32::WHITE);
+        assert_eq!(normalize_db(-50.0, -100.0, 0.0), 0.5);
+    }
+}
@@ -0,0 +1,499 @@
+//! Step Response pane - Control-loop step metrics
+//!
+//! Finds step changes in a setpoint variable (or uses the regions marked in a
+//! time-series pane) and measures how a response variable follows them: rise
+//! time, overshoot, settling time, steady-state error and damping ratio.
+//!
+//! Results from live data and from completed recordings can be kept side by
+//! side in the comparison table, e.g. to compare tuning iterations.
+
+use egui::{Color32, Ui};
+
+use crate::analysis::{
+    analyze_step, detect_steps, series_from_points, step_in_region, StepConfig, StepMetrics,
+};
+use crate::frontend::pane_trait::Pane;
+use crate::frontend::plot::variable_selector;
+use crate::frontend::state::{AppAction, SharedState};
+use crate::frontend::topics::StepAnnotation;
+use crate::frontend::workspace::PaneKind;
+
+/// Where steps are taken from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StepSource {
+    /// Step changes detected in the setpoint
+    #[default]
+    Detect,
+    /// Regions marked with region start/end markers
+    MarkedRegions,
+}
+
+impl StepSource {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            StepSource::Detect => "Detect Steps",
+            StepSource::MarkedRegions => "Marked Regions",
+        }
+    }
+
+    /// Get all step sources
+    pub fn all() -> &'static [StepSource] {
+        &[StepSource::Detect, StepSource::MarkedRegions]
+    }
+}
+
+/// Metrics of one analysis run, labelled with its data source
+#[derive(Debug, Clone)]
+pub struct StepRun {
+    /// Data source label (live data or recording name)
+    pub label: String,
+    /// Metrics of each step found
+    pub steps: Vec<StepMetrics>,
+}
+
+/// State for the Step Response pane
+pub struct StepResponseState {
+    /// Setpoint (reference) variable
+    pub setpoint_variable_id: Option<u32>,
+    /// Response (measured) variable
+    pub response_variable_id: Option<u32>,
+    /// Completed recording to analyze instead of live data
+    pub recording_index: Option<usize>,
+    /// Where steps are taken from
+    pub source: StepSource,
+    /// Detection and metric configuration
+    pub config: StepConfig,
+    /// Draw the current results on time-series plots
+    pub annotate: bool,
+    /// Result of the last analysis
+    pub current: Option<StepRun>,
+    /// Runs kept for comparison
+    pub comparison: Vec<StepRun>,
+    /// Reason the last analysis produced no results
+    pub message: Option<String>,
+}
+
+impl Default for StepResponseState {
+    fn default() -> Self {
+        Self {
+            setpoint_variable_id: None,
+            response_variable_id: None,
+            recording_index: None,
+            source: StepSource::default(),
+            config: StepConfig::default(),
+            annotate: true,
+            current: None,
+            comparison: Vec::new(),
+            message: None,
+        }
+    }
+}
+
+impl StepResponseState {
+    /// Run the analysis on the selected source
+    fn analyze(&mut self, shared: &SharedState<'_>) {
+        self.current = None;
+        self.message = None;
+        let (Some(setpoint_id), Some(response_id)) =
+            (self.setpoint_variable_id, self.response_variable_id)
+        else {
+            return;
+        };
+
+        let topics = &shared.state.topics;
+        let (label, setpoint, response) = match self.recording_index {
+            Some(index) => {
+                let Some(recording) = topics.completed_recordings.get(index) else {
+                    self.recording_index = None;
+                    self.message = Some("Recording no longer exists".to_string());
+                    return;
+                };
+                let end = recording.duration();
+                let start = std::time::Duration::ZERO;
+                (
+                    recording_label(index, &recording.metadata.name),
+                    series_from_points(&recording.get_variable_data(setpoint_id, start, end)),
+                    series_from_points(&recording.get_variable_data(response_id, start, end)),
+                )
+            }
+            None => {
+                let (Some(setpoint), Some(response)) = (
+                    topics.variable_data.get(&setpoint_id),
+                    topics.variable_data.get(&response_id),
+                ) else {
+                    self.message = Some("No data for the selected variables".to_string());
+                    return;
+                };
+                (
+                    "Live".to_string(),
+                    series_from_points(&setpoint.data_points),
+                    series_from_points(&response.data_points),
+                )
+            }
+        };
+        let ((setpoint_times, setpoint), (response_times, response)) = (setpoint, response);
+
+        let events = match self.source {
+            StepSource::Detect => detect_steps(&setpoint_times, &setpoint, &self.config),
+            StepSource::MarkedRegions => {
+                let mut regions: Vec<_> = topics.marker_regions.values().flatten().collect();
+                regions.sort_by(|a, b| a.start.total_cmp(&b.start));
+                regions
+                    .into_iter()
+                    .filter_map(|r| step_in_region(&setpoint_times, &setpoint, r.start, r.end))
+                    .collect()
+            }
+        };
+        if events.is_empty() {
+            self.message = Some(match self.source {
+                StepSource::Detect => "No steps found in the setpoint".to_string(),
+                StepSource::MarkedRegions => {
+                    "No marked regions with setpoint data (add Region Start/End markers)"
+                        .to_string()
+                }
+            });
+            return;
+        }
+
+        let steps: Vec<StepMetrics> = events
+            .iter()
+            .filter_map(|step| analyze_step(&response_times, &response, step, &self.config))
+            .collect();
+        if steps.is_empty() {
+            self.message = Some("The response does not change after any step".to_string());
+            return;
+        }
+        self.current = Some(StepRun { label, steps });
+    }
+}
+
+/// Label of a completed recording in selectors and the comparison table
+fn recording_label(index: usize, name: &str) -> String {
+    if name.is_empty() {
+        format!("Recording {}", index + 1)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Format an optional duration in seconds
+fn format_secs(value: Option<f64>) -> String {
+    match value {
+        Some(secs) if secs < 1.0 => format!("{:.1} ms", secs * 1000.0),
+        Some(secs) => format!("{:.3} s", secs),
+        None => "—".to_string(),
+    }
+}
+
+/// Metrics table for a set of runs
+fn render_table(ui: &mut Ui, id_salt: &str, runs: &[&StepRun]) {
+    egui::Grid::new(id_salt)
+        .striped(true)
+        .num_columns(9)
+        .show(ui, |ui| {
+            for header in [
+                "Source",
+                "Step",
+                "At",
+                "Setpoint",
+                "Rise",
+                "Overshoot",
+                "Settling",
+                "SS Error",
+                "Damping",
+            ] {
+                ui.strong(header);
+            }
+            ui.end_row();
+
+            for run in runs {
+                for (i, metrics) in run.steps.iter().enumerate() {
+                    let step = &metrics.step;
+                    ui.label(&run.label);
+                    ui.label(format!("{}", i + 1));
+                    ui.label(format!("{:.3} s", step.start));
+                    ui.label(format!("{:.4} → {:.4}", step.from, step.to));
+                    ui.label(format_secs(metrics.rise_time));
+                    ui.label(format!("{:.1}%", metrics.overshoot_percent));
+                    match metrics.settling_time {
+                        Some(_) => ui.label(format_secs(metrics.settling_time)),
+                        None => ui
+                            .colored_label(Color32::from_rgb(255, 165, 0), "not settled")
+                            .on_hover_text(
+                                "The response leaves the settling band before the window ends",
+                            ),
+                    };
+                    ui.label(format!("{:.4}", metrics.steady_state_error));
+                    ui.label(
+                        metrics
+                            .damping_ratio
+                            .map(|zeta| format!("{:.3}", zeta))
+                            .unwrap_or_else(|| "≥ 1".to_string()),
+                    );
+                    ui.end_row();
+                }
+            }
+        });
+}
+
+/// Render the step response pane
+pub fn render(
+    state: &mut StepResponseState,
+    shared: &mut SharedState<'_>,
+    ui: &mut Ui,
+) -> Vec<AppAction> {
+    let mut analyze = false;
+
+    // Toolbar
+    ui.horizontal_wrapped(|ui| {
+        ui.heading("Step Response");
+        ui.separator();
+
+        ui.label("Setpoint:");
+        analyze |= variable_selector(
+            ui,
+            "step_response_setpoint_selector",
+            &mut state.setpoint_variable_id,
+            &shared.state.config.variables,
+        );
+        ui.label("Response:");
+        analyze |= variable_selector(
+            ui,
+            "step_response_response_selector",
+            &mut state.response_variable_id,
+            &shared.state.config.variables,
+        );
+
+        ui.separator();
+
+        // Data source: live data or a completed recording
+        ui.label("Data:");
+        let recordings = &shared.state.topics.completed_recordings;
+        let selected_text = match state.recording_index.and_then(|i| recordings.get(i)) {
+            Some(recording) => {
+                recording_label(state.recording_index.unwrap_or(0), &recording.metadata.name)
+            }
+            None => "Live".to_string(),
+        };
+        egui::ComboBox::from_id_salt("step_response_data_selector")
+            .selected_text(selected_text)
+            .width(120.0)
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(state.recording_index.is_none(), "Live")
+                    .clicked()
+                {
+                    state.recording_index = None;
+                    analyze = true;
+                }
+                for (i, recording) in recordings.iter().enumerate() {
+                    let label = recording_label(i, &recording.metadata.name);
+                    if ui
+                        .selectable_label(state.recording_index == Some(i), label)
+                        .clicked()
+                    {
+                        state.recording_index = Some(i);
+                        analyze = true;
+                    }
+                }
+            });
+
+        for source in StepSource::all() {
+            if ui
+                .selectable_value(&mut state.source, *source, source.display_name())
+                .changed()
+            {
+                analyze = true;
+            }
+        }
+
+        ui.separator();
+
+        if state.source == StepSource::Detect {
+            ui.label("Min step:");
+            analyze |= ui
+                .add(
+                    egui::DragValue::new(&mut state.config.min_step)
+                        .speed(0.01)
+                        .range(0.0..=f64::MAX),
+                )
+                .on_hover_text(
+                    "Smallest setpoint change counted as a step (0: 5% of the setpoint range)",
+                )
+                .changed();
+        }
+
+        ui.label("Band:");
+        let mut band_percent = state.config.settling_band * 100.0;
+        if ui
+            .add(
+                egui::DragValue::new(&mut band_percent)
+                    .speed(0.1)
+                    .range(0.1..=20.0)
+                    .suffix(" %"),
+            )
+            .on_hover_text("Settling band around the final value")
+            .changed()
+        {
+            state.config.settling_band = band_percent / 100.0;
+            analyze = true;
+        }
+
+        ui.label("Rise:");
+        let mut rise = (
+            state.config.rise_low * 100.0,
+            state.config.rise_high * 100.0,
+        );
+        let low_changed = ui
+            .add(
+                egui::DragValue::new(&mut rise.0)
+                    .speed(1.0)
+                    .range(0.0..=49.0)
+                    .suffix(" %"),
+            )
+            .changed();
+        ui.label("–");
+        let high_changed = ui
+            .add(
+                egui::DragValue::new(&mut rise.1)
+                    .speed(1.0)
+                    .range(51.0..=100.0)
+                    .suffix(" %"),
+            )
+            .changed();
+        if low_changed || high_changed {
+            state.config.rise_low = rise.0 / 100.0;
+            state.config.rise_high = rise.1 / 100.0;
+            analyze = true;
+        }
+
+        ui.separator();
+
+        ui.checkbox(&mut state.annotate, "Annotate")
+            .on_hover_text("Draw steps, settling bands and overshoot on time-series plots");
+
+        if ui
+            .button("Analyze")
+            .on_hover_text("Recompute from the latest data")
+            .clicked()
+        {
+            analyze = true;
+        }
+    });
+
+    ui.separator();
+
+    if analyze {
+        state.analyze(shared);
+    }
+
+    // Publish annotations for live results; recordings have their own time base
+    if let Some(pane_id) = shared.ctx.current_pane_id {
+        let annotation = match (&state.current, state.response_variable_id) {
+            (Some(run), Some(variable_id)) if state.annotate && state.recording_index.is_none() => {
+                Some(StepAnnotation {
+                    variable_id,
+                    settling_band: state.config.settling_band,
+                    steps: run.steps.clone(),
+                })
+            }
+            _ => None,
+        };
+        match annotation {
+            Some(annotation) => {
+                shared
+                    .state
+                    .topics
+                    .step_annotations
+                    .insert(pane_id.0, annotation);
+            }
+            None => {
+                shared.state.topics.step_annotations.remove(&pane_id.0);
+            }
+        }
+    }
+
+    if state.setpoint_variable_id.is_none() || state.response_variable_id.is_none() {
+        ui.centered_and_justified(|ui| {
+            ui.label("Select a setpoint and a response variable to measure step responses");
+        });
+        return Vec::new();
+    }
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        match &state.current {
+            Some(run) => {
+                ui.horizontal(|ui| {
+                    ui.strong(format!("{}: {} step(s)", run.label, run.steps.len()));
+                    if ui
+                        .button("Add to Comparison")
+                        .on_hover_text("Keep these results in the comparison table")
+                        .clicked()
+                    {
+                        state.comparison.push(run.clone());
+                    }
+                });
+                render_table(ui, "step_response_current", &[run]);
+            }
+            None => {
+                ui.label(
+                    state
+                        .message
+                        .as_deref()
+                        .unwrap_or("Press Analyze to measure the step responses"),
+                );
+            }
+        }
+
+        if !state.comparison.is_empty() {
+            ui.add_space(8.0);
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.strong("Comparison");
+                if ui.button("Clear").clicked() {
+                    state.comparison.clear();
+                }
+            });
+            let runs: Vec<&StepRun> = state.comparison.iter().collect();
+            render_table(ui, "step_response_comparison", &runs);
+        }
+    });
+
+    Vec::new()
+}
+
+impl Pane for StepResponseState {
+    fn kind(&self) -> PaneKind {
+        PaneKind::StepResponse
+    }
+
+    fn render(&mut self, shared: &mut SharedState, ui: &mut Ui) -> Vec<AppAction> {
+        render(self, shared, ui)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_label() {
+        assert_eq!(recording_label(0, ""), "Recording 1");
+        assert_eq!(recording_label(2, "Tuning A"), "Tuning A");
+    }
+
+    #[test]
+    fn test_format_secs() {
+        assert_eq!(format_secs(Some(0.0125)), "12.5 ms");
+        assert_eq!(format_secs(Some(1.5)), "1.500 s");
+        assert_eq!(format_secs(None), "—");
+    }
+}
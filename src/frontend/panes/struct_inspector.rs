@@ -0,0 +1,408 @@
+//! Struct Inspector pane - Live watch-window view of a whole struct
+//!
+//! The selected struct/class/union (or array) is read with one bulk memory read
+//! per poll and decoded member by member, without adding any variables. The
+//! read is capped at the probe's maximum bulk read size; members past it are
+//! not shown.
+//! Members whose value changed are highlighted for a moment.
+
+use std::time::{Duration, Instant};
+
+use egui::{Color32, RichText, Ui};
+
+use crate::backend::{ElfInfo, ElfSymbol, SnapshotNode, SnapshotValue, TypeDef, TypeHandle};
+use crate::frontend::pane_trait::Pane;
+use crate::frontend::state::{AppAction, SharedState};
+use crate::frontend::workspace::PaneKind;
+use crate::types::Endianness;
+
+/// How long a changed member stays highlighted
+const HIGHLIGHT_DURATION: Duration = Duration::from_millis(1500);
+
+/// Maximum number of symbols listed in the picker
+const MAX_PICKER_RESULTS: usize = 100;
+
+/// The struct currently being inspected
+struct InspectTarget {
+    address: u64,
+    /// Bytes read per poll
+    size: usize,
+    /// Size of the whole struct
+    full_size: usize,
+    layout: SnapshotNode,
+}
+
+/// State for the Struct Inspector pane
+#[derive(Default)]
+pub struct StructInspectorState {
+    /// Symbol picker search query
+    pub query: String,
+    /// Inspected struct (None until a symbol is picked)
+    target: Option<InspectTarget>,
+    /// Last change time per layout node index
+    changed_at: Vec<Option<Instant>>,
+    /// Previous snapshot, compared against each new one
+    previous: Vec<u8>,
+    /// Last seen ELF generation (the layout is dropped on ELF reload)
+    last_elf_generation: u64,
+}
+
+impl StructInspectorState {
+    /// Inspect a new layout, forgetting the previous snapshot
+    ///
+    /// Returns the number of bytes to read: `size`, capped at `max_read`.
+    fn set_layout(
+        &mut self,
+        address: u64,
+        size: usize,
+        max_read: usize,
+        layout: SnapshotNode,
+    ) -> usize {
+        self.changed_at = vec![None; layout.node_count()];
+        self.previous.clear();
+        let read_size = size.min(max_read.max(1));
+        self.target = Some(InspectTarget {
+            address,
+            size: read_size,
+            full_size: size,
+            layout,
+        });
+        read_size
+    }
+
+    /// Record which members changed since the previous snapshot
+    fn apply_snapshot(&mut self, bytes: &[u8], endianness: Endianness, now: Instant) {
+        let Some(target) = &self.target else {
+            return;
+        };
+        if bytes == self.previous.as_slice() {
+            return;
+        }
+        if self.previous.len() == bytes.len() {
+            let mut changed = Vec::new();
+            target
+                .layout
+                .collect_changes(&self.previous, bytes, endianness, &mut changed);
+            for index in changed {
+                if let Some(slot) = self.changed_at.get_mut(index) {
+                    *slot = Some(now);
+                }
+            }
+        }
+        self.previous = bytes.to_vec();
+    }
+
+    /// Highlight strength (0.0-1.0) of a node at `now`
+    fn highlight(&self, index: usize, now: Instant) -> f32 {
+        match self.changed_at.get(index).copied().flatten() {
+            Some(at) => {
+                let elapsed = now.saturating_duration_since(at);
+                1.0 - (elapsed.as_secs_f32() / HIGHLIGHT_DURATION.as_secs_f32()).min(1.0)
+            }
+            None => 0.0,
+        }
+    }
+}
+
+/// Type handle of a symbol if it can be inspected (struct, union or non-string array)
+fn inspectable_type(info: &ElfInfo, symbol: &ElfSymbol) -> Option<TypeHandle> {
+    let handle = info.symbol_type_handle(symbol)?;
+    let table = info.type_table();
+    match table.get(table.get_underlying(handle.id())) {
+        Some(TypeDef::Struct(_)) | Some(TypeDef::Union(_)) => Some(handle),
+        Some(TypeDef::Array { element, .. }) if !table.is_char(*element) => Some(handle),
+        _ => None,
+    }
+}
+
+/// Render the struct inspector pane
+pub fn render(
+    state: &mut StructInspectorState,
+    shared: &mut SharedState<'_>,
+    ui: &mut Ui,
+) -> Vec<AppAction> {
+    let mut actions = Vec::new();
+    let Some(pane_id) = shared.ctx.current_pane_id.map(|id| id.0) else {
+        return actions;
+    };
+
+    // The layout refers to the old type table; drop it on ELF reload
+    if shared.state.topics.elf_generation != state.last_elf_generation {
+        state.last_elf_generation = shared.state.topics.elf_generation;
+        if state.target.take().is_some() {
+            actions.push(AppAction::StopMemoryWatch(pane_id));
+        }
+    }
+
+    let Some(info) = shared.ctx.elf_info else {
+        ui.heading("Struct Inspector");
+        ui.separator();
+        ui.colored_label(Color32::GRAY, "Load an ELF file to inspect structs");
+        return actions;
+    };
+    let endianness = shared
+        .state
+        .config
+        .probe
+        .endianness
+        .unwrap_or(info.endianness());
+    let max_read = shared.state.config.probe.max_bulk_read_size;
+
+    ui.horizontal(|ui| {
+        ui.heading("Struct Inspector");
+        ui.separator();
+
+        let picker_text = state
+            .target
+            .as_ref()
+            .map_or_else(|| "Select symbol...".to_string(), |t| t.layout.name.clone());
+        ui.menu_button(picker_text, |ui| {
+            ui.add(egui::TextEdit::singleline(&mut state.query).hint_text("Search structs..."));
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    let candidates = info
+                        .search_variables(&state.query)
+                        .into_iter()
+                        .filter(|s| s.is_readable())
+                        .filter_map(|s| inspectable_type(info, s).map(|h| (s, h)))
+                        .take(MAX_PICKER_RESULTS);
+                    for (symbol, handle) in candidates {
+                        let label = format!("{}: {}", symbol.display_name, handle.type_name());
+                        if ui.selectable_label(false, label).clicked() {
+                            let layout = SnapshotNode::build(
+                                info.type_table(),
+                                handle.id(),
+                                symbol.display_name.clone(),
+                                info.endianness(),
+                            );
+                            let size = match layout.size {
+                                0 => symbol.size as usize,
+                                size => size as usize,
+                            };
+                            if size > 0 {
+                                let size = state.set_layout(symbol.address, size, max_read, layout);
+                                actions.push(AppAction::StartMemoryWatch {
+                                    id: pane_id,
+                                    address: symbol.address,
+                                    size,
+                                });
+                            }
+                            ui.close();
+                        }
+                    }
+                });
+        });
+
+        if state.target.is_some()
+            && ui
+                .button("Stop")
+                .on_hover_text("Stop reading this struct")
+                .clicked()
+        {
+            state.target = None;
+            actions.push(AppAction::StopMemoryWatch(pane_id));
+        }
+    });
+
+    let Some(target) = &state.target else {
+        ui.separator();
+        ui.colored_label(
+            Color32::GRAY,
+            "Pick a struct, union or array to watch all of its members live",
+        );
+        return actions;
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new(format!(
+                "0x{:08X}  {} bytes  {}",
+                target.address, target.full_size, target.layout.type_name
+            ))
+            .monospace()
+            .color(Color32::GRAY),
+        );
+    });
+    if target.size < target.full_size {
+        ui.colored_label(
+            Color32::from_rgb(255, 165, 0),
+            format!(
+                "Truncated: reading the first {} of {} bytes (max bulk read size)",
+                target.size, target.full_size
+            ),
+        );
+    }
+
+    let snapshot = shared.state.topics.memory_snapshots.get(&pane_id);
+    if let Some(error) = snapshot.and_then(|s| s.error.as_ref()) {
+        ui.colored_label(Color32::RED, format!("Read failed: {}", error));
+    }
+    if !shared.state.settings.collecting {
+        ui.colored_label(Color32::GRAY, "Start collection to read the target");
+    }
+    ui.separator();
+
+    let now = Instant::now();
+    let bytes = snapshot.map(|s| s.bytes.clone()).unwrap_or_default();
+    if !bytes.is_empty() {
+        state.apply_snapshot(&bytes, endianness, now);
+    }
+
+    let mut fading = false;
+    egui::ScrollArea::both()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            if let Some(target) = &state.target {
+                render_node(
+                    ui,
+                    &target.layout,
+                    state,
+                    &bytes,
+                    endianness,
+                    now,
+                    &mut fading,
+                );
+            }
+        });
+    if fading {
+        ui.ctx().request_repaint();
+    }
+
+    actions
+}
+
+/// Render one layout node and its children
+fn render_node(
+    ui: &mut Ui,
+    node: &SnapshotNode,
+    state: &StructInspectorState,
+    bytes: &[u8],
+    endianness: Endianness,
+    now: Instant,
+    fading: &mut bool,
+) {
+    let highlight = state.highlight(node.index, now);
+    if highlight > 0.0 {
+        *fading = true;
+    }
+    let highlight_color = Color32::from_rgba_unmultiplied(255, 200, 0, (highlight * 120.0) as u8);
+
+    if node.children.is_empty() {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(&node.name).monospace());
+            let value = node
+                .format_value(bytes, endianness)
+                .unwrap_or_else(|| "-".to_string());
+            let mut value_text = RichText::new(value).monospace();
+            if highlight > 0.0 {
+                value_text = value_text.background_color(highlight_color);
+            }
+            ui.label(value_text);
+            let type_label = match node.value {
+                SnapshotValue::BitField { bit_size, .. } => {
+                    format!("{} : {}", node.type_name, bit_size)
+                }
+                _ => node.type_name.clone(),
+            };
+            ui.label(RichText::new(type_label).small().color(Color32::GRAY));
+        });
+        return;
+    }
+
+    let mut header = RichText::new(format!("{}  {}", node.name, node.type_name)).monospace();
+    if highlight > 0.0 {
+        header = header.background_color(highlight_color);
+    }
+    egui::CollapsingHeader::new(header)
+        .id_salt(node.index)
+        .default_open(node.index == 0)
+        .show(ui, |ui| {
+            for child in &node.children {
+                render_node(ui, child, state, bytes, endianness, now, fading);
+            }
+        });
+}
+
+impl Pane for StructInspectorState {
+    fn kind(&self) -> PaneKind {
+        PaneKind::StructInspector
+    }
+
+    fn render(&mut self, shared: &mut SharedState, ui: &mut Ui) -> Vec<AppAction> {
+        render(self, shared, ui)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MemberDef, PrimitiveDef, StructDef, TypeTable};
+
+    fn pair_layout() -> SnapshotNode {
+        let mut table = TypeTable::new();
+        let u16_id = table.insert(TypeDef::Primitive(PrimitiveDef::UnsignedShort));
+        let mut pair = StructDef::new(Some("Pair".to_string()), 4, false);
+        pair.members
+            .push(MemberDef::new("a".to_string(), 0, u16_id));
+        pair.members
+            .push(MemberDef::new("b".to_string(), 2, u16_id));
+        let pair_id = table.insert(TypeDef::Struct(pair));
+        SnapshotNode::build(&table, pair_id, "pair", Endianness::Little)
+    }
+
+    #[test]
+    fn test_changes_are_highlighted_then_fade() {
+        let mut state = StructInspectorState::default();
+        state.set_layout(0x2000_0000, 4, 256, pair_layout());
+        let t0 = Instant::now();
+
+        // The first snapshot has nothing to compare against
+        state.apply_snapshot(&[1, 0, 2, 0], Endianness::Little, t0);
+        assert_eq!(state.highlight(0, t0), 0.0);
+
+        // Only `b` changes
+        state.apply_snapshot(&[1, 0, 3, 0], Endianness::Little, t0);
+        assert_eq!(state.highlight(0, t0), 1.0);
+        assert_eq!(state.highlight(1, t0), 0.0);
+        assert_eq!(state.highlight(2, t0), 1.0);
+
+        let later = t0 + HIGHLIGHT_DURATION;
+        assert_eq!(state.highlight(2, later), 0.0);
+    }
+
+    #[test]
+    fn test_new_layout_resets_history() {
+        let mut state = StructInspectorState::default();
+        state.set_layout(0x2000_0000, 4, 256, pair_layout());
+        let t0 = Instant::now();
+        state.apply_snapshot(&[1, 0, 2, 0], Endianness::Little, t0);
+        state.apply_snapshot(&[9, 0, 2, 0], Endianness::Little, t0);
+        assert!(state.highlight(1, t0) > 0.0);
+
+        state.set_layout(0x2000_0010, 4, 256, pair_layout());
+        assert_eq!(state.highlight(1, t0), 0.0);
+        assert!(state.previous.is_empty());
+    }
+
+    #[test]
+    fn test_read_is_capped() {
+        let mut state = StructInspectorState::default();
+        assert_eq!(state.set_layout(0x2000_0000, 4, 2, pair_layout()), 2);
+
+        // Members past the read are not decoded
+        let t0 = Instant::now();
+        state.apply_snapshot(&[1, 0], Endianness::Little, t0);
+        let layout = &state.target.as_ref().unwrap().layout;
+        let b = &layout.children[1];
+        assert_eq!(b.format_value(&state.previous, Endianness::Little), None);
+    }
+}
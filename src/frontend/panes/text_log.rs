@@ -0,0 +1,254 @@
+//! Text Log pane - Scrolling history of string variables
+//!
+//! Shows every change of the selected string variables as a timestamped line,
+//! merged across variables in time order.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use egui::{Color32, Ui};
+
+use crate::frontend::pane_trait::Pane;
+use crate::frontend::state::{AppAction, SharedState};
+use crate::frontend::workspace::PaneKind;
+use crate::types::VariableData;
+
+/// Maximum number of lines rendered (the most recent ones are kept)
+const MAX_VISIBLE_LINES: usize = 5000;
+
+/// State for the Text Log pane
+pub struct TextLogState {
+    /// Variables shown in the log (empty means all string variables)
+    pub selected_variables: HashSet<u32>,
+    /// Keep the view scrolled to the newest line
+    pub auto_scroll: bool,
+    /// Prefix each line with the variable name
+    pub show_names: bool,
+}
+
+impl Default for TextLogState {
+    fn default() -> Self {
+        Self {
+            selected_variables: HashSet::new(),
+            auto_scroll: true,
+            show_names: true,
+        }
+    }
+}
+
+impl TextLogState {
+    /// Whether a string variable is included in the log
+    pub fn shows(&self, var_id: u32) -> bool {
+        self.selected_variables.is_empty() || self.selected_variables.contains(&var_id)
+    }
+}
+
+/// One line of the merged log
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine<'a> {
+    pub timestamp: Duration,
+    pub var_id: u32,
+    pub text: &'a str,
+}
+
+/// Merge the text logs of the shown variables, oldest first
+///
+/// Only the newest `limit` lines are returned.
+pub fn merge_text_logs<'a>(
+    state: &TextLogState,
+    variable_data: &'a HashMap<u32, VariableData>,
+    limit: usize,
+) -> Vec<LogLine<'a>> {
+    let mut lines: Vec<LogLine<'a>> = variable_data
+        .iter()
+        .filter(|(id, data)| data.variable.var_type.is_text() && state.shows(**id))
+        .flat_map(|(&var_id, data)| {
+            data.text_log.iter().map(move |entry| LogLine {
+                timestamp: entry.timestamp,
+                var_id,
+                text: &entry.text,
+            })
+        })
+        .collect();
+    // Stable sort keeps per-variable order for identical timestamps
+    lines.sort_by_key(|line| (line.timestamp, line.var_id));
+    let skip = lines.len().saturating_sub(limit);
+    lines.drain(..skip);
+    lines
+}
+
+/// Render the text log pane
+pub fn render(
+    state: &mut TextLogState,
+    shared: &mut SharedState<'_>,
+    ui: &mut Ui,
+) -> Vec<AppAction> {
+    let mut actions = Vec::new();
+
+    let mut text_vars: Vec<_> = shared
+        .state
+        .config
+        .variables
+        .values()
+        .filter(|v| v.var_type.is_text())
+        .collect();
+    text_vars.sort_by_key(|v| v.id);
+
+    ui.horizontal(|ui| {
+        ui.heading("Text Log");
+        ui.separator();
+
+        let selected_text = if state.selected_variables.is_empty() {
+            "All strings".to_string()
+        } else {
+            format!("{} selected", state.selected_variables.len())
+        };
+        ui.menu_button(&selected_text, |ui| {
+            if ui
+                .selectable_label(state.selected_variables.is_empty(), "All strings")
+                .clicked()
+            {
+                state.selected_variables.clear();
+            }
+            ui.separator();
+            for var in &text_vars {
+                let mut checked = state.selected_variables.contains(&var.id);
+                if ui.checkbox(&mut checked, &var.name).changed() {
+                    if checked {
+                        state.selected_variables.insert(var.id);
+                    } else {
+                        state.selected_variables.remove(&var.id);
+                    }
+                }
+            }
+        });
+
+        ui.checkbox(&mut state.auto_scroll, "Auto-scroll");
+        ui.checkbox(&mut state.show_names, "Names");
+
+        if ui
+            .button("Clear")
+            .on_hover_text("Clear the history of the shown variables")
+            .clicked()
+        {
+            for var in &text_vars {
+                if state.shows(var.id) {
+                    actions.push(AppAction::ClearVariableData(var.id));
+                }
+            }
+        }
+    });
+    ui.separator();
+
+    if text_vars.is_empty() {
+        ui.colored_label(
+            Color32::GRAY,
+            "No string variables configured (add a char array or char pointer)",
+        );
+        return actions;
+    }
+
+    let lines = merge_text_logs(state, &shared.state.topics.variable_data, MAX_VISIBLE_LINES);
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+
+    egui::ScrollArea::both()
+        .auto_shrink([false, false])
+        .stick_to_bottom(state.auto_scroll)
+        .show_rows(ui, row_height, lines.len(), |ui, range| {
+            for line in &lines[range] {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!("[{:>10.3}s]", line.timestamp.as_secs_f64()))
+                            .monospace()
+                            .color(Color32::GRAY),
+                    );
+                    if state.show_names {
+                        if let Some(var) = shared.state.config.variables.get(&line.var_id) {
+                            let color = Color32::from_rgba_unmultiplied(
+                                var.color[0],
+                                var.color[1],
+                                var.color[2],
+                                var.color[3],
+                            );
+                            ui.label(
+                                egui::RichText::new(format!("{}:", var.name))
+                                    .monospace()
+                                    .color(color),
+                            );
+                        }
+                    }
+                    ui.label(egui::RichText::new(line.text).monospace());
+                });
+            }
+        });
+
+    actions
+}
+
+impl Pane for TextLogState {
+    fn kind(&self) -> PaneKind {
+        PaneKind::TextLog
+    }
+
+    fn render(&mut self, shared: &mut SharedState, ui: &mut Ui) -> Vec<AppAction> {
+        render(self, shared, ui)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Variable, VariableType};
+
+    fn text_data(name: &str, entries: &[(u64, &str)]) -> VariableData {
+        let mut data = VariableData::new(Variable::new(name, 0x2000_0000, VariableType::Text(16)));
+        for (ms, text) in entries {
+            data.push_text(Duration::from_millis(*ms), text.to_string());
+        }
+        data
+    }
+
+    #[test]
+    fn test_text_log_state_default() {
+        let state = TextLogState::default();
+        assert!(state.selected_variables.is_empty());
+        assert!(state.auto_scroll);
+        assert!(state.shows(42));
+    }
+
+    #[test]
+    fn test_merge_orders_by_time_and_filters() {
+        let a = text_data("a", &[(0, "boot"), (30, "run")]);
+        let b = text_data("b", &[(10, "link up"), (20, "link up"), (40, "link down")]);
+        let numeric = VariableData::new(Variable::new("n", 0x2000_0010, VariableType::U32));
+        let (id_a, id_b) = (a.variable.id, b.variable.id);
+
+        let mut map = HashMap::new();
+        map.insert(id_a, a);
+        map.insert(id_b, b);
+        map.insert(numeric.variable.id, numeric);
+
+        let mut state = TextLogState::default();
+        let texts: Vec<&str> = merge_text_logs(&state, &map, 100)
+            .iter()
+            .map(|l| l.text)
+            .collect();
+        assert_eq!(texts, vec!["boot", "link up", "run", "link down"]);
+
+        state.selected_variables.insert(id_b);
+        let lines = merge_text_logs(&state, &map, 100);
+        assert!(lines.iter().all(|l| l.var_id == id_b));
+        assert_eq!(lines.len(), 2);
+
+        // The limit keeps the newest lines
+        let lines = merge_text_logs(&TextLogState::default(), &map, 1);
+        assert_eq!(lines[0].text, "link down");
+    }
+}
@@ -10,12 +10,16 @@ use egui_plot::{HLine, PlotPoint, Polygon, VLine};
 use crate::frontend::dialogs::{ExportConfigState, TriggerConfigState, ValueEditorState};
 use crate::frontend::markers::{MarkerManager, MarkerType};
 use crate::frontend::pane_trait::Pane;
-use crate::frontend::plot::{PlotCursor, PlotStatistics};
+use crate::frontend::plot::{run_style, PlotCursor, PlotStatistics};
 use crate::frontend::state::{AppAction, SharedState};
+use crate::frontend::topics::StepAnnotation;
 use crate::frontend::workspace::PaneKind;
 use crate::pipeline::id::NodeId;
 use crate::types::ConnectionStatus;
 
+/// What a cached decimated line was computed from: (points pushed, x_min, x_max)
+pub type DecimationKey = (u64, f64, f64);
+
 /// A horizontal threshold/reference line
 #[derive(Debug, Clone)]
 pub struct ThresholdLine {
@@ -62,8 +66,8 @@ pub struct TimeSeriesState {
     pub secondary_autoscale_y: bool,
     // Threshold lines
     pub threshold_lines: Vec<ThresholdLine>,
-    /// Decimation cache: var_id -> (source_point_count, decimated_points)
-    pub decimation_cache: HashMap<u32, (usize, Vec<[f64; 2]>)>,
+    /// Decimation cache: var_id -> (key, decimated_points)
+    pub decimation_cache: HashMap<u32, (DecimationKey, Vec<[f64; 2]>)>,
     /// Linked GraphSink node ID (if any). When set, this pane uses per-pane data.
     pub linked_graph_sink: Option<NodeId>,
 }
@@ -162,6 +166,33 @@ pub fn render(
         render_stale_warning(ui, shared, pane_id);
     }
 
+    // Publish markers, marked regions and the cursor range for other panes
+    if let Some(id) = shared.ctx.current_pane_id {
+        let regions = state.markers.regions();
+        if regions.is_empty() {
+            shared.state.topics.marker_regions.remove(&id.0);
+        } else {
+            shared.state.topics.marker_regions.insert(id.0, regions);
+        }
+        if state.markers.is_empty() {
+            shared.state.topics.plot_markers.remove(&id.0);
+        } else {
+            shared
+                .state
+                .topics
+                .plot_markers
+                .insert(id.0, state.markers.to_events());
+        }
+        match state.cursor.time_range() {
+            Some(range) => {
+                shared.state.topics.cursor_ranges.insert(id.0, range);
+            }
+            None => {
+                shared.state.topics.cursor_ranges.remove(&id.0);
+            }
+        }
+    }
+
     // Main content: plot fills all remaining space
     render_plot(state, shared, ui);
 
@@ -790,20 +821,56 @@ fn render_toolbar_advanced(
 // Plot
 // ============================================================================
 
+/// Draw step starts, settling bands and overshoot peaks published by a step-response pane
+fn draw_step_annotations(plot_ui: &mut egui_plot::PlotUi, annotation: &StepAnnotation) {
+    use egui_plot::{Line, PlotPoints, Text};
+
+    let color = Color32::from_rgb(255, 140, 0);
+    let band_color = Color32::from_rgba_unmultiplied(255, 140, 0, 120);
+    for (i, metrics) in annotation.steps.iter().enumerate() {
+        let step = &metrics.step;
+        plot_ui.vline(
+            VLine::new(format!("Step {}", i + 1), step.start)
+                .color(color)
+                .width(1.0)
+                .style(egui_plot::LineStyle::dashed_dense()),
+        );
+
+        let half_width = metrics.settling_half_width(annotation.settling_band);
+        for offset in [-half_width, half_width] {
+            let y = metrics.final_value + offset;
+            plot_ui.line(
+                Line::new(
+                    format!("Step {} band", i + 1),
+                    PlotPoints::from(vec![[step.start, y], [step.end, y]]),
+                )
+                .color(band_color)
+                .width(1.0)
+                .style(egui_plot::LineStyle::dotted_dense()),
+            );
+        }
+
+        if metrics.overshoot_percent > 0.0 {
+            let peak = PlotPoint::new(step.start + metrics.peak_time, metrics.peak_value);
+            plot_ui.text(
+                Text::new(
+                    format!("Step {} peak", i + 1),
+                    peak,
+                    format!("OS {:.1}%", metrics.overshoot_percent),
+                )
+                .color(color)
+                .anchor(egui::Align2::CENTER_BOTTOM),
+            );
+        }
+    }
+}
+
 fn render_plot(state: &mut TimeSeriesState, shared: &mut SharedState<'_>, ui: &mut Ui) {
     use egui_plot::{AxisHints, Line, Plot, PlotPoints, Points};
 
     let current_time = shared.ctx.display_time;
 
-    let (x_min, x_max) = if shared.state.settings.autoscale_x {
-        let window = shared.state.settings.display_time_window;
-        (current_time - window, current_time)
-    } else if let (Some(min), Some(max)) = (shared.state.settings.x_min, shared.state.settings.x_max) {
-        (min, max)
-    } else {
-        let window = shared.state.settings.display_time_window;
-        (current_time - window, current_time)
-    };
+    let (x_min, x_max) = shared.state.settings.visible_x_range(current_time);
 
     let mut plot = Plot::new("ts_data_plot")
         .legend(egui_plot::Legend::default())
@@ -874,24 +941,17 @@ fn render_plot(state: &mut TimeSeriesState, shared: &mut SharedState<'_>, ui: &m
             };
 
             if let Some(data) = data {
-                let raw_points = data.as_plot_points();
-                let points = if let Some((cached_len, cached)) = state.decimation_cache.get(&var.id)
-                {
-                    if *cached_len == raw_points.len() {
-                        cached.clone()
-                    } else {
-                        let decimated = decimate_points(&raw_points, MAX_RENDER_POINTS);
+                // Raw samples or the min/max pyramid level that suits the viewport
+                let key = (data.lod.point_count(), x_min, x_max);
+                let points = match state.decimation_cache.get(&var.id) {
+                    Some((cached_key, cached)) if *cached_key == key => cached.clone(),
+                    _ => {
+                        let decimated = data.plot_points_in(x_min, x_max, MAX_RENDER_POINTS);
                         state
                             .decimation_cache
-                            .insert(var.id, (raw_points.len(), decimated.clone()));
+                            .insert(var.id, (key, decimated.clone()));
                         decimated
                     }
-                } else {
-                    let decimated = decimate_points(&raw_points, MAX_RENDER_POINTS);
-                    state
-                        .decimation_cache
-                        .insert(var.id, (raw_points.len(), decimated.clone()));
-                    decimated
                 };
 
                 if points.is_empty() {
@@ -985,6 +1045,63 @@ fn render_plot(state: &mut TimeSeriesState, shared: &mut SharedState<'_>, ui: &m
             plot_ui.vline(vline);
         }
 
+        // Draw filter designer overlays for plotted variables
+        for overlay in shared.state.topics.filter_overlays.values() {
+            let Some(var) = shared.state.config.variables.get(&overlay.variable_id) else {
+                continue;
+            };
+            if !var.enabled || !var.show_in_graph || overlay.points.is_empty() {
+                continue;
+            }
+            let color = Color32::from_rgba_unmultiplied(
+                var.color[0],
+                var.color[1],
+                var.color[2],
+                var.color[3],
+            )
+            .gamma_multiply(0.8);
+            let points = decimate_points(&overlay.points, MAX_RENDER_POINTS);
+            plot_ui.line(
+                Line::new(
+                    format!("{} ({})", var.name, overlay.label),
+                    PlotPoints::from(points),
+                )
+                .color(color)
+                .width(1.5)
+                .style(egui_plot::LineStyle::dashed_dense()),
+            );
+        }
+
+        // Draw recordings overlaid by comparison panes
+        for overlay in shared.state.topics.recording_overlays.values().flatten() {
+            if overlay.points.is_empty() {
+                continue;
+            }
+            let points = decimate_points(&overlay.points, MAX_RENDER_POINTS);
+            let (color, style) = run_style(overlay.run, overlay.difference);
+            let width = if overlay.difference { 1.0 } else { 1.5 };
+            plot_ui.line(
+                Line::new(overlay.label.as_str(), PlotPoints::from(points))
+                    .color(color)
+                    .width(width)
+                    .style(style),
+            );
+        }
+
+        // Draw step-response annotations for plotted variables
+        for annotation in shared.state.topics.step_annotations.values() {
+            let shown = shared
+                .state
+                .config
+                .variables
+                .get(&annotation.variable_id)
+                .is_some_and(|v| v.enabled && v.show_in_graph);
+            if !shown {
+                continue;
+            }
+            draw_step_annotations(plot_ui, annotation);
+        }
+
         // Draw threshold lines
         for threshold in &state.threshold_lines {
             if !threshold.visible {
@@ -1309,13 +1426,17 @@ mod tests {
         let points1 = vec![[0.0, 1.0], [1.0, 2.0], [2.0, 3.0]];
         let points2 = vec![[0.0, 10.0], [1.0, 20.0]];
 
-        state.decimation_cache.insert(1, (100, points1.clone()));
-        state.decimation_cache.insert(2, (50, points2.clone()));
+        state
+            .decimation_cache
+            .insert(1, ((100, 0.0, 10.0), points1.clone()));
+        state
+            .decimation_cache
+            .insert(2, ((50, 0.0, 10.0), points2.clone()));
 
         assert_eq!(state.decimation_cache.len(), 2);
 
         // Verify cache content
-        let (source_count, cached_points) = state.decimation_cache.get(&1).unwrap();
+        let ((source_count, _, _), cached_points) = state.decimation_cache.get(&1).unwrap();
         assert_eq!(*source_count, 100);
         assert_eq!(cached_points.len(), 3);
 
@@ -1496,8 +1617,12 @@ mod tests {
         let mut state = TimeSeriesState::default();
 
         // Cache some data
-        state.decimation_cache.insert(1, (100, vec![[0.0, 1.0]]));
-        state.decimation_cache.insert(2, (50, vec![[0.0, 2.0]]));
+        state
+            .decimation_cache
+            .insert(1, ((100, 0.0, 10.0), vec![[0.0, 1.0]]));
+        state
+            .decimation_cache
+            .insert(2, ((50, 0.0, 10.0), vec![[0.0, 2.0]]));
 
         assert_eq!(state.decimation_cache.len(), 2);
 
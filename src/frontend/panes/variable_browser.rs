@@ -16,6 +16,9 @@ use crate::frontend::workspace::PaneKind;
 /// Maximum number of array elements to display when expanding an array
 const MAX_ARRAY_ELEMENTS: u64 = 1024;
 
+/// Number of bytes read behind a `char*` (the string ends at the first NUL)
+const POINTER_STRING_LEN: usize = 64;
+
 /// State for the Variable Browser pane
 #[derive(Default)]
 pub struct VariableBrowserState {
@@ -464,7 +467,34 @@ fn render_type_tree(
             }
         }
 
-        if is_addable {
+        let is_char_pointer = type_handle
+            .as_ref()
+            .map(|h| h.is_char_pointer())
+            .unwrap_or(false);
+
+        if is_addable && is_char_pointer {
+            if ui
+                .small_button("+")
+                .on_hover_text("Add string (reads the pointer, then the text it points to)")
+                .clicked()
+            {
+                let pointer_type = type_handle
+                    .as_ref()
+                    .map(|h| h.to_variable_type())
+                    .unwrap_or(crate::types::VariableType::U32);
+                let pointer_var = Variable::new(name, address, pointer_type);
+                struct_add_actions.push(AppAction::AddStructVariable {
+                    parent: pointer_var,
+                    children: vec![ChildVariableSpec {
+                        name: format!("*{}", name),
+                        address_mode: ChildAddressMode::RelativeToPointer { offset: 0 },
+                        var_type: crate::types::VariableType::Text(POINTER_STRING_LEN),
+                        children: vec![],
+                    }],
+                    pointer_poll_rate_hz: Some(1),
+                });
+            }
+        } else if is_addable {
             let hover_text = if is_pointer && can_expand {
                 "Add pointer variable (reads address stored in pointer)"
             } else if is_pointer {
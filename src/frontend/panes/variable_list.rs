@@ -186,7 +186,13 @@ pub fn render(
     if let Some(id) = deferred.var_to_open_detail {
         if let Some(var) = shared.state.config.find_variable(id) {
             state.variable_detail_state =
-                VariableDetailState::for_variable(id, &var.name, &var.unit, var.color);
+                VariableDetailState::for_variable(
+                    id,
+                    &var.name,
+                    &var.unit,
+                    var.color,
+                    var.var_type,
+                );
             state.variable_detail_open = true;
         }
     }
@@ -406,11 +412,24 @@ fn render_variable_card_simple(
                         }
                     }
                 } else if let Some(data) = shared.state.topics.variable_data.get(&var.id) {
-                    if let Some(point) = data.last() {
-                        let value_text = if var.unit.is_empty() {
+                    if let (true, Some(text)) = (var.var_type.is_text(), &data.last_text) {
+                        ui.label(
+                            egui::RichText::new(format!("\"{}\"", text))
+                                .monospace()
+                                .color(var_color),
+                        );
+                    } else if let Some(point) = data.last() {
+                        // Without a converter, show integers exactly instead of via f64
+                        let exact = var.converter_script.is_none() && point.raw_value.is_integer();
+                        let value = if exact {
+                            point.raw_value.to_string()
+                        } else {
                             format!("{:.3}", point.converted_value)
+                        };
+                        let value_text = if var.unit.is_empty() {
+                            value
                         } else {
-                            format!("{:.3} {}", point.converted_value, var.unit)
+                            format!("{} {}", value, var.unit)
                         };
                         ui.label(egui::RichText::new(value_text).monospace().color(var_color));
                     } else {
@@ -556,7 +575,14 @@ fn render_variable_card_advanced(
                         }
                     }
                 } else if let Some(data) = shared.state.topics.variable_data.get(&var.id) {
-                    if let Some(point) = data.last() {
+                    if let (true, Some(text)) = (var.var_type.is_text(), &data.last_text) {
+                        ui.label(
+                            egui::RichText::new(format!("\"{}\"", text))
+                                .monospace()
+                                .size(14.0)
+                                .color(var_color),
+                        );
+                    } else if let Some(point) = data.last() {
                         let value_text = if var.unit.is_empty() {
                             format!("{:.3}", point.converted_value)
                         } else {
@@ -588,8 +614,15 @@ fn render_variable_card_advanced(
                             state.value_editor_open = true;
                         }
 
+                        let hex = point.raw_value.format_hex(var.var_type.size_bytes());
                         if can_edit {
-                            value_response.on_hover_text("Double-click to edit value");
+                            let hint = match hex {
+                                Some(hex) => format!("Raw: {}\nDouble-click to edit value", hex),
+                                None => "Double-click to edit value".to_string(),
+                            };
+                            value_response.on_hover_text(hint);
+                        } else if let Some(hex) = hex {
+                            value_response.on_hover_text(format!("Raw: {}", hex));
                         }
                     } else {
                         ui.label(egui::RichText::new("—").color(Color32::GRAY));
@@ -809,7 +842,7 @@ pub fn render_dialogs(
     if state.variable_detail_open {
         let var_id = state.variable_detail_state.var_id;
         if let Some(var_id) = var_id {
-            let (address, var_type, enabled, show_in_graph, current_value) =
+            let (address, enabled, show_in_graph, current_value) =
                 match shared.state.config.find_variable(var_id) {
                     Some(var) => {
                         let value = shared.state
@@ -820,7 +853,6 @@ pub fn render_dialogs(
                             .map(|p| p.converted_value);
                         (
                             var.address(),
-                            var.var_type.to_string(),
                             var.enabled,
                             var.show_in_graph,
                             value,
@@ -834,7 +866,6 @@ pub fn render_dialogs(
 
             let dialog_ctx = VariableDetailContext {
                 address,
-                var_type,
                 enabled,
                 show_in_graph,
                 current_value,
@@ -852,16 +883,28 @@ pub fn render_dialogs(
                         name,
                         unit,
                         color,
+                        var_type,
                     } => {
+                        let mut type_changed = None;
                         if let Some(var) = shared.state.config.find_variable_mut(var_id) {
                             var.name = name.clone();
                             var.unit = unit.clone();
                             var.color = color;
+                            if var.var_type != var_type {
+                                var.var_type = var_type;
+                                type_changed = Some(var.clone());
+                            }
                         }
                         if let Some(data) = shared.state.topics.variable_data.get_mut(&var_id) {
                             data.variable.name = name;
                             data.variable.unit = unit;
                             data.variable.color = color;
+                            data.variable.var_type = var_type;
+                        }
+                        // Old samples were decoded with the previous type
+                        if let Some(var) = type_changed {
+                            actions.push(AppAction::UpdateVariable(var));
+                            actions.push(AppAction::ClearVariableData(var_id));
                         }
                     }
                 }
@@ -87,7 +87,19 @@ pub fn render(
                         ui.colored_label(color, &var.name);
 
                         if let Some(data) = shared.state.topics.variable_data.get(&var.id) {
-                            if let Some(last) = data.last() {
+                            if var.var_type.is_text() {
+                                match &data.last_text {
+                                    Some(text) => {
+                                        ui.label(
+                                            egui::RichText::new(format!("\"{}\"", text))
+                                                .monospace(),
+                                        );
+                                    }
+                                    None => {
+                                        ui.label("—");
+                                    }
+                                }
+                            } else if let Some(last) = data.last() {
                                 ui.label(
                                     egui::RichText::new(format!("{:.4}", last.converted_value))
                                         .monospace(),
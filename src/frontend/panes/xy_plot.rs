@@ -0,0 +1,345 @@
+//! X-Y Plot pane - One variable against another
+//!
+//! Plots one or more Y variables against an X variable instead of time, for
+//! phase portraits, Lissajous figures, alpha-beta vectors or 2D position
+//! traces. Samples are paired by timestamp: the X variable's samples set the
+//! instants and each Y variable is taken at its nearest sample or
+//! interpolated. Only a trailing time window is drawn, optionally fading
+//! towards its oldest end.
+
+use egui::{Color32, Ui};
+
+use crate::analysis::{xy_trace, Alignment, XySample};
+use crate::frontend::pane_trait::Pane;
+use crate::frontend::state::{AppAction, SharedState};
+use crate::frontend::workspace::PaneKind;
+
+/// Number of segments a faded trail is drawn in
+const FADE_SEGMENTS: usize = 24;
+
+/// Opacity of the oldest end of a faded trail
+const MIN_FADE_ALPHA: f32 = 0.08;
+
+/// State for the X-Y Plot pane
+pub struct XyPlotState {
+    /// Variable plotted on the X axis
+    pub x_variable_id: Option<u32>,
+    /// Variables plotted on the Y axis
+    pub y_variable_ids: Vec<u32>,
+    /// How Y samples are matched to X timestamps
+    pub alignment: Alignment,
+    /// Length of the trail in seconds
+    pub trail_secs: f64,
+    /// Fade the trail towards its oldest end
+    pub fade: bool,
+    /// Connect the samples with lines instead of drawing points
+    pub connect: bool,
+    /// Use the same scale on both axes
+    pub equal_aspect: bool,
+}
+
+impl Default for XyPlotState {
+    fn default() -> Self {
+        Self {
+            x_variable_id: None,
+            y_variable_ids: Vec::new(),
+            alignment: Alignment::default(),
+            trail_secs: 2.0,
+            fade: true,
+            connect: true,
+            equal_aspect: false,
+        }
+    }
+}
+
+/// Opacity of trail segment `index` of `count`, oldest first
+fn fade_alpha(index: usize, count: usize) -> f32 {
+    if count <= 1 {
+        return 1.0;
+    }
+    let age = 1.0 - index as f32 / (count - 1) as f32;
+    1.0 - age * (1.0 - MIN_FADE_ALPHA)
+}
+
+/// Split a trace into `count` consecutive chunks that share their boundary points
+fn trail_segments(trace: &[XySample], count: usize) -> Vec<&[XySample]> {
+    if trace.len() < 2 || count == 0 {
+        return vec![trace];
+    }
+    let count = count.min(trace.len() - 1);
+    (0..count)
+        .map(|i| {
+            let start = i * (trace.len() - 1) / count;
+            let end = (i + 1) * (trace.len() - 1) / count;
+            &trace[start..=end]
+        })
+        .collect()
+}
+
+/// Draw one trace, faded towards its oldest end if requested
+fn draw_trace(
+    plot_ui: &mut egui_plot::PlotUi,
+    name: &str,
+    trace: &[XySample],
+    color: Color32,
+    fade: bool,
+    connect: bool,
+) {
+    use egui_plot::{Line, PlotPoints, Points};
+
+    let segments = if fade {
+        trail_segments(trace, FADE_SEGMENTS)
+    } else {
+        vec![trace]
+    };
+    let count = segments.len();
+    for (i, segment) in segments.into_iter().enumerate() {
+        let alpha = if fade { fade_alpha(i, count) } else { 1.0 };
+        let color = color.gamma_multiply(alpha);
+        let points: Vec<[f64; 2]> = segment.iter().map(|s| [s.x, s.y]).collect();
+        // Segments share a name so the legend shows one entry per trace
+        if connect {
+            plot_ui.line(
+                Line::new(name, PlotPoints::from(points))
+                    .color(color)
+                    .width(1.5),
+            );
+        } else {
+            plot_ui.points(
+                Points::new(name, PlotPoints::from(points))
+                    .color(color)
+                    .radius(1.5),
+            );
+        }
+    }
+
+    // Mark the newest sample
+    if let Some(head) = trace.last() {
+        plot_ui.points(
+            Points::new(name, PlotPoints::from(vec![[head.x, head.y]]))
+                .color(color)
+                .radius(4.0),
+        );
+    }
+}
+
+/// Render the X-Y plot pane
+pub fn render(
+    state: &mut XyPlotState,
+    shared: &mut SharedState<'_>,
+    ui: &mut Ui,
+) -> Vec<AppAction> {
+    use egui_plot::{Legend, Plot};
+
+    let variables = &shared.state.config.variables;
+    let plottable = || variables.values().filter(|v| v.enabled && v.show_in_graph);
+
+    // Toolbar
+    ui.horizontal_wrapped(|ui| {
+        ui.heading("X-Y Plot");
+        ui.separator();
+
+        ui.label("X:");
+        egui::ComboBox::from_id_salt("xy_plot_x_selector")
+            .selected_text(
+                state
+                    .x_variable_id
+                    .and_then(|id| variables.get(&id))
+                    .map(|v| v.name.as_str())
+                    .unwrap_or("Select..."),
+            )
+            .width(120.0)
+            .show_ui(ui, |ui| {
+                for var in plottable() {
+                    let is_selected = state.x_variable_id == Some(var.id);
+                    if ui.selectable_label(is_selected, &var.name).clicked() {
+                        state.x_variable_id = Some(var.id);
+                    }
+                }
+            });
+
+        ui.label("Y:");
+        let y_text = match state.y_variable_ids.as_slice() {
+            [] => "Select...".to_string(),
+            [id] => variables
+                .get(id)
+                .map(|v| v.name.clone())
+                .unwrap_or_default(),
+            ids => format!("{} variables", ids.len()),
+        };
+        ui.menu_button(y_text, |ui| {
+            for var in plottable() {
+                let mut selected = state.y_variable_ids.contains(&var.id);
+                if ui.checkbox(&mut selected, &var.name).changed() {
+                    if selected {
+                        state.y_variable_ids.push(var.id);
+                    } else {
+                        state.y_variable_ids.retain(|id| *id != var.id);
+                    }
+                }
+            }
+        });
+
+        ui.separator();
+
+        ui.label("Align:");
+        for alignment in Alignment::all() {
+            ui.selectable_value(&mut state.alignment, *alignment, alignment.display_name())
+                .on_hover_text("How Y samples are matched to the X timestamps");
+        }
+
+        ui.separator();
+
+        ui.label("Trail:");
+        ui.add(
+            egui::DragValue::new(&mut state.trail_secs)
+                .speed(0.05)
+                .range(0.01..=shared.state.settings.max_time_window)
+                .suffix(" s"),
+        )
+        .on_hover_text("Time span of samples drawn, ending at the current time");
+        ui.checkbox(&mut state.fade, "Fade")
+            .on_hover_text("Fade older samples of the trail");
+        ui.checkbox(&mut state.connect, "Lines")
+            .on_hover_text("Connect samples with lines instead of drawing points");
+        ui.checkbox(&mut state.equal_aspect, "Equal Axes")
+            .on_hover_text("Use the same scale on both axes");
+    });
+
+    ui.separator();
+
+    // Skip selections whose variables were removed or hidden
+    let variables = &shared.state.config.variables;
+    let plottable_id = |id: &u32| {
+        variables
+            .get(id)
+            .is_some_and(|v| v.enabled && v.show_in_graph)
+    };
+    let Some(x_id) = state.x_variable_id.filter(plottable_id) else {
+        ui.centered_and_justified(|ui| {
+            ui.label("Select an X variable and one or more Y variables");
+        });
+        return Vec::new();
+    };
+    if !state.y_variable_ids.iter().any(plottable_id) {
+        ui.centered_and_justified(|ui| {
+            ui.label("Select one or more Y variables");
+        });
+        return Vec::new();
+    }
+
+    let topics = &shared.state.topics;
+    let Some(x_data) = topics.variable_data.get(&x_id) else {
+        ui.centered_and_justified(|ui| {
+            ui.label("No data for the X variable");
+        });
+        return Vec::new();
+    };
+
+    let end = shared.ctx.display_time;
+    let start = end - state.trail_secs;
+    let traces: Vec<(String, Color32, Vec<XySample>)> = state
+        .y_variable_ids
+        .iter()
+        .filter(|id| plottable_id(id))
+        .filter_map(|id| {
+            let var = variables.get(id)?;
+            let y_data = topics.variable_data.get(id)?;
+            let trace = xy_trace(
+                &x_data.data_points,
+                &y_data.data_points,
+                start,
+                end,
+                state.alignment,
+            );
+            let [r, g, b, _] = var.color;
+            Some((var.name.clone(), Color32::from_rgb(r, g, b), trace))
+        })
+        .collect();
+
+    let x_name = variables
+        .get(&x_id)
+        .map(|v| v.name.clone())
+        .unwrap_or_default();
+    let y_label = if traces.len() == 1 {
+        traces[0].0.clone()
+    } else {
+        "Value".to_string()
+    };
+
+    let mut plot = Plot::new("xy_plot")
+        .legend(Legend::default())
+        .x_axis_label(x_name)
+        .y_axis_label(y_label);
+    if state.equal_aspect {
+        plot = plot.data_aspect(1.0);
+    }
+
+    let (fade, connect) = (state.fade, state.connect);
+    plot.show(ui, |plot_ui| {
+        for (name, color, trace) in &traces {
+            draw_trace(plot_ui, name, trace, *color, fade, connect);
+        }
+    });
+
+    if shared.state.settings.collecting && !shared.state.settings.paused {
+        ui.ctx().request_repaint();
+    }
+
+    Vec::new()
+}
+
+impl Pane for XyPlotState {
+    fn kind(&self) -> PaneKind {
+        PaneKind::XyPlot
+    }
+
+    fn render(&mut self, shared: &mut SharedState, ui: &mut Ui) -> Vec<AppAction> {
+        render(self, shared, ui)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(i: usize) -> XySample {
+        XySample {
+            time: i as f64,
+            x: i as f64,
+            y: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_fade_alpha() {
+        assert_eq!(fade_alpha(0, 1), 1.0);
+        assert!((fade_alpha(0, 10) - MIN_FADE_ALPHA).abs() < 1e-6);
+        assert_eq!(fade_alpha(9, 10), 1.0);
+        assert!(fade_alpha(3, 10) < fade_alpha(4, 10));
+    }
+
+    #[test]
+    fn test_trail_segments_are_contiguous() {
+        let trace: Vec<XySample> = (0..100).map(sample).collect();
+        let segments = trail_segments(&trace, FADE_SEGMENTS);
+        assert_eq!(segments.len(), FADE_SEGMENTS);
+        assert_eq!(segments[0][0].time, 0.0);
+        assert_eq!(segments[FADE_SEGMENTS - 1].last().unwrap().time, 99.0);
+        for pair in segments.windows(2) {
+            assert_eq!(pair[0].last().unwrap().time, pair[1][0].time);
+        }
+
+        // Short traces get at most one segment per line
+        let short: Vec<XySample> = (0..3).map(sample).collect();
+        assert_eq!(trail_segments(&short, FADE_SEGMENTS).len(), 2);
+        assert_eq!(trail_segments(&short[..1], FADE_SEGMENTS).len(), 1);
+    }
+}
@@ -18,13 +18,17 @@
 //! - [`PlotStatistics`] - Statistical analysis of variable data
 //! - [`PlotCursor`] - Cursor tracking for data inspection
 //! - [`ColorPalette`] - Color generation for multiple variables
+//! - [`run_style`] - Colour and line style of overlaid recording runs
+//! - [`variable_selector`] - Combo box for picking a plotted variable
+//! - [`frequency_points`] - Frequency axis mapping shared by the spectrum plots
 
 use crate::config::settings::RuntimeSettings;
 use crate::config::UiConfig;
-use crate::types::VariableData;
+use crate::types::{Variable, VariableData};
 use egui::{Color32, Ui};
 use egui_plot::{
-    Corner, GridMark, Legend, Line, Plot, PlotBounds, PlotPoint, PlotPoints, PlotUi, VLine,
+    Corner, GridMark, Legend, Line, LineStyle, Plot, PlotBounds, PlotPoint, PlotPoints, PlotUi,
+    VLine,
 };
 use std::collections::HashMap;
 
@@ -713,10 +717,81 @@ impl PlotCursor {
     }
 }
 
+/// Colours of the compared runs, in comparison order
+const RUN_COLORS: [Color32; 6] = [
+    Color32::from_rgb(100, 150, 255),
+    Color32::from_rgb(255, 140, 60),
+    Color32::from_rgb(90, 200, 120),
+    Color32::from_rgb(220, 90, 200),
+    Color32::from_rgb(230, 210, 70),
+    Color32::from_rgb(80, 210, 220),
+];
+
+/// Colour and line style of a compared run (or of its difference trace)
+pub fn run_style(run: usize, difference: bool) -> (Color32, LineStyle) {
+    let color = RUN_COLORS[run % RUN_COLORS.len()];
+    if difference {
+        return (color.gamma_multiply(0.7), LineStyle::dotted_dense());
+    }
+    let style = match (run / RUN_COLORS.len() + run) % 3 {
+        0 => LineStyle::Solid,
+        1 => LineStyle::dashed_loose(),
+        _ => LineStyle::dashed_dense(),
+    };
+    (color, style)
+}
+
+/// Combo box listing plottable variables
+pub fn variable_selector(
+    ui: &mut Ui,
+    id_salt: &str,
+    selected: &mut Option<u32>,
+    variables: &HashMap<u32, Variable>,
+) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(
+            selected
+                .and_then(|id| variables.get(&id))
+                .map(|v| v.name.as_str())
+                .unwrap_or("Select..."),
+        )
+        .width(120.0)
+        .show_ui(ui, |ui| {
+            for var in variables.values() {
+                if var.enabled && var.show_in_graph {
+                    let is_selected = *selected == Some(var.id);
+                    if ui.selectable_label(is_selected, &var.name).clicked() {
+                        *selected = Some(var.id);
+                        changed = true;
+                    }
+                }
+            }
+        });
+    changed
+}
+
+/// Map a frequency onto a plot's x axis, as log10 on a logarithmic axis
+pub fn frequency_x(log_frequency: bool, frequency: f64) -> f64 {
+    if log_frequency {
+        frequency.log10()
+    } else {
+        frequency
+    }
+}
+
+/// Plot points with the frequency mapped onto the x axis
+pub fn frequency_points(log_frequency: bool, points: Vec<[f64; 2]>) -> Vec<[f64; 2]> {
+    points
+        .into_iter()
+        .map(|[f, y]| [frequency_x(log_frequency, f), y])
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{DataPoint, Variable, VariableData};
+    use crate::types::{DataPoint, RawValue, Variable, VariableData};
     use std::time::Duration;
 
     #[test]
@@ -868,7 +943,7 @@ mod tests {
         for i in 1..=5 {
             let dp = DataPoint {
                 timestamp: Duration::from_secs(i as u64),
-                raw_value: i as f64,
+                raw_value: RawValue::Float(i as f64),
                 converted_value: i as f64,
             };
             data.data_points.push_back(dp);
@@ -898,7 +973,7 @@ mod tests {
         for i in 0..10 {
             let dp = DataPoint {
                 timestamp: Duration::from_secs(i as u64),
-                raw_value: i as f64,
+                raw_value: RawValue::Float(i as f64),
                 converted_value: i as f64,
             };
             data.data_points.push_back(dp);
@@ -994,7 +1069,7 @@ mod tests {
         for i in 0..5 {
             let dp = DataPoint {
                 timestamp: Duration::from_secs(i as u64),
-                raw_value: i as f64,
+                raw_value: RawValue::Float(i as f64),
                 converted_value: i as f64,
             };
             data.data_points.push_back(dp);
@@ -1003,7 +1078,7 @@ mod tests {
         // Add NaN (gap marker)
         data.data_points.push_back(DataPoint {
             timestamp: Duration::from_secs(5),
-            raw_value: f64::NAN,
+            raw_value: RawValue::Float(f64::NAN),
             converted_value: f64::NAN,
         });
 
@@ -1011,7 +1086,7 @@ mod tests {
         for i in 6..10 {
             let dp = DataPoint {
                 timestamp: Duration::from_secs(i as u64),
-                raw_value: i as f64,
+                raw_value: RawValue::Float(i as f64),
                 converted_value: i as f64,
             };
             data.data_points.push_back(dp);
@@ -1036,7 +1111,7 @@ mod tests {
         for i in 0..5 {
             data.data_points.push_back(DataPoint {
                 timestamp: Duration::from_secs(i as u64),
-                raw_value: f64::NAN,
+                raw_value: RawValue::Float(f64::NAN),
                 converted_value: f64::NAN,
             });
         }
@@ -1059,7 +1134,7 @@ mod tests {
         for i in -5..=5 {
             data.data_points.push_back(DataPoint {
                 timestamp: Duration::from_secs((i + 5) as u64),
-                raw_value: i as f64,
+                raw_value: RawValue::Float(i as f64),
                 converted_value: i as f64,
             });
         }
@@ -1101,6 +1176,19 @@ mod tests {
         assert!(view.follow_latest);
     }
 
+    #[test]
+    fn test_run_styles_are_distinct() {
+        let styles: Vec<_> = (0..RUN_COLORS.len())
+            .map(|run| run_style(run, false))
+            .collect();
+        for (i, a) in styles.iter().enumerate() {
+            for b in &styles[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+        assert_ne!(run_style(0, false), run_style(0, true));
+    }
+
     // Property-based tests using proptest
     use proptest::prelude::*;
 
@@ -1115,7 +1203,7 @@ mod tests {
             for (i, &val) in values.iter().enumerate() {
                 data.data_points.push_back(DataPoint {
                     timestamp: Duration::from_secs(i as u64),
-                    raw_value: val,
+                    raw_value: RawValue::Float(val),
                     converted_value: val,
                 });
             }
@@ -1139,7 +1227,7 @@ mod tests {
             for i in 0..count {
                 data.data_points.push_back(DataPoint {
                     timestamp: Duration::from_secs(i as u64),
-                    raw_value: i as f64,
+                    raw_value: RawValue::Float(i as f64),
                     converted_value: i as f64,
                 });
             }
@@ -1162,7 +1250,7 @@ mod tests {
             for (i, &val) in finite_values.iter().enumerate() {
                 data.data_points.push_back(DataPoint {
                     timestamp: Duration::from_secs(i as u64),
-                    raw_value: val,
+                    raw_value: RawValue::Float(val),
                     converted_value: val,
                 });
             }
@@ -1170,7 +1258,7 @@ mod tests {
             if has_pos_inf {
                 data.data_points.push_back(DataPoint {
                     timestamp: Duration::from_secs(100),
-                    raw_value: f64::INFINITY,
+                    raw_value: RawValue::Float(f64::INFINITY),
                     converted_value: f64::INFINITY,
                 });
             }
@@ -1178,7 +1266,7 @@ mod tests {
             if has_neg_inf {
                 data.data_points.push_back(DataPoint {
                     timestamp: Duration::from_secs(101),
-                    raw_value: f64::NEG_INFINITY,
+                    raw_value: RawValue::Float(f64::NEG_INFINITY),
                     converted_value: f64::NEG_INFINITY,
                 });
             }
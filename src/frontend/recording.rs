@@ -0,0 +1,402 @@
+//! Live session recording driven by the Recorder pane
+//!
+//! The app owns one [`LiveRecorder`] and feeds it every sample it takes from
+//! the backend, so a recording keeps the collection rate rather than the UI
+//! frame rate. A recording is kept in memory and added to the saved
+//! recordings when it stops, or streamed to a binary session file so it can
+//! run for as long as disk allows.
+//!
+//! In-memory recordings are journaled to the app's journal directory, so a
+//! recording cut short by a crash is offered for recovery at the next launch.
+//!
+//! [`LivePersistence`] writes the same samples to the Data Persistence file
+//! while collection runs.
+
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::DataPersistenceConfig;
+use crate::frontend::topics::Topics;
+use crate::session::{PersistenceWriter, SessionMetadata, SessionRecorder, SessionState};
+use crate::types::{DataPoint, Variable};
+
+/// Recording settings chosen in the Recorder pane
+#[derive(Debug, Clone)]
+pub struct RecordingRequest {
+    /// Session name
+    pub name: String,
+    /// Max frames (0 = unlimited)
+    pub max_frames: usize,
+    /// Minimum time between samples of one variable
+    pub sample_interval: Duration,
+    /// Session file to stream to instead of keeping the recording in memory
+    pub output: Option<PathBuf>,
+}
+
+/// Records live samples and publishes its status to [`Topics`]
+#[derive(Debug, Default)]
+pub struct LiveRecorder {
+    recorder: SessionRecorder,
+    /// Sample timestamp at the clock origin and the recording time there
+    clock: (Duration, Duration),
+    /// Recording time of the latest sample
+    last_elapsed: Duration,
+}
+
+impl LiveRecorder {
+    /// Create an idle recorder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an idle recorder journaling in-memory recordings to `dir`
+    pub fn with_journal_dir(dir: Option<PathBuf>) -> Self {
+        let mut live = Self::default();
+        live.recorder.set_journal_dir(dir);
+        live
+    }
+
+    /// Whether a recording is in progress
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_recording()
+    }
+
+    /// Start recording `variables`, with `now` the timestamp of live samples
+    pub fn start(
+        &mut self,
+        request: RecordingRequest,
+        variables: Vec<Variable>,
+        now: Duration,
+        topics: &mut Topics,
+    ) -> io::Result<()> {
+        let mut metadata = SessionMetadata::new(request.name);
+        metadata.variables = variables;
+
+        self.recorder.set_max_frames(request.max_frames);
+        self.recorder.set_sample_interval(request.sample_interval);
+        match &request.output {
+            Some(path) => self.recorder.start_recording_to_file(metadata, path)?,
+            None => self.recorder.start_recording(metadata),
+        }
+        self.clock = (now, Duration::ZERO);
+        self.last_elapsed = Duration::ZERO;
+        topics.recorder_output = None;
+        self.publish(topics);
+        Ok(())
+    }
+
+    /// Live sample timestamps start from zero again (collection restarted)
+    ///
+    /// The recording carries on from its latest sample.
+    pub fn restart_clock(&mut self) {
+        self.clock = (Duration::ZERO, self.last_elapsed);
+    }
+
+    /// Record a live sample
+    pub fn record(&mut self, var_id: u32, point: &DataPoint) {
+        if !self.recorder.is_recording() {
+            return;
+        }
+        let (origin, at_origin) = self.clock;
+        let elapsed = at_origin + point.timestamp.saturating_sub(origin);
+        self.last_elapsed = self.last_elapsed.max(elapsed);
+        self.recorder.record_point(var_id, point, elapsed);
+    }
+
+    /// Write the samples recorded so far to disk (collection stopped)
+    pub fn checkpoint(&mut self) {
+        self.recorder.checkpoint();
+    }
+
+    /// Checkpoint the session file and publish the status, finishing a
+    /// recording the recorder stopped on its own (after a write error)
+    pub fn update(&mut self, topics: &mut Topics) {
+        match self.recorder.state() {
+            SessionState::Recording => {
+                self.recorder.checkpoint_if_due();
+                self.publish(topics);
+            }
+            SessionState::Stopped => self.finish(topics),
+            _ => {}
+        }
+    }
+
+    /// Stop recording and hand over the result
+    pub fn stop(&mut self, topics: &mut Topics) {
+        self.recorder.stop_recording();
+        self.finish(topics);
+    }
+
+    /// Stop recording and discard the result
+    pub fn cancel(&mut self, topics: &mut Topics) {
+        self.recorder.cancel_recording();
+        self.publish(topics);
+    }
+
+    /// Finish a recording streamed to a file as the app exits
+    ///
+    /// An in-memory recording is checkpointed and left in its journal, to be
+    /// offered for recovery at the next launch.
+    pub fn shutdown(&mut self, topics: &mut Topics) {
+        if self.recorder.is_recording() && self.recorder.output_path().is_some() {
+            self.stop(topics);
+        } else {
+            self.checkpoint();
+        }
+    }
+
+    /// Add a finished recording to the saved recordings, or remember the
+    /// file it was written to
+    fn finish(&mut self, topics: &mut Topics) {
+        let output = self.recorder.output_path().map(|p| p.to_path_buf());
+        let recording = self.recorder.take_recording();
+        match output {
+            Some(path) => {
+                tracing::info!("Recording written to {:?}", path);
+                topics.recorder_output = Some(path);
+            }
+            None => {
+                tracing::info!("Recording complete: {} frames", recording.frames.len());
+                topics.completed_recordings.push(recording);
+            }
+        }
+        self.publish(topics);
+    }
+
+    fn publish(&self, topics: &mut Topics) {
+        topics.recorder_state = self.recorder.state();
+        topics.recorder_frame_count = self.recorder.frame_count();
+    }
+}
+
+/// Writes live samples to the Data Persistence file
+#[derive(Debug, Default)]
+pub struct LivePersistence {
+    writer: Option<PersistenceWriter>,
+    /// Why writing stopped early, until it is reported
+    error: Option<String>,
+}
+
+impl LivePersistence {
+    /// Create an idle writer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether samples are being written
+    pub fn is_active(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// Open the persistence file if persistence is enabled
+    pub fn start(
+        &mut self,
+        config: &DataPersistenceConfig,
+        variables: &[Variable],
+    ) -> io::Result<()> {
+        self.stop();
+        if !config.enabled {
+            return Ok(());
+        }
+        let Some(path) = &config.file_path else {
+            return Ok(());
+        };
+        self.writer = Some(PersistenceWriter::create(config, path, variables)?);
+        tracing::info!("Persisting data to {:?} as {}", path, config.format);
+        Ok(())
+    }
+
+    /// Write a live sample
+    pub fn record(&mut self, var_id: u32, point: &DataPoint) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        if let Err(e) = writer.write(var_id, point) {
+            self.error = Some(format!("Data persistence stopped: {}", e));
+            self.stop();
+        } else if writer.is_full() {
+            tracing::warn!("Data persistence file reached its maximum size");
+            self.stop();
+        }
+    }
+
+    /// Flush written samples to disk, returning why writing stopped early
+    pub fn update(&mut self) -> Option<String> {
+        if let Some(writer) = &mut self.writer {
+            if let Err(e) = writer.flush() {
+                self.error = Some(format!("Data persistence stopped: {}", e));
+                self.stop();
+            }
+        }
+        self.error.take()
+    }
+
+    /// Close the persistence file
+    pub fn stop(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            let records = writer.record_count();
+            if let Err(e) = writer.finish() {
+                self.error = Some(format!("Failed to finish data persistence file: {}", e));
+            } else {
+                tracing::info!("Persisted {} samples", records);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::format::SessionFile;
+    use crate::types::{RawValue, VariableType};
+
+    fn point(ms: u64, value: u64) -> DataPoint {
+        DataPoint::with_conversion(
+            Duration::from_millis(ms),
+            RawValue::UInt(value),
+            value as f64,
+        )
+    }
+
+    fn request(output: Option<PathBuf>) -> RecordingRequest {
+        RecordingRequest {
+            name: "Bench".to_string(),
+            max_frames: 0,
+            sample_interval: Duration::ZERO,
+            output,
+        }
+    }
+
+    #[test]
+    fn test_in_memory_recording() {
+        let var = Variable::new("speed", 0x2000_0000, VariableType::U32);
+        let mut topics = Topics::default();
+        let mut live = LiveRecorder::new();
+        live.start(
+            request(None),
+            vec![var.clone()],
+            Duration::from_millis(500),
+            &mut topics,
+        )
+        .unwrap();
+        assert_eq!(topics.recorder_state, SessionState::Recording);
+
+        for (ms, value) in [(500, 1), (510, 2), (520, 3)] {
+            live.record(var.id, &point(ms, value));
+        }
+        live.update(&mut topics);
+        assert_eq!(topics.recorder_frame_count, 3);
+
+        // Collection restarted: timestamps continue from the recording time
+        live.restart_clock();
+        live.record(var.id, &point(0, 4));
+
+        live.stop(&mut topics);
+        assert_eq!(topics.recorder_state, SessionState::Idle);
+        let recording = &topics.completed_recordings[0];
+        assert_eq!(recording.metadata.name, "Bench");
+        assert_eq!(recording.metadata.variables[0].name, "speed");
+        assert_eq!(recording.frame_count(), 4);
+        assert_eq!(recording.frames[2].timestamp, Duration::from_millis(20));
+        assert_eq!(recording.frames[3].timestamp, Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_recording_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bench.dvs");
+        let var = Variable::new("speed", 0x2000_0000, VariableType::U32);
+        let mut topics = Topics::default();
+        let mut live = LiveRecorder::new();
+        live.start(
+            request(Some(path.clone())),
+            vec![var.clone()],
+            Duration::ZERO,
+            &mut topics,
+        )
+        .unwrap();
+        for ms in 0..100 {
+            live.record(var.id, &point(ms, ms));
+        }
+        live.stop(&mut topics);
+
+        // Nothing is kept in memory; the file holds the recording
+        assert!(topics.completed_recordings.is_empty());
+        assert_eq!(topics.recorder_output.as_deref(), Some(path.as_path()));
+        let file = SessionFile::open(&path).unwrap();
+        assert_eq!(file.metadata().total_data_points, 100);
+        assert_eq!(file.metadata().variables[0].name, "speed");
+    }
+
+    #[test]
+    fn test_recover_interrupted_recording() {
+        use crate::session::journal::{orphaned_journals, recover};
+
+        let dir = tempfile::tempdir().unwrap();
+        let var = Variable::new("speed", 0x2000_0000, VariableType::U32);
+        let mut topics = Topics::default();
+        let mut live = LiveRecorder::with_journal_dir(Some(dir.path().to_path_buf()));
+        live.start(
+            request(None),
+            vec![var.clone()],
+            Duration::ZERO,
+            &mut topics,
+        )
+        .unwrap();
+        for ms in 0..20 {
+            live.record(var.id, &point(ms, ms));
+        }
+        live.checkpoint();
+
+        // A running recording holds its journal
+        assert!(orphaned_journals(dir.path()).is_empty());
+
+        // The app crashes: the recorder goes away without stopping
+        drop(live);
+        let journals = orphaned_journals(dir.path());
+        assert_eq!(journals.len(), 1);
+
+        let recording = recover(&journals[0]).unwrap();
+        assert_eq!(recording.metadata.name, "Bench (recovered)");
+        assert_eq!(recording.metadata.variables[0].name, "speed");
+        assert_eq!(recording.frame_count(), 20);
+        assert_eq!(
+            recording.frames[19].values[&var.id].raw_value,
+            RawValue::UInt(19)
+        );
+    }
+
+    #[test]
+    fn test_persistence_to_mcap() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.mcap");
+        let var = Variable::new("speed", 0x2000_0000, VariableType::U32);
+        let mut config = DataPersistenceConfig {
+            file_path: Some(path.clone()),
+            format: crate::config::PersistenceFormat::Mcap,
+            ..Default::default()
+        };
+        let mut persistence = LivePersistence::new();
+
+        // Nothing is written while persistence is disabled
+        persistence
+            .start(&config, std::slice::from_ref(&var))
+            .unwrap();
+        assert!(!persistence.is_active());
+
+        config.enabled = true;
+        persistence
+            .start(&config, std::slice::from_ref(&var))
+            .unwrap();
+        for ms in 0..10 {
+            persistence.record(var.id, &point(ms, ms));
+        }
+        assert_eq!(persistence.update(), None);
+        persistence.stop();
+
+        let recording = crate::session::mcap::read_mcap(&path).unwrap();
+        assert_eq!(recording.metadata.variables[0].name, "speed");
+        assert_eq!(recording.frame_count(), 10);
+    }
+}
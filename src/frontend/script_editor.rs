@@ -116,6 +116,25 @@ pub fn get_script_items() -> Vec<ScriptItem> {
             signature: Some("has_prev() -> bool".to_string()),
             category: ScriptItemCategory::Context,
         },
+        ScriptItem {
+            name: "state".to_string(),
+            description: "Read a per-variable state slot kept between samples (0.0 if unset)"
+                .to_string(),
+            signature: Some("state(index) -> f64".to_string()),
+            category: ScriptItemCategory::Context,
+        },
+        ScriptItem {
+            name: "set_state".to_string(),
+            description: "Write a per-variable state slot for the next sample".to_string(),
+            signature: Some("set_state(index, value)".to_string()),
+            category: ScriptItemCategory::Context,
+        },
+        ScriptItem {
+            name: "has_state".to_string(),
+            description: "Returns true once any state slot has been written".to_string(),
+            signature: Some("has_state() -> bool".to_string()),
+            category: ScriptItemCategory::Context,
+        },
         // === Variables ===
         ScriptItem {
             name: "value".to_string(),
@@ -129,6 +148,13 @@ pub fn get_script_items() -> Vec<ScriptItem> {
             signature: None,
             category: ScriptItemCategory::Variable,
         },
+        ScriptItem {
+            name: "raw_int".to_string(),
+            description: "The raw value as an exact 64-bit integer (u64 as bit pattern)"
+                .to_string(),
+            signature: None,
+            category: ScriptItemCategory::Variable,
+        },
         // === Transformer Functions ===
         ScriptItem {
             name: "derivative".to_string(),
@@ -170,6 +170,18 @@ pub enum AppAction {
     ClearData,
     /// Clear data for a specific variable
     ClearVariableData(u32),
+    /// Read a memory block as a whole on every poll (keyed by pane ID)
+    StartMemoryWatch { id: u64, address: u64, size: usize },
+    /// Stop a memory watch and drop its last snapshot
+    StopMemoryWatch(u64),
+
+    // Recording
+    /// Start recording live data
+    StartRecording(crate::frontend::recording::RecordingRequest),
+    /// Stop recording and keep the result
+    StopRecording,
+    /// Stop recording and discard the result
+    CancelRecording,
 
     // Pipeline node configuration
     /// Send a config key/value to a specific pipeline node
@@ -5,10 +5,14 @@
 //! Panes read from it via `shared.topics`.
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
+use crate::analysis::StepMetrics;
 use crate::backend::DetectedProbe;
+use crate::frontend::markers::MarkedRegion;
 use crate::pipeline::bridge::VariableNodeSnapshot;
+use crate::session::events::RecordingEvent;
 use crate::session::types::{SessionRecording, SessionState};
 use crate::types::{CollectionStats, ConnectionStatus, PointerState, VariableData};
 
@@ -26,6 +30,9 @@ pub struct Topics {
     /// Used when GraphSink nodes route data to specific panes.
     pub graph_pane_data: HashMap<u64, HashMap<u32, VariableData>>,
 
+    /// Recording shown in `variable_data` in place of the live buffers
+    pub playback: Option<Playback>,
+
     /// Collection statistics (updated ~2Hz from pipeline)
     pub stats: CollectionStats,
 
@@ -37,6 +44,8 @@ pub struct Topics {
     pub recorder_state: SessionState,
     /// Recorder frame count
     pub recorder_frame_count: usize,
+    /// File the last recording was written to, when recorded to a file
+    pub recorder_output: Option<PathBuf>,
 
     /// Whether exporter is active
     pub exporter_active: bool,
@@ -70,6 +79,31 @@ pub struct Topics {
     /// Pointer states for UI display (populated by backend worker)
     pub pointer_states: HashMap<u32, PointerState>,
 
+    /// Latest contents of each memory watch, keyed by watch (pane) ID
+    pub memory_snapshots: HashMap<u64, MemorySnapshot>,
+
+    // --- Cross-pane annotations ---
+    /// Marked regions of each time-series pane, keyed by pane ID
+    pub marker_regions: HashMap<u64, Vec<MarkedRegion>>,
+
+    /// Markers of each time-series pane as recording events, keyed by pane ID
+    pub plot_markers: HashMap<u64, Vec<RecordingEvent>>,
+
+    /// Time range `(start, end)` between the A/B cursors of each time-series
+    /// pane, keyed by pane ID
+    pub cursor_ranges: HashMap<u64, (f64, f64)>,
+
+    /// Step-response results to draw on time-series plots, keyed by step pane ID
+    pub step_annotations: HashMap<u64, StepAnnotation>,
+
+    /// Filtered copies of variables to overlay on time-series plots, keyed by
+    /// filter designer pane ID
+    pub filter_overlays: HashMap<u64, FilterOverlay>,
+
+    /// Aligned recordings to overlay on time-series plots, keyed by
+    /// recording comparison pane ID
+    pub recording_overlays: HashMap<u64, Vec<RecordingOverlay>>,
+
     /// Track when global data was last updated
     pub global_data_freshness: Option<Instant>,
 
@@ -81,15 +115,114 @@ pub struct Topics {
     pub staleness_threshold: Duration,
 }
 
+/// A recording driving every pane, with the live buffers set aside
+pub struct Playback {
+    /// Recorder pane playing the recording
+    pub pane: u64,
+    /// Playback position, shown as the display time
+    pub time: Duration,
+    /// Live buffers, restored when playback ends
+    live: HashMap<u32, VariableData>,
+}
+
+impl Topics {
+    /// Show a recording played by `pane` in place of the live buffers
+    ///
+    /// `variable_data` is replaced by empty buffers for the same variables,
+    /// without on-disk history, and the live buffers keep receiving data
+    /// through [`live_data_mut`](Self::live_data_mut). Does nothing if a
+    /// playback is already shown.
+    pub fn begin_playback(&mut self, pane: u64) {
+        if self.playback.is_some() {
+            return;
+        }
+        let buffers = self
+            .variable_data
+            .iter()
+            .map(|(id, data)| (*id, VariableData::new(data.variable.clone())))
+            .collect();
+        let live = std::mem::replace(&mut self.variable_data, buffers);
+        self.playback = Some(Playback {
+            pane,
+            time: Duration::ZERO,
+            live,
+        });
+    }
+
+    /// Restore the live buffers set aside by [`begin_playback`](Self::begin_playback)
+    pub fn end_playback(&mut self) {
+        if let Some(playback) = self.playback.take() {
+            self.variable_data = playback.live;
+        }
+    }
+
+    /// Buffers that data from the backend goes to
+    pub fn live_data_mut(&mut self) -> &mut HashMap<u32, VariableData> {
+        match &mut self.playback {
+            Some(playback) => &mut playback.live,
+            None => &mut self.variable_data,
+        }
+    }
+}
+
+/// Latest result of a memory watch (one bulk read per poll)
+#[derive(Debug, Clone, Default)]
+pub struct MemorySnapshot {
+    /// Time of the last successful read
+    pub timestamp: Duration,
+    /// Bytes from the last successful read
+    pub bytes: Vec<u8>,
+    /// Error from the most recent read, cleared by the next success
+    pub error: Option<String>,
+}
+
+/// Step-response metrics published for a response variable
+#[derive(Debug, Clone, Default)]
+pub struct StepAnnotation {
+    /// Variable whose response was measured
+    pub variable_id: u32,
+    /// Settling band used for the metrics, as a fraction of the step
+    pub settling_band: f64,
+    /// Metrics of each analyzed step
+    pub steps: Vec<StepMetrics>,
+}
+
+/// A display-only filtered series drawn over its source variable
+#[derive(Debug, Clone, Default)]
+pub struct FilterOverlay {
+    /// Variable the series was computed from
+    pub variable_id: u32,
+    /// Legend label (filter summary)
+    pub label: String,
+    /// Filtered plot points `[time, value]`
+    pub points: Vec<[f64; 2]>,
+}
+
+/// A recorded run (or its difference from the reference run) drawn on
+/// time-series plots
+#[derive(Debug, Clone, Default)]
+pub struct RecordingOverlay {
+    /// Legend label (variable and recording name)
+    pub label: String,
+    /// Plot points `[time, value]`, already placed on the plot's time axis
+    pub points: Vec<[f64; 2]>,
+    /// Position of the run in the comparison, selecting its colour and style
+    pub run: usize,
+    /// Whether the points are the run minus the reference run
+    pub difference: bool,
+}
+
 impl Default for Topics {
     fn default() -> Self {
         Self {
             variable_data: HashMap::new(),
             graph_pane_data: HashMap::new(),
+            playback: None,
             stats: CollectionStats::default(),
             connection_status: ConnectionStatus::Disconnected,
             recorder_state: SessionState::Idle,
             recorder_frame_count: 0,
+            recorder_output: None,
             exporter_active: false,
             exporter_rows_written: 0,
             available_probes: Vec::new(),
@@ -99,7 +232,14 @@ impl Default for Topics {
             project_file_path: None,
             elf_generation: 0,
             pointer_states: HashMap::new(),
+            memory_snapshots: HashMap::new(),
             pane_data_freshness: HashMap::new(),
+            marker_regions: HashMap::new(),
+            plot_markers: HashMap::new(),
+            cursor_ranges: HashMap::new(),
+            step_annotations: HashMap::new(),
+            filter_overlays: HashMap::new(),
+            recording_overlays: HashMap::new(),
             global_data_freshness: None,
             last_stats_update: None,
             staleness_threshold: Duration::from_secs(3),
@@ -118,12 +258,20 @@ mod tests {
 
         assert!(topics.variable_data.is_empty());
         assert!(topics.graph_pane_data.is_empty());
+        assert!(topics.playback.is_none());
         assert_eq!(topics.connection_status, ConnectionStatus::Disconnected);
         assert!(topics.available_probes.is_empty());
         assert!(topics.completed_recordings.is_empty());
         assert_eq!(topics.project_name, "");
         assert_eq!(topics.project_file_path, None);
         assert_eq!(topics.elf_generation, 0);
+        assert!(topics.memory_snapshots.is_empty());
+        assert!(topics.marker_regions.is_empty());
+        assert!(topics.plot_markers.is_empty());
+        assert!(topics.cursor_ranges.is_empty());
+        assert!(topics.step_annotations.is_empty());
+        assert!(topics.filter_overlays.is_empty());
+        assert!(topics.recording_overlays.is_empty());
         assert_eq!(topics.staleness_threshold, Duration::from_secs(3));
     }
 
@@ -139,6 +287,43 @@ mod tests {
         // so we just test the HashMap structure
     }
 
+    #[test]
+    fn test_playback_sets_live_data_aside() {
+        use crate::types::{DataPoint, Variable, VariableType};
+
+        let mut topics = Topics::default();
+        let var = Variable::new("speed", 0x2000_0000, VariableType::F32);
+        let mut data = VariableData::new(var.clone());
+        data.push(DataPoint::new(Duration::ZERO, 1.0));
+        topics.variable_data.insert(var.id, data);
+
+        topics.begin_playback(7);
+        assert!(topics.variable_data[&var.id].data_points.is_empty());
+        topics
+            .variable_data
+            .get_mut(&var.id)
+            .unwrap()
+            .push(DataPoint::new(Duration::ZERO, 5.0));
+        topics
+            .live_data_mut()
+            .get_mut(&var.id)
+            .unwrap()
+            .push(DataPoint::new(Duration::from_millis(10), 2.0));
+
+        // A second playback does not replace the first
+        topics.begin_playback(8);
+        assert_eq!(topics.playback.as_ref().unwrap().pane, 7);
+
+        topics.end_playback();
+        assert!(topics.playback.is_none());
+        let live: Vec<f64> = topics.variable_data[&var.id]
+            .data_points
+            .iter()
+            .map(|p| p.converted_value)
+            .collect();
+        assert_eq!(live, [1.0, 2.0]);
+    }
+
     #[test]
     fn test_graph_pane_data_insertion() {
         let mut topics = Topics::default();
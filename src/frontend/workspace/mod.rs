@@ -37,6 +37,16 @@ pub enum PaneKind {
     TimeSeries,
     Watcher,
     FftView,
+    TextLog,
+    StructInspector,
+    Spectrogram,
+    FrequencyResponse,
+    StepResponse,
+    FilterDesigner,
+    Histogram,
+    XyPlot,
+    LogicAnalyzer,
+    RecordingCompare,
 }
 
 /// Metadata entry for a pane
@@ -192,6 +202,16 @@ impl Workspace {
                 "TimeSeries" => PaneKind::TimeSeries,
                 "Watcher" => PaneKind::Watcher,
                 "FftView" => PaneKind::FftView,
+                "TextLog" => PaneKind::TextLog,
+                "StructInspector" => PaneKind::StructInspector,
+                "Spectrogram" => PaneKind::Spectrogram,
+                "FrequencyResponse" => PaneKind::FrequencyResponse,
+                "StepResponse" => PaneKind::StepResponse,
+                "FilterDesigner" => PaneKind::FilterDesigner,
+                "Histogram" => PaneKind::Histogram,
+                "XyPlot" => PaneKind::XyPlot,
+                "LogicAnalyzer" => PaneKind::LogicAnalyzer,
+                "RecordingCompare" => PaneKind::RecordingCompare,
                 // Legacy: skip PipelineEditor from old configs
                 "PipelineEditor" => {
                     tracing::info!("Skipping legacy PipelineEditor pane from saved layout");
@@ -86,4 +86,4 @@ pub use backend::{ProbeBackend, SwdCommand, SwdResponse};
 pub use config::{AppConfig, AppState, ProjectFile};
 pub use error::{DataVisError, Result};
 pub use scripting::{ExecutionContext, ScriptEngine};
-pub use types::{DataPoint, Variable, VariableType};
+pub use types::{DataPoint, RawValue, Variable, VariableType};
@@ -231,6 +231,96 @@ fn build_view_menu(state: &MenuBarState) -> Submenu {
         ))
         .unwrap();
 
+    view_menu
+        .append(&MenuItem::with_id(
+            MenuId::ViewNewTextLog.to_muda_id(),
+            t!("menu_view_new_text_log"),
+            true,
+            None::<Accelerator>,
+        ))
+        .unwrap();
+
+    view_menu
+        .append(&MenuItem::with_id(
+            MenuId::ViewNewStructInspector.to_muda_id(),
+            t!("menu_view_new_struct_inspector"),
+            true,
+            None::<Accelerator>,
+        ))
+        .unwrap();
+
+    view_menu
+        .append(&MenuItem::with_id(
+            MenuId::ViewNewSpectrogram.to_muda_id(),
+            t!("menu_view_new_spectrogram"),
+            true,
+            None::<Accelerator>,
+        ))
+        .unwrap();
+
+    view_menu
+        .append(&MenuItem::with_id(
+            MenuId::ViewNewFrequencyResponse.to_muda_id(),
+            t!("menu_view_new_frequency_response"),
+            true,
+            None::<Accelerator>,
+        ))
+        .unwrap();
+
+    view_menu
+        .append(&MenuItem::with_id(
+            MenuId::ViewNewStepResponse.to_muda_id(),
+            t!("menu_view_new_step_response"),
+            true,
+            None::<Accelerator>,
+        ))
+        .unwrap();
+
+    view_menu
+        .append(&MenuItem::with_id(
+            MenuId::ViewNewFilterDesigner.to_muda_id(),
+            t!("menu_view_new_filter_designer"),
+            true,
+            None::<Accelerator>,
+        ))
+        .unwrap();
+
+    view_menu
+        .append(&MenuItem::with_id(
+            MenuId::ViewNewHistogram.to_muda_id(),
+            t!("menu_view_new_histogram"),
+            true,
+            None::<Accelerator>,
+        ))
+        .unwrap();
+
+    view_menu
+        .append(&MenuItem::with_id(
+            MenuId::ViewNewXyPlot.to_muda_id(),
+            t!("menu_view_new_xy_plot"),
+            true,
+            None::<Accelerator>,
+        ))
+        .unwrap();
+
+    view_menu
+        .append(&MenuItem::with_id(
+            MenuId::ViewNewLogicAnalyzer.to_muda_id(),
+            t!("menu_view_new_logic_analyzer"),
+            true,
+            None::<Accelerator>,
+        ))
+        .unwrap();
+
+    view_menu
+        .append(&MenuItem::with_id(
+            MenuId::ViewNewRecordingCompare.to_muda_id(),
+            t!("menu_view_new_recording_compare"),
+            true,
+            None::<Accelerator>,
+        ))
+        .unwrap();
+
     view_menu.append(&PredefinedMenuItem::separator()).unwrap();
 
     // Reset Layout
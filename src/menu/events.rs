@@ -82,6 +82,36 @@ impl MenuEvent {
             MenuId::ViewNewFft => Some(MenuEvent::Action(Box::new(AppAction::NewVisualizer(
                 PaneKind::FftView,
             )))),
+            MenuId::ViewNewTextLog => Some(MenuEvent::Action(Box::new(AppAction::NewVisualizer(
+                PaneKind::TextLog,
+            )))),
+            MenuId::ViewNewStructInspector => Some(MenuEvent::Action(Box::new(
+                AppAction::NewVisualizer(PaneKind::StructInspector),
+            ))),
+            MenuId::ViewNewSpectrogram => Some(MenuEvent::Action(Box::new(
+                AppAction::NewVisualizer(PaneKind::Spectrogram),
+            ))),
+            MenuId::ViewNewFrequencyResponse => Some(MenuEvent::Action(Box::new(
+                AppAction::NewVisualizer(PaneKind::FrequencyResponse),
+            ))),
+            MenuId::ViewNewStepResponse => Some(MenuEvent::Action(Box::new(
+                AppAction::NewVisualizer(PaneKind::StepResponse),
+            ))),
+            MenuId::ViewNewFilterDesigner => Some(MenuEvent::Action(Box::new(
+                AppAction::NewVisualizer(PaneKind::FilterDesigner),
+            ))),
+            MenuId::ViewNewHistogram => Some(MenuEvent::Action(Box::new(
+                AppAction::NewVisualizer(PaneKind::Histogram),
+            ))),
+            MenuId::ViewNewXyPlot => Some(MenuEvent::Action(Box::new(AppAction::NewVisualizer(
+                PaneKind::XyPlot,
+            )))),
+            MenuId::ViewNewLogicAnalyzer => Some(MenuEvent::Action(Box::new(
+                AppAction::NewVisualizer(PaneKind::LogicAnalyzer),
+            ))),
+            MenuId::ViewNewRecordingCompare => Some(MenuEvent::Action(Box::new(
+                AppAction::NewVisualizer(PaneKind::RecordingCompare),
+            ))),
             MenuId::ViewResetLayout => Some(MenuEvent::Action(Box::new(AppAction::ResetLayout))),
 
             // Tools menu
@@ -25,6 +25,16 @@ pub enum MenuId {
     ViewNewTimeSeries,
     ViewNewWatcher,
     ViewNewFft,
+    ViewNewTextLog,
+    ViewNewStructInspector,
+    ViewNewSpectrogram,
+    ViewNewFrequencyResponse,
+    ViewNewStepResponse,
+    ViewNewFilterDesigner,
+    ViewNewHistogram,
+    ViewNewXyPlot,
+    ViewNewLogicAnalyzer,
+    ViewNewRecordingCompare,
     ViewResetLayout,
 
     // Tools
@@ -74,6 +84,16 @@ impl MenuId {
             Self::ViewNewTimeSeries => "view_new_time_series",
             Self::ViewNewWatcher => "view_new_watcher",
             Self::ViewNewFft => "view_new_fft",
+            Self::ViewNewTextLog => "view_new_text_log",
+            Self::ViewNewStructInspector => "view_new_struct_inspector",
+            Self::ViewNewSpectrogram => "view_new_spectrogram",
+            Self::ViewNewFrequencyResponse => "view_new_frequency_response",
+            Self::ViewNewStepResponse => "view_new_step_response",
+            Self::ViewNewFilterDesigner => "view_new_filter_designer",
+            Self::ViewNewHistogram => "view_new_histogram",
+            Self::ViewNewXyPlot => "view_new_xy_plot",
+            Self::ViewNewLogicAnalyzer => "view_new_logic_analyzer",
+            Self::ViewNewRecordingCompare => "view_new_recording_compare",
             Self::ViewResetLayout => "view_reset_layout",
             Self::ToolsConnectionSettings => "tools_connection_settings",
             Self::ToolsLoadElf => "tools_load_elf",
@@ -104,6 +124,16 @@ impl MenuId {
             "view_new_time_series" => Some(Self::ViewNewTimeSeries),
             "view_new_watcher" => Some(Self::ViewNewWatcher),
             "view_new_fft" => Some(Self::ViewNewFft),
+            "view_new_text_log" => Some(Self::ViewNewTextLog),
+            "view_new_struct_inspector" => Some(Self::ViewNewStructInspector),
+            "view_new_spectrogram" => Some(Self::ViewNewSpectrogram),
+            "view_new_frequency_response" => Some(Self::ViewNewFrequencyResponse),
+            "view_new_step_response" => Some(Self::ViewNewStepResponse),
+            "view_new_filter_designer" => Some(Self::ViewNewFilterDesigner),
+            "view_new_histogram" => Some(Self::ViewNewHistogram),
+            "view_new_xy_plot" => Some(Self::ViewNewXyPlot),
+            "view_new_logic_analyzer" => Some(Self::ViewNewLogicAnalyzer),
+            "view_new_recording_compare" => Some(Self::ViewNewRecordingCompare),
             "view_reset_layout" => Some(Self::ViewResetLayout),
             "tools_connection_settings" => Some(Self::ToolsConnectionSettings),
             "tools_load_elf" => Some(Self::ToolsLoadElf),
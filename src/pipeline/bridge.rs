@@ -7,7 +7,9 @@ use crate::config::ProbeConfig;
 use crate::pipeline::id::{NodeId, VarId};
 use crate::pipeline::packet::ConfigValue;
 use crate::session::types::{SessionRecording, SessionState};
-use crate::types::{CollectionStats, ConnectionStatus, PointerState, Variable, VariableType};
+use crate::types::{
+    CollectionStats, ConnectionStatus, Endianness, PointerState, RawValue, Variable, VariableType,
+};
 use crossbeam_channel::{bounded, Receiver, Sender};
 use std::time::Duration;
 
@@ -15,16 +17,19 @@ use std::time::Duration;
 #[derive(Debug, Clone)]
 pub enum SinkMessage {
     /// Batch of data samples: (VarId, timestamp, raw, converted).
-    DataBatch(Vec<(VarId, Duration, f64, f64)>),
+    DataBatch(Vec<(VarId, Duration, RawValue, f64)>),
 
     /// Batch of data samples for a specific graph pane.
     GraphDataBatch {
         /// Which pane this data is for. None means broadcast to all.
         pane_id: Option<u64>,
         /// Data samples: (VarId, timestamp, raw, converted).
-        data: Vec<(VarId, Duration, f64, f64)>,
+        data: Vec<(VarId, Duration, RawValue, f64)>,
     },
 
+    /// Decoded text of string variables: (VarId, timestamp, text).
+    TextBatch(Vec<(VarId, Duration, String)>),
+
     /// Periodic collection statistics.
     Stats(CollectionStats),
 
@@ -70,6 +75,16 @@ pub enum SinkMessage {
     /// Pointer state updates for UI display.
     PointerStates(std::collections::HashMap<u32, PointerState>),
 
+    /// Contents of a memory watch from one poll.
+    MemorySnapshot {
+        id: u64,
+        timestamp: Duration,
+        bytes: Vec<u8>,
+    },
+
+    /// Memory watch read error.
+    MemorySnapshotError { id: u64, error: String },
+
     /// Pipeline is shutting down.
     Shutdown,
 }
@@ -132,6 +147,12 @@ pub enum PipelineCommand {
     RefreshProbes,
     /// Request a variable tree snapshot to be sent back.
     RequestVariableTree,
+    /// Set the target byte order reported by the loaded ELF.
+    SetTargetEndianness(Endianness),
+    /// Read a block of memory in one request on every poll.
+    AddMemoryWatch { id: u64, address: u64, size: usize },
+    /// Stop a memory watch.
+    RemoveMemoryWatch(u64),
     /// Shut down the pipeline thread.
     Shutdown,
 }
@@ -240,6 +261,11 @@ impl PipelineBridge {
                     .collect();
                 Some(SinkMessage::DataBatch(converted))
             }
+            BackendMessage::TextBatch(data) => Some(SinkMessage::TextBatch(
+                data.into_iter()
+                    .map(|(id, ts, text)| (VarId(id), ts, text))
+                    .collect(),
+            )),
             BackendMessage::Stats(stats) => Some(SinkMessage::Stats(stats)),
             BackendMessage::ConnectionStatus(status) => Some(SinkMessage::ConnectionStatus(status)),
             BackendMessage::ConnectionError(error) => Some(SinkMessage::ConnectionError(error)),
@@ -266,6 +292,18 @@ impl PipelineBridge {
             BackendMessage::VariableList(vars) => Some(SinkMessage::VariableList(vars)),
             BackendMessage::ProbeList(probes) => Some(SinkMessage::ProbeList(probes)),
             BackendMessage::PointerStates(states) => Some(SinkMessage::PointerStates(states)),
+            BackendMessage::MemorySnapshot {
+                id,
+                timestamp,
+                bytes,
+            } => Some(SinkMessage::MemorySnapshot {
+                id,
+                timestamp,
+                bytes,
+            }),
+            BackendMessage::MemorySnapshotError { id, error } => {
+                Some(SinkMessage::MemorySnapshotError { id, error })
+            }
             BackendMessage::Shutdown => None,
         }
     }
@@ -301,6 +339,13 @@ impl PipelineBridge {
             #[cfg(feature = "mock-probe")]
             PipelineCommand::UseMockProbe(use_mock) => BackendCommand::UseMockProbe(use_mock),
             PipelineCommand::RefreshProbes => BackendCommand::RefreshProbes,
+            PipelineCommand::SetTargetEndianness(endianness) => {
+                BackendCommand::SetTargetEndianness(endianness)
+            }
+            PipelineCommand::AddMemoryWatch { id, address, size } => {
+                BackendCommand::AddMemoryWatch { id, address, size }
+            }
+            PipelineCommand::RemoveMemoryWatch(id) => BackendCommand::RemoveMemoryWatch(id),
             _ => BackendCommand::Shutdown, // Fallback for unhandled commands
         }
     }
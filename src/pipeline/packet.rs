@@ -5,10 +5,11 @@
 //! allocation occurs during normal pipeline operation.
 
 use crate::pipeline::id::VarId;
+use crate::types::RawValue;
 use std::time::Duration;
 
 /// Maximum number of variable samples per packet.
-/// 256 samples * 20 bytes = ~5KB — fits comfortably in L1 cache.
+/// 256 samples * 32 bytes = 8KB — fits comfortably in L1 cache.
 pub const MAX_PACKET_VARS: usize = 256;
 
 /// A single variable sample within a `DataPacket`.
@@ -17,8 +18,8 @@ pub const MAX_PACKET_VARS: usize = 256;
 pub struct Sample {
     /// Which variable this sample belongs to.
     pub var_id: VarId,
-    /// Raw value read from the target.
-    pub raw: f64,
+    /// Raw value read from the target (exact for integer types).
+    pub raw: RawValue,
     /// Converted value (after script transform, or same as raw).
     pub converted: f64,
 }
@@ -27,7 +28,7 @@ impl Default for Sample {
     fn default() -> Self {
         Self {
             var_id: VarId::INVALID,
-            raw: 0.0,
+            raw: RawValue::Float(0.0),
             converted: 0.0,
         }
     }
@@ -103,10 +104,10 @@ impl DataPacket {
 
     /// Push a sample from components. Returns `false` if full.
     #[inline]
-    pub fn push_value(&mut self, var_id: VarId, raw: f64, converted: f64) -> bool {
+    pub fn push_value(&mut self, var_id: VarId, raw: impl Into<RawValue>, converted: f64) -> bool {
         self.push(Sample {
             var_id,
-            raw,
+            raw: raw.into(),
             converted,
         })
     }
@@ -248,7 +249,7 @@ mod tests {
 
         let samples: Vec<_> = pkt.iter().collect();
         assert_eq!(samples[0].var_id, VarId(0));
-        assert_eq!(samples[0].raw, 1.0);
+        assert_eq!(samples[0].raw, RawValue::Float(1.0));
         assert_eq!(samples[1].var_id, VarId(1));
         assert_eq!(samples[1].converted, 4.0);
     }
@@ -285,7 +286,7 @@ mod tests {
         dst.copy_from(&src);
         assert_eq!(dst.len(), 2);
         assert_eq!(dst.timestamp, Duration::from_millis(100));
-        assert_eq!(dst.get(0).unwrap().raw, 1.0);
+        assert_eq!(dst.get(0).unwrap().raw, RawValue::Float(1.0));
     }
 
     #[test]
@@ -303,10 +304,10 @@ mod tests {
 
     #[test]
     fn test_sample_size() {
-        // Sample is 24 bytes: VarId(u32)=4 + 4 padding + f64=8 + f64=8
-        // due to f64 alignment requirements. Still fits well in L1 cache:
-        // 256 samples * 24 bytes = 6KB.
-        assert_eq!(std::mem::size_of::<Sample>(), 24);
+        // Sample is 32 bytes: VarId(u32)=4 + 4 padding + RawValue=16 + f64=8
+        // due to alignment requirements. Still fits well in L1 cache:
+        // 256 samples * 32 bytes = 8KB.
+        assert_eq!(std::mem::size_of::<Sample>(), 32);
     }
 
     #[test]
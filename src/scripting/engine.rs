@@ -6,10 +6,13 @@
 //!
 //! The following dynamic variables are available in scripts:
 //! - `value` / `raw` - The current raw value being converted
+//! - `raw_int` - The raw value as an exact 64-bit integer (bit pattern for `u64`)
 //! - `time()` - Time in seconds since collection started (pauses when paused)
 //! - `dt()` - Delta time since last sample in seconds
 //! - `prev()` - Previous converted value (NaN if not available)
 //! - `prev_raw()` - Previous raw value (NaN if not available)
+//! - `state(i)` / `set_state(i, v)` - Per-variable state slots kept between samples
+//! - `has_state()` - Returns true once any state slot has been written
 //!
 //! ## Transformer Functions
 //!
@@ -23,6 +26,7 @@
 
 use crate::error::{DataVisError, Result};
 use crate::scripting::{CompiledConverter, ScriptCache, SharedScriptCache};
+use crate::types::RawValue;
 use rhai::{Dynamic, Engine, Scope};
 use std::sync::{Arc, RwLock};
 
@@ -37,6 +41,8 @@ pub struct ExecutionContext {
     pub prev_raw: f64,
     /// Previous converted value (NaN if not available)
     pub prev_converted: f64,
+    /// State slots written by the previous execution (see `state()` / `set_state()`)
+    pub state: Vec<f64>,
 }
 
 impl ExecutionContext {
@@ -47,9 +53,16 @@ impl ExecutionContext {
             dt_secs,
             prev_raw,
             prev_converted,
+            state: Vec::new(),
         }
     }
 
+    /// Attach the state slots carried over from the previous execution
+    pub fn with_state(mut self, state: Vec<f64>) -> Self {
+        self.state = state;
+        self
+    }
+
     /// Create context with no previous values
     pub fn first_sample(time_secs: f64) -> Self {
         Self {
@@ -57,6 +70,7 @@ impl ExecutionContext {
             dt_secs: 0.0,
             prev_raw: f64::NAN,
             prev_converted: f64::NAN,
+            state: Vec::new(),
         }
     }
 }
@@ -104,6 +118,9 @@ impl ScriptContext {
     }
 }
 
+/// Maximum number of state slots a script can use
+pub const MAX_STATE_SLOTS: usize = 1024;
+
 /// Thread-safe shared script context
 pub type SharedScriptContext = Arc<RwLock<ScriptContext>>;
 
@@ -194,6 +211,48 @@ impl ScriptEngine {
             });
         }
 
+        // State slots: values kept per variable between samples, for filters
+        // that need more history than prev() (e.g. generated biquad cascades)
+        {
+            let ctx = context.clone();
+            engine.register_fn("state", move |index: i64| -> f64 {
+                ctx.read()
+                    .ok()
+                    .and_then(|c| {
+                        usize::try_from(index)
+                            .ok()
+                            .and_then(|i| c.context.state.get(i).copied())
+                    })
+                    .unwrap_or(0.0)
+            });
+        }
+        {
+            let ctx = context.clone();
+            engine.register_fn("set_state", move |index: i64, value: f64| {
+                let Ok(index) = usize::try_from(index) else {
+                    return;
+                };
+                if index >= MAX_STATE_SLOTS {
+                    return;
+                }
+                if let Ok(mut c) = ctx.write() {
+                    let state = &mut c.context.state;
+                    if state.len() <= index {
+                        state.resize(index + 1, 0.0);
+                    }
+                    state[index] = value;
+                }
+            });
+        }
+        {
+            let ctx = context.clone();
+            engine.register_fn("has_state", move || -> bool {
+                ctx.read()
+                    .map(|c| !c.context.state.is_empty())
+                    .unwrap_or(false)
+            });
+        }
+
         // ===== Transformer Functions =====
 
         // Derivative: compute rate of change
@@ -500,6 +559,21 @@ impl ScriptEngine {
         raw_value: f64,
         ctx: ExecutionContext,
     ) -> Result<f64> {
+        self.execute_raw(converter, RawValue::Float(raw_value), ctx)
+    }
+
+    /// Execute a compiled converter with an exact raw value
+    ///
+    /// Besides `value` / `raw`, the script sees `raw_int`, which holds integer
+    /// raw values exactly so bit operations on 64-bit counters don't lose bits.
+    pub fn execute_raw(
+        &self,
+        converter: &CompiledConverter,
+        raw: RawValue,
+        ctx: ExecutionContext,
+    ) -> Result<f64> {
+        let raw_value = raw.as_f64();
+
         // Update the shared context before execution
         {
             let mut context = self.context.write().map_err(|e| {
@@ -511,15 +585,23 @@ impl ScriptEngine {
         let mut scope = Scope::new();
         scope.push("value", raw_value);
         scope.push("raw", raw_value);
+        scope.push("raw_int", raw.as_i64_bits());
 
-        // First, try calling a 'convert' function if it exists
-        let result =
+        // First, try calling a 'convert' function if it exists. Only when it is
+        // defined: a failed call has already run the top-level statements,
+        // which would apply their side effects (e.g. `set_state`) twice.
+        let has_convert = converter.ast.iter_functions().any(|f| f.name == "convert");
+        let result = if has_convert {
             self.engine
-                .call_fn::<f64>(&mut scope, &converter.ast, "convert", (raw_value,));
+                .call_fn::<f64>(&mut scope, &converter.ast, "convert", (raw_value,))
+                .ok()
+        } else {
+            None
+        };
 
         match result {
-            Ok(value) => Ok(value),
-            Err(_) => {
+            Some(value) => Ok(value),
+            None => {
                 // If no 'convert' function, evaluate the script as an expression
                 // where 'value' or 'raw' contains the input
                 self.engine
@@ -541,6 +623,17 @@ impl ScriptEngine {
         }
     }
 
+    /// Take the state slots left by the last execution
+    ///
+    /// Callers that keep per-variable state pass them back in the next
+    /// [`ExecutionContext`] via [`ExecutionContext::with_state`].
+    pub fn take_state(&self) -> Vec<f64> {
+        self.context
+            .write()
+            .map(|mut c| std::mem::take(&mut c.context.state))
+            .unwrap_or_default()
+    }
+
     /// Execute a compiled converter with just a raw value (no context - uses defaults)
     pub fn execute_simple(&self, converter: &CompiledConverter, raw_value: f64) -> Result<f64> {
         self.execute(converter, raw_value, ExecutionContext::default())
@@ -788,6 +881,41 @@ fn convert(raw) {
         assert!(result > 10.0 && result < 20.0);
     }
 
+    #[test]
+    fn test_state_slots() {
+        let engine = ScriptEngine::new();
+        let script = r#"
+            if !has_state() {
+                set_state(0, 100.0);
+            }
+            set_state(2, state(0) + value);
+            state(2)
+        "#;
+
+        let result = engine
+            .eval_with_context(script, 1.0, ExecutionContext::first_sample(0.0))
+            .unwrap();
+        assert_eq!(result, 101.0);
+        let state = engine.take_state();
+        assert_eq!(state, vec![100.0, 0.0, 101.0]);
+
+        // State passed back in is visible to the next execution
+        let ctx = ExecutionContext::new(0.1, 0.1, 1.0, 101.0).with_state(vec![5.0]);
+        let result = engine.eval_with_context(script, 1.0, ctx).unwrap();
+        assert_eq!(result, 6.0);
+
+        // Out-of-range slots read as zero and are not written
+        let result = engine
+            .eval_with_context(
+                "set_state(-1, 1.0); set_state(5000, 1.0); state(-1)",
+                0.0,
+                ExecutionContext::default(),
+            )
+            .unwrap();
+        assert_eq!(result, 0.0);
+        assert!(engine.take_state().is_empty());
+    }
+
     #[test]
     fn test_deadband_function() {
         let engine = ScriptEngine::new();
@@ -864,4 +992,27 @@ fn convert(raw) {
         let result = engine.eval_with_context("sin(time())", 0.0, ctx).unwrap();
         assert!((result - 1.0).abs() < 0.0001); // sin(PI/2) = 1
     }
+
+    #[test]
+    fn test_raw_int_is_exact() {
+        let engine = ScriptEngine::new();
+
+        // 2^53 + 1 is not representable as f64, but raw_int keeps it
+        let converter = engine
+            .compile("counter", "raw_int - 9007199254740992")
+            .unwrap();
+        let raw = RawValue::from_u64((1u64 << 53) + 1);
+        let result = engine
+            .execute_raw(&converter, raw, ExecutionContext::default())
+            .unwrap();
+        assert_eq!(result, 1.0);
+
+        // u64 values above i64::MAX are passed as their bit pattern
+        let converter = engine.compile("mask", "bit_and(raw_int, 0xFF)").unwrap();
+        let raw = RawValue::from_u64(u64::MAX - 1);
+        let result = engine
+            .execute_raw(&converter, raw, ExecutionContext::default())
+            .unwrap();
+        assert_eq!(result, 254.0);
+    }
 }
@@ -9,11 +9,14 @@
 //! The following dynamic variables and functions are available in scripts:
 //!
 //! - `value` / `raw` - The current raw value being converted
+//! - `raw_int` - The raw value as an exact 64-bit integer (bit pattern for `u64`)
 //! - `time()` - Time in seconds since collection started (pauses when paused)
 //! - `dt()` - Delta time since last sample in seconds
 //! - `prev()` - Previous converted value (NaN if not available)
 //! - `prev_raw()` - Previous raw value (NaN if not available)
 //! - `has_prev()` - Returns true if previous value is available
+//! - `state(i)` / `set_state(i, v)` - Per-variable state slots kept between samples
+//! - `has_state()` - Returns true once any state slot has been written
 //!
 //! ## Transformer Functions
 //!
@@ -77,7 +80,9 @@
 
 mod engine;
 
-pub use engine::{ExecutionContext, ScriptContext, ScriptEngine, SharedScriptContext};
+pub use engine::{
+    ExecutionContext, ScriptContext, ScriptEngine, SharedScriptContext, MAX_STATE_SLOTS,
+};
 
 use crate::error::{DataVisError, Result};
 use rhai::{Engine, AST};
@@ -0,0 +1,655 @@
+//! Arrow IPC and Parquet export
+//!
+//! Recordings and the live buffer are written as typed tables for pandas,
+//! Polars and other Arrow-based tools, in either [`ExportLayout`]:
+//!
+//! - long: one row per sample with `timestamp`, `variable_id`, `variable`,
+//!   `value` and a raw column picked by the variable's type (`raw_uint`,
+//!   `raw_int` or `raw_float`)
+//! - wide: one row per timestamp with a `<name>` and `<name>.raw` column per
+//!   variable, null where the variable has no sample
+//!
+//! Timestamps are `Duration(ns)` since the start of the session and integer
+//! raw values stay integers. Each variable's unit, type, address and
+//! converter script are kept in the `datavis.variables` schema metadata
+//! entry, and on the variable's own fields in the wide layout.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arrow_array::types::Int32Type;
+use arrow_array::{
+    ArrayRef, DictionaryArray, DurationNanosecondArray, Float64Array, Int64Array, RecordBatch,
+    UInt32Array, UInt64Array,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+
+use crate::types::{DataPoint, RawValue, Variable, VariableData, VariableType};
+
+use super::types::{SessionMetadata, SessionRecording};
+
+/// Schema metadata key holding the variable definitions as JSON
+pub const VARIABLES_METADATA_KEY: &str = "datavis.variables";
+
+/// Table layout of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportLayout {
+    /// Long format: one row per sample (timestamp, var_name, value)
+    Long,
+    /// Wide format: one row per timestamp, columns for each variable
+    Wide,
+}
+
+/// Columnar file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnarFormat {
+    /// Arrow IPC file (Feather v2)
+    ArrowIpc,
+    /// Apache Parquet (zstd compressed)
+    Parquet,
+}
+
+impl ColumnarFormat {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ColumnarFormat::ArrowIpc => "Arrow IPC (Feather)",
+            ColumnarFormat::Parquet => "Parquet",
+        }
+    }
+
+    /// Get file extension
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ColumnarFormat::ArrowIpc => "arrow",
+            ColumnarFormat::Parquet => "parquet",
+        }
+    }
+
+    /// Get all formats
+    pub fn all() -> &'static [ColumnarFormat] {
+        &[ColumnarFormat::ArrowIpc, ColumnarFormat::Parquet]
+    }
+}
+
+/// What to include in a columnar export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnarOptions {
+    /// Table layout
+    pub layout: ExportLayout,
+    /// Include raw values
+    pub include_raw: bool,
+    /// Include converted values
+    pub include_converted: bool,
+}
+
+impl Default for ColumnarOptions {
+    fn default() -> Self {
+        Self {
+            layout: ExportLayout::Long,
+            include_raw: true,
+            include_converted: true,
+        }
+    }
+}
+
+/// Arrow type a variable's raw values are stored as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RawColumn {
+    UInt,
+    Int,
+    Float,
+}
+
+impl RawColumn {
+    fn of(var_type: VariableType) -> Self {
+        match var_type {
+            VariableType::I8 | VariableType::I16 | VariableType::I32 | VariableType::I64 => {
+                RawColumn::Int
+            }
+            VariableType::F32
+            | VariableType::F64
+            | VariableType::F16
+            | VariableType::BF16
+            | VariableType::Fixed { .. } => RawColumn::Float,
+            VariableType::U8
+            | VariableType::U16
+            | VariableType::U32
+            | VariableType::U64
+            | VariableType::Bool
+            | VariableType::Raw(_)
+            | VariableType::Text(_) => RawColumn::UInt,
+        }
+    }
+
+    fn data_type(self) -> DataType {
+        match self {
+            RawColumn::UInt => DataType::UInt64,
+            RawColumn::Int => DataType::Int64,
+            RawColumn::Float => DataType::Float64,
+        }
+    }
+
+    /// Build a raw column, with nulls where the value does not fit the type
+    fn array(self, values: &[Option<RawValue>]) -> ArrayRef {
+        match self {
+            RawColumn::UInt => Arc::new(
+                values
+                    .iter()
+                    .map(|v| match (*v)? {
+                        RawValue::UInt(v) => Some(v),
+                        RawValue::Int(v) => u64::try_from(v).ok(),
+                        RawValue::Float(_) => None,
+                    })
+                    .collect::<UInt64Array>(),
+            ),
+            RawColumn::Int => Arc::new(
+                values
+                    .iter()
+                    .map(|v| match (*v)? {
+                        RawValue::UInt(v) => i64::try_from(v).ok(),
+                        RawValue::Int(v) => Some(v),
+                        RawValue::Float(_) => None,
+                    })
+                    .collect::<Int64Array>(),
+            ),
+            RawColumn::Float => Arc::new(
+                values
+                    .iter()
+                    .map(|v| v.map(|v| v.as_f64()))
+                    .collect::<Float64Array>(),
+            ),
+        }
+    }
+}
+
+/// Samples of one variable
+#[derive(Debug, Clone)]
+struct Series {
+    variable: Variable,
+    points: Vec<DataPoint>,
+}
+
+/// Split a recording into one series per variable
+fn recording_series(recording: &SessionRecording) -> Vec<Series> {
+    let mut series: HashMap<u32, Series> = recording
+        .metadata
+        .variables
+        .iter()
+        .map(|v| {
+            (
+                v.id,
+                Series {
+                    variable: v.clone(),
+                    points: Vec::new(),
+                },
+            )
+        })
+        .collect();
+    for frame in &recording.frames {
+        for (var_id, value) in &frame.values {
+            series
+                .entry(*var_id)
+                .or_insert_with(|| {
+                    let mut variable =
+                        Variable::new(format!("var_{}", var_id), 0, VariableType::F64);
+                    variable.id = *var_id;
+                    Series {
+                        variable,
+                        points: Vec::new(),
+                    }
+                })
+                .points
+                .push(DataPoint {
+                    timestamp: frame.timestamp,
+                    raw_value: value.raw_value,
+                    converted_value: value.converted_value,
+                });
+        }
+    }
+    let mut series: Vec<Series> = series
+        .into_values()
+        .filter(|s| !s.points.is_empty())
+        .collect();
+    series.sort_by_key(|s| s.variable.id);
+    series
+}
+
+/// Snapshot the live ring buffers, leaving out gap markers
+fn live_series<'a>(data: impl IntoIterator<Item = &'a VariableData>) -> Vec<Series> {
+    let mut series: Vec<Series> = data
+        .into_iter()
+        .map(|d| Series {
+            variable: d.variable.clone(),
+            points: d
+                .data_points
+                .iter()
+                .filter(|p| !p.is_gap())
+                .cloned()
+                .collect(),
+        })
+        .filter(|s| !s.points.is_empty())
+        .collect();
+    series.sort_by_key(|s| s.variable.id);
+    series
+}
+
+fn duration_nanos(duration: Duration) -> i64 {
+    i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX)
+}
+
+/// Definition of a variable as stored in metadata
+fn variable_json(variable: &Variable) -> serde_json::Value {
+    serde_json::json!({
+        "id": variable.id,
+        "name": variable.name,
+        "unit": variable.unit,
+        "type": variable.var_type,
+        "address": variable.address,
+        "converter_script": variable.converter_script,
+    })
+}
+
+/// Field metadata describing a variable's column
+fn variable_field_metadata(variable: &Variable) -> HashMap<String, String> {
+    let mut metadata = HashMap::new();
+    metadata.insert("variable_id".to_string(), variable.id.to_string());
+    metadata.insert("unit".to_string(), variable.unit.clone());
+    metadata.insert(
+        "type".to_string(),
+        serde_json::to_string(&variable.var_type).unwrap_or_default(),
+    );
+    metadata.insert("address".to_string(), format!("0x{:X}", variable.address));
+    if let Some(script) = &variable.converter_script {
+        metadata.insert("converter_script".to_string(), script.clone());
+    }
+    metadata
+}
+
+/// Schema-level metadata for a table
+fn schema_metadata(
+    session: Option<&SessionMetadata>,
+    series: &[Series],
+) -> HashMap<String, String> {
+    let mut metadata = HashMap::new();
+    let variables: Vec<_> = series.iter().map(|s| variable_json(&s.variable)).collect();
+    metadata.insert(
+        VARIABLES_METADATA_KEY.to_string(),
+        serde_json::Value::Array(variables).to_string(),
+    );
+    if let Some(session) = session {
+        metadata.insert("datavis.session_name".to_string(), session.name.clone());
+        metadata.insert(
+            "datavis.recorded_at".to_string(),
+            session.recorded_at.to_rfc3339(),
+        );
+        metadata.insert(
+            "datavis.poll_rate_hz".to_string(),
+            session.poll_rate_hz.to_string(),
+        );
+        if let Some(description) = &session.description {
+            metadata.insert("datavis.description".to_string(), description.clone());
+        }
+        if let Some(target) = &session.target_name {
+            metadata.insert("datavis.target_name".to_string(), target.clone());
+        }
+    }
+    metadata
+}
+
+fn long_table(
+    series: &[Series],
+    options: &ColumnarOptions,
+    metadata: HashMap<String, String>,
+) -> Result<RecordBatch, ArrowError> {
+    let mut rows: Vec<(usize, &DataPoint)> = series
+        .iter()
+        .enumerate()
+        .flat_map(|(i, s)| s.points.iter().map(move |p| (i, p)))
+        .collect();
+    // Stable, so samples at the same time keep variable order
+    rows.sort_by_key(|(_, p)| p.timestamp);
+
+    let mut fields = vec![
+        Field::new("timestamp", DataType::Duration(TimeUnit::Nanosecond), false),
+        Field::new("variable_id", DataType::UInt32, false),
+        Field::new(
+            "variable",
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+            false,
+        ),
+    ];
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(DurationNanosecondArray::from(
+            rows.iter()
+                .map(|(_, p)| duration_nanos(p.timestamp))
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(
+            rows.iter()
+                .map(|(i, _)| series[*i].variable.id)
+                .collect::<UInt32Array>(),
+        ),
+        Arc::new(
+            rows.iter()
+                .map(|(i, _)| series[*i].variable.name.as_str())
+                .collect::<DictionaryArray<Int32Type>>(),
+        ),
+    ];
+
+    if options.include_converted {
+        fields.push(Field::new("value", DataType::Float64, false));
+        columns.push(Arc::new(
+            rows.iter()
+                .map(|(_, p)| p.converted_value)
+                .collect::<Float64Array>(),
+        ));
+    }
+
+    if options.include_raw {
+        for (kind, name) in [
+            (RawColumn::UInt, "raw_uint"),
+            (RawColumn::Int, "raw_int"),
+            (RawColumn::Float, "raw_float"),
+        ] {
+            if !series
+                .iter()
+                .any(|s| RawColumn::of(s.variable.var_type) == kind)
+            {
+                continue;
+            }
+            let values: Vec<Option<RawValue>> = rows
+                .iter()
+                .map(|(i, p)| {
+                    (RawColumn::of(series[*i].variable.var_type) == kind).then_some(p.raw_value)
+                })
+                .collect();
+            fields.push(Field::new(name, kind.data_type(), true));
+            columns.push(kind.array(&values));
+        }
+    }
+
+    RecordBatch::try_new(
+        Arc::new(Schema::new(fields).with_metadata(metadata)),
+        columns,
+    )
+}
+
+fn wide_table(
+    series: &[Series],
+    options: &ColumnarOptions,
+    metadata: HashMap<String, String>,
+) -> Result<RecordBatch, ArrowError> {
+    let timestamps: Vec<Duration> = series
+        .iter()
+        .flat_map(|s| s.points.iter().map(|p| p.timestamp))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let row_of: HashMap<Duration, usize> = timestamps
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (*t, i))
+        .collect();
+
+    let mut fields = vec![Field::new(
+        "timestamp",
+        DataType::Duration(TimeUnit::Nanosecond),
+        false,
+    )];
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(DurationNanosecondArray::from(
+        timestamps
+            .iter()
+            .map(|t| duration_nanos(*t))
+            .collect::<Vec<_>>(),
+    ))];
+
+    for s in series {
+        // The last sample wins when a variable has several at one time
+        let mut raw = vec![None; timestamps.len()];
+        let mut converted = vec![None; timestamps.len()];
+        for point in &s.points {
+            let row = row_of[&point.timestamp];
+            raw[row] = Some(point.raw_value);
+            converted[row] = Some(point.converted_value);
+        }
+
+        let field_metadata = variable_field_metadata(&s.variable);
+        if options.include_converted {
+            fields.push(
+                Field::new(&s.variable.name, DataType::Float64, true)
+                    .with_metadata(field_metadata.clone()),
+            );
+            columns.push(Arc::new(Float64Array::from(converted)));
+        }
+        if options.include_raw {
+            let kind = RawColumn::of(s.variable.var_type);
+            fields.push(
+                Field::new(format!("{}.raw", s.variable.name), kind.data_type(), true)
+                    .with_metadata(field_metadata),
+            );
+            columns.push(kind.array(&raw));
+        }
+    }
+
+    RecordBatch::try_new(
+        Arc::new(Schema::new(fields).with_metadata(metadata)),
+        columns,
+    )
+}
+
+fn build_table(
+    series: &[Series],
+    session: Option<&SessionMetadata>,
+    options: &ColumnarOptions,
+) -> Result<RecordBatch, ArrowError> {
+    let metadata = schema_metadata(session, series);
+    match options.layout {
+        ExportLayout::Long => long_table(series, options, metadata),
+        ExportLayout::Wide => wide_table(series, options, metadata),
+    }
+}
+
+/// Build a table of a recording
+pub fn recording_table(
+    recording: &SessionRecording,
+    options: &ColumnarOptions,
+) -> Result<RecordBatch, ArrowError> {
+    build_table(
+        &recording_series(recording),
+        Some(&recording.metadata),
+        options,
+    )
+}
+
+/// Build a table of the samples currently in the live buffers
+pub fn live_table<'a>(
+    data: impl IntoIterator<Item = &'a VariableData>,
+    options: &ColumnarOptions,
+) -> Result<RecordBatch, ArrowError> {
+    build_table(&live_series(data), None, options)
+}
+
+fn arrow_error(e: ArrowError) -> io::Error {
+    match e {
+        ArrowError::IoError(_, e) => e,
+        e => io::Error::new(io::ErrorKind::InvalidData, e),
+    }
+}
+
+/// Write a table to a file
+pub fn write_table(batch: &RecordBatch, format: ColumnarFormat, path: &Path) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    match format {
+        ColumnarFormat::ArrowIpc => {
+            let mut writer = arrow_ipc::writer::FileWriter::try_new(file, &batch.schema())
+                .map_err(arrow_error)?;
+            writer.write(batch).map_err(arrow_error)?;
+            writer.finish().map_err(arrow_error)
+        }
+        ColumnarFormat::Parquet => {
+            let properties = WriterProperties::builder()
+                .set_compression(Compression::ZSTD(ZstdLevel::default()))
+                .build();
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            writer
+                .write(batch)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            writer
+                .close()
+                .map(|_| ())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        }
+    }
+}
+
+/// Export a recording as Arrow IPC or Parquet
+pub fn export_recording(
+    recording: &SessionRecording,
+    format: ColumnarFormat,
+    options: &ColumnarOptions,
+    path: &Path,
+) -> io::Result<()> {
+    let batch = recording_table(recording, options).map_err(arrow_error)?;
+    write_table(&batch, format, path)
+}
+
+/// Export the live buffers as Arrow IPC or Parquet
+pub fn export_live<'a>(
+    data: impl IntoIterator<Item = &'a VariableData>,
+    format: ColumnarFormat,
+    options: &ColumnarOptions,
+    path: &Path,
+) -> io::Result<()> {
+    let batch = live_table(data, options).map_err(arrow_error)?;
+    write_table(&batch, format, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::types::RecordingBuilder;
+    use arrow_array::Array;
+
+    fn test_recording() -> SessionRecording {
+        let count = Variable::new("count", 0x2000_0000, VariableType::U64).with_unit("ticks");
+        let temp = Variable::new("temp", 0x2000_0008, VariableType::I16)
+            .with_unit("°C")
+            .with_converter("value / 10.0");
+        let mut metadata = SessionMetadata::new("Columnar");
+        metadata.variables = vec![count.clone(), temp.clone()];
+
+        let mut builder = RecordingBuilder::new(metadata);
+        for i in 0..4u64 {
+            let mut values = vec![(count.id, RawValue::UInt(u64::MAX - i), i as f64)];
+            if i % 2 == 1 {
+                values.push((temp.id, RawValue::from_i64(-(i as i64) * 10), -(i as f64)));
+            }
+            builder.frame(Duration::from_millis(i * 5), &values, None);
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_long_table() {
+        let recording = test_recording();
+        let batch = recording_table(&recording, &ColumnarOptions::default()).unwrap();
+        assert_eq!(batch.num_rows(), 6);
+
+        let schema = batch.schema();
+        assert_eq!(
+            schema.field_with_name("timestamp").unwrap().data_type(),
+            &DataType::Duration(TimeUnit::Nanosecond)
+        );
+        assert!(schema.field_with_name("raw_float").is_err());
+        let variables = &schema.metadata()[VARIABLES_METADATA_KEY];
+        assert!(variables.contains("value / 10.0"));
+        assert!(variables.contains("ticks"));
+
+        let raw_uint = batch
+            .column_by_name("raw_uint")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(raw_uint.value(0), u64::MAX);
+        let raw_int = batch
+            .column_by_name("raw_int")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert!(raw_int.is_null(0));
+        // Row 2 is temp at 5 ms, after count at the same time
+        assert_eq!(raw_int.value(2), -10);
+    }
+
+    #[test]
+    fn test_wide_table() {
+        let recording = test_recording();
+        let options = ColumnarOptions {
+            layout: ExportLayout::Wide,
+            ..Default::default()
+        };
+        let batch = recording_table(&recording, &options).unwrap();
+        assert_eq!(batch.num_rows(), 4);
+        assert_eq!(batch.num_columns(), 5);
+
+        let schema = batch.schema();
+        let temp = schema.field_with_name("temp").unwrap();
+        assert_eq!(temp.metadata()["unit"], "°C");
+        assert_eq!(temp.metadata()["converter_script"], "value / 10.0");
+        assert_eq!(
+            schema.field_with_name("temp.raw").unwrap().data_type(),
+            &DataType::Int64
+        );
+
+        let temp = batch
+            .column_by_name("temp")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(temp.null_count(), 2);
+        assert_eq!(temp.value(3), -3.0);
+    }
+
+    #[test]
+    fn test_write_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let recording = test_recording();
+        for format in ColumnarFormat::all() {
+            let path = dir.path().join(format!("export.{}", format.extension()));
+            export_recording(&recording, *format, &ColumnarOptions::default(), &path).unwrap();
+            assert!(std::fs::metadata(&path).unwrap().len() > 0);
+        }
+
+        let file = File::open(dir.path().join("export.arrow")).unwrap();
+        let reader = arrow_ipc::reader::FileReader::try_new(file, None).unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(batches[0].num_rows(), 6);
+        assert!(batches[0]
+            .schema()
+            .metadata()
+            .contains_key(VARIABLES_METADATA_KEY));
+    }
+
+    #[test]
+    fn test_live_table_skips_gaps() {
+        let mut data = VariableData::new(Variable::new("live", 0, VariableType::F32));
+        data.push(DataPoint::new(Duration::from_millis(1), 1.0));
+        data.push(DataPoint::gap_marker(Duration::from_millis(2)));
+        data.push(DataPoint::new(Duration::from_millis(3), 3.0));
+        let batch = live_table([&data], &ColumnarOptions::default()).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert!(batch.column_by_name("raw_float").is_some());
+    }
+}
@@ -0,0 +1,419 @@
+//! Comparison of several recordings of the same variable
+//!
+//! Lines up runs such as "before fix" and "after fix" on a common time axis
+//! so they can be overlaid. Each run is shifted so its alignment event (the
+//! recording start, a tagged marker or the first trigger crossing) lands at
+//! zero. Variables are matched by name, because the same variable can have a
+//! different ID in recordings made from different projects or imported logs.
+//!
+//! Every run after the first is compared against the first (the reference):
+//! its difference trace is the run minus the reference, interpolated at the
+//! run's own timestamps, and its summary statistics are reported as deltas.
+
+use std::time::Duration;
+
+use crate::analysis::{align_at, Alignment};
+use crate::config::settings::TriggerCondition;
+
+use super::types::SessionRecording;
+
+/// Event each recording is aligned on
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum AlignMode {
+    /// First frame of the recording
+    #[default]
+    Start,
+    /// First event or frame tag with this name
+    Marker(String),
+    /// First frame where a variable meets a trigger condition
+    Trigger {
+        /// Name of the trigger variable
+        variable: String,
+        /// Condition checked between consecutive samples
+        condition: TriggerCondition,
+        /// Threshold of the condition
+        threshold: f64,
+    },
+}
+
+impl AlignMode {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AlignMode::Start => "Start",
+            AlignMode::Marker(_) => "Marker",
+            AlignMode::Trigger { .. } => "Trigger",
+        }
+    }
+}
+
+/// Summary statistics of one run
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RunStats {
+    /// Number of samples
+    pub count: usize,
+    /// Smallest value
+    pub min: f64,
+    /// Largest value
+    pub max: f64,
+    /// Arithmetic mean
+    pub mean: f64,
+    /// Root mean square
+    pub rms: f64,
+    /// Population standard deviation
+    pub std_dev: f64,
+}
+
+impl RunStats {
+    /// Compute the statistics of a set of values, `None` when empty
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let mean_sq = values.iter().map(|v| v * v).sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        Some(Self {
+            count: values.len(),
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            mean,
+            rms: mean_sq.sqrt(),
+            std_dev: variance.sqrt(),
+        })
+    }
+
+    /// Difference of each statistic from a reference run
+    pub fn delta(&self, reference: &RunStats) -> StatsDelta {
+        StatsDelta {
+            min: self.min - reference.min,
+            max: self.max - reference.max,
+            mean: self.mean - reference.mean,
+            rms: self.rms - reference.rms,
+            std_dev: self.std_dev - reference.std_dev,
+        }
+    }
+}
+
+/// Statistics of a run minus those of the reference run
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StatsDelta {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub rms: f64,
+    pub std_dev: f64,
+}
+
+/// One recording lined up on the common time axis
+#[derive(Debug, Clone, Default)]
+pub struct ComparedRun {
+    /// Recording name
+    pub name: String,
+    /// Time of the alignment event in the recording (seconds), `None` when
+    /// the event never happens
+    pub alignment: Option<f64>,
+    /// Aligned plot points `[time, value]`
+    pub points: Vec<[f64; 2]>,
+    /// Statistics over the comparison window
+    pub stats: Option<RunStats>,
+    /// Statistics minus those of the reference (None for the reference)
+    pub delta: Option<StatsDelta>,
+    /// This run minus the reference at this run's timestamps (empty for the
+    /// reference)
+    pub difference: Vec<[f64; 2]>,
+}
+
+impl ComparedRun {
+    /// Whether the run has aligned data to show
+    pub fn is_aligned(&self) -> bool {
+        self.alignment.is_some() && !self.points.is_empty()
+    }
+}
+
+/// Result of comparing one variable across recordings
+#[derive(Debug, Clone, Default)]
+pub struct Comparison {
+    /// Compared variable name
+    pub variable: String,
+    /// Runs in the order given; the first is the reference
+    pub runs: Vec<ComparedRun>,
+}
+
+/// Find a variable of a recording by name
+pub fn find_variable(recording: &SessionRecording, name: &str) -> Option<u32> {
+    recording
+        .metadata
+        .variables
+        .iter()
+        .find(|v| v.name == name)
+        .map(|v| v.id)
+}
+
+/// Distinct event names and frame tags of a recording, events first
+pub fn marker_names(recording: &SessionRecording) -> Vec<String> {
+    let events = recording.metadata.events.iter().map(|e| &e.name);
+    let tags = recording.frames.iter().filter_map(|f| f.tag.as_ref());
+    let mut names: Vec<String> = Vec::new();
+    for name in events.chain(tags) {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    names
+}
+
+/// Time of the alignment event in a recording
+pub fn alignment_time(recording: &SessionRecording, mode: &AlignMode) -> Option<Duration> {
+    match mode {
+        AlignMode::Start => recording.frames.first().map(|f| f.timestamp),
+        AlignMode::Marker(name) => {
+            let event = recording
+                .metadata
+                .events
+                .iter()
+                .find(|e| e.name == *name)
+                .map(|e| e.time);
+            let tag = recording
+                .frames
+                .iter()
+                .find(|f| f.tag.as_deref() == Some(name.as_str()))
+                .map(|f| f.timestamp);
+            event.into_iter().chain(tag).min()
+        }
+        AlignMode::Trigger {
+            variable,
+            condition,
+            threshold,
+        } => {
+            let var_id = find_variable(recording, variable)?;
+            let mut previous: Option<f64> = None;
+            for frame in &recording.frames {
+                let Some(value) = frame.values.get(&var_id) else {
+                    continue;
+                };
+                let current = value.converted_value;
+                if previous.is_some_and(|p| condition.is_met(*threshold, p, current)) {
+                    return Some(frame.timestamp);
+                }
+                previous = Some(current);
+            }
+            None
+        }
+    }
+}
+
+/// Compare a variable across recordings
+///
+/// The first recording is the reference. `window` limits the statistics to
+/// `[start, end]` seconds of aligned time; the plotted points are not
+/// limited.
+pub fn compare(
+    recordings: &[&SessionRecording],
+    variable: &str,
+    mode: &AlignMode,
+    window: Option<(f64, f64)>,
+) -> Comparison {
+    let mut runs: Vec<ComparedRun> = recordings
+        .iter()
+        .map(|recording| {
+            let alignment = alignment_time(recording, mode).map(|t| t.as_secs_f64());
+            let points = match (alignment, find_variable(recording, variable)) {
+                (Some(offset), Some(var_id)) => aligned_points(recording, var_id, offset),
+                _ => Vec::new(),
+            };
+            let values: Vec<f64> = points
+                .iter()
+                .filter(|[t, _]| window.is_none_or(|(start, end)| *t >= start && *t <= end))
+                .map(|[_, v]| *v)
+                .collect();
+            ComparedRun {
+                name: recording.metadata.name.clone(),
+                alignment,
+                stats: RunStats::from_values(&values),
+                points,
+                delta: None,
+                difference: Vec::new(),
+            }
+        })
+        .collect();
+
+    if let Some((reference, others)) = runs.split_first_mut() {
+        for run in others {
+            run.delta = match (&run.stats, &reference.stats) {
+                (Some(stats), Some(reference)) => Some(stats.delta(reference)),
+                _ => None,
+            };
+            run.difference = difference(&reference.points, &run.points);
+        }
+    }
+
+    Comparison {
+        variable: variable.to_string(),
+        runs,
+    }
+}
+
+/// `other` minus `reference`, evaluated at the timestamps of `other`
+///
+/// Only times within the span of the reference produce a point.
+pub fn difference(reference: &[[f64; 2]], other: &[[f64; 2]]) -> Vec<[f64; 2]> {
+    let (Some(first), Some(last)) = (reference.first(), reference.last()) else {
+        return Vec::new();
+    };
+    let other: Vec<[f64; 2]> = other
+        .iter()
+        .copied()
+        .filter(|[t, _]| *t >= first[0] && *t <= last[0])
+        .collect();
+    let (ref_times, ref_values): (Vec<f64>, Vec<f64>) =
+        reference.iter().map(|[t, v]| (*t, *v)).unzip();
+    let times: Vec<f64> = other.iter().map(|[t, _]| *t).collect();
+    align_at(&ref_times, &ref_values, &times, Alignment::Linear)
+        .into_iter()
+        .zip(&other)
+        .filter_map(|(r, [t, v])| r.map(|r| [*t, v - r]))
+        .collect()
+}
+
+/// Points of a variable with the alignment time subtracted
+fn aligned_points(recording: &SessionRecording, var_id: u32, offset: f64) -> Vec<[f64; 2]> {
+    recording
+        .frames
+        .iter()
+        .filter_map(|frame| {
+            frame.values.get(&var_id).map(|value| {
+                [
+                    frame.timestamp.as_secs_f64() - offset,
+                    value.converted_value,
+                ]
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::events::{MarkerType, RecordingEvent};
+    use crate::session::types::{RecordedFrame, RecordedValue, SessionMetadata};
+    use crate::types::{RawValue, Variable, VariableType};
+    use std::collections::HashMap;
+
+    /// Recording of a ramp `value(t) = t + bias` sampled every 10 ms, where
+    /// the variable has the given ID and the frame at `marker_ms` is tagged
+    fn ramp(name: &str, var_id: u32, bias: f64, marker_ms: u64) -> SessionRecording {
+        let mut variable = Variable::new("speed", 0x2000_0000, VariableType::F32);
+        variable.id = var_id;
+        let mut metadata = SessionMetadata::new(name);
+        metadata.variables = vec![variable];
+        let mut recording = SessionRecording::with_metadata(metadata);
+        for ms in (0..=1000u64).step_by(10) {
+            let value = ms as f64 / 1000.0 + bias;
+            let mut values = HashMap::new();
+            values.insert(
+                var_id,
+                RecordedValue {
+                    raw_value: RawValue::Float(value),
+                    converted_value: value,
+                },
+            );
+            recording.frames.push(RecordedFrame {
+                timestamp: Duration::from_millis(ms),
+                values,
+                tag: (ms == marker_ms).then(|| "step".to_string()),
+            });
+        }
+        recording
+    }
+
+    #[test]
+    fn test_run_stats() {
+        let stats = RunStats::from_values(&[1.0, -1.0, 1.0, -1.0]).unwrap();
+        assert_eq!(stats.count, 4);
+        assert_eq!((stats.min, stats.max), (-1.0, 1.0));
+        assert_eq!(stats.mean, 0.0);
+        assert!((stats.rms - 1.0).abs() < 1e-12);
+        assert!((stats.std_dev - 1.0).abs() < 1e-12);
+        assert!(RunStats::from_values(&[]).is_none());
+    }
+
+    #[test]
+    fn test_marker_alignment_and_difference() {
+        // The second run reaches the marker 200 ms later, and its ramp is
+        // 0.2 lower there plus a constant 0.5 bias
+        let before = ramp("before", 1, 0.0, 300);
+        let after = ramp("after", 7, 0.5, 500);
+        let mode = AlignMode::Marker("step".to_string());
+        assert_eq!(marker_names(&after), vec!["step".to_string()]);
+
+        // Events align like frame tags, and need not fall on a frame
+        let mut evented = ramp("evented", 1, 0.0, 0);
+        evented.frames[0].tag = None;
+        evented.metadata.events.push(RecordingEvent::new(
+            Duration::from_micros(300_500),
+            MarkerType::Note,
+            "step",
+        ));
+        assert_eq!(
+            alignment_time(&evented, &mode),
+            Some(Duration::from_micros(300_500))
+        );
+
+        let comparison = compare(&[&before, &after], "speed", &mode, None);
+        assert_eq!(comparison.runs.len(), 2);
+        assert_eq!(comparison.runs[0].alignment, Some(0.3));
+        assert_eq!(comparison.runs[1].alignment, Some(0.5));
+        assert!(comparison.runs[0].difference.is_empty());
+        assert!(comparison.runs[0].delta.is_none());
+
+        let after_run = &comparison.runs[1];
+        assert!(after_run.is_aligned());
+        // At aligned time 0: before = 0.3, after = 1.0
+        assert!(!after_run.difference.is_empty());
+        for [_, d] in &after_run.difference {
+            assert!((d - 0.7).abs() < 1e-9, "difference {}", d);
+        }
+        // The difference only covers the overlap of both runs
+        let first = after_run.difference.first().unwrap()[0];
+        let last = after_run.difference.last().unwrap()[0];
+        assert!(first > -0.301 && first < -0.289, "first {}", first);
+        assert!(last > 0.489 && last < 0.501, "last {}", last);
+    }
+
+    #[test]
+    fn test_trigger_alignment_and_window_stats() {
+        let before = ramp("before", 1, 0.0, 0);
+        let after = ramp("after", 1, 0.25, 0);
+        let mode = AlignMode::Trigger {
+            variable: "speed".to_string(),
+            condition: TriggerCondition::RisingEdge,
+            threshold: 0.5,
+        };
+        assert_eq!(
+            alignment_time(&before, &mode),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            alignment_time(&after, &mode),
+            Some(Duration::from_millis(250))
+        );
+
+        // Both ramps cross 0.5 at aligned time 0, so within ±0.1 s they match
+        let comparison = compare(&[&before, &after], "speed", &mode, Some((-0.1, 0.1)));
+        let reference = comparison.runs[0].stats.unwrap();
+        assert_eq!(reference.count, 21);
+        assert!((reference.mean - 0.5).abs() < 1e-9);
+        let delta = comparison.runs[1].delta.unwrap();
+        assert!(delta.mean.abs() < 1e-9 && delta.max.abs() < 1e-9);
+
+        // A run without the variable or the event has nothing to show
+        let never = AlignMode::Marker("missing".to_string());
+        let comparison = compare(&[&before, &after], "speed", &never, None);
+        assert!(comparison.runs.iter().all(|r| !r.is_aligned()));
+        let comparison = compare(&[&before], "torque", &AlignMode::Start, None);
+        assert!(comparison.runs[0].points.is_empty());
+        assert!(comparison.runs[0].stats.is_none());
+    }
+}
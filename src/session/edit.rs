@@ -0,0 +1,349 @@
+//! Trim, split, merge and prune recordings
+//!
+//! Every operation leaves its input untouched and keeps the metadata, frame
+//! tags and events that fall inside the result, moved onto its time axis.
+//! A region cut by a trim or split is clipped to the part that is kept; a
+//! region left with no length is dropped.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::time::Duration;
+
+use super::events::{insert_event, region_pairs, sort_events, MarkerType, RecordingEvent};
+use super::types::{RecordedFrame, SessionRecording};
+
+/// Names of the variables a run records, sorted
+fn variable_names(run: &SessionRecording) -> Vec<&str> {
+    let mut names: Vec<&str> = run
+        .metadata
+        .variables
+        .iter()
+        .map(|v| v.name.as_str())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+/// Events between `start` and `end`, clipped and moved to start at zero
+fn clip_events(
+    events: &[RecordingEvent],
+    start: Duration,
+    end: Duration,
+    include_end: bool,
+) -> Vec<RecordingEvent> {
+    let in_range = |t: Duration| t >= start && (t < end || (include_end && t == end));
+    let mut times: Vec<Option<Duration>> = events
+        .iter()
+        .map(|e| in_range(e.time).then_some(e.time))
+        .collect();
+    for (s, e) in region_pairs(events) {
+        let (from, to) = (events[s].time, events[e].time);
+        let (clipped_from, clipped_to) = (from.max(start), to.min(end));
+        let keep = if from == to {
+            in_range(from)
+        } else {
+            clipped_from < clipped_to
+        };
+        times[s] = keep.then_some(clipped_from);
+        times[e] = keep.then_some(clipped_to);
+    }
+
+    let mut clipped: Vec<RecordingEvent> = events
+        .iter()
+        .zip(times)
+        .filter_map(|(event, time)| {
+            Some(RecordingEvent {
+                time: time? - start,
+                ..event.clone()
+            })
+        })
+        .collect();
+    sort_events(&mut clipped);
+    clipped
+}
+
+impl SessionRecording {
+    /// Copy of the part between `start` and `end` (inclusive), moved to
+    /// start at zero
+    pub fn trim(&self, start: Duration, end: Duration) -> SessionRecording {
+        self.segment(start, end, true)
+    }
+
+    fn segment(&self, start: Duration, end: Duration, include_end: bool) -> SessionRecording {
+        let mut result = SessionRecording::with_metadata(self.metadata.clone());
+        result.metadata.recorded_at += chrono::Duration::from_std(start).unwrap_or_default();
+        result.metadata.events = clip_events(&self.metadata.events, start, end, include_end);
+        result.frames = self
+            .frames
+            .iter()
+            .filter(|f| {
+                f.timestamp >= start && (f.timestamp < end || (include_end && f.timestamp == end))
+            })
+            .map(|f| RecordedFrame {
+                timestamp: f.timestamp - start,
+                ..f.clone()
+            })
+            .collect();
+        result.finalize();
+        result
+    }
+
+    /// Split into consecutive parts at the given times
+    ///
+    /// Times outside the recording are ignored. A frame or event at a split
+    /// time starts the next part. Parts start at zero and are named after
+    /// their position.
+    pub fn split_at(&self, times: &[Duration]) -> Vec<SessionRecording> {
+        let duration = self.duration();
+        let mut bounds: Vec<Duration> = times
+            .iter()
+            .copied()
+            .filter(|t| !t.is_zero() && *t < duration)
+            .collect();
+        bounds.sort();
+        bounds.dedup();
+        bounds.insert(0, Duration::ZERO);
+        bounds.push(duration);
+
+        let count = bounds.len() - 1;
+        if count == 1 {
+            return vec![self.clone()];
+        }
+        bounds
+            .windows(2)
+            .enumerate()
+            .map(|(i, range)| {
+                let mut part = self.segment(range[0], range[1], i + 1 == count);
+                part.metadata.name = format!("{} (part {}/{})", self.metadata.name, i + 1, count);
+                part
+            })
+            .collect()
+    }
+
+    /// Times of the events and tagged frames, in order and without repeats
+    pub fn marker_times(&self) -> Vec<Duration> {
+        let mut times: Vec<Duration> = self
+            .metadata
+            .events
+            .iter()
+            .map(|e| e.time)
+            .chain(
+                self.frames
+                    .iter()
+                    .filter(|f| f.tag.is_some())
+                    .map(|f| f.timestamp),
+            )
+            .collect();
+        times.sort();
+        times.dedup();
+        times
+    }
+
+    /// Split at every event and tagged frame
+    pub fn split_at_markers(&self) -> Vec<SessionRecording> {
+        self.split_at(&self.marker_times())
+    }
+
+    /// Copy without the given variables
+    pub fn drop_variables(&self, ids: &HashSet<u32>) -> SessionRecording {
+        let mut result = self.clone();
+        result.metadata.variables.retain(|v| !ids.contains(&v.id));
+        for frame in &mut result.frames {
+            frame.values.retain(|id, _| !ids.contains(id));
+        }
+        result.finalize();
+        result
+    }
+
+    /// Append runs one after the other, `gap` apart
+    ///
+    /// The runs must record the same variables, matched by name; later runs
+    /// take the variable IDs of the first. An event named after each later
+    /// run marks where it starts. The result keeps the first run's metadata.
+    pub fn concat(runs: &[SessionRecording], gap: Duration) -> io::Result<SessionRecording> {
+        let Some(first) = runs.first() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "No recordings to merge",
+            ));
+        };
+        let names = variable_names(first);
+        if let Some(run) = runs.iter().find(|run| variable_names(run) != names) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "'{}' records different variables than '{}'",
+                    run.metadata.name, first.metadata.name
+                ),
+            ));
+        }
+
+        let mut result = SessionRecording::with_metadata(first.metadata.clone());
+        result.metadata.name = runs
+            .iter()
+            .map(|run| run.metadata.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" + ");
+        result.metadata.events.clear();
+
+        for (i, run) in runs.iter().enumerate() {
+            let offset = if i == 0 {
+                Duration::ZERO
+            } else {
+                let offset = result.duration() + gap;
+                insert_event(
+                    &mut result.metadata.events,
+                    RecordingEvent::new(offset, MarkerType::Event, run.metadata.name.clone()),
+                );
+                offset
+            };
+            let ids: HashMap<u32, u32> = run
+                .metadata
+                .variables
+                .iter()
+                .filter_map(|v| {
+                    let target = first.metadata.variables.iter().find(|f| f.name == v.name)?;
+                    Some((v.id, target.id))
+                })
+                .collect();
+
+            result.frames.extend(run.frames.iter().map(|frame| {
+                RecordedFrame {
+                    timestamp: frame.timestamp + offset,
+                    values: frame
+                        .values
+                        .iter()
+                        .map(|(id, value)| (ids.get(id).copied().unwrap_or(*id), value.clone()))
+                        .collect(),
+                    tag: frame.tag.clone(),
+                }
+            }));
+            result
+                .metadata
+                .events
+                .extend(run.metadata.events.iter().map(|event| RecordingEvent {
+                    time: event.time + offset,
+                    ..event.clone()
+                }));
+        }
+        sort_events(&mut result.metadata.events);
+        result.finalize();
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::types::{RecordedValue, SessionMetadata};
+    use crate::types::{RawValue, Variable, VariableType};
+
+    fn recording(name: &str, variables: &[Variable], frames: u64) -> SessionRecording {
+        let mut metadata = SessionMetadata::new(name);
+        metadata.variables = variables.to_vec();
+        let mut recording = SessionRecording::with_metadata(metadata);
+        for i in 0..frames {
+            recording.frames.push(RecordedFrame {
+                timestamp: Duration::from_millis(i * 100),
+                values: variables
+                    .iter()
+                    .map(|v| {
+                        (
+                            v.id,
+                            RecordedValue {
+                                raw_value: RawValue::UInt(i),
+                                converted_value: i as f64,
+                            },
+                        )
+                    })
+                    .collect(),
+                tag: None,
+            });
+        }
+        recording.finalize();
+        recording
+    }
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_trim_clips_regions() {
+        let var = Variable::new("speed", 0x2000_0000, VariableType::F32);
+        let mut source = recording("run", &[var], 10);
+        source.frames[5].tag = Some("kick".into());
+        source.metadata.events = vec![
+            RecordingEvent::new(ms(100), MarkerType::RegionStart, "warm-up"),
+            RecordingEvent::new(ms(250), MarkerType::Note, "fan on"),
+            RecordingEvent::new(ms(400), MarkerType::RegionEnd, "warm-up end"),
+            RecordingEvent::new(ms(800), MarkerType::Error, "stall"),
+        ];
+
+        let trimmed = source.trim(ms(200), ms(600));
+        assert_eq!(trimmed.frame_count(), 5);
+        assert_eq!(trimmed.duration(), ms(400));
+        assert_eq!(trimmed.frames[3].tag.as_deref(), Some("kick"));
+        let events: Vec<(u128, &str)> = trimmed
+            .metadata
+            .events
+            .iter()
+            .map(|e| (e.time.as_millis(), e.name.as_str()))
+            .collect();
+        assert_eq!(
+            events,
+            [(0, "warm-up"), (50, "fan on"), (200, "warm-up end")]
+        );
+        assert_eq!(trimmed.metadata.name, "run");
+    }
+
+    #[test]
+    fn test_split_at_markers() {
+        let var = Variable::new("speed", 0x2000_0000, VariableType::F32);
+        let mut source = recording("run", &[var], 10);
+        source.frames[7].tag = Some("kick".into());
+        source.metadata.events = vec![RecordingEvent::new(ms(300), MarkerType::Event, "step")];
+
+        assert_eq!(source.marker_times(), [ms(300), ms(700)]);
+        let parts = source.split_at_markers();
+        let sizes: Vec<usize> = parts.iter().map(|p| p.frame_count()).collect();
+        assert_eq!(sizes, [3, 4, 3]);
+        assert_eq!(parts[1].metadata.name, "run (part 2/3)");
+        // The marker starts the next part
+        assert_eq!(parts[0].metadata.events.len(), 0);
+        assert_eq!(parts[1].metadata.events[0].time, Duration::ZERO);
+        assert_eq!(parts[2].frames[0].tag.as_deref(), Some("kick"));
+        assert_eq!(source.split_at(&[]).len(), 1);
+    }
+
+    #[test]
+    fn test_concat_and_drop() {
+        let a = Variable::new("speed", 0x2000_0000, VariableType::F32);
+        let b = Variable::new("current", 0x2000_0004, VariableType::F32);
+        let first = recording("first", &[a.clone(), b.clone()], 3);
+        // Same variables under new IDs, as after reloading the project
+        let second = recording(
+            "second",
+            &[
+                Variable::new("current", 0x2000_0004, VariableType::F32),
+                Variable::new("speed", 0x2000_0000, VariableType::F32),
+            ],
+            2,
+        );
+
+        let merged = SessionRecording::concat(&[first.clone(), second], ms(50)).unwrap();
+        assert_eq!(merged.metadata.name, "first + second");
+        assert_eq!(merged.frame_count(), 5);
+        assert_eq!(merged.frames[3].timestamp, ms(250));
+        assert!(merged.frames.iter().all(|f| f.values.contains_key(&a.id)));
+        assert_eq!(merged.metadata.events[0].name, "second");
+        assert_eq!(merged.metadata.events[0].time, ms(250));
+
+        let pruned = merged.drop_variables(&HashSet::from([b.id]));
+        assert_eq!(pruned.metadata.variables.len(), 1);
+        assert!(pruned.frames.iter().all(|f| f.values.len() == 1));
+
+        let err = SessionRecording::concat(&[first, pruned], ms(0)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
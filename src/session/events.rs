@@ -0,0 +1,258 @@
+//! Markers, regions and notes attached to a recording
+//!
+//! Events are stored in the recording's [`SessionMetadata`](super::SessionMetadata)
+//! so every session format carries them: the JSON and binary containers
+//! serialize them with the metadata, MCAP files keep them in the
+//! `datavis.session` metadata record and MDF4 files write them as event
+//! blocks. Unlike frame tags they need not fall on a recorded frame, carry a
+//! type and a free-form note, and can be edited after recording.
+//!
+//! The events table export lists one row per event; a region start row also
+//! gives the end and duration of the region it opens.
+
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Marker type for categorization and color coding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum MarkerType {
+    /// Generic event marker (blue)
+    #[default]
+    Event,
+    /// Error or issue marker (red)
+    Error,
+    /// Note/annotation marker (yellow)
+    Note,
+    /// Start of a region marker (green)
+    RegionStart,
+    /// End of a region marker (green)
+    RegionEnd,
+    /// Custom marker (user-defined color)
+    Custom,
+}
+
+impl MarkerType {
+    /// Get the display name for this marker type
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            MarkerType::Event => "Event",
+            MarkerType::Error => "Error",
+            MarkerType::Note => "Note",
+            MarkerType::RegionStart => "Region Start",
+            MarkerType::RegionEnd => "Region End",
+            MarkerType::Custom => "Custom",
+        }
+    }
+
+    /// Get all marker types
+    pub fn all() -> &'static [MarkerType] {
+        &[
+            MarkerType::Event,
+            MarkerType::Error,
+            MarkerType::Note,
+            MarkerType::RegionStart,
+            MarkerType::RegionEnd,
+            MarkerType::Custom,
+        ]
+    }
+
+    /// Parse a display name, ignoring case
+    pub fn from_display_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|t| t.display_name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// A marker, region boundary or note at a point of a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingEvent {
+    /// Time offset from start of recording
+    pub time: Duration,
+    /// Event type
+    pub kind: MarkerType,
+    /// Short label
+    pub name: String,
+    /// Free-form note
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Colour override (RGBA)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<[u8; 4]>,
+}
+
+impl RecordingEvent {
+    /// Create an event without a note
+    pub fn new(time: Duration, kind: MarkerType, name: impl Into<String>) -> Self {
+        Self {
+            time,
+            kind,
+            name: name.into(),
+            note: None,
+            color: None,
+        }
+    }
+
+    /// Set the note
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+/// Insert an event, keeping the list sorted by time
+///
+/// Events at the same time keep their insertion order. Returns the index
+/// of the inserted event.
+pub fn insert_event(events: &mut Vec<RecordingEvent>, event: RecordingEvent) -> usize {
+    let index = events.partition_point(|e| e.time <= event.time);
+    events.insert(index, event);
+    index
+}
+
+/// Sort events by time, keeping the order of events at the same time
+pub fn sort_events(events: &mut [RecordingEvent]) {
+    events.sort_by_key(|e| e.time);
+}
+
+/// Indices `(start, end)` of the region start and end events that pair up
+///
+/// A region start is closed by the next region end; a later start replaces
+/// one that is still open, and an end with no open start is left unpaired.
+pub fn region_pairs(events: &[RecordingEvent]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    let mut open: Option<usize> = None;
+    for (i, event) in events.iter().enumerate() {
+        match event.kind {
+            MarkerType::RegionStart => open = Some(i),
+            MarkerType::RegionEnd => {
+                if let Some(start) = open.take() {
+                    pairs.push((start, i));
+                }
+            }
+            _ => {}
+        }
+    }
+    pairs
+}
+
+/// End of the region each event opens
+///
+/// Any event other than a paired region start opens no region.
+pub fn region_ends(events: &[RecordingEvent]) -> Vec<Option<Duration>> {
+    let mut ends = vec![None; events.len()];
+    for (start, end) in region_pairs(events) {
+        ends[start] = Some(events[end].time);
+    }
+    ends
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Write events as a CSV table
+///
+/// Columns: `time_s, type, name, end_s, duration_s, note`. The end and
+/// duration are only filled in for region starts.
+pub fn write_events_csv<W: Write>(events: &[RecordingEvent], mut writer: W) -> io::Result<()> {
+    writeln!(writer, "time_s,type,name,end_s,duration_s,note")?;
+    for (event, end) in events.iter().zip(region_ends(events)) {
+        let (end, duration) = match end {
+            Some(end) => (
+                format!("{:.6}", end.as_secs_f64()),
+                format!("{:.6}", end.saturating_sub(event.time).as_secs_f64()),
+            ),
+            None => (String::new(), String::new()),
+        };
+        writeln!(
+            writer,
+            "{:.6},{},{},{},{},{}",
+            event.time.as_secs_f64(),
+            csv_field(event.kind.display_name()),
+            csv_field(&event.name),
+            end,
+            duration,
+            csv_field(event.note.as_deref().unwrap_or_default()),
+        )?;
+    }
+    Ok(())
+}
+
+/// Save events as a CSV table
+pub fn save_events_csv(events: &[RecordingEvent], path: &Path) -> io::Result<()> {
+    let mut writer = io::BufWriter::new(std::fs::File::create(path)?);
+    write_events_csv(events, &mut writer)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(ms: u64, kind: MarkerType, name: &str) -> RecordingEvent {
+        RecordingEvent::new(Duration::from_millis(ms), kind, name)
+    }
+
+    #[test]
+    fn test_insert_keeps_time_order() {
+        let mut events = Vec::new();
+        insert_event(&mut events, event(300, MarkerType::Event, "c"));
+        insert_event(&mut events, event(100, MarkerType::Event, "a"));
+        let index = insert_event(&mut events, event(300, MarkerType::Note, "d"));
+        insert_event(&mut events, event(200, MarkerType::Error, "b"));
+        let names: Vec<&str> = events.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["a", "b", "c", "d"]);
+        assert_eq!(index, 2);
+    }
+
+    #[test]
+    fn test_events_table() {
+        let events = vec![
+            event(500, MarkerType::RegionStart, "warm-up"),
+            event(750, MarkerType::Note, "fan on").with_note("duty 40%, \"quiet\""),
+            event(1500, MarkerType::RegionEnd, "warm-up end"),
+            event(2000, MarkerType::RegionEnd, "stray end"),
+        ];
+        assert_eq!(
+            region_ends(&events),
+            vec![Some(Duration::from_millis(1500)), None, None, None]
+        );
+
+        let mut out = Vec::new();
+        write_events_csv(&events, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "time_s,type,name,end_s,duration_s,note");
+        assert_eq!(lines[1], "0.500000,Region Start,warm-up,1.500000,1.000000,");
+        assert_eq!(
+            lines[2],
+            "0.750000,Note,fan on,,,\"duty 40%, \"\"quiet\"\"\""
+        );
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn test_marker_type_names() {
+        for kind in MarkerType::all() {
+            assert_eq!(
+                MarkerType::from_display_name(kind.display_name()),
+                Some(*kind)
+            );
+        }
+        assert_eq!(
+            MarkerType::from_display_name(" region end "),
+            Some(MarkerType::RegionEnd)
+        );
+        assert_eq!(MarkerType::from_display_name("bogus"), None);
+    }
+}
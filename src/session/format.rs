@@ -0,0 +1,799 @@
+//! Compact binary session container
+//!
+//! Recordings are stored as a sequence of independently compressed chunks so
+//! they can be written while recording and read one block at a time during
+//! playback. All integers are little-endian.
+//!
+//! ```text
+//! header   "DVSR", u32 version, u32 length, deflated JSON SessionMetadata
+//! block    chunk of up to BLOCK_FRAMES frames in columnar layout
+//! ...
+//! footer   chunk holding deflated JSON of the final metadata and block index
+//! trailer  u64 footer offset, "DVSE"
+//! ```
+//!
+//! Every chunk starts with a 29-byte header: `u8` kind, `u32` frame count,
+//! `u64` first and last timestamps (nanoseconds), `u32` payload length and
+//! `u32` CRC-32 of the payload. A file without a valid footer (recording
+//! interrupted) is recovered by scanning its chunks up to the first damaged
+//! one.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::RawValue;
+
+use super::types::{RecordedFrame, RecordedValue, SessionMetadata, SessionRecording};
+
+/// Magic bytes at the start of a binary session file
+pub const SESSION_MAGIC: &[u8; 4] = b"DVSR";
+
+/// Current version of the container format
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Maximum number of frames per block
+pub const BLOCK_FRAMES: usize = 4096;
+
+const TRAILER_MAGIC: &[u8; 4] = b"DVSE";
+const TRAILER_LEN: u64 = 12;
+const CHUNK_HEADER_LEN: usize = 29;
+
+const CHUNK_BLOCK: u8 = 1;
+const CHUNK_FOOTER: u8 = 2;
+
+const KIND_UINT: u8 = 0;
+const KIND_INT: u8 = 1;
+const KIND_FLOAT: u8 = 2;
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn deflate(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+fn inflate(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    DeflateDecoder::new(bytes).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = Crc::new();
+    crc.update(bytes);
+    crc.sum()
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos() as u64
+}
+
+/// Check whether a file starts with the binary session magic
+pub fn is_binary_session(path: &Path) -> io::Result<bool> {
+    let mut magic = [0u8; 4];
+    match File::open(path)?.read_exact(&mut magic) {
+        Ok(()) => Ok(&magic == SESSION_MAGIC),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Position and time span of one block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockInfo {
+    /// Byte offset of the block's chunk header
+    pub offset: u64,
+    /// Number of frames in the block
+    pub frame_count: u32,
+    /// Timestamp of the first frame (nanoseconds)
+    pub first_nanos: u64,
+    /// Timestamp of the last frame (nanoseconds)
+    pub last_nanos: u64,
+}
+
+/// Contents of the footer chunk
+#[derive(Debug, Serialize, Deserialize)]
+struct Footer {
+    metadata: SessionMetadata,
+    blocks: Vec<BlockInfo>,
+}
+
+/// Header preceding each chunk payload
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ChunkHeader {
+    kind: u8,
+    frame_count: u32,
+    first_nanos: u64,
+    last_nanos: u64,
+    len: u32,
+    crc: u32,
+}
+
+impl ChunkHeader {
+    fn to_bytes(self) -> [u8; CHUNK_HEADER_LEN] {
+        let mut bytes = [0u8; CHUNK_HEADER_LEN];
+        bytes[0] = self.kind;
+        bytes[1..5].copy_from_slice(&self.frame_count.to_le_bytes());
+        bytes[5..13].copy_from_slice(&self.first_nanos.to_le_bytes());
+        bytes[13..21].copy_from_slice(&self.last_nanos.to_le_bytes());
+        bytes[21..25].copy_from_slice(&self.len.to_le_bytes());
+        bytes[25..29].copy_from_slice(&self.crc.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; CHUNK_HEADER_LEN]) -> Self {
+        let mut reader = ByteReader::new(bytes);
+        // The slice is exactly one header long, so these reads cannot fail
+        Self {
+            kind: reader.u8().unwrap_or_default(),
+            frame_count: reader.u32().unwrap_or_default(),
+            first_nanos: reader.u64().unwrap_or_default(),
+            last_nanos: reader.u64().unwrap_or_default(),
+            len: reader.u32().unwrap_or_default(),
+            crc: reader.u32().unwrap_or_default(),
+        }
+    }
+}
+
+/// Bounds-checked little-endian reader over a byte slice
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| invalid_data("session block is truncated"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+/// Encode frames as a columnar block payload (before compression)
+///
+/// Timestamps and frame indices are delta-coded so they compress well. Each
+/// variable's values form their own columns of frame index, raw kind, raw
+/// bits and converted value.
+fn encode_block(frames: &[RecordedFrame]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(frames.len() as u32).to_le_bytes());
+
+    let mut previous = 0;
+    for frame in frames {
+        let t = nanos(frame.timestamp);
+        out.extend_from_slice(&t.wrapping_sub(previous).to_le_bytes());
+        previous = t;
+    }
+
+    let tagged: Vec<(usize, &String)> = frames
+        .iter()
+        .enumerate()
+        .filter_map(|(i, f)| f.tag.as_ref().map(|tag| (i, tag)))
+        .collect();
+    out.extend_from_slice(&(tagged.len() as u32).to_le_bytes());
+    for (index, tag) in tagged {
+        out.extend_from_slice(&(index as u32).to_le_bytes());
+        out.extend_from_slice(&(tag.len() as u32).to_le_bytes());
+        out.extend_from_slice(tag.as_bytes());
+    }
+
+    let mut columns: BTreeMap<u32, Vec<(u32, &RecordedValue)>> = BTreeMap::new();
+    for (index, frame) in frames.iter().enumerate() {
+        for (var_id, value) in &frame.values {
+            columns
+                .entry(*var_id)
+                .or_default()
+                .push((index as u32, value));
+        }
+    }
+    out.extend_from_slice(&(columns.len() as u32).to_le_bytes());
+    for (var_id, values) in columns {
+        out.extend_from_slice(&var_id.to_le_bytes());
+        out.extend_from_slice(&(values.len() as u32).to_le_bytes());
+        let mut previous = 0;
+        for (index, _) in &values {
+            out.extend_from_slice(&(index - previous).to_le_bytes());
+            previous = *index;
+        }
+        for (_, value) in &values {
+            out.push(match value.raw_value {
+                RawValue::UInt(_) => KIND_UINT,
+                RawValue::Int(_) => KIND_INT,
+                RawValue::Float(_) => KIND_FLOAT,
+            });
+        }
+        for (_, value) in &values {
+            let bits = match value.raw_value {
+                RawValue::UInt(v) => v,
+                RawValue::Int(v) => v as u64,
+                RawValue::Float(v) => v.to_bits(),
+            };
+            out.extend_from_slice(&bits.to_le_bytes());
+        }
+        for (_, value) in &values {
+            out.extend_from_slice(&value.converted_value.to_le_bytes());
+        }
+    }
+    out
+}
+
+/// Decode a block payload produced by [`encode_block`]
+fn decode_block(bytes: &[u8]) -> io::Result<Vec<RecordedFrame>> {
+    let mut reader = ByteReader::new(bytes);
+    let frame_count = reader.u32()? as usize;
+    // Every frame needs at least its 8-byte timestamp
+    if frame_count > bytes.len() / 8 {
+        return Err(invalid_data("session block frame count is corrupt"));
+    }
+
+    let mut frames = Vec::with_capacity(frame_count);
+    let mut t = 0u64;
+    for _ in 0..frame_count {
+        t = t.wrapping_add(reader.u64()?);
+        frames.push(RecordedFrame {
+            timestamp: Duration::from_nanos(t),
+            values: HashMap::new(),
+            tag: None,
+        });
+    }
+
+    for _ in 0..reader.u32()? {
+        let index = reader.u32()? as usize;
+        let len = reader.u32()? as usize;
+        let tag = String::from_utf8(reader.take(len)?.to_vec())
+            .map_err(|_| invalid_data("session frame tag is not UTF-8"))?;
+        frames
+            .get_mut(index)
+            .ok_or_else(|| invalid_data("session frame tag index out of range"))?
+            .tag = Some(tag);
+    }
+
+    for _ in 0..reader.u32()? {
+        let var_id = reader.u32()?;
+        let count = reader.u32()? as usize;
+        let indices = reader.take(count * 4)?;
+        let kinds = reader.take(count)?;
+        let bits = reader.take(count * 8)?;
+        let converted = reader.take(count * 8)?;
+        let mut index = 0usize;
+        for i in 0..count {
+            index += u32::from_le_bytes(indices[i * 4..i * 4 + 4].try_into().unwrap()) as usize;
+            let raw_bits = u64::from_le_bytes(bits[i * 8..i * 8 + 8].try_into().unwrap());
+            let raw_value = match kinds[i] {
+                KIND_UINT => RawValue::UInt(raw_bits),
+                KIND_INT => RawValue::Int(raw_bits as i64),
+                _ => RawValue::Float(f64::from_bits(raw_bits)),
+            };
+            let converted_value =
+                f64::from_le_bytes(converted[i * 8..i * 8 + 8].try_into().unwrap());
+            frames
+                .get_mut(index)
+                .ok_or_else(|| invalid_data("session value frame index out of range"))?
+                .values
+                .insert(
+                    var_id,
+                    RecordedValue {
+                        raw_value,
+                        converted_value,
+                    },
+                );
+        }
+    }
+    Ok(frames)
+}
+
+/// Streaming writer of the binary session format
+///
+/// Frames are buffered until a block is full, then compressed and written,
+/// so memory use stays bounded however long the recording runs.
+#[derive(Debug)]
+pub struct SessionWriter<W: Write> {
+    writer: W,
+    /// Bytes written so far (offset of the next chunk)
+    position: u64,
+    pending: Vec<RecordedFrame>,
+    blocks: Vec<BlockInfo>,
+    frame_count: usize,
+}
+
+impl<W: Write> SessionWriter<W> {
+    /// Start a session, writing the header with the initial metadata
+    pub fn new(mut writer: W, metadata: &SessionMetadata) -> io::Result<Self> {
+        let json = serde_json::to_vec(metadata).map_err(|e| invalid_data(e.to_string()))?;
+        let compressed = deflate(&json)?;
+        writer.write_all(SESSION_MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&(compressed.len() as u32).to_le_bytes())?;
+        writer.write_all(&compressed)?;
+        Ok(Self {
+            writer,
+            position: 12 + compressed.len() as u64,
+            pending: Vec::with_capacity(BLOCK_FRAMES),
+            blocks: Vec::new(),
+            frame_count: 0,
+        })
+    }
+
+    /// Number of frames written (including buffered ones)
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// Underlying writer
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Append a frame; frames must arrive in time order
+    pub fn write_frame(&mut self, frame: RecordedFrame) -> io::Result<()> {
+        self.pending.push(frame);
+        self.frame_count += 1;
+        if self.pending.len() >= BLOCK_FRAMES {
+            self.flush_block()?;
+        }
+        Ok(())
+    }
+
+    /// Compress and write the buffered frames as a block
+    pub fn flush_block(&mut self) -> io::Result<()> {
+        let (Some(first), Some(last)) = (self.pending.first(), self.pending.last()) else {
+            return Ok(());
+        };
+        let info = BlockInfo {
+            offset: self.position,
+            frame_count: self.pending.len() as u32,
+            first_nanos: nanos(first.timestamp),
+            last_nanos: nanos(last.timestamp),
+        };
+        let payload = deflate(&encode_block(&self.pending))?;
+        self.write_chunk(
+            ChunkHeader {
+                kind: CHUNK_BLOCK,
+                frame_count: info.frame_count,
+                first_nanos: info.first_nanos,
+                last_nanos: info.last_nanos,
+                len: payload.len() as u32,
+                crc: crc32(&payload),
+            },
+            &payload,
+        )?;
+        self.writer.flush()?;
+        self.blocks.push(info);
+        self.pending.clear();
+        Ok(())
+    }
+
+    fn write_chunk(&mut self, header: ChunkHeader, payload: &[u8]) -> io::Result<()> {
+        self.writer.write_all(&header.to_bytes())?;
+        self.writer.write_all(payload)?;
+        self.position += (CHUNK_HEADER_LEN + payload.len()) as u64;
+        Ok(())
+    }
+
+    /// Write the remaining frames, the block index and the final metadata
+    pub fn finish(mut self, metadata: &SessionMetadata) -> io::Result<W> {
+        self.flush_block()?;
+        let footer = Footer {
+            metadata: metadata.clone(),
+            blocks: std::mem::take(&mut self.blocks),
+        };
+        let json = serde_json::to_vec(&footer).map_err(|e| invalid_data(e.to_string()))?;
+        let payload = deflate(&json)?;
+        let footer_offset = self.position;
+        self.write_chunk(
+            ChunkHeader {
+                kind: CHUNK_FOOTER,
+                frame_count: 0,
+                first_nanos: 0,
+                last_nanos: 0,
+                len: payload.len() as u32,
+                crc: crc32(&payload),
+            },
+            &payload,
+        )?;
+        self.writer.write_all(&footer_offset.to_le_bytes())?;
+        self.writer.write_all(TRAILER_MAGIC)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Read a chunk at the reader's position, verifying its checksum
+fn read_chunk(reader: &mut impl Read) -> io::Result<(ChunkHeader, Vec<u8>)> {
+    let mut header_bytes = [0u8; CHUNK_HEADER_LEN];
+    reader.read_exact(&mut header_bytes)?;
+    let header = ChunkHeader::from_bytes(&header_bytes);
+    let mut payload = Vec::new();
+    reader.take(header.len as u64).read_to_end(&mut payload)?;
+    if payload.len() != header.len as usize {
+        return Err(invalid_data("session chunk is truncated"));
+    }
+    if crc32(&payload) != header.crc {
+        return Err(invalid_data("session chunk checksum mismatch"));
+    }
+    Ok((header, payload))
+}
+
+/// A binary session file opened for lazy reading
+///
+/// Only the header and block index are read up front; blocks are
+/// decompressed on demand and the most recent one is kept.
+#[derive(Debug)]
+pub struct SessionFile {
+    file: File,
+    metadata: SessionMetadata,
+    blocks: Vec<BlockInfo>,
+    /// Index of the first frame of each block
+    block_starts: Vec<usize>,
+    frame_count: usize,
+    /// Whether the footer was missing and the index was rebuilt by scanning
+    recovered: bool,
+    cache: RefCell<Option<(usize, Rc<Vec<RecordedFrame>>)>>,
+}
+
+impl SessionFile {
+    /// Open a binary session file
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut reader = BufReader::new(&mut file);
+
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != SESSION_MAGIC {
+            return Err(invalid_data("not a binary session file"));
+        }
+        let mut word = [0u8; 4];
+        reader.read_exact(&mut word)?;
+        let version = u32::from_le_bytes(word);
+        if version > FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "session format version {} is newer than supported ({})",
+                version, FORMAT_VERSION
+            )));
+        }
+        reader.read_exact(&mut word)?;
+        let mut compressed = vec![0u8; u32::from_le_bytes(word) as usize];
+        reader.read_exact(&mut compressed)?;
+        let header_metadata: SessionMetadata = serde_json::from_slice(&inflate(&compressed)?)
+            .map_err(|e| invalid_data(e.to_string()))?;
+        let data_start = 12 + compressed.len() as u64;
+        drop(reader);
+
+        let (metadata, blocks, recovered) = match Self::read_footer(&mut file) {
+            Ok(footer) => (footer.metadata, footer.blocks, false),
+            Err(_) => {
+                let blocks = Self::scan_blocks(&mut file, data_start)?;
+                (header_metadata, blocks, true)
+            }
+        };
+
+        let mut block_starts = Vec::with_capacity(blocks.len());
+        let mut frame_count = 0;
+        for block in &blocks {
+            block_starts.push(frame_count);
+            frame_count += block.frame_count as usize;
+        }
+
+        let mut session = Self {
+            file,
+            metadata,
+            blocks,
+            block_starts,
+            frame_count,
+            recovered,
+            cache: RefCell::new(None),
+        };
+        if recovered {
+            session.metadata.duration = session.duration();
+            session.metadata.total_data_points = frame_count;
+        }
+        Ok(session)
+    }
+
+    fn read_footer(file: &mut File) -> io::Result<Footer> {
+        let len = file.seek(SeekFrom::End(0))?;
+        if len < TRAILER_LEN {
+            return Err(invalid_data("session file has no trailer"));
+        }
+        file.seek(SeekFrom::Start(len - TRAILER_LEN))?;
+        let mut trailer = [0u8; TRAILER_LEN as usize];
+        file.read_exact(&mut trailer)?;
+        if &trailer[8..] != TRAILER_MAGIC {
+            return Err(invalid_data("session file has no trailer"));
+        }
+        let offset = u64::from_le_bytes(trailer[..8].try_into().unwrap());
+        file.seek(SeekFrom::Start(offset))?;
+        let (header, payload) = read_chunk(&mut BufReader::new(&mut *file))?;
+        if header.kind != CHUNK_FOOTER {
+            return Err(invalid_data("session trailer does not point at the footer"));
+        }
+        serde_json::from_slice(&inflate(&payload)?).map_err(|e| invalid_data(e.to_string()))
+    }
+
+    /// Rebuild the block index of a file whose footer is missing
+    fn scan_blocks(file: &mut File, data_start: u64) -> io::Result<Vec<BlockInfo>> {
+        file.seek(SeekFrom::Start(data_start))?;
+        let mut reader = BufReader::new(file);
+        let mut blocks = Vec::new();
+        let mut offset = data_start;
+        // Stop at the footer or at the first incomplete or damaged chunk
+        while let Ok((header, payload)) = read_chunk(&mut reader) {
+            if header.kind != CHUNK_BLOCK {
+                break;
+            }
+            blocks.push(BlockInfo {
+                offset,
+                frame_count: header.frame_count,
+                first_nanos: header.first_nanos,
+                last_nanos: header.last_nanos,
+            });
+            offset += (CHUNK_HEADER_LEN + payload.len()) as u64;
+        }
+        Ok(blocks)
+    }
+
+    /// Session metadata (final metadata, or the header's if recovered)
+    pub fn metadata(&self) -> &SessionMetadata {
+        &self.metadata
+    }
+
+    /// Whether the file was not closed properly and its index was rebuilt
+    pub fn was_recovered(&self) -> bool {
+        self.recovered
+    }
+
+    /// Number of frames
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// Number of blocks
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Timestamp of the last frame
+    pub fn duration(&self) -> Duration {
+        self.blocks
+            .last()
+            .map(|b| Duration::from_nanos(b.last_nanos))
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Decompress one block
+    pub fn read_block(&self, index: usize) -> io::Result<Rc<Vec<RecordedFrame>>> {
+        if let Some((cached, frames)) = self.cache.borrow().as_ref() {
+            if *cached == index {
+                return Ok(Rc::clone(frames));
+            }
+        }
+        let info = self
+            .blocks
+            .get(index)
+            .ok_or_else(|| invalid_data("session block index out of range"))?;
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(info.offset))?;
+        let (_, payload) = read_chunk(&mut BufReader::new(file))?;
+        let frames = Rc::new(decode_block(&inflate(&payload)?)?);
+        *self.cache.borrow_mut() = Some((index, Rc::clone(&frames)));
+        Ok(frames)
+    }
+
+    /// Block holding frame `index` and the frame's position within it
+    fn locate(&self, index: usize) -> Option<(usize, usize)> {
+        if index >= self.frame_count {
+            return None;
+        }
+        let block = self.block_starts.partition_point(|&start| start <= index) - 1;
+        Some((block, index - self.block_starts[block]))
+    }
+
+    /// Read one frame
+    pub fn frame(&self, index: usize) -> io::Result<Option<RecordedFrame>> {
+        let Some((block, offset)) = self.locate(index) else {
+            return Ok(None);
+        };
+        Ok(self.read_block(block)?.get(offset).cloned())
+    }
+
+    /// Read the frames with indices in `range`
+    pub fn frames(&self, range: std::ops::Range<usize>) -> io::Result<Vec<RecordedFrame>> {
+        let end = range.end.min(self.frame_count);
+        let mut frames = Vec::with_capacity(end.saturating_sub(range.start));
+        let mut index = range.start;
+        while index < end {
+            let Some((block, offset)) = self.locate(index) else {
+                break;
+            };
+            let block_frames = self.read_block(block)?;
+            let take = (block_frames.len() - offset).min(end - index);
+            frames.extend_from_slice(&block_frames[offset..offset + take]);
+            index += take;
+        }
+        Ok(frames)
+    }
+
+    /// Find the frame index at or before a given time
+    pub fn find_frame_at(&self, time: Duration) -> io::Result<Option<usize>> {
+        if self.blocks.is_empty() {
+            return Ok(None);
+        }
+        let t = nanos(time);
+        let block = self.blocks.partition_point(|b| b.first_nanos <= t);
+        if block == 0 {
+            return Ok(Some(0));
+        }
+        let frames = self.read_block(block - 1)?;
+        let within = frames.partition_point(|f| f.timestamp <= time).max(1) - 1;
+        Ok(Some(self.block_starts[block - 1] + within))
+    }
+
+    /// Read the whole recording into memory
+    pub fn read_all(&self) -> io::Result<SessionRecording> {
+        let mut recording = SessionRecording::with_metadata(self.metadata.clone());
+        recording.frames = self.frames(0..self.frame_count)?;
+        Ok(recording)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::events::{MarkerType, RecordingEvent};
+
+    fn frame(i: u64) -> RecordedFrame {
+        let mut values = HashMap::new();
+        values.insert(
+            1,
+            RecordedValue {
+                raw_value: RawValue::UInt(i),
+                converted_value: i as f64 * 0.25,
+            },
+        );
+        if i.is_multiple_of(3) {
+            values.insert(
+                7,
+                RecordedValue {
+                    raw_value: RawValue::Int(-(i as i64)),
+                    converted_value: -1.5,
+                },
+            );
+        }
+        RecordedFrame {
+            timestamp: Duration::from_micros(i * 1000 + 7),
+            values,
+            tag: (i == 5).then(|| "trigger".to_string()),
+        }
+    }
+
+    fn write_session(path: &Path, count: u64) -> SessionMetadata {
+        let metadata = SessionMetadata::new("Binary");
+        let file = File::create(path).unwrap();
+        let mut writer = SessionWriter::new(io::BufWriter::new(file), &metadata).unwrap();
+        for i in 0..count {
+            writer.write_frame(frame(i)).unwrap();
+        }
+        let mut final_metadata = metadata;
+        final_metadata.total_data_points = writer.frame_count();
+        final_metadata.events = vec![RecordingEvent::new(
+            Duration::from_millis(3),
+            MarkerType::Error,
+            "stall",
+        )];
+        writer.finish(&final_metadata).unwrap();
+        final_metadata
+    }
+
+    #[test]
+    fn test_block_round_trip() {
+        let frames: Vec<RecordedFrame> = (0..50).map(frame).collect();
+        let decoded = decode_block(&encode_block(&frames)).unwrap();
+        assert_eq!(decoded.len(), 50);
+        for (a, b) in frames.iter().zip(&decoded) {
+            assert_eq!(a.timestamp, b.timestamp);
+            assert_eq!(a.tag, b.tag);
+            assert_eq!(a.values.len(), b.values.len());
+            for (id, value) in &a.values {
+                assert_eq!(b.values[id].raw_value, value.raw_value);
+                assert_eq!(b.values[id].converted_value, value.converted_value);
+            }
+        }
+        assert!(decode_block(&encode_block(&frames)[..100]).is_err());
+    }
+
+    #[test]
+    fn test_session_file_lazy_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.dvs");
+        let count = BLOCK_FRAMES as u64 * 2 + 10;
+        write_session(&path, count);
+
+        assert!(is_binary_session(&path).unwrap());
+        let session = SessionFile::open(&path).unwrap();
+        assert!(!session.was_recovered());
+        assert_eq!(session.block_count(), 3);
+        assert_eq!(session.frame_count(), count as usize);
+        assert_eq!(session.metadata().total_data_points, count as usize);
+        assert_eq!(session.metadata().events[0].name, "stall");
+        assert_eq!(session.duration(), frame(count - 1).timestamp);
+
+        let frames = session.frames(BLOCK_FRAMES - 2..BLOCK_FRAMES + 2).unwrap();
+        assert_eq!(frames.len(), 4);
+        assert_eq!(
+            frames[0].timestamp,
+            frame(BLOCK_FRAMES as u64 - 2).timestamp
+        );
+        assert_eq!(
+            session.frame(5).unwrap().unwrap().tag.as_deref(),
+            Some("trigger")
+        );
+        assert!(session.frame(count as usize).unwrap().is_none());
+
+        let at = frame(BLOCK_FRAMES as u64 + 100).timestamp + Duration::from_micros(500);
+        assert_eq!(session.find_frame_at(at).unwrap(), Some(BLOCK_FRAMES + 100));
+        assert_eq!(session.find_frame_at(Duration::ZERO).unwrap(), Some(0));
+
+        // Far smaller than the equivalent pretty-printed JSON
+        let recording = session.read_all().unwrap();
+        let json = serde_json::to_string_pretty(&recording).unwrap();
+        let size = std::fs::metadata(&path).unwrap().len();
+        assert!(size * 5 < json.len() as u64);
+    }
+
+    #[test]
+    fn test_session_file_recovers_without_footer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.dvs");
+        let count = BLOCK_FRAMES as u64 * 2 + 10;
+        write_session(&path, count);
+
+        // Cut into the footer: the three blocks are still readable
+        let len = std::fs::metadata(&path).unwrap().len();
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(len - 20).unwrap();
+        let session = SessionFile::open(&path).unwrap();
+        assert!(session.was_recovered());
+        assert_eq!(session.frame_count(), count as usize);
+        assert_eq!(session.metadata().total_data_points, count as usize);
+
+        // Cut into the last block: only the complete blocks survive
+        let last_block = session.blocks[2].offset;
+        file.set_len(last_block + 10).unwrap();
+        let session = SessionFile::open(&path).unwrap();
+        assert_eq!(session.frame_count(), BLOCK_FRAMES * 2);
+    }
+}
@@ -0,0 +1,511 @@
+//! Disk-backed history of a live session
+//!
+//! Keeps every data point of the current session on disk so the in-memory
+//! ring buffer only has to cache the recent past. Each variable writes its
+//! points into columnar segment files of [`SEGMENT_POINTS`] points; sealed
+//! segments are memory-mapped for reading, and the segment being filled
+//! stays in memory.
+//!
+//! # Segment format
+//!
+//! All values are little-endian. A 16-byte header (`DVHS` magic, `u32`
+//! version, `u64` point count) is followed by four columns of `count`
+//! entries each: timestamps (`u64` nanoseconds), converted values (`f64`),
+//! raw value bits (`u64`) and raw value kinds (`u8`).
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use memmap2::Mmap;
+
+use crate::types::{DataPoint, RawValue, Variable};
+
+use super::types::{RecordedFrame, RecordedValue, SessionMetadata, SessionRecording};
+
+/// Number of points per segment file
+pub const SEGMENT_POINTS: usize = 65_536;
+
+/// Session directories untouched for this long are left over from a crash
+const STALE_SESSION_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+const SEGMENT_MAGIC: &[u8; 4] = b"DVHS";
+const SEGMENT_VERSION: u32 = 1;
+const HEADER_LEN: usize = 16;
+/// Bytes per point over all columns
+const POINT_LEN: usize = 8 + 8 + 8 + 1;
+
+const KIND_UINT: u8 = 0;
+const KIND_INT: u8 = 1;
+const KIND_FLOAT: u8 = 2;
+
+fn encode_raw(value: RawValue) -> (u8, u64) {
+    match value {
+        RawValue::UInt(v) => (KIND_UINT, v),
+        RawValue::Int(v) => (KIND_INT, v as u64),
+        RawValue::Float(v) => (KIND_FLOAT, v.to_bits()),
+    }
+}
+
+fn decode_raw(kind: u8, bits: u64) -> RawValue {
+    match kind {
+        KIND_UINT => RawValue::UInt(bits),
+        KIND_INT => RawValue::Int(bits as i64),
+        _ => RawValue::Float(f64::from_bits(bits)),
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// First index in `0..len` for which `pred` is false (`pred` must be
+/// true for a prefix)
+fn partition_point(len: usize, pred: impl Fn(usize) -> bool) -> usize {
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if pred(mid) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+/// Points being collected before they are written to a segment file
+#[derive(Debug, Default)]
+struct ActiveSegment {
+    timestamps: Vec<u64>,
+    converted: Vec<f64>,
+    raw_bits: Vec<u64>,
+    raw_kinds: Vec<u8>,
+}
+
+impl ActiveSegment {
+    fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    fn push(&mut self, point: &DataPoint) {
+        let (kind, bits) = encode_raw(point.raw_value);
+        self.timestamps.push(point.timestamp.as_nanos() as u64);
+        self.converted.push(point.converted_value);
+        self.raw_bits.push(bits);
+        self.raw_kinds.push(kind);
+    }
+
+    fn point(&self, index: usize) -> DataPoint {
+        DataPoint {
+            timestamp: Duration::from_nanos(self.timestamps[index]),
+            raw_value: decode_raw(self.raw_kinds[index], self.raw_bits[index]),
+            converted_value: self.converted[index],
+        }
+    }
+
+    fn clear(&mut self) {
+        self.timestamps.clear();
+        self.converted.clear();
+        self.raw_bits.clear();
+        self.raw_kinds.clear();
+    }
+
+    /// Write the points as a segment file
+    fn write_to(&self, path: &Path) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.len() * POINT_LEN);
+        bytes.extend_from_slice(SEGMENT_MAGIC);
+        bytes.extend_from_slice(&SEGMENT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.len() as u64).to_le_bytes());
+        for t in &self.timestamps {
+            bytes.extend_from_slice(&t.to_le_bytes());
+        }
+        for v in &self.converted {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        for b in &self.raw_bits {
+            bytes.extend_from_slice(&b.to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.raw_kinds);
+
+        let mut file = File::create(path)?;
+        file.write_all(&bytes)?;
+        file.sync_data()
+    }
+}
+
+/// A segment file mapped into memory
+#[derive(Debug)]
+struct SealedSegment {
+    map: Mmap,
+    len: usize,
+}
+
+impl SealedSegment {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: segment files are written once before being mapped and
+        // never modified afterwards; only this process writes the session
+        // directory.
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < HEADER_LEN || &map[..4] != SEGMENT_MAGIC {
+            return Err(invalid_data("not a history segment"));
+        }
+        let version = u32::from_le_bytes(map[4..8].try_into().unwrap());
+        if version != SEGMENT_VERSION {
+            return Err(invalid_data("unsupported history segment version"));
+        }
+        let len = u64::from_le_bytes(map[8..16].try_into().unwrap()) as usize;
+        if map.len() != HEADER_LEN + len * POINT_LEN {
+            return Err(invalid_data("truncated history segment"));
+        }
+        Ok(Self { map, len })
+    }
+
+    fn u64_at(&self, column: usize, index: usize) -> u64 {
+        let offset = HEADER_LEN + (column * self.len + index) * 8;
+        u64::from_le_bytes(self.map[offset..offset + 8].try_into().unwrap())
+    }
+
+    fn timestamp(&self, index: usize) -> u64 {
+        self.u64_at(0, index)
+    }
+
+    fn point(&self, index: usize) -> DataPoint {
+        let kind = self.map[HEADER_LEN + 3 * self.len * 8 + index];
+        DataPoint {
+            timestamp: Duration::from_nanos(self.timestamp(index)),
+            raw_value: decode_raw(kind, self.u64_at(2, index)),
+            converted_value: f64::from_bits(self.u64_at(1, index)),
+        }
+    }
+}
+
+/// Every data point of one variable in the current session
+#[derive(Debug)]
+pub struct VariableHistory {
+    dir: PathBuf,
+    sealed: Vec<SealedSegment>,
+    active: ActiveSegment,
+    len: u64,
+}
+
+impl VariableHistory {
+    /// Start an empty history writing its segments into `dir`
+    ///
+    /// Segment files already in `dir` are removed.
+    pub fn create(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            sealed: Vec::new(),
+            active: ActiveSegment::default(),
+            len: 0,
+        })
+    }
+
+    /// Directory holding the segment files
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Number of points stored
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Check if no points are stored
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of bytes written to segment files
+    pub fn disk_size(&self) -> u64 {
+        self.sealed.iter().map(|s| s.map.len() as u64).sum()
+    }
+
+    /// Append a point; points must arrive in time order
+    pub fn push(&mut self, point: &DataPoint) -> io::Result<()> {
+        self.active.push(point);
+        self.len += 1;
+        if self.active.len() >= SEGMENT_POINTS {
+            self.seal()?;
+        }
+        Ok(())
+    }
+
+    /// Write the active points to a new segment file and map it
+    fn seal(&mut self) -> io::Result<()> {
+        let path = self
+            .dir
+            .join(format!("segment-{:06}.dvh", self.sealed.len()));
+        self.active.write_to(&path)?;
+        self.sealed.push(SealedSegment::open(&path)?);
+        self.active.clear();
+        Ok(())
+    }
+
+    /// Remove all points and segment files
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.sealed.clear();
+        self.active.clear();
+        self.len = 0;
+        for entry in std::fs::read_dir(&self.dir)? {
+            std::fs::remove_file(entry?.path())?;
+        }
+        Ok(())
+    }
+
+    /// Time of the first and last points
+    pub fn time_range(&self) -> Option<(Duration, Duration)> {
+        if self.is_empty() {
+            return None;
+        }
+        let first = match self.sealed.first() {
+            Some(segment) => segment.point(0).timestamp,
+            None => self.active.point(0).timestamp,
+        };
+        let last = match (self.active.len(), self.sealed.last()) {
+            (0, Some(segment)) => segment.point(segment.len - 1).timestamp,
+            (n, _) => self.active.point(n - 1).timestamp,
+        };
+        Some((first, last))
+    }
+
+    /// Index ranges of the points between `start` and `end` in each sealed
+    /// segment, then in the active segment
+    fn ranges(
+        &self,
+        start: Duration,
+        end: Duration,
+    ) -> (Vec<std::ops::Range<usize>>, std::ops::Range<usize>) {
+        let (start, end) = (start.as_nanos() as u64, end.as_nanos() as u64);
+        let sealed = self
+            .sealed
+            .iter()
+            .map(|segment| {
+                let first = partition_point(segment.len, |i| segment.timestamp(i) < start);
+                let last = partition_point(segment.len, |i| segment.timestamp(i) <= end);
+                first..last.max(first)
+            })
+            .collect();
+        let timestamps = &self.active.timestamps;
+        let first = timestamps.partition_point(|&t| t < start);
+        let last = timestamps.partition_point(|&t| t <= end);
+        (sealed, first..last.max(first))
+    }
+
+    /// Number of points between `start` and `end`
+    pub fn count_in(&self, start: Duration, end: Duration) -> usize {
+        let (sealed, active) = self.ranges(start, end);
+        sealed.iter().map(|r| r.len()).sum::<usize>() + active.len()
+    }
+
+    /// Points between `start` and `end`
+    pub fn points_in(&self, start: Duration, end: Duration) -> Vec<DataPoint> {
+        let (sealed, active) = self.ranges(start, end);
+        let mut points = Vec::new();
+        for (segment, range) in self.sealed.iter().zip(sealed) {
+            points.extend(range.map(|i| segment.point(i)));
+        }
+        points.extend(active.map(|i| self.active.point(i)));
+        points
+    }
+
+    /// All stored points, oldest first
+    pub fn points(&self) -> impl Iterator<Item = DataPoint> + '_ {
+        let sealed = self
+            .sealed
+            .iter()
+            .flat_map(|segment| (0..segment.len).map(|i| segment.point(i)));
+        let active = (0..self.active.len()).map(|i| self.active.point(i));
+        sealed.chain(active)
+    }
+}
+
+/// Directory holding the history of one live session
+///
+/// The directory is removed when the store is dropped.
+#[derive(Debug)]
+pub struct HistoryStore {
+    root: PathBuf,
+}
+
+impl HistoryStore {
+    /// Create a new session directory under `parent`
+    ///
+    /// Session directories left behind by a crashed run are removed first.
+    pub fn create_in(parent: &Path) -> io::Result<Self> {
+        std::fs::create_dir_all(parent)?;
+        Self::remove_stale(parent);
+        let stamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let root = parent.join(format!("session-{}-{}", std::process::id(), stamp));
+        std::fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// Session directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Start the history of a variable
+    pub fn variable(&self, var_id: u32) -> io::Result<VariableHistory> {
+        VariableHistory::create(self.root.join(format!("var-{}", var_id)))
+    }
+
+    fn remove_stale(parent: &Path) {
+        let Ok(entries) = std::fs::read_dir(parent) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let is_stale = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > STALE_SESSION_AGE);
+            let is_session = entry.file_name().to_string_lossy().starts_with("session-");
+            if is_session && is_stale {
+                if let Err(e) = std::fs::remove_dir_all(entry.path()) {
+                    tracing::warn!("Failed to remove stale history {:?}: {}", entry.path(), e);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for HistoryStore {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.root) {
+            tracing::warn!("Failed to remove session history {:?}: {}", self.root, e);
+        }
+    }
+}
+
+/// Build a recording from the histories of several variables
+///
+/// Points sharing a timestamp become one frame; pause gaps are skipped.
+pub fn recording_from_history<'a, I>(name: &str, histories: I) -> SessionRecording
+where
+    I: IntoIterator<Item = (&'a Variable, &'a VariableHistory)>,
+{
+    let mut frames: std::collections::BTreeMap<Duration, RecordedFrame> = Default::default();
+    let mut variables = Vec::new();
+    for (variable, history) in histories {
+        variables.push(variable.clone());
+        for point in history.points().filter(|p| !p.is_gap()) {
+            frames
+                .entry(point.timestamp)
+                .or_insert_with(|| RecordedFrame {
+                    timestamp: point.timestamp,
+                    values: Default::default(),
+                    tag: None,
+                })
+                .values
+                .insert(variable.id, RecordedValue::from(&point));
+        }
+    }
+
+    let mut metadata = SessionMetadata::new(name);
+    metadata.variables = variables;
+    let mut recording = SessionRecording::with_metadata(metadata);
+    recording.frames = frames.into_values().collect();
+    recording.finalize();
+    recording
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::VariableType;
+
+    fn point(i: u64) -> DataPoint {
+        DataPoint::with_conversion(
+            Duration::from_millis(i),
+            RawValue::from_i64(i as i64 - 10),
+            i as f64 * 0.5,
+        )
+    }
+
+    #[test]
+    fn test_history_round_trip_across_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = VariableHistory::create(dir.path().join("var")).unwrap();
+        let total = SEGMENT_POINTS as u64 * 2 + 100;
+        for i in 0..total {
+            history.push(&point(i)).unwrap();
+        }
+        history
+            .push(&DataPoint::gap_marker(Duration::from_millis(total)))
+            .unwrap();
+
+        assert_eq!(history.len(), total + 1);
+        assert_eq!(history.sealed.len(), 2);
+        assert_eq!(
+            history.disk_size(),
+            2 * (HEADER_LEN + SEGMENT_POINTS * POINT_LEN) as u64
+        );
+        assert_eq!(
+            history.time_range(),
+            Some((Duration::ZERO, Duration::from_millis(total)))
+        );
+
+        // A range spanning a segment boundary
+        let boundary = SEGMENT_POINTS as u64;
+        let points = history.points_in(
+            Duration::from_millis(boundary - 5),
+            Duration::from_millis(boundary + 4),
+        );
+        assert_eq!(points.len(), 10);
+        assert_eq!(points[0].timestamp, Duration::from_millis(boundary - 5));
+        assert_eq!(points[9].converted_value, (boundary + 4) as f64 * 0.5);
+        assert_eq!(points[0].raw_value, RawValue::UInt(boundary - 15));
+        assert_eq!(point(3).raw_value, RawValue::Int(-7));
+        assert_eq!(
+            history.count_in(Duration::ZERO, Duration::from_secs(1000)),
+            total as usize + 1
+        );
+        assert!(history.points().last().unwrap().is_gap());
+
+        history.clear().unwrap();
+        assert!(history.is_empty());
+        assert_eq!(std::fs::read_dir(history.dir()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_history_store_lifecycle_and_export() {
+        let parent = tempfile::tempdir().unwrap();
+        let store = HistoryStore::create_in(parent.path()).unwrap();
+        let root = store.root().to_path_buf();
+
+        let a = Variable::new("a", 0x2000_0000, VariableType::I32);
+        let b = Variable::new("b", 0x2000_0004, VariableType::F32);
+        let mut history_a = store.variable(a.id).unwrap();
+        let mut history_b = store.variable(b.id).unwrap();
+        for i in 0..10 {
+            history_a.push(&point(i)).unwrap();
+            if i % 2 == 0 {
+                history_b.push(&point(i)).unwrap();
+            }
+        }
+
+        let recording = recording_from_history("History", [(&a, &history_a), (&b, &history_b)]);
+        assert_eq!(recording.frame_count(), 10);
+        assert_eq!(recording.frames[2].values.len(), 2);
+        assert_eq!(recording.frames[3].values.len(), 1);
+        assert_eq!(recording.metadata.variables.len(), 2);
+        assert_eq!(recording.duration(), Duration::from_millis(9));
+
+        drop(store);
+        assert!(!root.exists());
+    }
+}
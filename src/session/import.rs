@@ -0,0 +1,837 @@
+//! Import of external CSV and JSON Lines logs as recordings
+//!
+//! Files are first read into an [`ImportTable`] of text cells, then an
+//! [`ImportSettings`] maps its columns to variables:
+//!
+//! - [`ImportLayout::Wide`]: one column per variable, one row per time
+//! - [`ImportLayout::Long`]: one row per sample, with a column naming the
+//!   variable, like our own data persistence output
+//!
+//! Timestamps are parsed with a [`TimestampFormat`] and rebased so the
+//! earliest row is at zero. Rows sharing a timestamp are merged into one
+//! frame. Imported variables have no converter, so their converted values
+//! equal the raw ones.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::config::settings::TimestampFormat;
+use crate::types::{RawValue, Variable, VariableType};
+
+use super::types::{RecordedFrame, RecordedValue, SessionMetadata, SessionRecording};
+
+/// Number of rows inspected when guessing column types and formats
+const GUESS_ROWS: usize = 100;
+
+/// Text format of an imported file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportFileFormat {
+    /// Delimiter separated values
+    #[default]
+    Csv,
+    /// One JSON object per line
+    JsonLines,
+}
+
+impl ImportFileFormat {
+    /// Guess the format from a file's extension and first line
+    pub fn detect(path: &Path, text: &str) -> Self {
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("jsonl" | "ndjson") => ImportFileFormat::JsonLines,
+            Some("csv" | "tsv" | "txt") => ImportFileFormat::Csv,
+            _ if text.trim_start().starts_with('{') => ImportFileFormat::JsonLines,
+            _ => ImportFileFormat::Csv,
+        }
+    }
+
+    /// Display name for the format
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ImportFileFormat::Csv => "CSV",
+            ImportFileFormat::JsonLines => "JSON Lines",
+        }
+    }
+}
+
+/// Options for splitting a file into cells
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOptions {
+    /// File format
+    pub format: ImportFileFormat,
+    /// Field separator for CSV
+    pub delimiter: char,
+    /// Whether the first (non-skipped) CSV row holds column names
+    pub has_header: bool,
+    /// Number of leading lines to skip (scope and logger preambles)
+    pub skip_lines: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            format: ImportFileFormat::Csv,
+            delimiter: ',',
+            has_header: true,
+            skip_lines: 0,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Guess options for a file from its contents
+    pub fn detect(path: &Path, text: &str) -> Self {
+        let format = ImportFileFormat::detect(path, text);
+        let first_line = text
+            .lines()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or_default();
+        // Pick the candidate splitting the first line into the most fields
+        let delimiter = [',', ';', '\t', '|']
+            .into_iter()
+            .max_by_key(|d| first_line.matches(*d).count())
+            .filter(|d| first_line.contains(*d))
+            .unwrap_or(',');
+        let has_header = first_line
+            .split(delimiter)
+            .any(|cell| parse_number(cell.trim().trim_matches('"'), '.').is_none());
+        Self {
+            format,
+            delimiter,
+            has_header,
+            skip_lines: 0,
+        }
+    }
+}
+
+/// A file split into named columns of text cells
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportTable {
+    /// Column names
+    pub columns: Vec<String>,
+    /// Rows of cells, `None` where a row has no value for a column
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
+impl ImportTable {
+    /// Split a file's text into a table
+    pub fn parse(text: &str, options: &ParseOptions) -> io::Result<Self> {
+        let lines = text.lines().skip(options.skip_lines);
+        match options.format {
+            ImportFileFormat::Csv => Ok(Self::parse_csv(lines, options)),
+            ImportFileFormat::JsonLines => Self::parse_json_lines(lines),
+        }
+    }
+
+    /// Read and split a file
+    pub fn read(path: &Path, options: &ParseOptions) -> io::Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?, options)
+    }
+
+    fn parse_csv<'a>(lines: impl Iterator<Item = &'a str>, options: &ParseOptions) -> Self {
+        let mut records = split_csv(lines, options.delimiter).into_iter();
+        let mut columns = if options.has_header {
+            records.next().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let rows: Vec<Vec<Option<String>>> = records
+            .filter(|record| record.iter().any(|cell| !cell.trim().is_empty()))
+            .map(|record| {
+                record
+                    .into_iter()
+                    .map(|cell| Some(cell.trim().to_string()).filter(|c| !c.is_empty()))
+                    .collect()
+            })
+            .collect();
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        for index in columns.len()..width {
+            columns.push(format!("Column {}", index + 1));
+        }
+        for (index, column) in columns.iter_mut().enumerate() {
+            *column = column.trim().to_string();
+            if column.is_empty() {
+                *column = format!("Column {}", index + 1);
+            }
+        }
+        Self { columns, rows }
+    }
+
+    fn parse_json_lines<'a>(lines: impl Iterator<Item = &'a str>) -> io::Result<Self> {
+        let mut columns: Vec<String> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut rows = Vec::new();
+        for (number, line) in lines.enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let value: Value = serde_json::from_str(line).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", number + 1, e),
+                )
+            })?;
+            let mut fields = Vec::new();
+            flatten_json("", &value, &mut fields);
+            let mut row = vec![None; columns.len()];
+            for (key, cell) in fields {
+                let column = *index.entry(key.clone()).or_insert_with(|| {
+                    columns.push(key);
+                    columns.len() - 1
+                });
+                if column >= row.len() {
+                    row.resize(column + 1, None);
+                }
+                row[column] = cell;
+            }
+            rows.push(row);
+        }
+        Ok(Self { columns, rows })
+    }
+
+    /// Number of columns
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Cell of a row, if present
+    pub fn cell(&self, row: usize, column: usize) -> Option<&str> {
+        self.rows.get(row)?.get(column)?.as_deref()
+    }
+
+    /// Index of the first column with one of the given names (case insensitive)
+    pub fn find_column(&self, names: &[&str]) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|c| names.iter().any(|n| c.eq_ignore_ascii_case(n)))
+    }
+
+    /// Sample of a column's cells, for guessing
+    fn sample(&self, column: usize) -> impl Iterator<Item = &str> {
+        self.rows
+            .iter()
+            .take(GUESS_ROWS)
+            .filter_map(move |row| row.get(column)?.as_deref())
+    }
+}
+
+/// Split CSV lines into records, honouring quoted fields (which may span lines)
+fn split_csv<'a>(lines: impl Iterator<Item = &'a str>, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    for line in lines {
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' if quoted => quoted = false,
+                '"' if field.trim().is_empty() => {
+                    field.clear();
+                    quoted = true;
+                }
+                c if c == delimiter && !quoted => record.push(std::mem::take(&mut field)),
+                c => field.push(c),
+            }
+        }
+        if quoted {
+            field.push('\n');
+        } else {
+            record.push(std::mem::take(&mut field));
+            records.push(std::mem::take(&mut record));
+        }
+    }
+    if quoted {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Flatten nested JSON objects into `a.b` keys with text cells
+fn flatten_json(prefix: &str, value: &Value, out: &mut Vec<(String, Option<String>)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_json(&key, value, out);
+            }
+        }
+        Value::Null => out.push((prefix.to_string(), None)),
+        Value::String(s) => out.push((prefix.to_string(), Some(s.clone()))),
+        // Numbers keep their exact text, so 64-bit integers survive
+        value => out.push((prefix.to_string(), Some(value.to_string()))),
+    }
+}
+
+/// Parse a numeric cell into an exact raw value
+///
+/// Accepts integers, `0x` hex, floats (with `decimal` as the separator) and
+/// `true`/`false`.
+pub fn parse_number(cell: &str, decimal: char) -> Option<RawValue> {
+    let cell = cell.trim();
+    if let Ok(v) = cell.parse::<u64>() {
+        return Some(RawValue::UInt(v));
+    }
+    if let Ok(v) = cell.parse::<i64>() {
+        return Some(RawValue::from_i64(v));
+    }
+    if let Some(hex) = cell.strip_prefix("0x").or_else(|| cell.strip_prefix("0X")) {
+        return u64::from_str_radix(&hex.replace('_', ""), 16)
+            .ok()
+            .map(RawValue::UInt);
+    }
+    match cell.to_ascii_lowercase().as_str() {
+        "true" => return Some(RawValue::UInt(1)),
+        "false" => return Some(RawValue::UInt(0)),
+        "nan" => return Some(RawValue::Float(f64::NAN)),
+        _ => {}
+    }
+    let normalized = if decimal == '.' {
+        cell.to_string()
+    } else {
+        cell.replace(decimal, ".")
+    };
+    normalized.parse::<f64>().ok().map(RawValue::Float)
+}
+
+/// Parse a timestamp cell into seconds
+///
+/// Relative formats give seconds since the start of the log, absolute ones
+/// (ISO 8601 and Unix time) seconds since the epoch.
+pub fn parse_timestamp(cell: &str, format: TimestampFormat, decimal: char) -> Option<f64> {
+    let number = || parse_number(cell, decimal).map(|v| v.as_f64());
+    match format {
+        TimestampFormat::Seconds | TimestampFormat::UnixTimestamp => number(),
+        TimestampFormat::Milliseconds => number().map(|v| v / 1e3),
+        TimestampFormat::Microseconds => number().map(|v| v / 1e6),
+        TimestampFormat::Iso8601 => parse_iso8601(cell.trim())
+            .map(|t| t.timestamp() as f64 + t.timestamp_subsec_nanos() as f64 / 1e9),
+    }
+    .filter(|v| v.is_finite())
+}
+
+/// Parse an ISO 8601 date and time, assuming UTC when no offset is given
+fn parse_iso8601(cell: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(cell) {
+        return Some(t.with_timezone(&chrono::Utc));
+    }
+    [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y/%m/%d %H:%M:%S%.f",
+    ]
+    .iter()
+    .find_map(|format| chrono::NaiveDateTime::parse_from_str(cell, format).ok())
+    .map(|t| t.and_utc())
+}
+
+/// Guess the format of a timestamp column from its name and cells
+pub fn guess_timestamp_format(name: &str, cells: &[&str]) -> TimestampFormat {
+    let first = cells.first().copied().unwrap_or_default();
+    if parse_number(first, '.').is_none() && parse_iso8601(first.trim()).is_some() {
+        return TimestampFormat::Iso8601;
+    }
+    let name = name.to_ascii_lowercase();
+    if name.ends_with("_us") || name.contains("(us)") || name.contains("micro") {
+        return TimestampFormat::Microseconds;
+    }
+    if name.ends_with("_ms") || name.contains("(ms)") || name.contains("milli") {
+        return TimestampFormat::Milliseconds;
+    }
+    // Seconds since 2001 or later are Unix time
+    match parse_number(first, '.').map(|v| v.as_f64()) {
+        Some(v) if v > 1e9 && v < 1e11 => TimestampFormat::UnixTimestamp,
+        _ => TimestampFormat::Seconds,
+    }
+}
+
+/// Variable type fitting the numeric cells of a column
+fn guess_var_type(cells: impl Iterator<Item = RawValue>) -> VariableType {
+    let mut var_type = None;
+    for value in cells {
+        var_type = Some(match (var_type, value) {
+            (_, RawValue::Float(_)) | (Some(VariableType::F64), _) => VariableType::F64,
+            (_, RawValue::Int(_)) | (Some(VariableType::I64), _) => VariableType::I64,
+            (_, RawValue::UInt(_)) => VariableType::U64,
+        });
+    }
+    var_type.unwrap_or(VariableType::F64)
+}
+
+/// How rows map to samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportLayout {
+    /// Every mapped column is a variable
+    Wide,
+    /// Each row is one sample of the variable named in `name_column`
+    Long {
+        /// Column holding the variable name or id
+        name_column: usize,
+        /// Column holding the value
+        value_column: usize,
+        /// Column holding the raw value, if separate from the value
+        raw_column: Option<usize>,
+    },
+}
+
+/// Mapping of a column (or, in the long layout, a variable name) to a variable
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMapping {
+    /// Source column (wide layout) or variable name (long layout)
+    pub source: String,
+    /// Whether the column is imported
+    pub enabled: bool,
+    /// Variable name
+    pub name: String,
+    /// Unit label
+    pub unit: String,
+    /// Type of the raw values
+    pub var_type: VariableType,
+}
+
+/// Mapping of a table to a recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSettings {
+    /// Column holding timestamps (rows are spaced `row_interval` apart without one)
+    pub timestamp_column: Option<usize>,
+    /// Format of the timestamp column
+    pub timestamp_format: TimestampFormat,
+    /// Spacing of rows when there is no timestamp column
+    pub row_interval: Duration,
+    /// Decimal separator of numeric cells
+    pub decimal_separator: char,
+    /// How rows map to samples
+    pub layout: ImportLayout,
+    /// Variables to create, by column or name
+    pub mappings: Vec<ColumnMapping>,
+}
+
+impl Default for ImportSettings {
+    fn default() -> Self {
+        Self {
+            timestamp_column: None,
+            timestamp_format: TimestampFormat::Seconds,
+            row_interval: Duration::from_millis(10),
+            decimal_separator: '.',
+            layout: ImportLayout::Wide,
+            mappings: Vec::new(),
+        }
+    }
+}
+
+impl ImportSettings {
+    /// Guess a mapping for a table
+    ///
+    /// Tables with `variable_name`/`variable_id` and `converted_value`
+    /// columns (our persistence output) use the long layout; everything else
+    /// is wide, with the first time-like column as the timestamp.
+    pub fn detect(table: &ImportTable) -> Self {
+        let name_column = table.find_column(&["variable_name", "variable", "name", "signal"]);
+        let id_column = table.find_column(&["variable_id"]);
+        let value_column = table.find_column(&["converted_value", "value"]);
+        let raw_column = table.find_column(&["raw_value", "raw"]);
+
+        let timestamp_column = table
+            .find_column(&[
+                "timestamp_us",
+                "timestamp_ms",
+                "timestamp",
+                "time",
+                "t",
+                "time_s",
+                "seconds",
+                "date",
+                "datetime",
+            ])
+            .or_else(|| {
+                table.columns.iter().position(|c| {
+                    let c = c.to_ascii_lowercase();
+                    c.contains("time") || c.starts_with("t[") || c.starts_with("t (")
+                })
+            });
+        let timestamp_format = timestamp_column
+            .map(|column| {
+                let cells: Vec<&str> = table.sample(column).collect();
+                guess_timestamp_format(&table.columns[column], &cells)
+            })
+            .unwrap_or(TimestampFormat::Seconds);
+
+        let mut settings = Self {
+            timestamp_column,
+            timestamp_format,
+            ..Default::default()
+        };
+        if let (Some(name_column), Some(value_column)) = (name_column.or(id_column), value_column) {
+            settings.layout = ImportLayout::Long {
+                name_column,
+                value_column,
+                raw_column: raw_column.filter(|c| *c != value_column),
+            };
+        }
+        settings.mappings = settings.default_mappings(table);
+        settings
+    }
+
+    /// Columns (wide) or variable names (long) that can be mapped
+    pub fn default_mappings(&self, table: &ImportTable) -> Vec<ColumnMapping> {
+        match self.layout {
+            ImportLayout::Wide => (0..table.width())
+                .filter(|&column| Some(column) != self.timestamp_column)
+                .filter_map(|column| {
+                    let values: Vec<RawValue> = table
+                        .sample(column)
+                        .filter_map(|cell| parse_number(cell, self.decimal_separator))
+                        .collect();
+                    // Text columns (comments, states) are not plotted
+                    if values.is_empty() {
+                        return None;
+                    }
+                    let (name, unit) = split_unit(&table.columns[column]);
+                    Some(ColumnMapping {
+                        source: table.columns[column].clone(),
+                        enabled: true,
+                        name,
+                        unit,
+                        var_type: guess_var_type(values.into_iter()),
+                    })
+                })
+                .collect(),
+            ImportLayout::Long {
+                name_column,
+                value_column,
+                raw_column,
+            } => {
+                let mut names: Vec<String> = Vec::new();
+                let mut values: HashMap<String, Vec<RawValue>> = HashMap::new();
+                for row in 0..table.rows.len() {
+                    let Some(name) = table.cell(row, name_column) else {
+                        continue;
+                    };
+                    if !values.contains_key(name) {
+                        names.push(name.to_string());
+                    }
+                    let samples = values.entry(name.to_string()).or_default();
+                    let cell = raw_column
+                        .and_then(|c| table.cell(row, c))
+                        .or_else(|| table.cell(row, value_column));
+                    if samples.len() < GUESS_ROWS {
+                        if let Some(value) =
+                            cell.and_then(|c| parse_number(c, self.decimal_separator))
+                        {
+                            samples.push(value);
+                        }
+                    }
+                }
+                names
+                    .into_iter()
+                    .map(|name| ColumnMapping {
+                        var_type: guess_var_type(
+                            values.remove(&name).unwrap_or_default().into_iter(),
+                        ),
+                        source: name.clone(),
+                        enabled: true,
+                        name,
+                        unit: String::new(),
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Time of a row in seconds
+    fn row_time(&self, table: &ImportTable, row: usize) -> Option<f64> {
+        match self.timestamp_column {
+            Some(column) => parse_timestamp(
+                table.cell(row, column)?,
+                self.timestamp_format,
+                self.decimal_separator,
+            ),
+            None => Some(row as f64 * self.row_interval.as_secs_f64()),
+        }
+    }
+
+    /// Build a recording from a table
+    ///
+    /// Rows without a parseable timestamp are skipped.
+    pub fn import(&self, table: &ImportTable, name: &str) -> io::Result<SessionRecording> {
+        let mut variables: Vec<(usize, Variable)> = Vec::new();
+        let mut by_source: HashMap<&str, u32> = HashMap::new();
+        for mapping in self.mappings.iter().filter(|m| m.enabled) {
+            let mut variable = Variable::new(mapping.name.clone(), 0, mapping.var_type);
+            variable.unit = mapping.unit.clone();
+            by_source.insert(&mapping.source, variable.id);
+            let column = table
+                .columns
+                .iter()
+                .position(|c| *c == mapping.source)
+                .unwrap_or(usize::MAX);
+            variables.push((column, variable));
+        }
+        if variables.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no columns selected for import",
+            ));
+        }
+
+        let times: Vec<Option<f64>> = (0..table.rows.len())
+            .map(|row| self.row_time(table, row))
+            .collect();
+        let Some(start) = times.iter().flatten().copied().reduce(f64::min) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no row has a valid timestamp",
+            ));
+        };
+
+        let mut frames: BTreeMap<u64, RecordedFrame> = BTreeMap::new();
+        let mut skipped = 0;
+        for (row, time) in times.iter().enumerate() {
+            let Some(time) = time else {
+                skipped += 1;
+                continue;
+            };
+            let offset = ((time - start) * 1e9).round() as u64;
+            let mut samples: Vec<(u32, RawValue)> = Vec::new();
+            match self.layout {
+                ImportLayout::Wide => {
+                    for (column, variable) in &variables {
+                        if let Some(value) = table
+                            .cell(row, *column)
+                            .and_then(|c| parse_number(c, self.decimal_separator))
+                        {
+                            samples.push((variable.id, value));
+                        }
+                    }
+                }
+                ImportLayout::Long {
+                    name_column,
+                    value_column,
+                    raw_column,
+                } => {
+                    let Some(id) = table
+                        .cell(row, name_column)
+                        .and_then(|name| by_source.get(name))
+                    else {
+                        continue;
+                    };
+                    let cell = raw_column
+                        .and_then(|c| table.cell(row, c))
+                        .or_else(|| table.cell(row, value_column));
+                    if let Some(value) = cell.and_then(|c| parse_number(c, self.decimal_separator))
+                    {
+                        samples.push((*id, value));
+                    }
+                }
+            }
+            if samples.is_empty() {
+                continue;
+            }
+            let frame = frames.entry(offset).or_insert_with(|| RecordedFrame {
+                timestamp: Duration::from_nanos(offset),
+                values: HashMap::new(),
+                tag: None,
+            });
+            for (id, raw_value) in samples {
+                frame.values.insert(
+                    id,
+                    RecordedValue {
+                        raw_value,
+                        converted_value: raw_value.as_f64(),
+                    },
+                );
+            }
+        }
+        if skipped > 0 {
+            tracing::warn!("Skipped {} rows without a valid timestamp", skipped);
+        }
+
+        let mut metadata = SessionMetadata::new(name);
+        if matches!(
+            self.timestamp_format,
+            TimestampFormat::Iso8601 | TimestampFormat::UnixTimestamp
+        ) && self.timestamp_column.is_some()
+        {
+            if let Some(recorded_at) = chrono::DateTime::from_timestamp(
+                start.floor() as i64,
+                ((start - start.floor()) * 1e9) as u32,
+            ) {
+                metadata.recorded_at = recorded_at;
+            }
+        }
+        if !self.row_interval.is_zero() && self.timestamp_column.is_none() {
+            metadata.poll_rate_hz = (1.0 / self.row_interval.as_secs_f64()).round() as u32;
+        }
+        metadata.variables = variables.into_iter().map(|(_, v)| v).collect();
+
+        let mut recording = SessionRecording::with_metadata(metadata);
+        recording.frames = frames.into_values().collect();
+        recording.finalize();
+        Ok(recording)
+    }
+}
+
+/// Split a `name [unit]` or `name (unit)` column header
+fn split_unit(header: &str) -> (String, String) {
+    for (open, close) in [('[', ']'), ('(', ')')] {
+        if let Some(inner) = header.trim().strip_suffix(close) {
+            if let Some((name, unit)) = inner.rsplit_once(open) {
+                if !name.trim().is_empty() {
+                    return (name.trim().to_string(), unit.trim().to_string());
+                }
+            }
+        }
+    }
+    (header.trim().to_string(), String::new())
+}
+
+/// Import a CSV or JSON Lines file with guessed options
+pub fn import_file(path: &Path) -> io::Result<SessionRecording> {
+    let text = std::fs::read_to_string(path)?;
+    let table = ImportTable::parse(&text, &ParseOptions::detect(path, &text))?;
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Imported Log".to_string());
+    ImportSettings::detect(&table).import(&table, &name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_csv_wide() {
+        let text = "Model,DSOX1204G\n\
+                    t (s);CH1 [V];CH2 [mA];Note\n\
+                    -0,0010;1,5;20;\"start; armed\"\n\
+                    -0,0005;1,6;21;\n\
+                    0,0000;;22;x\n";
+        let options = ParseOptions {
+            delimiter: ';',
+            skip_lines: 1,
+            ..Default::default()
+        };
+        let table = ImportTable::parse(text, &options).unwrap();
+        assert_eq!(table.columns, ["t (s)", "CH1 [V]", "CH2 [mA]", "Note"]);
+        assert_eq!(table.cell(0, 3), Some("start; armed"));
+
+        let mut settings = ImportSettings::detect(&table);
+        settings.decimal_separator = ',';
+        settings.mappings = settings.default_mappings(&table);
+        assert_eq!(settings.timestamp_column, Some(0));
+        assert_eq!(settings.timestamp_format, TimestampFormat::Seconds);
+        assert_eq!(settings.mappings.len(), 2);
+        assert_eq!(settings.mappings[0].name, "CH1");
+        assert_eq!(settings.mappings[0].unit, "V");
+        assert_eq!(settings.mappings[0].var_type, VariableType::F64);
+        assert_eq!(settings.mappings[1].var_type, VariableType::U64);
+
+        let recording = settings.import(&table, "scope").unwrap();
+        assert_eq!(recording.frame_count(), 3);
+        assert_eq!(recording.frames[0].timestamp, Duration::ZERO);
+        assert_eq!(recording.duration(), Duration::from_micros(1000));
+        let ch1 = recording.metadata.variables[0].id;
+        let ch2 = recording.metadata.variables[1].id;
+        assert_eq!(recording.frames[1].values[&ch1].converted_value, 1.6);
+        assert!(!recording.frames[2].values.contains_key(&ch1));
+        assert_eq!(
+            recording.frames[2].values[&ch2].raw_value,
+            RawValue::UInt(22)
+        );
+    }
+
+    #[test]
+    fn test_persistence_json_lines_long() {
+        let text = r#"{"timestamp_us":0,"variable_name":"speed","variable_id":3,"raw_value":18446744073709551615,"converted_value":1.5}
+{"timestamp_us":0,"variable_name":"temp","variable_id":4,"raw_value":-2,"converted_value":-1.0}
+{"timestamp_us":1500,"variable_name":"speed","variable_id":3,"raw_value":7,"converted_value":3.5}
+"#;
+        let path = Path::new("log.jsonl");
+        let options = ParseOptions::detect(path, text);
+        assert_eq!(options.format, ImportFileFormat::JsonLines);
+        let table = ImportTable::parse(text, &options).unwrap();
+
+        let settings = ImportSettings::detect(&table);
+        assert_eq!(settings.timestamp_format, TimestampFormat::Microseconds);
+        assert!(matches!(
+            settings.layout,
+            ImportLayout::Long {
+                raw_column: Some(_),
+                ..
+            }
+        ));
+        assert_eq!(settings.mappings.len(), 2);
+        assert_eq!(settings.mappings[0].var_type, VariableType::U64);
+        assert_eq!(settings.mappings[1].var_type, VariableType::I64);
+
+        let recording = settings.import(&table, "persisted").unwrap();
+        assert_eq!(recording.frame_count(), 2);
+        let speed = recording.metadata.variables[0].id;
+        let temp = recording.metadata.variables[1].id;
+        assert_eq!(recording.frames[0].values.len(), 2);
+        assert_eq!(
+            recording.frames[0].values[&speed].raw_value,
+            RawValue::UInt(u64::MAX)
+        );
+        assert_eq!(
+            recording.frames[0].values[&temp].raw_value,
+            RawValue::Int(-2)
+        );
+        assert_eq!(recording.frames[1].timestamp, Duration::from_micros(1500));
+    }
+
+    #[test]
+    fn test_timestamp_formats() {
+        let iso = parse_timestamp("2024-03-01T12:00:00.250Z", TimestampFormat::Iso8601, '.');
+        let naive = parse_timestamp("2024-03-01 12:00:00.5", TimestampFormat::Iso8601, '.');
+        assert_eq!(naive.unwrap() - iso.unwrap(), 0.25);
+        assert_eq!(
+            parse_timestamp("1500", TimestampFormat::Milliseconds, '.'),
+            Some(1.5)
+        );
+        assert_eq!(parse_timestamp("abc", TimestampFormat::Seconds, '.'), None);
+
+        assert_eq!(
+            guess_timestamp_format("time", &["2024-03-01 12:00:00"]),
+            TimestampFormat::Iso8601
+        );
+        assert_eq!(
+            guess_timestamp_format("epoch", &["1709294400.5"]),
+            TimestampFormat::UnixTimestamp
+        );
+        assert_eq!(
+            guess_timestamp_format("time_ms", &["10"]),
+            TimestampFormat::Milliseconds
+        );
+
+        // Absolute timestamps set the recording start
+        let text = "time,value\n2024-03-01T12:00:01Z,1\n2024-03-01T12:00:00Z,2\n";
+        let table = ImportTable::parse(text, &ParseOptions::default()).unwrap();
+        let recording = ImportSettings::detect(&table)
+            .import(&table, "absolute")
+            .unwrap();
+        assert_eq!(
+            recording.metadata.recorded_at.to_rfc3339(),
+            "2024-03-01T12:00:00+00:00"
+        );
+        assert_eq!(recording.frames[1].timestamp, Duration::from_secs(1));
+    }
+}
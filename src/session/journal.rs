@@ -0,0 +1,226 @@
+//! Crash-safe journal of a recording in progress
+//!
+//! A journal is a binary session file (see [`format`](super::format)) that a
+//! [`SessionRecorder`](super::SessionRecorder) writes frames to while it
+//! records. Buffered frames are written out as a block and synced to disk at
+//! every checkpoint, so a crash loses at most the frames since the last one.
+//! The journal is removed when the recording finishes normally.
+//!
+//! The recorder holds an exclusive lock on its journal. The lock goes away
+//! with the process, so a journal nobody holds is left over from a crash and
+//! can be recovered: the session format rebuilds the block index of a file
+//! that was never finished.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use super::format::{SessionFile, SessionWriter};
+use super::types::{RecordedFrame, SessionMetadata, SessionRecording};
+
+/// Time between checkpoints
+pub const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+const JOURNAL_PREFIX: &str = "journal-";
+const JOURNAL_EXTENSION: &str = "dvs";
+
+/// A session file written with periodic checkpoints while locked
+#[derive(Debug)]
+pub struct Journal {
+    writer: SessionWriter<BufWriter<File>>,
+    path: PathBuf,
+    last_checkpoint: Instant,
+}
+
+impl Journal {
+    /// Create a journal at `path` and lock it
+    pub fn create(path: &Path, metadata: &SessionMetadata) -> io::Result<Self> {
+        let file = File::create(path)?;
+        file.try_lock()?;
+        let writer = SessionWriter::new(BufWriter::new(file), metadata)?;
+        let mut journal = Self {
+            writer,
+            path: path.to_path_buf(),
+            last_checkpoint: Instant::now(),
+        };
+        journal.checkpoint()?;
+        Ok(journal)
+    }
+
+    /// Create a new journal in `dir`, named after the process and the time
+    pub fn create_in(dir: &Path, metadata: &SessionMetadata) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let stamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let name = format!(
+            "{}{}-{}.{}",
+            JOURNAL_PREFIX,
+            std::process::id(),
+            stamp,
+            JOURNAL_EXTENSION
+        );
+        Self::create(&dir.join(name), metadata)
+    }
+
+    /// Path of the journal file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of frames written (including ones not yet checkpointed)
+    pub fn frame_count(&self) -> usize {
+        self.writer.frame_count()
+    }
+
+    /// Append a frame, checkpointing when one is due
+    pub fn write_frame(&mut self, frame: RecordedFrame) -> io::Result<()> {
+        self.writer.write_frame(frame)?;
+        self.checkpoint_if_due()
+    }
+
+    /// Checkpoint if [`CHECKPOINT_INTERVAL`] has passed since the last one
+    pub fn checkpoint_if_due(&mut self) -> io::Result<()> {
+        if self.last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+
+    /// Write the buffered frames as a block and sync the file to disk
+    pub fn checkpoint(&mut self) -> io::Result<()> {
+        self.writer.flush_block()?;
+        let out = self.writer.get_mut();
+        out.flush()?;
+        out.get_ref().sync_data()?;
+        self.last_checkpoint = Instant::now();
+        Ok(())
+    }
+
+    /// Write the remaining frames and the final metadata, keeping the file
+    pub fn finish(self, metadata: &SessionMetadata) -> io::Result<()> {
+        self.writer.finish(metadata)?.get_ref().sync_data()
+    }
+
+    /// Delete the journal
+    pub fn remove(self) -> io::Result<()> {
+        let Self { writer, path, .. } = self;
+        drop(writer);
+        std::fs::remove_file(path)
+    }
+}
+
+fn is_journal(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy());
+    name.is_some_and(|n| n.starts_with(JOURNAL_PREFIX))
+        && path.extension().is_some_and(|ext| ext == JOURNAL_EXTENSION)
+}
+
+/// Creation time of a journal in milliseconds, from its name
+fn journal_stamp(path: &Path) -> Option<u128> {
+    let stem = path.file_stem()?.to_str()?;
+    stem.rsplit('-').next()?.parse().ok()
+}
+
+/// Journals in `dir` that no running recorder holds, oldest first
+pub fn orphaned_journals(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut journals: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_journal(path))
+        // The lock is released again when the file is closed
+        .filter(|path| File::open(path).is_ok_and(|file| file.try_lock().is_ok()))
+        .collect();
+    journals.sort_by_key(|path| (journal_stamp(path), path.clone()));
+    journals
+}
+
+/// Metadata of an orphaned journal, with the duration and size it reached
+pub fn journal_metadata(path: &Path) -> io::Result<SessionMetadata> {
+    Ok(SessionFile::open(path)?.metadata().clone())
+}
+
+/// Read an orphaned journal as a complete recording
+pub fn recover(path: &Path) -> io::Result<SessionRecording> {
+    let mut recording = SessionFile::open(path)?.read_all()?;
+    recording.metadata.name = format!("{} (recovered)", recording.metadata.name);
+    recording.finalize();
+    Ok(recording)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::types::RecordedValue;
+    use crate::types::RawValue;
+    use std::collections::HashMap;
+
+    fn frame(i: u64) -> RecordedFrame {
+        let mut values = HashMap::new();
+        values.insert(
+            1,
+            RecordedValue {
+                raw_value: RawValue::UInt(i),
+                converted_value: i as f64,
+            },
+        );
+        RecordedFrame {
+            timestamp: Duration::from_millis(i * 10),
+            values,
+            tag: None,
+        }
+    }
+
+    #[test]
+    fn test_orphaned_journal_is_recovered_up_to_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut journal = Journal::create_in(dir.path(), &SessionMetadata::new("Bench")).unwrap();
+        for i in 0..10 {
+            journal.write_frame(frame(i)).unwrap();
+        }
+        journal.checkpoint().unwrap();
+        for i in 10..15 {
+            journal.write_frame(frame(i)).unwrap();
+        }
+
+        // Held by this recorder, so not orphaned
+        assert!(orphaned_journals(dir.path()).is_empty());
+        let path = journal.path().to_path_buf();
+
+        // Crash: the frames after the checkpoint never reach the disk
+        drop(journal);
+        assert_eq!(orphaned_journals(dir.path()), std::slice::from_ref(&path));
+        assert_eq!(journal_metadata(&path).unwrap().total_data_points, 10);
+
+        let recording = recover(&path).unwrap();
+        assert_eq!(recording.metadata.name, "Bench (recovered)");
+        assert_eq!(recording.frame_count(), 10);
+        assert_eq!(recording.metadata.duration, Duration::from_millis(90));
+    }
+
+    #[test]
+    fn test_finished_journal_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = Journal::create_in(dir.path(), &SessionMetadata::new("Bench")).unwrap();
+        let path = journal.path().to_path_buf();
+        assert!(path.exists());
+        journal.remove().unwrap();
+        assert!(!path.exists());
+        assert!(orphaned_journals(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_orphaned_journals_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let newer = dir.path().join("journal-1-2000.dvs");
+        let older = dir.path().join("journal-2-1000.dvs");
+        File::create(&newer).unwrap();
+        File::create(&older).unwrap();
+        assert_eq!(orphaned_journals(dir.path()), vec![older, newer]);
+    }
+}
@@ -0,0 +1,655 @@
+//! MCAP export and import
+//!
+//! Sessions are written in the layout Foxglove and other MCAP tools expect:
+//!
+//! - one JSON-encoded channel per variable on `/variables/<name>`, whose
+//!   JSON schema and channel metadata carry the variable's name, unit, type
+//!   and address
+//! - frame tags as `foxglove.Log` messages on `/markers`
+//! - the [`SessionMetadata`] as a `datavis.session` metadata record, with
+//!   the recording's events as a JSON `events` entry
+//!
+//! Message log times are absolute (recording start plus frame offset), so
+//! files line up with other recordings of the same run.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, Write};
+use std::path::Path;
+
+use ::mcap::records::{MessageHeader, Metadata, Record};
+use ::mcap::write::{WriteOptions, Writer};
+use ::mcap::McapError;
+use serde_json::{json, Value};
+
+use crate::types::{RawValue, Variable, VariableType};
+
+use super::types::{frame_at, RecordedFrame, RecordedValue, SessionMetadata, SessionRecording};
+
+/// Magic bytes at the start of an MCAP file
+pub const MCAP_MAGIC: &[u8; 8] = b"\x89MCAP0\r\n";
+
+/// Topic of the marker (frame tag) channel
+pub const MARKER_TOPIC: &str = "/markers";
+
+/// Name of the metadata record holding the session metadata
+pub const METADATA_NAME: &str = "datavis.session";
+
+const VARIABLE_TOPIC_PREFIX: &str = "/variables/";
+const VARIABLE_SCHEMA_NAME: &str = "datavis.Variable";
+const LOG_SCHEMA_NAME: &str = "foxglove.Log";
+/// `foxglove.Log` level for informational messages
+const LOG_LEVEL_INFO: u8 = 2;
+
+fn mcap_error(e: McapError) -> io::Error {
+    match e {
+        McapError::Io(e) => e,
+        e => io::Error::new(io::ErrorKind::InvalidData, e),
+    }
+}
+
+fn json_error(e: serde_json::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Check whether a file starts with the MCAP magic
+pub fn is_mcap_file(path: &Path) -> io::Result<bool> {
+    use std::io::Read;
+
+    let mut magic = [0u8; 8];
+    match File::open(path)?.read_exact(&mut magic) {
+        Ok(()) => Ok(&magic == MCAP_MAGIC),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Foxglove `Time` JSON object for a nanosecond timestamp
+fn time_json(nanos: u64) -> Value {
+    json!({
+        "sec": nanos / 1_000_000_000,
+        "nsec": nanos % 1_000_000_000,
+    })
+}
+
+/// JSON schema of a variable channel
+///
+/// The `datavis` keyword is ignored by JSON schema consumers and lets the
+/// variable definition be restored on import.
+fn variable_schema(variable: &Variable) -> Value {
+    let unit = if variable.unit.is_empty() {
+        "Converted value".to_string()
+    } else {
+        format!("Converted value ({})", variable.unit)
+    };
+    json!({
+        "title": variable.name,
+        "type": "object",
+        "properties": {
+            "timestamp": {
+                "type": "object",
+                "properties": {
+                    "sec": { "type": "integer" },
+                    "nsec": { "type": "integer" },
+                },
+            },
+            "raw": { "type": "number", "description": "Raw value read from memory" },
+            "value": { "type": "number", "description": unit },
+        },
+        "datavis": {
+            "id": variable.id,
+            "name": variable.name,
+            "unit": variable.unit,
+            "type": variable.var_type,
+            "address": variable.address,
+        },
+    })
+}
+
+/// JSON schema of `foxglove.Log`, used for markers
+fn log_schema() -> Value {
+    json!({
+        "title": LOG_SCHEMA_NAME,
+        "type": "object",
+        "properties": {
+            "timestamp": {
+                "type": "object",
+                "properties": {
+                    "sec": { "type": "integer" },
+                    "nsec": { "type": "integer" },
+                },
+            },
+            "level": { "type": "integer" },
+            "message": { "type": "string" },
+            "name": { "type": "string" },
+            "file": { "type": "string" },
+            "line": { "type": "integer" },
+        },
+    })
+}
+
+/// Channel metadata of a variable channel
+fn variable_channel_metadata(variable: &Variable) -> io::Result<BTreeMap<String, String>> {
+    let mut metadata = BTreeMap::new();
+    metadata.insert("id".to_string(), variable.id.to_string());
+    metadata.insert("name".to_string(), variable.name.clone());
+    metadata.insert("unit".to_string(), variable.unit.clone());
+    metadata.insert(
+        "type".to_string(),
+        serde_json::to_string(&variable.var_type).map_err(json_error)?,
+    );
+    metadata.insert("address".to_string(), format!("0x{:X}", variable.address));
+    Ok(metadata)
+}
+
+/// Metadata record contents for a session
+fn session_metadata_map(metadata: &SessionMetadata, start_nanos: u64) -> BTreeMap<String, String> {
+    let mut map = BTreeMap::new();
+    map.insert("name".to_string(), metadata.name.clone());
+    if let Some(description) = &metadata.description {
+        map.insert("description".to_string(), description.clone());
+    }
+    map.insert("recorded_at".to_string(), metadata.recorded_at.to_rfc3339());
+    map.insert("start_time_ns".to_string(), start_nanos.to_string());
+    map.insert(
+        "duration_ns".to_string(),
+        (metadata.duration.as_nanos() as u64).to_string(),
+    );
+    map.insert(
+        "poll_rate_hz".to_string(),
+        metadata.poll_rate_hz.to_string(),
+    );
+    if let Some(target) = &metadata.target_name {
+        map.insert("target_name".to_string(), target.clone());
+    }
+    if let Some(elf) = &metadata.elf_path {
+        map.insert("elf_path".to_string(), elf.clone());
+    }
+    map.insert(
+        "total_data_points".to_string(),
+        metadata.total_data_points.to_string(),
+    );
+    if !metadata.events.is_empty() {
+        // Serializing plain data to JSON cannot fail
+        if let Ok(events) = serde_json::to_string(&metadata.events) {
+            map.insert("events".to_string(), events);
+        }
+    }
+    map
+}
+
+/// Apply a `datavis.session` metadata record to session metadata
+fn apply_metadata_map(metadata: &mut SessionMetadata, map: &BTreeMap<String, String>) {
+    if let Some(name) = map.get("name") {
+        metadata.name = name.clone();
+    }
+    metadata.description = map.get("description").cloned();
+    if let Some(recorded_at) = map
+        .get("recorded_at")
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+    {
+        metadata.recorded_at = recorded_at.with_timezone(&chrono::Utc);
+    }
+    if let Some(rate) = map.get("poll_rate_hz").and_then(|s| s.parse().ok()) {
+        metadata.poll_rate_hz = rate;
+    }
+    metadata.target_name = map.get("target_name").cloned();
+    metadata.elf_path = map.get("elf_path").cloned();
+    if let Some(events) = map.get("events").and_then(|s| serde_json::from_str(s).ok()) {
+        metadata.events = events;
+    }
+}
+
+/// Streaming MCAP writer for session frames
+///
+/// Channels are added as variables first appear, so frames holding
+/// variables missing from the metadata are still written.
+pub struct McapSessionWriter<W: Write + Seek> {
+    writer: Writer<W>,
+    /// Absolute time of the recording start (nanoseconds since the epoch)
+    start_nanos: u64,
+    variables: HashMap<u32, Variable>,
+    channels: HashMap<u32, u16>,
+    marker_channel: Option<u16>,
+    sequence: u32,
+    frame_count: usize,
+}
+
+impl<W: Write + Seek> std::fmt::Debug for McapSessionWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("McapSessionWriter")
+            .field("start_nanos", &self.start_nanos)
+            .field("channels", &self.channels)
+            .field("frame_count", &self.frame_count)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: Write + Seek> McapSessionWriter<W> {
+    /// Start an MCAP file for a session
+    pub fn new(writer: W, metadata: &SessionMetadata) -> io::Result<Self> {
+        let writer = WriteOptions::new()
+            .library(concat!("datavis-rs ", env!("CARGO_PKG_VERSION")))
+            .create(writer)
+            .map_err(mcap_error)?;
+        Ok(Self {
+            writer,
+            start_nanos: metadata
+                .recorded_at
+                .timestamp_nanos_opt()
+                .unwrap_or_default()
+                .max(0) as u64,
+            variables: metadata
+                .variables
+                .iter()
+                .map(|v| (v.id, v.clone()))
+                .collect(),
+            channels: HashMap::new(),
+            marker_channel: None,
+            sequence: 0,
+            frame_count: 0,
+        })
+    }
+
+    /// Number of frames written
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    fn variable_channel(&mut self, var_id: u32) -> io::Result<u16> {
+        if let Some(&channel) = self.channels.get(&var_id) {
+            return Ok(channel);
+        }
+        let variable = self.variables.get(&var_id).cloned().unwrap_or_else(|| {
+            let mut variable = Variable::new(format!("var_{}", var_id), 0, VariableType::F64);
+            variable.id = var_id;
+            variable
+        });
+        let schema = serde_json::to_vec(&variable_schema(&variable)).map_err(json_error)?;
+        let schema_id = self
+            .writer
+            .add_schema(
+                &format!("{}.{}", VARIABLE_SCHEMA_NAME, variable.name),
+                "jsonschema",
+                &schema,
+            )
+            .map_err(mcap_error)?;
+        let channel = self
+            .writer
+            .add_channel(
+                schema_id,
+                &format!("{}{}", VARIABLE_TOPIC_PREFIX, variable.name),
+                "json",
+                &variable_channel_metadata(&variable)?,
+            )
+            .map_err(mcap_error)?;
+        self.channels.insert(var_id, channel);
+        Ok(channel)
+    }
+
+    fn marker_channel(&mut self) -> io::Result<u16> {
+        if let Some(channel) = self.marker_channel {
+            return Ok(channel);
+        }
+        let schema = serde_json::to_vec(&log_schema()).map_err(json_error)?;
+        let schema_id = self
+            .writer
+            .add_schema(LOG_SCHEMA_NAME, "jsonschema", &schema)
+            .map_err(mcap_error)?;
+        let channel = self
+            .writer
+            .add_channel(schema_id, MARKER_TOPIC, "json", &BTreeMap::new())
+            .map_err(mcap_error)?;
+        self.marker_channel = Some(channel);
+        Ok(channel)
+    }
+
+    fn write_message(&mut self, channel_id: u16, log_time: u64, data: &Value) -> io::Result<()> {
+        let data = serde_json::to_vec(data).map_err(json_error)?;
+        self.writer
+            .write_to_known_channel(
+                &MessageHeader {
+                    channel_id,
+                    sequence: self.sequence,
+                    log_time,
+                    publish_time: log_time,
+                },
+                &data,
+            )
+            .map_err(mcap_error)?;
+        self.sequence = self.sequence.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Write one frame: a message per value plus a marker for its tag
+    pub fn write_frame(&mut self, frame: &RecordedFrame) -> io::Result<()> {
+        let log_time = self.start_nanos + frame.timestamp.as_nanos() as u64;
+        let timestamp = time_json(log_time);
+
+        // Sorted so files are reproducible
+        let mut var_ids: Vec<u32> = frame.values.keys().copied().collect();
+        var_ids.sort_unstable();
+        for var_id in var_ids {
+            let value = &frame.values[&var_id];
+            let channel = self.variable_channel(var_id)?;
+            let message = json!({
+                "timestamp": timestamp,
+                "raw": value.raw_value,
+                "value": value.converted_value,
+            });
+            self.write_message(channel, log_time, &message)?;
+        }
+
+        if let Some(tag) = &frame.tag {
+            let channel = self.marker_channel()?;
+            let message = json!({
+                "timestamp": timestamp,
+                "level": LOG_LEVEL_INFO,
+                "message": tag,
+                "name": "marker",
+                "file": "",
+                "line": 0,
+            });
+            self.write_message(channel, log_time, &message)?;
+        }
+
+        self.frame_count += 1;
+        Ok(())
+    }
+
+    /// Write the session metadata and the summary section
+    pub fn finish(mut self, metadata: &SessionMetadata) -> io::Result<W> {
+        self.writer
+            .write_metadata(&Metadata {
+                name: METADATA_NAME.to_string(),
+                metadata: session_metadata_map(metadata, self.start_nanos),
+            })
+            .map_err(mcap_error)?;
+        self.writer.finish().map_err(mcap_error)?;
+        Ok(self.writer.into_inner())
+    }
+}
+
+/// Write a recording as an MCAP file
+pub fn write_mcap(recording: &SessionRecording, path: &Path) -> io::Result<()> {
+    let file = File::create(path)?;
+    let mut writer = McapSessionWriter::new(BufWriter::new(file), &recording.metadata)?;
+    for frame in &recording.frames {
+        writer.write_frame(frame)?;
+    }
+    writer.finish(&recording.metadata)?.flush()
+}
+
+/// Variable restored from a channel's metadata or schema
+fn channel_variable(
+    topic: &str,
+    metadata: &BTreeMap<String, String>,
+    schema: Option<&Value>,
+    fallback_id: u32,
+) -> Variable {
+    let info = schema.and_then(|s| s.get("datavis"));
+    let field = |key: &str| -> Option<String> {
+        metadata.get(key).cloned().or_else(|| {
+            info.and_then(|i| i.get(key)).map(|v| match v {
+                Value::String(s) => s.clone(),
+                v => v.to_string(),
+            })
+        })
+    };
+
+    let name = field("name").unwrap_or_else(|| {
+        topic
+            .strip_prefix(VARIABLE_TOPIC_PREFIX)
+            .unwrap_or(topic)
+            .to_string()
+    });
+    let var_type = field("type")
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(VariableType::F64);
+    let address = field("address")
+        .and_then(|s| match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => s.parse().ok(),
+        })
+        .unwrap_or(0);
+
+    let mut variable = Variable::new(name, address, var_type);
+    variable.id = field("id")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(fallback_id);
+    variable.unit = field("unit").unwrap_or_default();
+    variable
+}
+
+/// Value of a message, from our `raw`/`value` layout or a plain `value` or
+/// `data` field written by other tools
+fn message_value(message: &Value) -> Option<RecordedValue> {
+    let converted = message.get("value").or_else(|| message.get("data"))?;
+    let converted_value = match converted {
+        Value::Number(n) => n.as_f64()?,
+        Value::Bool(b) => f64::from(u8::from(*b)),
+        // NaN and infinities are written as null
+        Value::Null => f64::NAN,
+        _ => return None,
+    };
+    let raw_value = message
+        .get("raw")
+        .and_then(|raw| serde_json::from_value::<RawValue>(raw.clone()).ok())
+        .unwrap_or(RawValue::Float(converted_value));
+    Some(RecordedValue {
+        raw_value,
+        converted_value,
+    })
+}
+
+/// Read an MCAP file into a recording
+///
+/// Channels carrying numeric JSON messages become variables, `foxglove.Log`
+/// messages on `/markers` become frame tags and messages sharing a log time
+/// are merged into one frame.
+pub fn read_mcap(path: &Path) -> io::Result<SessionRecording> {
+    let bytes = std::fs::read(path)?;
+
+    let mut schemas: HashMap<u16, (String, Option<Value>)> = HashMap::new();
+    let mut channels: HashMap<u16, ::mcap::records::Channel> = HashMap::new();
+    let mut variables: HashMap<u16, Variable> = HashMap::new();
+    let mut messages: Vec<(u16, u64, Vec<u8>)> = Vec::new();
+    let mut session_metadata: Option<BTreeMap<String, String>> = None;
+
+    for record in ::mcap::read::ChunkFlattener::new(&bytes).map_err(mcap_error)? {
+        match record.map_err(mcap_error)? {
+            Record::Schema { header, data } => {
+                let schema = (header.encoding == "jsonschema")
+                    .then(|| serde_json::from_slice(&data).ok())
+                    .flatten();
+                schemas.insert(header.id, (header.name, schema));
+            }
+            Record::Channel(channel) => {
+                channels.insert(channel.id, channel);
+            }
+            Record::Message { header, data } => {
+                messages.push((header.channel_id, header.log_time, data.into_owned()));
+            }
+            Record::Metadata(metadata) if metadata.name == METADATA_NAME => {
+                session_metadata = Some(metadata.metadata);
+            }
+            _ => {}
+        }
+    }
+
+    let start_nanos = session_metadata
+        .as_ref()
+        .and_then(|m| m.get("start_time_ns"))
+        .and_then(|s| s.parse().ok())
+        .or_else(|| messages.iter().map(|(_, t, _)| *t).min())
+        .unwrap_or(0);
+
+    let mut frames: BTreeMap<u64, RecordedFrame> = BTreeMap::new();
+    let mut next_id = 1;
+    for (channel_id, log_time, data) in messages {
+        let Some(channel) = channels.get(&channel_id) else {
+            continue;
+        };
+        if channel.message_encoding != "json" {
+            continue;
+        }
+        let Ok(message) = serde_json::from_slice::<Value>(&data) else {
+            continue;
+        };
+        let (schema_name, schema) = schemas
+            .get(&channel.schema_id)
+            .map(|(name, schema)| (name.as_str(), schema.as_ref()))
+            .unwrap_or_default();
+        let offset = log_time.saturating_sub(start_nanos);
+
+        if channel.topic == MARKER_TOPIC || schema_name == LOG_SCHEMA_NAME {
+            if let Some(text) = message.get("message").and_then(Value::as_str) {
+                let frame = frame_at(&mut frames, offset);
+                frame.tag = Some(match frame.tag.take() {
+                    Some(existing) => format!("{}; {}", existing, text),
+                    None => text.to_string(),
+                });
+            }
+            continue;
+        }
+
+        let Some(value) = message_value(&message) else {
+            continue;
+        };
+        let var_id = variables
+            .entry(channel_id)
+            .or_insert_with(|| {
+                let variable = channel_variable(&channel.topic, &channel.metadata, schema, next_id);
+                next_id = next_id.max(variable.id) + 1;
+                variable
+            })
+            .id;
+        frame_at(&mut frames, offset).values.insert(var_id, value);
+    }
+
+    let mut metadata = SessionMetadata::new(
+        path.file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "MCAP Import".to_string()),
+    );
+    if let Some(map) = &session_metadata {
+        apply_metadata_map(&mut metadata, map);
+    } else if let Some(start) = chrono::DateTime::from_timestamp(
+        (start_nanos / 1_000_000_000) as i64,
+        (start_nanos % 1_000_000_000) as u32,
+    ) {
+        metadata.recorded_at = start;
+    }
+    let mut variables: Vec<Variable> = variables.into_values().collect();
+    variables.sort_by_key(|v| v.id);
+    metadata.variables = variables;
+
+    let mut recording = SessionRecording::with_metadata(metadata);
+    recording.frames = frames.into_values().collect();
+    recording.finalize();
+    Ok(recording)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::events::{MarkerType, RecordingEvent};
+    use crate::session::types::RecordingBuilder;
+    use std::time::Duration;
+
+    fn test_recording() -> SessionRecording {
+        let speed = Variable::new("motor.speed", 0x2000_0010, VariableType::I32).with_unit("rpm");
+        let count = Variable::new("count", 0x2000_0020, VariableType::U64);
+        let mut metadata = SessionMetadata::new("Bench run").with_description("MCAP test");
+        metadata.variables = vec![speed.clone(), count.clone()];
+        metadata.events = vec![
+            RecordingEvent::new(Duration::from_millis(25), MarkerType::RegionStart, "load"),
+            RecordingEvent::new(Duration::from_millis(150), MarkerType::Note, "fan")
+                .with_note("duty raised"),
+        ];
+
+        let mut builder = RecordingBuilder::new(metadata);
+        for i in 0..20u64 {
+            let mut values = vec![(
+                speed.id,
+                RawValue::from_i64(i as i64 - 10),
+                (i as f64 - 10.0) * 0.5,
+            )];
+            if i % 2 == 0 {
+                values.push((count.id, RawValue::UInt(u64::MAX - i), f64::NAN));
+            }
+            let tag = (i == 7).then_some("fault injected");
+            builder.frame(Duration::from_millis(i * 10), &values, tag);
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_mcap_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.mcap");
+        let original = test_recording();
+        write_mcap(&original, &path).unwrap();
+        assert!(is_mcap_file(&path).unwrap());
+
+        let loaded = read_mcap(&path).unwrap();
+        assert_eq!(loaded.metadata.name, "Bench run");
+        assert_eq!(loaded.metadata.description.as_deref(), Some("MCAP test"));
+        assert_eq!(loaded.metadata.recorded_at, original.metadata.recorded_at);
+        assert_eq!(loaded.metadata.events, original.metadata.events);
+        assert_eq!(loaded.frame_count(), 20);
+        assert_eq!(loaded.duration(), original.duration());
+
+        let speed = &original.metadata.variables[0];
+        let restored = loaded
+            .metadata
+            .variables
+            .iter()
+            .find(|v| v.id == speed.id)
+            .unwrap();
+        assert_eq!(restored.name, "motor.speed");
+        assert_eq!(restored.unit, "rpm");
+        assert_eq!(restored.var_type, VariableType::I32);
+        assert_eq!(restored.address, 0x2000_0010);
+
+        for (a, b) in original.frames.iter().zip(&loaded.frames) {
+            assert_eq!(a.timestamp, b.timestamp);
+            assert_eq!(a.tag, b.tag);
+            for (id, value) in &a.values {
+                assert_eq!(b.values[id].raw_value, value.raw_value);
+                if value.converted_value.is_nan() {
+                    assert!(b.values[id].converted_value.is_nan());
+                } else {
+                    assert_eq!(b.values[id].converted_value, value.converted_value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unknown_variables_get_channels() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot.mcap");
+        let mut recording = SessionRecording::with_metadata(SessionMetadata::new("Snapshots"));
+        let mut values = HashMap::new();
+        values.insert(
+            42,
+            RecordedValue {
+                raw_value: RawValue::Float(1.5),
+                converted_value: 1.5,
+            },
+        );
+        recording.frames.push(RecordedFrame {
+            timestamp: Duration::ZERO,
+            values,
+            tag: None,
+        });
+        write_mcap(&recording, &path).unwrap();
+
+        let loaded = read_mcap(&path).unwrap();
+        assert_eq!(loaded.metadata.variables.len(), 1);
+        assert_eq!(loaded.metadata.variables[0].id, 42);
+        assert_eq!(loaded.metadata.variables[0].name, "var_42");
+        assert_eq!(loaded.frames[0].values[&42].converted_value, 1.5);
+    }
+}
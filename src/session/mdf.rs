@@ -0,0 +1,1820 @@
+//! ASAM MDF4 export and import
+//!
+//! Recordings are written as MDF 4.10 files for calibration and measurement
+//! tools:
+//!
+//! - one data group per poll rate, each holding a single channel group with
+//!   a time master channel and one channel per variable sampled at that rate
+//! - raw values stored in the variable's own type, with a linear channel
+//!   conversion when the converted values are a linear function of the raw
+//!   values (variables without a converter get no conversion)
+//! - variables whose converter is not linear store the converted value, with
+//!   the raw value in a second `<name>.raw` channel
+//! - units, and channel comments carrying the address, type and converter
+//! - frame tags as marker events, and recording events as marker events
+//!   whose comment carries the event type, note and colour
+//!
+//! The linear conversion is fitted to the recorded samples rather than read
+//! from the converter script, so any converter that behaved linearly over
+//! the recording exports as one. Rows in which a variable has no sample are
+//! flagged with invalidation bits.
+//!
+//! Import reads sorted and unsorted files with plain, list and compressed
+//! data blocks, integer and floating-point channels, and identity, linear,
+//! rational and value table conversions.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::types::{RawValue, Variable, VariableType};
+
+use super::events::{sort_events, MarkerType, RecordingEvent};
+use super::types::{frame_at, RecordedFrame, RecordedValue, SessionMetadata, SessionRecording};
+
+/// File identifier at the start of an MDF file
+pub const MDF_MAGIC: &[u8; 8] = b"MDF     ";
+
+const MDF_VERSION: &[u8; 8] = b"4.10    ";
+const MDF_VERSION_NUMBER: u16 = 410;
+const PROGRAM_ID: &[u8; 8] = b"datavis ";
+/// The header block always follows the 64 byte identification block
+const HEADER_OFFSET: u64 = 64;
+/// Size of a block header: id, reserved, length and link count
+const BLOCK_HEADER_LEN: usize = 24;
+
+/// Suffix of the channel holding the raw values of a non-linear variable
+pub const RAW_CHANNEL_SUFFIX: &str = ".raw";
+
+// Channel types
+const CN_TYPE_FIXED: u8 = 0;
+const CN_TYPE_MASTER: u8 = 2;
+const CN_TYPE_VIRTUAL_MASTER: u8 = 3;
+const CN_TYPE_SYNC: u8 = 4;
+const CN_SYNC_TIME: u8 = 1;
+
+// Channel data types
+const DT_UINT_LE: u8 = 0;
+const DT_UINT_BE: u8 = 1;
+const DT_INT_LE: u8 = 2;
+const DT_INT_BE: u8 = 3;
+const DT_FLOAT_LE: u8 = 4;
+const DT_FLOAT_BE: u8 = 5;
+
+// Channel flags
+const CN_FLAG_ALL_INVALID: u32 = 0x01;
+const CN_FLAG_INVAL_BIT: u32 = 0x02;
+
+// Conversion types
+const CC_LINEAR: u8 = 1;
+const CC_RATIONAL: u8 = 2;
+const CC_TABLE_INTERP: u8 = 4;
+const CC_TABLE: u8 = 5;
+
+// Event types and causes
+const EV_TRIGGER: u8 = 5;
+const EV_MARKER: u8 = 6;
+const EV_CAUSE_USER: u8 = 4;
+const EV_RANGE_POINT: u8 = 0;
+const EV_RANGE_BEGIN: u8 = 1;
+const EV_RANGE_END: u8 = 2;
+
+/// Channel group flag marking variable length signal data records
+const CG_FLAG_VLSD: u16 = 0x01;
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Check whether a file starts with the MDF4 identification block
+pub fn is_mdf_file(path: &Path) -> io::Result<bool> {
+    let mut id = [0u8; 16];
+    match File::open(path)?.read_exact(&mut id) {
+        // The format identifier is followed by the version, e.g. "4.10    "
+        Ok(()) => Ok(&id[..8] == MDF_MAGIC && id[8] == b'4'),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// XML comments
+// ---------------------------------------------------------------------------
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Comment XML with a `<TX>` text and `<common_properties>` entries
+fn comment_xml(root: &str, text: &str, properties: &[(&str, String)]) -> String {
+    let mut xml = format!("<{}>\n<TX>{}</TX>\n", root, xml_escape(text));
+    if !properties.is_empty() {
+        xml.push_str("<common_properties>\n");
+        for (name, value) in properties {
+            xml.push_str(&format!(
+                "<e name=\"{}\">{}</e>\n",
+                xml_escape(name),
+                xml_escape(value)
+            ));
+        }
+        xml.push_str("</common_properties>\n");
+    }
+    xml.push_str(&format!("</{}>", root));
+    xml
+}
+
+/// Contents of the first `<tag>` element
+fn xml_element(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut search = 0;
+    while let Some(found) = xml[search..].find(&open) {
+        let start = search + found + open.len();
+        // Skip longer tag names sharing the prefix
+        match xml[start..].chars().next() {
+            Some('>') | Some(' ') | Some('\t') | Some('\n') | Some('\r') => {}
+            Some('/') => return Some(String::new()),
+            _ => {
+                search = start;
+                continue;
+            }
+        }
+        let content = start + xml[start..].find('>')? + 1;
+        let end = content + xml[content..].find(&close)?;
+        return Some(xml_unescape(xml[content..end].trim()));
+    }
+    None
+}
+
+/// `<e name="...">value</e>` entries of a comment's common properties
+fn xml_properties(xml: &str) -> HashMap<String, String> {
+    let mut properties = HashMap::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("<e ") {
+        rest = &rest[start + 3..];
+        let Some(tag_end) = rest.find('>') else {
+            break;
+        };
+        let attributes = &rest[..tag_end];
+        let name = attributes
+            .split_once("name=\"")
+            .and_then(|(_, s)| s.split_once('"'))
+            .map(|(name, _)| xml_unescape(name));
+        rest = &rest[tag_end + 1..];
+        if attributes.ends_with('/') {
+            continue;
+        }
+        let Some(end) = rest.find("</e>") else {
+            break;
+        };
+        if let Some(name) = name {
+            properties.insert(name, xml_unescape(&rest[..end]));
+        }
+        rest = &rest[end + 4..];
+    }
+    properties
+}
+
+// ---------------------------------------------------------------------------
+// Export
+// ---------------------------------------------------------------------------
+
+/// Appends blocks to an in-memory MDF file
+struct BlockWriter {
+    bytes: Vec<u8>,
+}
+
+impl BlockWriter {
+    fn new() -> Self {
+        let mut bytes = Vec::with_capacity(4096);
+        bytes.extend_from_slice(MDF_MAGIC);
+        bytes.extend_from_slice(MDF_VERSION);
+        bytes.extend_from_slice(PROGRAM_ID);
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&MDF_VERSION_NUMBER.to_le_bytes());
+        bytes.extend_from_slice(&[0; 30]);
+        // Finalized: no unfinished flags
+        bytes.extend_from_slice(&[0; 4]);
+        debug_assert_eq!(bytes.len() as u64, HEADER_OFFSET);
+        Self { bytes }
+    }
+
+    /// Append a block and return its offset
+    fn block(&mut self, id: &[u8; 2], links: &[u64], data: &[u8]) -> u64 {
+        let offset = self.bytes.len() as u64;
+        let length = BLOCK_HEADER_LEN + links.len() * 8 + data.len();
+        self.bytes.extend_from_slice(b"##");
+        self.bytes.extend_from_slice(id);
+        self.bytes.extend_from_slice(&[0; 4]);
+        self.bytes.extend_from_slice(&(length as u64).to_le_bytes());
+        self.bytes
+            .extend_from_slice(&(links.len() as u64).to_le_bytes());
+        for link in links {
+            self.bytes.extend_from_slice(&link.to_le_bytes());
+        }
+        self.bytes.extend_from_slice(data);
+        // Blocks start on 8 byte boundaries
+        self.bytes.resize(self.bytes.len().next_multiple_of(8), 0);
+        offset
+    }
+
+    fn set_link(&mut self, block: u64, index: usize, target: u64) {
+        let at = block as usize + BLOCK_HEADER_LEN + index * 8;
+        self.bytes[at..at + 8].copy_from_slice(&target.to_le_bytes());
+    }
+
+    /// Zero-terminated, padded string data of a TX or MD block
+    fn string_data(text: &str) -> Vec<u8> {
+        let mut data = text.as_bytes().to_vec();
+        data.push(0);
+        data.resize(data.len().next_multiple_of(8), 0);
+        data
+    }
+
+    /// TX block, or a nil link for empty text
+    fn text(&mut self, text: &str) -> u64 {
+        if text.is_empty() {
+            return 0;
+        }
+        self.block(b"TX", &[], &Self::string_data(text))
+    }
+
+    fn xml(&mut self, xml: &str) -> u64 {
+        self.block(b"MD", &[], &Self::string_data(xml))
+    }
+
+    /// CC block of a linear conversion `offset + factor * raw`
+    fn linear_conversion(&mut self, offset: f64, factor: f64) -> u64 {
+        let mut data = Vec::with_capacity(40);
+        data.push(CC_LINEAR);
+        data.push(0); // precision
+        data.extend_from_slice(&0u16.to_le_bytes()); // flags
+        data.extend_from_slice(&0u16.to_le_bytes()); // ref count
+        data.extend_from_slice(&2u16.to_le_bytes()); // value count
+        data.extend_from_slice(&0f64.to_le_bytes()); // physical range
+        data.extend_from_slice(&0f64.to_le_bytes());
+        data.extend_from_slice(&offset.to_le_bytes());
+        data.extend_from_slice(&factor.to_le_bytes());
+        self.block(b"CC", &[0, 0, 0, 0], &data)
+    }
+}
+
+/// Binary encoding of a channel's values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    UInt(u8),
+    Int(u8),
+    Float(u8),
+}
+
+impl Encoding {
+    /// Encoding of a variable's raw values
+    ///
+    /// Integer variables whose recorded raw values are floats (recordings
+    /// made before raw values were exact) fall back to `f64`.
+    fn of(var_type: VariableType, samples: &[&RecordedValue]) -> Self {
+        let bits = (var_type.size_bytes().clamp(1, 8) * 8) as u8;
+        let declared = match var_type {
+            VariableType::U8
+            | VariableType::U16
+            | VariableType::U32
+            | VariableType::U64
+            | VariableType::Bool => Encoding::UInt(bits),
+            VariableType::I8 | VariableType::I16 | VariableType::I32 | VariableType::I64 => {
+                Encoding::Int(bits)
+            }
+            VariableType::F32 => Encoding::Float(32),
+            VariableType::F64
+            | VariableType::F16
+            | VariableType::BF16
+            | VariableType::Fixed { .. } => Encoding::Float(64),
+            VariableType::Raw(_) | VariableType::Text(_) => Encoding::UInt(64),
+        };
+        let has_float = samples.iter().any(|s| !s.raw_value.is_integer());
+        let has_negative = samples
+            .iter()
+            .any(|s| matches!(s.raw_value, RawValue::Int(_)));
+        match declared {
+            Encoding::UInt(_) | Encoding::Int(_) if has_float => Encoding::Float(64),
+            Encoding::UInt(_) if has_negative => Encoding::Int(64),
+            encoding => encoding,
+        }
+    }
+
+    fn data_type(self) -> u8 {
+        match self {
+            Encoding::UInt(_) => DT_UINT_LE,
+            Encoding::Int(_) => DT_INT_LE,
+            Encoding::Float(_) => DT_FLOAT_LE,
+        }
+    }
+
+    fn bits(self) -> u8 {
+        match self {
+            Encoding::UInt(bits) | Encoding::Int(bits) | Encoding::Float(bits) => bits,
+        }
+    }
+
+    fn byte_len(self) -> usize {
+        self.bits() as usize / 8
+    }
+
+    fn write(self, value: RawValue, out: &mut [u8]) {
+        match self {
+            Encoding::UInt(_) | Encoding::Int(_) => {
+                let bytes = value.as_i64_bits().to_le_bytes();
+                out.copy_from_slice(&bytes[..out.len()]);
+            }
+            Encoding::Float(32) => out.copy_from_slice(&(value.as_f64() as f32).to_le_bytes()),
+            Encoding::Float(_) => out.copy_from_slice(&value.as_f64().to_le_bytes()),
+        }
+    }
+}
+
+/// How the values of a variable are stored
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Storage {
+    /// Raw values, equal to the converted values
+    Identity,
+    /// Raw values with the conversion `offset + factor * raw`
+    Linear { offset: f64, factor: f64 },
+    /// Converted values, with the raw values in a second channel
+    Physical,
+}
+
+impl Storage {
+    /// Pick the storage reproducing every recorded sample
+    fn fit(samples: &[&RecordedValue]) -> Self {
+        let same = |a: f64, b: f64| a == b || (a.is_nan() && b.is_nan());
+        if samples
+            .iter()
+            .all(|s| same(s.raw_value.as_f64(), s.converted_value))
+        {
+            return Storage::Identity;
+        }
+
+        let points: Vec<(f64, f64)> = samples
+            .iter()
+            .map(|s| (s.raw_value.as_f64(), s.converted_value))
+            .collect();
+        if points.iter().any(|(r, c)| !r.is_finite() || !c.is_finite()) {
+            return Storage::Physical;
+        }
+        // Fit through the extreme raw values for the best precision
+        let (Some(low), Some(high)) = (
+            points.iter().min_by(|a, b| a.0.total_cmp(&b.0)),
+            points.iter().max_by(|a, b| a.0.total_cmp(&b.0)),
+        ) else {
+            return Storage::Physical;
+        };
+        if low.0 == high.0 {
+            return Storage::Physical;
+        }
+        let factor = (high.1 - low.1) / (high.0 - low.0);
+        let offset = low.1 - factor * low.0;
+        let fits = points.iter().all(|&(r, c)| {
+            let tolerance = 1e-9 * (c.abs() + (factor * r).abs() + offset.abs());
+            (offset + factor * r - c).abs() <= tolerance
+        });
+        if fits {
+            Storage::Linear { offset, factor }
+        } else {
+            Storage::Physical
+        }
+    }
+}
+
+/// A channel of an exported channel group
+struct ExportChannel {
+    var_id: u32,
+    name: String,
+    encoding: Encoding,
+    /// Whether the channel holds the raw rather than the converted value
+    raw: bool,
+    storage: Storage,
+    byte_offset: usize,
+}
+
+/// Variables sampled at one poll rate
+struct ExportGroup {
+    rate_hz: u32,
+    variables: Vec<Variable>,
+}
+
+/// Common properties describing a variable in its channel comment
+fn variable_properties(variable: &Variable) -> Vec<(&'static str, String)> {
+    let mut properties = vec![
+        ("datavis.id", variable.id.to_string()),
+        (
+            "datavis.type",
+            serde_json::to_string(&variable.var_type).unwrap_or_default(),
+        ),
+        ("datavis.address", format!("0x{:X}", variable.address)),
+    ];
+    if variable.poll_rate_hz != 0 {
+        properties.push(("datavis.poll_rate_hz", variable.poll_rate_hz.to_string()));
+    }
+    if let Some(script) = &variable.converter_script {
+        properties.push(("datavis.converter", script.clone()));
+    }
+    properties
+}
+
+/// Channel comment of a variable
+fn variable_comment(variable: &Variable, raw: bool) -> String {
+    let mut text = format!("{} at 0x{:08X}", variable.var_type, variable.address);
+    if raw {
+        text.push_str(", raw value");
+    }
+    if let Some(script) = &variable.converter_script {
+        text.push_str(&format!("\nConverter: {}", script));
+    }
+    let mut properties = variable_properties(variable);
+    if raw {
+        properties.push(("datavis.role", "raw".to_string()));
+    }
+    comment_xml("CNcomment", &text, &properties)
+}
+
+/// Group the recorded variables by their effective poll rate
+fn export_groups(recording: &SessionRecording) -> Vec<ExportGroup> {
+    let mut variables: Vec<Variable> = recording.metadata.variables.clone();
+    let mut unknown: Vec<u32> = recording
+        .frames
+        .iter()
+        .flat_map(|f| f.values.keys().copied())
+        .filter(|id| !variables.iter().any(|v| v.id == *id))
+        .collect();
+    unknown.sort_unstable();
+    unknown.dedup();
+    for id in unknown {
+        let mut variable = Variable::new(format!("var_{}", id), 0, VariableType::F64);
+        variable.id = id;
+        variables.push(variable);
+    }
+
+    let mut groups: BTreeMap<u32, Vec<Variable>> = BTreeMap::new();
+    for variable in variables {
+        let rate = if variable.poll_rate_hz == 0 {
+            recording.metadata.poll_rate_hz
+        } else {
+            variable.poll_rate_hz
+        };
+        groups.entry(rate).or_default().push(variable);
+    }
+    groups
+        .into_iter()
+        .map(|(rate_hz, variables)| ExportGroup { rate_hz, variables })
+        .collect()
+}
+
+/// Write a channel group's channels, data group and data block, returning
+/// the data group's offset
+fn write_group(
+    out: &mut BlockWriter,
+    recording: &SessionRecording,
+    group: &ExportGroup,
+    next_dg: u64,
+) -> u64 {
+    // Master time channel: u64 nanoseconds, converted to seconds
+    let mut channels = Vec::new();
+    let mut record_len = 8;
+    for variable in &group.variables {
+        let samples: Vec<&RecordedValue> = recording
+            .frames
+            .iter()
+            .filter_map(|f| f.values.get(&variable.id))
+            .collect();
+        let storage = Storage::fit(&samples);
+        let raw_encoding = Encoding::of(variable.var_type, &samples);
+        let encodings: &[(Encoding, bool)] = match storage {
+            Storage::Physical => &[(Encoding::Float(64), false), (raw_encoding, true)],
+            _ => &[(raw_encoding, true)],
+        };
+        for &(encoding, raw) in encodings {
+            let suffix = if raw && storage == Storage::Physical {
+                RAW_CHANNEL_SUFFIX
+            } else {
+                ""
+            };
+            channels.push(ExportChannel {
+                var_id: variable.id,
+                name: format!("{}{}", variable.name, suffix),
+                encoding,
+                raw,
+                storage,
+                byte_offset: record_len,
+            });
+            record_len += encoding.byte_len();
+        }
+    }
+    let inval_len = channels.len().div_ceil(8);
+
+    // Records: one row per frame holding any of the group's variables
+    let mut data = Vec::new();
+    let mut rows = 0u64;
+    for frame in &recording.frames {
+        if !group
+            .variables
+            .iter()
+            .any(|v| frame.values.contains_key(&v.id))
+        {
+            continue;
+        }
+        let start = data.len();
+        data.resize(start + record_len + inval_len, 0);
+        let record = &mut data[start..];
+        record[..8].copy_from_slice(&(frame.timestamp.as_nanos() as u64).to_le_bytes());
+        for (index, channel) in channels.iter().enumerate() {
+            let field = &mut record[channel.byte_offset..][..channel.encoding.byte_len()];
+            match frame.values.get(&channel.var_id) {
+                Some(value) if channel.raw => channel.encoding.write(value.raw_value, field),
+                Some(value) => channel
+                    .encoding
+                    .write(RawValue::Float(value.converted_value), field),
+                None => record[record_len + index / 8] |= 1 << (index % 8),
+            }
+        }
+        rows += 1;
+    }
+
+    let data_link = if rows == 0 {
+        0
+    } else {
+        out.block(b"DT", &[], &data)
+    };
+
+    // Channels, written back to front so each links to the next
+    let mut next_cn = 0;
+    for (index, channel) in channels.iter().enumerate().rev() {
+        let variable = group
+            .variables
+            .iter()
+            .find(|v| v.id == channel.var_id)
+            .expect("channel of a group variable");
+        let name = out.text(&channel.name);
+        let unit = if channel.raw && channel.storage == Storage::Physical {
+            0
+        } else {
+            out.text(&variable.unit)
+        };
+        let comment = out.xml(&variable_comment(
+            variable,
+            channel.raw && channel.storage == Storage::Physical,
+        ));
+        let conversion = match channel.storage {
+            Storage::Linear { offset, factor } => out.linear_conversion(offset, factor),
+            _ => 0,
+        };
+        let data = channel_data(
+            CN_TYPE_FIXED,
+            0,
+            channel.encoding.data_type(),
+            channel.byte_offset as u32,
+            channel.encoding.bits() as u32,
+            CN_FLAG_INVAL_BIT,
+            index as u32,
+        );
+        next_cn = out.block(
+            b"CN",
+            &[next_cn, 0, name, 0, conversion, 0, unit, comment],
+            &data,
+        );
+    }
+
+    let time_name = out.text("time");
+    let time_unit = out.text("s");
+    let time_conversion = out.linear_conversion(0.0, 1e-9);
+    let time_data = channel_data(CN_TYPE_MASTER, CN_SYNC_TIME, DT_UINT_LE, 0, 64, 0, 0);
+    let master = out.block(
+        b"CN",
+        &[next_cn, 0, time_name, 0, time_conversion, 0, time_unit, 0],
+        &time_data,
+    );
+
+    let acquisition = out.text(&format!("{} Hz", group.rate_hz));
+    let mut cg_data = Vec::with_capacity(32);
+    cg_data.extend_from_slice(&0u64.to_le_bytes()); // record id
+    cg_data.extend_from_slice(&rows.to_le_bytes());
+    cg_data.extend_from_slice(&0u16.to_le_bytes()); // flags
+    cg_data.extend_from_slice(&0u16.to_le_bytes()); // path separator
+    cg_data.extend_from_slice(&[0; 4]);
+    cg_data.extend_from_slice(&(record_len as u32).to_le_bytes());
+    cg_data.extend_from_slice(&(inval_len as u32).to_le_bytes());
+    let cg = out.block(b"CG", &[0, master, acquisition, 0, 0, 0], &cg_data);
+
+    // Sorted data group: no record ids
+    out.block(b"DG", &[next_dg, cg, data_link, 0], &[0; 8])
+}
+
+/// Data section of a CN block
+fn channel_data(
+    cn_type: u8,
+    sync_type: u8,
+    data_type: u8,
+    byte_offset: u32,
+    bit_count: u32,
+    flags: u32,
+    inval_bit_pos: u32,
+) -> Vec<u8> {
+    let mut data = Vec::with_capacity(72);
+    data.extend_from_slice(&[cn_type, sync_type, data_type, 0]);
+    data.extend_from_slice(&byte_offset.to_le_bytes());
+    data.extend_from_slice(&bit_count.to_le_bytes());
+    data.extend_from_slice(&flags.to_le_bytes());
+    data.extend_from_slice(&inval_bit_pos.to_le_bytes());
+    data.push(0); // precision
+    data.push(0);
+    data.extend_from_slice(&0u16.to_le_bytes()); // attachment count
+                                                 // Value range and limits (not valid)
+    data.extend_from_slice(&[0; 48]);
+    data
+}
+
+/// Encode a recording as an MDF4 file
+pub fn encode_mdf(recording: &SessionRecording) -> Vec<u8> {
+    let metadata = &recording.metadata;
+    let mut out = BlockWriter::new();
+
+    let start_nanos = metadata
+        .recorded_at
+        .timestamp_nanos_opt()
+        .unwrap_or_default()
+        .max(0) as u64;
+    let mut hd_data = Vec::with_capacity(32);
+    hd_data.extend_from_slice(&start_nanos.to_le_bytes());
+    hd_data.extend_from_slice(&[0; 4]); // time zone and DST offsets
+    hd_data.extend_from_slice(&[0, 0, 0, 0]); // time flags (UTC), class, flags
+    hd_data.extend_from_slice(&[0; 16]); // start angle and distance
+    let hd = out.block(b"HD", &[0; 6], &hd_data);
+
+    let fh_comment = out.xml(&format!(
+        "<FHcomment>\n<TX>Exported session recording</TX>\n<tool_id>datavis-rs</tool_id>\n\
+         <tool_vendor>datavis-rs</tool_vendor>\n<tool_version>{}</tool_version>\n</FHcomment>",
+        env!("CARGO_PKG_VERSION")
+    ));
+    let mut fh_data = start_nanos.to_le_bytes().to_vec();
+    fh_data.extend_from_slice(&[0; 8]);
+    let fh = out.block(b"FH", &[0, fh_comment], &fh_data);
+    out.set_link(hd, 1, fh);
+
+    let mut properties = vec![
+        ("name", metadata.name.clone()),
+        ("poll_rate_hz", metadata.poll_rate_hz.to_string()),
+    ];
+    if let Some(target) = &metadata.target_name {
+        properties.push(("target_name", target.clone()));
+    }
+    if let Some(elf) = &metadata.elf_path {
+        properties.push(("elf_path", elf.clone()));
+    }
+    let hd_comment = out.xml(&comment_xml(
+        "HDcomment",
+        metadata.description.as_deref().unwrap_or(&metadata.name),
+        &properties,
+    ));
+    out.set_link(hd, 5, hd_comment);
+
+    let mut next_dg = 0;
+    for group in export_groups(recording).iter().rev() {
+        next_dg = write_group(&mut out, recording, group, next_dg);
+    }
+    out.set_link(hd, 0, next_dg);
+
+    // Frame tags, then recording events, chained in order
+    let mut ev_blocks = Vec::new();
+    for frame in &recording.frames {
+        if let Some(tag) = &frame.tag {
+            let name = out.text(tag);
+            ev_blocks.push(write_event(
+                &mut out,
+                frame.timestamp,
+                EV_RANGE_POINT,
+                0,
+                name,
+                0,
+            ));
+        }
+    }
+    let mut open_region = 0;
+    for event in &metadata.events {
+        let (range_type, range) = match event.kind {
+            MarkerType::RegionStart => (EV_RANGE_BEGIN, 0),
+            MarkerType::RegionEnd => (EV_RANGE_END, std::mem::take(&mut open_region)),
+            _ => (EV_RANGE_POINT, 0),
+        };
+        let mut properties = vec![("datavis.kind", event.kind.display_name().to_string())];
+        if let Some([r, g, b, a]) = event.color {
+            properties.push((
+                "datavis.color",
+                format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a),
+            ));
+        }
+        let name = out.text(&event.name);
+        let comment = out.xml(&comment_xml(
+            "EVcomment",
+            event.note.as_deref().unwrap_or_default(),
+            &properties,
+        ));
+        let block = write_event(&mut out, event.time, range_type, range, name, comment);
+        if event.kind == MarkerType::RegionStart {
+            open_region = block;
+        }
+        ev_blocks.push(block);
+    }
+    for pair in ev_blocks.windows(2) {
+        out.set_link(pair[0], 0, pair[1]);
+    }
+    out.set_link(hd, 4, ev_blocks.first().copied().unwrap_or(0));
+
+    out.bytes
+}
+
+/// Write a marker EV block at a time offset, linked to no next event
+fn write_event(
+    out: &mut BlockWriter,
+    time: Duration,
+    range_type: u8,
+    range: u64,
+    name: u64,
+    comment: u64,
+) -> u64 {
+    let mut data = vec![
+        EV_MARKER,
+        CN_SYNC_TIME,
+        range_type,
+        EV_CAUSE_USER,
+        0,
+        0,
+        0,
+        0,
+    ];
+    data.extend_from_slice(&0u32.to_le_bytes()); // scope count
+    data.extend_from_slice(&0u16.to_le_bytes()); // attachment count
+    data.extend_from_slice(&0u16.to_le_bytes()); // creator (first FH)
+    data.extend_from_slice(&(time.as_nanos() as i64).to_le_bytes());
+    data.extend_from_slice(&1e-9f64.to_le_bytes());
+    out.block(b"EV", &[0, 0, range, name, comment], &data)
+}
+
+/// Write a recording as an MDF4 file
+pub fn write_mdf(recording: &SessionRecording, path: &Path) -> io::Result<()> {
+    std::fs::write(path, encode_mdf(recording))
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+/// A block read from the file
+struct Block<'a> {
+    id: [u8; 2],
+    links: Vec<u64>,
+    data: &'a [u8],
+}
+
+impl Block<'_> {
+    fn link(&self, index: usize) -> u64 {
+        self.links.get(index).copied().unwrap_or(0)
+    }
+}
+
+/// Little-endian reader over a block's data section
+struct Fields<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Fields<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| invalid_data("truncated MDF block"))?;
+        self.pos += N;
+        Ok(bytes.try_into().expect("slice of N bytes"))
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(self.bytes()?))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes()?))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.bytes()?))
+    }
+
+    fn i64(&mut self) -> io::Result<i64> {
+        Ok(i64::from_le_bytes(self.bytes()?))
+    }
+
+    fn f64(&mut self) -> io::Result<f64> {
+        Ok(f64::from_le_bytes(self.bytes()?))
+    }
+
+    fn skip(&mut self, count: usize) {
+        self.pos += count;
+    }
+}
+
+/// Read the block at an offset
+fn read_block(bytes: &[u8], offset: u64) -> io::Result<Block<'_>> {
+    let start = usize::try_from(offset).map_err(|_| invalid_data("MDF link out of range"))?;
+    let header = bytes
+        .get(start..start + BLOCK_HEADER_LEN)
+        .ok_or_else(|| invalid_data(format!("MDF link 0x{:X} out of range", offset)))?;
+    if &header[..2] != b"##" {
+        return Err(invalid_data(format!("no MDF block at 0x{:X}", offset)));
+    }
+    let mut fields = Fields::new(&header[8..]);
+    let length = fields.u64()? as usize;
+    let link_count = fields.u64()? as usize;
+    let links_end = BLOCK_HEADER_LEN + link_count.saturating_mul(8);
+    if length < links_end {
+        return Err(invalid_data(format!("corrupt MDF block at 0x{:X}", offset)));
+    }
+    let block = bytes
+        .get(start..start + length)
+        .ok_or_else(|| invalid_data(format!("MDF block at 0x{:X} truncated", offset)))?;
+    let links = block[BLOCK_HEADER_LEN..links_end]
+        .chunks_exact(8)
+        .map(|l| u64::from_le_bytes(l.try_into().expect("8 byte link")))
+        .collect();
+    Ok(Block {
+        id: [header[2], header[3]],
+        links,
+        data: &block[links_end..],
+    })
+}
+
+/// Text of a TX block, or the `<TX>` of an MD block
+fn read_text(bytes: &[u8], link: u64) -> io::Result<Option<String>> {
+    if link == 0 {
+        return Ok(None);
+    }
+    let block = read_block(bytes, link)?;
+    let end = block
+        .data
+        .iter()
+        .position(|&b| b == 0)
+        .unwrap_or(block.data.len());
+    let text = String::from_utf8_lossy(&block.data[..end]).into_owned();
+    Ok(match &block.id {
+        b"MD" => Some(xml_element(&text, "TX").unwrap_or_default()),
+        _ => Some(text),
+    })
+}
+
+/// Parse an `#rrggbbaa` colour
+fn parse_rgba(text: &str) -> Option<[u8; 4]> {
+    let hex = text.strip_prefix('#')?;
+    if hex.len() != 8 {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some(value.to_be_bytes())
+}
+
+/// Common properties of an MD comment
+fn read_properties(bytes: &[u8], link: u64) -> io::Result<HashMap<String, String>> {
+    if link == 0 {
+        return Ok(HashMap::new());
+    }
+    let block = read_block(bytes, link)?;
+    if &block.id != b"MD" {
+        return Ok(HashMap::new());
+    }
+    Ok(xml_properties(&String::from_utf8_lossy(block.data)))
+}
+
+/// Undo the byte transposition of a DZ block
+fn untranspose(data: &[u8], columns: usize) -> Vec<u8> {
+    if columns == 0 {
+        return data.to_vec();
+    }
+    let rows = data.len() / columns;
+    let mut out = vec![0; data.len()];
+    for column in 0..columns {
+        for row in 0..rows {
+            out[row * columns + column] = data[column * rows + row];
+        }
+    }
+    out[rows * columns..].copy_from_slice(&data[rows * columns..]);
+    out
+}
+
+/// Append the record data behind a data link
+fn read_data(bytes: &[u8], link: u64, out: &mut Vec<u8>) -> io::Result<()> {
+    if link == 0 {
+        return Ok(());
+    }
+    let block = read_block(bytes, link)?;
+    match &block.id {
+        b"DT" | b"RD" => out.extend_from_slice(block.data),
+        b"DZ" => {
+            let mut fields = Fields::new(block.data);
+            fields.skip(2); // original block type
+            let zip_type = fields.u8()?;
+            fields.skip(1);
+            let columns = fields.u32()? as usize;
+            let original_len = fields.u64()? as usize;
+            let compressed_len = fields.u64()? as usize;
+            let compressed = block
+                .data
+                .get(24..24 + compressed_len)
+                .ok_or_else(|| invalid_data("truncated DZ block"))?;
+            let mut inflated = Vec::with_capacity(original_len);
+            flate2::read::ZlibDecoder::new(compressed).read_to_end(&mut inflated)?;
+            match zip_type {
+                0 => out.extend_from_slice(&inflated),
+                1 => out.extend_from_slice(&untranspose(&inflated, columns)),
+                other => return Err(invalid_data(format!("unsupported DZ zip type {}", other))),
+            }
+        }
+        b"DL" => {
+            let mut next = link;
+            while next != 0 {
+                let list = read_block(bytes, next)?;
+                for &data in list.links.iter().skip(1) {
+                    read_data(bytes, data, out)?;
+                }
+                next = list.link(0);
+            }
+        }
+        b"HL" => read_data(bytes, block.link(0), out)?,
+        id => {
+            return Err(invalid_data(format!(
+                "unsupported MDF data block {}",
+                String::from_utf8_lossy(id)
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Conversion from raw to physical values
+#[derive(Debug, Clone, PartialEq)]
+enum Conversion {
+    Identity,
+    Linear {
+        offset: f64,
+        factor: f64,
+    },
+    Rational([f64; 6]),
+    /// Sorted `(raw, physical)` pairs
+    Table {
+        points: Vec<(f64, f64)>,
+        interpolate: bool,
+    },
+}
+
+impl Conversion {
+    fn read(bytes: &[u8], link: u64) -> io::Result<Self> {
+        if link == 0 {
+            return Ok(Conversion::Identity);
+        }
+        let block = read_block(bytes, link)?;
+        let mut fields = Fields::new(block.data);
+        let cc_type = fields.u8()?;
+        fields.skip(5);
+        let value_count = fields.u16()? as usize;
+        fields.skip(16);
+        let values = (0..value_count)
+            .map(|_| fields.f64())
+            .collect::<io::Result<Vec<f64>>>()?;
+        Ok(match (cc_type, values.as_slice()) {
+            (CC_LINEAR, &[offset, factor]) => Conversion::Linear { offset, factor },
+            (CC_RATIONAL, &[p1, p2, p3, p4, p5, p6]) => {
+                Conversion::Rational([p1, p2, p3, p4, p5, p6])
+            }
+            (CC_TABLE_INTERP | CC_TABLE, values) if values.len() >= 2 => {
+                let mut points: Vec<(f64, f64)> =
+                    values.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+                points.sort_by(|a, b| a.0.total_cmp(&b.0));
+                Conversion::Table {
+                    points,
+                    interpolate: cc_type == CC_TABLE_INTERP,
+                }
+            }
+            // Text and algebraic conversions keep the raw value
+            _ => Conversion::Identity,
+        })
+    }
+
+    fn apply(&self, x: f64) -> f64 {
+        match self {
+            Conversion::Identity => x,
+            Conversion::Linear { offset, factor } => offset + factor * x,
+            Conversion::Rational([p1, p2, p3, p4, p5, p6]) => {
+                (p1 * x * x + p2 * x + p3) / (p4 * x * x + p5 * x + p6)
+            }
+            Conversion::Table {
+                points,
+                interpolate,
+            } => {
+                let index = points.partition_point(|p| p.0 < x);
+                if index == 0 {
+                    return points[0].1;
+                }
+                if index == points.len() {
+                    return points[index - 1].1;
+                }
+                let (x0, y0) = points[index - 1];
+                let (x1, y1) = points[index];
+                if *interpolate {
+                    y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+                } else if x - x0 <= x1 - x {
+                    y0
+                } else {
+                    y1
+                }
+            }
+        }
+    }
+}
+
+/// A channel of an imported channel group
+struct ImportChannel {
+    name: String,
+    unit: String,
+    properties: HashMap<String, String>,
+    cn_type: u8,
+    sync_type: u8,
+    data_type: u8,
+    bit_offset: u32,
+    byte_offset: usize,
+    bit_count: u32,
+    flags: u32,
+    inval_bit_pos: u32,
+    conversion: Conversion,
+    /// Variable the channel's values belong to
+    var_id: u32,
+}
+
+impl ImportChannel {
+    fn read(bytes: &[u8], link: u64) -> io::Result<(Self, u64)> {
+        let block = read_block(bytes, link)?;
+        let mut fields = Fields::new(block.data);
+        let cn_type = fields.u8()?;
+        let sync_type = fields.u8()?;
+        let data_type = fields.u8()?;
+        let bit_offset = fields.u8()? as u32;
+        let byte_offset = fields.u32()? as usize;
+        let bit_count = fields.u32()?;
+        let flags = fields.u32()?;
+        let inval_bit_pos = fields.u32()?;
+
+        let conversion = Conversion::read(bytes, block.link(4))?;
+        let unit = match read_text(bytes, block.link(6))? {
+            Some(unit) => unit,
+            // Fall back to the conversion's unit
+            None if block.link(4) != 0 => {
+                read_text(bytes, read_block(bytes, block.link(4))?.link(1))?.unwrap_or_default()
+            }
+            None => String::new(),
+        };
+        let channel = Self {
+            name: read_text(bytes, block.link(2))?.unwrap_or_default(),
+            unit,
+            properties: read_properties(bytes, block.link(7))?,
+            cn_type,
+            sync_type,
+            data_type,
+            bit_offset,
+            byte_offset,
+            bit_count,
+            flags,
+            inval_bit_pos,
+            conversion,
+            var_id: 0,
+        };
+        Ok((channel, block.link(0)))
+    }
+
+    fn is_master(&self) -> bool {
+        matches!(self.cn_type, CN_TYPE_MASTER | CN_TYPE_VIRTUAL_MASTER)
+            && self.sync_type == CN_SYNC_TIME
+    }
+
+    /// Whether the channel carries numeric samples we can import
+    fn is_numeric(&self) -> bool {
+        matches!(self.cn_type, CN_TYPE_FIXED | CN_TYPE_SYNC)
+            && self.data_type <= DT_FLOAT_BE
+            && (1..=64).contains(&self.bit_count)
+            && (self.data_type < DT_FLOAT_LE || matches!(self.bit_count, 32 | 64))
+            && self.flags & CN_FLAG_ALL_INVALID == 0
+    }
+
+    /// Whether this channel holds the raw values of another channel
+    fn is_raw_channel(&self) -> bool {
+        self.properties.get("datavis.role").map(String::as_str) == Some("raw")
+    }
+
+    fn is_valid(&self, record: &[u8], data_bytes: usize) -> bool {
+        if self.flags & CN_FLAG_INVAL_BIT == 0 {
+            return true;
+        }
+        let byte = data_bytes + (self.inval_bit_pos / 8) as usize;
+        record
+            .get(byte)
+            .is_none_or(|b| b & (1 << (self.inval_bit_pos % 8)) == 0)
+    }
+
+    /// Decode the raw value of the channel from a record
+    fn decode(&self, record: &[u8]) -> Option<RawValue> {
+        let len = (self.bit_offset + self.bit_count).div_ceil(8) as usize;
+        let mut field = record
+            .get(self.byte_offset..self.byte_offset + len)?
+            .to_vec();
+        if matches!(self.data_type, DT_UINT_BE | DT_INT_BE | DT_FLOAT_BE) {
+            field.reverse();
+        }
+        let mut bits = 0u128;
+        for (i, byte) in field.iter().enumerate() {
+            bits |= (*byte as u128) << (i * 8);
+        }
+        let mask = if self.bit_count >= 64 {
+            u64::MAX
+        } else {
+            (1u64 << self.bit_count) - 1
+        };
+        let value = (bits >> self.bit_offset) as u64 & mask;
+        Some(match self.data_type {
+            DT_UINT_LE | DT_UINT_BE => RawValue::UInt(value),
+            DT_INT_LE | DT_INT_BE => {
+                let shift = 64 - self.bit_count;
+                RawValue::from_i64(((value << shift) as i64) >> shift)
+            }
+            _ if self.bit_count == 32 => RawValue::Float(f32::from_bits(value as u32) as f64),
+            _ => RawValue::Float(f64::from_bits(value)),
+        })
+    }
+
+    /// Variable type of the channel's raw values
+    fn var_type(&self) -> VariableType {
+        if let Some(var_type) = self
+            .properties
+            .get("datavis.type")
+            .and_then(|s| serde_json::from_str(s).ok())
+        {
+            return var_type;
+        }
+        let signed = matches!(self.data_type, DT_INT_LE | DT_INT_BE);
+        match (self.data_type, self.bit_count) {
+            (DT_FLOAT_LE | DT_FLOAT_BE, 32) => VariableType::F32,
+            (DT_FLOAT_LE | DT_FLOAT_BE, _) => VariableType::F64,
+            (_, 1) if !signed => VariableType::Bool,
+            (_, 0..=8) if signed => VariableType::I8,
+            (_, 0..=8) => VariableType::U8,
+            (_, 9..=16) if signed => VariableType::I16,
+            (_, 9..=16) => VariableType::U16,
+            (_, 17..=32) if signed => VariableType::I32,
+            (_, 17..=32) => VariableType::U32,
+            _ if signed => VariableType::I64,
+            _ => VariableType::U64,
+        }
+    }
+
+    fn variable(&self, id: u32) -> Variable {
+        let address = self
+            .properties
+            .get("datavis.address")
+            .and_then(|s| match s.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16).ok(),
+                None => s.parse().ok(),
+            })
+            .unwrap_or(0);
+        let mut variable = Variable::new(self.name.clone(), address, self.var_type());
+        variable.id = id;
+        variable.unit = self.unit.clone();
+        variable.converter_script = self.properties.get("datavis.converter").cloned();
+        variable.poll_rate_hz = self
+            .properties
+            .get("datavis.poll_rate_hz")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        variable
+    }
+}
+
+/// A channel group of an imported data group
+struct ImportGroup {
+    record_id: u64,
+    data_bytes: usize,
+    inval_bytes: usize,
+    vlsd: bool,
+    channels: Vec<ImportChannel>,
+}
+
+impl ImportGroup {
+    fn read(bytes: &[u8], link: u64) -> io::Result<(Self, u64)> {
+        let block = read_block(bytes, link)?;
+        let mut fields = Fields::new(block.data);
+        let record_id = fields.u64()?;
+        fields.skip(8); // cycle count
+        let flags = fields.u16()?;
+        fields.skip(6);
+        let data_bytes = fields.u32()? as usize;
+        let inval_bytes = fields.u32()? as usize;
+
+        let mut channels = Vec::new();
+        let mut next = block.link(1);
+        while next != 0 {
+            let (channel, following) = ImportChannel::read(bytes, next)?;
+            channels.push(channel);
+            next = following;
+        }
+        Ok((
+            Self {
+                record_id,
+                data_bytes,
+                inval_bytes,
+                vlsd: flags & CG_FLAG_VLSD != 0,
+                channels,
+            },
+            block.link(0),
+        ))
+    }
+
+    /// Time offset of a record in nanoseconds
+    fn time(&self, record: &[u8], index: u64) -> u64 {
+        let seconds = match self.channels.iter().find(|c| c.is_master()) {
+            Some(master) if master.cn_type == CN_TYPE_VIRTUAL_MASTER => {
+                master.conversion.apply(index as f64)
+            }
+            Some(master) => master
+                .decode(record)
+                .map(|raw| master.conversion.apply(raw.as_f64()))
+                .unwrap_or(f64::NAN),
+            None => index as f64,
+        };
+        if seconds.is_finite() && seconds > 0.0 {
+            (seconds * 1e9).round() as u64
+        } else {
+            0
+        }
+    }
+
+    /// Add a record's values to its frame
+    fn import_record(&self, record: &[u8], index: u64, frames: &mut BTreeMap<u64, RecordedFrame>) {
+        let offset = self.time(record, index);
+        let frame = frame_at(frames, offset);
+        let channels = self
+            .channels
+            .iter()
+            .filter(|c| !c.is_master() && c.is_numeric() && c.is_valid(record, self.data_bytes));
+        // Raw channels apply on top of their variable's converted value
+        let (raw, converted): (Vec<_>, Vec<_>) = channels.partition(|c| c.is_raw_channel());
+        for channel in converted {
+            let Some(raw_value) = channel.decode(record) else {
+                continue;
+            };
+            frame.values.insert(
+                channel.var_id,
+                RecordedValue {
+                    raw_value,
+                    converted_value: channel.conversion.apply(raw_value.as_f64()),
+                },
+            );
+        }
+        for channel in raw {
+            if let (Some(raw_value), Some(value)) = (
+                channel.decode(record),
+                frame.values.get_mut(&channel.var_id),
+            ) {
+                value.raw_value = raw_value;
+            }
+        }
+    }
+}
+
+/// Decode an MDF4 file into a recording
+///
+/// Every numeric channel becomes a variable (the `.raw` channels written for
+/// non-linear variables are folded back into theirs), samples sharing a time
+/// stamp are merged into one frame and marker and trigger events become frame
+/// tags.
+pub fn decode_mdf(bytes: &[u8], name: &str) -> io::Result<SessionRecording> {
+    if bytes.len() < HEADER_OFFSET as usize || &bytes[..8] != MDF_MAGIC {
+        return Err(invalid_data("not an MDF file"));
+    }
+    if bytes[8] != b'4' {
+        return Err(invalid_data(format!(
+            "unsupported MDF version {}",
+            String::from_utf8_lossy(&bytes[8..16]).trim()
+        )));
+    }
+
+    let hd = read_block(bytes, HEADER_OFFSET)?;
+    if &hd.id != b"HD" {
+        return Err(invalid_data("missing MDF header block"));
+    }
+    let start_nanos = Fields::new(hd.data).u64()?;
+
+    // Read the structure of every data group
+    let mut data_groups = Vec::new();
+    let mut next_dg = hd.link(0);
+    while next_dg != 0 {
+        let dg = read_block(bytes, next_dg)?;
+        let record_id_size = Fields::new(dg.data).u8()? as usize;
+        let mut groups = Vec::new();
+        let mut next_cg = dg.link(1);
+        while next_cg != 0 {
+            let (group, following) = ImportGroup::read(bytes, next_cg)?;
+            groups.push(group);
+            next_cg = following;
+        }
+        data_groups.push((record_id_size, groups, dg.link(2)));
+        next_dg = dg.link(0);
+    }
+
+    // Assign variable ids, keeping those written by us
+    let channels = || {
+        data_groups
+            .iter()
+            .flat_map(|(_, groups, _)| groups)
+            .flat_map(|g| &g.channels)
+            .filter(|c| !c.is_master() && c.is_numeric())
+    };
+    let explicit_id =
+        |c: &ImportChannel| c.properties.get("datavis.id").and_then(|s| s.parse().ok());
+    let mut next_id = channels().filter_map(explicit_id).max().unwrap_or(0) + 1;
+    let mut variables: BTreeMap<u32, Variable> = BTreeMap::new();
+    for (_, groups, _) in &mut data_groups {
+        for channel in groups.iter_mut().flat_map(|g| &mut g.channels) {
+            if channel.is_master() || !channel.is_numeric() {
+                continue;
+            }
+            channel.var_id = match explicit_id(channel) {
+                Some(id) => id,
+                None => {
+                    next_id += 1;
+                    next_id - 1
+                }
+            };
+            if !channel.is_raw_channel() {
+                variables
+                    .entry(channel.var_id)
+                    .or_insert_with(|| channel.variable(channel.var_id));
+            }
+        }
+    }
+
+    // Read the records
+    let mut frames: BTreeMap<u64, RecordedFrame> = BTreeMap::new();
+    for (record_id_size, groups, data_link) in &data_groups {
+        let mut data = Vec::new();
+        read_data(bytes, *data_link, &mut data)?;
+        let mut counts: HashMap<u64, u64> = HashMap::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let group = if *record_id_size == 0 {
+                groups.first()
+            } else {
+                let id = data
+                    .get(pos..pos + record_id_size)
+                    .ok_or_else(|| invalid_data("truncated MDF record id"))?;
+                pos += record_id_size;
+                let mut record_id = 0u64;
+                for (i, byte) in id.iter().take(8).enumerate() {
+                    record_id |= (*byte as u64) << (i * 8);
+                }
+                groups.iter().find(|g| g.record_id == record_id)
+            };
+            let Some(group) = group else {
+                return Err(invalid_data("MDF record of an unknown channel group"));
+            };
+            let len = if group.vlsd {
+                let len = data
+                    .get(pos..pos + 4)
+                    .ok_or_else(|| invalid_data("truncated MDF record"))?;
+                pos += 4;
+                u32::from_le_bytes(len.try_into().expect("4 bytes")) as usize
+            } else {
+                group.data_bytes + group.inval_bytes
+            };
+            let Some(record) = data.get(pos..pos + len) else {
+                // Incomplete last record of an unfinalized file
+                break;
+            };
+            pos += len;
+            if len == 0 {
+                continue;
+            }
+            if !group.vlsd {
+                let index = counts.entry(group.record_id).or_default();
+                group.import_record(record, *index, &mut frames);
+                *index += 1;
+            }
+        }
+    }
+
+    // Marker events
+    let mut events = Vec::new();
+    let mut next_ev = hd.link(4);
+    while next_ev != 0 {
+        let ev = read_block(bytes, next_ev)?;
+        next_ev = ev.link(0);
+        let mut fields = Fields::new(ev.data);
+        let ev_type = fields.u8()?;
+        let sync_type = fields.u8()?;
+        fields.skip(14);
+        let base = fields.i64()?;
+        let factor = fields.f64()?;
+        if !matches!(ev_type, EV_MARKER | EV_TRIGGER) || sync_type != CN_SYNC_TIME {
+            continue;
+        }
+        let offset = (base as f64 * factor * 1e9).round().max(0.0) as u64;
+
+        // Recording events written by us carry their type in the comment
+        let properties = read_properties(bytes, ev.link(4))?;
+        if let Some(kind) = properties
+            .get("datavis.kind")
+            .and_then(|k| MarkerType::from_display_name(k))
+        {
+            let mut event = RecordingEvent::new(
+                Duration::from_nanos(offset),
+                kind,
+                read_text(bytes, ev.link(3))?.unwrap_or_default(),
+            );
+            event.note = read_text(bytes, ev.link(4))?.filter(|t| !t.is_empty());
+            event.color = properties.get("datavis.color").and_then(|c| parse_rgba(c));
+            events.push(event);
+            continue;
+        }
+
+        let text = match read_text(bytes, ev.link(3))?.filter(|t| !t.is_empty()) {
+            Some(name) => name,
+            None => match read_text(bytes, ev.link(4))? {
+                Some(comment) => comment,
+                None => continue,
+            },
+        };
+        let frame = frame_at(&mut frames, offset);
+        frame.tag = Some(match frame.tag.take() {
+            Some(existing) => format!("{}; {}", existing, text),
+            None => text,
+        });
+    }
+
+    let mut metadata = SessionMetadata::new(name);
+    if let Some(start) = chrono::DateTime::from_timestamp(
+        (start_nanos / 1_000_000_000) as i64,
+        (start_nanos % 1_000_000_000) as u32,
+    ) {
+        metadata.recorded_at = start;
+    }
+    let properties = read_properties(bytes, hd.link(5))?;
+    if let Some(name) = properties.get("name") {
+        metadata.name = name.clone();
+    }
+    metadata.description =
+        read_text(bytes, hd.link(5))?.filter(|text| !text.is_empty() && *text != metadata.name);
+    if let Some(rate) = properties.get("poll_rate_hz").and_then(|s| s.parse().ok()) {
+        metadata.poll_rate_hz = rate;
+    }
+    metadata.target_name = properties.get("target_name").cloned();
+    metadata.elf_path = properties.get("elf_path").cloned();
+    metadata.variables = variables.into_values().collect();
+    sort_events(&mut events);
+    metadata.events = events;
+
+    let mut recording = SessionRecording::with_metadata(metadata);
+    recording.frames = frames.into_values().collect();
+    recording.finalize();
+    Ok(recording)
+}
+
+/// Read an MDF4 file into a recording
+pub fn read_mdf(path: &Path) -> io::Result<SessionRecording> {
+    let bytes = std::fs::read(path)?;
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "MDF Import".to_string());
+    decode_mdf(&bytes, &name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::types::RecordingBuilder;
+    use std::io::Write;
+
+    fn value(raw_value: RawValue, converted_value: f64) -> RecordedValue {
+        RecordedValue {
+            raw_value,
+            converted_value,
+        }
+    }
+
+    fn test_recording() -> SessionRecording {
+        let count = Variable::new("count", 0x2000_0000, VariableType::U64);
+        let mut voltage = Variable::new("adc.voltage", 0x2000_0010, VariableType::U16)
+            .with_unit("V")
+            .with_converter("value * 3.3 / 4096 + 0.1");
+        voltage.poll_rate_hz = 10;
+        let temperature = Variable::new("temperature", 0x2000_0020, VariableType::I32)
+            .with_unit("°C")
+            .with_converter("if value < 0 { 0.0 } else { value * 0.5 }");
+        let mut metadata = SessionMetadata::new("Bench run").with_description("MDF test");
+        metadata.target_name = Some("STM32F407VG".to_string());
+        metadata.variables = vec![count.clone(), voltage.clone(), temperature.clone()];
+        let mut note = RecordingEvent::new(Duration::from_millis(220), MarkerType::Note, "flow")
+            .with_note("flow <5> l/min");
+        note.color = Some([255, 128, 0, 200]);
+        metadata.events = vec![
+            RecordingEvent::new(
+                Duration::from_micros(105_500),
+                MarkerType::RegionStart,
+                "ramp",
+            ),
+            note,
+            RecordingEvent::new(
+                Duration::from_millis(300),
+                MarkerType::RegionEnd,
+                "ramp end",
+            ),
+        ];
+
+        let mut builder = RecordingBuilder::new(metadata);
+        for i in 0..50u64 {
+            let t = i as i64 - 25;
+            let mut values = vec![
+                (
+                    count.id,
+                    RawValue::UInt(u64::MAX - i),
+                    (u64::MAX - i) as f64,
+                ),
+                (
+                    temperature.id,
+                    RawValue::from_i64(t),
+                    (t.max(0) as f64) * 0.5,
+                ),
+            ];
+            if i % 10 == 0 {
+                let raw = i * 80;
+                values.push((
+                    voltage.id,
+                    RawValue::UInt(raw),
+                    raw as f64 * 3.3 / 4096.0 + 0.1,
+                ));
+            }
+            let tag = (i == 13).then_some("valve <open> & hold");
+            builder.frame(Duration::from_millis(i * 10), &values, tag);
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn test_mdf_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.mf4");
+        let original = test_recording();
+        write_mdf(&original, &path).unwrap();
+        assert!(is_mdf_file(&path).unwrap());
+
+        let loaded = SessionRecording::load_from_file(&path).unwrap();
+        assert_eq!(loaded.metadata.name, "Bench run");
+        assert_eq!(loaded.metadata.description.as_deref(), Some("MDF test"));
+        assert_eq!(loaded.metadata.target_name.as_deref(), Some("STM32F407VG"));
+        assert_eq!(loaded.metadata.recorded_at, original.metadata.recorded_at);
+        assert_eq!(loaded.frame_count(), 50);
+        assert_eq!(loaded.duration(), original.duration());
+        assert_eq!(loaded.metadata.variables.len(), 3);
+        assert_eq!(loaded.metadata.events, original.metadata.events);
+
+        for variable in &original.metadata.variables {
+            let restored = loaded
+                .metadata
+                .variables
+                .iter()
+                .find(|v| v.id == variable.id)
+                .unwrap();
+            assert_eq!(restored.name, variable.name);
+            assert_eq!(restored.unit, variable.unit);
+            assert_eq!(restored.var_type, variable.var_type);
+            assert_eq!(restored.address, variable.address);
+            assert_eq!(restored.poll_rate_hz, variable.poll_rate_hz);
+            assert_eq!(restored.converter_script, variable.converter_script);
+        }
+
+        for (a, b) in original.frames.iter().zip(&loaded.frames) {
+            assert_eq!(a.timestamp, b.timestamp);
+            assert_eq!(a.tag, b.tag);
+            assert_eq!(a.values.len(), b.values.len());
+            for (id, value) in &a.values {
+                assert_eq!(b.values[id].raw_value, value.raw_value);
+                let converted = b.values[id].converted_value;
+                assert!((converted - value.converted_value).abs() <= 1e-9 * converted.abs());
+            }
+        }
+    }
+
+    #[test]
+    fn test_groups_and_conversions() {
+        let recording = test_recording();
+        let bytes = encode_mdf(&recording);
+        let hd = read_block(&bytes, HEADER_OFFSET).unwrap();
+
+        // One data group per poll rate: 10 Hz and the session's 100 Hz
+        let mut names = Vec::new();
+        let mut next_dg = hd.link(0);
+        while next_dg != 0 {
+            let dg = read_block(&bytes, next_dg).unwrap();
+            let (group, _) = ImportGroup::read(&bytes, dg.link(1)).unwrap();
+            names.push(
+                group
+                    .channels
+                    .iter()
+                    .map(|c| (c.name.clone(), c.conversion.clone()))
+                    .collect::<Vec<_>>(),
+            );
+            next_dg = dg.link(0);
+        }
+        assert_eq!(names.len(), 2);
+
+        let time = Conversion::Linear {
+            offset: 0.0,
+            factor: 1e-9,
+        };
+        assert_eq!(names[0][0], ("time".to_string(), time.clone()));
+        assert_eq!(names[0][1].0, "adc.voltage");
+        let Conversion::Linear { offset, factor } = names[0][1].1 else {
+            panic!("expected a linear conversion");
+        };
+        assert!((factor - 3.3 / 4096.0).abs() < 1e-15);
+        assert!((offset - 0.1).abs() < 1e-12);
+
+        assert_eq!(names[1][0], ("time".to_string(), time));
+        assert_eq!(names[1][1], ("count".to_string(), Conversion::Identity));
+        assert_eq!(
+            names[1][2],
+            ("temperature".to_string(), Conversion::Identity)
+        );
+        assert_eq!(
+            names[1][3],
+            ("temperature.raw".to_string(), Conversion::Identity)
+        );
+    }
+
+    #[test]
+    fn test_storage_fit() {
+        let linear = [
+            value(RawValue::UInt(0), 1.0),
+            value(RawValue::UInt(10), 6.0),
+        ];
+        let refs: Vec<&RecordedValue> = linear.iter().collect();
+        assert_eq!(
+            Storage::fit(&refs),
+            Storage::Linear {
+                offset: 1.0,
+                factor: 0.5
+            }
+        );
+
+        let identity = [value(RawValue::Float(f64::NAN), f64::NAN)];
+        let refs: Vec<&RecordedValue> = identity.iter().collect();
+        assert_eq!(Storage::fit(&refs), Storage::Identity);
+
+        // A single raw value cannot pin down a slope
+        let constant = [value(RawValue::UInt(3), 7.0), value(RawValue::UInt(3), 7.0)];
+        let refs: Vec<&RecordedValue> = constant.iter().collect();
+        assert_eq!(Storage::fit(&refs), Storage::Physical);
+    }
+
+    /// Unsorted file with record ids and a transposed, compressed data block,
+    /// as written by other tools
+    #[test]
+    fn test_read_unsorted_compressed_file() {
+        let mut out = BlockWriter::new();
+        let hd = out.block(b"HD", &[0; 6], &[0; 32]);
+
+        // Group 1: f32 time in seconds + i16 with a value table
+        let table = {
+            let mut data = vec![CC_TABLE_INTERP, 0, 0, 0, 0, 0, 4, 0];
+            data.extend_from_slice(&[0; 16]);
+            for v in [0.0f64, 0.0, 100.0, 50.0] {
+                data.extend_from_slice(&v.to_le_bytes());
+            }
+            out.block(b"CC", &[0, 0, 0, 0], &data)
+        };
+        let unit = out.text("bar");
+        let name = out.text("pressure");
+        let pressure = out.block(
+            b"CN",
+            &[0, 0, name, 0, table, 0, unit, 0],
+            &channel_data(CN_TYPE_FIXED, 0, DT_INT_LE, 4, 16, 0, 0),
+        );
+        let name = out.text("t");
+        let time = out.block(
+            b"CN",
+            &[pressure, 0, name, 0, 0, 0, 0, 0],
+            &channel_data(CN_TYPE_MASTER, CN_SYNC_TIME, DT_FLOAT_LE, 0, 32, 0, 0),
+        );
+        let mut cg_data = 1u64.to_le_bytes().to_vec();
+        cg_data.extend_from_slice(&[0; 16]);
+        cg_data.extend_from_slice(&6u32.to_le_bytes());
+        cg_data.extend_from_slice(&0u32.to_le_bytes());
+        let cg1 = out.block(b"CG", &[0, time, 0, 0, 0, 0], &cg_data);
+
+        // Group 2: big-endian u8 flag in bit 3 of a byte, virtual master
+        let name = out.text("flag");
+        let mut flag_data = channel_data(CN_TYPE_FIXED, 0, DT_UINT_BE, 0, 1, 0, 0);
+        flag_data[3] = 3; // bit offset
+        let flag = out.block(b"CN", &[0, 0, name, 0, 0, 0, 0, 0], &flag_data);
+        let scale = out.linear_conversion(0.0, 0.5);
+        let name = out.text("index");
+        let virtual_time = out.block(
+            b"CN",
+            &[flag, 0, name, 0, scale, 0, 0, 0],
+            &channel_data(CN_TYPE_VIRTUAL_MASTER, CN_SYNC_TIME, DT_UINT_LE, 0, 0, 0, 0),
+        );
+        let mut cg_data = 2u64.to_le_bytes().to_vec();
+        cg_data.extend_from_slice(&[0; 16]);
+        cg_data.extend_from_slice(&1u32.to_le_bytes());
+        cg_data.extend_from_slice(&0u32.to_le_bytes());
+        let cg2 = out.block(b"CG", &[0, virtual_time, 0, 0, 0, 0], &cg_data);
+        out.set_link(cg1, 0, cg2);
+
+        let mut records = Vec::new();
+        for (i, raw) in [(0u8, 0i16), (1, 50), (2, 100)] {
+            records.push(1);
+            records.extend_from_slice(&(i as f32 * 0.5).to_le_bytes());
+            records.extend_from_slice(&raw.to_le_bytes());
+            records.push(2);
+            records.push(if i == 1 { 0b1000 } else { 0 });
+        }
+        let columns = 2;
+        let rows = records.len() / columns;
+        let mut transposed = Vec::new();
+        for column in 0..columns {
+            for row in 0..rows {
+                transposed.push(records[row * columns + column]);
+            }
+        }
+        transposed.extend_from_slice(&records[rows * columns..]);
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&transposed).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut dz = b"DT".to_vec();
+        dz.extend_from_slice(&[1, 0]);
+        dz.extend_from_slice(&(columns as u32).to_le_bytes());
+        dz.extend_from_slice(&(records.len() as u64).to_le_bytes());
+        dz.extend_from_slice(&(compressed.len() as u64).to_le_bytes());
+        dz.extend_from_slice(&compressed);
+        let dz = out.block(b"DZ", &[], &dz);
+        let dl = {
+            let mut data = 0u8.to_le_bytes().to_vec();
+            data.extend_from_slice(&[0; 3]);
+            data.extend_from_slice(&1u32.to_le_bytes());
+            data.extend_from_slice(&(records.len() as u64).to_le_bytes());
+            out.block(b"DL", &[0, dz], &data)
+        };
+        let dg = out.block(b"DG", &[0, cg1, dl, 0], &[1, 0, 0, 0, 0, 0, 0, 0]);
+        out.set_link(hd, 0, dg);
+
+        let recording = decode_mdf(&out.bytes, "foreign").unwrap();
+        assert_eq!(recording.metadata.name, "foreign");
+        let pressure = &recording.metadata.variables[0];
+        let flag = &recording.metadata.variables[1];
+        assert_eq!(pressure.name, "pressure");
+        assert_eq!(pressure.unit, "bar");
+        assert_eq!(pressure.var_type, VariableType::I16);
+        assert_eq!(flag.var_type, VariableType::Bool);
+
+        // Both groups land on the same 0, 0.5 and 1 s frames
+        assert_eq!(recording.frame_count(), 3);
+        let expected = [(0.0, 0), (25.0, 1), (50.0, 0)];
+        for (frame, (converted, bit)) in recording.frames.iter().zip(expected) {
+            assert_eq!(frame.values[&pressure.id].converted_value, converted);
+            assert_eq!(frame.values[&flag.id].raw_value, RawValue::UInt(bit));
+        }
+        assert_eq!(recording.frames[2].timestamp, Duration::from_secs(1));
+    }
+}
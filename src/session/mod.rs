@@ -7,15 +7,48 @@
 //! # Features
 //!
 //! - Record data collection sessions with full timing information
-//! - Save sessions to disk for later analysis
+//! - Save sessions to disk in a compact, chunked binary format
+//! - Play back large session files lazily, one block at a time
 //! - Play back sessions at original or variable speed
 //! - Seek to specific times within a session
+//! - Re-apply the current converter scripts to a recording's raw values
+//! - Keep markers, regions and notes with a recording and export them as a table
+//! - Trim, split, merge and drop variables from recordings
 //! - Compare recorded sessions with live data
+//! - Overlay several recordings aligned on their start, a marker or a trigger
+//! - Keep the full history of a live session on disk
+//! - Journal recordings in progress to disk and recover them after a crash
+//! - Export and import sessions as MCAP for Foxglove-compatible tools
+//! - Export recordings and live data as Arrow IPC or Parquet tables
+//! - Export and import sessions as ASAM MDF4 for calibration tools
+//! - Import CSV and JSON Lines logs from other tools as recordings
+//! - Stream live data to a CSV, JSON Lines, binary or MCAP persistence file
 
+pub mod columnar;
+pub mod compare;
+pub mod edit;
+pub mod events;
+pub mod format;
+pub mod history;
+pub mod import;
+pub mod journal;
+pub mod mcap;
+pub mod mdf;
+pub mod persistence;
 pub mod player;
+pub mod reconvert;
 pub mod recorder;
 pub mod types;
 
+pub use self::mcap::McapSessionWriter;
+pub use columnar::{ColumnarFormat, ColumnarOptions, ExportLayout};
+pub use compare::{AlignMode, Comparison, ComparedRun, RunStats, StatsDelta};
+pub use events::{MarkerType, RecordingEvent};
+pub use format::{SessionFile, SessionWriter};
+pub use history::{recording_from_history, HistoryStore, VariableHistory};
+pub use import::{ImportLayout, ImportSettings, ImportTable, ParseOptions};
+pub use journal::Journal;
+pub use persistence::PersistenceWriter;
 pub use player::SessionPlayer;
 pub use recorder::SessionRecorder;
 pub use types::{SessionMetadata, SessionRecording, SessionState};
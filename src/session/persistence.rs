@@ -0,0 +1,404 @@
+//! Data persistence writer
+//!
+//! Streams every live sample to the file chosen in the Data Persistence
+//! settings while collection runs. The text formats write one
+//! [`PersistedDataRecord`] per sample:
+//!
+//! - CSV: a header row, then `timestamp_us`, the optional name and address
+//!   columns, `variable_id`, `raw_value` and `converted_value`
+//! - JSON Lines: one serialized [`PersistedDataRecord`] per line
+//!
+//! Binary records are fixed-size and little-endian: timestamp in
+//! microseconds (u64), variable ID (u32), raw value kind (u8: 0 = unsigned,
+//! 1 = signed, 2 = float), raw value bits (8 bytes) and converted value
+//! (f64). They never carry the variable name or address.
+//!
+//! MCAP files use the layout of [`super::mcap`], with each sample as its own
+//! message. MCAP cannot be appended to, so an existing file is replaced.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::{DataPersistenceConfig, PersistedDataRecord, PersistenceFormat};
+use crate::types::{DataPoint, RawValue, Variable};
+
+use super::mcap::McapSessionWriter;
+use super::types::{RecordedFrame, RecordedValue, SessionMetadata};
+
+/// Size of one binary record
+pub const BINARY_RECORD_SIZE: usize = 29;
+
+/// Writer that keeps track of how much it wrote, for the file size limit
+///
+/// The size is shared because the MCAP writer does not hand its writer back
+/// until it finishes.
+struct Counted<W> {
+    inner: W,
+    position: u64,
+    size: Arc<AtomicU64>,
+}
+
+impl<W: Write> Write for Counted<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.position += written as u64;
+        self.size.fetch_max(self.position, Ordering::Relaxed);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write + Seek> Seek for Counted<W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(pos)?;
+        Ok(self.position)
+    }
+}
+
+type FileWriter = Counted<BufWriter<File>>;
+
+enum Output {
+    Csv(FileWriter),
+    JsonLines(FileWriter),
+    Binary(FileWriter),
+    Mcap(Box<McapSessionWriter<FileWriter>>, SessionMetadata),
+}
+
+/// Streams live samples to a persistence file
+pub struct PersistenceWriter {
+    output: Output,
+    /// Bytes written since the file was opened
+    written: Arc<AtomicU64>,
+    /// Name and address of the variables by ID
+    variables: HashMap<u32, (String, u64)>,
+    include_variable_name: bool,
+    include_variable_address: bool,
+    max_file_size: u64,
+    /// Size of the file when it was opened (append mode)
+    initial_size: u64,
+    records: usize,
+    last_timestamp: Duration,
+}
+
+impl std::fmt::Debug for PersistenceWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersistenceWriter")
+            .field("records", &self.records)
+            .field("file_size", &self.file_size())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PersistenceWriter {
+    /// Open the file of `config` for the given variables
+    pub fn create(
+        config: &DataPersistenceConfig,
+        path: &Path,
+        variables: &[Variable],
+    ) -> io::Result<Self> {
+        let append = config.append_mode && config.format != PersistenceFormat::Mcap;
+        let file = if append {
+            OpenOptions::new().create(true).append(true).open(path)?
+        } else {
+            File::create(path)?
+        };
+        let initial_size = file.metadata()?.len();
+        let written = Arc::new(AtomicU64::new(0));
+        let mut writer = Counted {
+            inner: BufWriter::new(file),
+            position: 0,
+            size: Arc::clone(&written),
+        };
+
+        let output = match config.format {
+            PersistenceFormat::Csv => {
+                if initial_size == 0 {
+                    let mut header = vec!["timestamp_us"];
+                    if config.include_variable_name {
+                        header.push("variable_name");
+                    }
+                    if config.include_variable_address {
+                        header.push("variable_address");
+                    }
+                    header.extend(["variable_id", "raw_value", "converted_value"]);
+                    writeln!(writer, "{}", header.join(","))?;
+                }
+                Output::Csv(writer)
+            }
+            PersistenceFormat::JsonLines => Output::JsonLines(writer),
+            PersistenceFormat::Binary => Output::Binary(writer),
+            PersistenceFormat::Mcap => {
+                let name = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "Persisted data".to_string());
+                let mut metadata = SessionMetadata::new(name);
+                metadata.variables = variables.to_vec();
+                let mcap = McapSessionWriter::new(writer, &metadata)?;
+                Output::Mcap(Box::new(mcap), metadata)
+            }
+        };
+
+        Ok(Self {
+            output,
+            written,
+            variables: variables
+                .iter()
+                .map(|v| (v.id, (v.name.clone(), v.address)))
+                .collect(),
+            include_variable_name: config.include_variable_name,
+            include_variable_address: config.include_variable_address,
+            max_file_size: config.max_file_size,
+            initial_size,
+            records: 0,
+            last_timestamp: Duration::ZERO,
+        })
+    }
+
+    /// Number of samples written
+    pub fn record_count(&self) -> usize {
+        self.records
+    }
+
+    /// Current size of the file in bytes
+    pub fn file_size(&self) -> u64 {
+        self.initial_size + self.written.load(Ordering::Relaxed)
+    }
+
+    /// Whether the file reached the maximum size
+    pub fn is_full(&self) -> bool {
+        self.file_size() >= self.max_file_size
+    }
+
+    /// Write one sample
+    pub fn write(&mut self, var_id: u32, point: &DataPoint) -> io::Result<()> {
+        self.records += 1;
+        self.last_timestamp = self.last_timestamp.max(point.timestamp);
+        match &mut self.output {
+            Output::Csv(w) => {
+                let mut row = point.timestamp.as_micros().to_string();
+                if let Some((name, address)) = self.variables.get(&var_id) {
+                    if self.include_variable_name {
+                        row.push(',');
+                        row.push_str(&csv_field(name));
+                    }
+                    if self.include_variable_address {
+                        row.push_str(&format!(",0x{:08X}", address));
+                    }
+                } else {
+                    if self.include_variable_name {
+                        row.push(',');
+                    }
+                    if self.include_variable_address {
+                        row.push(',');
+                    }
+                }
+                writeln!(
+                    w,
+                    "{},{},{},{}",
+                    row,
+                    var_id,
+                    raw_value_text(point.raw_value),
+                    point.converted_value
+                )
+            }
+            Output::JsonLines(w) => {
+                let variable = self.variables.get(&var_id);
+                let record = PersistedDataRecord {
+                    timestamp_us: point.timestamp.as_micros() as u64,
+                    variable_name: variable
+                        .filter(|_| self.include_variable_name)
+                        .map(|(name, _)| name.clone()),
+                    variable_address: variable
+                        .filter(|_| self.include_variable_address)
+                        .map(|(_, address)| *address),
+                    variable_id: var_id,
+                    raw_value: point.raw_value,
+                    converted_value: point.converted_value,
+                };
+                serde_json::to_writer(&mut *w, &record)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                writeln!(w)
+            }
+            Output::Binary(w) => w.write_all(&binary_record(var_id, point)),
+            Output::Mcap(mcap, _) => mcap.write_frame(&RecordedFrame {
+                timestamp: point.timestamp,
+                values: HashMap::from([(var_id, RecordedValue::from(point))]),
+                tag: None,
+            }),
+        }
+    }
+
+    /// Flush buffered samples to disk
+    pub fn flush(&mut self) -> io::Result<()> {
+        match &mut self.output {
+            Output::Csv(w) | Output::JsonLines(w) | Output::Binary(w) => w.flush(),
+            // MCAP chunks are written as they fill
+            Output::Mcap(..) => Ok(()),
+        }
+    }
+
+    /// Finish the file
+    pub fn finish(self) -> io::Result<()> {
+        match self.output {
+            Output::Csv(mut w) | Output::JsonLines(mut w) | Output::Binary(mut w) => w.flush(),
+            Output::Mcap(mcap, mut metadata) => {
+                metadata.duration = self.last_timestamp;
+                metadata.total_data_points = self.records;
+                mcap.finish(&metadata)?.flush()
+            }
+        }
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn raw_value_text(value: RawValue) -> String {
+    match value {
+        RawValue::UInt(v) => v.to_string(),
+        RawValue::Int(v) => v.to_string(),
+        RawValue::Float(v) => v.to_string(),
+    }
+}
+
+fn binary_record(var_id: u32, point: &DataPoint) -> [u8; BINARY_RECORD_SIZE] {
+    let (kind, bits) = match point.raw_value {
+        RawValue::UInt(v) => (0u8, v.to_le_bytes()),
+        RawValue::Int(v) => (1, v.to_le_bytes()),
+        RawValue::Float(v) => (2, v.to_le_bytes()),
+    };
+    let mut record = [0u8; BINARY_RECORD_SIZE];
+    record[0..8].copy_from_slice(&(point.timestamp.as_micros() as u64).to_le_bytes());
+    record[8..12].copy_from_slice(&var_id.to_le_bytes());
+    record[12] = kind;
+    record[13..21].copy_from_slice(&bits);
+    record[21..29].copy_from_slice(&point.converted_value.to_le_bytes());
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::mcap::read_mcap;
+    use crate::types::VariableType;
+
+    fn config(format: PersistenceFormat) -> DataPersistenceConfig {
+        DataPersistenceConfig {
+            enabled: true,
+            include_variable_address: true,
+            format,
+            ..Default::default()
+        }
+    }
+
+    fn write_samples(config: &DataPersistenceConfig, path: &Path, var: &Variable) {
+        let mut writer =
+            PersistenceWriter::create(config, path, std::slice::from_ref(var)).unwrap();
+        for ms in 0..3u64 {
+            let point = DataPoint::with_conversion(
+                Duration::from_millis(ms),
+                RawValue::UInt(ms),
+                ms as f64 * 0.5,
+            );
+            writer.write(var.id, &point).unwrap();
+        }
+        assert_eq!(writer.record_count(), 3);
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_csv_append() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        let var = Variable::new("speed", 0x2000_0010, VariableType::U32);
+        let mut config = config(PersistenceFormat::Csv);
+        write_samples(&config, &path, &var);
+        config.append_mode = true;
+        write_samples(&config, &path, &var);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "timestamp_us,variable_name,variable_address,variable_id,raw_value,converted_value"
+        );
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[2], format!("1000,speed,0x20000010,{},1,0.5", var.id));
+    }
+
+    #[test]
+    fn test_json_lines_and_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let var = Variable::new("speed", 0x2000_0010, VariableType::U32);
+
+        let path = dir.path().join("data.jsonl");
+        write_samples(&config(PersistenceFormat::JsonLines), &path, &var);
+        let text = std::fs::read_to_string(&path).unwrap();
+        let record: PersistedDataRecord =
+            serde_json::from_str(text.lines().nth(2).unwrap()).unwrap();
+        assert_eq!(record.timestamp_us, 2000);
+        assert_eq!(record.variable_name.as_deref(), Some("speed"));
+        assert_eq!(record.variable_address, Some(0x2000_0010));
+        assert_eq!(record.raw_value, RawValue::UInt(2));
+
+        let path = dir.path().join("data.bin");
+        write_samples(&config(PersistenceFormat::Binary), &path, &var);
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes.len(), 3 * BINARY_RECORD_SIZE);
+        let record = &bytes[BINARY_RECORD_SIZE..2 * BINARY_RECORD_SIZE];
+        assert_eq!(u64::from_le_bytes(record[0..8].try_into().unwrap()), 1000);
+        assert_eq!(
+            u32::from_le_bytes(record[8..12].try_into().unwrap()),
+            var.id
+        );
+        assert_eq!(record[12], 0);
+        assert_eq!(f64::from_le_bytes(record[21..29].try_into().unwrap()), 0.5);
+    }
+
+    #[test]
+    fn test_mcap() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bench.mcap");
+        let var = Variable::new("speed", 0x2000_0010, VariableType::U32);
+        write_samples(&config(PersistenceFormat::Mcap), &path, &var);
+
+        let recording = read_mcap(&path).unwrap();
+        assert_eq!(recording.metadata.name, "bench");
+        assert_eq!(recording.metadata.variables[0].name, "speed");
+        assert_eq!(recording.frame_count(), 3);
+        assert_eq!(
+            recording.frames[2].values[&var.id].raw_value,
+            RawValue::UInt(2)
+        );
+    }
+
+    #[test]
+    fn test_file_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.bin");
+        let config = DataPersistenceConfig {
+            max_file_size: 2 * BINARY_RECORD_SIZE as u64,
+            ..config(PersistenceFormat::Binary)
+        };
+        let mut writer = PersistenceWriter::create(&config, &path, &[]).unwrap();
+        let point = DataPoint::new(Duration::ZERO, 1.0);
+        writer.write(1, &point).unwrap();
+        assert!(!writer.is_full());
+        writer.write(1, &point).unwrap();
+        assert!(writer.is_full());
+    }
+}
@@ -1,11 +1,100 @@
 //! Session player for playing back recorded sessions
 
 use std::collections::HashMap;
+use std::io;
+use std::path::Path;
 use std::time::{Duration, Instant};
 
 use crate::types::DataPoint;
 
-use super::types::{SessionRecording, SessionState};
+use super::format::{is_binary_session, SessionFile};
+use super::types::{RecordedFrame, SessionMetadata, SessionRecording, SessionState};
+
+/// Frames being played back: held in memory or read lazily from disk
+#[derive(Debug)]
+enum PlaybackSource {
+    Memory(SessionRecording),
+    File(SessionFile),
+}
+
+impl PlaybackSource {
+    fn metadata(&self) -> &SessionMetadata {
+        match self {
+            Self::Memory(recording) => &recording.metadata,
+            Self::File(file) => file.metadata(),
+        }
+    }
+
+    fn duration(&self) -> Duration {
+        match self {
+            Self::Memory(recording) => recording.duration(),
+            Self::File(file) => file.duration(),
+        }
+    }
+
+    fn frame_count(&self) -> usize {
+        match self {
+            Self::Memory(recording) => recording.frame_count(),
+            Self::File(file) => file.frame_count(),
+        }
+    }
+
+    fn find_frame_at(&self, time: Duration) -> Option<usize> {
+        match self {
+            Self::Memory(recording) => recording.find_frame_at(time),
+            Self::File(file) => file.find_frame_at(time).unwrap_or_else(|e| {
+                tracing::error!("Failed to read session block: {}", e);
+                None
+            }),
+        }
+    }
+
+    fn timestamp(&self, index: usize) -> Option<Duration> {
+        match self {
+            Self::Memory(recording) => recording.frames.get(index).map(|f| f.timestamp),
+            Self::File(file) => file.frame(index).ok().flatten().map(|f| f.timestamp),
+        }
+    }
+
+    /// Visit the frames in `range` in order, one block at a time for files
+    fn for_each_frame(&self, range: std::ops::Range<usize>, mut f: impl FnMut(&RecordedFrame)) {
+        match self {
+            Self::Memory(recording) => {
+                let end = range.end.min(recording.frames.len());
+                recording.frames[range.start.min(end)..end]
+                    .iter()
+                    .for_each(f);
+            }
+            Self::File(file) => {
+                let end = range.end.min(file.frame_count());
+                let mut start = range.start;
+                while start < end {
+                    let chunk_end = (start + super::format::BLOCK_FRAMES).min(end);
+                    match file.frames(start..chunk_end) {
+                        Ok(frames) => frames.iter().for_each(&mut f),
+                        Err(e) => {
+                            tracing::error!("Failed to read session block: {}", e);
+                            return;
+                        }
+                    }
+                    start = chunk_end;
+                }
+            }
+        }
+    }
+}
+
+fn push_frame_points(result: &mut HashMap<u32, Vec<DataPoint>>, frame: &RecordedFrame) {
+    for (var_id, value) in &frame.values {
+        let point = DataPoint {
+            timestamp: frame.timestamp,
+            raw_value: value.raw_value,
+            converted_value: value.converted_value,
+        };
+
+        result.entry(*var_id).or_default().push(point);
+    }
+}
 
 /// Session player for playing back recorded sessions
 #[derive(Debug)]
@@ -13,7 +102,7 @@ pub struct SessionPlayer {
     /// Current playback state
     state: SessionState,
     /// The recording being played
-    recording: Option<SessionRecording>,
+    source: Option<PlaybackSource>,
     /// Current playback position (frame index)
     current_frame: usize,
     /// Current playback time
@@ -39,7 +128,7 @@ impl SessionPlayer {
     pub fn new() -> Self {
         Self {
             state: SessionState::Idle,
-            recording: None,
+            source: None,
             current_frame: 0,
             current_time: Duration::ZERO,
             playback_speed: 1.0,
@@ -66,12 +155,34 @@ impl SessionPlayer {
 
     /// Check if a recording is loaded
     pub fn has_recording(&self) -> bool {
-        self.recording.is_some()
+        self.source.is_some()
     }
 
-    /// Get the loaded recording
+    /// Get the loaded recording if it is held in memory
     pub fn recording(&self) -> Option<&SessionRecording> {
-        self.recording.as_ref()
+        match self.source.as_ref()? {
+            PlaybackSource::Memory(recording) => Some(recording),
+            PlaybackSource::File(_) => None,
+        }
+    }
+
+    /// Read the whole loaded recording into memory
+    pub fn read_recording(&self) -> io::Result<Option<SessionRecording>> {
+        match &self.source {
+            None => Ok(None),
+            Some(PlaybackSource::Memory(recording)) => Ok(Some(recording.clone())),
+            Some(PlaybackSource::File(file)) => file.read_all().map(Some),
+        }
+    }
+
+    /// Get the metadata of the loaded recording
+    pub fn metadata(&self) -> Option<&SessionMetadata> {
+        self.source.as_ref().map(|s| s.metadata())
+    }
+
+    /// Get the number of frames in the loaded recording
+    pub fn frame_count(&self) -> usize {
+        self.source.as_ref().map(|s| s.frame_count()).unwrap_or(0)
     }
 
     /// Get current playback time
@@ -112,9 +223,9 @@ impl SessionPlayer {
 
     /// Get total duration
     pub fn total_duration(&self) -> Duration {
-        self.recording
+        self.source
             .as_ref()
-            .map(|r| r.duration())
+            .map(|s| s.duration())
             .unwrap_or(Duration::ZERO)
     }
 
@@ -129,7 +240,25 @@ impl SessionPlayer {
 
     /// Load a recording for playback
     pub fn load(&mut self, recording: SessionRecording) {
-        self.recording = Some(recording);
+        self.set_source(PlaybackSource::Memory(recording));
+    }
+
+    /// Load a session file for playback
+    ///
+    /// Binary session files are read lazily, one block at a time; JSON
+    /// sessions are loaded into memory.
+    pub fn load_file(&mut self, path: &Path) -> io::Result<()> {
+        let source = if is_binary_session(path)? {
+            PlaybackSource::File(SessionFile::open(path)?)
+        } else {
+            PlaybackSource::Memory(SessionRecording::load_from_file(path)?)
+        };
+        self.set_source(source);
+        Ok(())
+    }
+
+    fn set_source(&mut self, source: PlaybackSource) {
+        self.source = Some(source);
         self.current_frame = 0;
         self.current_time = Duration::ZERO;
         self.playback_start = None;
@@ -139,7 +268,7 @@ impl SessionPlayer {
 
     /// Unload the current recording
     pub fn unload(&mut self) {
-        self.recording = None;
+        self.source = None;
         self.current_frame = 0;
         self.current_time = Duration::ZERO;
         self.playback_start = None;
@@ -149,7 +278,7 @@ impl SessionPlayer {
 
     /// Start or resume playback
     pub fn play(&mut self) {
-        if self.recording.is_none() {
+        if self.source.is_none() {
             return;
         }
 
@@ -184,19 +313,19 @@ impl SessionPlayer {
 
     /// Seek to a specific time
     pub fn seek(&mut self, time: Duration) {
-        let Some(ref recording) = self.recording else {
+        let Some(ref source) = self.source else {
             return;
         };
 
         // Clamp to recording duration
-        let duration = recording.duration();
+        let duration = source.duration();
         let time = if time > duration { duration } else { time };
 
         self.current_time = time;
         self.playback_offset = time;
 
         // Find the frame at this time
-        if let Some(idx) = recording.find_frame_at(time) {
+        if let Some(idx) = source.find_frame_at(time) {
             self.current_frame = idx;
         }
 
@@ -215,26 +344,30 @@ impl SessionPlayer {
 
     /// Step forward by one frame
     pub fn step_forward(&mut self) {
-        let Some(ref recording) = self.recording else {
+        let Some(ref source) = self.source else {
             return;
         };
 
-        if self.current_frame < recording.frames.len().saturating_sub(1) {
+        if self.current_frame < source.frame_count().saturating_sub(1) {
             self.current_frame += 1;
-            self.current_time = recording.frames[self.current_frame].timestamp;
+            if let Some(timestamp) = source.timestamp(self.current_frame) {
+                self.current_time = timestamp;
+            }
             self.playback_offset = self.current_time;
         }
     }
 
     /// Step backward by one frame
     pub fn step_backward(&mut self) {
-        let Some(ref recording) = self.recording else {
+        let Some(ref source) = self.source else {
             return;
         };
 
         if self.current_frame > 0 {
             self.current_frame -= 1;
-            self.current_time = recording.frames[self.current_frame].timestamp;
+            if let Some(timestamp) = source.timestamp(self.current_frame) {
+                self.current_time = timestamp;
+            }
             self.playback_offset = self.current_time;
         }
     }
@@ -249,24 +382,23 @@ impl SessionPlayer {
         }
     }
 
-    /// Update playback state (call this each frame)
-    /// Returns data points that should be displayed up to current time
-    pub fn update(&mut self) -> HashMap<u32, Vec<DataPoint>> {
-        let mut result = HashMap::new();
-
+    /// Advance the playback time without collecting data (call this each frame)
+    ///
+    /// Returns `false` when not playing.
+    pub fn advance(&mut self) -> bool {
         if !self.is_playing() {
-            return result;
+            return false;
         }
 
-        if self.recording.is_none() {
-            return result;
-        }
+        let Some(ref source) = self.source else {
+            return false;
+        };
+        let duration = source.duration();
 
         // Update current time
         self.update_playback_time();
 
         // Check if we've reached the end
-        let duration = self.recording.as_ref().unwrap().duration();
         if self.current_time >= duration {
             if self.loop_playback {
                 // Loop back to beginning
@@ -280,27 +412,25 @@ impl SessionPlayer {
                 self.state = SessionState::Stopped;
             }
         }
+        true
+    }
 
-        // Find frames to return (from last frame to current time)
-        let recording = self.recording.as_ref().unwrap();
-        if let Some(end_idx) = recording.find_frame_at(self.current_time) {
-            // Collect all frames from current to end_idx
-            for idx in self.current_frame..=end_idx {
-                if idx >= recording.frames.len() {
-                    break;
-                }
+    /// Update playback state (call this each frame)
+    /// Returns data points that should be displayed up to current time
+    pub fn update(&mut self) -> HashMap<u32, Vec<DataPoint>> {
+        let mut result = HashMap::new();
 
-                let frame = &recording.frames[idx];
-                for (var_id, value) in &frame.values {
-                    let point = DataPoint {
-                        timestamp: frame.timestamp,
-                        raw_value: value.raw_value,
-                        converted_value: value.converted_value,
-                    };
+        if !self.advance() {
+            return result;
+        }
 
-                    result.entry(*var_id).or_insert_with(Vec::new).push(point);
-                }
-            }
+        // Find frames to return (from last frame to current time)
+        let source = self.source.as_ref().unwrap();
+        if let Some(end_idx) = source.find_frame_at(self.current_time) {
+            // Collect all frames from current to end_idx
+            source.for_each_frame(self.current_frame..end_idx + 1, |frame| {
+                push_frame_points(&mut result, frame);
+            });
 
             self.current_frame = end_idx;
         }
@@ -312,26 +442,39 @@ impl SessionPlayer {
     pub fn get_data_until_now(&self) -> HashMap<u32, Vec<DataPoint>> {
         let mut result = HashMap::new();
 
-        let Some(ref recording) = self.recording else {
+        let Some(ref source) = self.source else {
             return result;
         };
 
         // Get all frames up to current time
-        for frame in &recording.frames {
-            if frame.timestamp > self.current_time {
-                break;
+        let Some(end_idx) = source.find_frame_at(self.current_time) else {
+            return result;
+        };
+        source.for_each_frame(0..end_idx + 1, |frame| {
+            if frame.timestamp <= self.current_time {
+                push_frame_points(&mut result, frame);
             }
+        });
 
-            for (var_id, value) in &frame.values {
-                let point = DataPoint {
-                    timestamp: frame.timestamp,
-                    raw_value: value.raw_value,
-                    converted_value: value.converted_value,
-                };
+        result
+    }
 
-                result.entry(*var_id).or_insert_with(Vec::new).push(point);
+    /// Get the data points recorded after `from` and up to `to`
+    pub fn data_between(&self, from: Duration, to: Duration) -> HashMap<u32, Vec<DataPoint>> {
+        let mut result = HashMap::new();
+
+        let Some(ref source) = self.source else {
+            return result;
+        };
+        let (Some(start), Some(end)) = (source.find_frame_at(from), source.find_frame_at(to))
+        else {
+            return result;
+        };
+        source.for_each_frame(start..end + 1, |frame| {
+            if frame.timestamp > from && frame.timestamp <= to {
+                push_frame_points(&mut result, frame);
             }
-        }
+        });
 
         result
     }
@@ -405,6 +548,45 @@ mod tests {
         assert_eq!(player.current_frame(), 1);
     }
 
+    #[test]
+    fn test_load_binary_file_lazily() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.dvs");
+        let mut recording = create_test_recording();
+        for frame in &mut recording.frames {
+            frame.values.insert(
+                1,
+                crate::session::types::RecordedValue {
+                    raw_value: crate::types::RawValue::UInt(1),
+                    converted_value: frame.timestamp.as_secs_f64(),
+                },
+            );
+        }
+        recording.finalize();
+        recording.save_to_file(&path).unwrap();
+
+        let mut player = SessionPlayer::new();
+        player.load_file(&path).unwrap();
+        assert!(player.has_recording());
+        assert!(player.recording().is_none());
+        assert_eq!(player.frame_count(), 10);
+        assert_eq!(player.total_duration(), Duration::from_millis(900));
+
+        player.seek(Duration::from_millis(450));
+        assert_eq!(player.current_frame(), 4);
+        player.step_forward();
+        assert_eq!(player.current_time(), Duration::from_millis(500));
+        assert_eq!(player.get_data_until_now()[&1].len(), 6);
+
+        // Frames after the first time and up to the second
+        let data = player.data_between(Duration::from_millis(250), Duration::from_millis(700));
+        let times: Vec<u128> = data[&1].iter().map(|p| p.timestamp.as_millis()).collect();
+        assert_eq!(times, [300, 400, 500, 600, 700]);
+        assert!(player
+            .data_between(Duration::from_millis(700), Duration::from_millis(750))
+            .is_empty());
+    }
+
     #[test]
     fn test_playback_speed() {
         let mut player = SessionPlayer::new();
@@ -0,0 +1,146 @@
+//! Re-apply converter scripts to a recording offline
+//!
+//! A recording keeps the raw value of every sample next to the converted
+//! one, so converters fixed after a capture can be re-evaluated without
+//! re-running the hardware. Frames are fed through a fresh
+//! [`ConverterEngine`] in time order, exactly as the backend worker feeds
+//! live reads, so stateful converters (derivatives, filters, `state()`
+//! slots) see the same sequence of samples they saw during the capture.
+//!
+//! Recorded variables are matched to the current ones by id and name, then
+//! by name alone, since ids are not stable across projects. Variables with
+//! no current match keep their recorded converted values.
+
+use std::collections::HashMap;
+
+use crate::backend::converter_engine::ConverterEngine;
+use crate::types::Variable;
+
+use super::types::SessionRecording;
+
+/// Map recorded variable ids to the ids of the matching current variables
+pub fn match_variables(recorded: &[Variable], current: &[Variable]) -> HashMap<u32, u32> {
+    recorded
+        .iter()
+        .filter_map(|var| {
+            current
+                .iter()
+                .find(|c| c.id == var.id && c.name == var.name)
+                .or_else(|| current.iter().find(|c| c.name == var.name))
+                .map(|c| (var.id, c.id))
+        })
+        .collect()
+}
+
+/// Copy of `recording` with the current converters applied to its raw values
+///
+/// Matched variables take the current converter script (or none, in which
+/// case the converted value is the raw value) in the copy's metadata too.
+pub fn reconvert(recording: &SessionRecording, current: &[Variable]) -> SessionRecording {
+    let matched = match_variables(&recording.metadata.variables, current);
+    let by_id: HashMap<u32, &Variable> = current.iter().map(|v| (v.id, v)).collect();
+
+    let mut engine = ConverterEngine::new();
+    for (&recorded_id, current_id) in &matched {
+        let var = by_id[current_id];
+        engine.update_converter(recorded_id, &var.name, var.converter_script.clone());
+    }
+
+    let mut result = recording.clone();
+    result.metadata.name = format!("{} (reconverted)", recording.metadata.name);
+    for var in &mut result.metadata.variables {
+        if let Some(current_id) = matched.get(&var.id) {
+            var.converter_script = by_id[current_id].converter_script.clone();
+        }
+    }
+
+    let mut samples = Vec::new();
+    for frame in &mut result.frames {
+        samples.clear();
+        samples.extend(
+            frame
+                .values
+                .iter()
+                .filter(|(id, _)| matched.contains_key(id))
+                .map(|(id, value)| (*id, frame.timestamp, value.raw_value)),
+        );
+        for (id, _, _, converted) in engine.apply_converters(&samples) {
+            if let Some(value) = frame.values.get_mut(&id) {
+                value.converted_value = converted;
+            }
+        }
+    }
+    result.finalize();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::types::{RecordedFrame, RecordedValue, SessionMetadata};
+    use crate::types::{RawValue, VariableType};
+    use std::time::Duration;
+
+    #[test]
+    fn test_reconvert_uses_current_scripts() {
+        let recorded =
+            Variable::new("adc", 0x2000_0000, VariableType::U16).with_converter("value * 100.0");
+        let other = Variable::new("flag", 0x2000_0004, VariableType::U8);
+
+        let mut metadata = SessionMetadata::new("bench");
+        metadata.variables = vec![recorded.clone(), other.clone()];
+        let mut recording = SessionRecording::with_metadata(metadata);
+        for i in 0..4u64 {
+            let mut values = HashMap::new();
+            values.insert(
+                recorded.id,
+                RecordedValue {
+                    raw_value: RawValue::UInt(i * 10),
+                    converted_value: (i * 1000) as f64,
+                },
+            );
+            values.insert(
+                other.id,
+                RecordedValue {
+                    raw_value: RawValue::UInt(1),
+                    converted_value: 7.0,
+                },
+            );
+            recording.frames.push(RecordedFrame {
+                timestamp: Duration::from_millis(i * 100),
+                values,
+                tag: None,
+            });
+        }
+
+        // The fixed script lives on a new variable with the same name
+        let current = [Variable::new("adc", 0x2000_0000, VariableType::U16)
+            .with_converter("if has_prev() { value / 10.0 + prev() } else { value / 10.0 }")];
+        let result = reconvert(&recording, &current);
+
+        assert_eq!(
+            match_variables(&recording.metadata.variables, &current),
+            HashMap::from([(recorded.id, current[0].id)])
+        );
+        let adc: Vec<f64> = result
+            .frames
+            .iter()
+            .map(|f| f.values[&recorded.id].converted_value)
+            .collect();
+        assert_eq!(adc, [0.0, 1.0, 3.0, 6.0]);
+        assert!(result
+            .frames
+            .iter()
+            .all(|f| f.values[&other.id].converted_value == 7.0));
+        assert_eq!(result.metadata.name, "bench (reconverted)");
+        assert_eq!(
+            result.metadata.variables[0].converter_script,
+            current[0].converter_script
+        );
+        // The original is untouched
+        assert_eq!(
+            recording.frames[3].values[&recorded.id].converted_value,
+            3000.0
+        );
+    }
+}
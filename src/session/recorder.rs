@@ -1,10 +1,16 @@
 //! Session recorder for capturing data collection sessions
+//!
+//! With a journal directory set, in-memory recordings are also journaled to
+//! disk (see [`journal`](super::journal)) so they survive a crash.
 
 use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use crate::types::{DataPoint, Variable, VariableData};
 
+use super::journal::Journal;
 use super::types::{RecordedFrame, RecordedValue, SessionMetadata, SessionRecording, SessionState};
 
 /// Session recorder for capturing data collection sessions
@@ -22,6 +28,15 @@ pub struct SessionRecorder {
     sample_interval: Duration,
     /// Last recorded time for each variable
     last_recorded: HashMap<u32, Duration>,
+    /// Session file being written: the output file, or the journal of an
+    /// in-memory recording
+    writer: Option<Journal>,
+    /// Path of the file being recorded to
+    output_path: Option<PathBuf>,
+    /// Directory to journal in-memory recordings to
+    journal_dir: Option<PathBuf>,
+    /// Timestamp of the last frame written to the file
+    last_written: Duration,
 }
 
 impl Default for SessionRecorder {
@@ -40,6 +55,10 @@ impl SessionRecorder {
             max_frames: 0,                              // Unlimited by default
             sample_interval: Duration::from_millis(10), // 100 Hz max recording rate
             last_recorded: HashMap::new(),
+            writer: None,
+            output_path: None,
+            journal_dir: None,
+            last_written: Duration::ZERO,
         }
     }
 
@@ -61,6 +80,22 @@ impl SessionRecorder {
         self.sample_interval = interval;
     }
 
+    /// Journal in-memory recordings to `dir`, or stop journaling them
+    ///
+    /// The journal is started with the first frame, so it carries the
+    /// variables set after [`start_recording`](Self::start_recording).
+    pub fn set_journal_dir(&mut self, dir: Option<PathBuf>) {
+        self.journal_dir = dir;
+    }
+
+    /// Path of the journal of the in-memory recording, if any
+    pub fn journal_path(&self) -> Option<&Path> {
+        match (&self.writer, &self.output_path) {
+            (Some(journal), None) => Some(journal.path()),
+            _ => None,
+        }
+    }
+
     /// Get current state
     pub fn state(&self) -> SessionState {
         self.state
@@ -72,42 +107,147 @@ impl SessionRecorder {
     }
 
     /// Get the current recording
+    ///
+    /// When recording to a file, only the metadata is kept in memory.
     pub fn recording(&self) -> &SessionRecording {
         &self.recording
     }
 
+    /// Path of the file being recorded to, if any
+    pub fn output_path(&self) -> Option<&Path> {
+        self.output_path.as_deref()
+    }
+
     /// Take the recording (consumes it)
     pub fn take_recording(&mut self) -> SessionRecording {
         self.state = SessionState::Idle;
         self.start_time = None;
         self.last_recorded.clear();
+        self.output_path = None;
         std::mem::take(&mut self.recording)
     }
 
     /// Start a new recording
     pub fn start_recording(&mut self, metadata: SessionMetadata) {
+        self.close_journal();
+        self.writer = None;
+        self.output_path = None;
         self.recording = SessionRecording::with_metadata(metadata);
         self.start_time = Some(Instant::now());
         self.last_recorded.clear();
         self.state = SessionState::Recording;
     }
 
+    /// Start a new recording written incrementally to a binary session file
+    ///
+    /// Frames are compressed into blocks as they fill up instead of being
+    /// kept in memory, so the recording can run for as long as disk allows.
+    pub fn start_recording_to_file(
+        &mut self,
+        metadata: SessionMetadata,
+        path: &Path,
+    ) -> io::Result<()> {
+        let writer = Journal::create(path, &metadata)?;
+        self.start_recording(metadata);
+        self.writer = Some(writer);
+        self.output_path = Some(path.to_path_buf());
+        self.last_written = Duration::ZERO;
+        Ok(())
+    }
+
     /// Stop recording
+    ///
+    /// The journal of an in-memory recording is removed.
     pub fn stop_recording(&mut self) {
         if self.state == SessionState::Recording {
-            self.recording.finalize();
+            match self.writer.take() {
+                Some(writer) if self.output_path.is_some() => {
+                    self.recording.metadata.duration = self.last_written;
+                    self.recording.metadata.total_data_points = writer.frame_count();
+                    if let Err(e) = writer.finish(&self.recording.metadata) {
+                        tracing::error!("Failed to finish session file: {}", e);
+                    }
+                }
+                journal => {
+                    self.writer = journal;
+                    self.close_journal();
+                    self.recording.finalize();
+                }
+            }
             self.state = SessionState::Stopped;
         }
     }
 
     /// Cancel recording (discard data)
     pub fn cancel_recording(&mut self) {
+        self.close_journal();
+        self.writer = None;
+        if let Some(path) = self.output_path.take() {
+            if let Err(e) = std::fs::remove_file(&path) {
+                tracing::warn!("Failed to remove cancelled session {:?}: {}", path, e);
+            }
+        }
         self.recording = SessionRecording::new();
         self.start_time = None;
         self.last_recorded.clear();
         self.state = SessionState::Idle;
     }
 
+    /// Remove the journal of an in-memory recording
+    fn close_journal(&mut self) {
+        if self.output_path.is_some() {
+            return;
+        }
+        if let Some(journal) = self.writer.take() {
+            if let Err(e) = journal.remove() {
+                tracing::warn!("Failed to remove recording journal: {}", e);
+            }
+        }
+    }
+
+    /// Copy a frame of an in-memory recording to its journal
+    ///
+    /// The journal is started with the first frame.
+    fn journal_frame(&mut self, frame: &RecordedFrame) {
+        if self.writer.is_none() && self.recording.frames.is_empty() {
+            if let Some(dir) = &self.journal_dir {
+                match Journal::create_in(dir, &self.recording.metadata) {
+                    Ok(journal) => self.writer = Some(journal),
+                    Err(e) => tracing::warn!("Failed to start recording journal: {}", e),
+                }
+            }
+        }
+        let Some(journal) = self.writer.as_mut() else {
+            return;
+        };
+        if let Err(e) = journal.write_frame(frame.clone()) {
+            tracing::warn!(
+                "Failed to write recording journal, continuing without: {}",
+                e
+            );
+            self.close_journal();
+        }
+    }
+
+    /// Store a frame in memory or append it to the session file
+    fn push_frame(&mut self, frame: RecordedFrame) {
+        if self.output_path.is_none() {
+            self.journal_frame(&frame);
+            self.recording.frames.push(frame);
+            return;
+        }
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        let timestamp = frame.timestamp;
+        if let Err(e) = writer.write_frame(frame) {
+            tracing::error!("Failed to write session frame, stopping recording: {}", e);
+            self.stop_recording();
+            return;
+        }
+        self.last_written = self.last_written.max(timestamp);
+    }
+
     /// Record a frame of data from all variables
     pub fn record_frame(&mut self, variable_data: &HashMap<u32, VariableData>) {
         if !self.is_recording() {
@@ -121,7 +261,7 @@ impl SessionRecorder {
         let current_time = start_time.elapsed();
 
         // Check max frames limit
-        if self.max_frames > 0 && self.recording.frames.len() >= self.max_frames {
+        if self.max_frames > 0 && self.frame_count() >= self.max_frames {
             return;
         }
 
@@ -157,11 +297,34 @@ impl SessionRecorder {
 
         // Only add frame if we have new data
         if any_new_data {
-            self.recording.frames.push(RecordedFrame {
+            self.push_frame(RecordedFrame {
                 timestamp: current_time,
                 values: frame_values,
                 tag: None,
             });
+        } else {
+            self.checkpoint_if_due();
+        }
+    }
+
+    /// Write buffered frames to the session file if a checkpoint is due
+    ///
+    /// Called while no new data arrives, so frames buffered before a lull
+    /// still reach the disk.
+    pub fn checkpoint_if_due(&mut self) {
+        if let Some(writer) = self.writer.as_mut() {
+            if let Err(e) = writer.checkpoint_if_due() {
+                tracing::warn!("Failed to checkpoint session file: {}", e);
+            }
+        }
+    }
+
+    /// Write buffered frames to the session file now
+    pub fn checkpoint(&mut self) {
+        if let Some(writer) = self.writer.as_mut() {
+            if let Err(e) = writer.checkpoint() {
+                tracing::warn!("Failed to checkpoint session file: {}", e);
+            }
         }
     }
 
@@ -172,7 +335,7 @@ impl SessionRecorder {
         }
 
         // Check max frames limit
-        if self.max_frames > 0 && self.recording.frames.len() >= self.max_frames {
+        if self.max_frames > 0 && self.frame_count() >= self.max_frames {
             return;
         }
 
@@ -180,7 +343,7 @@ impl SessionRecorder {
         let should_record = self
             .last_recorded
             .get(&var_id)
-            .map(|last| elapsed - *last >= self.sample_interval)
+            .map(|last| elapsed.saturating_sub(*last) >= self.sample_interval)
             .unwrap_or(true);
 
         if !should_record {
@@ -193,7 +356,7 @@ impl SessionRecorder {
         let mut values = HashMap::new();
         values.insert(var_id, RecordedValue::from(point));
 
-        self.recording.frames.push(RecordedFrame {
+        self.push_frame(RecordedFrame {
             timestamp: elapsed,
             values,
             tag: None,
@@ -211,7 +374,10 @@ impl SessionRecorder {
 
     /// Get number of recorded frames
     pub fn frame_count(&self) -> usize {
-        self.recording.frames.len()
+        match (&self.writer, &self.output_path) {
+            (Some(writer), Some(_)) => writer.frame_count(),
+            _ => self.recording.frames.len(),
+        }
     }
 
     /// Initialize recording variables from config
@@ -258,4 +424,68 @@ mod tests {
         assert_eq!(recorder.state(), SessionState::Idle);
         assert!(recorder.recording().is_empty());
     }
+
+    #[test]
+    fn test_record_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("live.dvs");
+        let mut recorder = SessionRecorder::with_sample_interval(Duration::ZERO);
+        recorder
+            .start_recording_to_file(SessionMetadata::new("Live"), &path)
+            .unwrap();
+
+        let point = DataPoint {
+            timestamp: Duration::ZERO,
+            raw_value: crate::types::RawValue::UInt(3),
+            converted_value: 3.0,
+        };
+        for i in 0..10 {
+            recorder.record_point(1, &point, Duration::from_millis(i * 10));
+        }
+        assert_eq!(recorder.frame_count(), 10);
+        assert!(recorder.recording().is_empty());
+        recorder.stop_recording();
+
+        let recording = SessionRecording::load_from_file(&path).unwrap();
+        assert_eq!(recording.metadata.name, "Live");
+        assert_eq!(recording.frame_count(), 10);
+        assert_eq!(recording.metadata.total_data_points, 10);
+        assert_eq!(recording.metadata.duration, Duration::from_millis(90));
+    }
+
+    #[test]
+    fn test_journal_follows_in_memory_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = SessionRecorder::with_sample_interval(Duration::ZERO);
+        recorder.set_journal_dir(Some(dir.path().to_path_buf()));
+        recorder.start_recording(SessionMetadata::new("Bench"));
+        recorder.set_variables(&[Variable::new(
+            "speed",
+            0x2000_0000,
+            crate::types::VariableType::F32,
+        )]);
+        assert!(recorder.journal_path().is_none());
+
+        let point = DataPoint {
+            timestamp: Duration::ZERO,
+            raw_value: crate::types::RawValue::UInt(3),
+            converted_value: 3.0,
+        };
+        for i in 0..5 {
+            recorder.record_point(1, &point, Duration::from_millis(i * 10));
+        }
+        let journal = recorder.journal_path().unwrap().to_path_buf();
+        assert_eq!(recorder.frame_count(), 5);
+        assert_eq!(
+            super::super::journal::journal_metadata(&journal)
+                .unwrap()
+                .variables[0]
+                .name,
+            "speed"
+        );
+
+        recorder.stop_recording();
+        assert!(!journal.exists());
+        assert_eq!(recorder.take_recording().frame_count(), 5);
+    }
 }
@@ -1,10 +1,13 @@
 //! Session data types
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
-use crate::types::{DataPoint, Variable};
+use crate::analysis::MinMaxPyramid;
+use crate::types::{DataPoint, RawValue, Variable};
+
+use super::events::RecordingEvent;
 
 /// State of session recording/playback
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -76,6 +79,9 @@ pub struct SessionMetadata {
     pub total_data_points: usize,
     /// Variables that were recorded
     pub variables: Vec<Variable>,
+    /// Markers, regions and notes, sorted by time
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<RecordingEvent>,
 }
 
 impl Default for SessionMetadata {
@@ -90,6 +96,7 @@ impl Default for SessionMetadata {
             elf_path: None,
             total_data_points: 0,
             variables: Vec::new(),
+            events: Vec::new(),
         }
     }
 }
@@ -122,11 +129,26 @@ pub struct RecordedFrame {
     pub tag: Option<String>,
 }
 
+/// Frame at a time offset in nanoseconds, created if missing
+///
+/// Importers collect samples here so those sharing a time stamp end up in
+/// one frame.
+pub(crate) fn frame_at(
+    frames: &mut BTreeMap<u64, RecordedFrame>,
+    offset: u64,
+) -> &mut RecordedFrame {
+    frames.entry(offset).or_insert_with(|| RecordedFrame {
+        timestamp: Duration::from_nanos(offset),
+        values: HashMap::new(),
+        tag: None,
+    })
+}
+
 /// A recorded value for a single variable at a point in time
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordedValue {
-    /// Raw value read from memory
-    pub raw_value: f64,
+    /// Raw value read from memory (exact for integer types)
+    pub raw_value: RawValue,
     /// Converted value (after script processing)
     pub converted_value: f64,
 }
@@ -205,6 +227,23 @@ impl SessionRecording {
             .collect()
     }
 
+    /// Build the min/max level-of-detail pyramid of one variable
+    ///
+    /// Lets long recordings be plotted zoomed out without walking every frame.
+    pub fn lod_pyramid(&self, var_id: u32) -> MinMaxPyramid {
+        let mut pyramid = MinMaxPyramid::new();
+        for frame in &self.frames {
+            if let Some(value) = frame.values.get(&var_id) {
+                pyramid.push(&DataPoint {
+                    timestamp: frame.timestamp,
+                    raw_value: value.raw_value,
+                    converted_value: value.converted_value,
+                });
+            }
+        }
+        pyramid
+    }
+
     /// Find the frame index at or before a given time
     pub fn find_frame_at(&self, time: Duration) -> Option<usize> {
         if self.frames.is_empty() {
@@ -220,15 +259,46 @@ impl SessionRecording {
         }
     }
 
-    /// Save recording to a file (JSON format)
+    /// Save recording to a file in the binary session format
     pub fn save_to_file(&self, path: &std::path::Path) -> Result<(), std::io::Error> {
+        let file = std::fs::File::create(path)?;
+        let mut writer =
+            super::format::SessionWriter::new(std::io::BufWriter::new(file), &self.metadata)?;
+        for frame in &self.frames {
+            writer.write_frame(frame.clone())?;
+        }
+        writer.finish(&self.metadata)?;
+        Ok(())
+    }
+
+    /// Export recording to a file as pretty-printed JSON
+    pub fn save_json_to_file(&self, path: &std::path::Path) -> Result<(), std::io::Error> {
         let json = serde_json::to_string_pretty(self)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
         std::fs::write(path, json)
     }
 
-    /// Load recording from a file
+    /// Export recording to a file as MCAP
+    pub fn save_mcap_to_file(&self, path: &std::path::Path) -> Result<(), std::io::Error> {
+        super::mcap::write_mcap(self, path)
+    }
+
+    /// Export recording to a file as ASAM MDF4
+    pub fn save_mdf_to_file(&self, path: &std::path::Path) -> Result<(), std::io::Error> {
+        super::mdf::write_mdf(self, path)
+    }
+
+    /// Load recording from a file, either binary, MCAP, MDF4 or JSON
     pub fn load_from_file(path: &std::path::Path) -> Result<Self, std::io::Error> {
+        if super::format::is_binary_session(path)? {
+            return super::format::SessionFile::open(path)?.read_all();
+        }
+        if super::mcap::is_mcap_file(path)? {
+            return super::mcap::read_mcap(path);
+        }
+        if super::mdf::is_mdf_file(path)? {
+            return super::mdf::read_mdf(path);
+        }
         let json = std::fs::read_to_string(path)?;
         serde_json::from_str(&json)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
@@ -241,6 +311,54 @@ impl SessionRecording {
     }
 }
 
+/// Builds recordings for the export format tests
+#[cfg(test)]
+pub(crate) struct RecordingBuilder {
+    recording: SessionRecording,
+}
+
+#[cfg(test)]
+impl RecordingBuilder {
+    /// Start an empty recording
+    pub fn new(metadata: SessionMetadata) -> Self {
+        Self {
+            recording: SessionRecording::with_metadata(metadata),
+        }
+    }
+
+    /// Append a frame of `(variable id, raw value, converted value)` samples
+    pub fn frame(
+        &mut self,
+        timestamp: Duration,
+        values: &[(u32, RawValue, f64)],
+        tag: Option<&str>,
+    ) {
+        let values = values
+            .iter()
+            .map(|&(id, raw_value, converted_value)| {
+                (
+                    id,
+                    RecordedValue {
+                        raw_value,
+                        converted_value,
+                    },
+                )
+            })
+            .collect();
+        self.recording.frames.push(RecordedFrame {
+            timestamp,
+            values,
+            tag: tag.map(str::to_string),
+        });
+    }
+
+    /// Finalize and return the recording
+    pub fn build(mut self) -> SessionRecording {
+        self.recording.finalize();
+        self.recording
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,4 +407,54 @@ mod tests {
             Some(9)
         );
     }
+
+    #[test]
+    fn test_lod_pyramid() {
+        let mut recording = SessionRecording::new();
+        for i in 0..1000u64 {
+            let mut values = HashMap::new();
+            values.insert(
+                1,
+                RecordedValue {
+                    raw_value: RawValue::UInt(i),
+                    converted_value: if i == 500 { -50.0 } else { 1.0 },
+                },
+            );
+            recording.frames.push(RecordedFrame {
+                timestamp: Duration::from_millis(i),
+                values,
+                tag: None,
+            });
+        }
+
+        let pyramid = recording.lod_pyramid(1);
+        assert_eq!(pyramid.sample_count(), 1000);
+        let points = pyramid.points(0.0, 1.0, 100);
+        assert!(points.len() <= 100 + 24);
+        assert!(points.iter().any(|p| p[1] == -50.0));
+        assert!(recording.lod_pyramid(2).is_empty());
+    }
+
+    #[test]
+    fn test_recorded_value_keeps_64_bit_integers() {
+        let value = RecordedValue {
+            raw_value: RawValue::from_u64(u64::MAX),
+            converted_value: u64::MAX as f64,
+        };
+        let json = serde_json::to_string(&value).unwrap();
+        assert!(json.contains("18446744073709551615"));
+
+        let loaded: RecordedValue = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.raw_value, RawValue::UInt(u64::MAX));
+
+        let negative: RecordedValue =
+            serde_json::from_str(r#"{"raw_value":-9007199254740993,"converted_value":0.0}"#)
+                .unwrap();
+        assert_eq!(negative.raw_value, RawValue::Int(-9007199254740993));
+
+        // Recordings written before raw values were exact still load
+        let legacy: RecordedValue =
+            serde_json::from_str(r#"{"raw_value":1.5,"converted_value":3.0}"#).unwrap();
+        assert_eq!(legacy.raw_value, RawValue::Float(1.5));
+    }
 }
@@ -16,6 +16,8 @@
 //! - Unsigned integers: u8, u16, u32, u64
 //! - Signed integers: i8, i16, i32, i64
 //! - Floating point: f32, f64
+//! - Half precision: IEEE 754 binary16 (f16) and bfloat16 (bf16)
+//! - Fixed point: Q-format values with configurable integer/fractional bits
 //! - Boolean values
 //! - Raw byte arrays (for custom interpretation)
 //!
@@ -28,18 +30,157 @@
 //!
 //! Variable data is stored in a ring buffer with a configurable maximum size
 //! ([`MAX_DATA_POINTS`]). When the buffer is full, old data is evicted
-//! automatically.
+//! automatically. A min/max level-of-detail pyramid
+//! ([`MinMaxPyramid`]) summarises everything pushed, so long sessions can
+//! still be plotted zoomed out.
 
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
+use crate::analysis::MinMaxPyramid;
+use crate::session::VariableHistory;
+
 /// Maximum number of data points to retain in memory per variable
 pub const MAX_DATA_POINTS: usize = 100_000;
 
 /// Maximum number of points to render per line for performance
 pub const MAX_RENDER_POINTS: usize = 2000;
 
+/// Maximum number of text changes to retain in memory per string variable
+pub const MAX_TEXT_ENTRIES: usize = 10_000;
+
+/// Byte order of values in target memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Endianness {
+    /// Least significant byte first (Cortex-M default)
+    #[default]
+    Little,
+    /// Most significant byte first
+    Big,
+}
+
+impl std::fmt::Display for Endianness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Endianness::Little => write!(f, "Little-endian"),
+            Endianness::Big => write!(f, "Big-endian"),
+        }
+    }
+}
+
+/// Raw value as read from target memory, without precision loss
+///
+/// Integer variables keep their exact value so 64-bit counters, timestamps and
+/// bitmasks above 2^53 survive the trip from the probe to the UI, recordings
+/// and exports. Floating-point and fixed-point variables are carried as `f64`.
+///
+/// Serialized untagged, so a raw value is written as a plain JSON number and
+/// recordings that stored raw values as `f64` still load. Non-negative integers
+/// are always stored as [`RawValue::UInt`] and negative ones as
+/// [`RawValue::Int`], which keeps equality stable across a round trip.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RawValue {
+    /// Non-negative integer value
+    UInt(u64),
+    /// Negative integer value
+    Int(i64),
+    /// Floating-point (or scaled fixed-point) value
+    Float(f64),
+}
+
+impl RawValue {
+    /// Create a raw value from a signed integer
+    pub fn from_i64(value: i64) -> Self {
+        if value < 0 {
+            RawValue::Int(value)
+        } else {
+            RawValue::UInt(value as u64)
+        }
+    }
+
+    /// Create a raw value from an unsigned integer
+    pub fn from_u64(value: u64) -> Self {
+        RawValue::UInt(value)
+    }
+
+    /// Lossy conversion to `f64` for plotting and analysis
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            RawValue::UInt(v) => v as f64,
+            RawValue::Int(v) => v as f64,
+            RawValue::Float(v) => v,
+        }
+    }
+
+    /// Integer bit pattern as `i64` (two's complement for values above `i64::MAX`)
+    ///
+    /// Floats are truncated toward zero.
+    pub fn as_i64_bits(&self) -> i64 {
+        match *self {
+            RawValue::UInt(v) => v as i64,
+            RawValue::Int(v) => v,
+            RawValue::Float(v) => v as i64,
+        }
+    }
+
+    /// Whether this value holds an exact integer
+    pub fn is_integer(&self) -> bool {
+        !matches!(self, RawValue::Float(_))
+    }
+
+    /// Whether this value is NaN (used for gap markers)
+    pub fn is_nan(&self) -> bool {
+        matches!(self, RawValue::Float(v) if v.is_nan())
+    }
+
+    /// Format an integer value as zero-padded hex for a value of `size_bytes`
+    ///
+    /// Negative values are shown as their two's complement bit pattern.
+    /// Returns `None` for floating-point values.
+    pub fn format_hex(&self, size_bytes: usize) -> Option<String> {
+        let bits = match *self {
+            RawValue::UInt(v) => v,
+            RawValue::Int(v) => v as u64,
+            RawValue::Float(_) => return None,
+        };
+        let width = size_bytes.clamp(1, 8) * 2;
+        let mask = if width >= 16 {
+            u64::MAX
+        } else {
+            (1u64 << (width * 4)) - 1
+        };
+        Some(format!("0x{:0width$X}", bits & mask, width = width))
+    }
+}
+
+impl Default for RawValue {
+    fn default() -> Self {
+        RawValue::Float(0.0)
+    }
+}
+
+impl From<f64> for RawValue {
+    fn from(value: f64) -> Self {
+        RawValue::Float(value)
+    }
+}
+
+impl std::fmt::Display for RawValue {
+    /// Integers are always printed exactly; a precision only applies to floats
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            RawValue::UInt(v) => write!(f, "{}", v),
+            RawValue::Int(v) => write!(f, "{}", v),
+            RawValue::Float(v) => match f.precision() {
+                Some(precision) => write!(f, "{:.*}", precision, v),
+                None => write!(f, "{}", v),
+            },
+        }
+    }
+}
+
 /// Represents the type of a variable being observed
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum VariableType {
@@ -64,31 +205,230 @@ pub enum VariableType {
     F32,
     /// 64-bit floating point
     F64,
+    /// IEEE 754 half precision (binary16) floating point
+    F16,
+    /// bfloat16 floating point (upper 16 bits of an f32)
+    BF16,
+    /// Fixed-point (Q-format) value
+    ///
+    /// `int_bits` does not include the sign bit, so the ARM Q15 format is
+    /// `Fixed { signed: true, int_bits: 0, frac_bits: 15 }`. The storage size
+    /// is the total bit count rounded up to 1, 2, 4 or 8 bytes.
+    Fixed {
+        /// Whether the value is two's complement signed
+        signed: bool,
+        /// Number of integer bits (excluding the sign bit)
+        int_bits: u8,
+        /// Number of fractional bits
+        frac_bits: u8,
+    },
     /// Boolean value
     Bool,
     /// Raw bytes (for custom interpretation)
     Raw(usize),
+    /// Character buffer of the given size holding a NUL-terminated string
+    ///
+    /// Used for `char name[N]` arrays and for the pointee of a `const char*`.
+    /// The plotted value is the decoded length; the text itself is carried
+    /// separately (see [`decode_text`](VariableType::decode_text)).
+    Text(usize),
 }
 
 impl VariableType {
+    /// Signed Q15 fixed-point format (16-bit, range [-1, 1))
+    pub const Q15: VariableType = VariableType::Fixed {
+        signed: true,
+        int_bits: 0,
+        frac_bits: 15,
+    };
+
+    /// Signed Q31 fixed-point format (32-bit, range [-1, 1))
+    pub const Q31: VariableType = VariableType::Fixed {
+        signed: true,
+        int_bits: 0,
+        frac_bits: 31,
+    };
+
     /// Returns the size in bytes of this variable type
     pub fn size_bytes(&self) -> usize {
         match self {
             VariableType::U8 | VariableType::I8 | VariableType::Bool => 1,
-            VariableType::U16 | VariableType::I16 => 2,
+            VariableType::U16 | VariableType::I16 | VariableType::F16 | VariableType::BF16 => 2,
             VariableType::U32 | VariableType::I32 | VariableType::F32 => 4,
             VariableType::U64 | VariableType::I64 | VariableType::F64 => 8,
-            VariableType::Raw(size) => *size,
+            VariableType::Fixed { .. } => match self.fixed_total_bits() {
+                0..=8 => 1,
+                9..=16 => 2,
+                17..=32 => 4,
+                _ => 8,
+            },
+            VariableType::Raw(size) | VariableType::Text(size) => *size,
+        }
+    }
+
+    /// Total number of significant bits of a fixed-point type (sign + integer + fraction)
+    ///
+    /// Returns 0 for non fixed-point types.
+    fn fixed_total_bits(&self) -> u32 {
+        match self {
+            VariableType::Fixed {
+                signed,
+                int_bits,
+                frac_bits,
+            } => (*signed as u32 + *int_bits as u32 + *frac_bits as u32).min(64),
+            _ => 0,
         }
     }
 
-    /// Returns true if this type is writable (primitive types, not Raw)
+    /// Returns true if this type is a fixed-point or half-precision type
+    ///
+    /// These types have the same storage as an integer of equal size but are
+    /// only ever chosen explicitly by the user, never inferred from debug info.
+    pub fn is_reinterpreted(&self) -> bool {
+        matches!(
+            self,
+            VariableType::F16 | VariableType::BF16 | VariableType::Fixed { .. }
+        )
+    }
+
+    /// Returns all built-in types that share the storage size of this type
+    ///
+    /// Used to offer "interpret as" choices for a variable. Fixed-point types
+    /// are not listed since they are parameterized.
+    pub fn same_size_types(&self) -> Vec<VariableType> {
+        let size = self.size_bytes();
+        [
+            VariableType::U8,
+            VariableType::I8,
+            VariableType::Bool,
+            VariableType::U16,
+            VariableType::I16,
+            VariableType::F16,
+            VariableType::BF16,
+            VariableType::U32,
+            VariableType::I32,
+            VariableType::F32,
+            VariableType::U64,
+            VariableType::I64,
+            VariableType::F64,
+        ]
+        .into_iter()
+        .filter(|t| t.size_bytes() == size)
+        .collect()
+    }
+
+    /// Returns true if this type is writable (primitive types, not Raw or Text)
     /// Raw types cannot be written because we don't know how to convert a f64 to them
     pub fn is_writable(&self) -> bool {
-        !matches!(self, VariableType::Raw(_))
+        !matches!(self, VariableType::Raw(_) | VariableType::Text(_))
+    }
+
+    /// Returns true if this is a string type
+    pub fn is_text(&self) -> bool {
+        matches!(self, VariableType::Text(_))
+    }
+
+    /// Decode a character buffer up to the first NUL (or the end of the buffer)
+    ///
+    /// Bytes are decoded as UTF-8, which covers plain ASCII; invalid sequences
+    /// and control characters other than tab are shown as U+FFFD, and trailing
+    /// line endings are dropped so `printf`-style buffers log as one line.
+    pub fn decode_text(bytes: &[u8]) -> String {
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end])
+            .trim_end_matches(['\r', '\n'])
+            .chars()
+            .map(|c| {
+                if c.is_control() && c != '\t' {
+                    char::REPLACEMENT_CHARACTER
+                } else {
+                    c
+                }
+            })
+            .collect()
+    }
+
+    /// Parse bytes in the given target byte order into a f64 value
+    pub fn parse_to_f64_endian(&self, bytes: &[u8], endianness: Endianness) -> Option<f64> {
+        match endianness {
+            Endianness::Little => self.parse_to_f64(bytes),
+            Endianness::Big => self.parse_to_f64(&self.swap_bytes(bytes)?),
+        }
+    }
+
+    /// Parse bytes in the given target byte order into an exact raw value
+    pub fn parse_raw_endian(&self, bytes: &[u8], endianness: Endianness) -> Option<RawValue> {
+        match endianness {
+            Endianness::Little => self.parse_raw(bytes),
+            Endianness::Big => self.parse_raw(&self.swap_bytes(bytes)?),
+        }
+    }
+
+    /// Parse little-endian raw bytes into an exact raw value
+    ///
+    /// Integer types (including `bool`) produce exact integers; all other
+    /// types fall back to [`parse_to_f64`](Self::parse_to_f64).
+    pub fn parse_raw(&self, bytes: &[u8]) -> Option<RawValue> {
+        if bytes.len() < self.size_bytes() {
+            return None;
+        }
+
+        Some(match self {
+            VariableType::U8 => RawValue::from_u64(bytes[0] as u64),
+            VariableType::I8 => RawValue::from_i64(bytes[0] as i8 as i64),
+            VariableType::Bool => RawValue::from_u64((bytes[0] != 0) as u64),
+            VariableType::U16 => {
+                RawValue::from_u64(u16::from_le_bytes([bytes[0], bytes[1]]) as u64)
+            }
+            VariableType::I16 => {
+                RawValue::from_i64(i16::from_le_bytes([bytes[0], bytes[1]]) as i64)
+            }
+            VariableType::U32 => {
+                RawValue::from_u64(
+                    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64
+                )
+            }
+            VariableType::I32 => {
+                RawValue::from_i64(
+                    i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as i64
+                )
+            }
+            VariableType::U64 => RawValue::from_u64(u64::from_le_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+            ])),
+            VariableType::I64 => RawValue::from_i64(i64::from_le_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+            ])),
+            VariableType::Text(size) => RawValue::from_u64(text_len(&bytes[..*size]) as u64),
+            _ => RawValue::Float(self.parse_to_f64(bytes)?),
+        })
+    }
+
+    /// Encode a f64 value into bytes in the given target byte order
+    pub fn encode_f64_endian(&self, value: f64, endianness: Endianness) -> Option<Vec<u8>> {
+        let bytes = self.encode_f64(value)?;
+        match endianness {
+            Endianness::Little => Some(bytes),
+            Endianness::Big => self.swap_bytes(&bytes),
+        }
+    }
+
+    /// Reverse the byte order of a single value of this type
+    ///
+    /// Raw byte arrays and strings have no byte order and are returned unchanged.
+    fn swap_bytes(&self, bytes: &[u8]) -> Option<Vec<u8>> {
+        let size = self.size_bytes();
+        if bytes.len() < size {
+            return None;
+        }
+        let mut swapped = bytes[..size].to_vec();
+        if !matches!(self, VariableType::Raw(_) | VariableType::Text(_)) {
+            swapped.reverse();
+        }
+        Some(swapped)
     }
 
-    /// Parse raw bytes into a f64 value for plotting
+    /// Parse little-endian raw bytes into a f64 value for plotting
     pub fn parse_to_f64(&self, bytes: &[u8]) -> Option<f64> {
         if bytes.len() < self.size_bytes() {
             return None;
@@ -124,14 +464,160 @@ impl VariableType {
             VariableType::F64 => f64::from_le_bytes([
                 bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
             ]),
+            VariableType::F16 => f16_bits_to_f32(u16::from_le_bytes([bytes[0], bytes[1]])) as f64,
+            VariableType::BF16 => {
+                f32::from_bits((u16::from_le_bytes([bytes[0], bytes[1]]) as u32) << 16) as f64
+            }
+            VariableType::Fixed {
+                signed, frac_bits, ..
+            } => {
+                let mut word = [0u8; 8];
+                word[..self.size_bytes()].copy_from_slice(&bytes[..self.size_bytes()]);
+                let raw = u64::from_le_bytes(word);
+                let bits = self.fixed_total_bits();
+                let raw = if bits == 0 || bits >= 64 {
+                    raw
+                } else {
+                    raw & ((1u64 << bits) - 1)
+                };
+                let value = if *signed && bits > 0 && bits < 64 {
+                    // Sign-extend from the top significant bit
+                    let shift = 64 - bits;
+                    (((raw << shift) as i64) >> shift) as f64
+                } else if *signed {
+                    raw as i64 as f64
+                } else {
+                    raw as f64
+                };
+                value / (1u64 << (*frac_bits).min(63)) as f64
+            }
             VariableType::Raw(_) => {
                 // For raw bytes, just return the first byte as a value
                 bytes[0] as f64
             }
+            VariableType::Text(size) => text_len(&bytes[..*size]) as f64,
+        })
+    }
+
+    /// Encode a f64 value into little-endian bytes for writing to memory
+    ///
+    /// Integer types truncate, fixed-point types round to the nearest step
+    /// and saturate at the representable range. Returns `None` for `Raw` and `Text`.
+    pub fn encode_f64(&self, value: f64) -> Option<Vec<u8>> {
+        Some(match self {
+            VariableType::U8 => vec![value as u8],
+            VariableType::I8 => vec![value as i8 as u8],
+            VariableType::Bool => vec![if value != 0.0 { 1 } else { 0 }],
+            VariableType::U16 => (value as u16).to_le_bytes().to_vec(),
+            VariableType::I16 => (value as i16).to_le_bytes().to_vec(),
+            VariableType::U32 => (value as u32).to_le_bytes().to_vec(),
+            VariableType::I32 => (value as i32).to_le_bytes().to_vec(),
+            VariableType::F32 => (value as f32).to_le_bytes().to_vec(),
+            VariableType::U64 => (value as u64).to_le_bytes().to_vec(),
+            VariableType::I64 => (value as i64).to_le_bytes().to_vec(),
+            VariableType::F64 => value.to_le_bytes().to_vec(),
+            VariableType::F16 => f32_to_f16_bits(value as f32).to_le_bytes().to_vec(),
+            VariableType::BF16 => f32_to_bf16_bits(value as f32).to_le_bytes().to_vec(),
+            VariableType::Fixed {
+                signed, frac_bits, ..
+            } => {
+                let bits = self.fixed_total_bits();
+                let scaled = (value * (1u64 << (*frac_bits).min(63)) as f64).round();
+                let raw = if *signed {
+                    let max = if bits >= 64 { i64::MAX } else { (1i64 << (bits - 1)) - 1 };
+                    let min = if bits >= 64 { i64::MIN } else { -(1i64 << (bits - 1)) };
+                    (scaled.clamp(min as f64, max as f64) as i64) as u64
+                } else {
+                    let max = if bits >= 64 { u64::MAX } else { (1u64 << bits) - 1 };
+                    scaled.clamp(0.0, max as f64) as u64
+                };
+                raw.to_le_bytes()[..self.size_bytes()].to_vec()
+            }
+            VariableType::Raw(_) | VariableType::Text(_) => return None,
         })
     }
 }
 
+/// Length in bytes of a NUL-terminated string within a character buffer
+fn text_len(bytes: &[u8]) -> usize {
+    bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len())
+}
+
+/// Convert IEEE 754 binary16 bits to f32
+fn f16_bits_to_f32(bits: u16) -> f32 {
+    let sign = ((bits >> 15) as u32) << 31;
+    let exponent = ((bits >> 10) & 0x1F) as u32;
+    let mantissa = (bits & 0x3FF) as u32;
+
+    let magnitude = match exponent {
+        0 => {
+            // Zero or subnormal: mantissa * 2^-24
+            let value = mantissa as f32 * 2f32.powi(-24);
+            return if sign != 0 { -value } else { value };
+        }
+        0x1F => 0x7F80_0000 | (mantissa << 13),
+        _ => ((exponent + 127 - 15) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(sign | magnitude)
+}
+
+/// Convert f32 to IEEE 754 binary16 bits (round to nearest even)
+fn f32_to_f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xFF) as i32;
+    let mantissa = bits & 0x7F_FFFF;
+
+    if exponent == 0xFF {
+        // Infinity or NaN (keep NaN quiet and non-zero)
+        let nan_bit = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7C00 | nan_bit;
+    }
+
+    let half_exp = exponent - 127 + 15;
+    if half_exp >= 0x1F {
+        // Overflow to infinity
+        return sign | 0x7C00;
+    }
+
+    if half_exp <= 0 {
+        // Subnormal or underflow to zero
+        if half_exp < -10 {
+            return sign;
+        }
+        let full_mantissa = mantissa | 0x80_0000;
+        let shift = (14 - half_exp) as u32;
+        let half_mantissa = full_mantissa >> shift;
+        let remainder = full_mantissa & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        let rounded = if remainder > halfway || (remainder == halfway && half_mantissa & 1 != 0) {
+            half_mantissa + 1
+        } else {
+            half_mantissa
+        };
+        return sign | rounded as u16;
+    }
+
+    let half_mantissa = mantissa >> 13;
+    let remainder = mantissa & 0x1FFF;
+    let mut result = ((half_exp as u32) << 10) | half_mantissa;
+    if remainder > 0x1000 || (remainder == 0x1000 && half_mantissa & 1 != 0) {
+        // Rounding may carry into the exponent, which correctly yields infinity at the top
+        result += 1;
+    }
+    sign | result as u16
+}
+
+/// Convert f32 to bfloat16 bits (round to nearest even)
+fn f32_to_bf16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    if value.is_nan() {
+        return ((bits >> 16) as u16) | 0x0040;
+    }
+    let rounding_bias = 0x7FFF + ((bits >> 16) & 1);
+    (bits.wrapping_add(rounding_bias) >> 16) as u16
+}
+
 impl std::fmt::Display for VariableType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -145,8 +631,19 @@ impl std::fmt::Display for VariableType {
             VariableType::I64 => write!(f, "i64"),
             VariableType::F32 => write!(f, "f32"),
             VariableType::F64 => write!(f, "f64"),
+            VariableType::F16 => write!(f, "f16"),
+            VariableType::BF16 => write!(f, "bf16"),
+            VariableType::Fixed {
+                signed,
+                int_bits,
+                frac_bits,
+            } => {
+                let prefix = if *signed { "Q" } else { "UQ" };
+                write!(f, "{}{}.{}", prefix, int_bits, frac_bits)
+            }
             VariableType::Bool => write!(f, "bool"),
             VariableType::Raw(size) => write!(f, "{} bytes", size),
+            VariableType::Text(size) => write!(f, "char[{}]", size),
         }
     }
 }
@@ -212,8 +709,8 @@ impl PlotStyle {
 pub struct DataPoint {
     /// Timestamp when the data point was captured (relative to start)
     pub timestamp: Duration,
-    /// The raw value read from memory
-    pub raw_value: f64,
+    /// The raw value read from memory (exact for integer types)
+    pub raw_value: RawValue,
     /// The converted value (after applying Rhai script, if any)
     pub converted_value: f64,
 }
@@ -223,16 +720,20 @@ impl DataPoint {
     pub fn new(timestamp: Duration, value: f64) -> Self {
         Self {
             timestamp,
-            raw_value: value,
+            raw_value: RawValue::Float(value),
             converted_value: value,
         }
     }
 
     /// Create a new data point with separate raw and converted values
-    pub fn with_conversion(timestamp: Duration, raw_value: f64, converted_value: f64) -> Self {
+    pub fn with_conversion(
+        timestamp: Duration,
+        raw_value: impl Into<RawValue>,
+        converted_value: f64,
+    ) -> Self {
         Self {
             timestamp,
-            raw_value,
+            raw_value: raw_value.into(),
             converted_value,
         }
     }
@@ -244,7 +745,7 @@ impl DataPoint {
     pub fn gap_marker(timestamp: Duration) -> Self {
         Self {
             timestamp,
-            raw_value: f64::NAN,
+            raw_value: RawValue::Float(f64::NAN),
             converted_value: f64::NAN,
         }
     }
@@ -255,6 +756,15 @@ impl DataPoint {
     }
 }
 
+/// A decoded string value and the time it first appeared
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEntry {
+    /// Timestamp of the read that observed the new text (relative to start)
+    pub timestamp: Duration,
+    /// The decoded text
+    pub text: String,
+}
+
 /// Describes how a variable's memory address is determined.
 /// Used by the UI to display the correct address representation.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -442,6 +952,8 @@ impl Variable {
             address,
             var_type,
             color: Self::generate_color(id),
+            // Strings have no meaningful numeric trace
+            show_in_graph: !var_type.is_text(),
             ..Default::default()
         }
     }
@@ -694,8 +1206,8 @@ pub struct VariableData {
     pub data_points: VecDeque<DataPoint>,
     /// Time when data collection started
     pub start_time: Instant,
-    /// Last received value
-    pub last_value: Option<f64>,
+    /// Last received raw value
+    pub last_value: Option<RawValue>,
     /// Last converted value
     pub last_converted_value: Option<f64>,
     /// Number of read errors
@@ -706,6 +1218,14 @@ pub struct VariableData {
     pub stats: IncrementalStats,
     /// Counter for periodic min/max recalculation (every N pops)
     stats_recalc_counter: u32,
+    /// Last decoded text (string variables only)
+    pub last_text: Option<String>,
+    /// History of text changes (string variables only)
+    pub text_log: VecDeque<TextEntry>,
+    /// Min/max pyramid of every point pushed, for plotting beyond the ring buffer
+    pub lod: MinMaxPyramid,
+    /// On-disk copy of every point pushed (when full history is enabled)
+    pub history: Option<VariableHistory>,
 }
 
 /// How often to recalculate exact min/max (every N evictions from ring buffer)
@@ -724,6 +1244,10 @@ impl VariableData {
             last_error: None,
             stats: IncrementalStats::new(),
             stats_recalc_counter: 0,
+            last_text: None,
+            text_log: VecDeque::new(),
+            lod: MinMaxPyramid::new(),
+            history: None,
         }
     }
 
@@ -734,6 +1258,13 @@ impl VariableData {
 
         // Update incremental stats with new value
         self.stats.push(point.converted_value);
+        self.lod.push(&point);
+        if let Some(history) = &mut self.history {
+            if let Err(e) = history.push(&point) {
+                tracing::warn!("Stopped keeping history of '{}': {}", self.variable.name, e);
+                self.history = None;
+            }
+        }
 
         // If we need to evict an old value, update stats
         if self.data_points.len() >= MAX_DATA_POINTS {
@@ -757,9 +1288,37 @@ impl VariableData {
         }
     }
 
+    /// Record a decoded string value
+    ///
+    /// The text log only grows when the text differs from the previous
+    /// entry. Returns true if a new entry was appended.
+    pub fn push_text(&mut self, timestamp: Duration, text: String) -> bool {
+        let changed = self.text_log.back().is_none_or(|last| last.text != text);
+        if changed {
+            if self.text_log.len() >= MAX_TEXT_ENTRIES {
+                self.text_log.pop_front();
+            }
+            self.text_log.push_back(TextEntry {
+                timestamp,
+                text: text.clone(),
+            });
+        }
+        self.last_text = Some(text);
+        changed
+    }
+
     /// Clear all data points and reset error state
     pub fn clear(&mut self) {
         self.data_points.clear();
+        self.last_text = None;
+        self.text_log.clear();
+        self.lod.clear();
+        if let Some(history) = &mut self.history {
+            if let Err(e) = history.clear() {
+                tracing::warn!("Stopped keeping history of '{}': {}", self.variable.name, e);
+                self.history = None;
+            }
+        }
         self.last_value = None;
         self.last_converted_value = None;
         self.start_time = Instant::now();
@@ -776,6 +1335,46 @@ impl VariableData {
             .collect()
     }
 
+    /// Get plot points within `[start, end]` seconds, at most about `max_points`
+    ///
+    /// Uses the raw samples when they cover the range sparsely enough, the
+    /// on-disk history for sparse ranges older than the ring buffer, and the
+    /// min/max pyramid otherwise. One point either side of an in-memory range
+    /// is included so lines run to the plot edges.
+    pub fn plot_points_in(&self, start: f64, end: f64, max_points: usize) -> Vec<[f64; 2]> {
+        let first = self
+            .data_points
+            .partition_point(|dp| dp.timestamp.as_secs_f64() < start);
+        let last = self
+            .data_points
+            .partition_point(|dp| dp.timestamp.as_secs_f64() <= end);
+        // Nothing before `start` was evicted if a sample precedes it or the
+        // ring buffer has never filled
+        let covers_start = first > 0 || self.data_points.len() < MAX_DATA_POINTS;
+        if covers_start && last - first <= max_points {
+            let end_index = (last + 1).min(self.data_points.len());
+            return self
+                .data_points
+                .range(first.saturating_sub(1)..end_index)
+                .map(|dp| [dp.timestamp.as_secs_f64(), dp.converted_value])
+                .collect();
+        }
+        if let Some(history) = &self.history {
+            let (from, to) = (
+                Duration::from_secs_f64(start.max(0.0)),
+                Duration::from_secs_f64(end.max(0.0)),
+            );
+            if !covers_start && history.count_in(from, to) <= max_points {
+                return history
+                    .points_in(from, to)
+                    .iter()
+                    .map(|dp| [dp.timestamp.as_secs_f64(), dp.converted_value])
+                    .collect();
+            }
+        }
+        self.lod.points(start, end, max_points)
+    }
+
     /// Get the time range of the data
     pub fn time_range(&self) -> Option<(f64, f64)> {
         if self.data_points.is_empty() {
@@ -1000,6 +1599,207 @@ mod tests {
         assert!((parsed - 3.14).abs() < 0.001);
     }
 
+    #[test]
+    fn test_fixed_point_parse() {
+        // Q15: 0x4000 = 0.5, 0x8000 = -1.0
+        assert_eq!(VariableType::Q15.size_bytes(), 2);
+        assert_eq!(VariableType::Q15.parse_to_f64(&0x4000u16.to_le_bytes()), Some(0.5));
+        assert_eq!(VariableType::Q15.parse_to_f64(&0x8000u16.to_le_bytes()), Some(-1.0));
+
+        // Q31: 0xC0000000 = -0.5
+        assert_eq!(VariableType::Q31.size_bytes(), 4);
+        assert_eq!(
+            VariableType::Q31.parse_to_f64(&0xC000_0000u32.to_le_bytes()),
+            Some(-0.5)
+        );
+
+        // Signed Q3.12 stored in 16 bits: -1.25 = 0xEC00
+        let q3_12 = VariableType::Fixed {
+            signed: true,
+            int_bits: 3,
+            frac_bits: 12,
+        };
+        assert_eq!(q3_12.parse_to_f64(&0xEC00u16.to_le_bytes()), Some(-1.25));
+
+        // Unsigned UQ8.8
+        let uq8_8 = VariableType::Fixed {
+            signed: false,
+            int_bits: 8,
+            frac_bits: 8,
+        };
+        assert_eq!(uq8_8.parse_to_f64(&0x0180u16.to_le_bytes()), Some(1.5));
+
+        // Signed 12-bit value (Q11 stored in 2 bytes) ignores padding bits
+        let q0_11 = VariableType::Fixed {
+            signed: true,
+            int_bits: 0,
+            frac_bits: 11,
+        };
+        assert_eq!(q0_11.parse_to_f64(&0xF800u16.to_le_bytes()), Some(-1.0));
+    }
+
+    #[test]
+    fn test_fixed_point_encode_rounds_and_saturates() {
+        assert_eq!(VariableType::Q15.encode_f64(0.5), Some(vec![0x00, 0x40]));
+        assert_eq!(VariableType::Q15.encode_f64(-1.0), Some(vec![0x00, 0x80]));
+        // 1.0 is not representable in Q15 and saturates to 0x7FFF
+        assert_eq!(VariableType::Q15.encode_f64(1.0), Some(vec![0xFF, 0x7F]));
+        assert_eq!(VariableType::Q15.encode_f64(-2.0), Some(vec![0x00, 0x80]));
+
+        let uq8_8 = VariableType::Fixed {
+            signed: false,
+            int_bits: 8,
+            frac_bits: 8,
+        };
+        assert_eq!(uq8_8.encode_f64(-3.0), Some(vec![0x00, 0x00]));
+        let bytes = uq8_8.encode_f64(12.34).unwrap();
+        let back = uq8_8.parse_to_f64(&bytes).unwrap();
+        assert!((back - 12.34).abs() <= 1.0 / 512.0);
+    }
+
+    #[test]
+    fn test_half_precision_parse() {
+        assert_eq!(VariableType::F16.size_bytes(), 2);
+        assert_eq!(VariableType::F16.parse_to_f64(&0x3C00u16.to_le_bytes()), Some(1.0));
+        assert_eq!(VariableType::F16.parse_to_f64(&0xC000u16.to_le_bytes()), Some(-2.0));
+        assert_eq!(VariableType::F16.parse_to_f64(&0x7BFFu16.to_le_bytes()), Some(65504.0));
+        // Smallest subnormal
+        assert_eq!(
+            VariableType::F16.parse_to_f64(&0x0001u16.to_le_bytes()),
+            Some(2f64.powi(-24))
+        );
+        assert_eq!(
+            VariableType::F16.parse_to_f64(&0x7C00u16.to_le_bytes()),
+            Some(f64::INFINITY)
+        );
+        assert!(VariableType::F16
+            .parse_to_f64(&0x7E00u16.to_le_bytes())
+            .unwrap()
+            .is_nan());
+
+        assert_eq!(VariableType::BF16.parse_to_f64(&0x3F80u16.to_le_bytes()), Some(1.0));
+        assert_eq!(VariableType::BF16.parse_to_f64(&0xC040u16.to_le_bytes()), Some(-3.0));
+    }
+
+    #[test]
+    fn test_half_precision_encode() {
+        assert_eq!(VariableType::F16.encode_f64(1.0), Some(vec![0x00, 0x3C]));
+        assert_eq!(VariableType::F16.encode_f64(-2.0), Some(vec![0x00, 0xC0]));
+        assert_eq!(VariableType::F16.encode_f64(1e6), Some(vec![0x00, 0x7C]));
+        assert_eq!(
+            VariableType::F16.encode_f64(2f64.powi(-24)),
+            Some(vec![0x01, 0x00])
+        );
+        assert_eq!(VariableType::BF16.encode_f64(1.0), Some(vec![0x80, 0x3F]));
+
+        for value in [0.1, -3.75, 1000.5, 6.1e-5] {
+            let bytes = VariableType::F16.encode_f64(value).unwrap();
+            let back = VariableType::F16.parse_to_f64(&bytes).unwrap();
+            assert!((back - value).abs() <= value.abs() * 1e-3, "{value} -> {back}");
+        }
+    }
+
+    #[test]
+    fn test_big_endian_parse_and_encode() {
+        let be = Endianness::Big;
+        assert_eq!(
+            VariableType::U32.parse_to_f64_endian(&[0x12, 0x34, 0x56, 0x78], be),
+            Some(0x12345678 as f64)
+        );
+        assert_eq!(
+            VariableType::I16.parse_to_f64_endian(&[0xFF, 0xFE], be),
+            Some(-2.0)
+        );
+        assert_eq!(
+            VariableType::F32.parse_to_f64_endian(&1.5f32.to_be_bytes(), be),
+            Some(1.5)
+        );
+        assert_eq!(
+            VariableType::Q15.parse_to_f64_endian(&[0xC0, 0x00], be),
+            Some(-0.5)
+        );
+        assert_eq!(
+            VariableType::U64.encode_f64_endian(258.0, be),
+            Some(vec![0, 0, 0, 0, 0, 0, 1, 2])
+        );
+        assert_eq!(
+            VariableType::F16.encode_f64_endian(1.0, be),
+            Some(vec![0x3C, 0x00])
+        );
+        // Single bytes and raw arrays are unaffected by byte order
+        assert_eq!(VariableType::U8.parse_to_f64_endian(&[7], be), Some(7.0));
+        assert_eq!(
+            VariableType::Raw(4).parse_to_f64_endian(&[9, 8, 7, 6], be),
+            Some(9.0)
+        );
+        assert_eq!(VariableType::U32.parse_to_f64_endian(&[1, 2], be), None);
+    }
+
+    #[test]
+    fn test_reinterpreted_type_display() {
+        assert_eq!(VariableType::Q15.to_string(), "Q0.15");
+        assert_eq!(
+            VariableType::Fixed {
+                signed: false,
+                int_bits: 8,
+                frac_bits: 8
+            }
+            .to_string(),
+            "UQ8.8"
+        );
+        assert_eq!(VariableType::F16.to_string(), "f16");
+        assert_eq!(VariableType::BF16.to_string(), "bf16");
+        assert!(VariableType::U16.same_size_types().contains(&VariableType::F16));
+        assert!(!VariableType::U32.same_size_types().contains(&VariableType::F16));
+    }
+
+    #[test]
+    fn test_text_type_decode() {
+        let ty = VariableType::Text(8);
+        assert_eq!(ty.size_bytes(), 8);
+        assert_eq!(ty.to_string(), "char[8]");
+        assert!(ty.is_text());
+        assert!(!ty.is_writable());
+        assert_eq!(ty.encode_f64(1.0), None);
+
+        let bytes = b"idle\0xyz";
+        assert_eq!(VariableType::decode_text(bytes), "idle");
+        assert_eq!(ty.parse_raw(bytes), Some(RawValue::UInt(4)));
+        assert_eq!(ty.parse_to_f64(bytes), Some(4.0));
+        // Byte order does not apply to strings
+        assert_eq!(
+            ty.parse_raw_endian(bytes, Endianness::Big),
+            Some(RawValue::UInt(4))
+        );
+
+        // No terminator: the whole buffer is used
+        assert_eq!(VariableType::decode_text(b"abcd"), "abcd");
+        // Trailing line endings are dropped, other control bytes are replaced
+        assert_eq!(VariableType::decode_text(b"ok\r\n\0"), "ok");
+        assert_eq!(VariableType::decode_text(b"a\x07b\tc"), "a\u{FFFD}b\tc");
+        // Invalid UTF-8 does not fail
+        assert_eq!(VariableType::decode_text(&[b'x', 0xFF, 0]), "x\u{FFFD}");
+    }
+
+    #[test]
+    fn test_variable_data_text_log_appends_on_change() {
+        let var = Variable::new("status", 0x2000_0000, VariableType::Text(16));
+        let mut data = VariableData::new(var);
+
+        assert!(data.push_text(Duration::from_millis(0), "boot".into()));
+        assert!(!data.push_text(Duration::from_millis(10), "boot".into()));
+        assert!(data.push_text(Duration::from_millis(20), "run".into()));
+
+        assert_eq!(data.last_text.as_deref(), Some("run"));
+        assert_eq!(data.text_log.len(), 2);
+        assert_eq!(data.text_log[0].timestamp, Duration::from_millis(0));
+        assert_eq!(data.text_log[1].text, "run");
+
+        data.clear();
+        assert!(data.last_text.is_none());
+        assert!(data.text_log.is_empty());
+    }
+
     #[test]
     fn test_variable_data_ring_buffer() {
         let var = Variable::new("test", 0x2000_0000, VariableType::U32);
@@ -1014,6 +1814,32 @@ mod tests {
         assert_eq!(data.data_points.len(), MAX_DATA_POINTS);
     }
 
+    #[test]
+    fn test_variable_data_plot_points_beyond_ring_buffer() {
+        let var = Variable::new("test", 0x2000_0000, VariableType::U32);
+        let mut data = VariableData::new(var);
+        for i in 0..(MAX_DATA_POINTS * 2) {
+            let value = if i == 10 { 500.0 } else { 1.0 };
+            data.push(DataPoint::new(Duration::from_millis(i as u64), value));
+        }
+
+        // A short recent window is drawn from the raw samples
+        let recent = data.plot_points_in(150.0, 150.5, MAX_RENDER_POINTS);
+        assert_eq!(recent.len(), 503);
+
+        // The whole session, including evicted data, comes from the pyramid
+        let all = data.plot_points_in(0.0, 200.0, MAX_RENDER_POINTS);
+        assert!(all.len() <= MAX_RENDER_POINTS + 64);
+        assert!(all.iter().any(|p| p[1] == 500.0));
+        assert!(all[0][0] < 1.0);
+
+        data.clear();
+        assert!(data.lod.is_empty());
+        assert!(data
+            .plot_points_in(0.0, 200.0, MAX_RENDER_POINTS)
+            .is_empty());
+    }
+
     #[test]
     fn test_incremental_stats() {
         let var = Variable::new("test", 0x2000_0000, VariableType::U32);
@@ -1080,6 +1906,9 @@ mod tests {
         assert!(VariableType::F32.is_writable());
         assert!(VariableType::F64.is_writable());
         assert!(VariableType::Bool.is_writable());
+        assert!(VariableType::F16.is_writable());
+        assert!(VariableType::BF16.is_writable());
+        assert!(VariableType::Q15.is_writable());
 
         // Raw types should not be writable
         assert!(!VariableType::Raw(4).is_writable());
@@ -1161,4 +1990,49 @@ mod tests {
         rt.mark_error();
         assert_eq!(rt.pointer_state, PointerState::ReadError);
     }
+
+    #[test]
+    fn test_parse_raw_keeps_64_bit_integers() {
+        // 2^53 + 1 is the first integer f64 cannot represent
+        let big = (1u64 << 53) + 1;
+        let raw = VariableType::U64.parse_raw(&big.to_le_bytes()).unwrap();
+        assert_eq!(raw, RawValue::UInt(big));
+        assert_eq!(raw.to_string(), "9007199254740993");
+
+        let raw = VariableType::I64
+            .parse_raw(&(-(big as i64)).to_le_bytes())
+            .unwrap();
+        assert_eq!(raw, RawValue::Int(-(big as i64)));
+
+        let raw = VariableType::U64
+            .parse_raw_endian(&big.to_be_bytes(), Endianness::Big)
+            .unwrap();
+        assert_eq!(raw, RawValue::UInt(big));
+
+        // Non-negative signed values are canonicalized to UInt
+        let raw = VariableType::I32.parse_raw(&7i32.to_le_bytes()).unwrap();
+        assert_eq!(raw, RawValue::UInt(7));
+
+        // Floating-point types fall back to f64
+        let raw = VariableType::F32.parse_raw(&1.5f32.to_le_bytes()).unwrap();
+        assert_eq!(raw, RawValue::Float(1.5));
+        assert!(!raw.is_integer());
+    }
+
+    #[test]
+    fn test_raw_value_formatting() {
+        assert_eq!(
+            RawValue::UInt(u64::MAX).format_hex(8).as_deref(),
+            Some("0xFFFFFFFFFFFFFFFF")
+        );
+        assert_eq!(RawValue::UInt(0xAB).format_hex(2).as_deref(), Some("0x00AB"));
+        // Negative values show the two's complement pattern for the type size
+        assert_eq!(RawValue::Int(-1).format_hex(2).as_deref(), Some("0xFFFF"));
+        assert_eq!(RawValue::Float(1.0).format_hex(4), None);
+
+        // Precision only applies to floats
+        assert_eq!(format!("{:.3}", RawValue::UInt(42)), "42");
+        assert_eq!(format!("{:.3}", RawValue::Float(0.5)), "0.500");
+        assert_eq!(RawValue::UInt(u64::MAX).as_i64_bits(), -1);
+    }
 }
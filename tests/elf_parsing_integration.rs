@@ -16,6 +16,7 @@ const TEST_STRUCT_ELF: &[u8] = include_bytes!("fixtures/test_struct.elf");
 const TEST_POINTER_ELF: &[u8] = include_bytes!("fixtures/test_pointer.elf");
 const TEST_COMPLEX_C_ELF: &[u8] = include_bytes!("fixtures/test_complex_c.elf");
 const TEST_CPP_ELF: &[u8] = include_bytes!("fixtures/test_cpp.elf");
+const TEST_BIG_ENDIAN_ELF: &[u8] = include_bytes!("fixtures/test_big_endian.elf");
 
 #[test]
 fn test_parse_basic_c_elf() {
@@ -305,3 +306,102 @@ fn test_diagnostic_categories() {
         "All variables should be categorized"
     );
 }
+
+#[test]
+fn test_elf_endianness_detection() {
+    use datavis_rs::backend::ElfParser;
+    use datavis_rs::types::Endianness;
+
+    let info = ElfParser::parse_bytes(TEST_ARM_ELF, "test_arm.elf").expect("Should parse");
+    assert_eq!(info.endianness(), Endianness::Little);
+
+    let info = ElfParser::parse_bytes(TEST_BIG_ENDIAN_ELF, "test_big_endian.elf")
+        .expect("Should parse test_big_endian.elf");
+    assert_eq!(info.endianness(), Endianness::Big);
+}
+
+#[test]
+fn test_parse_big_endian_elf() {
+    let result =
+        DwarfParser::parse_bytes(TEST_BIG_ENDIAN_ELF).expect("Should parse test_big_endian.elf");
+
+    let counter = result
+        .symbols
+        .iter()
+        .find(|s| s.name == "be_counter")
+        .expect("Should find be_counter");
+    assert_eq!(counter.address, 0x0800_0018);
+    assert_eq!(counter.size, 4);
+
+    let sensor = result
+        .symbols
+        .iter()
+        .find(|s| s.name == "be_sensor")
+        .expect("Should find be_sensor");
+    assert_eq!(sensor.size, 8);
+    let members: Vec<_> = result
+        .type_table
+        .get_members(sensor.type_id)
+        .expect("SensorData should have members")
+        .iter()
+        .map(|m| (m.name.as_str(), m.offset))
+        .collect();
+    assert_eq!(members, [("x", 0), ("y", 2), ("value", 4)]);
+}
+
+#[test]
+#[cfg(feature = "mock-probe")]
+fn test_read_big_endian_elf_through_mock_probe() {
+    use datavis_rs::backend::{DebugProbe, ElfParser, MockProbeBackend};
+    use datavis_rs::types::{Endianness, RawValue};
+    use object::{Object, ObjectSection};
+
+    let info = ElfParser::parse_bytes(TEST_BIG_ENDIAN_ELF, "test_big_endian.elf")
+        .expect("Should parse test_big_endian.elf");
+    let counter = info
+        .find_symbol("be_counter")
+        .expect("Should find be_counter")
+        .to_variable();
+    let sensor = info
+        .find_symbol("be_sensor")
+        .expect("Should find be_sensor");
+
+    // Load the initial contents of every allocated section into mock memory
+    let mut probe = MockProbeBackend::new().with_pattern_only_mode(false);
+    let elf = object::File::parse(TEST_BIG_ENDIAN_ELF).expect("Should parse ELF sections");
+    for section in elf.sections().filter(|s| s.address() != 0) {
+        let data = section.data().expect("Should read section data");
+        if !data.is_empty() {
+            probe.memory_mut().add_region(section.address(), data.len());
+            probe.memory_mut().write(section.address(), data);
+        }
+    }
+    probe.connect(None, "MockTarget").expect("Should connect");
+
+    // Before the byte order is known the value reads back swapped
+    assert_eq!(
+        probe.read_variable_raw(&counter).unwrap(),
+        RawValue::UInt(0x7856_3412)
+    );
+
+    probe.set_endianness(info.endianness());
+    assert_eq!(probe.endianness(), Endianness::Big);
+    assert_eq!(
+        probe.read_variable_raw(&counter).unwrap(),
+        RawValue::UInt(0x1234_5678)
+    );
+
+    let members: Vec<_> = info
+        .expand_symbol_to_variables(sensor)
+        .iter()
+        .map(|v| (v.name.clone(), probe.read_variable_raw(v).unwrap()))
+        .collect();
+    assert_eq!(
+        members,
+        [
+            ("be_sensor.x".to_string(), RawValue::UInt(0xFFFE)),
+            ("be_sensor.y".to_string(), RawValue::UInt(3)),
+            ("be_sensor.value".to_string(), RawValue::UInt(0xCAFE_F00D)),
+        ]
+    );
+}
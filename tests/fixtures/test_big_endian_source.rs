@@ -0,0 +1,47 @@
+//! Big-endian ARM fixture (armebv7r-none-eabi)
+//!
+//! Built without `core` so no target standard library is needed.
+
+#![feature(no_core, lang_items, auto_traits)]
+#![allow(internal_features, non_upper_case_globals)]
+#![no_core]
+#![no_std]
+#![no_main]
+
+// Minimal lang items for the statics below
+#[lang = "pointee_sized"]
+pub trait PointeeSized {}
+#[lang = "meta_sized"]
+pub trait MetaSized: PointeeSized {}
+#[lang = "sized"]
+pub trait Sized: MetaSized {}
+#[lang = "copy"]
+pub trait Copy {}
+#[lang = "freeze"]
+pub unsafe auto trait Freeze {}
+#[lang = "drop_in_place"]
+pub unsafe fn drop_in_place<T: ?Sized>(_: *mut T) {}
+
+#[repr(C)]
+pub struct SensorData {
+    pub x: u16,
+    pub y: u16,
+    pub value: u32,
+}
+
+#[no_mangle]
+#[used]
+pub static mut be_counter: u32 = 0x1234_5678;
+
+#[no_mangle]
+#[used]
+pub static mut be_sensor: SensorData = SensorData {
+    x: 0xFFFE,
+    y: 3,
+    value: 0xCAFE_F00D,
+};
+
+#[no_mangle]
+pub extern "C" fn _start() -> ! {
+    loop {}
+}
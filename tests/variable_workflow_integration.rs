@@ -8,6 +8,8 @@
 
 mod common;
 
+#[cfg(feature = "mock-probe")]
+use datavis_rs::backend::BackendCommand;
 use datavis_rs::backend::{BackendMessage, SwdBackend};
 use datavis_rs::config::AppConfig;
 use datavis_rs::types::{Variable, VariableType};
@@ -218,7 +220,7 @@ fn test_variable_with_converter_script() {
     let has_converted_data = messages.iter().any(|msg| {
         if let BackendMessage::DataBatch(batch) = msg {
             batch.iter().any(|(id, _, raw, converted)| {
-                *id == var_id && (converted - raw * 2.0).abs() < 0.001
+                *id == var_id && (converted - raw.as_f64() * 2.0).abs() < 0.001
             })
         } else {
             false
@@ -306,3 +308,104 @@ fn test_rapid_variable_changes() {
     frontend.shutdown();
     handle.join().unwrap();
 }
+
+#[test]
+#[cfg(feature = "mock-probe")]
+fn test_text_variable_collection() {
+    let config = AppConfig::default();
+    let (backend, frontend) = SwdBackend::new(config.clone());
+
+    let handle = thread::spawn(move || backend.run());
+
+    frontend.use_mock_probe(true);
+    frontend.connect(None, "MockTarget".to_string(), config.probe);
+    thread::sleep(Duration::from_millis(100));
+
+    let var = Variable::new("status", 0x20000100, VariableType::Text(32));
+    let var_id = var.id;
+    assert!(
+        !var.show_in_graph,
+        "Strings should not be plotted by default"
+    );
+    frontend.add_variable(var);
+    thread::sleep(Duration::from_millis(50));
+
+    frontend.start_collection();
+    thread::sleep(Duration::from_millis(200));
+
+    let messages = frontend.drain();
+    let has_text = messages.iter().any(|msg| {
+        if let BackendMessage::TextBatch(batch) = msg {
+            batch.iter().any(|(id, _, _)| *id == var_id)
+        } else {
+            false
+        }
+    });
+    assert!(
+        has_text,
+        "Should receive decoded text for a string variable"
+    );
+
+    // The decoded length is still delivered as a numeric sample
+    let has_length = messages.iter().any(|msg| {
+        if let BackendMessage::DataBatch(batch) = msg {
+            batch.iter().any(|(id, _, _, _)| *id == var_id)
+        } else {
+            false
+        }
+    });
+    assert!(has_length, "Should receive the string length as data");
+
+    frontend.stop_collection();
+    frontend.disconnect();
+    frontend.shutdown();
+    handle.join().unwrap();
+}
+
+#[test]
+#[cfg(feature = "mock-probe")]
+fn test_memory_watch_collection() {
+    let config = AppConfig::default();
+    let (backend, frontend) = SwdBackend::new(config.clone());
+
+    let handle = thread::spawn(move || backend.run());
+
+    frontend.use_mock_probe(true);
+    frontend.connect(None, "MockTarget".to_string(), config.probe);
+    thread::sleep(Duration::from_millis(100));
+
+    // A memory watch is read even when no variables are configured
+    frontend.send_command(BackendCommand::AddMemoryWatch {
+        id: 42,
+        address: 0x20000200,
+        size: 48,
+    });
+    frontend.start_collection();
+    thread::sleep(Duration::from_millis(200));
+
+    let messages = frontend.drain();
+    let snapshot_len = messages.iter().find_map(|msg| match msg {
+        BackendMessage::MemorySnapshot { id: 42, bytes, .. } => Some(bytes.len()),
+        _ => None,
+    });
+    assert_eq!(
+        snapshot_len,
+        Some(48),
+        "Should receive the whole block per poll"
+    );
+
+    frontend.send_command(BackendCommand::RemoveMemoryWatch(42));
+    thread::sleep(Duration::from_millis(50));
+    frontend.drain();
+    thread::sleep(Duration::from_millis(100));
+    let still_watching = frontend
+        .drain()
+        .iter()
+        .any(|msg| matches!(msg, BackendMessage::MemorySnapshot { .. }));
+    assert!(!still_watching, "Removed watches should no longer be read");
+
+    frontend.stop_collection();
+    frontend.disconnect();
+    frontend.shutdown();
+    handle.join().unwrap();
+}